@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+use super::*;
+
+/// In-memory adapter for tests, answering queries with canned results
+///
+/// Statements are matched by prefix, ignoring case, against the registered
+/// results; anything else returns no rows. Every statement run is recorded
+/// so tests can check what was sent.
+pub struct MockAdapter {
+    database_type: DatabaseType,
+    connected: bool,
+    results: Vec<(String, QueryResult)>,
+    failing: Vec<String>,
+    executed: Mutex<Vec<String>>,
+}
+
+impl MockAdapter {
+    /// A connected adapter speaking the dialect of `database_type`
+    pub fn new(database_type: DatabaseType) -> Self {
+        Self {
+            database_type,
+            connected: true,
+            results: Vec::new(),
+            failing: Vec::new(),
+            executed: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Answer statements starting with `prefix` with `columns` and `rows`
+    pub fn with_result(mut self, prefix: &str, columns: &[&str], rows: Vec<Vec<Option<&str>>>) -> Self {
+        let names: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+        let result = QueryResult {
+            columns: names
+                .iter()
+                .map(|name| ColumnInfo { name: name.clone(), data_type: "TEXT".to_string(), is_nullable: true })
+                .collect(),
+            rows: rows
+                .into_iter()
+                .map(|values| QueryRow {
+                    columns: names.clone(),
+                    values: values.into_iter().map(|v| v.map(str::to_string)).collect(),
+                })
+                .collect(),
+            rows_affected: None,
+            execution_time: Some(0),
+        };
+        self.results.push((prefix.to_lowercase(), result));
+        self
+    }
+
+    /// Fail statements starting with `prefix`
+    pub fn failing_on(mut self, prefix: &str) -> Self {
+        self.failing.push(prefix.to_lowercase());
+        self
+    }
+
+    /// Statements run so far, in order; a failed batch leaves none of its statements
+    pub fn executed(&self) -> Vec<String> {
+        self.executed.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn check(&self, statement: &str) -> Result<(), AppError> {
+        let statement = statement.trim().to_lowercase();
+        match self.failing.iter().find(|prefix| statement.starts_with(prefix.as_str())) {
+            Some(_) => Err(AppError::Database(crate::database::DatabaseError::QueryFailed(format!(
+                "Mock failure for: {}",
+                statement
+            )))),
+            None => Ok(()),
+        }
+    }
+
+    fn record(&self, statements: &[String]) {
+        self.executed.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(statements);
+    }
+
+    fn result_for(&self, statement: &str) -> QueryResult {
+        let statement = statement.trim().to_lowercase();
+        self.results
+            .iter()
+            .find(|(prefix, _)| statement.starts_with(prefix.as_str()))
+            .map(|(_, result)| result.clone())
+            .unwrap_or(QueryResult { columns: Vec::new(), rows: Vec::new(), rows_affected: None, execution_time: Some(0) })
+    }
+}
+
+#[async_trait]
+impl DatabaseAdapter for MockAdapter {
+    async fn connect(&mut self, params: &ConnectionParams) -> Result<(), AppError> {
+        self.database_type = params.database_type;
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), AppError> {
+        self.connected = false;
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<bool, AppError> {
+        Ok(self.connected)
+    }
+
+    async fn execute_query(&self, query: &str) -> Result<QueryResult, AppError> {
+        self.check(query)?;
+        self.record(&[query.to_string()]);
+        Ok(self.result_for(query))
+    }
+
+    async fn stream_query(&self, query: &str, sink: &mut dyn RowSink) -> Result<u64, AppError> {
+        let result = self.execute_query(query).await?;
+        sink.columns(&result.columns)?;
+        for row in &result.rows {
+            sink.row(row)?;
+        }
+        Ok(result.rows.len() as u64)
+    }
+
+    async fn execute_command(&self, command: &str) -> Result<u64, AppError> {
+        self.check(command)?;
+        self.record(&[command.to_string()]);
+        Ok(0)
+    }
+
+    async fn execute_batch(&self, commands: &[String]) -> Result<u64, AppError> {
+        for command in commands {
+            self.check(command)?;
+        }
+        self.record(commands);
+        Ok(0)
+    }
+
+    async fn begin_transaction(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn commit_transaction(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn rollback_transaction(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn get_metadata(&self) -> Result<DatabaseMetadata, AppError> {
+        Ok(DatabaseMetadata {
+            version: "mock".to_string(),
+            database_name: "mock".to_string(),
+            size: None,
+            encoding: None,
+        })
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableInfo>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn list_routines(&self) -> Result<Vec<RoutineInfo>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_table_columns(&self, table_name: &str) -> Result<Vec<ColumnInfo>, AppError> {
+        Ok(self.result_for(&format!("select * from {}", table_name)).columns)
+    }
+
+    async fn primary_key_columns(&self, _table_name: &str) -> Result<Vec<String>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn current_database(&self) -> Result<String, AppError> {
+        Ok("mock".to_string())
+    }
+
+    async fn exact_row_count(&self, table_name: &str) -> Result<i64, AppError> {
+        let result = self.execute_query(&format!("SELECT COUNT(*) FROM {}", table_name)).await?;
+        Ok(result.rows.first().and_then(|row| row.values.first()).cloned().flatten().and_then(|v| v.parse().ok()).unwrap_or(0))
+    }
+
+    async fn table_size(&self, _table_name: &str) -> Result<Option<i64>, AppError> {
+        Ok(None)
+    }
+
+    async fn index_usage(&self) -> Result<Vec<IndexUsage>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn foreign_keys(&self) -> Result<Vec<ForeignKeyInfo>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn table_health(&self) -> Result<Vec<TableHealth>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn replication_status(&self) -> Result<Vec<ReplicationStatus>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn relation_sizes(&self) -> Result<Vec<RelationSize>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sessions(&self) -> Result<Vec<SessionInfo>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn end_session(&self, _pid: i64, _action: SessionAction) -> Result<bool, AppError> {
+        Ok(false)
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn database_type(&self) -> DatabaseType {
+        self.database_type
+    }
+
+    fn get_capabilities(&self) -> DatabaseCapabilities {
+        DatabaseCapabilities::for_server(self.database_type, None)
+    }
+
+    fn get_query_templates(&self) -> QueryTemplates {
+        match self.database_type {
+            DatabaseType::PostgreSQL => QueryTemplates::postgresql(),
+            DatabaseType::MySQL => QueryTemplates::mysql(),
+            DatabaseType::SQLite => QueryTemplates::sqlite(),
+            DatabaseType::Other(_) => QueryTemplates::standard(),
+        }
+    }
+}
@@ -0,0 +1,607 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::stream::BoxStream;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+use crate::metrics::PoolStats;
+use crate::database::dialect::{create_dialect, SqlDialect};
+use crate::database::capabilities::{DatabaseCapabilities, QueryTemplates};
+use crate::database::engine_status::EngineHealth;
+use crate::database::registry;
+
+pub mod postgres;
+pub mod mysql;
+pub mod sqlite;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+
+/// Supported database types, stored and sent as their driver id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DatabaseType {
+    PostgreSQL,
+    MySQL,
+    SQLite,
+    /// A database whose driver was registered with `registry::register_driver`,
+    /// by driver id
+    Other(&'static str),
+}
+
+impl DatabaseType {
+    /// Id of the driver for this type, e.g. "postgresql"
+    pub fn driver_id(&self) -> &'static str {
+        match self {
+            DatabaseType::PostgreSQL => "postgresql",
+            DatabaseType::MySQL => "mysql",
+            DatabaseType::SQLite => "sqlite",
+            DatabaseType::Other(id) => id,
+        }
+    }
+
+    /// The type with driver id `id`; `None` when no such driver is registered
+    pub fn from_driver_id(id: &str) -> Option<Self> {
+        match id {
+            "postgresql" => Some(DatabaseType::PostgreSQL),
+            "mysql" => Some(DatabaseType::MySQL),
+            "sqlite" => Some(DatabaseType::SQLite),
+            _ => registry::driver(id).map(|driver| DatabaseType::Other(driver.id)),
+        }
+    }
+
+    pub fn default_port(&self) -> Option<u16> {
+        match self {
+            DatabaseType::PostgreSQL => Some(5432),
+            DatabaseType::MySQL => Some(3306),
+            DatabaseType::SQLite => None, // SQLite doesn't use ports
+            DatabaseType::Other(id) => registry::driver(id).and_then(|driver| driver.default_port),
+        }
+    }
+
+    pub fn requires_host(&self) -> bool {
+        match self {
+            DatabaseType::PostgreSQL | DatabaseType::MySQL => true,
+            DatabaseType::SQLite => false,
+            DatabaseType::Other(id) => registry::driver(id).is_none_or(|driver| driver.server),
+        }
+    }
+
+    pub fn requires_credentials(&self) -> bool {
+        match self {
+            DatabaseType::PostgreSQL | DatabaseType::MySQL => true,
+            DatabaseType::SQLite => false,
+            DatabaseType::Other(_) => self.requires_host(),
+        }
+    }
+}
+
+impl Serialize for DatabaseType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.driver_id())
+    }
+}
+
+impl<'de> Deserialize<'de> for DatabaseType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        DatabaseType::from_driver_id(&id)
+            .ok_or_else(|| de::Error::custom(format!("unknown database type `{}`", id)))
+    }
+}
+
+/// Connection parameters for any database type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionParams {
+    pub database_type: DatabaseType,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub database: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub ssl_mode: Option<String>,
+    pub connection_timeout: Option<u32>,
+    pub max_connections: Option<u32>,
+    /// Connections opened on connect and kept open while idle, so the first
+    /// queries do not wait for a new connection; 1 when unset
+    #[serde(default)]
+    pub min_connections: Option<u32>,
+    pub additional_params: HashMap<String, String>,
+    /// Schema search path (PostgreSQL, comma-separated) or database (MySQL) selected after connecting
+    #[serde(default)]
+    pub default_schema: Option<String>,
+}
+
+impl ConnectionParams {
+    /// Create new connection parameters
+    pub fn new(database_type: DatabaseType, database: String) -> Self {
+        Self {
+            database_type,
+            host: if database_type.requires_host() {
+                Some("localhost".to_string())
+            } else {
+                None
+            },
+            port: database_type.default_port(),
+            database,
+            username: None,
+            password: None,
+            ssl_mode: None,
+            connection_timeout: Some(5),
+            max_connections: Some(5),
+            min_connections: Some(1),
+            additional_params: HashMap::new(),
+            default_schema: None,
+        }
+    }
+
+    /// Validate connection parameters
+    pub fn validate(&self) -> Result<(), AppError> {
+        // Check required fields based on database type
+        if self.database_type.requires_host() && self.host.is_none() {
+            return Err(AppError::Validation("Host is required".to_string()));
+        }
+
+        if self.database_type.requires_credentials() {
+            if self.username.is_none() {
+                return Err(AppError::Validation("Username is required".to_string()));
+            }
+        }
+
+        if self.database.is_empty() {
+            return Err(AppError::Validation("Database name is required".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Query result row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRow {
+    pub columns: Vec<String>,
+    pub values: Vec<Option<String>>,
+}
+
+/// Query result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<QueryRow>,
+    pub rows_affected: Option<u64>,
+    pub execution_time: Option<u64>, // in milliseconds
+}
+
+/// Column information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub data_type: String,
+    pub is_nullable: bool,
+}
+
+/// Table information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub name: String,
+    pub schema: Option<String>,
+    pub table_type: String, // TABLE, VIEW, etc.
+    /// Estimate from the catalog statistics, which may be stale or missing
+    pub row_count: Option<i64>,
+}
+
+/// A function or stored procedure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineInfo {
+    pub name: String,
+    pub schema: Option<String>,
+    pub routine_type: String, // FUNCTION, PROCEDURE
+    pub return_type: Option<String>,
+}
+
+/// A client session on the database server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// Backend process (PostgreSQL) or connection (MySQL) ID
+    pub pid: i64,
+    pub user: Option<String>,
+    pub database: Option<String>,
+    pub client: Option<String>,
+    /// What the session is doing, e.g. "active"/"idle" or "Query"/"Sleep"
+    pub state: Option<String>,
+    pub query: Option<String>,
+    /// Time spent in the current state
+    pub duration_ms: Option<i64>,
+}
+
+/// A foreign key from `columns` of a table to `referenced_columns` of another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKeyInfo {
+    pub name: Option<String>,
+    pub schema: Option<String>,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub referenced_schema: Option<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+}
+
+/// An index with how often it has been used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexUsage {
+    pub schema: Option<String>,
+    pub table: String,
+    pub name: String,
+    /// Indexed columns (or expressions) in key order
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub primary: bool,
+    /// Scans since the statistics were last reset, if the database tracks them
+    pub scans: Option<i64>,
+    /// Size in bytes, if the database reports it
+    pub size: Option<i64>,
+}
+
+/// Vacuum state and estimated bloat of a table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableHealth {
+    pub schema: Option<String>,
+    pub table: String,
+    pub live_rows: i64,
+    pub dead_rows: i64,
+    /// Rows changed since the last analyze, where the server tracks it
+    pub modified_rows: Option<i64>,
+    /// Size in bytes of the table without its indexes
+    pub size: i64,
+    /// Estimated bytes beyond what the live rows need; unknown until analyzed
+    pub table_bloat: Option<i64>,
+    pub index_bloat: Option<i64>,
+    pub last_vacuum: Option<DateTime<Utc>>,
+    pub last_autovacuum: Option<DateTime<Utc>>,
+    pub last_analyze: Option<DateTime<Utc>>,
+    pub last_autoanalyze: Option<DateTime<Utc>>,
+    /// Dead rows at which autovacuum vacuums the table
+    pub vacuum_threshold: i64,
+    /// Changed rows at which autovacuum analyzes the table
+    pub analyze_threshold: i64,
+}
+
+/// Which end of a replication link the server is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplicationRole {
+    /// A replica streaming changes from this server
+    Replica,
+    /// The server this one replicates from
+    Source,
+    /// A replication slot retaining changes for a consumer (PostgreSQL)
+    Slot,
+}
+
+/// One replication link of the server, in the same shape for every database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicationStatus {
+    pub role: ReplicationRole,
+    /// Application, slot or channel name
+    pub name: Option<String>,
+    /// Address of the other server
+    pub host: Option<String>,
+    pub state: Option<String>,
+    /// Whether changes are currently flowing
+    pub active: bool,
+    /// Changes not yet applied on the replica, or retained by the slot
+    pub lag_bytes: Option<i64>,
+    /// How far the replica is behind in time
+    pub lag_ms: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+/// Level of the storage hierarchy a size belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKind {
+    Database,
+    Schema,
+    Table,
+    Index,
+    /// Out-of-line storage of large values (PostgreSQL)
+    Toast,
+}
+
+/// On-disk size of a table, or of an index or TOAST table belonging to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationSize {
+    pub schema: Option<String>,
+    pub table: String,
+    /// Table, Index or Toast
+    pub kind: StorageKind,
+    pub name: String,
+    pub size: i64,
+}
+
+/// A notification received on a channel the connection listens on (PostgreSQL NOTIFY)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
+    /// Backend process that sent it
+    pub process_id: i64,
+}
+
+/// Notifications as they arrive, until the connection fails
+pub type NotificationStream = BoxStream<'static, Result<Notification, AppError>>;
+
+/// How to stop another session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionAction {
+    /// Cancel the running query and keep the session
+    Cancel,
+    /// Close the session
+    Terminate,
+}
+
+impl SessionAction {
+    /// Statement performing the action on session `pid`
+    pub fn statement(self, database_type: DatabaseType, pid: i64) -> Result<String, AppError> {
+        match (database_type, self) {
+            (DatabaseType::PostgreSQL, SessionAction::Cancel) => Ok(format!("SELECT pg_cancel_backend({})", pid)),
+            (DatabaseType::PostgreSQL, SessionAction::Terminate) => Ok(format!("SELECT pg_terminate_backend({})", pid)),
+            (DatabaseType::MySQL, SessionAction::Cancel) => Ok(format!("KILL QUERY {}", pid)),
+            (DatabaseType::MySQL, SessionAction::Terminate) => Ok(format!("KILL CONNECTION {}", pid)),
+            (DatabaseType::SQLite, _) => Err(AppError::Validation("SQLite has no other sessions".to_string())),
+            (DatabaseType::Other(id), _) => Err(AppError::Validation(format!("Sessions of {} cannot be managed", id))),
+        }
+    }
+}
+
+/// Database metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseMetadata {
+    pub version: String,
+    pub database_name: String,
+    pub size: Option<i64>, // in bytes
+    pub encoding: Option<String>,
+}
+
+/// Names joined with the unit separator (0x1F) by a catalog query
+pub(crate) fn split_names(joined: Option<String>) -> Vec<String> {
+    joined.map(|names| names.split('\u{1f}').map(str::to_string).collect()).unwrap_or_default()
+}
+
+/// Minimum connections of the pool for `params`, never more than its maximum
+pub(crate) fn pool_min_connections(params: &ConnectionParams) -> u32 {
+    params.min_connections.unwrap_or(1).min(params.max_connections.unwrap_or(5))
+}
+
+/// Open the minimum connections of a new pool at once, returning how long it took
+///
+/// The pool only keeps its minimum topped up in the background, so without this
+/// the first queries after connecting would each wait for a connection of their own.
+pub(crate) async fn warm_up<DB: sqlx::Database>(pool: &sqlx::Pool<DB>, connections: u32) -> Duration {
+    let start = Instant::now();
+    let acquired = futures_util::future::join_all((0..connections).map(|_| pool.acquire())).await;
+    let failed = acquired.iter().filter(|connection| connection.is_err()).count();
+    if failed > 0 {
+        crate::log_warn!("adapter", "{} of {} warm-up connections failed to open", failed, connections);
+    }
+    start.elapsed()
+}
+
+/// Receives the rows of a streaming query one at a time
+pub trait RowSink: Send {
+    /// Called once with the result columns, before the first row
+    fn columns(&mut self, columns: &[ColumnInfo]) -> Result<(), AppError>;
+
+    /// Called for every row as it arrives from the database
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError>;
+}
+
+/// Common database operations trait
+#[async_trait]
+pub trait DatabaseAdapter: Send + Sync {
+    /// Connect to the database
+    async fn connect(&mut self, params: &ConnectionParams) -> Result<(), AppError>;
+
+    /// Disconnect from the database
+    async fn disconnect(&mut self) -> Result<(), AppError>;
+
+    /// Test if the connection is alive
+    async fn test_connection(&self) -> Result<bool, AppError>;
+
+    /// Execute a query and return results
+    async fn execute_query(&self, query: &str) -> Result<QueryResult, AppError>;
+
+    /// Execute a query and feed rows to the sink as they arrive, returning the row count
+    async fn stream_query(&self, query: &str, sink: &mut dyn RowSink) -> Result<u64, AppError>;
+
+    /// Execute a non-query command (INSERT, UPDATE, DELETE)
+    async fn execute_command(&self, command: &str) -> Result<u64, AppError>;
+
+    /// Execute several commands in a single transaction, rolling back if any fails
+    async fn execute_batch(&self, commands: &[String]) -> Result<u64, AppError>;
+
+    /// Begin a transaction
+    async fn begin_transaction(&mut self) -> Result<(), AppError>;
+
+    /// Commit a transaction
+    async fn commit_transaction(&mut self) -> Result<(), AppError>;
+
+    /// Rollback a transaction
+    async fn rollback_transaction(&mut self) -> Result<(), AppError>;
+
+    /// Get database metadata
+    async fn get_metadata(&self) -> Result<DatabaseMetadata, AppError>;
+
+    /// List all tables
+    async fn list_tables(&self) -> Result<Vec<TableInfo>, AppError>;
+
+    /// List the functions and stored procedures
+    async fn list_routines(&self) -> Result<Vec<RoutineInfo>, AppError>;
+
+    /// Get the columns of a table ("table" or "schema.table")
+    async fn get_table_columns(&self, table_name: &str) -> Result<Vec<ColumnInfo>, AppError>;
+
+    /// Get the primary key columns of a table ("table" or "schema.table"), in
+    /// key order; empty without a primary key
+    async fn primary_key_columns(&self, table_name: &str) -> Result<Vec<String>, AppError>;
+
+    /// Get the current database name
+    async fn current_database(&self) -> Result<String, AppError>;
+
+    /// Count the rows of a table ("table" or "schema.table"), which reads all of it
+    async fn exact_row_count(&self, table_name: &str) -> Result<i64, AppError>;
+
+    /// Size in bytes of a table ("table" or "schema.table") with its indexes,
+    /// if the database reports it
+    async fn table_size(&self, table_name: &str) -> Result<Option<i64>, AppError>;
+
+    /// List the indexes of the user tables with their usage statistics
+    async fn index_usage(&self) -> Result<Vec<IndexUsage>, AppError>;
+
+    /// Foreign keys of every table
+    async fn foreign_keys(&self) -> Result<Vec<ForeignKeyInfo>, AppError>;
+
+    /// Vacuum statistics of the user tables; empty for databases without autovacuum
+    async fn table_health(&self) -> Result<Vec<TableHealth>, AppError>;
+
+    /// Replication links of the server; empty when it does not replicate
+    async fn replication_status(&self) -> Result<Vec<ReplicationStatus>, AppError>;
+
+    /// Sizes of the user tables and their indexes, as the catalog reports them
+    async fn relation_sizes(&self) -> Result<Vec<RelationSize>, AppError>;
+
+    /// List the other client sessions on the server
+    async fn list_sessions(&self) -> Result<Vec<SessionInfo>, AppError>;
+
+    /// Cancel the query of session `pid` or close the session; false if the
+    /// server found no such session
+    async fn end_session(&self, pid: i64, action: SessionAction) -> Result<bool, AppError>;
+
+    /// Get the connection status
+    fn is_connected(&self) -> bool;
+
+    /// Get the database type
+    fn database_type(&self) -> DatabaseType;
+    
+    /// Get the SQL dialect for this database, as registered for its type
+    fn get_dialect(&self) -> Box<dyn SqlDialect> {
+        create_dialect(self.database_type())
+    }
+    
+    /// Get the capabilities for this database
+    fn get_capabilities(&self) -> DatabaseCapabilities;
+    
+    /// Get query templates for this database
+    fn get_query_templates(&self) -> QueryTemplates;
+
+    /// Listen on `channels` over a dedicated connection
+    async fn listen(&self, _channels: &[String]) -> Result<NotificationStream, AppError> {
+        Err(AppError::Validation(format!(
+            "{:?} does not support LISTEN/NOTIFY",
+            self.database_type()
+        )))
+    }
+
+    /// Status counters and InnoDB monitor figures for a health panel (MySQL)
+    async fn engine_health(&self) -> Result<EngineHealth, AppError> {
+        Err(AppError::Validation(format!(
+            "{:?} does not report InnoDB status",
+            self.database_type()
+        )))
+    }
+
+    /// Get the state of the connection pool, if connected through one
+    fn pool_stats(&self) -> Option<PoolStats> {
+        None
+    }
+}
+
+/// Error for a failed statement, keeping the database's error code and position
+pub(crate) fn query_error(err: &sqlx::Error, statement: &str) -> AppError {
+    AppError::Database(crate::database::DatabaseError::Query(Box::new(
+        crate::database::error::QueryError::from_sqlx(err, statement),
+    )))
+}
+
+/// Result columns of a statement as the database describes them when preparing
+/// it, for results without a row to take them from
+pub(crate) fn described_columns<DB: sqlx::Database>(describe: &sqlx::Describe<DB>) -> Vec<ColumnInfo> {
+    use sqlx::{Column, TypeInfo};
+
+    describe
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| ColumnInfo {
+            name: column.name().to_string(),
+            data_type: column.type_info().name().to_string(),
+            is_nullable: describe.nullable(i).unwrap_or(true),
+        })
+        .collect()
+}
+
+/// Factory function to create appropriate adapter
+pub fn create_adapter(database_type: DatabaseType) -> Result<Box<dyn DatabaseAdapter + Send + Sync>, AppError> {
+    let driver = registry::driver(database_type.driver_id())
+        .ok_or_else(|| AppError::Validation(format!("No driver registered for {}", database_type.driver_id())))?;
+    Ok((driver.create_adapter)())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use super::sqlite::SqliteAdapter;
+    use std::path::Path;
+
+    /// A SQLite adapter connected to the database file at `path`
+    pub(crate) async fn connect_sqlite(path: &Path) -> SqliteAdapter {
+        let mut adapter = SqliteAdapter::new();
+        adapter
+            .connect(&ConnectionParams::new(DatabaseType::SQLite, path.to_string_lossy().into_owned()))
+            .await
+            .unwrap();
+        adapter
+    }
+
+    /// A SQLite adapter connected to a new database in a temporary directory,
+    /// which is removed when dropped
+    pub(crate) async fn temp_sqlite(file_name: &str) -> (tempfile::TempDir, SqliteAdapter) {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let adapter = connect_sqlite(&temp_dir.path().join(file_name)).await;
+        (temp_dir, adapter)
+    }
+
+    #[test]
+    fn test_database_type_defaults() {
+        assert_eq!(DatabaseType::PostgreSQL.default_port(), Some(5432));
+        assert_eq!(DatabaseType::MySQL.default_port(), Some(3306));
+        assert_eq!(DatabaseType::SQLite.default_port(), None);
+    }
+
+    #[test]
+    fn test_connection_params_validation() {
+        let mut params = ConnectionParams::new(DatabaseType::PostgreSQL, "test_db".to_string());
+
+        // Should fail without username
+        assert!(params.validate().is_err());
+
+        // Add username
+        params.username = Some("user".to_string());
+        assert!(params.validate().is_ok());
+
+        // SQLite shouldn't require credentials
+        let sqlite_params = ConnectionParams::new(DatabaseType::SQLite, "test.db".to_string());
+        assert!(sqlite_params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_session_action_statements() {
+        assert_eq!(
+            SessionAction::Terminate.statement(DatabaseType::PostgreSQL, 4242).unwrap(),
+            "SELECT pg_terminate_backend(4242)"
+        );
+        assert_eq!(SessionAction::Cancel.statement(DatabaseType::MySQL, 17).unwrap(), "KILL QUERY 17");
+        assert!(SessionAction::Cancel.statement(DatabaseType::SQLite, 1).is_err());
+    }
+}
\ No newline at end of file
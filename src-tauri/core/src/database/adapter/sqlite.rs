@@ -0,0 +1,780 @@
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::pool::PoolConnection;
+use sqlx::{Column, Executor, Row, TypeInfo, ValueRef};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{
+    ColumnInfo, ConnectionParams, DatabaseAdapter, DatabaseMetadata, DatabaseType, ForeignKeyInfo,
+    IndexUsage, QueryResult, QueryRow, RelationSize, ReplicationStatus, RoutineInfo, RowSink,
+    SessionAction, SessionInfo, StorageKind, TableHealth, TableInfo, pool_min_connections, warm_up,
+};
+use crate::database::dialect::{SqlDialect, SQLiteDialect};
+use crate::database::capabilities::{DatabaseCapabilities, QueryTemplates, ServerVersion};
+use crate::database::registry::Driver;
+use crate::error::AppError;
+use crate::metrics::{AcquireStats, PoolStats};
+
+pub struct SqliteAdapter {
+    pool: Option<SqlitePool>,
+    acquire_stats: AcquireStats,
+    connected: bool,
+    server_version: Option<ServerVersion>,
+    /// Whether this SQLite build was compiled with FTS5, if it could be probed
+    fts5: Option<bool>,
+    database_path: String,
+    dialect: SQLiteDialect,
+}
+
+/// Registration of the SQLite adapter and dialect
+pub fn driver() -> Driver {
+    Driver {
+        id: DatabaseType::SQLite.driver_id(),
+        default_port: DatabaseType::SQLite.default_port(),
+        server: DatabaseType::SQLite.requires_host(),
+        create_adapter: || Box::new(SqliteAdapter::new()),
+        create_dialect: || Box::new(SQLiteDialect::new()),
+    }
+}
+
+impl SqliteAdapter {
+    pub fn new() -> Self {
+        Self {
+            pool: None,
+            acquire_stats: AcquireStats::default(),
+            connected: false,
+            server_version: None,
+            fts5: None,
+            database_path: String::new(),
+            dialect: SQLiteDialect::new(),
+        }
+    }
+
+    fn get_pool(&self) -> Result<&SqlitePool, AppError> {
+        self.pool
+            .as_ref()
+            .ok_or_else(|| AppError::Database(crate::database::DatabaseError::ConnectionFailed(
+                "Not connected to database".to_string(),
+            )))
+    }
+
+    /// Take a connection from the pool, recording how long it took
+    async fn acquire(&self) -> Result<PoolConnection<sqlx::Sqlite>, AppError> {
+        let pool = self.get_pool()?;
+        let start = Instant::now();
+        let conn = pool.acquire().await.map_err(|e| {
+            AppError::Database(crate::database::DatabaseError::ConnectionFailed(e.to_string()))
+        })?;
+        self.acquire_stats.record(start.elapsed());
+        Ok(conn)
+    }
+
+    fn build_connection_string(params: &ConnectionParams) -> Result<String, AppError> {
+        // For SQLite, the database parameter is the file path
+        let db_path = &params.database;
+
+        // Ensure parent directory exists
+        if let Some(parent) = Path::new(db_path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::ConnectionFailed(
+                    format!("Failed to create database directory: {}", e),
+                ))
+            })?;
+        }
+
+        // SQLite connection string with create mode
+        // If file doesn't exist, SQLite will create it automatically
+        Ok(format!("sqlite://{}?mode=rwc", db_path))
+    }
+
+    fn column_info(row: &SqliteRow) -> Vec<ColumnInfo> {
+        row.columns()
+            .iter()
+            .map(|col| ColumnInfo {
+                name: col.name().to_string(),
+                data_type: col.type_info().name().to_string(),
+                is_nullable: true, // SQLite doesn't track nullability well
+            })
+            .collect()
+    }
+
+    fn convert_row(row: &SqliteRow) -> QueryRow {
+        // SQLite types values rather than columns, so each value is decoded by its
+        // storage class; BLOBs have no text form and come back as NULL
+        let values: Vec<Option<String>> = (0..row.columns().len())
+            .map(|i| {
+                let value = row.try_get_raw(i).ok().filter(|value| !value.is_null())?;
+                match value.type_info().name() {
+                    "TEXT" => row.try_get_unchecked::<String, _>(i).ok(),
+                    "INTEGER" => row.try_get_unchecked::<i64, _>(i).ok().map(|v| v.to_string()),
+                    "REAL" => row.try_get_unchecked::<f64, _>(i).ok().map(|v| v.to_string()),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        QueryRow {
+            columns: row.columns().iter().map(|c| c.name().to_string()).collect(),
+            values,
+        }
+    }
+}
+
+impl Default for SqliteAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DatabaseAdapter for SqliteAdapter {
+    async fn connect(&mut self, params: &ConnectionParams) -> Result<(), AppError> {
+        params.validate()?;
+
+        let connection_string = Self::build_connection_string(params)?;
+        self.database_path = params.database.clone();
+
+        let timeout = Duration::from_secs(params.connection_timeout.unwrap_or(5) as u64);
+        let max_connections = params.max_connections.unwrap_or(5);
+        let min_connections = pool_min_connections(params);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .min_connections(min_connections)
+            .acquire_timeout(timeout)
+            .connect(&connection_string)
+            .await
+            .map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::ConnectionFailed(
+                    e.to_string(),
+                ))
+            })?;
+        let warm_up_time = warm_up(&pool, min_connections).await;
+
+        // Enable foreign key constraints
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&pool)
+            .await
+            .map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+
+        // Capabilities depend on the server version; they stay optimistic if it cannot be read
+        let version: Option<String> = sqlx::query("SELECT sqlite_version()")
+            .fetch_one(&pool)
+            .await
+            .ok()
+            .and_then(|row| row.try_get(0).ok());
+        self.server_version = version.as_deref().and_then(ServerVersion::parse);
+        self.fts5 = sqlx::query("SELECT sqlite_compileoption_used('ENABLE_FTS5')")
+            .fetch_one(&pool)
+            .await
+            .ok()
+            .and_then(|row| row.try_get::<i64, _>(0).ok())
+            .map(|used| used == 1);
+
+        self.pool = Some(pool);
+        self.acquire_stats = AcquireStats::default();
+        self.acquire_stats.record_warm_up(warm_up_time);
+        self.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), AppError> {
+        if let Some(pool) = &self.pool {
+            pool.close().await;
+        }
+        self.pool = None;
+        self.connected = false;
+        self.server_version = None;
+        self.fts5 = None;
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<bool, AppError> {
+        let pool = self.get_pool()?;
+
+        match sqlx::query("SELECT 1")
+            .fetch_one(pool)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+
+    async fn execute_query(&self, query: &str) -> Result<QueryResult, AppError> {
+        let mut conn = self.acquire().await?;
+
+        let start = std::time::Instant::now();
+        let rows: Vec<SqliteRow> = sqlx::query(query)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| super::query_error(&e, query))?;
+
+        let execution_time = start.elapsed().as_millis() as u64;
+
+        // Get column information from the first row, or from the prepared statement without rows
+        let columns = match rows.first() {
+            Some(row) => Self::column_info(row),
+            None => (&mut *conn)
+                .describe(query)
+                .await
+                .map(|describe| super::described_columns(&describe))
+                .unwrap_or_default(),
+        };
+
+        // Convert rows to QueryRow
+        let query_rows: Vec<QueryRow> = rows.iter().map(Self::convert_row).collect();
+
+        Ok(QueryResult {
+            columns,
+            rows: query_rows,
+            rows_affected: None,
+            execution_time: Some(execution_time),
+        })
+    }
+
+    async fn stream_query(&self, query: &str, sink: &mut dyn RowSink) -> Result<u64, AppError> {
+        let mut conn = self.acquire().await?;
+
+        let mut rows = sqlx::query(query).fetch(&mut *conn);
+        let mut count = 0u64;
+
+        while let Some(row) = rows.try_next().await.map_err(|e| super::query_error(&e, query))? {
+            if count == 0 {
+                sink.columns(&Self::column_info(&row))?;
+            }
+            sink.row(&Self::convert_row(&row))?;
+            count += 1;
+        }
+
+        // Headers for an empty result
+        if count == 0 {
+            drop(rows);
+            let columns = (&mut *conn)
+                .describe(query)
+                .await
+                .map(|describe| super::described_columns(&describe))
+                .unwrap_or_default();
+            sink.columns(&columns)?;
+        }
+
+        Ok(count)
+    }
+
+    async fn execute_command(&self, command: &str) -> Result<u64, AppError> {
+        let mut conn = self.acquire().await?;
+
+        let result = sqlx::query(command)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| super::query_error(&e, command))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn execute_batch(&self, commands: &[String]) -> Result<u64, AppError> {
+        let pool = self.get_pool()?;
+        let map_err = |e: sqlx::Error| {
+            AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+        };
+
+        let mut tx = pool.begin().await.map_err(map_err)?;
+        let mut rows_affected = 0;
+        for command in commands {
+            // Dropping the transaction on error rolls it back
+            let result = sqlx::query(command).execute(&mut *tx).await.map_err(map_err)?;
+            rows_affected += result.rows_affected();
+        }
+        tx.commit().await.map_err(map_err)?;
+
+        Ok(rows_affected)
+    }
+
+    async fn begin_transaction(&mut self) -> Result<(), AppError> {
+        // For now, we'll use implicit transactions with queries
+        // Real transaction support would require storing transaction state
+        Ok(())
+    }
+
+    async fn commit_transaction(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn rollback_transaction(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn get_metadata(&self) -> Result<DatabaseMetadata, AppError> {
+        let pool = self.get_pool()?;
+
+        // Get SQLite version
+        let version_row = sqlx::query("SELECT sqlite_version()")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+
+        let version: String = version_row.try_get(0).unwrap_or_else(|_| "Unknown".to_string());
+
+        // Get database file size
+        let size = if Path::new(&self.database_path).exists() {
+            std::fs::metadata(&self.database_path)
+                .ok()
+                .map(|m| m.len() as i64)
+        } else {
+            None
+        };
+
+        // SQLite uses UTF-8 encoding by default
+        let encoding = Some("UTF-8".to_string());
+
+        Ok(DatabaseMetadata {
+            version: format!("SQLite {}", version),
+            database_name: Path::new(&self.database_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            size,
+            encoding,
+        })
+    }
+
+    async fn list_tables(&self) -> Result<Vec<TableInfo>, AppError> {
+        let pool = self.get_pool()?;
+
+        // ANALYZE records row counts in sqlite_stat1, which only exists once it has run;
+        // the first number of each entry is the row count of the table
+        let analyzed: bool =
+            sqlx::query_scalar("SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'sqlite_stat1'")
+                .fetch_one(pool)
+                .await
+                .unwrap_or(false);
+        let row_count = if analyzed {
+            "(SELECT CAST(stat AS INTEGER) FROM sqlite_stat1 s WHERE s.tbl = m.name ORDER BY s.idx IS NOT NULL LIMIT 1)"
+        } else {
+            "NULL"
+        };
+        let query = format!(
+            r#"
+            SELECT
+                name,
+                type,
+                {}
+            FROM sqlite_master m
+            WHERE type IN ('table', 'view')
+                AND name NOT LIKE 'sqlite_%'
+            ORDER BY name
+            "#,
+            row_count
+        );
+        let rows = sqlx::query(&query)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+
+        let mut tables = Vec::new();
+        for row in rows {
+            let name: String = row.try_get(0).map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+            let table_type: String = row.try_get(1).map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+            let row_count: Option<i64> = row.try_get(2).ok().flatten();
+
+            tables.push(TableInfo {
+                name,
+                schema: None, // SQLite doesn't have schemas like PostgreSQL
+                table_type: table_type.to_uppercase(),
+                row_count,
+            });
+        }
+
+        Ok(tables)
+    }
+
+    async fn list_routines(&self) -> Result<Vec<RoutineInfo>, AppError> {
+        // SQLite has no stored routines; functions are registered by the application
+        Ok(Vec::new())
+    }
+
+    async fn get_table_columns(&self, table_name: &str) -> Result<Vec<ColumnInfo>, AppError> {
+        let pool = self.get_pool()?;
+
+        // Use PRAGMA table_info to get column information; the schema names an attached database
+        let query = match table_name.split_once('.') {
+            Some((schema, table)) => format!(
+                "PRAGMA {}.table_info({})",
+                self.dialect.quote_identifier(schema),
+                self.dialect.string_literal(table)
+            ),
+            None => format!("PRAGMA table_info({})", self.dialect.string_literal(table_name)),
+        };
+
+        let rows = sqlx::query(&query)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+
+        let mut columns = Vec::new();
+        for row in rows {
+            let name: String = row.try_get(1).map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+            let data_type: String = row.try_get(2).map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+            let notnull: i64 = row.try_get(3).map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+
+            columns.push(ColumnInfo {
+                name,
+                data_type,
+                is_nullable: notnull == 0,
+            });
+        }
+
+        Ok(columns)
+    }
+
+    async fn primary_key_columns(&self, table_name: &str) -> Result<Vec<String>, AppError> {
+        let pool = self.get_pool()?;
+
+        // The pk column of table_info is the position in the key, 0 outside of it
+        let query = match table_name.split_once('.') {
+            Some((schema, table)) => sqlx::query("SELECT name FROM pragma_table_info(?, ?) WHERE pk > 0 ORDER BY pk")
+                .bind(table)
+                .bind(schema),
+            None => sqlx::query("SELECT name FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk").bind(table_name),
+        };
+
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })?;
+
+        rows.iter()
+            .map(|row| row.try_get(0).map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            }))
+            .collect()
+    }
+
+    async fn current_database(&self) -> Result<String, AppError> {
+        Ok(Path::new(&self.database_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown")
+            .to_string())
+    }
+
+    async fn exact_row_count(&self, table_name: &str) -> Result<i64, AppError> {
+        let pool = self.get_pool()?;
+
+        let table = match table_name.split_once('.') {
+            Some((schema, table)) => self.dialect.qualified_table_name(Some(schema), table),
+            None => self.dialect.quote_identifier(table_name),
+        };
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+            })
+    }
+
+    async fn table_size(&self, table_name: &str) -> Result<Option<i64>, AppError> {
+        let pool = self.get_pool()?;
+
+        // The dbstat table is only there when SQLite was built with it
+        let query = format!(
+            "SELECT SUM(pgsize) FROM dbstat WHERE name IN (SELECT name FROM sqlite_master WHERE tbl_name = {})",
+            self.dialect.string_literal(table_name)
+        );
+        Ok(sqlx::query(&query)
+            .fetch_one(pool)
+            .await
+            .ok()
+            .and_then(|row| row.try_get(0).ok().flatten()))
+    }
+
+    async fn foreign_keys(&self) -> Result<Vec<ForeignKeyInfo>, AppError> {
+        let pool = self.get_pool()?;
+
+        // One row per column; keys are unnamed and numbered per table
+        let rows = sqlx::query(
+            r#"
+            SELECT m.name, fk.id, fk."table", fk."from", fk."to"
+            FROM sqlite_master m
+            JOIN pragma_foreign_key_list(m.name) fk
+            WHERE m.type = 'table'
+            ORDER BY m.name, fk.id, fk.seq
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+        })?;
+
+        let mut keys: Vec<(i64, ForeignKeyInfo)> = Vec::new();
+        for row in &rows {
+            let table: String = row.try_get(0).unwrap_or_default();
+            let id: i64 = row.try_get(1).unwrap_or_default();
+            let key = match keys.last_mut() {
+                Some((last_id, key)) if *last_id == id && key.table == table => key,
+                _ => {
+                    keys.push((id, ForeignKeyInfo {
+                        name: None,
+                        schema: None,
+                        table,
+                        columns: Vec::new(),
+                        referenced_schema: None,
+                        referenced_table: row.try_get(2).unwrap_or_default(),
+                        referenced_columns: Vec::new(),
+                    }));
+                    &mut keys.last_mut().expect("key was just added").1
+                }
+            };
+            key.columns.extend(row.try_get::<String, _>(3).ok());
+            // "to" is NULL when the key references the primary key of the other table
+            key.referenced_columns.extend(row.try_get::<Option<String>, _>(4).ok().flatten());
+        }
+
+        Ok(keys.into_iter().map(|(_, key)| key).collect())
+    }
+
+    async fn index_usage(&self) -> Result<Vec<IndexUsage>, AppError> {
+        let pool = self.get_pool()?;
+
+        // SQLite keeps no usage statistics; column names are joined with the unit separator
+        let rows = sqlx::query(
+            r#"
+            SELECT m.tbl_name, il.name, il."unique", il.origin,
+                (SELECT group_concat(ii.name, char(31))
+                 FROM (SELECT name FROM pragma_index_info(il.name) ORDER BY seqno) ii)
+            FROM sqlite_master m
+            JOIN pragma_index_list(m.name) il
+            WHERE m.type = 'table'
+            ORDER BY 1, 2
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+        })?;
+
+        // The dbstat table is only there when SQLite was built with it
+        let sizes: HashMap<String, i64> = sqlx::query("SELECT name, SUM(pgsize) FROM dbstat GROUP BY name")
+            .fetch_all(pool)
+            .await
+            .map(|rows| rows.iter().filter_map(|row| Some((row.try_get(0).ok()?, row.try_get(1).ok()?))).collect())
+            .unwrap_or_default();
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let name: String = row.try_get(1).unwrap_or_default();
+                let columns: Option<String> = row.try_get(4).ok().flatten();
+                IndexUsage {
+                    schema: None,
+                    table: row.try_get(0).unwrap_or_default(),
+                    columns: columns.map(|c| c.split('\u{1f}').map(str::to_string).collect()).unwrap_or_default(),
+                    unique: row.try_get(2).unwrap_or_default(),
+                    primary: row.try_get::<String, _>(3).is_ok_and(|origin| origin == "pk"),
+                    scans: None,
+                    size: sizes.get(&name).copied(),
+                    name,
+                }
+            })
+            .collect())
+    }
+
+    async fn table_health(&self) -> Result<Vec<TableHealth>, AppError> {
+        // SQLite has no autovacuum daemon or per-table dead row statistics
+        Ok(Vec::new())
+    }
+
+    async fn replication_status(&self) -> Result<Vec<ReplicationStatus>, AppError> {
+        // SQLite has no built-in replication
+        Ok(Vec::new())
+    }
+
+    async fn relation_sizes(&self) -> Result<Vec<RelationSize>, AppError> {
+        let pool = self.get_pool()?;
+
+        // Needs the dbstat table, which the bundled SQLite is built with
+        let rows = sqlx::query(
+            r#"
+            SELECT m.tbl_name, m.type, m.name, SUM(d.pgsize)
+            FROM sqlite_master m
+            JOIN dbstat d ON d.name = m.name
+            WHERE m.type IN ('table', 'index')
+            GROUP BY m.tbl_name, m.type, m.name
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            AppError::Database(crate::database::DatabaseError::QueryFailed(e.to_string()))
+        })?;
+
+        Ok(rows
+            .iter()
+            .map(|row| RelationSize {
+                schema: None,
+                table: row.try_get(0).unwrap_or_default(),
+                kind: match row.try_get::<String, _>(1).unwrap_or_default().as_str() {
+                    "index" => StorageKind::Index,
+                    _ => StorageKind::Table,
+                },
+                name: row.try_get(2).unwrap_or_default(),
+                size: row.try_get(3).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn list_sessions(&self) -> Result<Vec<SessionInfo>, AppError> {
+        // SQLite is embedded; there is no server with other sessions
+        Ok(Vec::new())
+    }
+
+    async fn end_session(&self, pid: i64, action: SessionAction) -> Result<bool, AppError> {
+        action.statement(self.database_type(), pid).map(|_| false)
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::SQLite
+    }
+    
+    fn get_capabilities(&self) -> DatabaseCapabilities {
+        let mut capabilities = DatabaseCapabilities::for_server(self.database_type(), self.server_version);
+        if let Some(fts5) = self.fts5 {
+            capabilities.full_text_search = fts5;
+        }
+        capabilities
+    }
+    
+    fn pool_stats(&self) -> Option<PoolStats> {
+        self.pool.as_ref().map(|pool| {
+            self.acquire_stats.pool_stats(
+                pool.size(),
+                pool.num_idle() as u32,
+                pool.options().get_min_connections(),
+                pool.options().get_max_connections(),
+            )
+        })
+    }
+
+    fn get_query_templates(&self) -> QueryTemplates {
+        QueryTemplates::sqlite()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::tests::temp_sqlite;
+
+    #[test]
+    fn test_connection_string_building() {
+        let params = ConnectionParams::new(DatabaseType::SQLite, "./database/sqlite/test.db".to_string());
+
+        let conn_str = SqliteAdapter::build_connection_string(&params).unwrap();
+        assert_eq!(conn_str, "sqlite://./database/sqlite/test.db?mode=rwc");
+    }
+
+    #[tokio::test]
+    async fn test_columns_of_empty_result() {
+        let (_temp_dir, mut adapter) = temp_sqlite("empty.db").await;
+        adapter
+            .execute_command("CREATE TABLE notes (id INTEGER PRIMARY KEY, title TEXT NOT NULL, body TEXT)")
+            .await
+            .unwrap();
+
+        let result = adapter.execute_query("SELECT id, title, body FROM notes").await.unwrap();
+        assert!(result.rows.is_empty());
+        let columns: Vec<(&str, bool)> = result.columns.iter().map(|c| (c.name.as_str(), c.is_nullable)).collect();
+        assert_eq!(columns, vec![("id", false), ("title", false), ("body", true)]);
+
+        adapter.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_values_decoded_by_storage_class() {
+        let (_temp_dir, mut adapter) = temp_sqlite("values.db").await;
+
+        // A column holds values of any class whatever its declared type
+        let result = adapter
+            .execute_query("SELECT 42, 'text', 2.5, NULL, X'00' UNION ALL SELECT 'forty-two', 7, NULL, 1.0, 3")
+            .await
+            .unwrap();
+        let values: Vec<Vec<Option<&str>>> = result
+            .rows
+            .iter()
+            .map(|row| row.values.iter().map(Option::as_deref).collect())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                vec![Some("42"), Some("text"), Some("2.5"), None, None],
+                vec![Some("forty-two"), Some("7"), None, Some("1"), Some("3")],
+            ]
+        );
+
+        adapter.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_foreign_keys() {
+        let (_temp_dir, mut adapter) = temp_sqlite("keys.db").await;
+        for sql in [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY)",
+            "CREATE TABLE shops (region TEXT, code TEXT, PRIMARY KEY (region, code))",
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users, \
+             region TEXT, code TEXT, FOREIGN KEY (region, code) REFERENCES shops (region, code))",
+        ] {
+            adapter.execute_command(sql).await.unwrap();
+        }
+
+        let mut keys: Vec<(String, Vec<String>, String, Vec<String>)> = adapter
+            .foreign_keys()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|k| (k.table, k.columns, k.referenced_table, k.referenced_columns))
+            .collect();
+        keys.sort();
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                ("orders".to_string(), names(&["region", "code"]), "shops".to_string(), names(&["region", "code"])),
+                // The primary key is referenced implicitly
+                ("orders".to_string(), names(&["user_id"]), "users".to_string(), Vec::new()),
+            ]
+        );
+
+        adapter.disconnect().await.unwrap();
+    }
+}
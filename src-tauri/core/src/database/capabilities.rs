@@ -0,0 +1,375 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::adapter::DatabaseType;
+
+/// Version of the database server, as probed when connecting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Find the first dotted version number in a version string such as
+    /// `PostgreSQL 15.3 on x86_64-pc-linux-gnu`, `8.0.34` or `10.11.2-MariaDB`
+    pub fn parse(text: &str) -> Option<Self> {
+        text.split(|c: char| !c.is_ascii_digit() && c != '.')
+            .find(|token| token.contains('.') && token.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|token| {
+                let mut parts = token.split('.').map(|part| part.parse().unwrap_or(0));
+                Self::new(
+                    parts.next().unwrap_or(0),
+                    parts.next().unwrap_or(0),
+                    parts.next().unwrap_or(0),
+                )
+            })
+    }
+
+    fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        *self >= Self::new(major, minor, patch)
+    }
+}
+
+/// Database capabilities that define what features are supported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseCapabilities {
+    /// Supports schemas (namespaces)
+    pub schemas: bool,
+    
+    /// Supports views
+    pub views: bool,
+    
+    /// Supports stored procedures
+    pub stored_procedures: bool,
+    
+    /// Supports triggers
+    pub triggers: bool,
+    
+    /// Supports transactions
+    pub transactions: bool,
+    
+    /// Supports foreign keys
+    pub foreign_keys: bool,
+    
+    /// Supports partial indexes
+    pub partial_indexes: bool,
+    
+    /// Supports RETURNING clause
+    pub returning_clause: bool,
+    
+    /// Supports common table expressions (WITH queries)
+    pub common_table_expressions: bool,
+    
+    /// Supports window functions (OVER clauses)
+    pub window_functions: bool,
+    
+    /// Supports JSON data type
+    pub json_type: bool,
+    
+    /// Supports arrays
+    pub arrays: bool,
+    
+    /// Supports full text search
+    pub full_text_search: bool,
+    
+    /// Supports materialized views
+    pub materialized_views: bool,
+    
+    /// Maximum identifier length
+    pub max_identifier_length: usize,
+    
+    /// Maximum number of columns in a table
+    pub max_columns: usize,
+    
+    /// Supports SSL/TLS connections
+    pub ssl_support: bool,
+    
+    /// Supports connection pooling
+    pub connection_pooling: bool,
+    
+    /// Supports EXPLAIN ANALYZE
+    pub explain_analyze: bool,
+    
+    /// Supports savepoints
+    pub savepoints: bool,
+    
+    /// DDL statements can be rolled back as part of a transaction
+    pub transactional_ddl: bool,
+    
+    /// Extensions installed in the database, as probed when connecting
+    pub extensions: Vec<String>,
+}
+
+impl DatabaseCapabilities {
+    /// Capabilities of a server of the given version
+    ///
+    /// The per-database constructors describe current releases; features older
+    /// servers lack are switched off here. Without a version the constructor's
+    /// values are kept.
+    pub fn for_server(database_type: DatabaseType, version: Option<ServerVersion>) -> Self {
+        let mut capabilities = match database_type {
+            DatabaseType::PostgreSQL => Self::postgresql(),
+            DatabaseType::MySQL => Self::mysql(),
+            DatabaseType::SQLite => Self::sqlite(),
+            DatabaseType::Other(_) => Self::standard(),
+        };
+        let Some(version) = version else {
+            return capabilities;
+        };
+
+        match database_type {
+            DatabaseType::PostgreSQL => {
+                capabilities.materialized_views = version.at_least(9, 3, 0);
+            }
+            // MariaDB reports 10.x and later, which has all of these since 10.2
+            DatabaseType::MySQL => {
+                capabilities.common_table_expressions = version.at_least(8, 0, 0);
+                capabilities.window_functions = version.at_least(8, 0, 0);
+                capabilities.json_type = version.at_least(5, 7, 8);
+            }
+            DatabaseType::SQLite => {
+                capabilities.returning_clause = version.at_least(3, 35, 0);
+                capabilities.common_table_expressions = version.at_least(3, 8, 3);
+                capabilities.window_functions = version.at_least(3, 25, 0);
+            }
+            DatabaseType::Other(_) => {}
+        }
+        capabilities
+    }
+
+    /// PostgreSQL capabilities
+    pub fn postgresql() -> Self {
+        Self {
+            schemas: true,
+            views: true,
+            stored_procedures: true,
+            triggers: true,
+            transactions: true,
+            foreign_keys: true,
+            partial_indexes: true,
+            returning_clause: true,
+            common_table_expressions: true,
+            window_functions: true,
+            json_type: true,
+            arrays: true,
+            full_text_search: true,
+            materialized_views: true,
+            max_identifier_length: 63,
+            max_columns: 1600,
+            ssl_support: true,
+            connection_pooling: true,
+            explain_analyze: true,
+            savepoints: true,
+            transactional_ddl: true,
+            extensions: Vec::new(),
+        }
+    }
+    
+    /// MySQL capabilities
+    pub fn mysql() -> Self {
+        Self {
+            schemas: true, // MySQL calls them databases
+            views: true,
+            stored_procedures: true,
+            triggers: true,
+            transactions: true,
+            foreign_keys: true,
+            partial_indexes: false,
+            returning_clause: false,
+            common_table_expressions: true,
+            window_functions: true,
+            json_type: true,
+            arrays: false,
+            full_text_search: true,
+            materialized_views: false,
+            max_identifier_length: 64,
+            max_columns: 4096,
+            ssl_support: true,
+            connection_pooling: true,
+            explain_analyze: false, // Has EXPLAIN but not ANALYZE
+            savepoints: true,
+            transactional_ddl: false, // DDL causes an implicit commit
+            extensions: Vec::new(),
+        }
+    }
+    
+    /// Capabilities assumed for databases of registered drivers: standard SQL
+    /// only, so nothing is offered the database may lack
+    pub fn standard() -> Self {
+        Self {
+            schemas: false,
+            views: true,
+            stored_procedures: false,
+            triggers: false,
+            transactions: true,
+            foreign_keys: true,
+            partial_indexes: false,
+            returning_clause: false,
+            common_table_expressions: false,
+            window_functions: false,
+            json_type: false,
+            arrays: false,
+            full_text_search: false,
+            materialized_views: false,
+            max_identifier_length: 128,
+            max_columns: 1000,
+            ssl_support: false,
+            connection_pooling: true,
+            explain_analyze: false,
+            savepoints: false,
+            transactional_ddl: false,
+            extensions: Vec::new(),
+        }
+    }
+
+    /// SQLite capabilities
+    pub fn sqlite() -> Self {
+        Self {
+            schemas: false,
+            views: true,
+            stored_procedures: false,
+            triggers: true,
+            transactions: true,
+            foreign_keys: true, // Must be enabled with PRAGMA
+            partial_indexes: true,
+            returning_clause: true,
+            common_table_expressions: true,
+            window_functions: true,
+            json_type: true, // JSON1 extension
+            arrays: false,
+            full_text_search: true, // FTS5 extension
+            materialized_views: false,
+            max_identifier_length: 2147483647,
+            max_columns: 32767,
+            ssl_support: false,
+            connection_pooling: false,
+            explain_analyze: true, // Via EXPLAIN QUERY PLAN
+            savepoints: true,
+            transactional_ddl: true,
+            extensions: Vec::new(),
+        }
+    }
+}
+
+/// Query templates for different database operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryTemplates {
+    pub create_table: String,
+    pub create_index: String,
+    pub add_foreign_key: String,
+    pub drop_table: String,
+    pub truncate_table: String,
+    pub analyze_table: String,
+    pub show_create_table: Option<String>,
+}
+
+impl QueryTemplates {
+    pub fn postgresql() -> Self {
+        Self {
+            create_table: r#"CREATE TABLE {table_name} (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+)"#.to_string(),
+            create_index: "CREATE INDEX {index_name} ON {table_name} ({columns})".to_string(),
+            add_foreign_key: "ALTER TABLE {table} ADD CONSTRAINT {constraint_name} FOREIGN KEY ({column}) REFERENCES {ref_table}({ref_column})".to_string(),
+            drop_table: "DROP TABLE IF EXISTS {table_name} CASCADE".to_string(),
+            truncate_table: "TRUNCATE TABLE {table_name} RESTART IDENTITY CASCADE".to_string(),
+            analyze_table: "ANALYZE {table_name}".to_string(),
+            show_create_table: None,
+        }
+    }
+    
+    pub fn mysql() -> Self {
+        Self {
+            create_table: r#"CREATE TABLE `{table_name}` (
+    `id` INT AUTO_INCREMENT PRIMARY KEY,
+    `created_at` TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4"#.to_string(),
+            create_index: "CREATE INDEX `{index_name}` ON `{table_name}` ({columns})".to_string(),
+            add_foreign_key: "ALTER TABLE `{table}` ADD CONSTRAINT `{constraint_name}` FOREIGN KEY (`{column}`) REFERENCES `{ref_table}`(`{ref_column}`)".to_string(),
+            drop_table: "DROP TABLE IF EXISTS `{table_name}`".to_string(),
+            truncate_table: "TRUNCATE TABLE `{table_name}`".to_string(),
+            analyze_table: "ANALYZE TABLE `{table_name}`".to_string(),
+            show_create_table: Some("SHOW CREATE TABLE `{table_name}`".to_string()),
+        }
+    }
+    
+    pub fn sqlite() -> Self {
+        Self {
+            create_table: r#"CREATE TABLE "{table_name}" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "created_at" TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+)"#.to_string(),
+            create_index: "CREATE INDEX \"{index_name}\" ON \"{table_name}\" ({columns})".to_string(),
+            add_foreign_key: "-- SQLite requires foreign keys to be defined at table creation".to_string(),
+            drop_table: "DROP TABLE IF EXISTS \"{table_name}\"".to_string(),
+            truncate_table: "DELETE FROM \"{table_name}\"; DELETE FROM sqlite_sequence WHERE name='{table_name}'".to_string(),
+            analyze_table: "ANALYZE \"{table_name}\"".to_string(),
+            show_create_table: None,
+        }
+    }
+
+    /// Standard SQL templates for databases of registered drivers
+    pub fn standard() -> Self {
+        Self {
+            create_table: r#"CREATE TABLE "{table_name}" (
+    "id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    "created_at" TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+)"#.to_string(),
+            create_index: "CREATE INDEX \"{index_name}\" ON \"{table_name}\" ({columns})".to_string(),
+            add_foreign_key: "ALTER TABLE \"{table}\" ADD CONSTRAINT \"{constraint_name}\" FOREIGN KEY (\"{column}\") REFERENCES \"{ref_table}\"(\"{ref_column}\")".to_string(),
+            drop_table: "DROP TABLE \"{table_name}\"".to_string(),
+            truncate_table: "DELETE FROM \"{table_name}\"".to_string(),
+            analyze_table: "-- Standard SQL has no statement to analyze a table".to_string(),
+            show_create_table: None,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_version() {
+        assert_eq!(
+            ServerVersion::parse("PostgreSQL 15.3 on x86_64-pc-linux-gnu, compiled by gcc"),
+            Some(ServerVersion::new(15, 3, 0))
+        );
+        assert_eq!(ServerVersion::parse("8.0.34"), Some(ServerVersion::new(8, 0, 34)));
+        assert_eq!(ServerVersion::parse("5.7.42-log"), Some(ServerVersion::new(5, 7, 42)));
+        assert_eq!(
+            ServerVersion::parse("10.11.2-MariaDB-1:10.11.2+maria~ubu2204"),
+            Some(ServerVersion::new(10, 11, 2))
+        );
+        assert_eq!(ServerVersion::parse("Unknown"), None);
+    }
+
+    #[test]
+    fn test_capabilities_for_server() {
+        let old_sqlite = DatabaseCapabilities::for_server(DatabaseType::SQLite, ServerVersion::parse("3.31.1"));
+        assert!(!old_sqlite.returning_clause);
+        assert!(old_sqlite.common_table_expressions);
+        assert!(old_sqlite.window_functions);
+        assert!(!DatabaseCapabilities::for_server(DatabaseType::SQLite, ServerVersion::parse("3.22.0")).window_functions);
+        assert!(DatabaseCapabilities::for_server(DatabaseType::SQLite, ServerVersion::parse("3.45.1")).returning_clause);
+
+        let mysql57 = DatabaseCapabilities::for_server(DatabaseType::MySQL, ServerVersion::parse("5.7.42-log"));
+        assert!(!mysql57.common_table_expressions);
+        assert!(!mysql57.window_functions);
+        assert!(mysql57.json_type);
+        let mariadb = DatabaseCapabilities::for_server(DatabaseType::MySQL, ServerVersion::parse("10.6.12-MariaDB"));
+        assert!(mariadb.common_table_expressions);
+
+        // An unknown version keeps the values of current releases
+        assert!(DatabaseCapabilities::for_server(DatabaseType::SQLite, None).returning_clause);
+    }
+}
@@ -0,0 +1,261 @@
+//! Per-column statistics of a table for the data quality overview
+
+use serde::Serialize;
+
+use crate::database::adapter::ColumnInfo;
+use crate::database::dialect::SqlDialect;
+use crate::database::types::AbstractType;
+use crate::database::DatabaseType;
+
+/// Alias of the row count in the statistics query
+const ROWS_ALIAS: &str = "_rows";
+
+/// Alias of the occurrence count in a top values query
+const COUNT_ALIAS: &str = "_count";
+
+/// A value and how many profiled rows hold it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Statistics of one column over the profiled rows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub data_type: String,
+    pub null_count: u64,
+    /// Share of NULLs from 0 to 100
+    pub null_percent: f64,
+    pub distinct_count: u64,
+    /// Smallest and largest value; not computed for boolean, JSON and binary columns
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Average length of the values as text; not computed for binary columns
+    pub avg_length: Option<f64>,
+    /// Most frequent non-NULL values, the most frequent first
+    pub top_values: Vec<ValueCount>,
+    /// Whether a masking rule hides the values of the column
+    pub masked: bool,
+}
+
+/// Statistics of every column of a table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableProfile {
+    pub table: String,
+    pub rows_profiled: u64,
+    /// Whether the statistics come from the first rows only, the table having
+    /// at least as many rows as the sample holds
+    pub sampled: bool,
+    pub columns: Vec<ColumnProfile>,
+}
+
+/// Builds the queries profiling the columns of a table
+///
+/// Every query reads the same sample of the table's first rows, so large
+/// tables are profiled in bounded time. Minimum, maximum and top values come
+/// back under the column's own name, so masking rules for the column apply.
+pub struct ColumnStatsQueries<'a> {
+    dialect: &'a dyn SqlDialect,
+    source: String,
+}
+
+impl<'a> ColumnStatsQueries<'a> {
+    pub fn new(dialect: &'a dyn SqlDialect, schema: Option<&str>, table: &str, sample_rows: usize) -> Self {
+        let source = format!(
+            "(SELECT * FROM {}{}) {}",
+            dialect.qualified_table_name(schema, table),
+            dialect.limit_clause(Some(sample_rows), None),
+            dialect.quote_identifier("_sample")
+        );
+        Self { dialect, source }
+    }
+
+    fn abstract_type(&self, column: &ColumnInfo) -> AbstractType {
+        self.dialect.type_mapper().to_abstract(&column.data_type)
+    }
+
+    /// The column cast to text, for types the database cannot compare or measure
+    fn as_text(&self, column: &str) -> String {
+        let text_type = match self.dialect.database_type() {
+            DatabaseType::MySQL => "CHAR".to_string(),
+            DatabaseType::PostgreSQL | DatabaseType::SQLite => "TEXT".to_string(),
+            DatabaseType::Other(_) => self.dialect.type_mapper().to_native(&AbstractType::Text),
+        };
+        self.dialect.cast(&self.dialect.quote_identifier(column), &text_type)
+    }
+
+    /// Expression values are counted and grouped by; JSON is compared as text
+    fn comparable(&self, column: &ColumnInfo) -> String {
+        match self.abstract_type(column) {
+            AbstractType::Json => self.as_text(&column.name),
+            _ => self.dialect.quote_identifier(&column.name),
+        }
+    }
+
+    fn has_range(&self, column: &ColumnInfo) -> bool {
+        !matches!(self.abstract_type(column), AbstractType::Boolean | AbstractType::Json | AbstractType::Binary)
+    }
+
+    /// Row count, and non-NULL count, distinct count and average length of every column
+    pub fn stats(&self, columns: &[ColumnInfo]) -> String {
+        let length = match self.dialect.database_type() {
+            DatabaseType::MySQL | DatabaseType::Other(_) => "CHAR_LENGTH",
+            DatabaseType::PostgreSQL | DatabaseType::SQLite => "LENGTH",
+        };
+        let float = self.dialect.type_mapper().to_native(&AbstractType::Double);
+        let mut select = vec![format!("COUNT(*) AS {}", self.dialect.quote_identifier(ROWS_ALIAS))];
+        for (i, column) in columns.iter().enumerate() {
+            let quoted = self.dialect.quote_identifier(&column.name);
+            select.push(format!("COUNT({}) AS {}", quoted, self.dialect.quote_identifier(&format!("_non_null{}", i))));
+            select.push(format!(
+                "COUNT(DISTINCT {}) AS {}",
+                self.comparable(column),
+                self.dialect.quote_identifier(&format!("_distinct{}", i))
+            ));
+            if self.abstract_type(column) != AbstractType::Binary {
+                // PostgreSQL averages integers to NUMERIC, so the average is cast to a float
+                let average = format!("AVG({}({}))", length, self.as_text(&column.name));
+                select.push(format!(
+                    "{} AS {}",
+                    self.dialect.cast(&average, &float),
+                    self.dialect.quote_identifier(&format!("_length{}", i))
+                ));
+            }
+        }
+        format!("SELECT {} FROM {}", select.join(", "), self.source)
+    }
+
+    /// Minimum in the first row and maximum in the second, or `None` where the
+    /// column's values have no order
+    pub fn min_max(&self, column: &ColumnInfo) -> Option<String> {
+        if !self.has_range(column) {
+            return None;
+        }
+        let quoted = self.dialect.quote_identifier(&column.name);
+        Some(format!(
+            "SELECT MIN({q}) AS {q} FROM {s} UNION ALL SELECT MAX({q}) AS {q} FROM {s}",
+            q = quoted,
+            s = self.source
+        ))
+    }
+
+    /// The `limit` most frequent non-NULL values with their counts
+    pub fn top_values(&self, column: &ColumnInfo, limit: usize) -> String {
+        let value = self.comparable(column);
+        format!(
+            "SELECT {} AS {}, COUNT(*) AS {} FROM {} WHERE {} GROUP BY {} ORDER BY 2 DESC, 1{}",
+            value,
+            self.dialect.quote_identifier(&column.name),
+            self.dialect.quote_identifier(COUNT_ALIAS),
+            self.source,
+            self.dialect.is_not_null(&self.dialect.quote_identifier(&column.name)),
+            value,
+            self.dialect.limit_clause(Some(limit), None)
+        )
+    }
+}
+
+fn number(row: &serde_json::Value, column: &str) -> Option<f64> {
+    row.get(column).and_then(|v| v.as_str()).and_then(|v| v.parse().ok())
+}
+
+/// Profiles of `columns` from the row of the statistics query, with the
+/// number of rows profiled; ranges and top values are left empty
+pub fn column_profiles(columns: &[ColumnInfo], stats: &serde_json::Value) -> (u64, Vec<ColumnProfile>) {
+    let rows = number(stats, ROWS_ALIAS).unwrap_or(0.0) as u64;
+    let profiles = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let non_null = number(stats, &format!("_non_null{}", i)).unwrap_or(0.0) as u64;
+            let null_count = rows.saturating_sub(non_null);
+            ColumnProfile {
+                name: column.name.clone(),
+                data_type: column.data_type.clone(),
+                null_count,
+                null_percent: if rows == 0 { 0.0 } else { null_count as f64 * 100.0 / rows as f64 },
+                distinct_count: number(stats, &format!("_distinct{}", i)).unwrap_or(0.0) as u64,
+                min: None,
+                max: None,
+                avg_length: number(stats, &format!("_length{}", i)),
+                top_values: Vec::new(),
+                masked: false,
+            }
+        })
+        .collect();
+    (rows, profiles)
+}
+
+/// Values and counts from the rows of a top values query
+pub fn value_counts(column: &str, rows: &[serde_json::Value]) -> Vec<ValueCount> {
+    rows.iter()
+        .filter_map(|row| {
+            Some(ValueCount {
+                value: row.get(column)?.as_str()?.to_string(),
+                count: number(row, COUNT_ALIAS)? as u64,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::tests::temp_sqlite;
+    use crate::database::adapter::{DatabaseAdapter, QueryResult};
+    use crate::database::dialect::{PostgreSQLDialect, SQLiteDialect};
+
+    /// Rows as `run_query` returns them, one object per row
+    fn objects(result: &QueryResult) -> Vec<serde_json::Value> {
+        result
+            .rows
+            .iter()
+            .map(|row| {
+                let values = row.columns.iter().cloned().zip(row.values.iter().map(|v| serde_json::json!(v)));
+                serde_json::Value::Object(values.collect())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_profile_columns() {
+        let (_temp_dir, mut adapter) = temp_sqlite("stats.db").await;
+        adapter.execute_command("CREATE TABLE pets (id INTEGER PRIMARY KEY, kind TEXT)").await.unwrap();
+        adapter
+            .execute_command("INSERT INTO pets (kind) VALUES ('cat'), ('dog'), ('cat'), (NULL), ('parrot')")
+            .await
+            .unwrap();
+
+        let dialect = SQLiteDialect::new();
+        let columns = adapter.get_table_columns("pets").await.unwrap();
+        let queries = ColumnStatsQueries::new(&dialect, None, "pets", 4);
+
+        let stats = adapter.execute_query(&queries.stats(&columns)).await.unwrap();
+        let (rows, profiles) = column_profiles(&columns, &objects(&stats)[0]);
+        assert_eq!(rows, 4);
+        assert_eq!((profiles[1].null_count, profiles[1].null_percent, profiles[1].distinct_count), (1, 25.0, 2));
+        assert_eq!(profiles[1].avg_length, Some(3.0));
+
+        let range = adapter.execute_query(&queries.min_max(&columns[0]).unwrap()).await.unwrap();
+        let range: Vec<Option<&str>> = range.rows.iter().map(|row| row.values[0].as_deref()).collect();
+        assert_eq!(range, vec![Some("1"), Some("4")]);
+
+        let top = adapter.execute_query(&queries.top_values(&columns[1], 1)).await.unwrap();
+        assert_eq!(value_counts("kind", &objects(&top)), vec![ValueCount { value: "cat".to_string(), count: 2 }]);
+
+        adapter.disconnect().await.unwrap();
+    }
+
+    #[test]
+    fn test_postgres_stats_query() {
+        let dialect = PostgreSQLDialect::new();
+        let columns = vec![ColumnInfo { name: "kind".to_string(), data_type: "TEXT".to_string(), is_nullable: true }];
+        let queries = ColumnStatsQueries::new(&dialect, Some("shop"), "pets", 100);
+        assert_eq!(
+            queries.stats(&columns),
+            r#"SELECT COUNT(*) AS "_rows", COUNT("kind") AS "_non_null0", COUNT(DISTINCT "kind") AS "_distinct0", CAST(AVG(LENGTH(CAST("kind" AS TEXT))) AS DOUBLE PRECISION) AS "_length0" FROM (SELECT * FROM "shop"."pets" LIMIT 100) "_sample""#
+        );
+    }
+}
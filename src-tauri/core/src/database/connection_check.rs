@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use super::adapter::{ConnectionParams, DatabaseType};
+
+/// Steps of opening a connection, in the order they happen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStage {
+    Dns,
+    Tcp,
+    Tls,
+    Auth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Passed,
+    Failed,
+    /// Not checked, because an earlier stage failed or it does not apply
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageResult {
+    pub stage: DiagnosticStage,
+    pub status: StageStatus,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+/// Which step of a failed connection attempt went wrong
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionDiagnostics {
+    pub stages: Vec<StageResult>,
+    pub failed_stage: Option<DiagnosticStage>,
+}
+
+impl ConnectionDiagnostics {
+    fn push(&mut self, stage: DiagnosticStage, status: StageStatus, message: String, started: Option<Instant>) {
+        if status == StageStatus::Failed && self.failed_stage.is_none() {
+            self.failed_stage = Some(stage);
+        }
+        self.stages.push(StageResult {
+            stage,
+            status,
+            message,
+            duration_ms: started.map_or(0, |t| t.elapsed().as_millis() as u64),
+        });
+    }
+
+    fn skip_rest(&mut self, stages: &[DiagnosticStage]) {
+        for stage in stages {
+            self.push(*stage, StageStatus::Skipped, "Not checked".to_string(), None);
+        }
+    }
+
+    /// Message of the stage that failed
+    pub fn failure(&self) -> Option<&str> {
+        self.stages
+            .iter()
+            .find(|s| s.status == StageStatus::Failed)
+            .map(|s| s.message.as_str())
+    }
+}
+
+/// Whether the server answered that it accepts TLS
+#[derive(Debug, PartialEq, Eq)]
+enum TlsProbe {
+    Supported,
+    Unsupported,
+    /// The server turned the client away before TLS came up, e.g. an unknown host
+    Refused(String),
+    /// The database's protocol is not known here, as for registered drivers
+    Unknown,
+}
+
+/// Find out where connecting with `params` fails, after `connect_error` was raised
+///
+/// DNS, TCP and whether the server accepts TLS are checked directly; when they
+/// pass, the login itself is what failed. Returns `None` for SQLite, which does
+/// not connect over the network.
+pub async fn diagnose(params: &ConnectionParams, connect_error: &str) -> Option<ConnectionDiagnostics> {
+    use DiagnosticStage::*;
+
+    let port = params.port.or(params.database_type.default_port())?;
+    let host = params.host.as_deref().unwrap_or("localhost");
+    let timeout = Duration::from_secs(params.connection_timeout.unwrap_or(5) as u64);
+    let mut diagnostics = ConnectionDiagnostics { stages: Vec::new(), failed_stage: None };
+
+    let started = Instant::now();
+    let addrs: Vec<SocketAddr> = match tokio::time::timeout(timeout, tokio::net::lookup_host((host, port))).await {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => {
+            diagnostics.push(Dns, StageStatus::Failed, format!("Could not resolve {}: {}", host, e), Some(started));
+            diagnostics.skip_rest(&[Tcp, Tls, Auth]);
+            return Some(diagnostics);
+        }
+        Err(_) => {
+            diagnostics.push(Dns, StageStatus::Failed, format!("Resolving {} timed out", host), Some(started));
+            diagnostics.skip_rest(&[Tcp, Tls, Auth]);
+            return Some(diagnostics);
+        }
+    };
+    let listed: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
+    diagnostics.push(Dns, StageStatus::Passed, format!("Resolved {} to {}", host, listed.join(", ")), Some(started));
+
+    let started = Instant::now();
+    let mut last_error = format!("{} has no addresses", host);
+    let mut stream = None;
+    for addr in &addrs {
+        match tokio::time::timeout(timeout, TcpStream::connect(addr)).await {
+            Ok(Ok(s)) => {
+                stream = Some((s, *addr));
+                break;
+            }
+            Ok(Err(e)) => last_error = format!("Could not connect to {}: {}", addr, e),
+            Err(_) => last_error = format!("Connecting to {} timed out", addr),
+        }
+    }
+    let Some((mut stream, addr)) = stream else {
+        diagnostics.push(Tcp, StageStatus::Failed, last_error, Some(started));
+        diagnostics.skip_rest(&[Tls, Auth]);
+        return Some(diagnostics);
+    };
+    diagnostics.push(Tcp, StageStatus::Passed, format!("Connected to {}", addr), Some(started));
+
+    let started = Instant::now();
+    if tls_disabled(params.ssl_mode.as_deref()) {
+        diagnostics.push(Tls, StageStatus::Skipped, "TLS is disabled for this connection".to_string(), None);
+    } else {
+        let probe = tokio::time::timeout(timeout, probe_tls(&mut stream, params.database_type)).await;
+        match probe {
+            Ok(Ok(TlsProbe::Supported)) => {
+                diagnostics.push(Tls, StageStatus::Passed, "Server accepts TLS".to_string(), Some(started));
+            }
+            Ok(Ok(TlsProbe::Unsupported)) if tls_required(params.ssl_mode.as_deref()) => {
+                diagnostics.push(
+                    Tls,
+                    StageStatus::Failed,
+                    "TLS is required but the server does not offer it".to_string(),
+                    Some(started),
+                );
+                diagnostics.skip_rest(&[Auth]);
+                return Some(diagnostics);
+            }
+            Ok(Ok(TlsProbe::Unsupported)) => {
+                diagnostics.push(
+                    Tls,
+                    StageStatus::Passed,
+                    "Server does not offer TLS; connecting without it".to_string(),
+                    Some(started),
+                );
+            }
+            Ok(Ok(TlsProbe::Unknown)) => {
+                diagnostics.push(Tls, StageStatus::Skipped, "TLS support is not checked for this database".to_string(), None);
+            }
+            Ok(Ok(TlsProbe::Refused(message))) => {
+                diagnostics.push(Tls, StageStatus::Skipped, "Not checked".to_string(), None);
+                diagnostics.push(Auth, StageStatus::Failed, crate::redact::redacted(message), Some(started));
+                return Some(diagnostics);
+            }
+            Ok(Err(e)) => {
+                diagnostics.push(Tls, StageStatus::Failed, format!("TLS negotiation failed: {}", e), Some(started));
+                diagnostics.skip_rest(&[Auth]);
+                return Some(diagnostics);
+            }
+            Err(_) => {
+                diagnostics.push(Tls, StageStatus::Failed, "TLS negotiation timed out".to_string(), Some(started));
+                diagnostics.skip_rest(&[Auth]);
+                return Some(diagnostics);
+            }
+        }
+    }
+
+    // The server is reachable, so the driver failed during the handshake or login
+    let message = crate::redact::redacted(connect_error);
+    let lower = message.to_lowercase();
+    if ["tls", "certificate", "ssl handshake", "ssl connection", "ssl error"]
+        .iter()
+        .any(|word| lower.contains(word))
+    {
+        if let Some(tls) = diagnostics.stages.iter_mut().find(|s| s.stage == Tls) {
+            tls.status = StageStatus::Failed;
+            tls.message = message;
+            diagnostics.failed_stage = Some(Tls);
+        }
+        diagnostics.skip_rest(&[Auth]);
+    } else {
+        diagnostics.push(Auth, StageStatus::Failed, message, None);
+    }
+
+    Some(diagnostics)
+}
+
+fn tls_disabled(ssl_mode: Option<&str>) -> bool {
+    ssl_mode.is_some_and(|mode| matches!(mode.to_lowercase().as_str(), "disable" | "disabled"))
+}
+
+fn tls_required(ssl_mode: Option<&str>) -> bool {
+    ssl_mode.is_some_and(|mode| {
+        matches!(
+            mode.to_lowercase().replace('_', "-").as_str(),
+            "require" | "required" | "verify-ca" | "verify-full" | "verify-identity"
+        )
+    })
+}
+
+/// Ask the server whether it accepts TLS, without starting a handshake
+async fn probe_tls(stream: &mut TcpStream, database_type: DatabaseType) -> std::io::Result<TlsProbe> {
+    match database_type {
+        DatabaseType::PostgreSQL => {
+            // SSLRequest: length 8, request code 80877103
+            stream.write_all(&[0, 0, 0, 8, 0x04, 0xd2, 0x16, 0x2f]).await?;
+            let mut answer = [0u8; 1];
+            stream.read_exact(&mut answer).await?;
+            Ok(match answer[0] {
+                b'S' => TlsProbe::Supported,
+                b'N' => TlsProbe::Unsupported,
+                other => TlsProbe::Refused(format!("Unexpected answer to the TLS request: {:#04x}", other)),
+            })
+        }
+        DatabaseType::MySQL => {
+            // The server speaks first with a greeting that lists its capabilities
+            let mut header = [0u8; 4];
+            stream.read_exact(&mut header).await?;
+            let length = u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize;
+            let mut payload = vec![0u8; length];
+            stream.read_exact(&mut payload).await?;
+            Ok(mysql_greeting_tls(&payload))
+        }
+        DatabaseType::SQLite => Ok(TlsProbe::Unsupported),
+        DatabaseType::Other(_) => Ok(TlsProbe::Unknown),
+    }
+}
+
+/// TLS support announced in a MySQL server greeting packet
+fn mysql_greeting_tls(payload: &[u8]) -> TlsProbe {
+    const CLIENT_SSL: u16 = 0x0800;
+
+    // An error packet instead of a greeting: 0xff, error code, message
+    if payload.first() == Some(&0xff) {
+        let message = payload.get(3..).map(String::from_utf8_lossy).unwrap_or_default();
+        return TlsProbe::Refused(message.into_owned());
+    }
+
+    // Protocol version, NUL-terminated server version, connection ID (4),
+    // auth data (8), filler (1), then the lower capability flags (2)
+    let Some(version_end) = payload.iter().skip(1).position(|b| *b == 0).map(|i| i + 1) else {
+        return TlsProbe::Unsupported;
+    };
+    let flags_at = version_end + 1 + 4 + 8 + 1;
+    match payload.get(flags_at..flags_at + 2) {
+        Some(flags) if u16::from_le_bytes([flags[0], flags[1]]) & CLIENT_SSL != 0 => TlsProbe::Supported,
+        _ => TlsProbe::Unsupported,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn postgres_params(port: u16) -> ConnectionParams {
+        let mut params = ConnectionParams::new(DatabaseType::PostgreSQL, "app".to_string());
+        params.host = Some("127.0.0.1".to_string());
+        params.port = Some(port);
+        params.username = Some("app".to_string());
+        params.connection_timeout = Some(2);
+        params
+    }
+
+    #[test]
+    fn test_mysql_greeting_tls() {
+        let mut greeting = vec![10];
+        greeting.extend_from_slice(b"8.0.36\0");
+        greeting.extend_from_slice(&[1, 0, 0, 0]);
+        greeting.extend_from_slice(&[0; 8]);
+        greeting.push(0);
+        greeting.extend_from_slice(&0xffffu16.to_le_bytes());
+        assert_eq!(mysql_greeting_tls(&greeting), TlsProbe::Supported);
+
+        let flags_at = greeting.len() - 2;
+        greeting[flags_at..].copy_from_slice(&0xf7ffu16.to_le_bytes());
+        assert_eq!(mysql_greeting_tls(&greeting), TlsProbe::Unsupported);
+
+        let refused = [&[0xff, 0x6a, 0x04][..], b"Host '10.0.0.9' is not allowed to connect"].concat();
+        assert!(matches!(mysql_greeting_tls(&refused), TlsProbe::Refused(m) if m.contains("not allowed")));
+    }
+
+    #[tokio::test]
+    async fn test_closed_port_fails_at_tcp() {
+        let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+
+        let diagnostics = diagnose(&postgres_params(port), "Connection refused").await.unwrap();
+        assert_eq!(diagnostics.failed_stage, Some(DiagnosticStage::Tcp));
+        assert_eq!(diagnostics.stages[0].status, StageStatus::Passed);
+        assert_eq!(diagnostics.stages[2].status, StageStatus::Skipped);
+    }
+
+    #[tokio::test]
+    async fn test_required_tls_and_login_failures() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0u8; 8];
+                let _ = socket.read_exact(&mut request).await;
+                let _ = socket.write_all(b"N").await;
+            }
+        });
+
+        let mut params = postgres_params(port);
+        params.ssl_mode = Some("require".to_string());
+        let diagnostics = diagnose(&params, "error communicating with database").await.unwrap();
+        assert_eq!(diagnostics.failed_stage, Some(DiagnosticStage::Tls));
+
+        params.ssl_mode = None;
+        let diagnostics = diagnose(&params, "password authentication failed for user \"app\"").await.unwrap();
+        assert_eq!(diagnostics.failed_stage, Some(DiagnosticStage::Auth));
+        assert!(diagnostics.failure().unwrap().contains("password authentication failed"));
+
+        assert!(diagnose(&ConnectionParams::new(DatabaseType::SQLite, "app.db".to_string()), "").await.is_none());
+    }
+}
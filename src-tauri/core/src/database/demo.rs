@@ -0,0 +1,204 @@
+//! Sample shop database for exploring the app without a server of one's own
+
+use std::path::Path;
+
+use crate::database::adapter::{create_adapter, ConnectionParams, DatabaseAdapter, DatabaseType};
+use crate::database::dialect::{SQLiteDialect, SqlDialect};
+use crate::error::AppError;
+
+/// Name of the profile connecting to the demo database
+pub const DEMO_PROFILE_NAME: &str = "Demo shop";
+
+/// File name of the demo database in its directory
+pub const DEMO_DATABASE_FILE: &str = "demo.sqlite";
+
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE customers (\
+     id INTEGER PRIMARY KEY, \
+     name TEXT NOT NULL, \
+     email TEXT NOT NULL UNIQUE, \
+     city TEXT, \
+     country TEXT NOT NULL, \
+     created_at TEXT NOT NULL)",
+    "CREATE TABLE products (\
+     id INTEGER PRIMARY KEY, \
+     name TEXT NOT NULL, \
+     category TEXT NOT NULL, \
+     price NUMERIC NOT NULL, \
+     stock INTEGER NOT NULL DEFAULT 0)",
+    "CREATE TABLE orders (\
+     id INTEGER PRIMARY KEY, \
+     customer_id INTEGER NOT NULL REFERENCES customers (id), \
+     product_id INTEGER NOT NULL REFERENCES products (id), \
+     quantity INTEGER NOT NULL, \
+     status TEXT NOT NULL, \
+     ordered_at TEXT NOT NULL)",
+    "CREATE INDEX orders_customer_id ON orders (customer_id)",
+    "CREATE INDEX orders_product_id ON orders (product_id)",
+    "CREATE VIEW customer_totals AS \
+     SELECT c.id, c.name, c.country, COUNT(o.id) AS order_count, \
+     COALESCE(SUM(o.quantity * p.price), 0) AS total_spent \
+     FROM customers c \
+     LEFT JOIN orders o ON o.customer_id = c.id AND o.status <> 'cancelled' \
+     LEFT JOIN products p ON p.id = o.product_id \
+     GROUP BY c.id, c.name, c.country",
+];
+
+const FIRST_NAMES: &[&str] = &[
+    "Ada", "Bruno", "Chloe", "Dmitri", "Elena", "Farah", "Goro", "Hana", "Ivan", "Julia",
+    "Kofi", "Lena", "Mateo", "Nora", "Omar", "Priya", "Quinn", "Rosa", "Sven", "Tariq",
+];
+
+const LAST_NAMES: &[&str] = &["Almeida", "Berg", "Costa", "Dubois", "Eriksen", "Fischer", "Garcia", "Hoshino"];
+
+const CITIES: &[(&str, &str)] = &[
+    ("Lisbon", "Portugal"),
+    ("Lyon", "France"),
+    ("Osaka", "Japan"),
+    ("Berlin", "Germany"),
+    ("Toronto", "Canada"),
+    ("Austin", "United States"),
+];
+
+const PRODUCTS: &[(&str, &str, &str)] = &[
+    ("Espresso beans 1kg", "Coffee", "24.90"),
+    ("Filter coffee 500g", "Coffee", "11.50"),
+    ("Green tea 100g", "Tea", "8.75"),
+    ("Earl grey 250g", "Tea", "9.95"),
+    ("Pour-over kettle", "Equipment", "59.00"),
+    ("Burr grinder", "Equipment", "129.00"),
+    ("Ceramic mug", "Accessories", "14.00"),
+    ("Travel tumbler", "Accessories", "22.50"),
+    ("Paper filters x100", "Accessories", "4.20"),
+    ("Milk frother", "Equipment", "34.99"),
+];
+
+const STATUSES: &[&str] = &["delivered", "delivered", "delivered", "shipped", "pending", "cancelled"];
+
+const CUSTOMER_COUNT: usize = 40;
+const ORDER_COUNT: usize = 250;
+
+/// Statements creating and filling the demo tables
+///
+/// The data is generated the same way every time, so the demo looks the same
+/// on every machine.
+pub fn seed_statements() -> Vec<String> {
+    let dialect = SQLiteDialect::new();
+    let mut statements: Vec<String> = SCHEMA.iter().map(|s| s.to_string()).collect();
+
+    let customers: Vec<String> = (0..CUSTOMER_COUNT)
+        .map(|i| {
+            let first = FIRST_NAMES[i % FIRST_NAMES.len()];
+            let last = LAST_NAMES[(i * 3) % LAST_NAMES.len()];
+            let (city, country) = CITIES[(i * 7) % CITIES.len()];
+            // Every fifth customer has no city on record, for trying out NULLs
+            let city = if i % 5 == 4 { "NULL".to_string() } else { dialect.string_literal(city) };
+            format!(
+                "({}, {}, {}, {}, {}, '2024-{:02}-{:02} 09:{:02}:00')",
+                i + 1,
+                dialect.string_literal(&format!("{} {}", first, last)),
+                dialect.string_literal(&format!("{}.{}{}@example.com", first.to_lowercase(), last.to_lowercase(), i + 1)),
+                city,
+                dialect.string_literal(country),
+                i % 12 + 1,
+                i % 28 + 1,
+                (i * 13) % 60
+            )
+        })
+        .collect();
+    statements.push(format!(
+        "INSERT INTO customers (id, name, email, city, country, created_at) VALUES {}",
+        customers.join(", ")
+    ));
+
+    let products: Vec<String> = PRODUCTS
+        .iter()
+        .enumerate()
+        .map(|(i, (name, category, price))| {
+            format!(
+                "({}, {}, {}, {}, {})",
+                i + 1,
+                dialect.string_literal(name),
+                dialect.string_literal(category),
+                price,
+                (i * 37) % 150
+            )
+        })
+        .collect();
+    statements.push(format!(
+        "INSERT INTO products (id, name, category, price, stock) VALUES {}",
+        products.join(", ")
+    ));
+
+    let orders: Vec<String> = (0..ORDER_COUNT)
+        .map(|i| {
+            format!(
+                "({}, {}, {}, {}, {}, '2025-{:02}-{:02} {:02}:{:02}:00')",
+                i + 1,
+                (i * 17) % CUSTOMER_COUNT + 1,
+                (i * 7 + i / 3) % PRODUCTS.len() + 1,
+                i % 4 + 1,
+                dialect.string_literal(STATUSES[(i * 5) % STATUSES.len()]),
+                i * 12 / ORDER_COUNT + 1,
+                (i * 11) % 28 + 1,
+                8 + i % 12,
+                (i * 29) % 60
+            )
+        })
+        .collect();
+    statements.push(format!(
+        "INSERT INTO orders (id, customer_id, product_id, quantity, status, ordered_at) VALUES {}",
+        orders.join(", ")
+    ));
+
+    statements
+}
+
+/// Create the demo database at `path`, replacing any earlier one
+pub async fn create_demo_database(path: &Path) -> Result<(), AppError> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+
+    let mut adapter = create_adapter(DatabaseType::SQLite)?;
+    adapter
+        .connect(&ConnectionParams::new(DatabaseType::SQLite, path.to_string_lossy().into_owned()))
+        .await?;
+    let seeded = seed(adapter.as_ref()).await;
+    adapter.disconnect().await?;
+    seeded
+}
+
+/// Create the demo tables and rows in one transaction
+pub async fn seed(adapter: &dyn DatabaseAdapter) -> Result<(), AppError> {
+    adapter.execute_batch(&seed_statements()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::tests::connect_sqlite;
+
+    #[tokio::test]
+    async fn test_create_demo_database() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(DEMO_DATABASE_FILE);
+        create_demo_database(&path).await.unwrap();
+        // Creating it again starts over instead of failing on the existing tables
+        create_demo_database(&path).await.unwrap();
+
+        let mut adapter = connect_sqlite(&path).await;
+        let counts = adapter
+            .execute_query(
+                "SELECT (SELECT COUNT(*) FROM customers), (SELECT COUNT(*) FROM products), \
+                 (SELECT COUNT(*) FROM orders), (SELECT COUNT(*) FROM customer_totals)",
+            )
+            .await
+            .unwrap();
+        let counts: Vec<Option<&str>> = counts.rows[0].values.iter().map(Option::as_deref).collect();
+        assert_eq!(counts, vec![Some("40"), Some("10"), Some("250"), Some("40")]);
+
+        adapter.disconnect().await.unwrap();
+    }
+}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+
+use super::table::{ColumnDefinition, TableDefinition};
+use super::SqlDialect;
+use crate::database::DatabaseType;
+use crate::error::AppError;
+
+/// A change to one column of an existing table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ColumnChange {
+    Add { column: ColumnDefinition },
+    Drop { name: String },
+    /// Replace the definition of the column with the same name
+    Modify { column: ColumnDefinition },
+}
+
+/// Statements that apply `changes` to the table described by `current`
+///
+/// PostgreSQL and MySQL alter the table in place with a single statement.
+/// SQLite can only add columns in place, so dropping or modifying one rebuilds
+/// the table: a copy with the new definition is filled from the old table,
+/// which is then dropped and replaced by the copy, and its indexes recreated.
+pub fn alter_table_statements(
+    current: &TableDefinition,
+    changes: &[ColumnChange],
+    dialect: &dyn SqlDialect,
+) -> Result<Vec<String>, AppError> {
+    let altered = apply_changes(current, changes)?;
+    altered.validate(dialect)?;
+    if changes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    match dialect.database_type() {
+        // SQLite takes one ADD COLUMN per statement
+        DatabaseType::SQLite if changes.iter().all(|change| adds_in_place(&altered, change)) => Ok(changes
+            .iter()
+            .map(|change| {
+                let action = alter_action(current, &altered, change, dialect);
+                format!("ALTER TABLE {} {}", altered.table_name(dialect), action)
+            })
+            .collect()),
+        DatabaseType::SQLite => Ok(rebuild_statements(current, &altered, dialect)),
+        DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::Other(_) => {
+            let actions: Vec<String> = changes
+                .iter()
+                .map(|change| alter_action(current, &altered, change, dialect))
+                .filter(|action| !action.is_empty())
+                .collect();
+            if actions.is_empty() {
+                return Ok(Vec::new());
+            }
+            Ok(vec![format!("ALTER TABLE {} {}", altered.table_name(dialect), actions.join(", "))])
+        }
+    }
+}
+
+/// Definition of the table once `changes` are applied
+fn apply_changes(current: &TableDefinition, changes: &[ColumnChange]) -> Result<TableDefinition, AppError> {
+    let mut altered = current.clone();
+    let position = |table: &TableDefinition, name: &str| table.columns.iter().position(|c| c.name.eq_ignore_ascii_case(name));
+
+    for change in changes {
+        match change {
+            ColumnChange::Add { column } => {
+                if position(&altered, &column.name).is_some() {
+                    return Err(AppError::Validation(format!("Column '{}' already exists", column.name)));
+                }
+                altered.columns.push(column.clone());
+            }
+            ColumnChange::Drop { name } => {
+                let index = position(&altered, name)
+                    .ok_or_else(|| AppError::Validation(format!("Column '{}' does not exist", name)))?;
+                if altered.primary_key.iter().any(|k| k.eq_ignore_ascii_case(name)) {
+                    return Err(AppError::Validation(format!("Column '{}' is part of the primary key", name)));
+                }
+                altered.columns.remove(index);
+
+                // Constraints and indexes on the column go with it, as they do in the database
+                let mentions = |columns: &Vec<String>| columns.iter().any(|c| c.eq_ignore_ascii_case(name));
+                altered.unique.retain(|columns| !mentions(columns));
+                altered.foreign_keys.retain(|fk| !mentions(&fk.columns));
+                altered.indexes.retain(|index| !mentions(&index.columns));
+            }
+            ColumnChange::Modify { column } => {
+                let index = position(&altered, &column.name)
+                    .ok_or_else(|| AppError::Validation(format!("Column '{}' does not exist", column.name)))?;
+                altered.columns[index] = column.clone();
+            }
+        }
+    }
+
+    Ok(altered)
+}
+
+/// Whether SQLite can apply the change with ALTER TABLE ... ADD COLUMN, which
+/// cannot add key columns or NOT NULL columns without a default
+fn adds_in_place(altered: &TableDefinition, change: &ColumnChange) -> bool {
+    let ColumnChange::Add { column } = change else {
+        return false;
+    };
+    let in_key = altered
+        .primary_key
+        .iter()
+        .chain(altered.unique.iter().flatten())
+        .any(|c| c.eq_ignore_ascii_case(&column.name));
+
+    !column.auto_increment && !in_key && (column.nullable || column.default.is_some())
+}
+
+/// Clause of ALTER TABLE for one change; empty when a modified column is unchanged
+fn alter_action(current: &TableDefinition, altered: &TableDefinition, change: &ColumnChange, dialect: &dyn SqlDialect) -> String {
+    match change {
+        ColumnChange::Add { column } => format!("ADD COLUMN {}", altered.column_sql(dialect, column)),
+        ColumnChange::Drop { name } => format!("DROP COLUMN {}", dialect.quote_identifier(name)),
+        // MySQL restates the whole column definition
+        ColumnChange::Modify { column } if dialect.database_type() == DatabaseType::MySQL => {
+            format!("MODIFY COLUMN {}", altered.column_sql(dialect, column))
+        }
+        ColumnChange::Modify { column } => {
+            let name = dialect.quote_identifier(&column.name);
+            let Some(before) = current.columns.iter().find(|c| c.name.eq_ignore_ascii_case(&column.name)) else {
+                return String::new();
+            };
+
+            let mut actions = Vec::new();
+            let native = dialect.type_mapper().to_native(&column.data_type);
+            if dialect.type_mapper().to_native(&before.data_type) != native {
+                actions.push(format!("ALTER COLUMN {} TYPE {} USING {}::{}", name, native, name, native));
+            }
+            if before.nullable != column.nullable {
+                let action = if column.nullable { "DROP" } else { "SET" };
+                actions.push(format!("ALTER COLUMN {} {} NOT NULL", name, action));
+            }
+            if before.default != column.default {
+                actions.push(match &column.default {
+                    Some(default) => format!("ALTER COLUMN {} SET DEFAULT {}", name, default),
+                    None => format!("ALTER COLUMN {} DROP DEFAULT", name),
+                });
+            }
+            actions.join(", ")
+        }
+    }
+}
+
+/// SQLite's procedure for changes ALTER TABLE cannot make
+fn rebuild_statements(current: &TableDefinition, altered: &TableDefinition, dialect: &dyn SqlDialect) -> Vec<String> {
+    let mut copy = altered.clone();
+    copy.name = format!("_dataforge_new_{}", altered.name);
+    copy.if_not_exists = false;
+
+    // Columns that exist before and after keep their values; added ones get their default
+    let kept: Vec<String> = altered
+        .columns
+        .iter()
+        .filter(|c| current.columns.iter().any(|before| before.name.eq_ignore_ascii_case(&c.name)))
+        .map(|c| dialect.quote_identifier(&c.name))
+        .collect();
+
+    let mut statements = vec![
+        copy.create_table_sql(dialect),
+        format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {}",
+            copy.table_name(dialect),
+            kept.join(", "),
+            kept.join(", "),
+            current.table_name(dialect)
+        ),
+        format!("DROP TABLE {}", current.table_name(dialect)),
+        format!(
+            "ALTER TABLE {} RENAME TO {}",
+            copy.table_name(dialect),
+            dialect.quote_identifier(&altered.name)
+        ),
+    ];
+    statements.extend(altered.index_statements(dialect));
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::table::IndexDefinition;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect, SQLiteDialect};
+    use crate::database::types::AbstractType;
+
+    fn users() -> TableDefinition {
+        let mut table = TableDefinition::new("users")
+            .column(ColumnDefinition::new("id", AbstractType::Integer).not_null())
+            .column(ColumnDefinition::new("email", AbstractType::Varchar { length: Some(100) }))
+            .column(ColumnDefinition::new("age", AbstractType::SmallInt))
+            .primary_key(&["id".to_string()]);
+        table.indexes.push(IndexDefinition {
+            name: None,
+            columns: vec!["email".to_string()],
+            unique: true,
+        });
+        table
+    }
+
+    fn changes() -> Vec<ColumnChange> {
+        vec![
+            ColumnChange::Drop { name: "age".to_string() },
+            ColumnChange::Modify {
+                column: ColumnDefinition::new("email", AbstractType::Text).not_null(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_alter_in_place() {
+        assert_eq!(
+            alter_table_statements(&users(), &changes(), &PostgreSQLDialect::new()).unwrap(),
+            vec![
+                "ALTER TABLE \"users\" DROP COLUMN \"age\", \
+                 ALTER COLUMN \"email\" TYPE TEXT USING \"email\"::TEXT, ALTER COLUMN \"email\" SET NOT NULL"
+            ]
+        );
+        assert_eq!(
+            alter_table_statements(&users(), &changes(), &MySQLDialect::new()).unwrap(),
+            vec!["ALTER TABLE `users` DROP COLUMN `age`, MODIFY COLUMN `email` LONGTEXT NOT NULL"]
+        );
+    }
+
+    #[test]
+    fn test_sqlite_add_column() {
+        let add = vec![ColumnChange::Add {
+            column: ColumnDefinition::new("nickname", AbstractType::Text),
+        }];
+        assert_eq!(
+            alter_table_statements(&users(), &add, &SQLiteDialect::new()).unwrap(),
+            vec!["ALTER TABLE \"users\" ADD COLUMN \"nickname\" TEXT"]
+        );
+    }
+
+    #[test]
+    fn test_sqlite_rebuild() {
+        assert_eq!(
+            alter_table_statements(&users(), &changes(), &SQLiteDialect::new()).unwrap(),
+            vec![
+                "CREATE TABLE \"_dataforge_new_users\" (\n    \"id\" INTEGER NOT NULL,\n    \"email\" TEXT NOT NULL,\n    PRIMARY KEY (\"id\")\n)",
+                "INSERT INTO \"_dataforge_new_users\" (\"id\", \"email\") SELECT \"id\", \"email\" FROM \"users\"",
+                "DROP TABLE \"users\"",
+                "ALTER TABLE \"_dataforge_new_users\" RENAME TO \"users\"",
+                "CREATE UNIQUE INDEX \"idx_users_email\" ON \"users\" (\"email\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_changes() {
+        let sqlite = SQLiteDialect::new();
+        let drop_key = vec![ColumnChange::Drop { name: "id".to_string() }];
+        let drop_unknown = vec![ColumnChange::Drop { name: "missing".to_string() }];
+        let add_existing = vec![ColumnChange::Add {
+            column: ColumnDefinition::new("EMAIL", AbstractType::Text),
+        }];
+
+        assert!(alter_table_statements(&users(), &drop_key, &sqlite).is_err());
+        assert!(alter_table_statements(&users(), &drop_unknown, &sqlite).is_err());
+        assert!(alter_table_statements(&users(), &add_existing, &sqlite).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+pub mod postgres;
+pub mod mysql;
+pub mod sqlite;
+pub mod alter;
+pub mod table;
+pub mod summary;
+pub mod upsert;
+pub mod users;
+
+pub use postgres::PostgreSQLDialect;
+pub use mysql::MySQLDialect;
+pub use sqlite::SQLiteDialect;
+pub use alter::{alter_table_statements, ColumnChange};
+pub use table::{ColumnDefinition, TableDefinition};
+pub use summary::{Aggregate, SummaryBuilder, SummarySource};
+pub use upsert::{ConflictAction, UpsertBuilder};
+pub use users::{user_statement, UserChange};
+
+use crate::database::registry;
+use crate::database::types::TypeMapper;
+use crate::database::DatabaseType;
+
+/// How a database stores identifiers written without quotes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierCase {
+    /// Folded to lowercase; quoted names keep their case and compare exactly
+    Lower,
+    /// Kept as written; names compare ignoring case
+    Preserve,
+}
+
+/// SQL dialect trait for database-specific SQL generation
+pub trait SqlDialect: Send + Sync {
+    /// Quote an identifier (table or column name) according to database rules
+    /// 
+    /// # Examples
+    /// - PostgreSQL: "table_name" -> "table_name"
+    /// - MySQL: `table_name` -> `table_name`
+    /// - SQLite: "table_name" -> "table_name"
+    fn quote_identifier(&self, identifier: &str) -> String;
+    
+    /// How unquoted identifiers are folded before they are looked up
+    ///
+    /// # Examples
+    /// - PostgreSQL: Lower (SELECT * FROM Users reads users)
+    /// - MySQL/SQLite: Preserve
+    fn identifier_case(&self) -> IdentifierCase;
+    
+    /// Name in the catalog that an unquoted identifier refers to
+    fn fold_identifier(&self, identifier: &str) -> String {
+        match self.identifier_case() {
+            IdentifierCase::Lower => identifier.to_lowercase(),
+            IdentifierCase::Preserve => identifier.to_string(),
+        }
+    }
+    
+    /// Whether two catalog names refer to the same object
+    ///
+    /// MySQL table names are case sensitive where lower_case_table_names is 0,
+    /// but its column names never are; both are compared ignoring case.
+    fn identifiers_equal(&self, a: &str, b: &str) -> bool {
+        match self.identifier_case() {
+            IdentifierCase::Lower => a == b,
+            IdentifierCase::Preserve => a.eq_ignore_ascii_case(b),
+        }
+    }
+    
+    /// Generate a LIMIT/OFFSET clause
+    /// 
+    /// # Examples
+    /// - PostgreSQL/SQLite: "LIMIT 10 OFFSET 20"
+    /// - MySQL: "LIMIT 20, 10" or "LIMIT 10 OFFSET 20"
+    fn limit_clause(&self, limit: Option<usize>, offset: Option<usize>) -> String;
+    
+    /// Generate a WHERE clause that seeks past the last row of the previous page
+    ///
+    /// Rows are read in ascending order of `order_columns`, which must identify a
+    /// row (e.g. the primary key); `last_values` are that row's values. Unlike
+    /// OFFSET, the database skips the earlier rows through the index, and pages do
+    /// not shift when rows are inserted. Empty for the first page.
+    ///
+    /// # Examples
+    /// - PostgreSQL/SQLite: ` WHERE ("a", "b") > ('1', '2')`
+    /// - MySQL: " WHERE (`a` > '1' OR (`a` = '1' AND `b` > '2'))"
+    fn keyset_clause(&self, order_columns: &[&str], last_values: &[&str]) -> String;
+
+    /// Name `query` as a common table expression for `body` to select from
+    ///
+    /// Needs `DatabaseCapabilities::common_table_expressions`.
+    ///
+    /// # Examples
+    /// - `WITH "recent" AS (SELECT ...) SELECT * FROM "recent"`
+    fn with_cte(&self, name: &str, query: &str, body: &str) -> String {
+        format!("WITH {} AS ({}) {}", self.quote_identifier(name), query, body)
+    }
+
+    /// Rows `offset + 1` to `offset + limit` of `query` in `order_by` order
+    ///
+    /// The rows are numbered with ROW_NUMBER() in a `_row_number` column, so each
+    /// row keeps its position in the whole result. Needs both
+    /// `DatabaseCapabilities::window_functions` and `common_table_expressions`.
+    fn row_number_page(&self, query: &str, order_by: &[&str], offset: usize, limit: usize) -> String {
+        let order: Vec<String> = order_by.iter().map(|c| self.quote_identifier(c)).collect();
+        let order = if order.is_empty() {
+            String::new()
+        } else {
+            format!("ORDER BY {}", order.join(", "))
+        };
+        let row_number = self.quote_identifier("_row_number");
+        let numbered = format!(
+            "SELECT {q}.*, ROW_NUMBER() OVER ({}) AS {} FROM ({}) {q}",
+            order,
+            row_number,
+            query,
+            q = self.quote_identifier("_query")
+        );
+
+        self.with_cte(
+            "_page",
+            &numbered,
+            &format!(
+                "SELECT * FROM {} WHERE {} BETWEEN {} AND {} ORDER BY {}",
+                self.quote_identifier("_page"),
+                row_number,
+                offset + 1,
+                offset + limit,
+                row_number
+            ),
+        )
+    }
+
+    /// Quote text as a string literal
+    ///
+    /// Every value rendered into SQL as text goes through here.
+    ///
+    /// # Examples
+    /// - PostgreSQL/SQLite: O'Brien -> 'O''Brien'
+    /// - MySQL: C:\temp -> 'C:\\temp' (backslashes escape unless NO_BACKSLASH_ESCAPES is set)
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+    
+    /// Format bytes as a binary literal
+    ///
+    /// # Examples
+    /// - PostgreSQL: '\xdeadbeef'::bytea
+    /// - MySQL/SQLite: X'deadbeef'
+    fn bytes_literal(&self, value: &[u8]) -> String {
+        format!("X'{}'", hex(value))
+    }
+    
+    /// Format a number literal, or `None` when `value` is not a number, so that
+    /// text is never pasted into SQL unquoted
+    ///
+    /// # Examples
+    /// - "42", "-1.5", "6.02e23" -> unchanged
+    /// - "NaN": PostgreSQL 'NaN'::double precision, MySQL/SQLite None
+    fn number_literal(&self, value: &str) -> Option<String> {
+        plain_number(value)
+    }
+    
+    /// Format a boolean literal
+    /// 
+    /// # Examples
+    /// - PostgreSQL: true/false or TRUE/FALSE
+    /// - MySQL: 1/0 or TRUE/FALSE
+    /// - SQLite: 1/0
+    fn boolean_literal(&self, value: bool) -> String;
+    
+    /// Get the current timestamp syntax
+    /// 
+    /// # Examples
+    /// - PostgreSQL: CURRENT_TIMESTAMP or NOW()
+    /// - MySQL: CURRENT_TIMESTAMP or NOW()
+    /// - SQLite: CURRENT_TIMESTAMP
+    fn current_timestamp(&self) -> &'static str;
+    
+    /// Get the auto-increment column type definition
+    /// 
+    /// # Examples
+    /// - PostgreSQL: "SERIAL" or "BIGSERIAL"
+    /// - MySQL: "INT AUTO_INCREMENT"
+    /// - SQLite: "INTEGER PRIMARY KEY AUTOINCREMENT"
+    fn auto_increment_type(&self) -> &'static str;
+    
+    /// Get the string concatenation operator or function
+    /// 
+    /// # Examples
+    /// - PostgreSQL: "||" operator
+    /// - MySQL: CONCAT() function
+    /// - SQLite: "||" operator
+    fn string_concat(&self, left: &str, right: &str) -> String;
+    
+    /// Get the case-insensitive LIKE operator
+    /// 
+    /// # Examples
+    /// - PostgreSQL: ILIKE
+    /// - MySQL: LIKE (case-insensitive by default)
+    /// - SQLite: LIKE (case-insensitive by default)
+    fn case_insensitive_like(&self) -> &'static str;
+    
+    /// Match an expression against a regular expression, or `None` when the
+    /// database has no regex operator
+    ///
+    /// # Examples
+    /// - PostgreSQL: "name" ~ '^a.*z$'
+    /// - MySQL: `name` REGEXP '^a.*z$'
+    /// - SQLite: None (REGEXP calls a function the application has to provide)
+    fn regex_match(&self, expression: &str, pattern: &str) -> Option<String>;
+    
+    /// Extract the value at `path` of a JSON document as text
+    ///
+    /// Path elements are object keys, or array indexes when numeric.
+    ///
+    /// # Examples
+    /// - PostgreSQL: "doc"->'tags'->>0
+    /// - MySQL: JSON_UNQUOTE(JSON_EXTRACT(`doc`, '$.tags[0]'))
+    /// - SQLite: json_extract("doc", '$.tags[0]')
+    fn json_extract(&self, expression: &str, path: &[&str]) -> String;
+    
+    /// Format a date/datetime literal
+    /// 
+    /// # Examples
+    /// - PostgreSQL: DATE '2023-01-01', TIMESTAMP '2023-01-01 12:00:00'
+    /// - MySQL: '2023-01-01', '2023-01-01 12:00:00'
+    /// - SQLite: '2023-01-01', '2023-01-01 12:00:00'
+    fn date_literal(&self, date: &str) -> String;
+    fn datetime_literal(&self, datetime: &str) -> String;
+    
+    /// Get the database type this dialect is for
+    fn database_type(&self) -> DatabaseType;
+    
+    /// Check if the database supports a specific feature
+    fn supports_returning_clause(&self) -> bool;
+    fn supports_upsert(&self) -> bool;
+    fn supports_schemas(&self) -> bool;
+    
+    /// Build a fully qualified table name with optional schema
+    fn qualified_table_name(&self, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            Some(s) if self.supports_schemas() => {
+                format!("{}.{}", self.quote_identifier(s), self.quote_identifier(table))
+            }
+            _ => self.quote_identifier(table),
+        }
+    }
+    
+    /// Get the appropriate NULL check syntax
+    fn is_null(&self, column: &str) -> String {
+        format!("{} IS NULL", column)
+    }
+    
+    fn is_not_null(&self, column: &str) -> String {
+        format!("{} IS NOT NULL", column)
+    }
+    
+    /// Mapper between this database's column types and the abstract type model
+    fn type_mapper(&self) -> TypeMapper {
+        TypeMapper::new(self.database_type())
+    }
+    
+    /// Get the CAST syntax
+    fn cast(&self, expression: &str, data_type: &str) -> String {
+        format!("CAST({} AS {})", expression, data_type)
+    }
+}
+
+/// JSON path of MySQL and SQLite, e.g. `$.tags[0]`, quoting keys that are
+/// not plain identifiers
+fn json_path(path: &[&str]) -> String {
+    path.iter().fold("$".to_string(), |mut json_path, element| {
+        if !element.is_empty() && element.chars().all(|c| c.is_ascii_digit()) {
+            json_path.push_str(&format!("[{}]", element));
+        } else if !element.is_empty() && element.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            json_path.push_str(&format!(".{}", element));
+        } else {
+            json_path.push_str(&format!(".\"{}\"", element.replace('\\', "\\\\").replace('"', "\\\"")));
+        }
+        json_path
+    })
+}
+
+/// Lowercase hex digits of `bytes`
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// `value` trimmed if it is a finite decimal number: an optional minus sign,
+/// digits with an optional fraction, and an optional exponent
+fn plain_number(value: &str) -> Option<String> {
+    let value = value.trim();
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let valid_mantissa = digits(whole) && digits(fraction) && !(whole.is_empty() && fraction.is_empty());
+    let valid_exponent = exponent.is_none_or(|e| {
+        let e = e.strip_prefix(['+', '-']).unwrap_or(e);
+        !e.is_empty() && digits(e)
+    });
+
+    (valid_mantissa && valid_exponent).then(|| value.to_string())
+}
+
+/// Seek condition over quoted columns and literals, as a row value comparison or,
+/// for databases that cannot use an index for those, expanded into ORs
+fn seek_condition(columns: &[String], literals: &[String], row_values: bool) -> String {
+    if columns.is_empty() || columns.len() != literals.len() {
+        return String::new();
+    }
+
+    if columns.len() == 1 {
+        return format!(" WHERE {} > {}", columns[0], literals[0]);
+    }
+
+    if row_values {
+        return format!(" WHERE ({}) > ({})", columns.join(", "), literals.join(", "));
+    }
+
+    // (a > x) OR (a = x AND b > y) OR (a = x AND b = y AND c > z) ...
+    let alternatives: Vec<String> = (0..columns.len())
+        .map(|i| {
+            let mut terms: Vec<String> = (0..i)
+                .map(|j| format!("{} = {}", columns[j], literals[j]))
+                .collect();
+            terms.push(format!("{} > {}", columns[i], literals[i]));
+            if terms.len() == 1 {
+                terms.remove(0)
+            } else {
+                format!("({})", terms.join(" AND "))
+            }
+        })
+        .collect();
+    format!(" WHERE ({})", alternatives.join(" OR "))
+}
+
+/// Factory function to create appropriate dialect
+pub fn create_dialect(database_type: DatabaseType) -> Box<dyn SqlDialect> {
+    // Built-in types always have a driver, and other types come from registered ones
+    let driver = registry::driver(database_type.driver_id()).expect("database types have a registered driver");
+    (driver.create_dialect)()
+}
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod basic_tests {
+    use super::*;
+    
+    #[test]
+    fn test_dialect_creation() {
+        let pg_dialect = create_dialect(DatabaseType::PostgreSQL);
+        assert_eq!(pg_dialect.database_type(), DatabaseType::PostgreSQL);
+        
+        let mysql_dialect = create_dialect(DatabaseType::MySQL);
+        assert_eq!(mysql_dialect.database_type(), DatabaseType::MySQL);
+        
+        let sqlite_dialect = create_dialect(DatabaseType::SQLite);
+        assert_eq!(sqlite_dialect.database_type(), DatabaseType::SQLite);
+    }
+}
\ No newline at end of file
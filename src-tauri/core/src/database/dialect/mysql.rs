@@ -1,4 +1,4 @@
-use super::SqlDialect;
+use super::{IdentifierCase, SqlDialect};
 use crate::database::DatabaseType;
 
 /// MySQL-specific SQL dialect implementation
@@ -19,6 +19,11 @@ impl SqlDialect for MySQLDialect {
         format!("`{}`", escaped)
     }
     
+    fn identifier_case(&self) -> IdentifierCase {
+        // MySQL keeps identifiers as written
+        IdentifierCase::Preserve
+    }
+    
     fn limit_clause(&self, limit: Option<usize>, offset: Option<usize>) -> String {
         match (limit, offset) {
             (Some(limit_val), Some(offset_val)) => {
@@ -38,6 +43,19 @@ impl SqlDialect for MySQLDialect {
         }
     }
     
+    fn keyset_clause(&self, order_columns: &[&str], last_values: &[&str]) -> String {
+        // MySQL does not use indexes for row value comparisons in older versions,
+        // so spell the comparison out
+        let columns: Vec<String> = order_columns.iter().map(|c| self.quote_identifier(c)).collect();
+        let literals: Vec<String> = last_values.iter().map(|v| self.string_literal(v)).collect();
+        super::seek_condition(&columns, &literals, false)
+    }
+    
+    fn string_literal(&self, value: &str) -> String {
+        // Backslashes escape in string literals unless NO_BACKSLASH_ESCAPES is set
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    }
+    
     fn boolean_literal(&self, value: bool) -> String {
         // MySQL treats 1/0 as boolean values
         // It also accepts TRUE/FALSE keywords
@@ -69,14 +87,28 @@ impl SqlDialect for MySQLDialect {
         "LIKE"
     }
     
+    fn regex_match(&self, expression: &str, pattern: &str) -> Option<String> {
+        // MySQL uses the REGEXP operator
+        Some(format!("{} REGEXP {}", expression, self.string_literal(pattern)))
+    }
+    
+    fn json_extract(&self, expression: &str, path: &[&str]) -> String {
+        // JSON_EXTRACT returns JSON; unquote strings so they compare as text
+        format!(
+            "JSON_UNQUOTE(JSON_EXTRACT({}, {}))",
+            expression,
+            self.string_literal(&super::json_path(path))
+        )
+    }
+    
     fn date_literal(&self, date: &str) -> String {
         // MySQL uses string literals for dates
-        format!("'{}'", date)
+        self.string_literal(date)
     }
     
     fn datetime_literal(&self, datetime: &str) -> String {
         // MySQL uses string literals for datetime
-        format!("'{}'", datetime)
+        self.string_literal(datetime)
     }
     
     fn database_type(&self) -> DatabaseType {
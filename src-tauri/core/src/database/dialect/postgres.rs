@@ -1,4 +1,4 @@
-use super::SqlDialect;
+use super::{IdentifierCase, SqlDialect};
 use crate::database::DatabaseType;
 
 /// PostgreSQL-specific SQL dialect implementation
@@ -19,6 +19,11 @@ impl SqlDialect for PostgreSQLDialect {
         format!(r#""{}""#, escaped)
     }
     
+    fn identifier_case(&self) -> IdentifierCase {
+        // PostgreSQL folds unquoted identifiers to lowercase
+        IdentifierCase::Lower
+    }
+    
     fn limit_clause(&self, limit: Option<usize>, offset: Option<usize>) -> String {
         let mut clause = String::new();
         
@@ -33,6 +38,26 @@ impl SqlDialect for PostgreSQLDialect {
         clause
     }
     
+    fn keyset_clause(&self, order_columns: &[&str], last_values: &[&str]) -> String {
+        // Row value comparisons can use a multicolumn index
+        let columns: Vec<String> = order_columns.iter().map(|c| self.quote_identifier(c)).collect();
+        let literals: Vec<String> = last_values.iter().map(|v| self.string_literal(v)).collect();
+        super::seek_condition(&columns, &literals, true)
+    }
+    
+    fn bytes_literal(&self, value: &[u8]) -> String {
+        // bytea accepts hex input; standard_conforming_strings keeps the backslash
+        format!("'\\x{}'::bytea", super::hex(value))
+    }
+    
+    fn number_literal(&self, value: &str) -> Option<String> {
+        // Floating point columns also hold these special values
+        match value.trim() {
+            special @ ("NaN" | "Infinity" | "-Infinity") => Some(format!("'{}'::double precision", special)),
+            other => super::plain_number(other),
+        }
+    }
+    
     fn boolean_literal(&self, value: bool) -> String {
         // PostgreSQL accepts TRUE/FALSE, true/false, 't'/'f', '1'/'0'
         // We'll use the standard TRUE/FALSE
@@ -64,14 +89,40 @@ impl SqlDialect for PostgreSQLDialect {
         "ILIKE"
     }
     
+    fn regex_match(&self, expression: &str, pattern: &str) -> Option<String> {
+        // PostgreSQL has POSIX regular expression operators (~ and ~* ignoring case)
+        Some(format!("{} ~ {}", expression, self.string_literal(pattern)))
+    }
+    
+    fn json_extract(&self, expression: &str, path: &[&str]) -> String {
+        // -> steps into the document, ->> returns the last value as text
+        let steps: Vec<String> = path
+            .iter()
+            .map(|element| {
+                if !element.is_empty() && element.chars().all(|c| c.is_ascii_digit()) {
+                    element.to_string()
+                } else {
+                    self.string_literal(element)
+                }
+            })
+            .collect();
+        match steps.split_last() {
+            Some((last, inner)) => {
+                let inner: String = inner.iter().map(|step| format!("->{}", step)).collect();
+                format!("{}{}->>{}", expression, inner, last)
+            }
+            None => expression.to_string(),
+        }
+    }
+    
     fn date_literal(&self, date: &str) -> String {
         // PostgreSQL uses DATE 'YYYY-MM-DD' format
-        format!("DATE '{}'", date)
+        format!("DATE {}", self.string_literal(date))
     }
     
     fn datetime_literal(&self, datetime: &str) -> String {
         // PostgreSQL uses TIMESTAMP 'YYYY-MM-DD HH:MM:SS' format
-        format!("TIMESTAMP '{}'", datetime)
+        format!("TIMESTAMP {}", self.string_literal(datetime))
     }
     
     fn database_type(&self) -> DatabaseType {
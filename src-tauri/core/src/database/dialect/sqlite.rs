@@ -1,4 +1,4 @@
-use super::SqlDialect;
+use super::{IdentifierCase, SqlDialect};
 use crate::database::DatabaseType;
 
 /// SQLite-specific SQL dialect implementation
@@ -20,6 +20,11 @@ impl SqlDialect for SQLiteDialect {
         format!(r#""{}""#, escaped)
     }
     
+    fn identifier_case(&self) -> IdentifierCase {
+        // SQLite keeps identifiers as written and compares them ignoring case
+        IdentifierCase::Preserve
+    }
+    
     fn limit_clause(&self, limit: Option<usize>, offset: Option<usize>) -> String {
         let mut clause = String::new();
         
@@ -39,6 +44,22 @@ impl SqlDialect for SQLiteDialect {
         clause
     }
     
+    fn keyset_clause(&self, order_columns: &[&str], last_values: &[&str]) -> String {
+        // Row values are supported since SQLite 3.15
+        let columns: Vec<String> = order_columns.iter().map(|c| self.quote_identifier(c)).collect();
+        let literals: Vec<String> = last_values.iter().map(|v| self.string_literal(v)).collect();
+        super::seek_condition(&columns, &literals, true)
+    }
+    
+    fn number_literal(&self, value: &str) -> Option<String> {
+        // REAL columns hold infinities, written as numbers too large for a double
+        match value.trim() {
+            "Inf" | "Infinity" => Some("9e999".to_string()),
+            "-Inf" | "-Infinity" => Some("-9e999".to_string()),
+            other => super::plain_number(other),
+        }
+    }
+    
     fn boolean_literal(&self, value: bool) -> String {
         // SQLite stores booleans as integers (0 or 1)
         if value {
@@ -71,16 +92,26 @@ impl SqlDialect for SQLiteDialect {
         "LIKE"
     }
     
+    fn regex_match(&self, _expression: &str, _pattern: &str) -> Option<String> {
+        // SQLite parses REGEXP but fails unless a regexp() function is registered
+        None
+    }
+    
+    fn json_extract(&self, expression: &str, path: &[&str]) -> String {
+        // json_extract returns strings as SQL text
+        format!("json_extract({}, {})", expression, self.string_literal(&super::json_path(path)))
+    }
+    
     fn date_literal(&self, date: &str) -> String {
         // SQLite stores dates as strings, numbers, or NULL
         // String format should be 'YYYY-MM-DD'
-        format!("'{}'", date)
+        self.string_literal(date)
     }
     
     fn datetime_literal(&self, datetime: &str) -> String {
         // SQLite stores datetime as strings
         // String format should be 'YYYY-MM-DD HH:MM:SS'
-        format!("'{}'", datetime)
+        self.string_literal(datetime)
     }
     
     fn database_type(&self) -> DatabaseType {
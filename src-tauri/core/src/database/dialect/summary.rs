@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+
+use super::SqlDialect;
+use crate::database::types::AbstractType;
+use crate::error::AppError;
+
+/// Most group columns a summary can have: one for the categories of the
+/// chart, one to split its values into series
+pub const MAX_GROUP_COLUMNS: usize = 2;
+
+/// Rows a summary is summarized from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SummarySource {
+    /// "table" or "schema.table"
+    Table { name: String },
+    /// A SELECT query
+    Query { sql: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateFunction {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+/// Aggregate computed for each group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    /// Column aggregated; COUNT counts rows without one
+    #[serde(default)]
+    pub column: Option<String>,
+    /// Name of the series in the chart; derived from the function and column when unset
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl Aggregate {
+    pub fn label(&self) -> String {
+        if let Some(label) = self.label.as_ref().filter(|l| !l.trim().is_empty()) {
+            return label.clone();
+        }
+        let function = match self.function {
+            AggregateFunction::Count => "count",
+            AggregateFunction::CountDistinct => "count distinct",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        };
+        match &self.column {
+            Some(column) => format!("{} of {}", function, column),
+            None => function.to_string(),
+        }
+    }
+
+    /// SQL of the aggregate; sums and averages are cast to a float, since
+    /// PostgreSQL would return NUMERIC for integer and decimal columns
+    fn expression(&self, dialect: &dyn SqlDialect) -> Result<String, AppError> {
+        let float = dialect.type_mapper().to_native(&AbstractType::Double);
+        let column = self.column.as_deref().map(|c| dialect.quote_identifier(c));
+        let expression = match (self.function, column) {
+            (AggregateFunction::Count, None) => "COUNT(*)".to_string(),
+            (AggregateFunction::Count, Some(column)) => format!("COUNT({})", column),
+            (AggregateFunction::CountDistinct, Some(column)) => format!("COUNT(DISTINCT {})", column),
+            (AggregateFunction::Sum, Some(column)) => dialect.cast(&format!("SUM({})", column), &float),
+            (AggregateFunction::Avg, Some(column)) => dialect.cast(&format!("AVG({})", column), &float),
+            (AggregateFunction::Min, Some(column)) => format!("MIN({})", column),
+            (AggregateFunction::Max, Some(column)) => format!("MAX({})", column),
+            (function, None) => {
+                return Err(AppError::Validation(format!("{:?} needs a column to aggregate", function)));
+            }
+        };
+        Ok(expression)
+    }
+}
+
+/// Column alias of the `i`th group column in a summary query
+pub fn group_alias(i: usize) -> String {
+    format!("_group{}", i)
+}
+
+/// Column alias of the `i`th aggregate in a summary query
+pub fn value_alias(i: usize) -> String {
+    format!("_value{}", i)
+}
+
+/// Builds the GROUP BY query of a summary in the syntax of the dialect
+///
+/// Groups come back ordered by their columns, with the group columns and the
+/// aggregates under the aliases of `group_alias` and `value_alias`.
+///
+/// # Examples
+/// - `SELECT "region" AS "_group0", SUM("amount") AS "_value0" FROM "sales" WHERE ... GROUP BY "region" ORDER BY 1 LIMIT 1000`
+pub struct SummaryBuilder<'a> {
+    dialect: &'a dyn SqlDialect,
+    source: SummarySource,
+    group_by: Vec<String>,
+    aggregates: Vec<Aggregate>,
+    conditions: Vec<String>,
+    limit: Option<usize>,
+}
+
+impl<'a> SummaryBuilder<'a> {
+    pub fn new(dialect: &'a dyn SqlDialect, source: SummarySource) -> Self {
+        Self {
+            dialect,
+            source,
+            group_by: Vec::new(),
+            aggregates: Vec::new(),
+            conditions: Vec::new(),
+            limit: None,
+        }
+    }
+
+    pub fn group_by(mut self, columns: &[String]) -> Self {
+        self.group_by = columns.to_vec();
+        self
+    }
+
+    pub fn aggregates(mut self, aggregates: &[Aggregate]) -> Self {
+        self.aggregates = aggregates.to_vec();
+        self
+    }
+
+    /// SQL conditions every summarized row must meet
+    pub fn conditions(mut self, conditions: Vec<String>) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// Most groups returned
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn source_sql(&self) -> Result<String, AppError> {
+        match &self.source {
+            SummarySource::Table { name } => {
+                if name.trim().is_empty() {
+                    return Err(AppError::Validation("Table name is required".to_string()));
+                }
+                Ok(match name.split_once('.') {
+                    Some((schema, table)) => self.dialect.qualified_table_name(Some(schema), table),
+                    None => self.dialect.qualified_table_name(None, name),
+                })
+            }
+            SummarySource::Query { sql } => {
+                let sql = sql.trim().trim_end_matches(';').trim_end();
+                if sql.is_empty() {
+                    return Err(AppError::Validation("Query is empty".to_string()));
+                }
+                Ok(format!("({}) {}", sql, self.dialect.quote_identifier("_source")))
+            }
+        }
+    }
+
+    pub fn build(&self) -> Result<String, AppError> {
+        if self.aggregates.is_empty() {
+            return Err(AppError::Validation("At least one aggregate is required".to_string()));
+        }
+        if self.group_by.len() > MAX_GROUP_COLUMNS {
+            return Err(AppError::Validation(format!(
+                "A summary can be grouped by at most {} columns",
+                MAX_GROUP_COLUMNS
+            )));
+        }
+
+        let groups: Vec<String> = self.group_by.iter().map(|c| self.dialect.quote_identifier(c)).collect();
+        let mut select: Vec<String> = groups
+            .iter()
+            .enumerate()
+            .map(|(i, column)| format!("{} AS {}", column, self.dialect.quote_identifier(&group_alias(i))))
+            .collect();
+        for (i, aggregate) in self.aggregates.iter().enumerate() {
+            select.push(format!(
+                "{} AS {}",
+                aggregate.expression(self.dialect)?,
+                self.dialect.quote_identifier(&value_alias(i))
+            ));
+        }
+
+        let mut query = format!("SELECT {} FROM {}", select.join(", "), self.source_sql()?);
+        if !self.conditions.is_empty() {
+            query.push_str(&format!(" WHERE {}", self.conditions.join(" AND ")));
+        }
+        if !groups.is_empty() {
+            let positions: Vec<String> = (1..=groups.len()).map(|i| i.to_string()).collect();
+            query.push_str(&format!(" GROUP BY {} ORDER BY {}", groups.join(", "), positions.join(", ")));
+        }
+        query.push_str(&self.dialect.limit_clause(self.limit, None));
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect};
+
+    fn aggregate(function: AggregateFunction, column: Option<&str>) -> Aggregate {
+        Aggregate { function, column: column.map(str::to_string), label: None }
+    }
+
+    #[test]
+    fn test_summary_query() {
+        let pg = PostgreSQLDialect::new();
+        let query = SummaryBuilder::new(&pg, SummarySource::Table { name: "shop.orders".to_string() })
+            .group_by(&["status".to_string()])
+            .aggregates(&[aggregate(AggregateFunction::Count, None), aggregate(AggregateFunction::Sum, Some("total"))])
+            .conditions(vec![r#""country" = 'Japan'"#.to_string()])
+            .limit(100)
+            .build()
+            .unwrap();
+        assert_eq!(
+            query,
+            r#"SELECT "status" AS "_group0", COUNT(*) AS "_value0", CAST(SUM("total") AS DOUBLE PRECISION) AS "_value1" FROM "shop"."orders" WHERE "country" = 'Japan' GROUP BY "status" ORDER BY 1 LIMIT 100"#
+        );
+
+        let mysql = MySQLDialect::new();
+        let query = SummaryBuilder::new(&mysql, SummarySource::Query { sql: "SELECT * FROM t;".to_string() })
+            .aggregates(&[aggregate(AggregateFunction::CountDistinct, Some("user_id"))])
+            .build()
+            .unwrap();
+        assert_eq!(query, "SELECT COUNT(DISTINCT `user_id`) AS `_value0` FROM (SELECT * FROM t) `_source`");
+        let query = SummaryBuilder::new(&mysql, SummarySource::Table { name: "t".to_string() })
+            .aggregates(&[aggregate(AggregateFunction::Avg, Some("price"))])
+            .build()
+            .unwrap();
+        assert_eq!(query, "SELECT CAST(AVG(`price`) AS DOUBLE) AS `_value0` FROM `t`");
+
+        assert!(SummaryBuilder::new(&pg, SummarySource::Table { name: "t".to_string() })
+            .aggregates(&[aggregate(AggregateFunction::Avg, None)])
+            .build()
+            .is_err());
+        assert_eq!(aggregate(AggregateFunction::Max, Some("price")).label(), "max of price");
+    }
+}
@@ -0,0 +1,451 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::SqlDialect;
+use crate::database::types::AbstractType;
+use crate::database::DatabaseType;
+use crate::error::AppError;
+
+/// Definition of a table, independent of the database it is created in
+///
+/// `create_statements` renders it as the CREATE TABLE and CREATE INDEX
+/// statements of a dialect, with column types mapped through its `TypeMapper`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableDefinition {
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub name: String,
+    pub columns: Vec<ColumnDefinition>,
+    #[serde(default)]
+    pub primary_key: Vec<String>,
+    /// Column sets that must be unique, besides the primary key
+    #[serde(default)]
+    pub unique: Vec<Vec<String>>,
+    /// SQL boolean expressions every row must satisfy
+    #[serde(default)]
+    pub checks: Vec<String>,
+    #[serde(default)]
+    pub foreign_keys: Vec<ForeignKeyDefinition>,
+    #[serde(default)]
+    pub indexes: Vec<IndexDefinition>,
+    /// Render CREATE TABLE IF NOT EXISTS
+    #[serde(default)]
+    pub if_not_exists: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub data_type: AbstractType,
+    #[serde(default = "default_nullable")]
+    pub nullable: bool,
+    /// SQL expression of the default value, e.g. `0` or `CURRENT_TIMESTAMP`
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Generate values from a sequence; only for integer primary key columns
+    #[serde(default)]
+    pub auto_increment: bool,
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignKeyDefinition {
+    pub columns: Vec<String>,
+    pub references_table: String,
+    pub references_columns: Vec<String>,
+    #[serde(default)]
+    pub on_delete: Option<ReferentialAction>,
+}
+
+/// What happens to referencing rows when the referenced row is deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferentialAction {
+    Cascade,
+    SetNull,
+    Restrict,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexDefinition {
+    /// Defaults to `idx_<table>_<columns>`
+    #[serde(default)]
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub unique: bool,
+}
+
+impl ColumnDefinition {
+    pub fn new(name: &str, data_type: AbstractType) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+            default: None,
+            auto_increment: false,
+        }
+    }
+
+    pub fn not_null(mut self) -> Self {
+        self.nullable = false;
+        self
+    }
+}
+
+impl TableDefinition {
+    pub fn new(name: &str) -> Self {
+        Self {
+            schema: None,
+            name: name.to_string(),
+            columns: Vec::new(),
+            primary_key: Vec::new(),
+            unique: Vec::new(),
+            checks: Vec::new(),
+            foreign_keys: Vec::new(),
+            indexes: Vec::new(),
+            if_not_exists: false,
+        }
+    }
+
+    pub fn column(mut self, column: ColumnDefinition) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    pub fn primary_key(mut self, columns: &[String]) -> Self {
+        self.primary_key = columns.to_vec();
+        self
+    }
+
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_not_exists = true;
+        self
+    }
+
+    /// Check that the definition can be created in the dialect's database
+    pub fn validate(&self, dialect: &dyn SqlDialect) -> Result<(), AppError> {
+        let invalid = |message: String| Err(AppError::Validation(message));
+
+        if self.name.trim().is_empty() {
+            return invalid("Table name is required".to_string());
+        }
+        if self.columns.is_empty() {
+            return invalid(format!("Table '{}' needs at least one column", self.name));
+        }
+
+        let mut names = HashSet::new();
+        for column in &self.columns {
+            if column.name.trim().is_empty() {
+                return invalid(format!("Table '{}' has a column without a name", self.name));
+            }
+            if !names.insert(column.name.to_lowercase()) {
+                return invalid(format!("Column '{}' is defined more than once", column.name));
+            }
+        }
+
+        let referenced = self
+            .primary_key
+            .iter()
+            .chain(self.unique.iter().flatten())
+            .chain(self.foreign_keys.iter().flat_map(|fk| &fk.columns))
+            .chain(self.indexes.iter().flat_map(|index| &index.columns));
+        for name in referenced {
+            if !names.contains(&name.to_lowercase()) {
+                return invalid(format!("Column '{}' does not exist in table '{}'", name, self.name));
+            }
+        }
+
+        if let Some(fk) = self.foreign_keys.iter().find(|fk| fk.columns.len() != fk.references_columns.len()) {
+            return invalid(format!(
+                "Foreign key to '{}' references {} columns with {}",
+                fk.references_table,
+                fk.references_columns.len(),
+                fk.columns.len()
+            ));
+        }
+        if let Some(index) = self.indexes.iter().find(|index| index.columns.is_empty()) {
+            return invalid(format!("Index {} has no columns", self.index_name(index)));
+        }
+
+        // MySQL can only index TEXT and BLOB columns by a prefix of given length
+        if dialect.database_type() == DatabaseType::MySQL {
+            let native = |name: &String| {
+                self.columns
+                    .iter()
+                    .find(|c| c.name.eq_ignore_ascii_case(name))
+                    .map(|c| dialect.type_mapper().to_native(&c.data_type))
+                    .unwrap_or_default()
+            };
+            let mut keys = self.primary_key.iter().chain(self.unique.iter().flatten());
+            if let Some(name) = keys.find(|name| native(name).ends_with("TEXT") || native(name).ends_with("BLOB")) {
+                return invalid(format!("Key column '{}' needs a type with a length in MySQL", name));
+            }
+        }
+
+        let auto_increment: Vec<&ColumnDefinition> = self.columns.iter().filter(|c| c.auto_increment).collect();
+        if auto_increment.len() > 1 {
+            return invalid("Only one column can be auto-incremented".to_string());
+        }
+        if let Some(column) = auto_increment.first() {
+            if !matches!(
+                column.data_type,
+                AbstractType::SmallInt | AbstractType::Integer | AbstractType::BigInt
+            ) {
+                return invalid(format!("Auto-increment column '{}' must be an integer", column.name));
+            }
+            // MySQL needs the column to be a key; SQLite only generates values for a sole INTEGER PRIMARY KEY
+            let sole_key = self.primary_key.len() == 1 && self.primary_key[0].eq_ignore_ascii_case(&column.name);
+            let is_key = self.primary_key.iter().any(|k| k.eq_ignore_ascii_case(&column.name));
+            let valid = match dialect.database_type() {
+                DatabaseType::SQLite => sole_key,
+                DatabaseType::MySQL => is_key,
+                DatabaseType::PostgreSQL | DatabaseType::Other(_) => true,
+            };
+            if !valid {
+                return invalid(format!(
+                    "Auto-increment column '{}' must be the primary key in {:?}",
+                    column.name,
+                    dialect.database_type()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// CREATE TABLE statement followed by one CREATE INDEX statement per index
+    pub fn create_statements(&self, dialect: &dyn SqlDialect) -> Result<Vec<String>, AppError> {
+        self.validate(dialect)?;
+
+        let mut statements = vec![self.create_table_sql(dialect)];
+        statements.extend(self.index_statements(dialect));
+        Ok(statements)
+    }
+
+    pub(super) fn index_statements(&self, dialect: &dyn SqlDialect) -> Vec<String> {
+        self.indexes
+            .iter()
+            .map(|index| {
+                format!(
+                    "CREATE {}INDEX {} ON {} ({})",
+                    if index.unique { "UNIQUE " } else { "" },
+                    dialect.quote_identifier(&self.index_name(index)),
+                    self.table_name(dialect),
+                    quote_list(dialect, &index.columns)
+                )
+            })
+            .collect()
+    }
+
+    pub(super) fn table_name(&self, dialect: &dyn SqlDialect) -> String {
+        dialect.qualified_table_name(self.schema.as_deref(), &self.name)
+    }
+
+    fn index_name(&self, index: &IndexDefinition) -> String {
+        index
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("idx_{}_{}", self.name, index.columns.join("_")))
+    }
+
+    /// SQLite declares an auto-increment column as its INTEGER PRIMARY KEY inline
+    fn inline_primary_key(&self, dialect: &dyn SqlDialect) -> Option<&str> {
+        if dialect.database_type() != DatabaseType::SQLite {
+            return None;
+        }
+        self.columns
+            .iter()
+            .find(|c| c.auto_increment)
+            .map(|c| c.name.as_str())
+    }
+
+    pub(super) fn column_sql(&self, dialect: &dyn SqlDialect, column: &ColumnDefinition) -> String {
+        let native = dialect.type_mapper().to_native(&column.data_type);
+        let data_type = match (column.auto_increment, dialect.database_type()) {
+            (false, _) => native,
+            (true, DatabaseType::PostgreSQL) => match column.data_type {
+                AbstractType::SmallInt => "SMALLSERIAL".to_string(),
+                AbstractType::Integer => "SERIAL".to_string(),
+                _ => "BIGSERIAL".to_string(),
+            },
+            (true, DatabaseType::MySQL) => format!("{} AUTO_INCREMENT", native),
+            // Only the exact type name INTEGER aliases the rowid
+            (true, DatabaseType::SQLite) => "INTEGER PRIMARY KEY AUTOINCREMENT".to_string(),
+            // Standard SQL identity column
+            (true, DatabaseType::Other(_)) => format!("{} GENERATED BY DEFAULT AS IDENTITY", native),
+        };
+
+        let mut sql = format!("{} {}", dialect.quote_identifier(&column.name), data_type);
+        if !column.nullable {
+            sql.push_str(" NOT NULL");
+        }
+        if let Some(default) = &column.default {
+            sql.push_str(&format!(" DEFAULT {}", default));
+        }
+        sql
+    }
+
+    pub(super) fn create_table_sql(&self, dialect: &dyn SqlDialect) -> String {
+        let mut definitions: Vec<String> = self.columns.iter().map(|c| self.column_sql(dialect, c)).collect();
+
+        if !self.primary_key.is_empty() && self.inline_primary_key(dialect).is_none() {
+            definitions.push(format!("PRIMARY KEY ({})", quote_list(dialect, &self.primary_key)));
+        }
+        definitions.extend(
+            self.unique
+                .iter()
+                .map(|columns| format!("UNIQUE ({})", quote_list(dialect, columns))),
+        );
+        definitions.extend(self.checks.iter().map(|check| format!("CHECK ({})", check)));
+        definitions.extend(self.foreign_keys.iter().map(|fk| {
+            let on_delete = match fk.on_delete {
+                Some(ReferentialAction::Cascade) => " ON DELETE CASCADE",
+                Some(ReferentialAction::SetNull) => " ON DELETE SET NULL",
+                Some(ReferentialAction::Restrict) => " ON DELETE RESTRICT",
+                None => "",
+            };
+            format!(
+                "FOREIGN KEY ({}) REFERENCES {} ({}){}",
+                quote_list(dialect, &fk.columns),
+                dialect.qualified_table_name(self.schema.as_deref(), &fk.references_table),
+                quote_list(dialect, &fk.references_columns),
+                on_delete
+            )
+        }));
+
+        format!(
+            "CREATE TABLE {}{} (\n    {}\n)",
+            if self.if_not_exists { "IF NOT EXISTS " } else { "" },
+            self.table_name(dialect),
+            definitions.join(",\n    ")
+        )
+    }
+}
+
+fn quote_list(dialect: &dyn SqlDialect, names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| dialect.quote_identifier(name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect, SQLiteDialect};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn serial(name: &str) -> ColumnDefinition {
+        ColumnDefinition {
+            auto_increment: true,
+            ..ColumnDefinition::new(name, AbstractType::BigInt).not_null()
+        }
+    }
+
+    fn orders() -> TableDefinition {
+        let total = ColumnDefinition::new("total", AbstractType::Decimal { precision: Some(10), scale: Some(2) });
+        let mut table = TableDefinition::new("orders")
+            .column(serial("id"))
+            .column(ColumnDefinition::new("customer_id", AbstractType::Integer).not_null())
+            .column(ColumnDefinition {
+                default: Some("0".to_string()),
+                ..total
+            })
+            .primary_key(&names(&["id"]));
+        table.indexes.push(IndexDefinition {
+            name: None,
+            columns: names(&["customer_id"]),
+            unique: false,
+        });
+        table.foreign_keys.push(ForeignKeyDefinition {
+            columns: vec!["customer_id".to_string()],
+            references_table: "customers".to_string(),
+            references_columns: vec!["id".to_string()],
+            on_delete: Some(ReferentialAction::Cascade),
+        });
+        table
+    }
+
+    #[test]
+    fn test_create_table_per_dialect() {
+        assert_eq!(
+            orders().create_statements(&PostgreSQLDialect::new()).unwrap(),
+            vec![
+                "CREATE TABLE \"orders\" (\n    \"id\" BIGSERIAL NOT NULL,\n    \"customer_id\" INTEGER NOT NULL,\n    \
+                 \"total\" NUMERIC(10, 2) DEFAULT 0,\n    PRIMARY KEY (\"id\"),\n    \
+                 FOREIGN KEY (\"customer_id\") REFERENCES \"customers\" (\"id\") ON DELETE CASCADE\n)",
+                "CREATE INDEX \"idx_orders_customer_id\" ON \"orders\" (\"customer_id\")",
+            ]
+        );
+        assert_eq!(
+            orders().create_statements(&MySQLDialect::new()).unwrap()[0],
+            "CREATE TABLE `orders` (\n    `id` BIGINT AUTO_INCREMENT NOT NULL,\n    `customer_id` INT NOT NULL,\n    \
+             `total` DECIMAL(10, 2) DEFAULT 0,\n    PRIMARY KEY (`id`),\n    \
+             FOREIGN KEY (`customer_id`) REFERENCES `customers` (`id`) ON DELETE CASCADE\n)"
+        );
+        // The auto-increment key is declared inline instead of as a table constraint
+        assert_eq!(
+            orders().create_statements(&SQLiteDialect::new()).unwrap()[0],
+            "CREATE TABLE \"orders\" (\n    \"id\" INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\n    \"customer_id\" INTEGER NOT NULL,\n    \
+             \"total\" NUMERIC DEFAULT 0,\n    \
+             FOREIGN KEY (\"customer_id\") REFERENCES \"customers\" (\"id\") ON DELETE CASCADE\n)"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let sqlite = SQLiteDialect::new();
+        let postgres = PostgreSQLDialect::new();
+
+        assert!(TableDefinition::new("empty").validate(&sqlite).is_err());
+
+        let duplicate = TableDefinition::new("t")
+            .column(ColumnDefinition::new("a", AbstractType::Text))
+            .column(ColumnDefinition::new("A", AbstractType::Text));
+        assert!(duplicate.validate(&sqlite).is_err());
+
+        let unknown_key = TableDefinition::new("t")
+            .column(ColumnDefinition::new("a", AbstractType::Text))
+            .primary_key(&names(&["b"]));
+        assert!(unknown_key.validate(&sqlite).is_err());
+
+        // SQLite can only auto-increment a sole integer primary key
+        let composite = TableDefinition::new("t")
+            .column(serial("a"))
+            .column(ColumnDefinition::new("b", AbstractType::Integer))
+            .primary_key(&names(&["a", "b"]));
+        assert!(composite.validate(&sqlite).is_err());
+        assert!(composite.validate(&postgres).is_ok());
+
+        let text_key = TableDefinition::new("t")
+            .column(ColumnDefinition::new("code", AbstractType::Varchar { length: None }))
+            .primary_key(&names(&["code"]));
+        assert!(text_key.validate(&MySQLDialect::new()).is_err());
+        assert!(text_key.validate(&postgres).is_ok());
+    }
+
+    #[test]
+    fn test_definition_json() {
+        let table: TableDefinition = serde_json::from_str(
+            r#"{"name": "tags", "columns": [{"name": "label", "data_type": {"kind": "varchar", "length": 40}}],
+                "indexes": [{"columns": ["label"], "unique": true}]}"#,
+        )
+        .unwrap();
+        assert!(table.columns[0].nullable);
+        assert_eq!(
+            table.create_statements(&SQLiteDialect::new()).unwrap(),
+            vec![
+                "CREATE TABLE \"tags\" (\n    \"label\" TEXT\n)",
+                "CREATE UNIQUE INDEX \"idx_tags_label\" ON \"tags\" (\"label\")",
+            ]
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 #[cfg(test)]
 mod dialect_tests {
-    use crate::database::dialect::{SqlDialect, PostgreSQLDialect, MySQLDialect, SQLiteDialect};
+    use crate::database::dialect::{IdentifierCase, SqlDialect, PostgreSQLDialect, MySQLDialect, SQLiteDialect};
     use crate::database::DatabaseType;
     
     #[test]
@@ -46,6 +46,92 @@ mod dialect_tests {
         assert_eq!(sqlite.limit_clause(None, Some(20)), " LIMIT -1 OFFSET 20");
     }
     
+    #[test]
+    fn test_all_dialects_keyset_clause() {
+        let pg = PostgreSQLDialect::new();
+        let mysql = MySQLDialect::new();
+        let sqlite = SQLiteDialect::new();
+        
+        // First page
+        assert_eq!(pg.keyset_clause(&["id"], &[]), "");
+        
+        assert_eq!(pg.keyset_clause(&["id"], &["42"]), r#" WHERE "id" > '42'"#);
+        assert_eq!(
+            pg.keyset_clause(&["org_id", "id"], &["7", "O'Brien"]),
+            r#" WHERE ("org_id", "id") > ('7', 'O''Brien')"#
+        );
+        assert_eq!(
+            sqlite.keyset_clause(&["org_id", "id"], &["7", "42"]),
+            r#" WHERE ("org_id", "id") > ('7', '42')"#
+        );
+        assert_eq!(
+            mysql.keyset_clause(&["a", "b", "c"], &["1", "2", r"x\y"]),
+            r" WHERE (`a` > '1' OR (`a` = '1' AND `b` > '2') OR (`a` = '1' AND `b` = '2' AND `c` > 'x\\y'))"
+        );
+    }
+    
+    #[test]
+    fn test_all_dialects_value_literals() {
+        let pg = PostgreSQLDialect::new();
+        let mysql = MySQLDialect::new();
+        let sqlite = SQLiteDialect::new();
+        
+        assert_eq!(pg.string_literal("O'Brien"), "'O''Brien'");
+        assert_eq!(pg.string_literal(r"C:\temp"), r"'C:\temp'");
+        assert_eq!(mysql.string_literal(r"C:\temp\'x"), r"'C:\\temp\\''x'");
+        assert_eq!(sqlite.string_literal("it's"), "'it''s'");
+        assert_eq!(mysql.date_literal("2023-01-15' OR '1"), "'2023-01-15'' OR ''1'");
+        
+        assert_eq!(pg.bytes_literal(&[0xde, 0xad, 0x01]), r"'\xdead01'::bytea");
+        assert_eq!(mysql.bytes_literal(&[0xde, 0xad, 0x01]), "X'dead01'");
+        assert_eq!(sqlite.bytes_literal(&[]), "X''");
+        
+        for number in ["42", "-1.5", ".5", "6.02e23", "1E-3", " 7 "] {
+            assert_eq!(pg.number_literal(number), Some(number.trim().to_string()), "{}", number);
+        }
+        for not_a_number in ["", "-", ".", "1e", "0x1F", "1; DROP TABLE t", "1 2", "+-1"] {
+            assert_eq!(mysql.number_literal(not_a_number), None, "{}", not_a_number);
+        }
+        assert_eq!(pg.number_literal("NaN").as_deref(), Some("'NaN'::double precision"));
+        assert_eq!(mysql.number_literal("NaN"), None);
+        assert_eq!(sqlite.number_literal("-Infinity").as_deref(), Some("-9e999"));
+    }
+    
+    #[test]
+    fn test_all_dialects_identifier_case() {
+        let pg = PostgreSQLDialect::new();
+        let mysql = MySQLDialect::new();
+        let sqlite = SQLiteDialect::new();
+        
+        assert_eq!(pg.fold_identifier("OrderItems"), "orderitems");
+        assert_eq!(mysql.fold_identifier("OrderItems"), "OrderItems");
+        assert_eq!(sqlite.identifier_case(), IdentifierCase::Preserve);
+        
+        assert!(!pg.identifiers_equal("Users", "users"));
+        assert!(mysql.identifiers_equal("Users", "users"));
+        assert!(sqlite.identifiers_equal("USERS", "users"));
+    }
+    
+    #[test]
+    fn test_all_dialects_regex_and_json() {
+        let pg = PostgreSQLDialect::new();
+        let mysql = MySQLDialect::new();
+        let sqlite = SQLiteDialect::new();
+        
+        assert_eq!(pg.regex_match("\"name\"", r"^\d+$").as_deref(), Some(r#""name" ~ '^\d+$'"#));
+        assert_eq!(mysql.regex_match("`name`", r"^\d+$").as_deref(), Some(r"`name` REGEXP '^\\d+$'"));
+        assert_eq!(sqlite.regex_match("\"name\"", "a"), None);
+        
+        let path = ["tags", "0", "first name"];
+        assert_eq!(pg.json_extract("\"doc\"", &path), r#""doc"->'tags'->0->>'first name'"#);
+        assert_eq!(
+            mysql.json_extract("`doc`", &path),
+            r#"JSON_UNQUOTE(JSON_EXTRACT(`doc`, '$.tags[0]."first name"'))"#
+        );
+        assert_eq!(sqlite.json_extract("\"doc\"", &["id"]), r#"json_extract("doc", '$.id')"#);
+        assert_eq!(pg.json_extract("\"doc\"", &[]), "\"doc\"");
+    }
+    
     #[test]
     fn test_all_dialects_boolean_literal() {
         let pg = PostgreSQLDialect::new();
@@ -233,4 +319,20 @@ mod dialect_tests {
             r#""table""with""quotes""#
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cte_and_row_number_page() {
+        let pg = PostgreSQLDialect::new();
+
+        assert_eq!(
+            pg.with_cte("recent", "SELECT * FROM orders", "SELECT COUNT(*) FROM \"recent\""),
+            "WITH \"recent\" AS (SELECT * FROM orders) SELECT COUNT(*) FROM \"recent\""
+        );
+        // Without an order the rows are numbered in whatever order the query returns them
+        assert_eq!(
+            pg.row_number_page("SELECT 1", &[], 0, 5),
+            "WITH \"_page\" AS (SELECT \"_query\".*, ROW_NUMBER() OVER () AS \"_row_number\" FROM (SELECT 1) \"_query\") \
+             SELECT * FROM \"_page\" WHERE \"_row_number\" BETWEEN 1 AND 5 ORDER BY \"_row_number\""
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+
+use super::SqlDialect;
+use crate::database::DatabaseType;
+use crate::error::AppError;
+
+/// What to do when an inserted row collides with an existing key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictAction {
+    /// Plain INSERT statements
+    #[default]
+    None,
+    /// Skip rows that already exist
+    DoNothing,
+    /// Overwrite existing rows with the inserted values
+    Update,
+}
+
+/// Builds INSERT statements that resolve key conflicts in the syntax of the dialect
+///
+/// # Examples
+/// - PostgreSQL/SQLite: `INSERT INTO "t" ("id", "a") VALUES (1, 'x') ON CONFLICT ("id") DO UPDATE SET "a" = EXCLUDED."a"`
+/// - MySQL: ``INSERT INTO `t` (`id`, `a`) VALUES (1, 'x') ON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `a` = VALUES(`a`)``
+pub struct UpsertBuilder<'a> {
+    dialect: &'a dyn SqlDialect,
+    schema: Option<String>,
+    table: String,
+    columns: Vec<String>,
+    conflict_keys: Vec<String>,
+    action: ConflictAction,
+}
+
+impl<'a> UpsertBuilder<'a> {
+    pub fn new(dialect: &'a dyn SqlDialect, table: &str) -> Self {
+        Self {
+            dialect,
+            schema: None,
+            table: table.to_string(),
+            columns: Vec::new(),
+            conflict_keys: Vec::new(),
+            action: ConflictAction::None,
+        }
+    }
+
+    pub fn schema(mut self, schema: Option<&str>) -> Self {
+        self.schema = schema.map(str::to_string);
+        self
+    }
+
+    /// Columns given a value by every row
+    pub fn columns(mut self, columns: &[String]) -> Self {
+        self.columns = columns.to_vec();
+        self
+    }
+
+    /// Columns of the unique key that decides whether a row already exists;
+    /// MySQL ignores them and checks every unique key
+    pub fn conflict_keys(mut self, keys: &[String]) -> Self {
+        self.conflict_keys = keys.to_vec();
+        self
+    }
+
+    pub fn on_conflict(mut self, action: ConflictAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Check that the dialect can express the requested conflict handling
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.action != ConflictAction::None && !self.dialect.supports_upsert() {
+            return Err(AppError::Validation(
+                "The target database does not support conflict handling".to_string(),
+            ));
+        }
+
+        // ON CONFLICT ... DO UPDATE needs an explicit conflict target; MySQL infers it from unique keys
+        if self.action == ConflictAction::Update && !self.is_mysql() && self.conflict_keys.is_empty() {
+            return Err(AppError::Validation(
+                "Conflict columns are required to update existing rows".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `INSERT ... INTO <table> (<columns>) VALUES`, to be followed by the rows
+    pub fn insert_into(&self) -> String {
+        // MySQL skips duplicates with INSERT IGNORE instead of a trailing clause
+        let ignore = if self.is_mysql() && self.skips_conflicts() { " IGNORE" } else { "" };
+        let columns: Vec<String> = self.columns.iter().map(|c| self.dialect.quote_identifier(c)).collect();
+
+        format!(
+            "INSERT{} INTO {} ({}) VALUES",
+            ignore,
+            self.dialect.qualified_table_name(self.schema.as_deref(), &self.table),
+            columns.join(", ")
+        )
+    }
+
+    /// Clause following the rows, with a leading space; empty for a plain INSERT
+    pub fn conflict_clause(&self) -> String {
+        if self.action == ConflictAction::None {
+            return String::new();
+        }
+
+        if self.is_mysql() {
+            if self.skips_conflicts() {
+                return String::new();
+            }
+            return format!(" ON DUPLICATE KEY UPDATE {}", self.update_assignments().join(", "));
+        }
+
+        let target = if self.conflict_keys.is_empty() {
+            String::new()
+        } else {
+            let keys: Vec<String> = self.conflict_keys.iter().map(|k| self.dialect.quote_identifier(k)).collect();
+            format!(" ({})", keys.join(", "))
+        };
+
+        if self.skips_conflicts() {
+            format!(" ON CONFLICT{} DO NOTHING", target)
+        } else {
+            format!(" ON CONFLICT{} DO UPDATE SET {}", target, self.update_assignments().join(", "))
+        }
+    }
+
+    /// Complete statement for rows already rendered as `(<literal>, ...)`
+    pub fn build(&self, rows: &[String]) -> String {
+        format!("{} {}{}", self.insert_into(), rows.join(", "), self.conflict_clause())
+    }
+
+    fn is_mysql(&self) -> bool {
+        self.dialect.database_type() == DatabaseType::MySQL
+    }
+
+    /// SET assignments for the non-key columns when updating on conflict
+    fn update_assignments(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .filter(|c| !self.conflict_keys.contains(c))
+            .map(|c| {
+                let column = self.dialect.quote_identifier(c);
+                if self.is_mysql() {
+                    format!("{} = VALUES({})", column, column)
+                } else {
+                    format!("{} = EXCLUDED.{}", column, column)
+                }
+            })
+            .collect()
+    }
+
+    /// Whether conflicting rows should simply be skipped
+    fn skips_conflicts(&self) -> bool {
+        match self.action {
+            ConflictAction::None => false,
+            ConflictAction::DoNothing => true,
+            // Nothing is left to update when every column is part of the key
+            ConflictAction::Update => self.update_assignments().is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect, SQLiteDialect};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_upsert_per_dialect() {
+        let rows = vec!["(1, 'a')".to_string(), "(2, 'b')".to_string()];
+        let build = |dialect: &dyn SqlDialect| {
+            UpsertBuilder::new(dialect, "users")
+                .columns(&names(&["id", "name"]))
+                .conflict_keys(&names(&["id"]))
+                .on_conflict(ConflictAction::Update)
+                .build(&rows)
+        };
+
+        assert_eq!(
+            build(&PostgreSQLDialect::new()),
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES (1, 'a'), (2, 'b') \
+             ON CONFLICT (\"id\") DO UPDATE SET \"name\" = EXCLUDED.\"name\""
+        );
+        assert_eq!(
+            build(&SQLiteDialect::new()),
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES (1, 'a'), (2, 'b') \
+             ON CONFLICT (\"id\") DO UPDATE SET \"name\" = EXCLUDED.\"name\""
+        );
+        assert_eq!(
+            build(&MySQLDialect::new()),
+            "INSERT INTO `users` (`id`, `name`) VALUES (1, 'a'), (2, 'b') \
+             ON DUPLICATE KEY UPDATE `name` = VALUES(`name`)"
+        );
+    }
+
+    #[test]
+    fn test_skipping_conflicts() {
+        let sqlite = SQLiteDialect::new();
+        let mysql = MySQLDialect::new();
+        let rows = vec!["(1)".to_string()];
+
+        let skip = UpsertBuilder::new(&sqlite, "tags")
+            .columns(&names(&["id"]))
+            .on_conflict(ConflictAction::DoNothing);
+        assert_eq!(skip.build(&rows), "INSERT INTO \"tags\" (\"id\") VALUES (1) ON CONFLICT DO NOTHING");
+
+        // Updating a row made only of key columns changes nothing
+        let key_only = UpsertBuilder::new(&mysql, "tags")
+            .columns(&names(&["id"]))
+            .conflict_keys(&names(&["id"]))
+            .on_conflict(ConflictAction::Update);
+        assert_eq!(key_only.build(&rows), "INSERT IGNORE INTO `tags` (`id`) VALUES (1)");
+    }
+
+    #[test]
+    fn test_validate() {
+        let sqlite = SQLiteDialect::new();
+        let mysql = MySQLDialect::new();
+
+        assert!(UpsertBuilder::new(&sqlite, "t").on_conflict(ConflictAction::Update).validate().is_err());
+        assert!(UpsertBuilder::new(&mysql, "t").on_conflict(ConflictAction::Update).validate().is_ok());
+        assert!(UpsertBuilder::new(&sqlite, "t").on_conflict(ConflictAction::DoNothing).validate().is_ok());
+    }
+}
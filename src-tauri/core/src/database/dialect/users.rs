@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+use super::SqlDialect;
+use crate::database::DatabaseType;
+use crate::error::AppError;
+
+/// Shown in place of passwords in statements meant for display
+const REDACTED_PASSWORD: &str = "********";
+
+/// Table privilege that can be granted or revoked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    All,
+}
+
+impl Privilege {
+    fn keyword(self) -> &'static str {
+        match self {
+            Privilege::Select => "SELECT",
+            Privilege::Insert => "INSERT",
+            Privilege::Update => "UPDATE",
+            Privilege::Delete => "DELETE",
+            Privilege::All => "ALL PRIVILEGES",
+        }
+    }
+}
+
+/// A change to the users of the server or their privileges
+///
+/// `host` is the host part of a MySQL account and defaults to any host ('%');
+/// PostgreSQL ignores it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum UserChange {
+    CreateUser {
+        user: String,
+        password: String,
+        host: Option<String>,
+    },
+    DropUser {
+        user: String,
+        host: Option<String>,
+    },
+    SetPassword {
+        user: String,
+        password: String,
+        host: Option<String>,
+    },
+    Grant {
+        user: String,
+        host: Option<String>,
+        privileges: Vec<Privilege>,
+        /// "table" or "schema.table"
+        table: String,
+    },
+    Revoke {
+        user: String,
+        host: Option<String>,
+        privileges: Vec<Privilege>,
+        table: String,
+    },
+}
+
+/// Statement making `change`, with the password replaced by asterisks when `redact` is set
+///
+/// # Examples
+/// - PostgreSQL: `CREATE ROLE "app" WITH LOGIN PASSWORD 'secret'`
+/// - MySQL: `CREATE USER 'app'@'%' IDENTIFIED BY 'secret'`
+pub fn user_statement(change: &UserChange, dialect: &dyn SqlDialect, redact: bool) -> Result<String, AppError> {
+    let database_type = dialect.database_type();
+    if database_type == DatabaseType::SQLite {
+        return Err(AppError::Validation("SQLite has no users or privileges".to_string()));
+    }
+
+    let (UserChange::CreateUser { user, host, .. }
+    | UserChange::DropUser { user, host }
+    | UserChange::SetPassword { user, host, .. }
+    | UserChange::Grant { user, host, .. }
+    | UserChange::Revoke { user, host, .. }) = change;
+    if user.trim().is_empty() {
+        return Err(AppError::Validation("User name is required".to_string()));
+    }
+
+    let account = match database_type {
+        DatabaseType::MySQL => format!(
+            "{}@{}",
+            dialect.string_literal(user),
+            dialect.string_literal(host.as_deref().unwrap_or("%"))
+        ),
+        _ => dialect.quote_identifier(user),
+    };
+    let password = |password: &str| {
+        if password.is_empty() {
+            return Err(AppError::Validation("Password is required".to_string()));
+        }
+        Ok(dialect.string_literal(if redact { REDACTED_PASSWORD } else { password }))
+    };
+    let grant = |privileges: &[Privilege], table: &str| {
+        if privileges.is_empty() {
+            return Err(AppError::Validation("At least one privilege is required".to_string()));
+        }
+        let privileges: Vec<&str> = privileges.iter().map(|p| p.keyword()).collect();
+        let table = match table.split_once('.') {
+            Some((schema, table)) => dialect.qualified_table_name(Some(schema), table),
+            None => dialect.quote_identifier(table),
+        };
+        Ok(format!("{} ON {}", privileges.join(", "), table))
+    };
+
+    let mysql = database_type == DatabaseType::MySQL;
+    Ok(match change {
+        UserChange::CreateUser { password: p, .. } if mysql => {
+            format!("CREATE USER {} IDENTIFIED BY {}", account, password(p)?)
+        }
+        UserChange::CreateUser { password: p, .. } => {
+            format!("CREATE ROLE {} WITH LOGIN PASSWORD {}", account, password(p)?)
+        }
+        UserChange::DropUser { .. } if mysql => format!("DROP USER {}", account),
+        UserChange::DropUser { .. } => format!("DROP ROLE {}", account),
+        UserChange::SetPassword { password: p, .. } if mysql => {
+            format!("ALTER USER {} IDENTIFIED BY {}", account, password(p)?)
+        }
+        UserChange::SetPassword { password: p, .. } => {
+            format!("ALTER ROLE {} WITH PASSWORD {}", account, password(p)?)
+        }
+        UserChange::Grant { privileges, table, .. } => {
+            format!("GRANT {} TO {}", grant(privileges, table)?, account)
+        }
+        UserChange::Revoke { privileges, table, .. } => {
+            format!("REVOKE {} FROM {}", grant(privileges, table)?, account)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect, SQLiteDialect};
+
+    fn create() -> UserChange {
+        UserChange::CreateUser {
+            user: "app".to_string(),
+            password: "it's secret".to_string(),
+            host: None,
+        }
+    }
+
+    #[test]
+    fn test_user_statements() {
+        let pg = PostgreSQLDialect::new();
+        let mysql = MySQLDialect::new();
+
+        assert_eq!(
+            user_statement(&create(), &pg, false).unwrap(),
+            "CREATE ROLE \"app\" WITH LOGIN PASSWORD 'it''s secret'"
+        );
+        assert_eq!(
+            user_statement(&create(), &mysql, true).unwrap(),
+            "CREATE USER 'app'@'%' IDENTIFIED BY '********'"
+        );
+
+        let grant = UserChange::Grant {
+            user: "app".to_string(),
+            host: Some("10.0.0.%".to_string()),
+            privileges: vec![Privilege::Select, Privilege::Update],
+            table: "sales.orders".to_string(),
+        };
+        assert_eq!(
+            user_statement(&grant, &pg, false).unwrap(),
+            "GRANT SELECT, UPDATE ON \"sales\".\"orders\" TO \"app\""
+        );
+        assert_eq!(
+            user_statement(&grant, &mysql, false).unwrap(),
+            "GRANT SELECT, UPDATE ON `sales`.`orders` TO 'app'@'10.0.0.%'"
+        );
+
+        let drop = UserChange::DropUser { user: "app".to_string(), host: None };
+        assert_eq!(user_statement(&drop, &mysql, false).unwrap(), "DROP USER 'app'@'%'");
+    }
+
+    #[test]
+    fn test_invalid_user_changes() {
+        let pg = PostgreSQLDialect::new();
+        let no_privileges = UserChange::Revoke {
+            user: "app".to_string(),
+            host: None,
+            privileges: Vec::new(),
+            table: "orders".to_string(),
+        };
+        let no_password = UserChange::SetPassword {
+            user: "app".to_string(),
+            password: String::new(),
+            host: None,
+        };
+
+        assert!(user_statement(&no_privileges, &pg, false).is_err());
+        assert!(user_statement(&no_password, &pg, false).is_err());
+        assert!(user_statement(&create(), &SQLiteDialect::new(), false).is_err());
+    }
+}
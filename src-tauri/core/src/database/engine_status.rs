@@ -0,0 +1,202 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Health metrics of a MySQL server from its global status counters and the
+/// InnoDB monitor
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EngineHealth {
+    pub uptime_secs: Option<i64>,
+    pub threads_connected: Option<i64>,
+    pub threads_running: Option<i64>,
+    pub threads_created: Option<i64>,
+    pub max_used_connections: Option<i64>,
+    pub slow_queries: Option<i64>,
+    /// Share of page reads served from the buffer pool rather than disk, 0 to 1
+    pub buffer_pool_hit_rate: Option<f64>,
+    pub buffer_pool_pages_total: Option<i64>,
+    pub buffer_pool_pages_free: Option<i64>,
+    pub buffer_pool_pages_dirty: Option<i64>,
+    pub tmp_tables: Option<i64>,
+    pub tmp_disk_tables: Option<i64>,
+    /// Share of implicit temporary tables that spilled to disk, 0 to 1
+    pub tmp_disk_table_ratio: Option<f64>,
+    /// Absent without the PROCESS privilege
+    pub innodb: Option<InnodbStatus>,
+}
+
+/// Figures from the text of `SHOW ENGINE INNODB STATUS`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InnodbStatus {
+    /// Undo log entries purge has yet to process
+    pub history_list_length: Option<i64>,
+    pub log_sequence_number: Option<i64>,
+    pub last_checkpoint: Option<i64>,
+    /// Redo log bytes written since the last checkpoint
+    pub checkpoint_age: Option<i64>,
+    pub inserts_per_sec: Option<f64>,
+    pub updates_per_sec: Option<f64>,
+    pub deletes_per_sec: Option<f64>,
+    pub reads_per_sec: Option<f64>,
+    pub latest_deadlock: Option<String>,
+    /// The whole monitor output
+    pub raw: String,
+}
+
+impl EngineHealth {
+    /// Build from the rows of `SHOW GLOBAL STATUS`, keyed by variable name
+    pub fn from_status(status: &HashMap<String, String>, innodb: Option<&str>) -> Self {
+        let counter = |name: &str| status.get(name).and_then(|value| value.trim().parse::<i64>().ok());
+        let ratio = |part: Option<i64>, whole: Option<i64>| match (part, whole) {
+            (Some(part), Some(whole)) if whole > 0 => Some(part as f64 / whole as f64),
+            _ => None,
+        };
+
+        let tmp_tables = counter("Created_tmp_tables");
+        let tmp_disk_tables = counter("Created_tmp_disk_tables");
+        Self {
+            uptime_secs: counter("Uptime"),
+            threads_connected: counter("Threads_connected"),
+            threads_running: counter("Threads_running"),
+            threads_created: counter("Threads_created"),
+            max_used_connections: counter("Max_used_connections"),
+            slow_queries: counter("Slow_queries"),
+            buffer_pool_hit_rate: ratio(counter("Innodb_buffer_pool_reads"), counter("Innodb_buffer_pool_read_requests"))
+                .map(|misses| 1.0 - misses),
+            buffer_pool_pages_total: counter("Innodb_buffer_pool_pages_total"),
+            buffer_pool_pages_free: counter("Innodb_buffer_pool_pages_free"),
+            buffer_pool_pages_dirty: counter("Innodb_buffer_pool_pages_dirty"),
+            tmp_tables,
+            tmp_disk_tables,
+            tmp_disk_table_ratio: ratio(tmp_disk_tables, tmp_tables),
+            innodb: innodb.map(InnodbStatus::parse),
+        }
+    }
+}
+
+impl InnodbStatus {
+    pub fn parse(text: &str) -> Self {
+        let sections = sections(text);
+        let line_starting = |prefix: &str| {
+            text.lines()
+                .map(str::trim)
+                .find_map(|line| line.strip_prefix(prefix))
+                .map(str::trim)
+        };
+        let number = |prefix: &str| line_starting(prefix).and_then(|rest| rest.split_whitespace().next()?.parse::<i64>().ok());
+
+        // "0.50 inserts/s, 1.00 updates/s, 0.00 deletes/s, 12.25 reads/s"
+        let rate = |unit: &str| {
+            sections.get("ROW OPERATIONS")?.split(',').find_map(|part| {
+                let (value, name) = part.trim().split_once(' ')?;
+                (name.trim() == unit).then(|| value.parse::<f64>().ok()).flatten()
+            })
+        };
+
+        let log_sequence_number = number("Log sequence number");
+        let last_checkpoint = number("Last checkpoint at");
+        Self {
+            history_list_length: number("History list length"),
+            log_sequence_number,
+            last_checkpoint,
+            checkpoint_age: log_sequence_number.zip(last_checkpoint).map(|(lsn, checkpoint)| lsn - checkpoint),
+            inserts_per_sec: rate("inserts/s"),
+            updates_per_sec: rate("updates/s"),
+            deletes_per_sec: rate("deletes/s"),
+            reads_per_sec: rate("reads/s"),
+            latest_deadlock: sections.get("LATEST DETECTED DEADLOCK").map(|body| body.trim().to_string()),
+            raw: text.to_string(),
+        }
+    }
+}
+
+/// Bodies of the monitor's sections, keyed by their title; a title sits between
+/// two lines of dashes
+fn sections(text: &str) -> HashMap<&str, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let is_rule = |line: &str| line.len() >= 3 && line.trim_end().chars().all(|c| c == '-');
+
+    let mut sections = HashMap::new();
+    let mut current: Option<&str> = None;
+    let mut index = 0;
+    while index < lines.len() {
+        if index + 2 < lines.len() && is_rule(lines[index]) && is_rule(lines[index + 2]) {
+            current = Some(lines[index + 1].trim());
+            sections.insert(lines[index + 1].trim(), String::new());
+            index += 3;
+            continue;
+        }
+        if let Some(body) = current.and_then(|title| sections.get_mut(title)) {
+            body.push_str(lines[index]);
+            body.push('\n');
+        }
+        index += 1;
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: &str = "
+=====================================
+2024-05-01 10:00:00 INNODB MONITOR OUTPUT
+=====================================
+------------------------
+LATEST DETECTED DEADLOCK
+------------------------
+*** (1) TRANSACTION:
+UPDATE accounts SET balance = 0 WHERE id = 1
+------------
+TRANSACTIONS
+------------
+Trx id counter 5123
+History list length 42
+---
+LOG
+---
+Log sequence number          19243584
+Log flushed up to   19243584
+Last checkpoint at  19240000
+--------------
+ROW OPERATIONS
+--------------
+0 queries inside InnoDB, 0 queries in queue
+0.50 inserts/s, 1.00 updates/s, 0.00 deletes/s, 12.25 reads/s
+";
+
+    #[test]
+    fn test_parse_innodb_status() {
+        let status = InnodbStatus::parse(MONITOR);
+
+        assert_eq!(status.history_list_length, Some(42));
+        assert_eq!(status.checkpoint_age, Some(3584));
+        assert_eq!(status.updates_per_sec, Some(1.0));
+        assert_eq!(status.reads_per_sec, Some(12.25));
+        assert_eq!(
+            status.latest_deadlock.as_deref(),
+            Some("*** (1) TRANSACTION:\nUPDATE accounts SET balance = 0 WHERE id = 1")
+        );
+    }
+
+    #[test]
+    fn test_health_from_status() {
+        let status: HashMap<String, String> = [
+            ("Threads_running", "3"),
+            ("Innodb_buffer_pool_read_requests", "1000"),
+            ("Innodb_buffer_pool_reads", "50"),
+            ("Created_tmp_tables", "200"),
+            ("Created_tmp_disk_tables", "20"),
+        ]
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        let health = EngineHealth::from_status(&status, None);
+
+        assert_eq!(health.threads_running, Some(3));
+        assert_eq!(health.buffer_pool_hit_rate, Some(0.95));
+        assert_eq!(health.tmp_disk_table_ratio, Some(0.1));
+        assert_eq!(health.threads_connected, None);
+        assert!(health.innodb.is_none());
+    }
+}
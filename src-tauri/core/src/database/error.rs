@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DatabaseError {
+    #[error("Connection failed: {0}")]
+    ConnectionFailed(String),
+
+    #[error("Query failed: {0}")]
+    QueryFailed(String),
+
+    /// Query failure reported by the database, with its code and position
+    #[error("Query failed: {0}")]
+    Query(Box<QueryError>),
+
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("SQLx error: {0}")]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error("Environment variable error: {0}")]
+    Env(#[from] std::env::VarError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Other error: {0}")]
+    Other(String),
+}
+
+pub type Result<T> = std::result::Result<T, DatabaseError>;
+
+impl DatabaseError {
+    /// Whether the same statement may succeed when simply run again
+    pub fn is_transient(&self) -> bool {
+        match self {
+            DatabaseError::Query(error) => error.transient,
+            // Raised when no pooled connection could be had in time
+            DatabaseError::ConnectionFailed(_) => true,
+            DatabaseError::Sqlx(error) => matches!(error, sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut),
+            _ => false,
+        }
+    }
+}
+
+impl From<DatabaseError> for String {
+    fn from(err: DatabaseError) -> Self {
+        err.to_string()
+    }
+}
+
+/// Where in the SQL text an error was found, for underlining it in the editor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorPosition {
+    /// Offset in characters from the start of the text, starting at 0
+    pub offset: usize,
+    /// Line, starting at 1
+    pub line: usize,
+    /// Column in characters, starting at 1
+    pub column: usize,
+    /// Length in characters of the offending token, at least 1
+    pub length: usize,
+}
+
+impl ErrorPosition {
+    /// Position of the token starting `offset` characters into `text`
+    pub fn at(text: &str, offset: usize) -> Option<Self> {
+        let chars: Vec<char> = text.chars().collect();
+        if offset >= chars.len() {
+            return None;
+        }
+
+        let before = &chars[..offset];
+        let line = before.iter().filter(|c| **c == '\n').count() + 1;
+        let column = offset - before.iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1) + 1;
+
+        let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+        let length = if is_word(&chars[offset]) {
+            chars[offset..].iter().take_while(|c| is_word(c)).count()
+        } else {
+            1
+        };
+
+        Some(Self { offset, line, column, length })
+    }
+}
+
+/// Query failure reported by the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryError {
+    pub message: String,
+    /// SQLSTATE code (PostgreSQL and MySQL)
+    pub sqlstate: Option<String>,
+    /// Database-specific error number (MySQL error number, SQLite result code)
+    pub native_code: Option<String>,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    pub position: Option<ErrorPosition>,
+    /// Set for failures that may go away on their own, such as deadlocks,
+    /// serialization failures and dropped connections
+    #[serde(default)]
+    pub transient: bool,
+    /// Similar known names when the statement referred to an unknown table or column
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl QueryError {
+    /// Build from the error of running `statement`; the position is relative to it
+    pub fn from_sqlx(err: &sqlx::Error, statement: &str) -> Self {
+        let sqlx::Error::Database(db_err) = err else {
+            return Self {
+                message: crate::redact::redacted(err.to_string()),
+                sqlstate: None,
+                native_code: None,
+                detail: None,
+                hint: None,
+                position: None,
+                transient: matches!(err, sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut),
+                suggestions: Vec::new(),
+            };
+        };
+
+        let message = db_err.message().to_string();
+        let mut error = Self {
+            message: crate::redact::redacted(&message),
+            sqlstate: None,
+            native_code: None,
+            detail: None,
+            hint: None,
+            position: None,
+            transient: false,
+            suggestions: Vec::new(),
+        };
+
+        if let Some(pg_err) = db_err.try_downcast_ref::<sqlx::postgres::PgDatabaseError>() {
+            error.sqlstate = Some(pg_err.code().to_string());
+            error.transient = is_transient_sqlstate(pg_err.code());
+            error.detail = pg_err.detail().map(crate::redact::redacted);
+            error.hint = pg_err.hint().map(str::to_string);
+            // PostgreSQL counts characters from 1
+            if let Some(sqlx::postgres::PgErrorPosition::Original(position)) = pg_err.position() {
+                error.position = ErrorPosition::at(statement, position.saturating_sub(1));
+            }
+        } else if let Some(mysql_err) = db_err.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+            error.sqlstate = mysql_err.code().map(str::to_string);
+            error.native_code = Some(mysql_err.number().to_string());
+            // Deadlock, lock wait timeout, server gone away, lost connection
+            error.transient = matches!(mysql_err.number(), 1205 | 1213 | 2006 | 2013)
+                || mysql_err.code().is_some_and(is_transient_sqlstate);
+            error.position = mysql_error_offset(&message, statement)
+                .and_then(|offset| ErrorPosition::at(statement, offset));
+        } else {
+            // SQLite
+            error.native_code = db_err.code().map(|code| code.into_owned());
+            // SQLITE_BUSY and SQLITE_LOCKED, including their extended codes
+            error.transient = error
+                .native_code
+                .as_deref()
+                .and_then(|code| code.parse::<i32>().ok())
+                .is_some_and(|code| matches!(code & 0xff, 5 | 6));
+            error.position = sqlite_error_offset(&message, statement)
+                .and_then(|offset| ErrorPosition::at(statement, offset));
+        }
+
+        error
+    }
+
+    /// Move the position from `statement` into the `script` it was split from
+    ///
+    /// The position is dropped when the statement text does not appear in the
+    /// script at or after `search_from` (a byte offset), e.g. because the parser
+    /// rewrote it, since it would underline the wrong text.
+    pub fn relative_to(mut self, script: &str, statement: &str, search_from: usize) -> Self {
+        let start = script
+            .get(search_from..)
+            .and_then(|rest| rest.find(statement))
+            .map(|index| script[..search_from + index].chars().count());
+
+        self.position = match (self.position.take(), start) {
+            (Some(position), Some(start)) => ErrorPosition::at(script, start + position.offset)
+                .map(|p| ErrorPosition { length: position.length, ..p }),
+            _ => None,
+        };
+        self
+    }
+}
+
+/// Serialization failures, deadlocks, lock timeouts and connection errors
+fn is_transient_sqlstate(sqlstate: &str) -> bool {
+    matches!(sqlstate, "40001" | "40P01" | "55P03" | "57P01") || sqlstate.starts_with("08")
+}
+
+/// Character offset of the error in a MySQL message such as
+/// "... near 'FORM users' at line 1"
+fn mysql_error_offset(message: &str, statement: &str) -> Option<usize> {
+    let start = message.find("near '")? + "near '".len();
+    let end = start + message[start..].rfind("' at line ")?;
+    let near = &message[start..end];
+    let line: usize = message[end + "' at line ".len()..].trim().parse().ok()?;
+
+    // Nothing is quoted when the statement ended too early
+    if near.is_empty() {
+        return statement.trim_end().chars().count().checked_sub(1);
+    }
+
+    // The quoted text is the rest of the statement from the error, cut short
+    let line_start = if line <= 1 {
+        0
+    } else {
+        statement.match_indices('\n').nth(line - 2)?.0 + 1
+    };
+    let probe: String = near.chars().take(20).collect();
+    let index = line_start + statement[line_start..].find(&probe)?;
+    Some(statement[..index].chars().count())
+}
+
+/// Character offset of the error in an SQLite message such as
+/// `near "FORM": syntax error`
+fn sqlite_error_offset(message: &str, statement: &str) -> Option<usize> {
+    let token = ["near \"", "unrecognized token: \""]
+        .iter()
+        .find_map(|prefix| {
+            let start = message.find(prefix)? + prefix.len();
+            let end = start + message[start..].find('"')?;
+            Some(&message[start..end])
+        })
+        .filter(|token| !token.is_empty())?;
+
+    let index = statement.find(token)?;
+    Some(statement[..index].chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_position() {
+        let sql = "SELECT id\nFORM users";
+        assert_eq!(
+            ErrorPosition::at(sql, 10),
+            Some(ErrorPosition { offset: 10, line: 2, column: 1, length: 4 })
+        );
+        assert_eq!(ErrorPosition::at(sql, 6).unwrap().length, 1);
+        assert!(ErrorPosition::at(sql, 100).is_none());
+    }
+
+    #[test]
+    fn test_mysql_error_offset() {
+        let sql = "SELECT id\nFORM users WHERE id = 1";
+        let message = "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use near 'FORM users WHERE id = 1' at line 2";
+        assert_eq!(mysql_error_offset(message, sql), Some(10));
+
+        // Errors at the end of the statement quote nothing
+        let message = "You have an error in your SQL syntax; ... near '' at line 1";
+        assert_eq!(mysql_error_offset(message, "SELECT * FROM"), Some(12));
+    }
+
+    #[test]
+    fn test_sqlite_error_offset() {
+        assert_eq!(sqlite_error_offset("near \"FORM\": syntax error", "SELECT 1 FORM t"), Some(9));
+        assert_eq!(sqlite_error_offset("no such table: t", "SELECT * FROM t"), None);
+    }
+
+    #[test]
+    fn test_transient_sqlstates() {
+        assert!(is_transient_sqlstate("40001"));
+        assert!(is_transient_sqlstate("40P01"));
+        assert!(is_transient_sqlstate("08006"));
+        assert!(!is_transient_sqlstate("42601"));
+        assert!(DatabaseError::Sqlx(sqlx::Error::PoolTimedOut).is_transient());
+        assert!(!DatabaseError::QueryFailed("syntax error".to_string()).is_transient());
+    }
+
+    #[test]
+    fn test_relative_to_script() {
+        let script = "SELECT 1;\nSELECT id\nFORM users;";
+        let error = QueryError {
+            message: "syntax error".to_string(),
+            sqlstate: Some("42601".to_string()),
+            native_code: None,
+            detail: None,
+            hint: None,
+            position: ErrorPosition::at("SELECT id\nFORM users;", 10),
+            transient: false,
+            suggestions: Vec::new(),
+        };
+
+        let mapped = error.clone().relative_to(script, "SELECT id\nFORM users;", 9);
+        assert_eq!(mapped.position, Some(ErrorPosition { offset: 20, line: 3, column: 1, length: 4 }));
+
+        let rewritten = error.relative_to(script, "SELECT id FORM users", 0);
+        assert!(rewritten.position.is_none());
+    }
+}
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::adapter::{DatabaseType, IndexUsage, TableHealth};
+use crate::database::dialect::SqlDialect;
+use crate::error::AppError;
+
+/// Housekeeping operation on a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceAction {
+    /// Reclaim space of deleted rows (PostgreSQL, SQLite)
+    Vacuum,
+    /// Refresh the statistics the planner uses
+    Analyze,
+    /// Rebuild the table and its indexes (MySQL)
+    Optimize,
+}
+
+/// Statement performing `action` on `table_name` ("table" or "schema.table")
+///
+/// SQLite can only vacuum the whole database, so its VACUUM ignores the table.
+pub fn maintenance_statement(
+    action: MaintenanceAction,
+    dialect: &dyn SqlDialect,
+    table_name: &str,
+) -> Result<String, AppError> {
+    let table = match table_name.split_once('.') {
+        Some((schema, table)) => dialect.qualified_table_name(Some(schema), table),
+        None => dialect.quote_identifier(table_name),
+    };
+
+    match (dialect.database_type(), action) {
+        (DatabaseType::PostgreSQL, MaintenanceAction::Vacuum) => Ok(format!("VACUUM {}", table)),
+        (DatabaseType::PostgreSQL | DatabaseType::SQLite, MaintenanceAction::Analyze) => Ok(format!("ANALYZE {}", table)),
+        (DatabaseType::SQLite, MaintenanceAction::Vacuum) => Ok("VACUUM".to_string()),
+        (DatabaseType::MySQL, MaintenanceAction::Analyze) => Ok(format!("ANALYZE TABLE {}", table)),
+        (DatabaseType::MySQL, MaintenanceAction::Optimize) => Ok(format!("OPTIMIZE TABLE {}", table)),
+        (database_type, action) => Err(AppError::Validation(format!(
+            "{:?} is not available for {:?}",
+            action, database_type
+        ))),
+    }
+}
+
+/// An index whose lookups another index of the same table can serve
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateIndex {
+    pub index: IndexUsage,
+    pub covered_by: String,
+}
+
+/// Indexes that could be dropped to reclaim space, largest first
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexReport {
+    /// Indexes scanned at most the threshold number of times
+    pub unused: Vec<IndexUsage>,
+    pub duplicates: Vec<DuplicateIndex>,
+    /// Size of the listed indexes, each counted once
+    pub reclaimable_bytes: i64,
+}
+
+/// Find the indexes scanned at most `max_scans` times and those duplicated by another
+///
+/// Primary key and unique indexes enforce constraints, so they are never
+/// reported as unused, and only as duplicates of an index with the same columns
+/// that enforces the same constraint.
+pub fn index_report(indexes: &[IndexUsage], max_scans: i64) -> IndexReport {
+    let by_size = |a: &IndexUsage, b: &IndexUsage| b.size.cmp(&a.size);
+
+    let mut unused: Vec<IndexUsage> = indexes
+        .iter()
+        .filter(|index| !index.primary && !index.unique && index.scans.is_some_and(|scans| scans <= max_scans))
+        .cloned()
+        .collect();
+    unused.sort_by(by_size);
+
+    let mut duplicates: Vec<DuplicateIndex> = indexes
+        .iter()
+        .filter_map(|index| {
+            let covering = indexes.iter().find(|other| covers(other, index))?;
+            Some(DuplicateIndex {
+                index: index.clone(),
+                covered_by: covering.name.clone(),
+            })
+        })
+        .collect();
+    duplicates.sort_by(|a, b| by_size(&a.index, &b.index));
+
+    let mut listed: Vec<&IndexUsage> = unused.iter().chain(duplicates.iter().map(|d| &d.index)).collect();
+    listed.sort_by(|a, b| (&a.schema, &a.table, &a.name).cmp(&(&b.schema, &b.table, &b.name)));
+    listed.dedup_by(|a, b| (&a.schema, &a.table, &a.name) == (&b.schema, &b.table, &b.name));
+    let reclaimable_bytes = listed.iter().filter_map(|index| index.size).sum();
+
+    IndexReport {
+        unused,
+        duplicates,
+        reclaimable_bytes,
+    }
+}
+
+/// Whether `other` makes `index` redundant: it indexes the same columns, or
+/// starts with them, and enforces any constraint `index` does
+fn covers(other: &IndexUsage, index: &IndexUsage) -> bool {
+    let same_table = other.schema == index.schema && other.table == index.table;
+    if !same_table || other.name == index.name || index.primary || index.columns.is_empty() {
+        return false;
+    }
+
+    let prefix = index.columns.len() <= other.columns.len()
+        && index
+            .columns
+            .iter()
+            .zip(&other.columns)
+            .all(|(a, b)| a.eq_ignore_ascii_case(b));
+    if !prefix {
+        return false;
+    }
+
+    if index.columns.len() < other.columns.len() {
+        // A unique index on fewer columns is a stricter constraint
+        return !index.unique;
+    }
+
+    // Of two equivalent indexes, keep the one enforcing the most, then the first by name
+    let rank = |i: &IndexUsage| (i.primary, i.unique);
+    rank(other) > rank(index) || (rank(other) == rank(index) && other.name < index.name)
+}
+
+/// Share of a table or its indexes above which the space is reported as bloat
+const BLOAT_RATIO: f64 = 0.3;
+
+/// Reason a table needs maintenance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthIssue {
+    /// More dead rows than the autovacuum threshold, so autovacuum is behind
+    VacuumOverdue,
+    /// More changed rows than the autoanalyze threshold
+    AnalyzeOverdue,
+    /// Statistics were never collected, so the planner guesses
+    NeverAnalyzed,
+    TableBloat,
+    IndexBloat,
+}
+
+/// A table with what needs attention about it
+#[derive(Debug, Clone, Serialize)]
+pub struct TableHealthReport {
+    #[serde(flatten)]
+    pub table: TableHealth,
+    pub issues: Vec<HealthIssue>,
+}
+
+/// Check each table against its autovacuum thresholds and for bloat, listing
+/// the tables with issues first, most dead rows first
+pub fn table_health_report(tables: Vec<TableHealth>) -> Vec<TableHealthReport> {
+    let mut report: Vec<TableHealthReport> = tables
+        .into_iter()
+        .map(|table| TableHealthReport {
+            issues: health_issues(&table),
+            table,
+        })
+        .collect();
+    report.sort_by(|a, b| {
+        a.issues.is_empty().cmp(&b.issues.is_empty()).then(b.table.dead_rows.cmp(&a.table.dead_rows))
+    });
+    report
+}
+
+fn health_issues(table: &TableHealth) -> Vec<HealthIssue> {
+    let mut issues = Vec::new();
+    if table.dead_rows > table.vacuum_threshold {
+        issues.push(HealthIssue::VacuumOverdue);
+    }
+    if table.last_analyze.is_none() && table.last_autoanalyze.is_none() {
+        if table.live_rows > 0 {
+            issues.push(HealthIssue::NeverAnalyzed);
+        }
+    } else if table.modified_rows.is_some_and(|rows| rows > table.analyze_threshold) {
+        issues.push(HealthIssue::AnalyzeOverdue);
+    }
+
+    let bloated = |bloat: Option<i64>| bloat.is_some_and(|bloat| bloat as f64 > table.size as f64 * BLOAT_RATIO);
+    if bloated(table.table_bloat) {
+        issues.push(HealthIssue::TableBloat);
+    }
+    if bloated(table.index_bloat) {
+        issues.push(HealthIssue::IndexBloat);
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect, SQLiteDialect};
+
+    #[test]
+    fn test_maintenance_statements() {
+        let pg = PostgreSQLDialect::new();
+        let mysql = MySQLDialect::new();
+        let sqlite = SQLiteDialect::new();
+
+        assert_eq!(
+            maintenance_statement(MaintenanceAction::Vacuum, &pg, "sales.orders").unwrap(),
+            "VACUUM \"sales\".\"orders\""
+        );
+        assert_eq!(
+            maintenance_statement(MaintenanceAction::Optimize, &mysql, "orders").unwrap(),
+            "OPTIMIZE TABLE `orders`"
+        );
+        assert_eq!(maintenance_statement(MaintenanceAction::Vacuum, &sqlite, "orders").unwrap(), "VACUUM");
+        assert_eq!(
+            maintenance_statement(MaintenanceAction::Analyze, &sqlite, "orders").unwrap(),
+            "ANALYZE \"orders\""
+        );
+
+        assert!(maintenance_statement(MaintenanceAction::Optimize, &pg, "orders").is_err());
+        assert!(maintenance_statement(MaintenanceAction::Vacuum, &mysql, "orders").is_err());
+    }
+
+    fn index(name: &str, columns: &[&str], unique: bool, scans: i64, size: i64) -> IndexUsage {
+        IndexUsage {
+            schema: Some("public".to_string()),
+            table: "orders".to_string(),
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            unique,
+            primary: name.ends_with("_pkey"),
+            scans: Some(scans),
+            size: Some(size),
+        }
+    }
+
+    #[test]
+    fn test_index_report() {
+        let indexes = vec![
+            index("orders_pkey", &["id"], true, 0, 100),
+            index("orders_id_idx", &["id"], false, 50, 80),
+            index("orders_customer_idx", &["customer_id"], false, 0, 300),
+            index("orders_customer_date_idx", &["customer_id", "created_at"], false, 900, 500),
+            index("orders_number_key", &["number"], true, 0, 60),
+            index("orders_number_status_key", &["number", "status"], true, 10, 70),
+        ];
+        // The unique index on "number" is stricter than the one on "number" and "status"
+        let report = index_report(&indexes, 0);
+
+        let names = |list: Vec<&IndexUsage>| list.iter().map(|i| i.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(report.unused.iter().collect()), vec!["orders_customer_idx"]);
+        assert_eq!(
+            names(report.duplicates.iter().map(|d| &d.index).collect()),
+            vec!["orders_customer_idx", "orders_id_idx"]
+        );
+        assert_eq!(report.duplicates[1].covered_by, "orders_pkey");
+        assert_eq!(report.reclaimable_bytes, 300 + 80);
+    }
+
+    fn table(name: &str, dead_rows: i64, modified_rows: i64, table_bloat: i64, analyzed: bool) -> TableHealth {
+        TableHealth {
+            schema: Some("public".to_string()),
+            table: name.to_string(),
+            live_rows: 10_000,
+            dead_rows,
+            modified_rows: Some(modified_rows),
+            size: 1_000_000,
+            table_bloat: Some(table_bloat),
+            index_bloat: None,
+            last_vacuum: None,
+            last_autovacuum: None,
+            last_analyze: None,
+            last_autoanalyze: analyzed.then(chrono::Utc::now),
+            vacuum_threshold: 2_050,
+            analyze_threshold: 1_050,
+        }
+    }
+
+    #[test]
+    fn test_table_health_report() {
+        let report = table_health_report(vec![
+            table("stale", 3_000, 0, 0, true),
+            table("quiet", 100, 100, 0, true),
+            table("busy", 2_500, 5_000, 400_000, true),
+            table("new", 0, 0, 0, false),
+        ]);
+
+        let issues: Vec<(&str, Vec<HealthIssue>)> =
+            report.iter().map(|r| (r.table.table.as_str(), r.issues.clone())).collect();
+        assert_eq!(
+            issues,
+            vec![
+                ("stale", vec![HealthIssue::VacuumOverdue]),
+                (
+                    "busy",
+                    vec![HealthIssue::VacuumOverdue, HealthIssue::AnalyzeOverdue, HealthIssue::TableBloat]
+                ),
+                ("new", vec![HealthIssue::NeverAnalyzed]),
+                ("quiet", vec![]),
+            ]
+        );
+    }
+}
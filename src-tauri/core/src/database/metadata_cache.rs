@@ -0,0 +1,163 @@
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::database::adapter::{ColumnInfo, DatabaseAdapter, RoutineInfo, TableInfo};
+use crate::error::AppError;
+
+/// Column lookups run at the same time while loading the schema, leaving the
+/// rest of the pool to queries
+const COLUMN_LOOKUPS: usize = 4;
+
+/// Everything the schema browser shows for the active connection
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaSnapshot {
+    /// Tables and views
+    pub tables: Vec<TableInfo>,
+    pub routines: Vec<RoutineInfo>,
+    /// Columns by table name as it appears in the catalog
+    pub columns: HashMap<String, Vec<ColumnInfo>>,
+}
+
+/// Load the schema of the connection
+///
+/// Tables and routines are listed concurrently, then the columns of every table
+/// are fetched with up to `COLUMN_LOOKUPS` lookups in flight.
+pub async fn load_schema(adapter: &(dyn DatabaseAdapter + Send + Sync)) -> Result<SchemaSnapshot, AppError> {
+    let (tables, routines) = tokio::try_join!(adapter.list_tables(), adapter.list_routines())?;
+
+    let columns = stream::iter(&tables)
+        .map(|table| async move {
+            let columns = adapter.get_table_columns(&table.name).await?;
+            Ok::<_, AppError>((table.name.clone(), columns))
+        })
+        .buffer_unordered(COLUMN_LOOKUPS)
+        .try_collect()
+        .await?;
+
+    Ok(SchemaSnapshot { tables, routines, columns })
+}
+
+/// Table and column names of the active connection, kept so lookups such as
+/// "did you mean" suggestions do not query the database every time
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    tables: Option<Vec<String>>,
+    /// Column names by table name as it appears in the catalog
+    columns: HashMap<String, Vec<String>>,
+    schema: Option<SchemaSnapshot>,
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Table names, or `None` when they were not loaded yet
+    pub fn tables(&self) -> Option<&[String]> {
+        self.tables.as_deref()
+    }
+
+    pub fn set_tables(&mut self, tables: Vec<String>) {
+        self.tables = Some(tables);
+    }
+
+    /// Column names of `table`, or `None` when they were not loaded yet
+    pub fn columns(&self, table: &str) -> Option<&[String]> {
+        self.columns.get(table).map(Vec::as_slice)
+    }
+
+    pub fn set_columns(&mut self, table: &str, columns: Vec<String>) {
+        self.columns.insert(table.to_string(), columns);
+    }
+
+    /// The schema last loaded by the schema browser, or `None` when it was not
+    /// loaded yet or has changed since
+    pub fn schema(&self) -> Option<&SchemaSnapshot> {
+        self.schema.as_ref()
+    }
+
+    /// Keep a loaded schema, which also fills in the table and column names
+    pub fn set_schema(&mut self, schema: SchemaSnapshot) {
+        self.tables = Some(schema.tables.iter().map(|t| t.name.clone()).collect());
+        self.columns = schema
+            .columns
+            .iter()
+            .map(|(table, columns)| (table.clone(), columns.iter().map(|c| c.name.clone()).collect()))
+            .collect();
+        self.schema = Some(schema);
+    }
+
+    /// Forget what was loaded about one table, along with the schema holding it
+    pub fn forget_table(&mut self, table: &str) {
+        self.columns.remove(table);
+        self.schema = None;
+    }
+
+    /// Forget everything, e.g. when the connection changes or a DDL statement ran
+    pub fn clear(&mut self) {
+        self.tables = None;
+        self.columns.clear();
+        self.schema = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_cache() {
+        let mut cache = MetadataCache::new();
+        assert!(cache.tables().is_none());
+
+        cache.set_tables(vec!["users".to_string()]);
+        cache.set_columns("users", vec!["id".to_string(), "email".to_string()]);
+        assert_eq!(cache.tables(), Some(&["users".to_string()][..]));
+        assert_eq!(cache.columns("users").unwrap().len(), 2);
+        // Quoted names of different case are different tables in PostgreSQL
+        assert!(cache.columns("Users").is_none());
+
+        cache.forget_table("users");
+        assert!(cache.columns("users").is_none());
+        assert!(cache.tables().is_some());
+
+        cache.clear();
+        assert!(cache.tables().is_none());
+        assert!(cache.columns("users").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_load_schema() {
+        use crate::database::adapter::{sqlite::SqliteAdapter, ConnectionParams, DatabaseType};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("schema.db");
+        let mut adapter = SqliteAdapter::new();
+        adapter
+            .connect(&ConnectionParams::new(DatabaseType::SQLite, path.to_string_lossy().into_owned()))
+            .await
+            .unwrap();
+        for statement in [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT)",
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER, total REAL)",
+            "CREATE VIEW totals AS SELECT user_id, SUM(total) AS total FROM orders GROUP BY user_id",
+        ] {
+            adapter.execute_command(statement).await.unwrap();
+        }
+
+        let schema = load_schema(&adapter).await.unwrap();
+        let tables: Vec<(&str, &str)> = schema.tables.iter().map(|t| (t.name.as_str(), t.table_type.as_str())).collect();
+        assert_eq!(tables, vec![("orders", "TABLE"), ("totals", "VIEW"), ("users", "TABLE")]);
+        assert_eq!(schema.columns["orders"].len(), 3);
+        assert_eq!(schema.columns["totals"].len(), 2);
+
+        let mut cache = MetadataCache::new();
+        cache.set_schema(schema);
+        assert_eq!(cache.columns("users"), Some(&["id".to_string(), "email".to_string()][..]));
+        cache.clear();
+        assert!(cache.schema().is_none());
+
+        adapter.disconnect().await.unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+pub mod adapter;
+pub mod column_stats;
+pub mod config;
+pub mod connection;
+pub mod connection_check;
+pub mod demo;
+pub mod dialect;
+pub mod engine_status;
+pub mod error;
+pub mod maintenance;
+pub mod metadata_cache;
+pub mod registry;
+pub mod result_cache;
+pub mod spill;
+pub mod sql_utils;
+pub mod statement;
+pub mod storage;
+pub mod suggestions;
+pub mod templates;
+pub mod types;
+pub mod capabilities;
+
+pub use adapter::{DatabaseAdapter, DatabaseType, ConnectionParams, create_adapter};
+pub use config::DatabaseConfig;
+pub use connection::DatabaseConnection;
+pub use error::{DatabaseError, Result};
+pub use capabilities::{DatabaseCapabilities, QueryTemplates};
\ No newline at end of file
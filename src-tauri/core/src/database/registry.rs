@@ -0,0 +1,83 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::database::adapter::{mysql, postgres, sqlite, DatabaseAdapter};
+use crate::database::dialect::SqlDialect;
+
+/// Constructors of the adapter and dialect of one database type
+///
+/// Each adapter module provides its own `driver()`; `create_adapter` and
+/// `create_dialect` find them here instead of matching on the database type.
+/// Drivers registered outside this crate show up as `DatabaseType::Other(id)`.
+#[derive(Clone, Copy)]
+pub struct Driver {
+    /// Name the database type is stored and sent as, e.g. "postgresql"
+    pub id: &'static str,
+    /// Port used when a profile gives none
+    pub default_port: Option<u16>,
+    /// Whether connections need a host and credentials, unlike database files
+    pub server: bool,
+    pub create_adapter: fn() -> Box<dyn DatabaseAdapter + Send + Sync>,
+    pub create_dialect: fn() -> Box<dyn SqlDialect>,
+}
+
+/// Registered drivers by id; the built-in ones are there before any other is
+/// registered, so registering can replace them
+static DRIVERS: Lazy<RwLock<HashMap<&'static str, Driver>>> = Lazy::new(|| {
+    let builtin = [postgres::driver(), mysql::driver(), sqlite::driver()];
+    RwLock::new(builtin.into_iter().map(|driver| (driver.id, driver)).collect())
+});
+
+/// Make a driver available, replacing any registered with the same id
+pub fn register_driver(driver: Driver) {
+    DRIVERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(driver.id, driver);
+}
+
+/// The driver registered as `id`
+pub fn driver(id: &str) -> Option<Driver> {
+    DRIVERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(id)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::{create_adapter, DatabaseType};
+    use crate::database::dialect::create_dialect;
+
+    #[test]
+    fn test_builtin_drivers() {
+        for database_type in [DatabaseType::PostgreSQL, DatabaseType::MySQL, DatabaseType::SQLite] {
+            let driver = driver(database_type.driver_id()).unwrap();
+            assert_eq!((driver.create_adapter)().database_type(), database_type);
+            assert_eq!((driver.create_dialect)().database_type(), database_type);
+        }
+    }
+
+    #[test]
+    fn test_registered_driver() {
+        register_driver(Driver {
+            id: "embedded",
+            default_port: None,
+            server: false,
+            ..sqlite::driver()
+        });
+
+        let database_type: DatabaseType = serde_json::from_str("\"embedded\"").unwrap();
+        assert_eq!(database_type, DatabaseType::Other("embedded"));
+        assert_eq!(serde_json::to_string(&database_type).unwrap(), "\"embedded\"");
+        assert!(!database_type.requires_host());
+        assert!(create_adapter(database_type).is_ok());
+        assert_eq!(create_dialect(database_type).database_type(), DatabaseType::SQLite);
+
+        assert!(serde_json::from_str::<DatabaseType>("\"unregistered\"").is_err());
+        assert!(create_adapter(DatabaseType::PostgreSQL).is_ok());
+    }
+}
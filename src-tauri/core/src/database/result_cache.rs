@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// Results kept at most; the oldest is dropped to make room
+const MAX_ENTRIES: usize = 100;
+
+/// Identity of a cached result
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    connection_id: String,
+    sql: String,
+    params: BTreeMap<String, String>,
+    masked: bool,
+}
+
+impl CacheKey {
+    /// Key of `sql` run on a connection with template `params`; SQL differing only
+    /// in whitespace or a trailing semicolon shares a key
+    pub fn new(connection_id: &str, sql: &str, params: &HashMap<String, String>, masked: bool) -> Self {
+        Self {
+            connection_id: connection_id.to_string(),
+            sql: normalize_sql(sql),
+            params: params.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            masked,
+        }
+    }
+}
+
+/// `sql` with runs of whitespace outside quotes collapsed to one space and
+/// trailing semicolons removed
+pub fn normalize_sql(sql: &str) -> String {
+    let mut normalized = String::with_capacity(sql.len());
+    let mut quote: Option<char> = None;
+    let mut pending_space = false;
+
+    for c in sql.trim().chars() {
+        match quote {
+            Some(q) => {
+                normalized.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => pending_space = true,
+            None => {
+                if pending_space {
+                    normalized.push(' ');
+                    pending_space = false;
+                }
+                if matches!(c, '\'' | '"' | '`') {
+                    quote = Some(c);
+                }
+                normalized.push(c);
+            }
+        }
+    }
+
+    while normalized.ends_with(';') || normalized.ends_with(' ') {
+        normalized.pop();
+    }
+    normalized
+}
+
+struct CachedResult {
+    result: serde_json::Value,
+    stored_at: Instant,
+    expires_at: Instant,
+}
+
+/// Results of read-only queries that callers opted to cache, each kept for its own TTL
+#[derive(Default)]
+pub struct ResultCache {
+    entries: HashMap<CacheKey, CachedResult>,
+}
+
+impl ResultCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The result stored under `key`, unless it has expired
+    pub fn get(&self, key: &CacheKey, now: Instant) -> Option<&serde_json::Value> {
+        self.entries
+            .get(key)
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| &entry.result)
+    }
+
+    pub fn insert(&mut self, key: CacheKey, result: serde_json::Value, ttl: Duration, now: Instant) {
+        self.entries.retain(|_, entry| entry.expires_at > now);
+        if self.entries.len() >= MAX_ENTRIES && !self.entries.contains_key(&key) {
+            let oldest = self.entries.iter().min_by_key(|(_, entry)| entry.stored_at).map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.entries.insert(key, CachedResult {
+            result,
+            stored_at: now,
+            expires_at: now + ttl,
+        });
+    }
+
+    /// Drop the results of one connection, or of all when `None`; returns how many
+    pub fn invalidate(&mut self, connection_id: Option<&str>) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|key, _| connection_id.is_some_and(|id| key.connection_id != id));
+        before - self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(connection_id: &str, sql: &str) -> CacheKey {
+        CacheKey::new(connection_id, sql, &HashMap::new(), true)
+    }
+
+    #[test]
+    fn test_normalize_sql() {
+        assert_eq!(normalize_sql("  SELECT *\n\tFROM  users ;; "), "SELECT * FROM users");
+        assert_eq!(normalize_sql("SELECT 'a  b'  FROM t"), "SELECT 'a  b' FROM t");
+        assert_eq!(key("c1", "SELECT 1;"), key("c1", "SELECT   1"));
+        assert_ne!(key("c1", "SELECT 1"), key("c2", "SELECT 1"));
+
+        let params = HashMap::from([("limit".to_string(), "10".to_string())]);
+        assert_ne!(CacheKey::new("c1", "SELECT 1", &params, true), key("c1", "SELECT 1"));
+    }
+
+    #[test]
+    fn test_ttl_and_invalidation() {
+        let now = Instant::now();
+        let mut cache = ResultCache::new();
+        cache.insert(key("c1", "SELECT 1"), json!([1]), Duration::from_secs(30), now);
+        cache.insert(key("c2", "SELECT 2"), json!([2]), Duration::from_secs(30), now);
+
+        assert_eq!(cache.get(&key("c1", "SELECT 1"), now + Duration::from_secs(10)), Some(&json!([1])));
+        assert!(cache.get(&key("c1", "SELECT 1"), now + Duration::from_secs(30)).is_none());
+
+        assert_eq!(cache.invalidate(Some("c1")), 1);
+        assert!(cache.get(&key("c1", "SELECT 1"), now).is_none());
+        assert!(cache.get(&key("c2", "SELECT 2"), now).is_some());
+        assert_eq!(cache.invalidate(None), 1);
+    }
+
+    #[test]
+    fn test_oldest_evicted() {
+        let now = Instant::now();
+        let mut cache = ResultCache::new();
+        for i in 0..=MAX_ENTRIES {
+            let stored_at = now + Duration::from_millis(i as u64);
+            cache.insert(key("c1", &format!("SELECT {}", i)), json!(i), Duration::from_secs(60), stored_at);
+        }
+
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert!(cache.get(&key("c1", "SELECT 0"), now).is_none());
+        assert!(cache.get(&key("c1", "SELECT 1"), now).is_some());
+    }
+}
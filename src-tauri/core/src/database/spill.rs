@@ -0,0 +1,159 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+
+use crate::error::AppError;
+
+/// Spilled rows between two recorded file offsets
+const CHECKPOINT_ROWS: u64 = 1024;
+
+/// Bytes counted for each value besides its text, for the `Option<String>` itself
+const VALUE_OVERHEAD: usize = 32;
+
+type Row = Vec<Option<String>>;
+
+/// Rows of a query result held in memory up to `memory_limit` bytes
+///
+/// Rows past the limit are appended to an anonymous scratch file as JSON lines,
+/// which the OS removes once it is closed. The offset of every 1024th spilled
+/// row is kept so a page can be read back without scanning the whole file.
+pub struct SpooledRows {
+    memory_limit: usize,
+    memory: Vec<Row>,
+    memory_bytes: usize,
+    spill: Option<Spill>,
+}
+
+struct Spill {
+    writer: BufWriter<File>,
+    /// File offset of spilled rows 0, 1024, 2048, ...
+    checkpoints: Vec<u64>,
+    rows: u64,
+    bytes: u64,
+}
+
+impl SpooledRows {
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            memory_limit,
+            memory: Vec::new(),
+            memory_bytes: 0,
+            spill: None,
+        }
+    }
+
+    pub fn push(&mut self, row: Row) -> Result<(), AppError> {
+        let size = row.iter().map(|v| v.as_ref().map_or(0, String::len) + VALUE_OVERHEAD).sum::<usize>();
+        if self.spill.is_none() && self.memory_bytes + size <= self.memory_limit {
+            self.memory_bytes += size;
+            self.memory.push(row);
+            return Ok(());
+        }
+
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            None => self.spill.insert(Spill {
+                writer: BufWriter::new(tempfile::tempfile()?),
+                checkpoints: Vec::new(),
+                rows: 0,
+                bytes: 0,
+            }),
+        };
+        if spill.rows % CHECKPOINT_ROWS == 0 {
+            spill.checkpoints.push(spill.bytes);
+        }
+
+        let mut line = serde_json::to_vec(&row)?;
+        line.push(b'\n');
+        spill.writer.write_all(&line)?;
+        spill.rows += 1;
+        spill.bytes += line.len() as u64;
+        Ok(())
+    }
+
+    pub fn row_count(&self) -> u64 {
+        self.memory.len() as u64 + self.spill.as_ref().map_or(0, |s| s.rows)
+    }
+
+    /// Whether some rows went to the scratch file
+    pub fn spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Up to `limit` rows starting at `offset`
+    pub fn page(&mut self, offset: u64, limit: usize) -> Result<Vec<Row>, AppError> {
+        let in_memory = self.memory.len() as u64;
+        let mut rows: Vec<Row> = self
+            .memory
+            .iter()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(limit)
+            .cloned()
+            .collect();
+
+        let Some(spill) = &mut self.spill else {
+            return Ok(rows);
+        };
+        let wanted = limit - rows.len();
+        let first = offset.saturating_sub(in_memory);
+        if wanted == 0 || first >= spill.rows {
+            return Ok(rows);
+        }
+
+        spill.writer.flush()?;
+        let checkpoint = first / CHECKPOINT_ROWS;
+        let mut file = spill.writer.get_ref().try_clone()?;
+        file.seek(SeekFrom::Start(spill.checkpoints[checkpoint as usize]))?;
+
+        // The clone shares the file position with the writer, so put it back afterwards
+        let lines = BufReader::new(&mut file)
+            .lines()
+            .skip((first - checkpoint * CHECKPOINT_ROWS) as usize)
+            .take(wanted);
+        for line in lines {
+            rows.push(serde_json::from_str(&line?)?);
+        }
+        file.seek(SeekFrom::Start(spill.bytes))?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: u64) -> Row {
+        vec![Some(id.to_string()), None]
+    }
+
+    #[test]
+    fn test_spill_past_limit() {
+        // Room for three rows in memory
+        let mut rows = SpooledRows::new(3 * (2 * VALUE_OVERHEAD + 4));
+        for id in 0..3000 {
+            rows.push(row(id)).unwrap();
+        }
+        assert_eq!(rows.row_count(), 3000);
+        assert!(rows.spilled());
+        assert_eq!(rows.memory.len(), 3);
+
+        // A page spanning memory and the file, and one past a checkpoint
+        assert_eq!(rows.page(1, 4).unwrap(), (1..5).map(row).collect::<Vec<_>>());
+        assert_eq!(rows.page(2500, 3).unwrap(), (2500..2503).map(row).collect::<Vec<_>>());
+        assert_eq!(rows.page(2998, 10).unwrap(), vec![row(2998), row(2999)]);
+        assert!(rows.page(5000, 10).unwrap().is_empty());
+
+        // Rows pushed after a read land at the end of the file
+        rows.push(row(3000)).unwrap();
+        assert_eq!(rows.page(2999, 10).unwrap(), vec![row(2999), row(3000)]);
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let mut rows = SpooledRows::new(usize::MAX);
+        for id in 0..10 {
+            rows.push(row(id)).unwrap();
+        }
+        assert!(!rows.spilled());
+        assert_eq!(rows.page(8, 5).unwrap(), vec![row(8), row(9)]);
+    }
+}
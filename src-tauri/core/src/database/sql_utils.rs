@@ -1,4 +1,4 @@
-use sqlparser::dialect::{Dialect, PostgreSqlDialect, MySqlDialect, SQLiteDialect};
+use sqlparser::dialect::{Dialect, GenericDialect, PostgreSqlDialect, MySqlDialect, SQLiteDialect};
 use sqlparser::parser::Parser;
 
 /// SQL文を分割して返す
@@ -18,11 +18,12 @@ pub fn split_sql_statements(sql: &str, database_type: &super::adapter::DatabaseT
 }
 
 /// データベースタイプに応じたDialectを取得
-fn get_dialect(database_type: &super::adapter::DatabaseType) -> Box<dyn Dialect> {
+pub(crate) fn get_dialect(database_type: &super::adapter::DatabaseType) -> Box<dyn Dialect> {
     match database_type {
         super::adapter::DatabaseType::PostgreSQL => Box::new(PostgreSqlDialect {}),
         super::adapter::DatabaseType::MySQL => Box::new(MySqlDialect {}),
         super::adapter::DatabaseType::SQLite => Box::new(SQLiteDialect {}),
+        super::adapter::DatabaseType::Other(_) => Box::new(GenericDialect {}),
     }
 }
 
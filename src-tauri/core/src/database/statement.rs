@@ -0,0 +1,635 @@
+use sqlparser::ast::{FromTable, ObjectName, Query, SelectItem, SetExpr, Statement, TableAlias, TableFactor, TableWithJoins};
+use sqlparser::parser::Parser;
+use super::adapter::DatabaseType;
+use super::sql_utils::get_dialect;
+
+/// What a single SQL statement does, as far as safety checks are concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// Reads data only (SELECT, SHOW, EXPLAIN, ...)
+    Query,
+    Insert,
+    /// `filtered` is false when the statement has no WHERE clause
+    Update { filtered: bool },
+    Delete { filtered: bool },
+    /// Changes the schema (CREATE, ALTER, DROP, TRUNCATE, ...); `destructive` is set
+    /// for statements that can lose data or break existing code
+    Ddl { destructive: bool },
+    Other,
+}
+
+impl StatementKind {
+    pub fn is_ddl(&self) -> bool {
+        matches!(self, StatementKind::Ddl { .. })
+    }
+
+    /// UPDATE or DELETE that touches every row of its table
+    pub fn is_unfiltered_dml(&self) -> bool {
+        matches!(
+            self,
+            StatementKind::Update { filtered: false } | StatementKind::Delete { filtered: false }
+        )
+    }
+
+    /// Why the statement needs an explicit confirmation, if it does
+    pub fn destructive_reason(&self) -> Option<&'static str> {
+        match self {
+            StatementKind::Ddl { destructive: true } => Some("drops, truncates, or alters an object"),
+            StatementKind::Update { filtered: false } => Some("updates every row of the table"),
+            StatementKind::Delete { filtered: false } => Some("deletes every row of the table"),
+            _ => None,
+        }
+    }
+}
+
+/// Classify one SQL statement
+///
+/// Statements the parser does not understand are classified by their leading keyword.
+pub fn classify_statement(sql: &str, database_type: &DatabaseType) -> StatementKind {
+    let dialect = get_dialect(database_type);
+    match Parser::parse_sql(&*dialect, sql) {
+        Ok(statements) if statements.len() == 1 => classify_parsed(&statements[0]),
+        _ => classify_by_keyword(sql),
+    }
+}
+
+/// SQLite pragmas that take an argument without changing anything
+const LOOKUP_PRAGMAS: &[&str] = &[
+    "table_info",
+    "table_xinfo",
+    "table_list",
+    "index_list",
+    "index_info",
+    "index_xinfo",
+    "foreign_key_list",
+    "foreign_key_check",
+    "integrity_check",
+    "quick_check",
+];
+
+/// Whether every statement in `sql` only reads data
+///
+/// Unlike `classify_statement` this never guesses: SQL the parser does not understand
+/// is not read-only. Data-modifying CTEs, `SELECT ... INTO`, locking reads, and
+/// PRAGMAs that set a value are not read-only either.
+pub fn is_read_only(sql: &str, database_type: &DatabaseType) -> bool {
+    let dialect = get_dialect(database_type);
+    match Parser::parse_sql(&*dialect, sql) {
+        Ok(statements) => !statements.is_empty() && statements.iter().all(read_only_statement),
+        // The parser only accepts quoted pragma arguments, as in `table_info('users')`
+        Err(_) => is_lookup_pragma(sql),
+    }
+}
+
+/// `PRAGMA name(arg)` with a bare argument, where `name` only looks something up
+fn is_lookup_pragma(sql: &str) -> bool {
+    let sql = sql.trim().trim_end_matches(';');
+    if !sql.get(..7).is_some_and(|prefix| prefix.eq_ignore_ascii_case("PRAGMA ")) {
+        return false;
+    }
+    let Some((name, arg)) = sql[7..].split_once('(') else {
+        return false;
+    };
+
+    let name = name.trim().rsplit('.').next().unwrap_or("").to_lowercase();
+    let arg_is_identifier = arg
+        .strip_suffix(')')
+        .is_some_and(|arg| arg.trim().chars().all(|c| c.is_alphanumeric() || c == '_' || c == '"'));
+
+    LOOKUP_PRAGMAS.contains(&name.as_str()) && arg_is_identifier
+}
+
+fn read_only_statement(statement: &Statement) -> bool {
+    match statement {
+        Statement::Query(query) => read_only_query(query),
+        Statement::Explain { statement, .. } => read_only_statement(statement),
+        Statement::ExplainTable { .. }
+        | Statement::ShowFunctions { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowStatus { .. }
+        | Statement::ShowVariables { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowDatabases { .. }
+        | Statement::ShowSchemas { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowViews { .. }
+        | Statement::ShowCollation { .. } => true,
+        Statement::Pragma { name, value, is_eq } => {
+            // `PRAGMA x(arg)` sets x for most pragmas but only looks something up for these
+            let lookup = !is_eq && name.0.last().is_some_and(|ident| {
+                LOOKUP_PRAGMAS.contains(&ident.value.to_lowercase().as_str())
+            });
+            value.is_none() || lookup
+        }
+        _ => false,
+    }
+}
+
+fn read_only_query(query: &Query) -> bool {
+    let ctes_read_only = query
+        .with
+        .as_ref()
+        .is_none_or(|with| with.cte_tables.iter().all(|cte| read_only_query(&cte.query)));
+
+    ctes_read_only && query.locks.is_empty() && read_only_set_expr(&query.body)
+}
+
+fn read_only_set_expr(body: &SetExpr) -> bool {
+    match body {
+        SetExpr::Select(select) => select.into.is_none(),
+        SetExpr::Query(query) => read_only_query(query),
+        SetExpr::SetOperation { left, right, .. } => read_only_set_expr(left) && read_only_set_expr(right),
+        SetExpr::Values(_) | SetExpr::Table(_) => true,
+        _ => false,
+    }
+}
+
+/// Names of the tables a query reads from, lowercased and without schema
+///
+/// Returns `None` when `sql` is not a single query the parser understands.
+pub fn referenced_tables(sql: &str, database_type: &DatabaseType) -> Option<Vec<String>> {
+    let dialect = get_dialect(database_type);
+    let statements = Parser::parse_sql(&*dialect, sql).ok()?;
+    match statements.as_slice() {
+        [Statement::Query(query)] => {
+            let mut tables = Vec::new();
+            query_tables(query, &mut tables);
+            Some(tables)
+        }
+        _ => None,
+    }
+}
+
+/// Names a query gives to its output columns, each with the identifiers of the
+/// expression it names, all lowercased
+///
+/// Covers `expr AS alias` in every SELECT of the query, including subqueries and
+/// CTEs, and the column lists of CTE and subquery aliases, which rename whatever
+/// the inner query selects. Empty when `sql` is not SQL the parser understands.
+pub fn column_aliases(sql: &str, database_type: &DatabaseType) -> Vec<(String, Vec<String>)> {
+    let dialect = get_dialect(database_type);
+    let mut aliases = Vec::new();
+    for statement in Parser::parse_sql(&*dialect, sql).unwrap_or_default() {
+        if let Statement::Query(query) = statement {
+            query_aliases(&query, &mut aliases);
+        }
+    }
+    aliases
+}
+
+fn query_aliases(query: &Query, aliases: &mut Vec<(String, Vec<String>)>) {
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            query_aliases(&cte.query, aliases);
+            renamed_columns(&cte.alias, &cte.query, aliases);
+        }
+    }
+    set_expr_aliases(&query.body, aliases);
+}
+
+fn set_expr_aliases(body: &SetExpr, aliases: &mut Vec<(String, Vec<String>)>) {
+    match body {
+        SetExpr::Select(select) => {
+            for item in &select.projection {
+                if let SelectItem::ExprWithAlias { expr, alias } = item {
+                    aliases.push((alias.value.to_lowercase(), identifiers(&expr.to_string())));
+                }
+            }
+            for table in &select.from {
+                table_with_joins_aliases(table, aliases);
+            }
+        }
+        SetExpr::Query(query) => query_aliases(query, aliases),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_aliases(left, aliases);
+            set_expr_aliases(right, aliases);
+        }
+        _ => {}
+    }
+}
+
+fn table_with_joins_aliases(table: &TableWithJoins, aliases: &mut Vec<(String, Vec<String>)>) {
+    for factor in std::iter::once(&table.relation).chain(table.joins.iter().map(|j| &j.relation)) {
+        match factor {
+            TableFactor::Derived { subquery, alias, .. } => {
+                query_aliases(subquery, aliases);
+                if let Some(alias) = alias {
+                    renamed_columns(alias, subquery, aliases);
+                }
+            }
+            TableFactor::NestedJoin { table_with_joins, .. } => {
+                table_with_joins_aliases(table_with_joins, aliases)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Columns renamed by position, as in `WITH t (a, b) AS (...)`; each may be any
+/// of the inner query's outputs
+fn renamed_columns(alias: &TableAlias, query: &Query, aliases: &mut Vec<(String, Vec<String>)>) {
+    if alias.columns.is_empty() {
+        return;
+    }
+    let selected = identifiers(&query.body.to_string());
+    for column in &alias.columns {
+        aliases.push((column.value.to_lowercase(), selected.clone()));
+    }
+}
+
+/// Every identifier-like word of `text`, lowercased and without quotes
+fn identifiers(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        let word = word.to_lowercase();
+        if !word.is_empty() && !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// Names of the tables a statement reads, writes, or changes, lowercased and
+/// without schema
+///
+/// Unlike `referenced_tables` this covers DML and DDL too. Returns `None` when
+/// `sql` is not a single statement the parser understands.
+pub fn statement_tables(sql: &str, database_type: &DatabaseType) -> Option<Vec<String>> {
+    let dialect = get_dialect(database_type);
+    let statements = Parser::parse_sql(&*dialect, sql).ok()?;
+    let [statement] = statements.as_slice() else {
+        return None;
+    };
+
+    let mut tables = Vec::new();
+    match statement {
+        Statement::Query(query) => query_tables(query, &mut tables),
+        Statement::Insert(insert) => {
+            push_table(&insert.table_name, &mut tables);
+            if let Some(source) = &insert.source {
+                query_tables(source, &mut tables);
+            }
+        }
+        Statement::Update { table, from, .. } => {
+            table_with_joins_tables(table, &mut tables);
+            if let Some(from) = from {
+                table_with_joins_tables(from, &mut tables);
+            }
+        }
+        Statement::Delete(delete) => {
+            for name in &delete.tables {
+                push_table(name, &mut tables);
+            }
+            let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = &delete.from;
+            for table in from.iter().chain(delete.using.iter().flatten()) {
+                table_with_joins_tables(table, &mut tables);
+            }
+        }
+        Statement::CreateTable(create) => {
+            push_table(&create.name, &mut tables);
+            if let Some(query) = &create.query {
+                query_tables(query, &mut tables);
+            }
+        }
+        Statement::AlterTable { name, .. } => push_table(name, &mut tables),
+        Statement::Drop { names, .. } => {
+            for name in names {
+                push_table(name, &mut tables);
+            }
+        }
+        Statement::Truncate { table_names, .. } => {
+            for target in table_names {
+                push_table(&target.name, &mut tables);
+            }
+        }
+        _ => {}
+    }
+    Some(tables)
+}
+
+fn push_table(name: &ObjectName, tables: &mut Vec<String>) {
+    if let Some(ident) = name.0.last() {
+        let name = ident.value.to_lowercase();
+        if !tables.contains(&name) {
+            tables.push(name);
+        }
+    }
+}
+
+fn query_tables(query: &Query, tables: &mut Vec<String>) {
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            query_tables(&cte.query, tables);
+        }
+    }
+    set_expr_tables(&query.body, tables);
+}
+
+fn set_expr_tables(body: &SetExpr, tables: &mut Vec<String>) {
+    match body {
+        SetExpr::Select(select) => {
+            for table in &select.from {
+                table_with_joins_tables(table, tables);
+            }
+        }
+        SetExpr::Query(query) => query_tables(query, tables),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_tables(left, tables);
+            set_expr_tables(right, tables);
+        }
+        _ => {}
+    }
+}
+
+fn table_with_joins_tables(table: &TableWithJoins, tables: &mut Vec<String>) {
+    for factor in std::iter::once(&table.relation).chain(table.joins.iter().map(|j| &j.relation)) {
+        match factor {
+            TableFactor::Table { name, .. } => push_table(name, tables),
+            TableFactor::Derived { subquery, .. } => query_tables(subquery, tables),
+            TableFactor::NestedJoin { table_with_joins, .. } => {
+                table_with_joins_tables(table_with_joins, tables)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn classify_parsed(statement: &Statement) -> StatementKind {
+    match statement {
+        Statement::Query(query) => classify_query(query),
+        // EXPLAIN ANALYZE runs the statement, so it counts as what it explains
+        Statement::Explain { statement, .. } => classify_parsed(statement),
+        Statement::ExplainTable { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowCreate { .. } => StatementKind::Query,
+        Statement::Insert(_) => StatementKind::Insert,
+        Statement::Update { selection, .. } => StatementKind::Update {
+            filtered: selection.is_some(),
+        },
+        Statement::Delete(delete) => StatementKind::Delete {
+            filtered: delete.selection.is_some(),
+        },
+        Statement::AlterTable { .. }
+        | Statement::AlterIndex { .. }
+        | Statement::AlterView { .. }
+        | Statement::Drop { .. }
+        | Statement::DropFunction { .. }
+        | Statement::DropProcedure { .. }
+        | Statement::DropTrigger { .. }
+        | Statement::Truncate { .. } => StatementKind::Ddl { destructive: true },
+        Statement::CreateTable(_)
+        | Statement::CreateView { .. }
+        | Statement::CreateVirtualTable { .. }
+        | Statement::CreateIndex(_)
+        | Statement::CreateSchema { .. }
+        | Statement::CreateDatabase { .. }
+        | Statement::CreateFunction { .. }
+        | Statement::CreateProcedure { .. }
+        | Statement::CreateTrigger { .. }
+        | Statement::CreateSequence { .. }
+        | Statement::CreateType { .. }
+        | Statement::CreateExtension { .. }
+        | Statement::Comment { .. } => StatementKind::Ddl { destructive: false },
+        _ => StatementKind::Other,
+    }
+}
+
+/// A query that reads only is a `Query`; one with data-modifying CTEs or
+/// `SELECT ... INTO` counts as the most destructive thing it does
+fn classify_query(query: &Query) -> StatementKind {
+    if read_only_query(query) {
+        return StatementKind::Query;
+    }
+
+    let mut kinds = Vec::new();
+    query_write_kinds(query, &mut kinds);
+    kinds
+        .iter()
+        .find(|kind| kind.destructive_reason().is_some())
+        .or(kinds.first())
+        .copied()
+        .unwrap_or(StatementKind::Other)
+}
+
+fn query_write_kinds(query: &Query, kinds: &mut Vec<StatementKind>) {
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            query_write_kinds(&cte.query, kinds);
+        }
+    }
+    set_expr_write_kinds(&query.body, kinds);
+}
+
+fn set_expr_write_kinds(body: &SetExpr, kinds: &mut Vec<StatementKind>) {
+    match body {
+        SetExpr::Select(select) if select.into.is_some() => kinds.push(StatementKind::Ddl { destructive: false }),
+        SetExpr::Query(query) => query_write_kinds(query, kinds),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_write_kinds(left, kinds);
+            set_expr_write_kinds(right, kinds);
+        }
+        SetExpr::Insert(statement) | SetExpr::Update(statement) => kinds.push(classify_parsed(statement)),
+        _ => {}
+    }
+}
+
+fn classify_by_keyword(sql: &str) -> StatementKind {
+    let upper = sql.trim_start().to_uppercase();
+    let keyword = upper.split_whitespace().next().unwrap_or("");
+    let has_where = upper.split_whitespace().any(|word| word == "WHERE");
+
+    if matches!(keyword, "WITH" | "EXPLAIN") {
+        if let Some(start) = data_modifying_keyword(&upper) {
+            return classify_by_keyword(enclosed(&upper[start..]));
+        }
+    }
+
+    match keyword {
+        "SELECT" | "WITH" | "SHOW" | "EXPLAIN" | "DESCRIBE" | "DESC" | "PRAGMA" | "VALUES" => {
+            StatementKind::Query
+        }
+        "INSERT" | "REPLACE" => StatementKind::Insert,
+        "UPDATE" => StatementKind::Update { filtered: has_where },
+        "DELETE" => StatementKind::Delete { filtered: has_where },
+        "ALTER" | "DROP" | "TRUNCATE" | "RENAME" => StatementKind::Ddl { destructive: true },
+        "CREATE" | "COMMENT" => StatementKind::Ddl { destructive: false },
+        _ => StatementKind::Other,
+    }
+}
+
+/// Offset of the first INSERT, UPDATE, or DELETE keyword in `upper`, skipping
+/// the `UPDATE` of `FOR UPDATE`
+fn data_modifying_keyword(upper: &str) -> Option<usize> {
+    let mut previous = "";
+    let mut start = None;
+    for (i, c) in upper.char_indices().chain(std::iter::once((upper.len(), ' '))) {
+        if c.is_alphanumeric() || c == '_' {
+            start.get_or_insert(i);
+        } else if let Some(word_start) = start.take() {
+            let word = &upper[word_start..i];
+            if matches!(word, "INSERT" | "UPDATE" | "DELETE") && previous != "FOR" {
+                return Some(word_start);
+            }
+            previous = word;
+        }
+    }
+    None
+}
+
+/// `sql` up to the parenthesis closing the CTE it starts in, if any
+fn enclosed(sql: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in sql.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return &sql[..i],
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(sql: &str) -> StatementKind {
+        classify_statement(sql, &DatabaseType::PostgreSQL)
+    }
+
+    #[test]
+    fn test_classify_statement() {
+        assert_eq!(classify("SELECT * FROM users"), StatementKind::Query);
+        assert_eq!(classify("INSERT INTO users (id) VALUES (1)"), StatementKind::Insert);
+        assert_eq!(classify("UPDATE users SET a = 1 WHERE id = 2"), StatementKind::Update { filtered: true });
+        assert_eq!(classify("DELETE FROM users"), StatementKind::Delete { filtered: false });
+        assert_eq!(classify("DROP TABLE users"), StatementKind::Ddl { destructive: true });
+        assert_eq!(classify("TRUNCATE users"), StatementKind::Ddl { destructive: true });
+        assert_eq!(classify("ALTER TABLE users ADD COLUMN b int"), StatementKind::Ddl { destructive: true });
+        assert_eq!(classify("CREATE INDEX idx ON users (a)"), StatementKind::Ddl { destructive: false });
+    }
+
+    #[test]
+    fn test_classify_writing_queries() {
+        assert_eq!(classify("WITH a AS (SELECT 1) SELECT * FROM a"), StatementKind::Query);
+        assert_eq!(classify("EXPLAIN SELECT * FROM users"), StatementKind::Query);
+        assert_eq!(classify("EXPLAIN ANALYZE DELETE FROM users"), StatementKind::Delete { filtered: false });
+        assert_eq!(
+            classify("EXPLAIN (ANALYZE) UPDATE users SET a = 1 WHERE id = 2"),
+            StatementKind::Update { filtered: true }
+        );
+        assert_eq!(
+            classify("WITH d AS (DELETE FROM users RETURNING *) SELECT * FROM d WHERE id = 1"),
+            StatementKind::Delete { filtered: false }
+        );
+        assert_eq!(
+            classify("WITH u AS (UPDATE users SET a = 1 RETURNING *) SELECT * FROM u"),
+            StatementKind::Update { filtered: false }
+        );
+        assert_eq!(
+            classify("WITH i AS (INSERT INTO users VALUES (1) RETURNING *) SELECT * FROM i"),
+            StatementKind::Insert
+        );
+        assert_eq!(classify("SELECT * INTO backup FROM users"), StatementKind::Ddl { destructive: false });
+        assert_eq!(classify("SELECT * FROM users FOR UPDATE"), StatementKind::Other);
+    }
+
+    #[test]
+    fn test_destructive_reason() {
+        assert!(classify("DROP TABLE users").destructive_reason().is_some());
+        assert!(classify("DELETE FROM users").destructive_reason().is_some());
+        assert!(classify("DELETE FROM users WHERE id = 1").destructive_reason().is_none());
+        assert!(classify("CREATE TABLE t (a int)").destructive_reason().is_none());
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        let postgres = DatabaseType::PostgreSQL;
+        assert!(is_read_only("SELECT * FROM users", &postgres));
+        assert!(is_read_only("WITH a AS (SELECT 1) SELECT * FROM a", &postgres));
+        assert!(is_read_only("EXPLAIN SELECT * FROM users", &postgres));
+        assert!(is_read_only("SHOW search_path", &postgres));
+
+        assert!(!is_read_only("DELETE FROM users", &postgres));
+        assert!(!is_read_only("EXPLAIN ANALYZE DELETE FROM users", &postgres));
+        assert!(!is_read_only("SELECT * INTO backup FROM users", &postgres));
+        assert!(!is_read_only("SELECT * FROM users FOR UPDATE", &postgres));
+        assert!(!is_read_only("SELECT 1; DROP TABLE users", &postgres));
+        assert!(!is_read_only("not even sql", &postgres));
+
+        let sqlite = DatabaseType::SQLite;
+        assert!(is_read_only("PRAGMA table_info(users)", &sqlite));
+        assert!(!is_read_only("PRAGMA user_version = 5", &sqlite));
+        assert!(!is_read_only("PRAGMA journal_mode(WAL)", &sqlite));
+        assert!(!is_read_only("PRAGMA table_info(users); DROP TABLE users", &sqlite));
+    }
+
+    #[test]
+    fn test_referenced_tables() {
+        let postgres = DatabaseType::PostgreSQL;
+        assert_eq!(
+            referenced_tables("SELECT * FROM public.Users u JOIN orders o ON o.user_id = u.id", &postgres),
+            Some(vec!["users".to_string(), "orders".to_string()])
+        );
+        assert_eq!(
+            referenced_tables("WITH r AS (SELECT * FROM refunds) SELECT * FROM (SELECT * FROM payments) p, r", &postgres),
+            Some(vec!["refunds".to_string(), "payments".to_string(), "r".to_string()])
+        );
+        assert_eq!(referenced_tables("DELETE FROM users", &postgres), None);
+    }
+
+    #[test]
+    fn test_column_aliases() {
+        let postgres = DatabaseType::PostgreSQL;
+        let names = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            column_aliases("SELECT id, lower(\"Email\") AS e FROM users", &postgres),
+            vec![("e".to_string(), names(&["lower", "email"]))]
+        );
+        assert_eq!(
+            column_aliases("SELECT x FROM (SELECT ssn AS x FROM people) p", &postgres),
+            vec![("x".to_string(), names(&["ssn"]))]
+        );
+        assert_eq!(
+            column_aliases("WITH t (a) AS (SELECT phone FROM users) SELECT a FROM t", &postgres),
+            vec![("a".to_string(), names(&["select", "phone", "from", "users"]))]
+        );
+        assert!(column_aliases("SELECT email FROM users", &postgres).is_empty());
+        assert!(column_aliases("not even sql", &postgres).is_empty());
+    }
+
+    #[test]
+    fn test_statement_tables() {
+        let postgres = DatabaseType::PostgreSQL;
+        let tables = |sql| statement_tables(sql, &postgres);
+
+        assert_eq!(tables("DELETE FROM app.Users WHERE id = 1"), Some(vec!["users".to_string()]));
+        assert_eq!(
+            tables("INSERT INTO archive SELECT * FROM orders"),
+            Some(vec!["archive".to_string(), "orders".to_string()])
+        );
+        assert_eq!(tables("UPDATE payments SET a = 1"), Some(vec!["payments".to_string()]));
+        assert_eq!(tables("DROP TABLE a, b"), Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(tables("TRUNCATE TABLE logs"), Some(vec!["logs".to_string()]));
+        assert_eq!(tables("SELECT 1; SELECT 2"), None);
+    }
+
+    #[test]
+    fn test_keyword_fallback() {
+        assert_eq!(classify_by_keyword("delete from users"), StatementKind::Delete { filtered: false });
+        assert_eq!(classify_by_keyword("update t set a = 1 where b"), StatementKind::Update { filtered: true });
+        assert_eq!(classify_by_keyword("VACUUM"), StatementKind::Other);
+        assert!(classify_by_keyword("CREATE WEIRD THING").is_ddl());
+        assert_eq!(classify_by_keyword("drop weird thing"), StatementKind::Ddl { destructive: true });
+        assert_eq!(
+            classify_by_keyword("with d as (delete from t where (a) > 1) weird select"),
+            StatementKind::Delete { filtered: true }
+        );
+        assert_eq!(classify_by_keyword("with l as (select 1 for update) weird"), StatementKind::Query);
+    }
+}
@@ -0,0 +1,132 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::database::adapter::{RelationSize, StorageKind};
+
+/// Node of the disk usage hierarchy: database, schemas, tables, then the
+/// indexes and TOAST table of each table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageNode {
+    pub name: String,
+    pub kind: StorageKind,
+    /// Bytes used by the node and everything below it; a table's own rows take
+    /// what its children leave
+    pub size: i64,
+    /// Largest first
+    pub children: Vec<StorageNode>,
+}
+
+impl StorageNode {
+    fn new(name: &str, kind: StorageKind, children: Vec<StorageNode>) -> Self {
+        let mut node = Self {
+            name: name.to_string(),
+            kind,
+            size: children.iter().map(|child| child.size).sum(),
+            children,
+        };
+        node.children.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+        node
+    }
+
+    fn leaf(name: &str, kind: StorageKind, size: i64) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            size,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Arrange the sizes of `database`'s relations into a tree
+///
+/// Databases without schemas, like MySQL and SQLite, put tables right under
+/// the database.
+pub fn storage_tree(database: &str, relations: Vec<RelationSize>) -> StorageNode {
+    let mut schemas: BTreeMap<Option<String>, BTreeMap<String, Vec<RelationSize>>> = BTreeMap::new();
+    for relation in relations {
+        schemas
+            .entry(relation.schema.clone())
+            .or_default()
+            .entry(relation.table.clone())
+            .or_default()
+            .push(relation);
+    }
+
+    let table_node = |table: &str, parts: Vec<RelationSize>| {
+        let own: i64 = parts.iter().filter(|p| p.kind == StorageKind::Table).map(|p| p.size).sum();
+        let children: Vec<StorageNode> = parts
+            .iter()
+            .filter(|p| p.kind != StorageKind::Table)
+            .map(|p| StorageNode::leaf(&p.name, p.kind, p.size))
+            .collect();
+        let mut node = StorageNode::new(table, StorageKind::Table, children);
+        node.size += own;
+        node
+    };
+
+    let mut children = Vec::new();
+    for (schema, tables) in schemas {
+        let tables: Vec<StorageNode> = tables.into_iter().map(|(table, parts)| table_node(&table, parts)).collect();
+        match schema {
+            Some(schema) => children.push(StorageNode::new(&schema, StorageKind::Schema, tables)),
+            None => children.extend(tables),
+        }
+    }
+    StorageNode::new(database, StorageKind::Database, children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(schema: Option<&str>, table: &str, kind: StorageKind, name: &str, size: i64) -> RelationSize {
+        RelationSize {
+            schema: schema.map(str::to_string),
+            table: table.to_string(),
+            kind,
+            name: name.to_string(),
+            size,
+        }
+    }
+
+    #[test]
+    fn test_storage_tree() {
+        let public = Some("public");
+        let tree = storage_tree(
+            "shop",
+            vec![
+                relation(public, "orders", StorageKind::Table, "orders", 800),
+                relation(public, "orders", StorageKind::Index, "orders_pkey", 100),
+                relation(public, "orders", StorageKind::Toast, "pg_toast.pg_toast_16384", 50),
+                relation(public, "users", StorageKind::Table, "users", 2000),
+                relation(Some("audit"), "events", StorageKind::Table, "events", 10),
+            ],
+        );
+
+        assert_eq!(tree.size, 2960);
+        assert_eq!(tree.children.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["public", "audit"]);
+
+        let public = &tree.children[0];
+        assert_eq!(public.size, 2950);
+        assert_eq!(public.children[0].name, "users");
+        let orders = &public.children[1];
+        assert_eq!((orders.size, orders.children.len()), (950, 2));
+        assert_eq!(orders.children[0].kind, StorageKind::Index);
+    }
+
+    #[test]
+    fn test_tree_without_schemas() {
+        let tree = storage_tree(
+            "main",
+            vec![
+                relation(None, "notes", StorageKind::Table, "notes", 4096),
+                relation(None, "notes", StorageKind::Index, "idx_notes_title", 4096),
+            ],
+        );
+
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].kind, StorageKind::Table);
+        assert_eq!(tree.children[0].size, 8192);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::database::dialect::SqlDialect;
+
+/// An object a failed statement referred to that the database does not know
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnknownObject {
+    Table(String),
+    Column(String),
+}
+
+impl UnknownObject {
+    /// Recognize "table/column does not exist" errors of PostgreSQL, MySQL and SQLite
+    pub fn from_message(message: &str) -> Option<Self> {
+        // PostgreSQL: relation "usr" does not exist / column "nme" does not exist
+        //             column u.nme does not exist
+        if let Some(rest) = message.strip_prefix("relation ") {
+            let name = rest.strip_suffix(" does not exist")?;
+            return Some(Self::Table(unqualified(name)));
+        }
+        if let Some(rest) = message.strip_prefix("column ") {
+            let name = rest.strip_suffix(" does not exist")?;
+            // column "nme" of relation "users" does not exist
+            let name = name.split(" of relation ").next().unwrap_or(name);
+            return Some(Self::Column(unqualified(name)));
+        }
+
+        // MySQL: Table 'app.usr' doesn't exist / Unknown column 'nme' in 'field list'
+        if let Some(rest) = message.strip_prefix("Table '") {
+            let name = rest.strip_suffix("' doesn't exist")?;
+            return Some(Self::Table(unqualified(name)));
+        }
+        if let Some(rest) = message.strip_prefix("Unknown column '") {
+            let name = &rest[..rest.find('\'')?];
+            return Some(Self::Column(unqualified(name)));
+        }
+
+        // SQLite: no such table: usr / no such column: u.nme
+        if let Some(name) = message.strip_prefix("no such table: ") {
+            return Some(Self::Table(unqualified(name)));
+        }
+        if let Some(name) = message.strip_prefix("no such column: ") {
+            return Some(Self::Column(unqualified(name)));
+        }
+
+        None
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Table(name) | Self::Column(name) => name,
+        }
+    }
+}
+
+/// Name without schema or table qualifier and quotes
+fn unqualified(name: &str) -> String {
+    let name = name.trim().trim_matches(|c| c == '"' || c == '`');
+    name.rsplit('.')
+        .next()
+        .unwrap_or(name)
+        .trim_matches(|c| c == '"' || c == '`')
+        .to_string()
+}
+
+/// Edit distance between two names, ignoring case
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Up to `limit` candidates close to `name`, closest first
+///
+/// A candidate is close when at most a third of the characters of `name` (and at
+/// least one) must change to turn it into the candidate. Candidates the database
+/// considers the same name as `name` are left out.
+pub fn similar_names<'a>(
+    dialect: &dyn SqlDialect,
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<String> {
+    let max_distance = name.chars().count().max(3) / 3;
+
+    let mut matches: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter(|candidate| !dialect.identifiers_equal(candidate, name))
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    matches.sort();
+    matches.dedup_by(|a, b| a.1 == b.1);
+
+    matches.into_iter().take(limit).map(|(_, candidate)| candidate.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{PostgreSQLDialect, SQLiteDialect};
+
+    #[test]
+    fn test_unknown_object_from_message() {
+        assert_eq!(
+            UnknownObject::from_message("relation \"public.usr\" does not exist"),
+            Some(UnknownObject::Table("usr".to_string()))
+        );
+        assert_eq!(
+            UnknownObject::from_message("column u.nme does not exist"),
+            Some(UnknownObject::Column("nme".to_string()))
+        );
+        assert_eq!(
+            UnknownObject::from_message("column \"nme\" of relation \"users\" does not exist"),
+            Some(UnknownObject::Column("nme".to_string()))
+        );
+        assert_eq!(
+            UnknownObject::from_message("Table 'app.usr' doesn't exist"),
+            Some(UnknownObject::Table("usr".to_string()))
+        );
+        assert_eq!(
+            UnknownObject::from_message("Unknown column 'nme' in 'field list'"),
+            Some(UnknownObject::Column("nme".to_string()))
+        );
+        assert_eq!(
+            UnknownObject::from_message("no such column: nme"),
+            Some(UnknownObject::Column("nme".to_string()))
+        );
+        assert_eq!(UnknownObject::from_message("syntax error at or near \"FORM\""), None);
+    }
+
+    #[test]
+    fn test_similar_names() {
+        let sqlite = SQLiteDialect::new();
+        let tables = ["users", "user_roles", "orders", "Usage"];
+        assert_eq!(similar_names(&sqlite, "user", tables, 3), vec!["users"]);
+        assert_eq!(similar_names(&sqlite, "USERS_", tables, 3), vec!["users"]);
+        assert_eq!(similar_names(&sqlite, "ordrs", tables, 3), vec!["orders"]);
+        assert!(similar_names(&sqlite, "invoices", tables, 3).is_empty());
+        assert!(similar_names(&sqlite, "Users", tables, 3).is_empty());
+
+        // A quoted "Users" is a different table from users in PostgreSQL
+        assert_eq!(similar_names(&PostgreSQLDialect::new(), "Users", tables, 3), vec!["users"]);
+    }
+}
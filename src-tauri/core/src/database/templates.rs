@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
 use super::adapter::DatabaseType;
+use super::capabilities::DatabaseCapabilities;
+use super::dialect::SqlDialect;
 
 /// Query template category
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TemplateCategory {
     Table,
     Index,
@@ -22,6 +26,33 @@ pub struct QueryTemplate {
     pub template: String,
     pub parameters: Vec<TemplateParameter>,
     pub supported_databases: Vec<DatabaseType>,
+    /// Features the server must have for the template to be offered
+    #[serde(default)]
+    pub requires: Vec<TemplateRequirement>,
+}
+
+/// Feature of the connected server a template depends on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateRequirement {
+    MaterializedViews,
+    FullTextSearch,
+    StoredProcedures,
+    Triggers,
+    /// An installed extension, such as `pg_trgm`
+    Extension(String),
+}
+
+impl TemplateRequirement {
+    pub fn is_met(&self, capabilities: &DatabaseCapabilities) -> bool {
+        match self {
+            Self::MaterializedViews => capabilities.materialized_views,
+            Self::FullTextSearch => capabilities.full_text_search,
+            Self::StoredProcedures => capabilities.stored_procedures,
+            Self::Triggers => capabilities.triggers,
+            Self::Extension(name) => capabilities.extensions.iter().any(|e| e == name),
+        }
+    }
 }
 
 /// Template parameter
@@ -31,14 +62,241 @@ pub struct TemplateParameter {
     pub description: String,
     pub default_value: Option<String>,
     pub required: bool,
+    #[serde(default)]
+    pub kind: ParameterKind,
+    /// Values offered when filling in the parameter
+    #[serde(default)]
+    pub choices: Vec<String>,
+}
+
+/// How the value of a parameter is written into the SQL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterKind {
+    /// Name of a database object, quoted by the dialect; dots separate the
+    /// schema from the name
+    Identifier,
+    /// Value quoted as a string literal
+    Literal,
+    /// SQL inserted as written, such as a column list or a condition
+    #[default]
+    Sql,
+}
+
+/// SQL of a template with its parameters substituted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenderedTemplate {
+    pub sql: String,
+    /// Parameters left without a value; their placeholders remain in `sql`
+    pub unresolved: Vec<String>,
+}
+
+/// Editor tab stop for a placeholder of a template or snippet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TabStop {
+    /// Order in which the editor visits the tab stops, from 1
+    pub index: usize,
+    pub name: String,
+    /// Text filled in before the user edits it: the default, the first choice
+    /// or the name of the parameter
+    pub placeholder: String,
+    pub choices: Vec<String>,
+    /// Where each occurrence of the placeholder ends up in the expanded text,
+    /// as offsets in UTF-16 code units like JavaScript strings
+    pub ranges: Vec<(usize, usize)>,
+}
+
+/// Text of a template or snippet ready for snippet expansion in the editor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnippetExpansion {
+    /// The text with every placeholder replaced by its tab stop's placeholder
+    pub text: String,
+    /// Tab stops in order of first appearance; repeated placeholders share one
+    pub tab_stops: Vec<TabStop>,
+}
+
+impl QueryTemplate {
+    /// Substitute the `{{name}}` placeholders of the template
+    ///
+    /// A parameter takes its value from `values`, falling back to its default.
+    /// Optional parameters without either are left empty; required ones, and
+    /// placeholders the template does not declare, are reported as unresolved.
+    pub fn render(&self, values: &HashMap<String, String>, dialect: &dyn SqlDialect) -> RenderedTemplate {
+        let mut sql = String::with_capacity(self.template.len());
+        let mut unresolved: Vec<String> = Vec::new();
+        let mut copied = 0;
+
+        for (range, name) in placeholders(&self.template) {
+            sql.push_str(&self.template[copied..range.start]);
+            copied = range.end;
+
+            let parameter = self.parameters.iter().find(|p| p.name == name);
+            let value = values
+                .get(name)
+                .filter(|v| !v.trim().is_empty())
+                .or_else(|| parameter.and_then(|p| p.default_value.as_ref()));
+
+            match (value, parameter) {
+                (Some(value), Some(parameter)) => sql.push_str(&render_value(value, parameter.kind, dialect)),
+                (Some(value), None) => sql.push_str(value),
+                (None, Some(parameter)) if !parameter.required => {}
+                (None, _) => {
+                    if !unresolved.iter().any(|u| u == name) {
+                        unresolved.push(name.to_string());
+                    }
+                    sql.push_str(&self.template[range]);
+                }
+            }
+        }
+        sql.push_str(&self.template[copied..]);
+
+        RenderedTemplate { sql, unresolved }
+    }
+
+    /// Whether the server described by `capabilities` can run the template
+    pub fn is_available(&self, capabilities: &DatabaseCapabilities) -> bool {
+        self.requires.iter().all(|r| r.is_met(capabilities))
+    }
+
+    pub fn expansion(&self) -> SnippetExpansion {
+        expand(&self.template, &self.parameters)
+    }
+}
+
+/// Templates matching every word of `query`, best matches first
+///
+/// A word matching the template's name counts most, then its ID, category,
+/// description and parameters, and least its SQL. An empty query keeps every
+/// template, sorted by name.
+pub fn search(templates: Vec<QueryTemplate>, query: &str, category: Option<TemplateCategory>) -> Vec<QueryTemplate> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let mut ranked: Vec<(u32, QueryTemplate)> = templates
+        .into_iter()
+        .filter(|t| category.is_none_or(|c| t.category == c))
+        .filter_map(|t| {
+            let score = words
+                .iter()
+                .map(|word| word_score(&t, word))
+                .try_fold(0, |total, score| (score > 0).then_some(total + score))?;
+            Some((score, t))
+        })
+        .collect();
+
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        b_score.cmp(a_score).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    ranked.into_iter().map(|(_, t)| t).collect()
+}
+
+/// How well one lowercase word matches a template; 0 when it does not
+fn word_score(template: &QueryTemplate, word: &str) -> u32 {
+    let name = template.name.to_lowercase();
+    let name_score = if name == *word {
+        100
+    } else if name.split(|c: char| !c.is_alphanumeric()).any(|w| w.starts_with(word)) {
+        50
+    } else if name.contains(word) {
+        30
+    } else {
+        0
+    };
+
+    let contains = |text: &str| text.to_lowercase().contains(word);
+    let other_score = [
+        (contains(&template.id), 20),
+        (contains(&format!("{:?}", template.category)), 15),
+        (contains(&template.description), 10),
+        (template.parameters.iter().any(|p| contains(&p.name) || contains(&p.description)), 5),
+        (contains(&template.template), 2),
+    ]
+    .into_iter()
+    .filter_map(|(matched, score)| matched.then_some(score))
+    .max()
+    .unwrap_or(0);
+
+    name_score + other_score
+}
+
+/// Tab stops for the `{{name}}` placeholders of `text`, described by the
+/// matching entries of `parameters` where there are any
+pub fn expand(text: &str, parameters: &[TemplateParameter]) -> SnippetExpansion {
+    let mut expanded = String::with_capacity(text.len());
+    let mut tab_stops: Vec<TabStop> = Vec::new();
+    let mut copied = 0;
+
+    for (range, name) in placeholders(text) {
+        expanded.push_str(&text[copied..range.start]);
+        copied = range.end;
+
+        let position = match tab_stops.iter().position(|t| t.name == name) {
+            Some(position) => position,
+            None => {
+                let parameter = parameters.iter().find(|p| p.name == name);
+                let choices = parameter.map(|p| p.choices.clone()).unwrap_or_default();
+                let placeholder = parameter
+                    .and_then(|p| p.default_value.clone())
+                    .or_else(|| choices.first().cloned())
+                    .unwrap_or_else(|| name.to_string());
+                tab_stops.push(TabStop {
+                    index: tab_stops.len() + 1,
+                    name: name.to_string(),
+                    placeholder,
+                    choices,
+                    ranges: Vec::new(),
+                });
+                tab_stops.len() - 1
+            }
+        };
+
+        let tab_stop = &mut tab_stops[position];
+        let start = expanded.encode_utf16().count();
+        expanded.push_str(&tab_stop.placeholder);
+        tab_stop.ranges.push((start, start + tab_stop.placeholder.encode_utf16().count()));
+    }
+    expanded.push_str(&text[copied..]);
+
+    SnippetExpansion { text: expanded, tab_stops }
+}
+
+/// Byte ranges and names of the `{{name}}` placeholders in `text`; braces
+/// around anything but a name are left alone
+pub fn placeholders(text: &str) -> Vec<(Range<usize>, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find("{{").map(|i| offset + i) {
+        let Some(end) = text[start + 2..].find("}}").map(|i| start + 2 + i) else {
+            break;
+        };
+        let name = text[start + 2..end].trim();
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            found.push((start..end + 2, name));
+        }
+        offset = end + 2;
+    }
+    found
+}
+
+/// A parameter value as it appears in the SQL
+fn render_value(value: &str, kind: ParameterKind, dialect: &dyn SqlDialect) -> String {
+    match kind {
+        ParameterKind::Identifier => value
+            .split('.')
+            .map(|part| dialect.quote_identifier(part.trim()))
+            .collect::<Vec<_>>()
+            .join("."),
+        ParameterKind::Literal => dialect.string_literal(value),
+        ParameterKind::Sql => value.to_string(),
+    }
 }
 
 /// Database-specific query templates
 pub struct QueryTemplates;
 
 impl QueryTemplates {
-    /// Get all templates for a specific database type
-    pub fn for_database(db_type: DatabaseType) -> Vec<QueryTemplate> {
+    /// Get the templates for a specific database type that the server
+    /// described by `capabilities` can run
+    pub fn for_database(db_type: DatabaseType, capabilities: &DatabaseCapabilities) -> Vec<QueryTemplate> {
         let mut templates = Vec::new();
         
         // Add common templates
@@ -49,8 +307,10 @@ impl QueryTemplates {
             DatabaseType::PostgreSQL => templates.extend(Self::postgres_templates()),
             DatabaseType::MySQL => templates.extend(Self::mysql_templates()),
             DatabaseType::SQLite => templates.extend(Self::sqlite_templates()),
+            DatabaseType::Other(_) => {}
         }
         
+        templates.retain(|t| t.is_available(capabilities));
         templates
     }
     
@@ -83,6 +343,13 @@ impl QueryTemplates {
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);"#.to_string()
+                    },
+                    DatabaseType::Other(_) => {
+                        r#"CREATE TABLE {{table_name}} (
+    id INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
 );"#.to_string()
                     },
                 },
@@ -92,6 +359,8 @@ impl QueryTemplates {
                         description: "Name of the table".to_string(),
                         default_value: Some("new_table".to_string()),
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     if db_type == DatabaseType::PostgreSQL {
                         TemplateParameter {
@@ -99,6 +368,8 @@ impl QueryTemplates {
                             description: "Schema name".to_string(),
                             default_value: Some("public".to_string()),
                             required: true,
+                            kind: ParameterKind::Identifier,
+                            choices: Vec::new(),
                         }
                     } else if db_type == DatabaseType::MySQL {
                         TemplateParameter {
@@ -106,6 +377,8 @@ impl QueryTemplates {
                             description: "Database name".to_string(),
                             default_value: None,
                             required: true,
+                            kind: ParameterKind::Identifier,
+                            choices: Vec::new(),
                         }
                     } else {
                         TemplateParameter {
@@ -113,10 +386,13 @@ impl QueryTemplates {
                             description: "".to_string(),
                             default_value: None,
                             required: false,
+                            kind: ParameterKind::Sql,
+                            choices: Vec::new(),
                         }
                     },
                 ].into_iter().filter(|p| !p.name.is_empty()).collect(),
                 supported_databases: vec![db_type],
+                requires: Vec::new(),
             },
             
             // CREATE INDEX
@@ -131,7 +407,7 @@ impl QueryTemplates {
 ON {{schema}}.{{table_name}} ({{columns}})
 WHERE {{condition}};"#.to_string()
                     },
-                    DatabaseType::MySQL => {
+                    DatabaseType::MySQL | DatabaseType::Other(_) => {
                         r#"CREATE INDEX {{index_name}} 
 ON {{table_name}} ({{columns}});"#.to_string()
                     },
@@ -147,21 +423,28 @@ WHERE {{condition}};"#.to_string()
                         description: "Name of the index".to_string(),
                         default_value: Some("idx_table_column".to_string()),
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "table_name".to_string(),
                         description: "Table to index".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "columns".to_string(),
                         description: "Columns to index".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Sql,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![db_type],
+                requires: Vec::new(),
             },
             
             // ADD FOREIGN KEY
@@ -179,7 +462,7 @@ REFERENCES {{ref_table}}({{ref_column}})
 ON DELETE CASCADE
 ON UPDATE CASCADE;"#.to_string()
                     },
-                    DatabaseType::MySQL => {
+                    DatabaseType::MySQL | DatabaseType::Other(_) => {
                         r#"ALTER TABLE {{table_name}}
 ADD CONSTRAINT {{constraint_name}}
 FOREIGN KEY ({{column}})
@@ -199,15 +482,20 @@ ON UPDATE CASCADE;"#.to_string()
                         description: "Table to add constraint to".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "constraint_name".to_string(),
                         description: "Name of the constraint".to_string(),
                         default_value: Some("fk_table_ref".to_string()),
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![db_type],
+                requires: Vec::new(),
             },
         ]
     }
@@ -229,15 +517,20 @@ AUTHORIZATION {{owner}};"#.to_string(),
                         description: "Name of the schema".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "owner".to_string(),
                         description: "Schema owner".to_string(),
                         default_value: Some("CURRENT_USER".to_string()),
                         required: false,
+                        kind: ParameterKind::Sql,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::PostgreSQL],
+                requires: Vec::new(),
             },
             
             // CREATE MATERIALIZED VIEW
@@ -255,15 +548,20 @@ WITH DATA;"#.to_string(),
                         description: "Name of the materialized view".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "query".to_string(),
                         description: "Query to materialize".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Sql,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::PostgreSQL],
+                requires: vec![TemplateRequirement::MaterializedViews],
             },
             
             // CREATE FUNCTION
@@ -285,9 +583,12 @@ $$ LANGUAGE plpgsql;"#.to_string(),
                         description: "Name of the function".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::PostgreSQL],
+                requires: vec![TemplateRequirement::StoredProcedures],
             },
             
             // UPSERT (INSERT ON CONFLICT)
@@ -307,9 +608,12 @@ DO UPDATE SET
                         description: "Table name".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::PostgreSQL],
+                requires: Vec::new(),
             },
         ]
     }
@@ -336,9 +640,12 @@ DELIMITER ;"#.to_string(),
                         description: "Name of the procedure".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::MySQL],
+                requires: vec![TemplateRequirement::StoredProcedures],
             },
             
             // CREATE TRIGGER
@@ -361,21 +668,28 @@ END;"#.to_string(),
                         description: "Name of the trigger".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "timing".to_string(),
                         description: "BEFORE or AFTER".to_string(),
                         default_value: Some("BEFORE".to_string()),
                         required: true,
+                        kind: ParameterKind::Sql,
+                        choices: vec!["BEFORE".to_string(), "AFTER".to_string()],
                     },
                     TemplateParameter {
                         name: "event".to_string(),
                         description: "INSERT, UPDATE, or DELETE".to_string(),
                         default_value: Some("INSERT".to_string()),
                         required: true,
+                        kind: ParameterKind::Sql,
+                        choices: vec!["INSERT".to_string(), "UPDATE".to_string(), "DELETE".to_string()],
                     },
                 ],
                 supported_databases: vec![DatabaseType::MySQL],
+                requires: vec![TemplateRequirement::Triggers],
             },
             
             // PARTITION TABLE
@@ -401,9 +715,12 @@ PARTITION BY RANGE (YEAR({{date_column}})) (
                         description: "Name of the table".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::MySQL],
+                requires: Vec::new(),
             },
         ]
     }
@@ -425,15 +742,20 @@ USING fts5({{columns}});"#.to_string(),
                         description: "Name of the FTS table".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "columns".to_string(),
                         description: "Columns for FTS".to_string(),
                         default_value: Some("title, content".to_string()),
                         required: true,
+                        kind: ParameterKind::Sql,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::SQLite],
+                requires: vec![TemplateRequirement::FullTextSearch],
             },
             
             // PRAGMA SETTINGS
@@ -458,6 +780,7 @@ PRAGMA cache_size = -20000;  -- 20MB
 PRAGMA busy_timeout = 5000;"#.to_string(),
                 parameters: vec![],
                 supported_databases: vec![DatabaseType::SQLite],
+                requires: Vec::new(),
             },
             
             // CREATE TRIGGER FOR UPDATED_AT
@@ -480,15 +803,20 @@ END;"#.to_string(),
                         description: "Name of the trigger".to_string(),
                         default_value: Some("update_timestamp".to_string()),
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                     TemplateParameter {
                         name: "table_name".to_string(),
                         description: "Table to add trigger to".to_string(),
                         default_value: None,
                         required: true,
+                        kind: ParameterKind::Identifier,
+                        choices: Vec::new(),
                     },
                 ],
                 supported_databases: vec![DatabaseType::SQLite],
+                requires: vec![TemplateRequirement::Triggers],
             },
         ]
     }
@@ -499,6 +827,8 @@ END;"#.to_string(),
             DatabaseType::PostgreSQL => Self::postgres_data_types(),
             DatabaseType::MySQL => Self::mysql_data_types(),
             DatabaseType::SQLite => Self::sqlite_data_types(),
+            // Nothing is known about the types of a registered driver's database
+            DatabaseType::Other(_) => Vec::new(),
         }
     }
     
@@ -632,4 +962,143 @@ impl DataTypeInfo {
             range: range.to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect};
+
+    fn builtin(db_type: DatabaseType, id: &str) -> QueryTemplate {
+        QueryTemplates::for_database(db_type, &DatabaseCapabilities::for_server(db_type, None))
+            .into_iter()
+            .find(|t| t.id == id)
+            .unwrap()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_render_quotes_by_kind() {
+        let template = builtin(DatabaseType::PostgreSQL, "create_table");
+        let rendered = template.render(&values(&[("table_name", "order\"items")]), &PostgreSQLDialect::new());
+
+        assert!(rendered.unresolved.is_empty());
+        assert!(rendered.sql.starts_with("CREATE TABLE \"public\".\"order\"\"items\" ("));
+
+        let lookup = QueryTemplate {
+            id: "lookup".to_string(),
+            name: "Lookup".to_string(),
+            category: TemplateCategory::Query,
+            description: String::new(),
+            template: "SELECT {{columns}} FROM {{table}} WHERE name = {{name}}".to_string(),
+            parameters: vec![
+                TemplateParameter {
+                    name: "table".to_string(),
+                    description: String::new(),
+                    default_value: None,
+                    required: true,
+                    kind: ParameterKind::Identifier,
+                    choices: Vec::new(),
+                },
+                TemplateParameter {
+                    name: "name".to_string(),
+                    description: String::new(),
+                    default_value: None,
+                    required: true,
+                    kind: ParameterKind::Literal,
+                    choices: Vec::new(),
+                },
+            ],
+            supported_databases: vec![DatabaseType::MySQL],
+            requires: Vec::new(),
+        };
+        let rendered = lookup.render(
+            &values(&[("columns", "id, name"), ("table", "shop.users"), ("name", "O'Brien")]),
+            &MySQLDialect::new(),
+        );
+        assert_eq!(rendered.sql, "SELECT id, name FROM `shop`.`users` WHERE name = 'O''Brien'");
+    }
+
+    #[test]
+    fn test_render_unresolved() {
+        let pg = PostgreSQLDialect::new();
+        let index = builtin(DatabaseType::PostgreSQL, "create_index");
+        let rendered = index.render(&values(&[("table_name", "users"), ("columns", " ")]), &pg);
+
+        // Blank values count as missing; undeclared placeholders need a value too
+        assert_eq!(rendered.unresolved, vec!["schema", "columns", "condition"]);
+        assert!(rendered.sql.contains("({{columns}})"));
+
+        // Optional parameters without a value are left out
+        let mut schema = builtin(DatabaseType::PostgreSQL, "pg_create_schema");
+        schema.parameters[1].default_value = None;
+        let rendered = schema.render(&values(&[("schema_name", "sales")]), &pg);
+        assert_eq!(rendered.sql, "CREATE SCHEMA IF NOT EXISTS \"sales\"\nAUTHORIZATION ;");
+    }
+
+    #[test]
+    fn test_expansion() {
+        let trigger = builtin(DatabaseType::MySQL, "mysql_create_trigger");
+        let expansion = trigger.expansion();
+        assert!(expansion.text.starts_with("CREATE TRIGGER trigger_name\nBEFORE INSERT ON table_name"));
+
+        let names: Vec<&str> = expansion.tab_stops.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["trigger_name", "timing", "event", "table_name"]);
+        assert_eq!(expansion.tab_stops[1].index, 2);
+        assert_eq!(expansion.tab_stops[1].choices, vec!["BEFORE", "AFTER"]);
+        assert_eq!(expansion.tab_stops[1].ranges, vec![(28, 34)]);
+
+        // Repeated placeholders share a tab stop; offsets count UTF-16 code units
+        let expansion = expand("-- 📦 {{t}}\nSELECT * FROM {{t}} {{ not a name }}", &[]);
+        assert_eq!(expansion.text, "-- 📦 t\nSELECT * FROM t {{ not a name }}");
+        assert_eq!(expansion.tab_stops.len(), 1);
+        assert_eq!(expansion.tab_stops[0].ranges, vec![(6, 7), (22, 23)]);
+    }
+
+    #[test]
+    fn test_filter_by_capabilities() {
+        use crate::database::capabilities::ServerVersion;
+
+        let ids = |db_type, capabilities: &DatabaseCapabilities| -> Vec<String> {
+            QueryTemplates::for_database(db_type, capabilities).into_iter().map(|t| t.id).collect()
+        };
+
+        let pg92 = DatabaseCapabilities::for_server(DatabaseType::PostgreSQL, ServerVersion::parse("9.2.24"));
+        assert!(!ids(DatabaseType::PostgreSQL, &pg92).contains(&"pg_create_mat_view".to_string()));
+
+        let mut sqlite = DatabaseCapabilities::for_server(DatabaseType::SQLite, None);
+        assert!(ids(DatabaseType::SQLite, &sqlite).contains(&"sqlite_create_fts".to_string()));
+        sqlite.full_text_search = false;
+        assert!(!ids(DatabaseType::SQLite, &sqlite).contains(&"sqlite_create_fts".to_string()));
+
+        let mut pg = DatabaseCapabilities::for_server(DatabaseType::PostgreSQL, None);
+        let trigram = TemplateRequirement::Extension("pg_trgm".to_string());
+        assert!(!trigram.is_met(&pg));
+        pg.extensions.push("pg_trgm".to_string());
+        assert!(trigram.is_met(&pg));
+    }
+
+    #[test]
+    fn test_search() {
+        let pg = DatabaseCapabilities::for_server(DatabaseType::PostgreSQL, None);
+        let templates = || QueryTemplates::for_database(DatabaseType::PostgreSQL, &pg);
+        let ids = |found: Vec<QueryTemplate>| -> Vec<String> { found.into_iter().map(|t| t.id).collect() };
+
+        // A name match ranks above a match in the SQL only
+        let found = ids(search(templates(), "view", None));
+        assert_eq!(found[0], "pg_create_mat_view");
+
+        // Every word has to match, in the name or elsewhere
+        let found = ids(search(templates(), "create schema", None));
+        assert_eq!(found[0], "pg_create_schema");
+        assert!(found.contains(&"create_table".to_string()));
+        assert!(!found.contains(&"pg_create_function".to_string()));
+        assert!(search(templates(), "create nothing", None).is_empty());
+
+        let admin = search(templates(), "", Some(TemplateCategory::Admin));
+        assert_eq!(ids(admin), vec!["pg_create_function", "pg_create_schema"]);
+    }
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::adapter::DatabaseType;
+
+/// Database-independent column type
+///
+/// Types of one database are normalized to this model by `TypeMapper::to_abstract`
+/// and rendered as the closest type of another by `TypeMapper::to_native`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AbstractType {
+    SmallInt,
+    Integer,
+    BigInt,
+    Double,
+    Decimal { precision: Option<u32>, scale: Option<u32> },
+    Boolean,
+    Varchar { length: Option<u32> },
+    Char { length: Option<u32> },
+    Text,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Json,
+    Uuid,
+    Binary,
+}
+
+/// Converts between the column types of one database and `AbstractType`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeMapper {
+    database_type: DatabaseType,
+}
+
+impl TypeMapper {
+    pub fn new(database_type: DatabaseType) -> Self {
+        Self { database_type }
+    }
+
+    /// Classify a type name as reported by this database; names it does not
+    /// know become `Text`
+    pub fn to_abstract(self, data_type: &str) -> AbstractType {
+        let lowered = data_type.trim().to_lowercase();
+        let (base, args) = match lowered.split_once('(') {
+            Some((base, rest)) => (base.trim(), rest.split(')').next().map(str::trim)),
+            None => (lowered.as_str(), None),
+        };
+        let base = base.trim_end_matches(" unsigned").trim();
+        let numbers: Vec<u32> = args
+            .map(|a| a.split(',').filter_map(|n| n.trim().parse().ok()).collect())
+            .unwrap_or_default();
+        let length = numbers.first().copied();
+
+        match base {
+            // MySQL has no boolean type; BOOLEAN columns are TINYINT(1)
+            "tinyint" if self.database_type == DatabaseType::MySQL && length == Some(1) => AbstractType::Boolean,
+            "smallint" | "int2" | "tinyint" => AbstractType::SmallInt,
+            "integer" | "int" | "int4" | "mediumint" | "serial" => AbstractType::Integer,
+            "bigint" | "int8" | "bigserial" => AbstractType::BigInt,
+            "real" | "float" | "float4" | "float8" | "double" | "double precision" => AbstractType::Double,
+            "numeric" | "decimal" => AbstractType::Decimal {
+                precision: length,
+                scale: numbers.get(1).copied(),
+            },
+            "boolean" | "bool" => AbstractType::Boolean,
+            "character varying" | "varchar" | "nvarchar" => AbstractType::Varchar { length },
+            "character" | "char" | "bpchar" | "nchar" => AbstractType::Char { length },
+            "text" | "tinytext" | "mediumtext" | "longtext" | "clob" => AbstractType::Text,
+            "date" => AbstractType::Date,
+            "time" | "time without time zone" => AbstractType::Time,
+            "timestamp" | "timestamp without time zone" | "datetime" => AbstractType::Timestamp,
+            "timestamp with time zone" | "timestamptz" => AbstractType::TimestampTz,
+            "json" | "jsonb" => AbstractType::Json,
+            "uuid" => AbstractType::Uuid,
+            "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary" => {
+                AbstractType::Binary
+            }
+            _ if self.database_type == DatabaseType::SQLite => sqlite_affinity(base),
+            _ => AbstractType::Text,
+        }
+    }
+
+    /// Render an abstract type as the closest column type of this database
+    pub fn to_native(self, abstract_type: &AbstractType) -> String {
+        let sized = |name: &str, length: &Option<u32>| match length {
+            Some(length) => format!("{}({})", name, length),
+            None => name.to_string(),
+        };
+        let decimal = |name: &str, precision: &Option<u32>, scale: &Option<u32>| match (precision, scale) {
+            (Some(precision), Some(scale)) => format!("{}({}, {})", name, precision, scale),
+            (Some(precision), None) => format!("{}({})", name, precision),
+            _ => name.to_string(),
+        };
+
+        match self.database_type {
+            DatabaseType::PostgreSQL => match abstract_type {
+                AbstractType::SmallInt => "SMALLINT".to_string(),
+                AbstractType::Integer => "INTEGER".to_string(),
+                AbstractType::BigInt => "BIGINT".to_string(),
+                AbstractType::Double => "DOUBLE PRECISION".to_string(),
+                AbstractType::Decimal { precision, scale } => decimal("NUMERIC", precision, scale),
+                AbstractType::Boolean => "BOOLEAN".to_string(),
+                AbstractType::Varchar { length } => sized("VARCHAR", length),
+                AbstractType::Char { length } => sized("CHAR", length),
+                AbstractType::Text => "TEXT".to_string(),
+                AbstractType::Date => "DATE".to_string(),
+                AbstractType::Time => "TIME".to_string(),
+                AbstractType::Timestamp => "TIMESTAMP".to_string(),
+                AbstractType::TimestampTz => "TIMESTAMPTZ".to_string(),
+                AbstractType::Json => "JSONB".to_string(),
+                AbstractType::Uuid => "UUID".to_string(),
+                AbstractType::Binary => "BYTEA".to_string(),
+            },
+            DatabaseType::MySQL => match abstract_type {
+                AbstractType::SmallInt => "SMALLINT".to_string(),
+                AbstractType::Integer => "INT".to_string(),
+                AbstractType::BigInt => "BIGINT".to_string(),
+                AbstractType::Double => "DOUBLE".to_string(),
+                AbstractType::Decimal { precision, scale } => decimal("DECIMAL", precision, scale),
+                AbstractType::Boolean => "BOOLEAN".to_string(),
+                // MySQL requires a length for VARCHAR
+                AbstractType::Varchar { length: Some(length) } => format!("VARCHAR({})", length),
+                AbstractType::Varchar { length: None } | AbstractType::Text => "LONGTEXT".to_string(),
+                AbstractType::Char { length } => sized("CHAR", length),
+                AbstractType::Date => "DATE".to_string(),
+                AbstractType::Time => "TIME".to_string(),
+                AbstractType::Timestamp | AbstractType::TimestampTz => "DATETIME".to_string(),
+                AbstractType::Json => "JSON".to_string(),
+                AbstractType::Uuid => "CHAR(36)".to_string(),
+                AbstractType::Binary => "LONGBLOB".to_string(),
+            },
+            DatabaseType::SQLite => match abstract_type {
+                AbstractType::SmallInt
+                | AbstractType::Integer
+                | AbstractType::BigInt
+                | AbstractType::Boolean => "INTEGER".to_string(),
+                AbstractType::Double => "REAL".to_string(),
+                AbstractType::Decimal { .. } => "NUMERIC".to_string(),
+                AbstractType::Binary => "BLOB".to_string(),
+                _ => "TEXT".to_string(),
+            },
+            // Standard SQL names for databases of registered drivers
+            DatabaseType::Other(_) => match abstract_type {
+                AbstractType::SmallInt => "SMALLINT".to_string(),
+                AbstractType::Integer => "INTEGER".to_string(),
+                AbstractType::BigInt => "BIGINT".to_string(),
+                AbstractType::Double => "DOUBLE PRECISION".to_string(),
+                AbstractType::Decimal { precision, scale } => decimal("DECIMAL", precision, scale),
+                AbstractType::Boolean => "BOOLEAN".to_string(),
+                AbstractType::Varchar { length } => sized("VARCHAR", length),
+                AbstractType::Char { length } => sized("CHAR", length),
+                AbstractType::Text | AbstractType::Json => "CLOB".to_string(),
+                AbstractType::Uuid => "CHAR(36)".to_string(),
+                AbstractType::Date => "DATE".to_string(),
+                AbstractType::Time => "TIME".to_string(),
+                AbstractType::Timestamp => "TIMESTAMP".to_string(),
+                AbstractType::TimestampTz => "TIMESTAMP WITH TIME ZONE".to_string(),
+                AbstractType::Binary => "BLOB".to_string(),
+            },
+        }
+    }
+}
+
+/// Type of an SQLite column by the affinity rules SQLite itself applies to
+/// declared type names it does not know
+fn sqlite_affinity(declared: &str) -> AbstractType {
+    if declared.contains("int") {
+        AbstractType::BigInt
+    } else if ["char", "clob", "text"].iter().any(|s| declared.contains(s)) {
+        AbstractType::Text
+    } else if declared.contains("blob") || declared.is_empty() {
+        AbstractType::Binary
+    } else if ["real", "floa", "doub"].iter().any(|s| declared.contains(s)) {
+        AbstractType::Double
+    } else {
+        AbstractType::Decimal { precision: None, scale: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate_type(data_type: &str, target: DatabaseType) -> String {
+        TypeMapper::new(target).to_native(&TypeMapper::new(DatabaseType::PostgreSQL).to_abstract(data_type))
+    }
+
+    #[test]
+    fn test_postgres_to_sqlite() {
+        assert_eq!(translate_type("integer", DatabaseType::SQLite), "INTEGER");
+        assert_eq!(translate_type("boolean", DatabaseType::SQLite), "INTEGER");
+        assert_eq!(translate_type("double precision", DatabaseType::SQLite), "REAL");
+        assert_eq!(translate_type("character varying", DatabaseType::SQLite), "TEXT");
+        assert_eq!(translate_type("timestamp with time zone", DatabaseType::SQLite), "TEXT");
+        assert_eq!(translate_type("bytea", DatabaseType::SQLite), "BLOB");
+    }
+
+    #[test]
+    fn test_to_mysql() {
+        assert_eq!(translate_type("VARCHAR(255)", DatabaseType::MySQL), "VARCHAR(255)");
+        assert_eq!(translate_type("character varying", DatabaseType::MySQL), "LONGTEXT");
+        assert_eq!(translate_type("numeric(10, 2)", DatabaseType::MySQL), "DECIMAL(10, 2)");
+        assert_eq!(translate_type("uuid", DatabaseType::MySQL), "CHAR(36)");
+        assert_eq!(translate_type("jsonb", DatabaseType::MySQL), "JSON");
+    }
+
+    #[test]
+    fn test_to_postgres() {
+        assert_eq!(translate_type("int unsigned", DatabaseType::PostgreSQL), "INTEGER");
+        assert_eq!(translate_type("datetime", DatabaseType::PostgreSQL), "TIMESTAMP");
+        assert_eq!(translate_type("longtext", DatabaseType::PostgreSQL), "TEXT");
+        assert_eq!(translate_type("", DatabaseType::PostgreSQL), "TEXT");
+    }
+
+    #[test]
+    fn test_database_specific_types() {
+        let mysql = TypeMapper::new(DatabaseType::MySQL);
+        let sqlite = TypeMapper::new(DatabaseType::SQLite);
+        let postgres = TypeMapper::new(DatabaseType::PostgreSQL);
+
+        assert_eq!(mysql.to_abstract("tinyint(1)"), AbstractType::Boolean);
+        assert_eq!(mysql.to_abstract("tinyint(4)"), AbstractType::SmallInt);
+        assert_eq!(postgres.to_native(&mysql.to_abstract("tinyint(1)")), "BOOLEAN");
+
+        assert_eq!(sqlite.to_abstract("UNSIGNED BIG INT"), AbstractType::BigInt);
+        assert_eq!(sqlite.to_abstract("NATIVE CHARACTER(70)"), AbstractType::Text);
+        assert_eq!(sqlite.to_abstract(""), AbstractType::Binary);
+        assert_eq!(postgres.to_abstract("inet"), AbstractType::Text);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let postgres = TypeMapper::new(DatabaseType::PostgreSQL);
+        for native in ["SMALLINT", "BIGINT", "NUMERIC(12, 4)", "VARCHAR(80)", "TIMESTAMPTZ", "JSONB", "UUID", "BYTEA"] {
+            assert_eq!(postgres.to_native(&postgres.to_abstract(native)), native);
+        }
+
+        let json = serde_json::to_value(AbstractType::Varchar { length: Some(80) }).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "varchar", "length": 80 }));
+    }
+}
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Application-wide error type
+///
+/// Messages are passed through `redact` so credentials in driver errors or
+/// connection strings never reach the log file or the frontend.
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("Database error: {}", crate::redact::redacted(.0.to_string()))]
+    Database(#[from] crate::database::DatabaseError),
+
+    #[error("Configuration error: {}", crate::redact::redacted(.0))]
+    Config(String),
+
+    #[error("IO error: {}", crate::redact::redacted(.0.to_string()))]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {}", crate::redact::redacted(.0.to_string()))]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Network error: {}", crate::redact::redacted(.0))]
+    Network(String),
+
+    #[error("Authentication error: {}", crate::redact::redacted(.0))]
+    Auth(String),
+
+    #[error("Validation error: {}", crate::redact::redacted(.0))]
+    Validation(String),
+
+    #[error("Storage error: {}", crate::redact::redacted(.0))]
+    Storage(String),
+
+    #[error("Encryption error: {}", crate::redact::redacted(.0))]
+    Encryption(String),
+
+    #[error("Not found: {}", crate::redact::redacted(.0))]
+    NotFound(String),
+
+    #[error("Permission denied: {}", crate::redact::redacted(.0))]
+    PermissionDenied(String),
+
+    #[error("Confirmation required: {}", crate::redact::redacted(.0))]
+    ConfirmationRequired(String),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error("Unknown error: {}", crate::redact::redacted(.0))]
+    Unknown(String),
+}
+
+pub type Result<T> = std::result::Result<T, AppError>;
+
+impl AppError {
+    /// What went wrong beyond the kind of error, with credentials redacted
+    pub fn detail(&self) -> Option<String> {
+        let detail = match self {
+            AppError::Database(e) => e.to_string(),
+            AppError::Io(e) => e.to_string(),
+            AppError::Serialization(e) => e.to_string(),
+            AppError::Config(message)
+            | AppError::Network(message)
+            | AppError::Auth(message)
+            | AppError::Validation(message)
+            | AppError::Storage(message)
+            | AppError::Encryption(message)
+            | AppError::NotFound(message)
+            | AppError::PermissionDenied(message)
+            | AppError::ConfirmationRequired(message)
+            | AppError::Unknown(message) => message.clone(),
+            AppError::Cancelled => return None,
+        };
+        Some(crate::redact::redacted(detail))
+    }
+
+    /// Whether the operation may succeed when simply tried again
+    pub fn is_transient(&self) -> bool {
+        matches!(self, AppError::Database(error) if error.is_transient())
+    }
+}
+
+/// Error response structure for frontend
+///
+/// `message` is in the locale chosen in the settings; `error_type` is the stable key
+/// it was translated from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error_type: String,
+    pub message: String,
+    pub details: Option<String>,
+    pub code: Option<String>,
+    /// Code and position of a failed statement, for marking it in the editor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_error: Option<crate::database::error::QueryError>,
+    /// Which step of a failed connection attempt went wrong
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_diagnostics: Option<crate::database::connection_check::ConnectionDiagnostics>,
+    /// What had run when a script stopped at a failing statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script_failure: Option<ScriptFailure>,
+}
+
+/// The failing statement of a script and the results of those run before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptFailure {
+    /// Position of the failing statement in the script, starting at 0
+    pub statement_index: usize,
+    pub statement: String,
+    /// Results of the statements that completed, in the same form as a successful run
+    pub completed: Vec<serde_json::Value>,
+    /// Whether the completed statements were undone; scripts run without a
+    /// surrounding transaction, so their changes are kept
+    pub rolled_back: bool,
+}
+
+impl From<&AppError> for ErrorResponse {
+    fn from(err: &AppError) -> Self {
+        let error_type = match err {
+            AppError::Database(_) => "database",
+            AppError::Config(_) => "config",
+            AppError::Io(_) => "io",
+            AppError::Serialization(_) => "serialization",
+            AppError::Network(_) => "network",
+            AppError::Auth(_) => "auth",
+            AppError::Validation(_) => "validation",
+            AppError::Storage(_) => "storage",
+            AppError::Encryption(_) => "encryption",
+            AppError::NotFound(_) => "not_found",
+            AppError::PermissionDenied(_) => "permission_denied",
+            AppError::ConfirmationRequired(_) => "confirmation_required",
+            AppError::Cancelled => "cancelled",
+            AppError::Unknown(_) => "unknown",
+        };
+
+        let query_error = match err {
+            AppError::Database(crate::database::DatabaseError::Query(query_error)) => Some(query_error.as_ref().clone()),
+            _ => None,
+        };
+
+        ErrorResponse {
+            error_type: error_type.to_string(),
+            message: crate::i18n::error_message(crate::i18n::locale(), error_type, err.detail().as_deref()),
+            details: None,
+            code: query_error
+                .as_ref()
+                .and_then(|e| e.sqlstate.clone().or_else(|| e.native_code.clone())),
+            query_error,
+            connection_diagnostics: None,
+            script_failure: None,
+        }
+    }
+}
+
+impl ErrorResponse {
+    /// Response for statements that only run once the user confirms them
+    ///
+    /// `details` lists the statements, one per line, for the confirmation dialog; the
+    /// call is then repeated with `force` set.
+    pub fn confirmation_required(code: &str, message: String, statements: &[String]) -> Self {
+        ErrorResponse {
+            error_type: "confirmation_required".to_string(),
+            message,
+            details: Some(statements.join("\n")),
+            code: Some(code.to_string()),
+            query_error: None,
+            connection_diagnostics: None,
+            script_failure: None,
+        }
+    }
+}
+
+impl From<ErrorResponse> for String {
+    fn from(response: ErrorResponse) -> Self {
+        serde_json::to_string(&response).unwrap_or(response.message)
+    }
+}
+
+impl From<AppError> for ErrorResponse {
+    fn from(err: AppError) -> Self {
+        ErrorResponse::from(&err)
+    }
+}
+
+/// Convert AppError to a format suitable for Tauri commands
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        serde_json::to_string(&ErrorResponse::from(&err))
+            .unwrap_or_else(|_| err.to_string())
+    }
+}
+
+/// Macro for creating validation errors with context
+#[macro_export]
+macro_rules! validation_error {
+    ($msg:expr) => {
+        $crate::error::AppError::Validation($msg.to_string())
+    };
+    ($fmt:expr, $($arg:tt)*) => {
+        $crate::error::AppError::Validation(format!($fmt, $($arg)*))
+    };
+}
+
+/// Macro for creating not found errors with context
+#[macro_export]
+macro_rules! not_found_error {
+    ($msg:expr) => {
+        $crate::error::AppError::NotFound($msg.to_string())
+    };
+    ($fmt:expr, $($arg:tt)*) => {
+        $crate::error::AppError::NotFound(format!($fmt, $($arg)*))
+    };
+}
+
+/// Extension trait for Result types to add context
+pub trait ErrorContext<T> {
+    fn context<C>(self, context: C) -> Result<T>
+    where
+        C: std::fmt::Display + Send + Sync + 'static;
+
+    fn with_context<C, F>(self, f: F) -> Result<T>
+    where
+        C: std::fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C;
+}
+
+impl<T, E> ErrorContext<T> for std::result::Result<T, E>
+where
+    E: Into<AppError>,
+{
+    fn context<C>(self, context: C) -> Result<T>
+    where
+        C: std::fmt::Display + Send + Sync + 'static,
+    {
+        self.map_err(|e| {
+            let base_error = e.into();
+            AppError::Unknown(format!("{}: {}", context, base_error))
+        })
+    }
+
+    fn with_context<C, F>(self, f: F) -> Result<T>
+    where
+        C: std::fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        self.map_err(|e| {
+            let base_error = e.into();
+            AppError::Unknown(format!("{}: {}", f(), base_error))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_conversion() {
+        let err = AppError::Database(crate::database::DatabaseError::ConnectionFailed(
+            "test".to_string(),
+        ));
+        let response = ErrorResponse::from(err);
+        assert_eq!(response.error_type, "database");
+        assert_eq!(response.message, "Database error: Connection failed: test");
+    }
+
+    #[test]
+    fn test_query_error_response() {
+        let err = AppError::Database(crate::database::DatabaseError::Query(Box::new(crate::database::error::QueryError {
+            message: "syntax error at or near \"FORM\"".to_string(),
+            sqlstate: Some("42601".to_string()),
+            native_code: None,
+            detail: None,
+            hint: None,
+            position: crate::database::error::ErrorPosition::at("SELECT 1 FORM t", 9),
+            transient: false,
+            suggestions: Vec::new(),
+        })));
+
+        let response = ErrorResponse::from(&err);
+        assert_eq!(response.code.as_deref(), Some("42601"));
+        assert_eq!(response.query_error.unwrap().position.unwrap().column, 10);
+    }
+
+    #[test]
+    fn test_confirmation_required_response() {
+        let statements = vec!["DROP TABLE t".to_string(), "DELETE FROM u".to_string()];
+        let json: String = ErrorResponse::confirmation_required("destructive_statement", "Confirm".to_string(), &statements).into();
+
+        let response: ErrorResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(response.error_type, "confirmation_required");
+        assert_eq!(response.code.as_deref(), Some("destructive_statement"));
+        assert_eq!(response.details.as_deref(), Some("DROP TABLE t\nDELETE FROM u"));
+    }
+
+    #[test]
+    fn test_script_failure_response() {
+        let mut response = ErrorResponse::from(AppError::Validation("bad".to_string()));
+        let json: String = ErrorResponse::from(AppError::Validation("bad".to_string())).into();
+        assert!(!json.contains("script_failure"));
+
+        response.script_failure = Some(ScriptFailure {
+            statement_index: 1,
+            statement: "SELECT nope".to_string(),
+            completed: vec![serde_json::json!({ "type": "command", "rows_affected": 2 })],
+            rolled_back: false,
+        });
+        let json: String = response.into();
+        let parsed: ErrorResponse = serde_json::from_str(&json).unwrap();
+        let failure = parsed.script_failure.unwrap();
+        assert_eq!(failure.statement_index, 1);
+        assert_eq!(failure.completed[0]["rows_affected"], 2);
+    }
+
+    #[test]
+    fn test_validation_error_macro() {
+        let err = validation_error!("Invalid input");
+        match err {
+            AppError::Validation(msg) => assert_eq!(msg, "Invalid input"),
+            _ => panic!("Expected validation error"),
+        }
+    }
+
+    #[test]
+    fn test_error_context() {
+        let result: std::result::Result<i32, std::io::Error> =
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"));
+
+        let with_context = result.context("Failed to read configuration");
+        assert!(with_context.is_err());
+
+        if let Err(err) = with_context {
+            let msg = err.to_string();
+            assert!(msg.contains("Failed to read configuration"));
+        }
+    }
+}
\ No newline at end of file
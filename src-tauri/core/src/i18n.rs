@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// Language of messages shown to the user
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+/// Locale used for error messages sent to the frontend
+static LOCALE: RwLock<Locale> = RwLock::new(Locale::En);
+
+pub fn locale() -> Locale {
+    *LOCALE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_locale(locale: Locale) {
+    *LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+/// Localized text for a message key, falling back to English and then to the key
+///
+/// Error keys are the `error_type` codes of `ErrorResponse`.
+pub fn text(locale: Locale, key: &str) -> &str {
+    let localized = match locale {
+        Locale::En => None,
+        Locale::Ja => japanese(key),
+    };
+    localized.or_else(|| english(key)).unwrap_or(key)
+}
+
+fn english(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "database" => "Database error",
+        "config" => "Configuration error",
+        "io" => "IO error",
+        "serialization" => "Serialization error",
+        "network" => "Network error",
+        "auth" => "Authentication error",
+        "validation" => "Validation error",
+        "storage" => "Storage error",
+        "encryption" => "Encryption error",
+        "not_found" => "Not found",
+        "permission_denied" => "Permission denied",
+        "confirmation_required" => "Confirmation required",
+        "cancelled" => "Operation cancelled",
+        "unknown" => "Unknown error",
+        "statement_failed" => "Failed to execute statement",
+        "connection_failed" => "Connection failed",
+        _ => return None,
+    })
+}
+
+fn japanese(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "database" => "データベースエラー",
+        "config" => "設定エラー",
+        "io" => "入出力エラー",
+        "serialization" => "シリアライズエラー",
+        "network" => "ネットワークエラー",
+        "auth" => "認証エラー",
+        "validation" => "入力エラー",
+        "storage" => "保存エラー",
+        "encryption" => "暗号化エラー",
+        "not_found" => "見つかりません",
+        "permission_denied" => "権限がありません",
+        "confirmation_required" => "確認が必要です",
+        "cancelled" => "操作はキャンセルされました",
+        "unknown" => "不明なエラー",
+        "statement_failed" => "ステートメントの実行に失敗しました",
+        "connection_failed" => "接続に失敗しました",
+        _ => return None,
+    })
+}
+
+/// "<localized title>: <detail>", or only the title without a detail
+pub fn error_message(locale: Locale, key: &str, detail: Option<&str>) -> String {
+    match detail {
+        Some(detail) => format!("{}: {}", text(locale, key), detail),
+        None => text(locale, key).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text() {
+        assert_eq!(text(Locale::En, "not_found"), "Not found");
+        assert_eq!(text(Locale::Ja, "not_found"), "見つかりません");
+        assert_eq!(text(Locale::Ja, "no_such_key"), "no_such_key");
+    }
+
+    #[test]
+    fn test_every_english_key_is_translated() {
+        for key in [
+            "database", "config", "io", "serialization", "network", "auth", "validation",
+            "storage", "encryption", "not_found", "permission_denied", "confirmation_required",
+            "cancelled", "unknown", "statement_failed", "connection_failed",
+        ] {
+            assert!(english(key).is_some(), "{}", key);
+            assert!(japanese(key).is_some(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(error_message(Locale::Ja, "auth", Some("Wrong master password")), "認証エラー: Wrong master password");
+        assert_eq!(error_message(Locale::En, "cancelled", None), "Operation cancelled");
+    }
+}
@@ -0,0 +1,12 @@
+//! Database access, connection profiles and error types of DataForge
+//!
+//! Nothing here depends on Tauri, so the crate builds and is tested on its own;
+//! the app crate wires it up to commands.
+
+pub mod database;
+pub mod error;
+pub mod i18n;
+mod logger;
+pub mod metrics;
+pub mod profile;
+pub mod redact;
@@ -0,0 +1,34 @@
+//! Logging macros shared by both crates
+
+/// Convenience macros for logging
+///
+/// The first argument becomes the event's target, so it can be filtered on, e.g.
+/// `DATAFORGE_LOG=warn,audit=info`. Unlike the logger these replaced, events logged
+/// before `init_logger` (or in tests) are dropped instead of panicking.
+#[macro_export]
+macro_rules! log_debug {
+    ($module:expr, $($arg:tt)*) => {
+        ::tracing::debug!(target: $module, "{}", format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($module:expr, $($arg:tt)*) => {
+        ::tracing::info!(target: $module, "{}", format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($module:expr, $($arg:tt)*) => {
+        ::tracing::warn!(target: $module, "{}", format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($module:expr, $($arg:tt)*) => {
+        ::tracing::error!(target: $module, "{}", format_args!($($arg)*))
+    };
+}
@@ -0,0 +1,254 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Number of connections whose query counters are kept
+const MAX_CONNECTIONS: usize = 50;
+
+/// Waits for a pool connection at least this long are counted as slow
+const SLOW_ACQUIRE: Duration = Duration::from_millis(100);
+
+/// Time spent waiting for pool connections, updated by the adapters
+#[derive(Debug, Default)]
+pub struct AcquireStats {
+    acquires: AtomicU64,
+    total_wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+    slow_acquires: AtomicU64,
+    warm_up_us: AtomicU64,
+}
+
+impl AcquireStats {
+    pub fn record(&self, wait: Duration) {
+        let wait_us = wait.as_micros().min(u64::MAX as u128) as u64;
+        self.acquires.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_us.fetch_add(wait_us, Ordering::Relaxed);
+        self.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+        if wait >= SLOW_ACQUIRE {
+            self.slow_acquires.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record how long opening the pool's minimum connections took on connect
+    pub fn record_warm_up(&self, elapsed: Duration) {
+        let elapsed_us = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        self.warm_up_us.store(elapsed_us, Ordering::Relaxed);
+    }
+
+    /// Combine the wait times with the current state of a pool
+    pub fn pool_stats(&self, size: u32, idle: u32, min_connections: u32, max_connections: u32) -> PoolStats {
+        let acquires = self.acquires.load(Ordering::Relaxed);
+        let total_wait_us = self.total_wait_us.load(Ordering::Relaxed);
+
+        PoolStats {
+            size,
+            idle,
+            active: size.saturating_sub(idle),
+            min_connections,
+            max_connections,
+            acquire_count: acquires,
+            avg_acquire_wait_ms: if acquires == 0 {
+                0.0
+            } else {
+                total_wait_us as f64 / acquires as f64 / 1000.0
+            },
+            max_acquire_wait_ms: self.max_wait_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            slow_acquire_count: self.slow_acquires.load(Ordering::Relaxed),
+            warm_up_ms: self.warm_up_us.load(Ordering::Relaxed) as f64 / 1000.0,
+        }
+    }
+}
+
+/// Current state of a connection pool
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStats {
+    /// Open connections, idle or in use
+    pub size: u32,
+    pub idle: u32,
+    pub active: u32,
+    /// Connections opened on connect and kept open while idle
+    pub min_connections: u32,
+    pub max_connections: u32,
+    pub acquire_count: u64,
+    pub avg_acquire_wait_ms: f64,
+    pub max_acquire_wait_ms: f64,
+    /// Waits of 100 ms or more, a sign the pool is too small
+    pub slow_acquire_count: u64,
+    /// Time taken to open the minimum connections on connect
+    pub warm_up_ms: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Counters {
+    profile_id: Option<String>,
+    first_query_at: DateTime<Utc>,
+    last_query_at: DateTime<Utc>,
+    queries: u64,
+    errors: u64,
+    total_duration_ms: u64,
+    max_duration_ms: u64,
+    /// Order of last use, as timestamps can repeat
+    last_used: u64,
+}
+
+/// Query counters of one connection
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionMetrics {
+    pub connection_id: String,
+    pub profile_id: Option<String>,
+    pub first_query_at: DateTime<Utc>,
+    pub last_query_at: DateTime<Utc>,
+    pub queries: u64,
+    pub errors: u64,
+    /// Share of statements that failed, from 0 to 1
+    pub error_rate: f64,
+    pub avg_duration_ms: f64,
+    pub max_duration_ms: u64,
+}
+
+/// Statement counts and durations per connection since the app started
+///
+/// Only the most recently used connections are kept.
+#[derive(Debug, Default)]
+pub struct QueryMetrics {
+    connections: Mutex<HashMap<String, Counters>>,
+    uses: AtomicU64,
+}
+
+impl QueryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one executed statement
+    pub fn record(&self, connection_id: &str, profile_id: Option<&str>, duration_ms: u64, success: bool) {
+        let mut connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        let last_used = self.uses.fetch_add(1, Ordering::Relaxed);
+
+        if !connections.contains_key(connection_id) && connections.len() >= MAX_CONNECTIONS {
+            let oldest = connections
+                .iter()
+                .min_by_key(|(_, counters)| counters.last_used)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                connections.remove(&oldest);
+            }
+        }
+
+        let counters = connections.entry(connection_id.to_string()).or_insert_with(|| Counters {
+            profile_id: profile_id.map(str::to_string),
+            first_query_at: now,
+            last_query_at: now,
+            queries: 0,
+            errors: 0,
+            total_duration_ms: 0,
+            max_duration_ms: 0,
+            last_used,
+        });
+        counters.last_query_at = now;
+        counters.last_used = last_used;
+        counters.queries += 1;
+        if !success {
+            counters.errors += 1;
+        }
+        counters.total_duration_ms += duration_ms;
+        counters.max_duration_ms = counters.max_duration_ms.max(duration_ms);
+    }
+
+    /// Counters of every tracked connection, most recently used first
+    pub fn snapshot(&self) -> Vec<ConnectionMetrics> {
+        let connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut connections: Vec<(&String, &Counters)> = connections.iter().collect();
+        connections.sort_by_key(|(_, c)| std::cmp::Reverse(c.last_used));
+
+        connections
+            .into_iter()
+            .map(|(id, c)| ConnectionMetrics {
+                connection_id: id.clone(),
+                profile_id: c.profile_id.clone(),
+                first_query_at: c.first_query_at,
+                last_query_at: c.last_query_at,
+                queries: c.queries,
+                errors: c.errors,
+                error_rate: if c.queries == 0 { 0.0 } else { c.errors as f64 / c.queries as f64 },
+                avg_duration_ms: if c.queries == 0 {
+                    0.0
+                } else {
+                    c.total_duration_ms as f64 / c.queries as f64
+                },
+                max_duration_ms: c.max_duration_ms,
+            })
+            .collect()
+    }
+}
+
+/// Everything shown on the metrics panel
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSnapshot {
+    pub collected_at: DateTime<Utc>,
+    pub active_connection_id: Option<String>,
+    /// Pool of the active connection
+    pub pool: Option<PoolStats>,
+    pub connections: Vec<ConnectionMetrics>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_metrics() {
+        let metrics = QueryMetrics::new();
+        metrics.record("c1", Some("p1"), 10, true);
+        metrics.record("c1", Some("p1"), 30, false);
+        metrics.record("c2", None, 5, true);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].connection_id, "c2");
+
+        let c1 = snapshot.iter().find(|m| m.connection_id == "c1").unwrap();
+        assert_eq!(c1.queries, 2);
+        assert_eq!(c1.errors, 1);
+        assert_eq!(c1.error_rate, 0.5);
+        assert_eq!(c1.avg_duration_ms, 20.0);
+        assert_eq!(c1.max_duration_ms, 30);
+        assert_eq!(c1.profile_id.as_deref(), Some("p1"));
+    }
+
+    #[test]
+    fn test_oldest_connection_is_dropped() {
+        let metrics = QueryMetrics::new();
+        for i in 0..=MAX_CONNECTIONS {
+            metrics.record(&format!("c{}", i), None, 1, true);
+        }
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), MAX_CONNECTIONS);
+        assert!(snapshot.iter().all(|m| m.connection_id != "c0"));
+    }
+
+    #[test]
+    fn test_pool_stats() {
+        let stats = AcquireStats::default();
+        assert_eq!(stats.pool_stats(2, 2, 1, 5).avg_acquire_wait_ms, 0.0);
+
+        stats.record(Duration::from_millis(2));
+        stats.record(Duration::from_millis(4));
+        stats.record(Duration::from_millis(150));
+        stats.record_warm_up(Duration::from_millis(12));
+
+        let pool = stats.pool_stats(3, 1, 1, 5);
+        assert_eq!(pool.active, 2);
+        assert_eq!(pool.acquire_count, 3);
+        assert_eq!(pool.avg_acquire_wait_ms, 52.0);
+        assert_eq!(pool.max_acquire_wait_ms, 150.0);
+        assert_eq!(pool.slow_acquire_count, 1);
+        assert_eq!(pool.warm_up_ms, 12.0);
+    }
+}
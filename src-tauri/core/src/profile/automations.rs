@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+use crate::error::AppError;
+use super::encrypted_file::EncryptedJsonFile;
+
+const AUTOMATIONS_FILE: &str = "automations.encrypted";
+
+/// Earlier versions kept for each script; older ones are dropped
+const MAX_VERSIONS: usize = 20;
+
+/// Earlier body of an automation script
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationVersion {
+    pub version: u32,
+    pub body: String,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// Rhai script saved by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Automation {
+    /// Assigned when the script is first saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub body: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Incremented whenever the body changes
+    #[serde(default)]
+    pub version: u32,
+    /// Earlier versions, oldest first
+    #[serde(default)]
+    pub history: Vec<AutomationVersion>,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Saved automation scripts, encrypted with the profile key
+pub struct AutomationStore {
+    file: EncryptedJsonFile,
+}
+
+impl AutomationStore {
+    pub fn new(profiles_dir: &Path) -> Self {
+        Self {
+            file: EncryptedJsonFile::new(profiles_dir.join(AUTOMATIONS_FILE)),
+        }
+    }
+
+    /// All scripts, sorted by name
+    pub fn list(&self) -> Result<Vec<Automation>, AppError> {
+        let mut automations: Vec<Automation> = self.file.load()?;
+        automations.sort_by_key(|a| a.name.to_lowercase());
+        Ok(automations)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Automation, AppError> {
+        self.list()?
+            .into_iter()
+            .find(|a| a.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Automation {} not found", id)))
+    }
+
+    /// Add a script, or replace the one with the same ID; a changed body keeps
+    /// the previous one as a version
+    pub fn save(&self, mut automation: Automation) -> Result<Automation, AppError> {
+        if automation.name.trim().is_empty() {
+            return Err(AppError::Validation("Automation name is required".to_string()));
+        }
+
+        self.file.update(|automations: &mut Vec<Automation>| {
+            let now = Utc::now();
+            match automations.iter_mut().find(|a| !automation.id.is_empty() && a.id == automation.id) {
+                Some(existing) => {
+                    automation.created_at = existing.created_at;
+                    automation.history = std::mem::take(&mut existing.history);
+                    automation.version = existing.version;
+                    if existing.body != automation.body {
+                        automation.history.push(AutomationVersion {
+                            version: existing.version,
+                            body: std::mem::take(&mut existing.body),
+                            saved_at: existing.updated_at,
+                        });
+                        let excess = automation.history.len().saturating_sub(MAX_VERSIONS);
+                        automation.history.drain(..excess);
+                        automation.version += 1;
+                    }
+                    automation.updated_at = now;
+                    *existing = automation.clone();
+                }
+                None => {
+                    automation.id = Uuid::new_v4().to_string();
+                    automation.version = 1;
+                    automation.history.clear();
+                    automation.created_at = now;
+                    automation.updated_at = now;
+                    automations.push(automation.clone());
+                }
+            }
+            Ok(automation)
+        })
+    }
+
+    /// Make an earlier version the current body, itself saved as a new version
+    pub fn restore(&self, id: &str, version: u32) -> Result<Automation, AppError> {
+        let automation = self.get(id)?;
+        let body = automation
+            .history
+            .iter()
+            .find(|v| v.version == version)
+            .map(|v| v.body.clone())
+            .ok_or_else(|| AppError::NotFound(format!("Version {} of automation {} not found", version, id)))?;
+        self.save(Automation { body, ..automation })
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.file.update(|automations: &mut Vec<Automation>| {
+            let before = automations.len();
+            automations.retain(|a| a.id != id);
+            if automations.len() == before {
+                return Err(AppError::NotFound(format!("Automation {} not found", id)));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.file.reencrypt(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn automation(name: &str, body: &str) -> Automation {
+        Automation {
+            id: String::new(),
+            name: name.to_string(),
+            body: body.to_string(),
+            description: None,
+            version: 0,
+            history: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AutomationStore::new(temp_dir.path());
+
+        let saved = store.save(automation("Nightly", "log(\"v1\")")).unwrap();
+        assert_eq!(saved.version, 1);
+
+        // Renaming keeps the version, a new body adds one
+        let renamed = store.save(Automation { name: "Nightly report".to_string(), ..saved.clone() }).unwrap();
+        assert_eq!(renamed.version, 1);
+        let changed = store.save(Automation { body: "log(\"v2\")".to_string(), ..renamed }).unwrap();
+        assert_eq!(changed.version, 2);
+        assert_eq!(changed.history.len(), 1);
+        assert_eq!(changed.history[0].body, "log(\"v1\")");
+
+        let restored = store.restore(&saved.id, 1).unwrap();
+        assert_eq!(restored.version, 3);
+        assert_eq!(restored.body, "log(\"v1\")");
+        assert_eq!(store.get(&saved.id).unwrap().history.len(), 2);
+        assert!(matches!(store.restore(&saved.id, 9), Err(AppError::NotFound(_))));
+
+        for i in 0..MAX_VERSIONS + 5 {
+            store.save(Automation { body: format!("log(\"{}\")", i), ..store.get(&saved.id).unwrap() }).unwrap();
+        }
+        assert_eq!(store.get(&saved.id).unwrap().history.len(), MAX_VERSIONS);
+
+        store.delete(&saved.id).unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.save(automation(" ", "1")).is_err());
+    }
+}
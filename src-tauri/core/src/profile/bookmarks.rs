@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+use crate::error::AppError;
+use super::encrypted_file::EncryptedJsonFile;
+
+const BOOKMARKS_FILE: &str = "bookmarks.encrypted";
+
+/// What a bookmark opens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BookmarkTarget {
+    Table {
+        name: String,
+        #[serde(default)]
+        schema: Option<String>,
+    },
+    View {
+        name: String,
+        #[serde(default)]
+        schema: Option<String>,
+    },
+    Query { sql: String },
+}
+
+/// Table, view or query the user marked as a favorite on one connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Assigned when the bookmark is first saved
+    #[serde(default)]
+    pub id: String,
+    /// Profile of the connection; the active one when saved empty
+    #[serde(default)]
+    pub profile_id: String,
+    /// Label shown in the listing; the table or view name when empty
+    #[serde(default)]
+    pub name: String,
+    pub target: BookmarkTarget,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Bookmarks of one connection by kind, each sorted by name
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BookmarkListing {
+    pub tables: Vec<Bookmark>,
+    pub views: Vec<Bookmark>,
+    pub queries: Vec<Bookmark>,
+}
+
+/// Saved bookmarks of all connections, encrypted with the profile key
+pub struct BookmarkStore {
+    file: EncryptedJsonFile,
+}
+
+impl BookmarkStore {
+    pub fn new(profiles_dir: &Path) -> Self {
+        Self {
+            file: EncryptedJsonFile::new(profiles_dir.join(BOOKMARKS_FILE)),
+        }
+    }
+
+    pub fn list(&self, profile_id: &str) -> Result<BookmarkListing, AppError> {
+        let mut bookmarks: Vec<Bookmark> = self.file.load()?;
+        bookmarks.retain(|b| b.profile_id == profile_id);
+        bookmarks.sort_by_key(|b| b.name.to_lowercase());
+
+        let mut listing = BookmarkListing::default();
+        for bookmark in bookmarks {
+            match bookmark.target {
+                BookmarkTarget::Table { .. } => listing.tables.push(bookmark),
+                BookmarkTarget::View { .. } => listing.views.push(bookmark),
+                BookmarkTarget::Query { .. } => listing.queries.push(bookmark),
+            }
+        }
+        Ok(listing)
+    }
+
+    /// Add a bookmark, or replace the one with the same ID
+    ///
+    /// Bookmarking a table or view that is already bookmarked on the
+    /// connection returns the existing bookmark.
+    pub fn save(&self, mut bookmark: Bookmark) -> Result<Bookmark, AppError> {
+        match &bookmark.target {
+            BookmarkTarget::Table { name, .. } | BookmarkTarget::View { name, .. } => {
+                if name.trim().is_empty() {
+                    return Err(AppError::Validation("Bookmarked object name is required".to_string()));
+                }
+                if bookmark.name.trim().is_empty() {
+                    bookmark.name = name.clone();
+                }
+            }
+            BookmarkTarget::Query { sql } => {
+                if sql.trim().is_empty() {
+                    return Err(AppError::Validation("Bookmarked query is empty".to_string()));
+                }
+                if bookmark.name.trim().is_empty() {
+                    return Err(AppError::Validation("Bookmark name is required".to_string()));
+                }
+            }
+        }
+
+        self.file.update(|bookmarks: &mut Vec<Bookmark>| {
+            if let Some(existing) = bookmarks.iter_mut().find(|b| !bookmark.id.is_empty() && b.id == bookmark.id) {
+                bookmark.created_at = existing.created_at;
+                *existing = bookmark.clone();
+                return Ok(bookmark);
+            }
+            let is_query = matches!(bookmark.target, BookmarkTarget::Query { .. });
+            if let Some(existing) = bookmarks
+                .iter()
+                .find(|b| !is_query && b.profile_id == bookmark.profile_id && b.target == bookmark.target)
+            {
+                return Ok(existing.clone());
+            }
+
+            bookmark.id = Uuid::new_v4().to_string();
+            bookmark.created_at = Utc::now();
+            bookmarks.push(bookmark.clone());
+            Ok(bookmark)
+        })
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.file.update(|bookmarks: &mut Vec<Bookmark>| {
+            let before = bookmarks.len();
+            bookmarks.retain(|b| b.id != id);
+            if bookmarks.len() == before {
+                return Err(AppError::NotFound(format!("Bookmark {} not found", id)));
+            }
+            Ok(())
+        })
+    }
+
+    /// Drop the bookmarks of a deleted profile
+    pub fn remove_profile(&self, profile_id: &str) -> Result<(), AppError> {
+        self.file.update(|bookmarks: &mut Vec<Bookmark>| {
+            bookmarks.retain(|b| b.profile_id != profile_id);
+            Ok(())
+        })
+    }
+
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.file.reencrypt(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn bookmark(profile_id: &str, name: &str, target: BookmarkTarget) -> Bookmark {
+        Bookmark {
+            id: String::new(),
+            profile_id: profile_id.to_string(),
+            name: name.to_string(),
+            target,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn table(name: &str) -> BookmarkTarget {
+        BookmarkTarget::Table { name: name.to_string(), schema: None }
+    }
+
+    #[test]
+    fn test_listing() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = BookmarkStore::new(temp_dir.path());
+
+        let orders = store.save(bookmark("local", "", table("orders"))).unwrap();
+        assert_eq!(orders.name, "orders");
+        assert_eq!(store.save(bookmark("local", "Orders", table("orders"))).unwrap().id, orders.id);
+        store.save(bookmark("local", "", BookmarkTarget::View { name: "active_users".to_string(), schema: None })).unwrap();
+        store.save(bookmark("local", "Top customers", BookmarkTarget::Query { sql: "SELECT 1".to_string() })).unwrap();
+        store.save(bookmark("staging", "", table("orders"))).unwrap();
+        assert!(store.save(bookmark("local", "", BookmarkTarget::Query { sql: "SELECT 1".to_string() })).is_err());
+
+        let listing = store.list("local").unwrap();
+        assert_eq!(listing.tables.len(), 1);
+        assert_eq!(listing.views[0].name, "active_users");
+        assert_eq!(listing.queries[0].name, "Top customers");
+
+        store.delete(&orders.id).unwrap();
+        assert!(store.list("local").unwrap().tables.is_empty());
+        store.remove_profile("staging").unwrap();
+        assert!(store.list("staging").unwrap().tables.is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use super::ConnectionProfile;
+use super::crypto::{self, KdfParams};
+
+const BUNDLE_FORMAT: &str = "dataforge-profiles";
+const BUNDLE_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+/// A profile as stored in a bundle, optionally with its passwords
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledProfile {
+    #[serde(flatten)]
+    pub profile: ConnectionProfile,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub ssh_passphrase: Option<String>,
+}
+
+/// Decrypted contents of a profile bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileBundle {
+    pub exported_at: DateTime<Utc>,
+    pub profiles: Vec<BundledProfile>,
+}
+
+impl ProfileBundle {
+    pub fn new(profiles: Vec<BundledProfile>) -> Self {
+        Self {
+            exported_at: Utc::now(),
+            profiles,
+        }
+    }
+}
+
+/// On-disk layout of a bundle file; everything but the key derivation inputs is encrypted
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleFile {
+    format: String,
+    version: u32,
+    kdf: KdfParams,
+    salt: String,
+    data: String,
+}
+
+/// Encrypt a bundle with a key derived from `passphrase`
+pub fn seal(bundle: &ProfileBundle, passphrase: &str) -> Result<String, AppError> {
+    seal_with_params(bundle, passphrase, KdfParams::default())
+}
+
+fn seal_with_params(
+    bundle: &ProfileBundle,
+    passphrase: &str,
+    kdf: KdfParams,
+) -> Result<String, AppError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Bundle passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LENGTH
+        )));
+    }
+
+    let json_data = serde_json::to_vec(bundle)
+        .map_err(|e| AppError::Storage(format!("Failed to serialize profile bundle: {}", e)))?;
+
+    let salt = crypto::generate_salt();
+    let key = crypto::derive_key(passphrase, &salt, &kdf)?;
+
+    let file = BundleFile {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        kdf,
+        salt: BASE64.encode(&salt),
+        data: crypto::encrypt_with_key(&key, &json_data)?,
+    };
+
+    serde_json::to_string_pretty(&file)
+        .map_err(|e| AppError::Storage(format!("Failed to serialize profile bundle: {}", e)))
+}
+
+/// Decrypt a bundle produced by `seal`
+pub fn open(contents: &str, passphrase: &str) -> Result<ProfileBundle, AppError> {
+    let file: BundleFile = serde_json::from_str(contents)
+        .map_err(|_| AppError::Validation("Not a DataForge profile bundle".to_string()))?;
+
+    if file.format != BUNDLE_FORMAT {
+        return Err(AppError::Validation("Not a DataForge profile bundle".to_string()));
+    }
+    if file.version > BUNDLE_VERSION {
+        return Err(AppError::Validation(format!(
+            "Profile bundle version {} is not supported by this version of DataForge",
+            file.version
+        )));
+    }
+
+    let salt = BASE64
+        .decode(&file.salt)
+        .map_err(|e| AppError::Encryption(format!("Failed to decode base64: {}", e)))?;
+    let key = crypto::derive_key(passphrase, &salt, &file.kdf)?;
+
+    // A wrong passphrase shows up as an authentication failure of the cipher
+    let decrypted = crypto::decrypt_with_key(&key, &file.data)
+        .map_err(|_| AppError::Auth("Wrong passphrase or damaged profile bundle".to_string()))?;
+
+    serde_json::from_slice(&decrypted)
+        .map_err(|e| AppError::Storage(format!("Failed to deserialize profile bundle: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::DatabaseType;
+
+    fn test_params() -> KdfParams {
+        KdfParams { memory_kib: 1024, iterations: 1, parallelism: 1 }
+    }
+
+    fn bundle() -> ProfileBundle {
+        let mut profile = ConnectionProfile::new(
+            "Shared DB".to_string(),
+            DatabaseType::PostgreSQL,
+            "shared".to_string(),
+        );
+        profile.username = Some("team".to_string());
+
+        ProfileBundle::new(vec![BundledProfile {
+            profile,
+            password: Some("s3cret".to_string()),
+            ssh_passphrase: None,
+        }])
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let sealed = seal_with_params(&bundle(), "team passphrase", test_params()).unwrap();
+        assert!(!sealed.contains("s3cret"));
+        assert!(!sealed.contains("Shared DB"));
+
+        let opened = open(&sealed, "team passphrase").unwrap();
+        assert_eq!(opened.profiles.len(), 1);
+        assert_eq!(opened.profiles[0].profile.name, "Shared DB");
+        assert_eq!(opened.profiles[0].password.as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let sealed = seal_with_params(&bundle(), "team passphrase", test_params()).unwrap();
+        assert!(matches!(open(&sealed, "other passphrase"), Err(AppError::Auth(_))));
+    }
+
+    #[test]
+    fn test_rejects_short_passphrase_and_foreign_files() {
+        assert!(seal_with_params(&bundle(), "short", test_params()).is_err());
+        assert!(open("{\"profiles\": []}", "team passphrase").is_err());
+    }
+}
@@ -0,0 +1,314 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use sha2::{Sha256, Digest};
+use rand::RngCore;
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+use once_cell::sync::{Lazy, OnceCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use crate::error::AppError;
+use super::vault;
+
+const NONCE_SIZE: usize = 12;
+const KEY_SIZE: usize = 32;
+
+/// Keyring entry holding the base64-encoded master key
+const MASTER_KEY_ENTRY: &str = "profile_master_key";
+
+/// Passphrase the profile file was encrypted with before the master key moved to the keyring
+const LEGACY_PASSPHRASE: &str = "dataforge_profile_encryption_key_v1";
+
+/// Key currently used for the profile file; cleared while profiles are locked
+static ACTIVE_KEY: Lazy<RwLock<Option<Vec<u8>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Whether the key comes from a user master password instead of the keyring
+static PASSWORD_MODE: AtomicBool = AtomicBool::new(false);
+
+/// When the user was last active, for locking after a period of inactivity; using the
+/// key does not count, since background jobs use it too
+static LAST_ACTIVITY: Lazy<Mutex<Instant>> = Lazy::new(|| Mutex::new(Instant::now()));
+
+/// Stand-in for the keyring key in tests
+static TEST_KEYRING_KEY: OnceCell<Vec<u8>> = OnceCell::new();
+
+/// Derive a key from a password using SHA-256
+fn derive_key_from_password(password: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(password.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// Generate a random 256-bit key
+pub fn generate_key() -> Vec<u8> {
+    let mut key = vec![0u8; KEY_SIZE];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+/// Get the encryption key for profiles
+///
+/// Without a master password, a random key is generated on first run and stored in
+/// the OS keyring. With one, the key only exists while the profiles are unlocked.
+pub fn get_or_create_key() -> Result<Vec<u8>, AppError> {
+    if let Some(key) = ACTIVE_KEY.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Ok(key.clone());
+    }
+
+    if password_mode() {
+        return Err(AppError::Auth(
+            "Profiles are locked. Unlock them with the master password.".to_string(),
+        ));
+    }
+
+    let key = keyring_key()?;
+    set_active_key(Some(key.clone()));
+    Ok(key)
+}
+
+/// The random master key kept in the OS keyring (or the fallback vault); tests use a
+/// per-process key instead
+pub fn keyring_key() -> Result<Vec<u8>, AppError> {
+    if cfg!(test) {
+        return Ok(TEST_KEYRING_KEY.get_or_init(generate_key).clone());
+    }
+    load_or_create_master_key()
+}
+
+/// Replace the key used for the profile file, or clear it to lock the profiles
+pub fn set_active_key(key: Option<Vec<u8>>) {
+    *ACTIVE_KEY.write().unwrap_or_else(|e| e.into_inner()) = key;
+}
+
+pub fn is_unlocked() -> bool {
+    ACTIVE_KEY.read().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+pub fn set_password_mode(enabled: bool) {
+    PASSWORD_MODE.store(enabled, Ordering::SeqCst);
+}
+
+pub fn password_mode() -> bool {
+    PASSWORD_MODE.load(Ordering::SeqCst)
+}
+
+/// Restart the idle timer, on keyboard or mouse input in the window or a command the
+/// user started
+pub fn record_activity() {
+    *LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+}
+
+/// Time since the user was last active
+pub fn idle_time() -> Duration {
+    LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner()).elapsed()
+}
+
+fn load_or_create_master_key() -> Result<Vec<u8>, AppError> {
+    match vault::get_secret(MASTER_KEY_ENTRY)? {
+        Some(encoded) => {
+            let key = BASE64
+                .decode(encoded.trim())
+                .map_err(|e| AppError::Encryption(format!("Failed to decode master key: {}", e)))?;
+            if key.len() != KEY_SIZE {
+                return Err(AppError::Encryption("Master key in keyring is invalid".to_string()));
+            }
+            Ok(key)
+        }
+        None => {
+            let key = generate_key();
+            vault::set_secret(MASTER_KEY_ENTRY, &BASE64.encode(&key))?;
+            crate::log_info!("crypto", "Generated a new profile master key");
+            Ok(key)
+        }
+    }
+}
+
+/// Decrypt data written with the old hard-coded key, for migrating existing files
+pub fn decrypt_legacy(encrypted_data: &str) -> Result<Vec<u8>, AppError> {
+    decrypt_with_key(&derive_key_from_password(LEGACY_PASSPHRASE), encrypted_data)
+}
+
+/// Encrypt data using AES-256-GCM
+pub fn encrypt(data: &[u8]) -> Result<String, AppError> {
+    let key_bytes = get_or_create_key()?;
+    encrypt_with_key(&key_bytes, data)
+}
+
+/// Decrypt data using AES-256-GCM
+pub fn decrypt(encrypted_data: &str) -> Result<Vec<u8>, AppError> {
+    let key_bytes = get_or_create_key()?;
+    decrypt_with_key(&key_bytes, encrypted_data)
+}
+
+/// Encrypt data with an explicit 256-bit key, returning base64 of nonce + ciphertext
+pub fn encrypt_with_key(key_bytes: &[u8], data: &[u8]) -> Result<String, AppError> {
+    if key_bytes.len() < KEY_SIZE {
+        return Err(AppError::Encryption("Encryption key is too short".to_string()));
+    }
+    let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..KEY_SIZE]);
+    let cipher = Aes256Gcm::new(&key);
+
+    // Generate a random nonce
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    // Encrypt the data
+    let ciphertext = cipher
+        .encrypt(&nonce, data)
+        .map_err(|e| AppError::Encryption(format!("Failed to encrypt data: {}", e)))?;
+
+    // Combine nonce and ciphertext
+    let mut combined = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+    combined.extend_from_slice(&nonce);
+    combined.extend_from_slice(&ciphertext);
+
+    // Encode as base64
+    Ok(BASE64.encode(combined))
+}
+
+/// Decrypt data produced by `encrypt_with_key`
+pub fn decrypt_with_key(key_bytes: &[u8], encrypted_data: &str) -> Result<Vec<u8>, AppError> {
+    if key_bytes.len() < KEY_SIZE {
+        return Err(AppError::Encryption("Encryption key is too short".to_string()));
+    }
+
+    // Decode from base64
+    let combined = BASE64
+        .decode(encrypted_data)
+        .map_err(|e| AppError::Encryption(format!("Failed to decode base64: {}", e)))?;
+
+    if combined.len() < NONCE_SIZE {
+        return Err(AppError::Encryption("Invalid encrypted data".to_string()));
+    }
+
+    // Split nonce and ciphertext
+    let (nonce_bytes, ciphertext) = combined.split_at(NONCE_SIZE);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..KEY_SIZE]);
+    let cipher = Aes256Gcm::new(&key);
+
+    // Decrypt the data
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|e| AppError::Encryption(format!("Failed to decrypt data: {}", e)))
+}
+
+/// Cost parameters for Argon2id key derivation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Derive a 256-bit key from a passphrase with Argon2id
+pub fn derive_key(passphrase: &str, salt: &[u8], params: &KdfParams) -> Result<Vec<u8>, AppError> {
+    let params = Params::new(params.memory_kib, params.iterations, params.parallelism, Some(KEY_SIZE))
+        .map_err(|e| AppError::Encryption(format!("Invalid key derivation parameters: {}", e)))?;
+
+    let mut key = vec![0u8; KEY_SIZE];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Encryption(format!("Failed to derive key: {}", e)))?;
+
+    Ok(key)
+}
+
+/// Generate a random salt
+pub fn generate_salt() -> Vec<u8> {
+    let mut salt = vec![0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let original = b"Hello, World! This is a secret message.";
+
+        let encrypted = encrypt(original).expect("Failed to encrypt");
+        assert_ne!(BASE64.encode(original), encrypted);
+
+        let decrypted = decrypt(&encrypted).expect("Failed to decrypt");
+        assert_eq!(original.to_vec(), decrypted);
+    }
+
+    #[test]
+    fn test_encrypt_different_nonces() {
+        let data = b"Test data";
+
+        let encrypted1 = encrypt(data).expect("Failed to encrypt");
+        let encrypted2 = encrypt(data).expect("Failed to encrypt");
+
+        // Same data encrypted twice should produce different results due to random nonces
+        assert_ne!(encrypted1, encrypted2);
+
+        // But both should decrypt to the same original data
+        let decrypted1 = decrypt(&encrypted1).expect("Failed to decrypt");
+        let decrypted2 = decrypt(&encrypted2).expect("Failed to decrypt");
+
+        assert_eq!(decrypted1, decrypted2);
+        assert_eq!(decrypted1, data.to_vec());
+    }
+
+    #[test]
+    fn test_invalid_encrypted_data() {
+        let result = decrypt("invalid_base64!");
+        assert!(result.is_err());
+
+        let result = decrypt("dG9vc2hvcnQ="); // "tooshort" in base64
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_legacy_data_needs_legacy_key() {
+        let legacy_key = derive_key_from_password(LEGACY_PASSPHRASE);
+        let encrypted = encrypt_with_key(&legacy_key, b"old profiles").unwrap();
+
+        assert!(decrypt(&encrypted).is_err());
+        assert_eq!(decrypt_legacy(&encrypted).unwrap(), b"old profiles");
+    }
+
+    #[test]
+    fn test_key_use_is_not_activity() {
+        record_activity();
+        std::thread::sleep(Duration::from_millis(20));
+        encrypt(b"background job").unwrap();
+        assert!(idle_time() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_derive_key() {
+        let params = KdfParams { memory_kib: 1024, iterations: 1, parallelism: 1 };
+        let salt = generate_salt();
+
+        let key = derive_key("correct horse", &salt, &params).unwrap();
+        assert_eq!(key.len(), KEY_SIZE);
+        assert_eq!(key, derive_key("correct horse", &salt, &params).unwrap());
+        assert_ne!(key, derive_key("wrong horse", &salt, &params).unwrap());
+
+        let encrypted = encrypt_with_key(&key, b"secret").unwrap();
+        assert_eq!(decrypt_with_key(&key, &encrypted).unwrap(), b"secret");
+
+        let other = derive_key("wrong horse", &salt, &params).unwrap();
+        assert!(decrypt_with_key(&other, &encrypted).is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,122 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use crate::error::AppError;
+use super::{crypto, safe_file};
+
+/// JSON file encrypted with the profile key
+///
+/// Used for data that is as sensitive as the profiles themselves, like query text,
+/// which often contains literal credentials and personal data. Like the profile
+/// file it cannot be read while the profiles are locked.
+pub struct EncryptedJsonFile {
+    path: PathBuf,
+}
+
+impl EncryptedJsonFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Decrypt and parse the file; a missing file gives the default value
+    pub fn load<T: DeserializeOwned + Default>(&self) -> Result<T, AppError> {
+        let _lock = safe_file::FileLock::acquire(&self.path)?;
+        self.read()
+    }
+
+    /// Change the contents in one locked read-modify-write cycle
+    pub fn update<T, R>(&self, change: impl FnOnce(&mut T) -> Result<R, AppError>) -> Result<R, AppError>
+    where
+        T: Serialize + DeserializeOwned + Default,
+    {
+        let _lock = safe_file::FileLock::acquire(&self.path)?;
+        let mut value = self.read()?;
+        let result = change(&mut value)?;
+        self.write(&value)?;
+        Ok(result)
+    }
+
+    /// Re-encrypt the file from one key to another, e.g. when a master password is set
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        let _lock = safe_file::FileLock::acquire(&self.path)?;
+        let Some(encrypted) = self.read_raw()? else {
+            return Ok(());
+        };
+
+        let decrypted = crypto::decrypt_with_key(from, &encrypted)?;
+        let encrypted = crypto::encrypt_with_key(to, &decrypted)?;
+        safe_file::write_atomic(&self.path, encrypted.as_bytes())
+    }
+
+    fn read_raw(&self) -> Result<Option<String>, AppError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let data = fs::read_to_string(&self.path)
+            .map_err(|e| AppError::Storage(format!("Failed to read {}: {}", self.path.display(), e)))?;
+        Ok(Some(data).filter(|data| !data.trim().is_empty()))
+    }
+
+    fn read<T: DeserializeOwned + Default>(&self) -> Result<T, AppError> {
+        let Some(encrypted) = self.read_raw()? else {
+            return Ok(T::default());
+        };
+
+        let decrypted = crypto::decrypt(&encrypted)?;
+        serde_json::from_slice(&decrypted)
+            .map_err(|e| AppError::Storage(format!("Failed to parse {}: {}", self.path.display(), e)))
+    }
+
+    fn write<T: Serialize>(&self, value: &T) -> Result<(), AppError> {
+        let json = serde_json::to_vec(value)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize {}: {}", self.path.display(), e)))?;
+        let encrypted = crypto::encrypt(&json)?;
+        safe_file::write_atomic(&self.path, encrypted.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip_is_encrypted() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.encrypted");
+        let file = EncryptedJsonFile::new(path.clone());
+
+        assert_eq!(file.load::<Vec<String>>().unwrap(), Vec::<String>::new());
+
+        file.update(|notes: &mut Vec<String>| {
+            notes.push("password = 'hunter2'".to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(file.load::<Vec<String>>().unwrap(), vec!["password = 'hunter2'".to_string()]);
+        assert!(!fs::read_to_string(&path).unwrap().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_reencrypt() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.encrypted");
+        let file = EncryptedJsonFile::new(path.clone());
+        file.update(|notes: &mut Vec<String>| {
+            notes.push("SELECT 1".to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        let current = crypto::get_or_create_key().unwrap();
+        let other = crypto::generate_key();
+        file.reencrypt(&current, &other).unwrap();
+
+        let encrypted = fs::read_to_string(&path).unwrap();
+        assert!(crypto::decrypt_with_key(&other, &encrypted).is_ok());
+        assert!(crypto::decrypt_with_key(&current, &encrypted).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+use crate::error::AppError;
+use super::encrypted_file::EncryptedJsonFile;
+
+const HISTORY_FILE: &str = "history.encrypted";
+
+/// Number of queries kept in the history
+const HISTORY_LIMIT: usize = 1000;
+
+/// One script run from the query editor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryHistoryEntry {
+    pub id: String,
+    pub executed_at: DateTime<Utc>,
+    /// Not set for connections made without a profile
+    pub profile_id: Option<String>,
+    pub query: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl QueryHistoryEntry {
+    pub fn new(profile_id: Option<String>, query: String, error: Option<String>, duration_ms: u64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            executed_at: Utc::now(),
+            profile_id,
+            query,
+            success: error.is_none(),
+            error,
+            duration_ms,
+        }
+    }
+}
+
+/// Query history, encrypted with the profile key
+pub struct QueryHistoryStore {
+    file: EncryptedJsonFile,
+}
+
+impl QueryHistoryStore {
+    pub fn new(profiles_dir: &Path) -> Self {
+        Self {
+            file: EncryptedJsonFile::new(profiles_dir.join(HISTORY_FILE)),
+        }
+    }
+
+    /// Add an entry, dropping the oldest ones beyond the limit
+    pub fn record(&self, entry: QueryHistoryEntry) -> Result<(), AppError> {
+        self.file.update(|entries: &mut Vec<QueryHistoryEntry>| {
+            entries.insert(0, entry);
+            entries.truncate(HISTORY_LIMIT);
+            Ok(())
+        })
+    }
+
+    /// Newest entries first, optionally only those of one profile
+    pub fn list(&self, profile_id: Option<&str>, limit: usize) -> Result<Vec<QueryHistoryEntry>, AppError> {
+        let entries: Vec<QueryHistoryEntry> = self.file.load()?;
+        Ok(entries
+            .into_iter()
+            .filter(|e| profile_id.is_none() || e.profile_id.as_deref() == profile_id)
+            .take(limit)
+            .collect())
+    }
+
+    /// Remove all entries, or only those of one profile
+    pub fn clear(&self, profile_id: Option<&str>) -> Result<(), AppError> {
+        self.file.update(|entries: &mut Vec<QueryHistoryEntry>| {
+            entries.retain(|e| profile_id.is_some() && e.profile_id.as_deref() != profile_id);
+            Ok(())
+        })
+    }
+
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.file.reencrypt(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_list_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = QueryHistoryStore::new(temp_dir.path());
+
+        store.record(QueryHistoryEntry::new(Some("a".to_string()), "SELECT 1".to_string(), None, 2)).unwrap();
+        store.record(QueryHistoryEntry::new(Some("b".to_string()), "SELECT x".to_string(), Some("no column x".to_string()), 1)).unwrap();
+
+        let all = store.list(None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].query, "SELECT x");
+        assert!(!all[0].success);
+
+        assert_eq!(store.list(Some("a"), 10).unwrap().len(), 1);
+
+        store.clear(Some("a")).unwrap();
+        assert_eq!(store.list(None, 10).unwrap().len(), 1);
+        store.clear(None).unwrap();
+        assert!(store.list(None, 10).unwrap().is_empty());
+    }
+}
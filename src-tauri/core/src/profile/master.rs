@@ -0,0 +1,169 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use crate::error::AppError;
+use super::crypto::{self, KdfParams};
+
+const SECURITY_FILE: &str = "security.json";
+
+/// Known plaintext encrypted with the derived key, used to check a password
+const VERIFIER_PLAINTEXT: &[u8] = b"dataforge-master-password";
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+fn default_lock_after_minutes() -> u32 {
+    15
+}
+
+/// Key derivation inputs for the master password
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterPassword {
+    /// Per-install random salt, base64-encoded
+    pub salt: String,
+    pub kdf: KdfParams,
+    pub verifier: String,
+}
+
+impl MasterPassword {
+    /// Set up a new master password, returning its settings and the derived key
+    pub fn create(password: &str, kdf: KdfParams) -> Result<(Self, Vec<u8>), AppError> {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(AppError::Validation(format!(
+                "Master password must be at least {} characters",
+                MIN_PASSWORD_LENGTH
+            )));
+        }
+
+        let salt = crypto::generate_salt();
+        let key = crypto::derive_key(password, &salt, &kdf)?;
+        let verifier = crypto::encrypt_with_key(&key, VERIFIER_PLAINTEXT)?;
+
+        Ok((
+            Self {
+                salt: BASE64.encode(&salt),
+                kdf,
+                verifier,
+            },
+            key,
+        ))
+    }
+
+    /// Derive the key for `password`, failing if it is not the master password
+    pub fn unlock(&self, password: &str) -> Result<Vec<u8>, AppError> {
+        let salt = BASE64
+            .decode(&self.salt)
+            .map_err(|e| AppError::Encryption(format!("Failed to decode base64: {}", e)))?;
+        let key = crypto::derive_key(password, &salt, &self.kdf)?;
+
+        match crypto::decrypt_with_key(&key, &self.verifier) {
+            Ok(plaintext) if plaintext == VERIFIER_PLAINTEXT => Ok(key),
+            _ => Err(AppError::Auth("Wrong master password".to_string())),
+        }
+    }
+}
+
+/// How the profile file is protected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecuritySettings {
+    /// Set when the profile key is derived from a master password instead of the keyring
+    #[serde(default)]
+    pub master_password: Option<MasterPassword>,
+    /// Lock the profiles after this many idle minutes; 0 never locks. Only applies
+    /// with a master password, since the keyring key cannot be locked away
+    #[serde(default = "default_lock_after_minutes")]
+    pub lock_after_minutes: u32,
+}
+
+impl Default for SecuritySettings {
+    fn default() -> Self {
+        Self {
+            master_password: None,
+            lock_after_minutes: default_lock_after_minutes(),
+        }
+    }
+}
+
+/// Lock state reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct LockStatus {
+    pub password_enabled: bool,
+    pub locked: bool,
+    pub lock_after_minutes: u32,
+    /// Whether the profiles lock after `lock_after_minutes`; without a master password
+    /// they stay unlocked, so the settings can say so
+    pub locks_when_idle: bool,
+}
+
+/// JSON file holding the security settings next to the profile file
+pub struct SecurityStore {
+    settings_path: PathBuf,
+}
+
+impl SecurityStore {
+    /// Open the store in the profiles directory
+    pub fn new(profiles_dir: PathBuf) -> Self {
+        Self {
+            settings_path: profiles_dir.join(SECURITY_FILE),
+        }
+    }
+
+    pub fn load(&self) -> Result<SecuritySettings, AppError> {
+        if !self.settings_path.exists() {
+            return Ok(SecuritySettings::default());
+        }
+
+        let data = fs::read_to_string(&self.settings_path).map_err(|e| {
+            AppError::Storage(format!("Failed to read security settings: {}", e))
+        })?;
+        serde_json::from_str(&data)
+            .map_err(|e| AppError::Storage(format!("Failed to parse security settings: {}", e)))
+    }
+
+    pub fn save(&self, settings: &SecuritySettings) -> Result<(), AppError> {
+        let data = serde_json::to_string_pretty(settings).map_err(|e| {
+            AppError::Storage(format!("Failed to serialize security settings: {}", e))
+        })?;
+        fs::write(&self.settings_path, data).map_err(|e| {
+            AppError::Storage(format!("Failed to write security settings: {}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_params() -> KdfParams {
+        KdfParams { memory_kib: 1024, iterations: 1, parallelism: 1 }
+    }
+
+    #[test]
+    fn test_master_password() {
+        let (master, key) = MasterPassword::create("open sesame", test_params()).unwrap();
+
+        assert_eq!(master.unlock("open sesame").unwrap(), key);
+        assert!(matches!(master.unlock("close sesame"), Err(AppError::Auth(_))));
+        assert!(MasterPassword::create("short", test_params()).is_err());
+    }
+
+    #[test]
+    fn test_security_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SecurityStore::new(temp_dir.path().to_path_buf());
+
+        let settings = store.load().unwrap();
+        assert!(settings.master_password.is_none());
+        assert_eq!(settings.lock_after_minutes, 15);
+
+        let (master, _) = MasterPassword::create("open sesame", test_params()).unwrap();
+        store
+            .save(&SecuritySettings { master_password: Some(master), lock_after_minutes: 5 })
+            .unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.lock_after_minutes, 5);
+        assert!(loaded.master_password.unwrap().unlock("open sesame").is_ok());
+    }
+}
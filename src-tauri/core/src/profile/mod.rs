@@ -0,0 +1,672 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::database::adapter::{ConnectionParams, DatabaseType};
+use crate::database::templates::QueryTemplate;
+use crate::error::AppError;
+
+pub mod storage;
+pub mod crypto;
+pub mod bundle;
+pub mod placeholders;
+pub mod master;
+pub mod vault;
+pub mod usage;
+pub mod safe_file;
+pub mod ssh;
+pub mod policy;
+pub mod rules;
+pub mod encrypted_file;
+pub mod history;
+pub mod snippets;
+pub mod automations;
+pub mod templates;
+pub mod secret_ref;
+pub mod retry;
+pub mod workspace;
+pub mod bookmarks;
+pub mod recent_objects;
+
+/// Connection profile that stores database connection information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub id: String,
+    pub name: String,
+    pub database_type: DatabaseType,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub database: String,
+    pub username: Option<String>,
+    /// External secret the password is read from instead of the keyring
+    #[serde(default)]
+    pub password_ref: Option<secret_ref::SecretReference>,
+    pub ssl_mode: Option<String>,
+    /// Schema (PostgreSQL) or database (MySQL) to switch to after connecting
+    #[serde(default)]
+    pub default_schema: Option<String>,
+    /// SSH tunnel to connect through
+    #[serde(default)]
+    pub ssh_tunnel: Option<ssh::SshTunnelConfig>,
+    /// Rules for statements run on this connection
+    #[serde(default)]
+    pub audit_policy: policy::AuditPolicy,
+    /// Only statements that read data may run on this connection
+    #[serde(default)]
+    pub read_only: bool,
+    /// Retrying read-only statements after transient errors
+    #[serde(default)]
+    pub retry_policy: retry::RetryPolicy,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    /// Pinned profiles are listed before all others
+    #[serde(default)]
+    pub pinned: bool,
+    /// Position in the manually arranged list; unarranged profiles follow by name
+    #[serde(default)]
+    pub sort_order: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_connected: Option<DateTime<Utc>>,
+}
+
+impl ConnectionProfile {
+    /// Create a new connection profile
+    pub fn new(name: String, database_type: DatabaseType, database: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            database_type,
+            host: if database_type.requires_host() {
+                Some("localhost".to_string())
+            } else {
+                None
+            },
+            port: database_type.default_port(),
+            database,
+            username: None,
+            password_ref: None,
+            ssl_mode: None,
+            default_schema: None,
+            ssh_tunnel: None,
+            audit_policy: policy::AuditPolicy::default(),
+            read_only: false,
+            retry_policy: retry::RetryPolicy::default(),
+            color: None,
+            icon: None,
+            pinned: false,
+            sort_order: None,
+            created_at: now,
+            updated_at: now,
+            last_connected: None,
+        }
+    }
+
+    /// Convert profile to connection parameters
+    pub fn to_connection_params(&self) -> ConnectionParams {
+        ConnectionParams {
+            database_type: self.database_type,
+            host: self.host.clone(),
+            port: self.port,
+            database: self.database.clone(),
+            username: self.username.clone(),
+            password: None, // Password is retrieved separately from keyring
+            ssl_mode: self.ssl_mode.clone(),
+            connection_timeout: Some(5),
+            max_connections: Some(5),
+            min_connections: Some(1),
+            additional_params: std::collections::HashMap::new(),
+            default_schema: self.default_schema.clone(),
+        }
+    }
+
+    /// Update the last connected timestamp
+    pub fn update_last_connected(&mut self) {
+        self.last_connected = Some(Utc::now());
+        self.updated_at = Utc::now();
+    }
+}
+
+/// A profile's password comes either from the keyring or from a secret manager
+fn check_password_source(profile: &ConnectionProfile, password: Option<&str>) -> Result<(), AppError> {
+    if let Some(reference) = profile.password_ref.as_ref() {
+        reference.validate()?;
+        if password.is_some() {
+            return Err(AppError::Validation(
+                "A profile that reads its password from a secret manager cannot store one".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Sort profiles for display: pinned first, then by manual order, then by name
+pub fn sort_profiles(profiles: &mut [ConnectionProfile]) {
+    profiles.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| match (a.sort_order, b.sort_order) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+/// Give the listed profiles consecutive positions; profiles not listed keep their
+/// relative order after them
+pub fn apply_order(profiles: &mut [ConnectionProfile], ordered_ids: &[String]) {
+    sort_profiles(profiles);
+
+    let mut next = ordered_ids.len() as u32;
+    for profile in profiles.iter_mut() {
+        match ordered_ids.iter().position(|id| *id == profile.id) {
+            Some(position) => profile.sort_order = Some(position as u32),
+            None => {
+                profile.sort_order = Some(next);
+                next += 1;
+            }
+        }
+    }
+}
+
+/// Profile manager that handles all profile operations
+pub struct ProfileManager {
+    storage: storage::ProfileStorage,
+    security: master::SecurityStore,
+    usage: usage::UsageStore,
+    history: history::QueryHistoryStore,
+    snippets: snippets::SnippetStore,
+    automations: automations::AutomationStore,
+    templates: templates::TemplateStore,
+    workspace: workspace::WorkspaceStore,
+    bookmarks: bookmarks::BookmarkStore,
+    recent_objects: recent_objects::RecentObjectStore,
+}
+
+impl ProfileManager {
+    /// Create a new profile manager keeping its files in `app_data_dir`
+    pub fn new(app_data_dir: &std::path::Path) -> Result<Self, AppError> {
+        let storage = storage::ProfileStorage::new(app_data_dir)?;
+        let security = master::SecurityStore::new(storage.profiles_dir());
+        let usage = usage::UsageStore::new(storage.profiles_dir());
+        let history = history::QueryHistoryStore::new(&storage.profiles_dir());
+        let snippets = snippets::SnippetStore::new(&storage.profiles_dir());
+        let automations = automations::AutomationStore::new(&storage.profiles_dir());
+        let templates = templates::TemplateStore::new(&storage.profiles_dir());
+        let workspace = workspace::WorkspaceStore::new(&storage.profiles_dir());
+        let bookmarks = bookmarks::BookmarkStore::new(&storage.profiles_dir());
+        let recent_objects = recent_objects::RecentObjectStore::new(&storage.profiles_dir());
+
+        // The manager is created before any connection of this run, so open ones are stale
+        usage.update(|stats| stats.close_stale())?;
+
+        // With a master password the profiles stay locked until unlocked
+        crypto::set_password_mode(security.load()?.master_password.is_some());
+
+        Ok(Self { storage, security, usage, history, snippets, automations, templates, workspace, bookmarks, recent_objects })
+    }
+
+    /// Create and save a new profile
+    pub async fn create_profile(&self, profile: ConnectionProfile, password: Option<String>) -> Result<ConnectionProfile, AppError> {
+        if let Some(tunnel) = profile.ssh_tunnel.as_ref() {
+            tunnel.validate()?;
+        }
+        check_password_source(&profile, password.as_deref())?;
+
+        // Save password to keyring if provided
+        if let Some(pwd) = password.as_ref() {
+            self.storage.save_password(&profile.id, pwd)?;
+        }
+
+        // Save profile to storage
+        self.storage.save_profile(&profile).await?;
+
+        Ok(profile)
+    }
+
+    /// List all profiles in display order
+    pub async fn list_profiles(&self) -> Result<Vec<ConnectionProfile>, AppError> {
+        let mut profiles = self.storage.list_profiles().await?;
+        sort_profiles(&mut profiles);
+        Ok(profiles)
+    }
+
+    /// Pin or unpin a profile
+    pub async fn set_pinned(&self, id: &str, pinned: bool) -> Result<ConnectionProfile, AppError> {
+        let mut updated = None;
+        self.storage
+            .modify_profiles(|profiles| {
+                let profile = profiles
+                    .iter_mut()
+                    .find(|p| p.id == id)
+                    .ok_or_else(|| AppError::Storage(format!("Profile {} not found", id)))?;
+                profile.pinned = pinned;
+                updated = Some(profile.clone());
+                Ok(())
+            })
+            .await?;
+
+        updated.ok_or_else(|| AppError::Storage(format!("Profile {} not found", id)))
+    }
+
+    /// Arrange profiles in the given order
+    pub async fn reorder_profiles(&self, ordered_ids: &[String]) -> Result<Vec<ConnectionProfile>, AppError> {
+        self.storage
+            .modify_profiles(|profiles| {
+                apply_order(profiles, ordered_ids);
+                Ok(())
+            })
+            .await?;
+
+        self.list_profiles().await
+    }
+
+    /// Get a specific profile by ID
+    pub async fn get_profile(&self, id: &str) -> Result<ConnectionProfile, AppError> {
+        self.storage.get_profile(id).await
+    }
+
+    /// Update an existing profile
+    pub async fn update_profile(&self, mut profile: ConnectionProfile, password: Option<String>) -> Result<ConnectionProfile, AppError> {
+        profile.updated_at = Utc::now();
+
+        match profile.ssh_tunnel.as_ref() {
+            Some(tunnel) => tunnel.validate()?,
+            None => self.storage.delete_ssh_passphrase(&profile.id)?,
+        }
+        check_password_source(&profile, password.as_deref())?;
+
+        // A password from a secret manager must not linger in the keyring
+        if profile.password_ref.is_some() {
+            self.storage.delete_password(&profile.id)?;
+        }
+
+        // Update password if provided
+        if let Some(pwd) = password.as_ref() {
+            self.storage.save_password(&profile.id, pwd)?;
+        }
+
+        // Update profile in storage
+        self.storage.update_profile(&profile).await?;
+
+        Ok(profile)
+    }
+
+    /// Delete a profile
+    pub async fn delete_profile(&self, id: &str) -> Result<(), AppError> {
+        // Delete password from keyring
+        self.storage.delete_password(id)?;
+        self.storage.delete_ssh_passphrase(id)?;
+
+        // Delete profile from storage
+        self.storage.delete_profile(id).await?;
+
+        self.usage.update(|stats| stats.remove_profile(id))?;
+        self.bookmarks.remove_profile(id)?;
+        self.recent_objects.remove_profile(id)?;
+
+        Ok(())
+    }
+
+    /// Store the SSH key passphrase (or SSH password) of a profile's tunnel
+    pub fn save_ssh_passphrase(&self, id: &str, passphrase: &str) -> Result<(), AppError> {
+        self.storage.save_ssh_passphrase(id, passphrase)
+    }
+
+    /// Collect profiles for a bundle, optionally with their keyring passwords
+    pub async fn export_bundle(&self, ids: &[String], include_passwords: bool) -> Result<bundle::ProfileBundle, AppError> {
+        let mut profiles = Vec::with_capacity(ids.len());
+        for id in ids {
+            let profile = self.get_profile(id).await?;
+            let (password, ssh_passphrase) = if include_passwords {
+                (self.storage.get_password(id).ok(), self.storage.get_ssh_passphrase(id)?)
+            } else {
+                (None, None)
+            };
+            profiles.push(bundle::BundledProfile { profile, password, ssh_passphrase });
+        }
+
+        Ok(bundle::ProfileBundle::new(profiles))
+    }
+
+    /// Save the profiles of a bundle
+    ///
+    /// Profiles whose ID already exists are replaced when `overwrite` is set and
+    /// imported as copies with a new ID otherwise.
+    pub async fn import_bundle(&self, bundle: bundle::ProfileBundle, overwrite: bool) -> Result<Vec<ConnectionProfile>, AppError> {
+        let existing: Vec<String> = self.list_profiles().await?.into_iter().map(|p| p.id).collect();
+
+        let mut imported = Vec::with_capacity(bundle.profiles.len());
+        for bundled in bundle.profiles {
+            let mut profile = bundled.profile;
+            if existing.contains(&profile.id) && !overwrite {
+                profile.id = Uuid::new_v4().to_string();
+            }
+            profile.last_connected = None;
+            profile.updated_at = Utc::now();
+
+            let profile = self.create_profile(profile, bundled.password).await?;
+            if let Some(passphrase) = bundled.ssh_passphrase.as_deref() {
+                self.save_ssh_passphrase(&profile.id, passphrase)?;
+            }
+            imported.push(profile);
+        }
+
+        Ok(imported)
+    }
+
+    /// Record that a connection was opened with a profile
+    pub fn record_connect(&self, id: &str) -> Result<(), AppError> {
+        self.usage.update(|stats| stats.record_connect(id, Utc::now()))
+    }
+
+    /// Record that the open profile connection was closed or replaced
+    pub fn record_disconnect(&self) -> Result<(), AppError> {
+        self.usage.update(|stats| stats.record_disconnect(Utc::now()))
+    }
+
+    /// Usage statistics of a profile
+    pub fn profile_usage(&self, id: &str) -> Result<usage::ProfileUsage, AppError> {
+        Ok(self.usage.load()?.profiles.remove(id).unwrap_or_default())
+    }
+
+    /// The most recently used profiles, newest first
+    pub async fn recent_profiles(&self, limit: usize) -> Result<Vec<ConnectionProfile>, AppError> {
+        let profiles = self.list_profiles().await?;
+        Ok(self
+            .usage
+            .load()?
+            .recent_profile_ids(limit)
+            .into_iter()
+            .filter_map(|id| profiles.iter().find(|p| p.id == id).cloned())
+            .collect())
+    }
+
+    /// Whether a master password is set and the profiles are currently locked
+    pub fn lock_status(&self) -> Result<master::LockStatus, AppError> {
+        let settings = self.security.load()?;
+        Ok(master::LockStatus {
+            password_enabled: settings.master_password.is_some(),
+            locked: settings.master_password.is_some() && !crypto::is_unlocked(),
+            lock_after_minutes: settings.lock_after_minutes,
+            locks_when_idle: settings.master_password.is_some() && settings.lock_after_minutes > 0,
+        })
+    }
+
+    /// Unlock the profiles with the master password
+    pub fn unlock(&self, password: &str) -> Result<(), AppError> {
+        let master = self.security.load()?.master_password.ok_or_else(|| {
+            AppError::Validation("No master password is set".to_string())
+        })?;
+
+        crypto::set_active_key(Some(master.unlock(password)?));
+        Ok(())
+    }
+
+    /// Forget the derived key until the master password is entered again
+    pub fn lock(&self) -> Result<(), AppError> {
+        if self.security.load()?.master_password.is_some() {
+            crypto::set_active_key(None);
+        }
+        Ok(())
+    }
+
+    /// Switch from the keyring key to a key derived from a master password
+    pub async fn enable_master_password(&self, password: &str) -> Result<(), AppError> {
+        let mut settings = self.security.load()?;
+        if settings.master_password.is_some() {
+            return Err(AppError::Validation("A master password is already set".to_string()));
+        }
+
+        // Loading first migrates a legacy profile file to the current key
+        self.storage.list_profiles().await?;
+        let current_key = crypto::get_or_create_key()?;
+
+        let (master, key) = master::MasterPassword::create(password, crypto::KdfParams::default())?;
+        settings.master_password = Some(master);
+        self.security.save(&settings)?;
+
+        if let Err(e) = self.reencrypt_all(&current_key, &key) {
+            settings.master_password = None;
+            self.security.save(&settings)?;
+            // Files that were already converted go back to the old key
+            let _ = self.reencrypt_all(&key, &current_key);
+            return Err(e);
+        }
+
+        crypto::set_password_mode(true);
+        crypto::set_active_key(Some(key));
+        Ok(())
+    }
+
+    /// Go back to the keyring key after confirming the master password
+    pub fn disable_master_password(&self, password: &str) -> Result<(), AppError> {
+        let mut settings = self.security.load()?;
+        let master = settings.master_password.take().ok_or_else(|| {
+            AppError::Validation("No master password is set".to_string())
+        })?;
+
+        let key = master.unlock(password)?;
+        let keyring_key = crypto::keyring_key()?;
+        self.reencrypt_all(&key, &keyring_key)?;
+        self.security.save(&settings)?;
+
+        crypto::set_password_mode(false);
+        crypto::set_active_key(Some(keyring_key));
+        Ok(())
+    }
+
+    /// Re-encrypt every file protected by the profile key
+    fn reencrypt_all(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.storage.reencrypt(from, to)?;
+        self.history.reencrypt(from, to)?;
+        self.snippets.reencrypt(from, to)?;
+        self.automations.reencrypt(from, to)?;
+        self.templates.reencrypt(from, to)?;
+        self.workspace.reencrypt(from, to)?;
+        self.bookmarks.reencrypt(from, to)?;
+        self.recent_objects.reencrypt(from, to)
+    }
+
+    /// Add a script to the query history
+    pub fn record_query(&self, entry: history::QueryHistoryEntry) -> Result<(), AppError> {
+        self.history.record(entry)
+    }
+
+    /// Newest history entries first, optionally only those of one profile
+    pub fn query_history(&self, profile_id: Option<&str>, limit: usize) -> Result<Vec<history::QueryHistoryEntry>, AppError> {
+        self.history.list(profile_id, limit)
+    }
+
+    pub fn clear_query_history(&self, profile_id: Option<&str>) -> Result<(), AppError> {
+        self.history.clear(profile_id)
+    }
+
+    pub fn list_snippets(&self) -> Result<Vec<snippets::Snippet>, AppError> {
+        self.snippets.list()
+    }
+
+    pub fn save_snippet(&self, snippet: snippets::Snippet) -> Result<snippets::Snippet, AppError> {
+        self.snippets.save(snippet)
+    }
+
+    pub fn delete_snippet(&self, id: &str) -> Result<(), AppError> {
+        self.snippets.delete(id)
+    }
+
+    pub fn list_automations(&self) -> Result<Vec<automations::Automation>, AppError> {
+        self.automations.list()
+    }
+
+    pub fn get_automation(&self, id: &str) -> Result<automations::Automation, AppError> {
+        self.automations.get(id)
+    }
+
+    pub fn save_automation(&self, automation: automations::Automation) -> Result<automations::Automation, AppError> {
+        self.automations.save(automation)
+    }
+
+    pub fn restore_automation(&self, id: &str, version: u32) -> Result<automations::Automation, AppError> {
+        self.automations.restore(id, version)
+    }
+
+    pub fn delete_automation(&self, id: &str) -> Result<(), AppError> {
+        self.automations.delete(id)
+    }
+
+    pub fn load_workspace(&self) -> Result<workspace::Workspace, AppError> {
+        self.workspace.load()
+    }
+
+    pub fn save_workspace(&self, workspace: workspace::Workspace) -> Result<workspace::Workspace, AppError> {
+        self.workspace.save(workspace)
+    }
+
+    pub fn clear_workspace(&self) -> Result<(), AppError> {
+        self.workspace.clear()
+    }
+
+    pub fn list_bookmarks(&self, profile_id: &str) -> Result<bookmarks::BookmarkListing, AppError> {
+        self.bookmarks.list(profile_id)
+    }
+
+    pub fn save_bookmark(&self, bookmark: bookmarks::Bookmark) -> Result<bookmarks::Bookmark, AppError> {
+        self.bookmarks.save(bookmark)
+    }
+
+    pub fn delete_bookmark(&self, id: &str) -> Result<(), AppError> {
+        self.bookmarks.delete(id)
+    }
+
+    /// Record that a table or view was opened on a profile's connection
+    pub fn record_object_opened(
+        &self,
+        profile_id: &str,
+        kind: recent_objects::ObjectKind,
+        name: &str,
+        schema: Option<&str>,
+    ) -> Result<(), AppError> {
+        self.recent_objects.record(profile_id, kind, name, schema, Utc::now())
+    }
+
+    /// Tables and views of a profile's connection ranked by how often and how
+    /// recently they were opened
+    pub fn recent_objects(&self, profile_id: &str, limit: usize) -> Result<Vec<recent_objects::RecentObject>, AppError> {
+        self.recent_objects.ranked(profile_id, limit, Utc::now())
+    }
+
+    pub fn list_templates(&self) -> Result<Vec<QueryTemplate>, AppError> {
+        self.templates.list()
+    }
+
+    pub fn save_template(&self, template: QueryTemplate) -> Result<QueryTemplate, AppError> {
+        self.templates.save(template)
+    }
+
+    pub fn import_templates(&self, library: templates::TemplateLibrary, overwrite: bool) -> Result<Vec<QueryTemplate>, AppError> {
+        self.templates.import(library, overwrite)
+    }
+
+    pub fn delete_template(&self, id: &str) -> Result<(), AppError> {
+        self.templates.delete(id)
+    }
+
+    /// Change how many idle minutes pass before the profiles lock; 0 never locks
+    ///
+    /// Without a master password the setting is kept but has no effect; see
+    /// `LockStatus::locks_when_idle`.
+    pub fn set_lock_timeout(&self, minutes: u32) -> Result<(), AppError> {
+        let mut settings = self.security.load()?;
+        settings.lock_after_minutes = minutes;
+        self.security.save(&settings)
+    }
+
+    /// Get connection parameters with password for a profile, resolving `${VAR}` placeholders
+    pub async fn get_connection_params(&self, id: &str) -> Result<ConnectionParams, AppError> {
+        let profile = self.get_profile(id).await?;
+        let mut params = profile.to_connection_params();
+
+        // Retrieve password from the secret manager or the keyring
+        if let Some(reference) = profile.password_ref.as_ref() {
+            params.password = Some(reference.resolve().await?);
+        } else if let Ok(password) = self.storage.get_password(id) {
+            params.password = Some(password);
+        }
+
+        // Fields may refer to environment variables, e.g. "${PGHOST}"
+        placeholders::resolve_params(&mut params)?;
+
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_profile() {
+        let profile = ConnectionProfile::new(
+            "Test DB".to_string(),
+            DatabaseType::PostgreSQL,
+            "testdb".to_string(),
+        );
+
+        assert_eq!(profile.name, "Test DB");
+        assert_eq!(profile.database_type, DatabaseType::PostgreSQL);
+        assert_eq!(profile.database, "testdb");
+        assert_eq!(profile.host, Some("localhost".to_string()));
+        assert_eq!(profile.port, Some(5432));
+        assert!(profile.id.len() > 0);
+    }
+
+    #[test]
+    fn test_profile_to_connection_params() {
+        let profile = ConnectionProfile::new(
+            "Test DB".to_string(),
+            DatabaseType::PostgreSQL,
+            "testdb".to_string(),
+        );
+
+        let params = profile.to_connection_params();
+
+        assert_eq!(params.database_type, DatabaseType::PostgreSQL);
+        assert_eq!(params.database, "testdb");
+        assert_eq!(params.host, Some("localhost".to_string()));
+        assert_eq!(params.port, Some(5432));
+        assert!(params.password.is_none());
+    }
+
+    fn named(name: &str) -> ConnectionProfile {
+        ConnectionProfile::new(name.to_string(), DatabaseType::SQLite, format!("{}.db", name))
+    }
+
+    fn names(profiles: &[ConnectionProfile]) -> Vec<&str> {
+        profiles.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_profiles() {
+        let mut profiles = vec![named("delta"), named("Charlie"), named("bravo"), named("alpha")];
+        profiles[0].sort_order = Some(0);
+        profiles[1].sort_order = Some(1);
+        profiles[2].pinned = true;
+
+        sort_profiles(&mut profiles);
+        assert_eq!(names(&profiles), vec!["bravo", "delta", "Charlie", "alpha"]);
+    }
+
+    #[test]
+    fn test_apply_order() {
+        let mut profiles = vec![named("alpha"), named("bravo"), named("charlie")];
+        let ordered = vec![profiles[2].id.clone(), profiles[0].id.clone()];
+
+        apply_order(&mut profiles, &ordered);
+        sort_profiles(&mut profiles);
+        assert_eq!(names(&profiles), vec!["charlie", "alpha", "bravo"]);
+        assert_eq!(profiles[2].sort_order, Some(2));
+    }
+}
\ No newline at end of file
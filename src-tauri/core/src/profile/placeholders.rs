@@ -0,0 +1,120 @@
+use crate::database::adapter::ConnectionParams;
+use crate::error::AppError;
+
+/// Replace `${VAR}` and `${VAR:-default}` placeholders using `lookup`
+///
+/// `$${` produces a literal `${`; any other `$` is left alone. A placeholder whose variable is unset and has no
+/// default is an error, so a missing variable is reported at connect time instead
+/// of turning into an empty host or user name.
+pub fn expand(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, AppError> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        if let Some(after_escape) = after.strip_prefix("${") {
+            expanded.push_str("${");
+            rest = after_escape;
+            continue;
+        }
+
+        let Some(body) = after.strip_prefix('{') else {
+            expanded.push('$');
+            rest = after;
+            continue;
+        };
+        let end = body.find('}').ok_or_else(|| {
+            AppError::Config(format!("Unclosed placeholder in \"{}\"", value))
+        })?;
+
+        let (name, default) = match body[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&body[..end], None),
+        };
+        if name.is_empty() {
+            return Err(AppError::Config(format!("Empty placeholder in \"{}\"", value)));
+        }
+
+        // Like the shell, `:-` also applies when the variable is set but empty
+        match (lookup(name), default) {
+            (Some(resolved), Some(default)) if resolved.is_empty() => expanded.push_str(default),
+            (Some(resolved), _) => expanded.push_str(&resolved),
+            (None, Some(default)) => expanded.push_str(default),
+            (None, None) => {
+                return Err(AppError::Config(format!("Environment variable {} is not set", name)))
+            }
+        }
+        rest = &body[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Expand placeholders from the process environment
+pub fn expand_env(value: &str) -> Result<String, AppError> {
+    expand(value, |name| std::env::var(name).ok())
+}
+
+/// Resolve placeholders in every text field of connection parameters
+pub fn resolve_params(params: &mut ConnectionParams) -> Result<(), AppError> {
+    params.database = expand_env(&params.database)?;
+
+    for field in [
+        &mut params.host,
+        &mut params.username,
+        &mut params.password,
+        &mut params.ssl_mode,
+    ] {
+        if let Some(value) = field.as_mut() {
+            *value = expand_env(value)?;
+        }
+    }
+
+    for value in params.additional_params.values_mut() {
+        *value = expand_env(value)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "DB_HOST" => Some("db.internal".to_string()),
+            "DB_USER" => Some("ci".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        assert_eq!(expand("${DB_HOST}", lookup).unwrap(), "db.internal");
+        assert_eq!(expand("${DB_USER}@${DB_HOST}:5432", lookup).unwrap(), "ci@db.internal:5432");
+        assert_eq!(expand("plain-value", lookup).unwrap(), "plain-value");
+        assert_eq!(expand("pa$$word", lookup).unwrap(), "pa$$word");
+        assert_eq!(expand("$${DB_HOST}", lookup).unwrap(), "${DB_HOST}");
+        assert_eq!(expand("cost$5", lookup).unwrap(), "cost$5");
+    }
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(expand("${DB_PORT:-5432}", lookup).unwrap(), "5432");
+        assert_eq!(expand("${EMPTY:-fallback}", lookup).unwrap(), "fallback");
+        assert_eq!(expand("${DB_HOST:-localhost}", lookup).unwrap(), "db.internal");
+        assert_eq!(expand("${EMPTY}", lookup).unwrap(), "");
+    }
+
+    #[test]
+    fn test_missing_variables() {
+        assert!(matches!(expand("${MISSING}", lookup), Err(AppError::Config(_))));
+        assert!(expand("${DB_HOST", lookup).is_err());
+        assert!(expand("${}", lookup).is_err());
+    }
+}
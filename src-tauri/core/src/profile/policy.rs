@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use crate::database::statement::StatementKind;
+use crate::error::AppError;
+
+/// Per-profile rules for statements run through `execute_query`
+///
+/// The rules are enforced by the backend, so a frontend bug cannot bypass them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditPolicy {
+    /// Write every executed statement to the application log
+    pub log_statements: bool,
+    /// Schema changes only run once the user has confirmed them
+    pub confirm_ddl: bool,
+    /// Refuse UPDATE and DELETE statements without a WHERE clause
+    pub block_unfiltered_dml: bool,
+}
+
+impl AuditPolicy {
+    /// Check whether `statement` may run under this policy
+    pub fn check(&self, statement: &str, kind: StatementKind, force: bool) -> Result<(), AppError> {
+        if self.block_unfiltered_dml && kind.is_unfiltered_dml() {
+            return Err(AppError::PermissionDenied(format!(
+                "UPDATE and DELETE without a WHERE clause are blocked for this connection: {}",
+                statement
+            )));
+        }
+
+        if self.confirm_ddl && kind.is_ddl() && !force {
+            return Err(AppError::ConfirmationRequired(format!(
+                "Schema changes must be confirmed for this connection: {}",
+                statement
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_allows_everything() {
+        let policy = AuditPolicy::default();
+        assert!(policy.check("DELETE FROM t", StatementKind::Delete { filtered: false }, false).is_ok());
+        assert!(policy.check("DROP TABLE t", StatementKind::Ddl { destructive: true }, false).is_ok());
+    }
+
+    #[test]
+    fn test_check() {
+        let policy = AuditPolicy {
+            log_statements: false,
+            confirm_ddl: true,
+            block_unfiltered_dml: true,
+        };
+
+        let unfiltered = StatementKind::Update { filtered: false };
+        assert!(matches!(policy.check("UPDATE t SET a = 1", unfiltered, true), Err(AppError::PermissionDenied(_))));
+        assert!(policy.check("UPDATE t SET a = 1 WHERE id = 1", StatementKind::Update { filtered: true }, false).is_ok());
+
+        let create = StatementKind::Ddl { destructive: false };
+        assert!(matches!(policy.check("CREATE TABLE t (a int)", create, false), Err(AppError::ConfirmationRequired(_))));
+        assert!(policy.check("CREATE TABLE t (a int)", create, true).is_ok());
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::error::AppError;
+use super::encrypted_file::EncryptedJsonFile;
+
+const RECENT_OBJECTS_FILE: &str = "recent_objects.encrypted";
+
+/// Time after which an opening counts half as much towards an object's rank
+const HALF_LIFE_HOURS: f64 = 72.0;
+
+/// Objects remembered per connection; the lowest ranked are forgotten first
+const OBJECTS_PER_PROFILE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    Table,
+    View,
+}
+
+/// Table or view opened on one connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentObject {
+    pub profile_id: String,
+    pub kind: ObjectKind,
+    pub name: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub open_count: u64,
+    pub last_opened: DateTime<Utc>,
+    /// Openings weighted by their age, as of `last_opened`
+    pub score: f64,
+}
+
+impl RecentObject {
+    fn is(&self, profile_id: &str, kind: ObjectKind, name: &str, schema: Option<&str>) -> bool {
+        self.profile_id == profile_id && self.kind == kind && self.name == name && self.schema.as_deref() == schema
+    }
+
+    /// Score decayed from `last_opened` to `now`
+    pub fn score_at(&self, now: DateTime<Utc>) -> f64 {
+        let hours = (now - self.last_opened).num_seconds().max(0) as f64 / 3600.0;
+        self.score * 0.5f64.powf(hours / HALF_LIFE_HOURS)
+    }
+}
+
+/// Recently opened tables and views of all connections, encrypted with the profile key
+pub struct RecentObjectStore {
+    file: EncryptedJsonFile,
+}
+
+impl RecentObjectStore {
+    pub fn new(profiles_dir: &Path) -> Self {
+        Self {
+            file: EncryptedJsonFile::new(profiles_dir.join(RECENT_OBJECTS_FILE)),
+        }
+    }
+
+    /// Count an opening of a table or view
+    pub fn record(
+        &self,
+        profile_id: &str,
+        kind: ObjectKind,
+        name: &str,
+        schema: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        if name.trim().is_empty() {
+            return Err(AppError::Validation("Object name is required".to_string()));
+        }
+
+        self.file.update(|objects: &mut Vec<RecentObject>| {
+            match objects.iter_mut().find(|o| o.is(profile_id, kind, name, schema)) {
+                Some(object) => {
+                    object.score = object.score_at(now) + 1.0;
+                    object.open_count += 1;
+                    object.last_opened = now;
+                }
+                None => objects.push(RecentObject {
+                    profile_id: profile_id.to_string(),
+                    kind,
+                    name: name.to_string(),
+                    schema: schema.map(str::to_string),
+                    open_count: 1,
+                    last_opened: now,
+                    score: 1.0,
+                }),
+            }
+
+            if objects.iter().filter(|o| o.profile_id == profile_id).count() > OBJECTS_PER_PROFILE {
+                let lowest = objects
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, o)| o.profile_id == profile_id)
+                    .min_by(|(_, a), (_, b)| a.score_at(now).total_cmp(&b.score_at(now)))
+                    .map(|(i, _)| i);
+                if let Some(i) = lowest {
+                    objects.remove(i);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Objects of a connection, the most often and most recently opened first
+    pub fn ranked(&self, profile_id: &str, limit: usize, now: DateTime<Utc>) -> Result<Vec<RecentObject>, AppError> {
+        let mut objects: Vec<RecentObject> = self.file.load()?;
+        objects.retain(|o| o.profile_id == profile_id);
+        objects.sort_by(|a, b| {
+            b.score_at(now)
+                .total_cmp(&a.score_at(now))
+                .then(b.last_opened.cmp(&a.last_opened))
+        });
+        objects.truncate(limit);
+        for object in &mut objects {
+            object.score = object.score_at(now);
+        }
+        Ok(objects)
+    }
+
+    /// Forget the objects of a deleted profile
+    pub fn remove_profile(&self, profile_id: &str) -> Result<(), AppError> {
+        self.file.update(|objects: &mut Vec<RecentObject>| {
+            objects.retain(|o| o.profile_id != profile_id);
+            Ok(())
+        })
+    }
+
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.file.reencrypt(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_decay_ranking() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = RecentObjectStore::new(temp_dir.path());
+        let now = Utc::now();
+        let week_ago = now - Duration::days(7);
+
+        // Opened often a week ago, but once today outranks it
+        for _ in 0..3 {
+            store.record("local", ObjectKind::Table, "orders", None, week_ago).unwrap();
+        }
+        store.record("local", ObjectKind::View, "active_users", Some("public"), now).unwrap();
+        store.record("local", ObjectKind::Table, "customers", None, now - Duration::hours(1)).unwrap();
+        store.record("staging", ObjectKind::Table, "orders", None, now).unwrap();
+        assert!(store.record("local", ObjectKind::Table, " ", None, now).is_err());
+
+        let ranked = store.ranked("local", 10, now).unwrap();
+        let names: Vec<&str> = ranked.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, vec!["active_users", "customers", "orders"]);
+        assert_eq!(ranked[2].open_count, 3);
+        assert!(ranked[2].score < 1.0);
+        assert_eq!(store.ranked("local", 1, now).unwrap().len(), 1);
+
+        store.remove_profile("staging").unwrap();
+        assert!(store.ranked("staging", 10, now).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Retrying read-only statements that failed with a transient error
+///
+/// Off by default; only statements that cannot change data are retried, so a
+/// retry never applies a change twice.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub enabled: bool,
+    /// Attempts in total, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: 3,
+            base_delay_ms: 100,
+            max_delay_ms: 2000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether another attempt may follow attempt number `attempt` (starting at 1)
+    pub fn should_retry(&self, attempt: u32) -> bool {
+        self.enabled && attempt < self.max_attempts
+    }
+
+    /// Delay before retry number `retry` (starting at 1): a random time between half
+    /// and all of the exponential backoff, so clients that failed together do not
+    /// retry together
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(16))
+            .min(self.max_delay_ms);
+        Duration::from_millis(rand::thread_rng().gen_range(backoff / 2..=backoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_retry() {
+        assert!(!RetryPolicy::default().should_retry(1));
+
+        let policy = RetryPolicy { enabled: true, ..Default::default() };
+        assert!(policy.should_retry(1));
+        assert!(policy.should_retry(2));
+        assert!(!policy.should_retry(3));
+    }
+
+    #[test]
+    fn test_delay_is_jittered_and_capped() {
+        let policy = RetryPolicy { enabled: true, max_attempts: 10, base_delay_ms: 100, max_delay_ms: 1000 };
+
+        for _ in 0..20 {
+            let first = policy.delay(1).as_millis();
+            assert!((50..=100).contains(&first));
+            let third = policy.delay(3).as_millis();
+            assert!((200..=400).contains(&third));
+            assert!(policy.delay(9).as_millis() <= 1000);
+        }
+    }
+}
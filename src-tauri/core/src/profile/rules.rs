@@ -0,0 +1,277 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::database::adapter::DatabaseType;
+use crate::database::statement::{statement_tables, StatementKind};
+use crate::error::AppError;
+use super::ConnectionProfile;
+
+const RULES_FILE: &str = "statement_rules.json";
+
+/// Environment variable pointing at a rules file outside the app data directory
+pub const RULES_FILE_ENV: &str = "DATAFORGE_STATEMENT_RULES";
+
+/// Whether a matching rule lets a statement run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    #[default]
+    Allow,
+    Deny,
+}
+
+/// Group of statements a rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementCategory {
+    All,
+    Query,
+    Insert,
+    Update,
+    Delete,
+    /// UPDATE or DELETE without a WHERE clause
+    UnfilteredDml,
+    Ddl,
+    /// DROP, TRUNCATE, and ALTER statements that can lose data
+    DestructiveDdl,
+    Other,
+}
+
+impl StatementCategory {
+    fn matches(&self, kind: StatementKind) -> bool {
+        match self {
+            StatementCategory::All => true,
+            StatementCategory::Query => kind == StatementKind::Query,
+            StatementCategory::Insert => kind == StatementKind::Insert,
+            StatementCategory::Update => matches!(kind, StatementKind::Update { .. }),
+            StatementCategory::Delete => matches!(kind, StatementKind::Delete { .. }),
+            StatementCategory::UnfilteredDml => kind.is_unfiltered_dml(),
+            StatementCategory::Ddl => kind.is_ddl(),
+            StatementCategory::DestructiveDdl => kind == StatementKind::Ddl { destructive: true },
+            StatementCategory::Other => kind == StatementKind::Other,
+        }
+    }
+}
+
+/// One allow or deny rule; every condition that is set must match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatementRule {
+    pub action: RuleAction,
+    pub categories: Vec<StatementCategory>,
+    /// Profile IDs or names; any connection when not set
+    #[serde(default)]
+    pub profiles: Option<Vec<String>>,
+    #[serde(default)]
+    pub database_types: Option<Vec<DatabaseType>>,
+    /// Tables the statement must touch, without schema; any table when not set
+    #[serde(default)]
+    pub tables: Option<Vec<String>>,
+    /// Shown to the user when the rule denies a statement
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl StatementRule {
+    fn matches(&self, target: &RuleTarget) -> bool {
+        if !self.categories.iter().any(|c| c.matches(target.kind)) {
+            return false;
+        }
+
+        if let Some(profiles) = &self.profiles {
+            let Some(profile) = target.profile else {
+                return false;
+            };
+            if !profiles.iter().any(|p| *p == profile.id || p.eq_ignore_ascii_case(&profile.name)) {
+                return false;
+            }
+        }
+
+        if let Some(database_types) = &self.database_types {
+            if !database_types.contains(&target.database_type) {
+                return false;
+            }
+        }
+
+        if let Some(tables) = &self.tables {
+            match &target.tables {
+                Some(touched) => {
+                    if !tables.iter().any(|t| touched.contains(&t.to_lowercase())) {
+                        return false;
+                    }
+                }
+                // Tables that cannot be determined only match deny rules, so an
+                // unparseable statement cannot slip past a table restriction
+                None => return self.action == RuleAction::Deny,
+            }
+        }
+
+        true
+    }
+}
+
+/// The statement and connection a rule set is checked against
+struct RuleTarget<'a> {
+    kind: StatementKind,
+    profile: Option<&'a ConnectionProfile>,
+    database_type: DatabaseType,
+    tables: Option<Vec<String>>,
+}
+
+/// Admin-provided statement rules, applied to every connection
+///
+/// Rules are checked in order and the first matching one decides; statements no
+/// rule matches get `default_action`. Rules match on the parsed statement, not
+/// its text, so comments and formatting cannot get around them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatementRules {
+    pub default_action: RuleAction,
+    pub rules: Vec<StatementRule>,
+}
+
+impl StatementRules {
+    /// Check whether `statement` may run on the given connection
+    pub fn check(
+        &self,
+        statement: &str,
+        kind: StatementKind,
+        profile: Option<&ConnectionProfile>,
+        database_type: DatabaseType,
+    ) -> Result<(), AppError> {
+        if self.rules.is_empty() && self.default_action == RuleAction::Allow {
+            return Ok(());
+        }
+
+        let needs_tables = self.rules.iter().any(|r| r.tables.is_some());
+        let target = RuleTarget {
+            kind,
+            profile,
+            database_type,
+            tables: needs_tables.then(|| statement_tables(statement, &database_type)).flatten(),
+        };
+
+        let rule = self.rules.iter().find(|r| r.matches(&target));
+        match rule.map(|r| r.action).unwrap_or(self.default_action) {
+            RuleAction::Allow => Ok(()),
+            RuleAction::Deny => Err(AppError::PermissionDenied(format!(
+                "{}: {}",
+                rule.and_then(|r| r.message.as_deref())
+                    .unwrap_or("This statement is not allowed by the statement rules"),
+                statement
+            ))),
+        }
+    }
+}
+
+/// Read-only access to the statement rules file
+///
+/// The file is maintained by an administrator; the application never writes it.
+pub struct StatementRuleStore {
+    rules_path: PathBuf,
+}
+
+impl StatementRuleStore {
+    /// Use the file named by `DATAFORGE_STATEMENT_RULES`, or the one in the app data directory
+    pub fn new(app_data_dir: &Path) -> Self {
+        if let Some(path) = std::env::var_os(RULES_FILE_ENV) {
+            return Self::with_path(PathBuf::from(path));
+        }
+
+        Self::with_path(app_data_dir.join(RULES_FILE))
+    }
+
+    /// Open a store at a specific path
+    pub fn with_path(rules_path: PathBuf) -> Self {
+        Self { rules_path }
+    }
+
+    /// The configured rules; no file allows everything
+    ///
+    /// A file that cannot be read or parsed is an error rather than an empty rule
+    /// set, so a broken config does not silently lift the restrictions.
+    pub fn load(&self) -> Result<StatementRules, AppError> {
+        if !self.rules_path.exists() {
+            return Ok(StatementRules::default());
+        }
+
+        let data = fs::read_to_string(&self.rules_path)
+            .map_err(|e| AppError::Config(format!("Failed to read statement rules: {}", e)))?;
+        serde_json::from_str(&data)
+            .map_err(|e| AppError::Config(format!("Invalid statement rules in {}: {}", self.rules_path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn profile(name: &str) -> ConnectionProfile {
+        ConnectionProfile::new(name.to_string(), DatabaseType::PostgreSQL, "app".to_string())
+    }
+
+    fn rules(json: &str) -> StatementRules {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_empty_rules_allow_everything() {
+        let rules = StatementRules::default();
+        let kind = StatementKind::Ddl { destructive: true };
+        assert!(rules.check("DROP TABLE t", kind, None, DatabaseType::PostgreSQL).is_ok());
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = rules(r#"{
+            "default_action": "deny",
+            "rules": [
+                {"action": "deny", "categories": ["query"], "tables": ["salaries"], "message": "Salaries are off limits"},
+                {"action": "allow", "categories": ["query"]}
+            ]
+        }"#);
+        let postgres = DatabaseType::PostgreSQL;
+
+        assert!(rules.check("SELECT * FROM users", StatementKind::Query, None, postgres).is_ok());
+
+        let denied = rules.check("SELECT * FROM hr.salaries", StatementKind::Query, None, postgres);
+        assert!(matches!(denied, Err(AppError::PermissionDenied(m)) if m.starts_with("Salaries are off limits")));
+
+        let insert = rules.check("INSERT INTO users VALUES (1)", StatementKind::Insert, None, postgres);
+        assert!(matches!(insert, Err(AppError::PermissionDenied(_))));
+    }
+
+    #[test]
+    fn test_profile_conditions() {
+        let rules = rules(r#"{"rules": [{"action": "deny", "categories": ["ddl", "delete"], "profiles": ["production"]}]}"#);
+        let postgres = DatabaseType::PostgreSQL;
+        let delete = StatementKind::Delete { filtered: true };
+
+        let production = profile("Production");
+        assert!(rules.check("DELETE FROM t WHERE id = 1", delete, Some(&production), postgres).is_err());
+        assert!(rules.check("DELETE FROM t WHERE id = 1", delete, Some(&profile("Staging")), postgres).is_ok());
+        assert!(rules.check("DELETE FROM t WHERE id = 1", delete, None, postgres).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_tables_only_match_deny_rules() {
+        let rules = rules(r#"{"rules": [{"action": "deny", "categories": ["all"], "tables": ["payments"]}]}"#);
+        let statement = "FROBNICATE payments";
+        assert!(rules.check(statement, StatementKind::Other, None, DatabaseType::PostgreSQL).is_err());
+    }
+
+    #[test]
+    fn test_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(RULES_FILE);
+        let store = StatementRuleStore::with_path(path.clone());
+
+        assert_eq!(store.load().unwrap(), StatementRules::default());
+
+        fs::write(&path, r#"{"rules": [{"action": "deny", "categories": ["ddl"]}]}"#).unwrap();
+        assert_eq!(store.load().unwrap().rules.len(), 1);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(store.load(), Err(AppError::Config(_))));
+    }
+}
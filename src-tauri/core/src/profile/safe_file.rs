@@ -0,0 +1,174 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use crate::error::AppError;
+
+/// How long `FileLock::acquire` waits for another holder before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Pause between attempts to take a held lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+
+/// `path` with `suffix` appended to its file name
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+    path.with_file_name(file_name)
+}
+
+/// Exclusive lock on a file, held until dropped
+///
+/// The lock is taken on a separate `.lock` file so the data file itself can be
+/// replaced by a rename while the lock is held. It is advisory: it keeps other
+/// DataForge windows and processes out, not unrelated programs.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Wait until the lock for `path` is held, failing after `LOCK_TIMEOUT`
+    ///
+    /// A holder that never lets go, such as a hung process, produces an error
+    /// instead of blocking the calling thread forever.
+    pub fn acquire(path: &Path) -> Result<Self, AppError> {
+        Self::acquire_within(path, LOCK_TIMEOUT)
+    }
+
+    fn acquire_within(path: &Path, timeout: Duration) -> Result<Self, AppError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(sibling(path, ".lock"))
+            .map_err(|e| AppError::Storage(format!("Failed to open lock file: {}", e)))?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(AppError::Storage(format!(
+                        "Timed out waiting for {} to be unlocked",
+                        path.display()
+                    )));
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(AppError::Storage(format!("Failed to lock {}: {}", path.display(), e)));
+                }
+            }
+        }
+    }
+}
+
+/// Replace `path` with `data` so that readers see either the old or the new contents
+///
+/// The data is written and synced to a temporary file next to `path`, which is then
+/// renamed over it.
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), AppError> {
+    let temp_path = sibling(path, ".tmp");
+
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    };
+
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        AppError::Storage(format!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+/// Backup files of `path`, newest first
+pub fn backup_paths(path: &Path, keep: usize) -> Vec<PathBuf> {
+    (1..=keep).map(|n| sibling(path, &format!(".bak{}", n))).collect()
+}
+
+/// Copy the current contents of `path` to its newest backup, shifting older ones
+pub fn rotate_backups(path: &Path, keep: usize) -> Result<(), AppError> {
+    if !path.exists() || keep == 0 {
+        return Ok(());
+    }
+
+    let backups = backup_paths(path, keep);
+    let rotate = || -> std::io::Result<()> {
+        for i in (1..backups.len()).rev() {
+            if backups[i - 1].exists() {
+                fs::rename(&backups[i - 1], &backups[i])?;
+            }
+        }
+        fs::copy(path, &backups[0])?;
+        Ok(())
+    };
+
+    rotate().map_err(|e| AppError::Storage(format!("Failed to back up {}: {}", path.display(), e)))
+}
+
+/// Move a damaged file aside so it can be inspected later, returning its new path
+pub fn quarantine(path: &Path) -> Result<PathBuf, AppError> {
+    let target = sibling(path, &format!(".corrupt-{}", chrono::Utc::now().format("%Y%m%d%H%M%S")));
+    fs::rename(path, &target)
+        .map_err(|e| AppError::Storage(format!("Failed to move aside {}: {}", path.display(), e)))?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_atomic() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.json");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert!(!sibling(&path, ".tmp").exists());
+    }
+
+    #[test]
+    fn test_rotate_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.json");
+
+        for version in ["v1", "v2", "v3", "v4"] {
+            rotate_backups(&path, 2).unwrap();
+            write_atomic(&path, version.as_bytes()).unwrap();
+        }
+
+        let backups = backup_paths(&path, 2);
+        assert_eq!(fs::read(&backups[0]).unwrap(), b"v3");
+        assert_eq!(fs::read(&backups[1]).unwrap(), b"v2");
+        assert!(!sibling(&path, ".bak3").exists());
+    }
+
+    #[test]
+    fn test_lock_is_released_on_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.json");
+
+        drop(FileLock::acquire(&path).unwrap());
+        assert!(FileLock::acquire(&path).is_ok());
+    }
+
+    #[test]
+    fn test_lock_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.json");
+
+        let held = FileLock::acquire(&path).unwrap();
+        assert!(matches!(
+            FileLock::acquire_within(&path, Duration::from_millis(50)),
+            Err(AppError::Storage(_))
+        ));
+        drop(held);
+        assert!(FileLock::acquire_within(&path, Duration::from_millis(50)).is_ok());
+    }
+}
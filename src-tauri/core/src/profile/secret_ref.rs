@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use crate::error::AppError;
+
+/// How long a secret manager CLI may take, including an interactive sign-in prompt
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// External secret a profile's password is read from at connect time
+///
+/// Only the reference is stored in the profile; the password itself is fetched
+/// through the manager's CLI on every connect and never written to the keyring.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum SecretReference {
+    /// Field of a HashiCorp Vault KV secret, read with `vault kv get`; the CLI
+    /// picks up `VAULT_ADDR` and `VAULT_TOKEN` from the environment
+    Vault {
+        path: String,
+        #[serde(default = "default_vault_field")]
+        field: String,
+        /// KV mount, when it is not part of the path
+        #[serde(default)]
+        mount: Option<String>,
+    },
+    /// 1Password secret reference (`op://vault/item/field`), read with `op read`
+    OnePassword { reference: String },
+}
+
+fn default_vault_field() -> String {
+    "password".to_string()
+}
+
+impl SecretReference {
+    /// Check the reference before it is saved
+    pub fn validate(&self) -> Result<(), AppError> {
+        match self {
+            SecretReference::Vault { path, field, .. } => {
+                if path.trim().is_empty() {
+                    return Err(AppError::Validation("Vault secret path is required".to_string()));
+                }
+                if path.trim_start().starts_with('-') {
+                    return Err(AppError::Validation("Vault secret path must not start with '-'".to_string()));
+                }
+                if field.trim().is_empty() {
+                    return Err(AppError::Validation("Vault secret field is required".to_string()));
+                }
+            }
+            SecretReference::OnePassword { reference } => {
+                if !reference.starts_with("op://") {
+                    return Err(AppError::Validation(
+                        "1Password references must look like op://vault/item/field".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// CLI program and arguments that print the secret
+    fn command_line(&self) -> (&'static str, Vec<String>) {
+        match self {
+            SecretReference::Vault { path, field, mount } => {
+                let mut args = vec!["kv".to_string(), "get".to_string(), format!("-field={}", field)];
+                if let Some(mount) = mount {
+                    args.push(format!("-mount={}", mount));
+                }
+                // Ends the flags, so the path is never read as one
+                args.push("--".to_string());
+                args.push(path.clone());
+                ("vault", args)
+            }
+            SecretReference::OnePassword { reference } => {
+                ("op", vec!["read".to_string(), "--no-newline".to_string(), reference.clone()])
+            }
+        }
+    }
+
+    /// Fetch the secret from the secret manager
+    pub async fn resolve(&self) -> Result<String, AppError> {
+        let (program, args) = self.command_line();
+
+        let output = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(RESOLVE_TIMEOUT, output)
+            .await
+            .map_err(|_| AppError::Auth(format!("Timed out waiting for the {} CLI", program)))?
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => AppError::Config(format!(
+                    "The {} CLI is not installed or not on PATH",
+                    program
+                )),
+                _ => AppError::Auth(format!("Failed to run the {} CLI: {}", program, e)),
+            })?;
+
+        if !output.status.success() {
+            return Err(AppError::Auth(format!(
+                "Failed to read secret with the {} CLI: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let secret = String::from_utf8(output.stdout)
+            .map_err(|_| AppError::Auth("Secret is not valid UTF-8".to_string()))?;
+        let secret = secret.trim_end_matches(['\r', '\n']).to_string();
+        if secret.is_empty() {
+            return Err(AppError::Auth(format!("The {} CLI returned an empty secret", program)));
+        }
+
+        Ok(secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let vault: SecretReference =
+            serde_json::from_str(r#"{"provider": "vault", "path": "secret/app/db"}"#).unwrap();
+        assert_eq!(
+            vault,
+            SecretReference::Vault {
+                path: "secret/app/db".to_string(),
+                field: "password".to_string(),
+                mount: None,
+            }
+        );
+
+        let op: SecretReference =
+            serde_json::from_str(r#"{"provider": "one_password", "reference": "op://Dev/db/password"}"#).unwrap();
+        assert!(op.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        let op = SecretReference::OnePassword { reference: "Dev/db/password".to_string() };
+        assert!(matches!(op.validate(), Err(AppError::Validation(_))));
+
+        let vault = SecretReference::Vault { path: " ".to_string(), field: "password".to_string(), mount: None };
+        assert!(matches!(vault.validate(), Err(AppError::Validation(_))));
+
+        let vault = SecretReference::Vault { path: "-address=http://evil".to_string(), field: "password".to_string(), mount: None };
+        assert!(matches!(vault.validate(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_command_line() {
+        let vault = SecretReference::Vault {
+            path: "app/db".to_string(),
+            field: "pw".to_string(),
+            mount: Some("kv".to_string()),
+        };
+        assert_eq!(
+            vault.command_line(),
+            ("vault", vec!["kv".into(), "get".into(), "-field=pw".into(), "-mount=kv".into(), "--".into(), "app/db".into()])
+        );
+
+        let op = SecretReference::OnePassword { reference: "op://Dev/db/password".to_string() };
+        assert_eq!(op.command_line().0, "op");
+        assert_eq!(op.command_line().1.last().unwrap(), "op://Dev/db/password");
+    }
+}
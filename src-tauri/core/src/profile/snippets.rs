@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+use crate::error::AppError;
+use super::encrypted_file::EncryptedJsonFile;
+
+const SNIPPETS_FILE: &str = "snippets.encrypted";
+
+/// Reusable piece of SQL saved by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    /// Assigned when the snippet is first saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub body: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Saved snippets, encrypted with the profile key
+pub struct SnippetStore {
+    file: EncryptedJsonFile,
+}
+
+impl SnippetStore {
+    pub fn new(profiles_dir: &Path) -> Self {
+        Self {
+            file: EncryptedJsonFile::new(profiles_dir.join(SNIPPETS_FILE)),
+        }
+    }
+
+    /// All snippets, sorted by name
+    pub fn list(&self) -> Result<Vec<Snippet>, AppError> {
+        let mut snippets: Vec<Snippet> = self.file.load()?;
+        snippets.sort_by_key(|s| s.name.to_lowercase());
+        Ok(snippets)
+    }
+
+    /// Add a snippet, or replace the one with the same ID
+    pub fn save(&self, mut snippet: Snippet) -> Result<Snippet, AppError> {
+        if snippet.name.trim().is_empty() {
+            return Err(AppError::Validation("Snippet name is required".to_string()));
+        }
+
+        self.file.update(|snippets: &mut Vec<Snippet>| {
+            snippet.updated_at = Utc::now();
+            match snippets.iter_mut().find(|s| !snippet.id.is_empty() && s.id == snippet.id) {
+                Some(existing) => {
+                    snippet.created_at = existing.created_at;
+                    *existing = snippet.clone();
+                }
+                None => {
+                    snippet.id = Uuid::new_v4().to_string();
+                    snippet.created_at = snippet.updated_at;
+                    snippets.push(snippet.clone());
+                }
+            }
+            Ok(snippet)
+        })
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.file.update(|snippets: &mut Vec<Snippet>| {
+            let before = snippets.len();
+            snippets.retain(|s| s.id != id);
+            if snippets.len() == before {
+                return Err(AppError::NotFound(format!("Snippet {} not found", id)));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.file.reencrypt(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn snippet(name: &str, body: &str) -> Snippet {
+        Snippet {
+            id: String::new(),
+            name: name.to_string(),
+            body: body.to_string(),
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_save_update_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SnippetStore::new(temp_dir.path());
+
+        let saved = store.save(snippet("Recent orders", "SELECT * FROM orders")).unwrap();
+        assert!(!saved.id.is_empty());
+        store.save(snippet("active users", "SELECT * FROM users")).unwrap();
+
+        let updated = store.save(Snippet { body: "SELECT id FROM orders".to_string(), ..saved.clone() }).unwrap();
+        assert_eq!(updated.id, saved.id);
+
+        let snippets = store.list().unwrap();
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(snippets[0].name, "active users");
+        assert_eq!(snippets[1].body, "SELECT id FROM orders");
+
+        store.delete(&saved.id).unwrap();
+        assert!(matches!(store.delete(&saved.id), Err(AppError::NotFound(_))));
+        assert!(store.save(snippet(" ", "SELECT 1")).is_err());
+    }
+}
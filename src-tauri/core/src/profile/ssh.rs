@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use crate::error::AppError;
+
+/// Default port of an SSH server
+pub const DEFAULT_SSH_PORT: u16 = 22;
+
+fn default_ssh_port() -> u16 {
+    DEFAULT_SSH_PORT
+}
+
+/// SSH tunnel a profile connects through
+///
+/// The key passphrase (or the SSH password when no key is used) is not part of the
+/// profile; it is kept in the keyring next to the database password.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshTunnelConfig {
+    pub host: String,
+    #[serde(default = "default_ssh_port")]
+    pub port: u16,
+    pub username: String,
+    /// Private key file; password authentication is used when not set
+    #[serde(default)]
+    pub key_path: Option<String>,
+    /// Local port to bind the tunnel to; any free port when not set
+    #[serde(default)]
+    pub local_port: Option<u16>,
+}
+
+impl SshTunnelConfig {
+    /// Key path with a leading `~` expanded to the home directory
+    pub fn resolved_key_path(&self) -> Option<PathBuf> {
+        let key_path = self.key_path.as_deref()?;
+        match key_path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None => Some(PathBuf::from(key_path)),
+        }
+    }
+
+    /// Check the settings before they are saved
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.host.trim().is_empty() {
+            return Err(AppError::Validation("SSH host is required".to_string()));
+        }
+        if self.username.trim().is_empty() {
+            return Err(AppError::Validation("SSH username is required".to_string()));
+        }
+        if self.port == 0 {
+            return Err(AppError::Validation("SSH port must not be 0".to_string()));
+        }
+        if self.local_port == Some(0) {
+            return Err(AppError::Validation(
+                "Local tunnel port must not be 0; leave it empty to pick a free port".to_string(),
+            ));
+        }
+
+        if let Some(key_path) = self.resolved_key_path() {
+            if !key_path.is_file() {
+                return Err(AppError::Validation(format!(
+                    "SSH key file not found: {}",
+                    key_path.display()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config() -> SshTunnelConfig {
+        SshTunnelConfig {
+            host: "bastion.example.com".to_string(),
+            port: DEFAULT_SSH_PORT,
+            username: "deploy".to_string(),
+            key_path: None,
+            local_port: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+
+        let missing_host = SshTunnelConfig { host: " ".to_string(), ..config() };
+        assert!(matches!(missing_host.validate(), Err(AppError::Validation(_))));
+
+        let zero_port = SshTunnelConfig { local_port: Some(0), ..config() };
+        assert!(matches!(zero_port.validate(), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_validate_key_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let key_path = temp_dir.path().join("id_ed25519");
+
+        let with_key = SshTunnelConfig {
+            key_path: Some(key_path.to_string_lossy().to_string()),
+            ..config()
+        };
+        assert!(with_key.validate().is_err());
+
+        std::fs::write(&key_path, "key").unwrap();
+        assert!(with_key.validate().is_ok());
+    }
+
+    #[test]
+    fn test_defaults_when_deserializing() {
+        let config: SshTunnelConfig =
+            serde_json::from_str(r#"{"host": "bastion", "username": "deploy"}"#).unwrap();
+        assert_eq!(config.port, DEFAULT_SSH_PORT);
+        assert!(config.key_path.is_none());
+    }
+}
@@ -0,0 +1,361 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use serde_json;
+use crate::error::AppError;
+use super::{ConnectionProfile, crypto, safe_file, vault};
+
+const PROFILE_FILE: &str = "profiles.encrypted";
+
+/// Number of previous versions of the profile file that are kept
+const BACKUP_COUNT: usize = 3;
+
+/// Profile storage that handles saving/loading profiles and passwords
+pub struct ProfileStorage {
+    profiles_path: PathBuf,
+}
+
+impl ProfileStorage {
+    /// Create a new profile storage instance in the `profiles` directory of the app data directory
+    pub fn new(app_data_dir: &Path) -> Result<Self, AppError> {
+        let profiles_dir = app_data_dir.join("profiles");
+
+        // Ensure the directory exists
+        fs::create_dir_all(&profiles_dir).map_err(|e| {
+            AppError::Storage(format!("Failed to create profiles directory: {}", e))
+        })?;
+
+        let profiles_path = profiles_dir.join(PROFILE_FILE);
+
+        // Secrets fall back to a vault in the same directory when there is no keyring
+        vault::configure(profiles_dir);
+
+        Ok(Self { profiles_path })
+    }
+
+    /// Directory holding the profile file
+    pub fn profiles_dir(&self) -> PathBuf {
+        self.profiles_path
+            .parent()
+            .map(|dir| dir.to_path_buf())
+            .unwrap_or_default()
+    }
+
+    /// Hold the profile file lock for a read-modify-write cycle
+    fn lock(&self) -> Result<safe_file::FileLock, AppError> {
+        safe_file::FileLock::acquire(&self.profiles_path)
+    }
+
+    /// Re-encrypt the profile file and its backups from one key to another
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        let _lock = self.lock()?;
+
+        let backups = safe_file::backup_paths(&self.profiles_path, BACKUP_COUNT);
+        for (index, path) in std::iter::once(&self.profiles_path).chain(&backups).enumerate() {
+            if !path.exists() {
+                continue;
+            }
+
+            let encrypted_data = fs::read_to_string(path).map_err(|e| {
+                AppError::Storage(format!("Failed to read profiles file: {}", e))
+            })?;
+            if encrypted_data.trim().is_empty() {
+                continue;
+            }
+
+            let decrypted = match crypto::decrypt_with_key(from, &encrypted_data) {
+                Ok(decrypted) => decrypted,
+                // The profile file itself must be readable; unreadable backups are dropped
+                Err(e) if index == 0 => return Err(e),
+                Err(_) => {
+                    let _ = fs::remove_file(path);
+                    continue;
+                }
+            };
+            let encrypted = crypto::encrypt_with_key(to, &decrypted)?;
+            safe_file::write_atomic(path, encrypted.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Save a profile to storage
+    pub async fn save_profile(&self, profile: &ConnectionProfile) -> Result<(), AppError> {
+        let _lock = self.lock()?;
+        let mut profiles = self.load_all_profiles().await?;
+
+        // Add or update the profile
+        profiles.retain(|p| p.id != profile.id);
+        profiles.push(profile.clone());
+
+        self.save_all_profiles(&profiles).await
+    }
+
+    /// Change several profiles in one locked read-modify-write cycle
+    pub async fn modify_profiles(
+        &self,
+        change: impl FnOnce(&mut Vec<ConnectionProfile>) -> Result<(), AppError>,
+    ) -> Result<(), AppError> {
+        let _lock = self.lock()?;
+        let mut profiles = self.load_all_profiles().await?;
+
+        change(&mut profiles)?;
+
+        self.save_all_profiles(&profiles).await
+    }
+
+    /// Update an existing profile
+    pub async fn update_profile(&self, profile: &ConnectionProfile) -> Result<(), AppError> {
+        let _lock = self.lock()?;
+        let mut profiles = self.load_all_profiles().await?;
+
+        // Find and update the profile
+        let found = profiles.iter_mut().find(|p| p.id == profile.id);
+        match found {
+            Some(p) => *p = profile.clone(),
+            None => return Err(AppError::Storage(format!("Profile {} not found", profile.id))),
+        }
+
+        self.save_all_profiles(&profiles).await
+    }
+
+    /// Delete a profile from storage
+    pub async fn delete_profile(&self, id: &str) -> Result<(), AppError> {
+        let _lock = self.lock()?;
+        let mut profiles = self.load_all_profiles().await?;
+
+        // Remove the profile
+        profiles.retain(|p| p.id != id);
+
+        self.save_all_profiles(&profiles).await
+    }
+
+    /// Get a specific profile by ID
+    pub async fn get_profile(&self, id: &str) -> Result<ConnectionProfile, AppError> {
+        let _lock = self.lock()?;
+        let profiles = self.load_all_profiles().await?;
+
+        profiles
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| AppError::Storage(format!("Profile {} not found", id)))
+    }
+
+    /// List all profiles
+    pub async fn list_profiles(&self) -> Result<Vec<ConnectionProfile>, AppError> {
+        let _lock = self.lock()?;
+        self.load_all_profiles().await
+    }
+
+    /// Load all profiles from storage; the caller holds the file lock
+    ///
+    /// A damaged profile file is moved aside and replaced by the newest readable backup.
+    async fn load_all_profiles(&self) -> Result<Vec<ConnectionProfile>, AppError> {
+        if !self.profiles_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let encrypted_data = fs::read_to_string(&self.profiles_path).map_err(|e| {
+            AppError::Storage(format!("Failed to read profiles file: {}", e))
+        })?;
+
+        let error = match Self::decode(&encrypted_data) {
+            Ok((profiles, false)) => return Ok(profiles),
+            Ok((profiles, true)) => {
+                // Written with the old hard-coded key; re-encrypt with the master key
+                self.save_all_profiles(&profiles).await?;
+                return Ok(profiles);
+            }
+            Err(e) => e,
+        };
+
+        // Locked profiles are not damaged, so leave the files alone
+        if matches!(error, AppError::Auth(_)) {
+            return Err(error);
+        }
+
+        for backup in safe_file::backup_paths(&self.profiles_path, BACKUP_COUNT) {
+            let Ok(backup_data) = fs::read_to_string(&backup) else {
+                continue;
+            };
+            if let Ok((profiles, _)) = Self::decode(&backup_data) {
+                safe_file::quarantine(&self.profiles_path)?;
+                self.save_all_profiles(&profiles).await?;
+                return Ok(profiles);
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Decrypt and parse profile file contents, also reporting whether the legacy key was needed
+    fn decode(encrypted_data: &str) -> Result<(Vec<ConnectionProfile>, bool), AppError> {
+        if encrypted_data.trim().is_empty() {
+            return Ok((Vec::new(), false));
+        }
+
+        let (decrypted, legacy) = match crypto::decrypt(encrypted_data) {
+            Ok(decrypted) => (decrypted, false),
+            Err(e) => (crypto::decrypt_legacy(encrypted_data).map_err(|_| e)?, true),
+        };
+
+        // Deserialize profiles
+        let profiles: Vec<ConnectionProfile> = serde_json::from_slice(&decrypted).map_err(|e| {
+            AppError::Storage(format!("Failed to deserialize profiles: {}", e))
+        })?;
+
+        Ok((profiles, legacy))
+    }
+
+    /// Save all profiles to storage; the caller holds the file lock
+    async fn save_all_profiles(&self, profiles: &[ConnectionProfile]) -> Result<(), AppError> {
+        // Serialize profiles
+        let json_data = serde_json::to_vec(profiles).map_err(|e| {
+            AppError::Storage(format!("Failed to serialize profiles: {}", e))
+        })?;
+
+        // Encrypt the data
+        let encrypted = crypto::encrypt(&json_data)?;
+
+        // Keep the previous versions, then replace the file in one step
+        safe_file::rotate_backups(&self.profiles_path, BACKUP_COUNT)?;
+        safe_file::write_atomic(&self.profiles_path, encrypted.as_bytes())
+    }
+
+    /// Save a password to the OS keyring, or the fallback vault if there is none
+    pub fn save_password(&self, profile_id: &str, password: &str) -> Result<(), AppError> {
+        vault::set_secret(&format!("profile_{}", profile_id), password)
+    }
+
+    /// Get a password from the OS keyring or the fallback vault
+    pub fn get_password(&self, profile_id: &str) -> Result<String, AppError> {
+        vault::get_secret(&format!("profile_{}", profile_id))?
+            .ok_or_else(|| AppError::NotFound(format!("No password saved for profile {}", profile_id)))
+    }
+
+    /// Delete a password from the OS keyring or the fallback vault
+    pub fn delete_password(&self, profile_id: &str) -> Result<(), AppError> {
+        vault::delete_secret(&format!("profile_{}", profile_id))
+    }
+
+    /// Save the SSH tunnel passphrase of a profile
+    pub fn save_ssh_passphrase(&self, profile_id: &str, passphrase: &str) -> Result<(), AppError> {
+        vault::set_secret(&format!("profile_{}_ssh", profile_id), passphrase)
+    }
+
+    /// Get the SSH tunnel passphrase of a profile, if one is saved
+    pub fn get_ssh_passphrase(&self, profile_id: &str) -> Result<Option<String>, AppError> {
+        vault::get_secret(&format!("profile_{}_ssh", profile_id))
+    }
+
+    /// Delete the SSH tunnel passphrase of a profile
+    pub fn delete_ssh_passphrase(&self, profile_id: &str) -> Result<(), AppError> {
+        vault::delete_secret(&format!("profile_{}_ssh", profile_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_profile_storage() {
+        // Create a temporary directory for testing
+        let temp_dir = TempDir::new().unwrap();
+        let profiles_path = temp_dir.path().join("profiles.encrypted");
+
+        // Create a test storage instance
+        let storage = ProfileStorage {
+            profiles_path: profiles_path.clone(),
+        };
+
+        // Create a test profile
+        let profile = ConnectionProfile::new(
+            "Test DB".to_string(),
+            crate::database::adapter::DatabaseType::PostgreSQL,
+            "testdb".to_string(),
+        );
+
+        // Save the profile
+        storage.save_profile(&profile).await.unwrap();
+
+        // Load profiles
+        let profiles = storage.list_profiles().await.unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "Test DB");
+
+        // Get specific profile
+        let loaded_profile = storage.get_profile(&profile.id).await.unwrap();
+        assert_eq!(loaded_profile.name, profile.name);
+
+        // Delete profile
+        storage.delete_profile(&profile.id).await.unwrap();
+        let profiles = storage.list_profiles().await.unwrap();
+        assert_eq!(profiles.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_migrates_legacy_profile_file() {
+        use sha2::{Digest, Sha256};
+
+        let temp_dir = TempDir::new().unwrap();
+        let storage = ProfileStorage {
+            profiles_path: temp_dir.path().join("profiles.encrypted"),
+        };
+
+        // Write a file the way older versions did, with the hard-coded key
+        let profile = ConnectionProfile::new(
+            "Legacy DB".to_string(),
+            crate::database::adapter::DatabaseType::SQLite,
+            "legacy.db".to_string(),
+        );
+        let legacy_key = Sha256::digest(b"dataforge_profile_encryption_key_v1");
+        let json_data = serde_json::to_vec(&vec![profile]).unwrap();
+        let legacy = crypto::encrypt_with_key(&legacy_key, &json_data).unwrap();
+        fs::write(&storage.profiles_path, &legacy).unwrap();
+
+        let profiles = storage.list_profiles().await.unwrap();
+        assert_eq!(profiles[0].name, "Legacy DB");
+
+        // The file is now readable with the master key alone
+        let migrated = fs::read_to_string(&storage.profiles_path).unwrap();
+        assert_ne!(migrated, legacy);
+        assert!(crypto::decrypt(&migrated).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_recovers_from_corrupted_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = ProfileStorage {
+            profiles_path: temp_dir.path().join("profiles.encrypted"),
+        };
+
+        let first = ConnectionProfile::new(
+            "First DB".to_string(),
+            crate::database::adapter::DatabaseType::SQLite,
+            "first.db".to_string(),
+        );
+        let second = ConnectionProfile::new(
+            "Second DB".to_string(),
+            crate::database::adapter::DatabaseType::SQLite,
+            "second.db".to_string(),
+        );
+        storage.save_profile(&first).await.unwrap();
+        storage.save_profile(&second).await.unwrap();
+
+        // Simulate a torn write
+        fs::write(&storage.profiles_path, "garbage").unwrap();
+
+        // The newest backup only has the first profile
+        let profiles = storage.list_profiles().await.unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "First DB");
+
+        let corrupt_copies = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+        assert_eq!(corrupt_copies, 1);
+        assert!(crypto::decrypt(&fs::read_to_string(&storage.profiles_path).unwrap()).is_ok());
+    }
+}
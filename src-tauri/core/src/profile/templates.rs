@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::database::templates::QueryTemplate;
+use crate::error::AppError;
+use super::encrypted_file::EncryptedJsonFile;
+
+const TEMPLATES_FILE: &str = "templates.encrypted";
+const LIBRARY_FORMAT: &str = "dataforge-templates";
+const LIBRARY_VERSION: u32 = 1;
+
+/// A set of templates written to a file to share with others
+///
+/// Unlike the store, library files are plain JSON so they can be reviewed and
+/// kept in version control.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateLibrary {
+    format: String,
+    version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub templates: Vec<QueryTemplate>,
+}
+
+impl TemplateLibrary {
+    pub fn new(templates: Vec<QueryTemplate>) -> Self {
+        Self {
+            format: LIBRARY_FORMAT.to_string(),
+            version: LIBRARY_VERSION,
+            exported_at: Utc::now(),
+            templates,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, AppError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize template library: {}", e)))
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, AppError> {
+        let library: Self = serde_json::from_str(contents)
+            .map_err(|_| AppError::Validation("Not a DataForge template library".to_string()))?;
+
+        if library.format != LIBRARY_FORMAT {
+            return Err(AppError::Validation("Not a DataForge template library".to_string()));
+        }
+        if library.version > LIBRARY_VERSION {
+            return Err(AppError::Validation(format!(
+                "Template library version {} is not supported by this version of DataForge",
+                library.version
+            )));
+        }
+        for template in &library.templates {
+            validate(template)?;
+        }
+        Ok(library)
+    }
+}
+
+/// Templates added by the user, encrypted with the profile key
+pub struct TemplateStore {
+    file: EncryptedJsonFile,
+}
+
+impl TemplateStore {
+    pub fn new(profiles_dir: &Path) -> Self {
+        Self {
+            file: EncryptedJsonFile::new(profiles_dir.join(TEMPLATES_FILE)),
+        }
+    }
+
+    /// All user templates, sorted by name
+    pub fn list(&self) -> Result<Vec<QueryTemplate>, AppError> {
+        let mut templates: Vec<QueryTemplate> = self.file.load()?;
+        templates.sort_by_key(|t| t.name.to_lowercase());
+        Ok(templates)
+    }
+
+    /// Add a template, or replace the one with the same ID
+    pub fn save(&self, template: QueryTemplate) -> Result<QueryTemplate, AppError> {
+        validate(&template)?;
+
+        self.file.update(|templates: &mut Vec<QueryTemplate>| {
+            match templates.iter_mut().find(|t| t.id == template.id) {
+                Some(existing) => *existing = template.clone(),
+                None => templates.push(template.clone()),
+            }
+            Ok(template)
+        })
+    }
+
+    /// Add the templates of a library; with `overwrite` those whose ID is taken
+    /// replace the existing one, otherwise they are skipped. Returns the
+    /// templates that were added.
+    pub fn import(&self, library: TemplateLibrary, overwrite: bool) -> Result<Vec<QueryTemplate>, AppError> {
+        self.file.update(|templates: &mut Vec<QueryTemplate>| {
+            let mut imported = Vec::new();
+            for template in library.templates {
+                match templates.iter_mut().find(|t| t.id == template.id) {
+                    Some(existing) if overwrite => *existing = template.clone(),
+                    Some(_) => continue,
+                    None => templates.push(template.clone()),
+                }
+                imported.push(template);
+            }
+            Ok(imported)
+        })
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.file.update(|templates: &mut Vec<QueryTemplate>| {
+            let before = templates.len();
+            templates.retain(|t| t.id != id);
+            if templates.len() == before {
+                return Err(AppError::NotFound(format!("Template {} not found", id)));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.file.reencrypt(from, to)
+    }
+}
+
+fn validate(template: &QueryTemplate) -> Result<(), AppError> {
+    if template.id.trim().is_empty() || template.name.trim().is_empty() {
+        return Err(AppError::Validation("Template ID and name are required".to_string()));
+    }
+    if template.supported_databases.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Template '{}' does not name any supported database",
+            template.name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::DatabaseType;
+    use crate::database::templates::TemplateCategory;
+    use tempfile::TempDir;
+
+    fn template(id: &str, sql: &str) -> QueryTemplate {
+        QueryTemplate {
+            id: id.to_string(),
+            name: format!("Template {}", id),
+            category: TemplateCategory::Query,
+            description: String::new(),
+            template: sql.to_string(),
+            parameters: Vec::new(),
+            supported_databases: vec![DatabaseType::PostgreSQL],
+            requires: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_library_round_trip() {
+        let json = TemplateLibrary::new(vec![template("locks", "SELECT * FROM pg_locks")]).to_json().unwrap();
+        let library = TemplateLibrary::from_json(&json).unwrap();
+        assert_eq!(library.templates[0].template, "SELECT * FROM pg_locks");
+
+        assert!(TemplateLibrary::from_json("{\"templates\": []}").is_err());
+        let unnamed = TemplateLibrary::new(vec![template(" ", "SELECT 1")]).to_json().unwrap();
+        assert!(TemplateLibrary::from_json(&unnamed).is_err());
+    }
+
+    #[test]
+    fn test_import() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TemplateStore::new(temp_dir.path());
+        store.save(template("locks", "SELECT 1")).unwrap();
+
+        let library = || TemplateLibrary::new(vec![template("locks", "SELECT 2"), template("vacuum", "VACUUM")]);
+        let imported = store.import(library(), false).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(store.list().unwrap()[0].template, "SELECT 1");
+
+        store.import(library(), true).unwrap();
+        assert_eq!(store.list().unwrap()[0].template, "SELECT 2");
+
+        store.delete("vacuum").unwrap();
+        assert!(matches!(store.delete("vacuum"), Err(AppError::NotFound(_))));
+    }
+}
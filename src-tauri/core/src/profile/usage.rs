@@ -0,0 +1,187 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::error::AppError;
+
+const USAGE_FILE: &str = "usage.json";
+
+/// Number of connections kept in the connection history
+const HISTORY_LIMIT: usize = 50;
+
+/// Accumulated usage of one profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileUsage {
+    pub connect_count: u64,
+    /// Total time spent connected, in seconds
+    pub connected_seconds: u64,
+    pub last_connected: Option<DateTime<Utc>>,
+}
+
+/// One connection made with a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionRecord {
+    pub profile_id: String,
+    pub connected_at: DateTime<Utc>,
+    /// Not set while the connection is open
+    pub disconnected_at: Option<DateTime<Utc>>,
+}
+
+/// Usage statistics of all profiles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    pub profiles: HashMap<String, ProfileUsage>,
+    /// Most recent connections first
+    pub history: Vec<ConnectionRecord>,
+}
+
+impl UsageStats {
+    /// Record a new connection, ending any connection that is still open
+    pub fn record_connect(&mut self, profile_id: &str, now: DateTime<Utc>) {
+        self.record_disconnect(now);
+
+        let usage = self.profiles.entry(profile_id.to_string()).or_default();
+        usage.connect_count += 1;
+        usage.last_connected = Some(now);
+
+        self.history.insert(0, ConnectionRecord {
+            profile_id: profile_id.to_string(),
+            connected_at: now,
+            disconnected_at: None,
+        });
+        self.history.truncate(HISTORY_LIMIT);
+    }
+
+    /// End the open connection, adding its duration to the profile's connected time
+    pub fn record_disconnect(&mut self, now: DateTime<Utc>) {
+        for record in self.history.iter_mut().filter(|r| r.disconnected_at.is_none()) {
+            record.disconnected_at = Some(now);
+
+            let seconds = (now - record.connected_at).num_seconds().max(0) as u64;
+            if let Some(usage) = self.profiles.get_mut(&record.profile_id) {
+                usage.connected_seconds += seconds;
+            }
+        }
+    }
+
+    /// End connections left open by a previous run without counting their time
+    pub fn close_stale(&mut self) {
+        for record in self.history.iter_mut().filter(|r| r.disconnected_at.is_none()) {
+            record.disconnected_at = Some(record.connected_at);
+        }
+    }
+
+    /// IDs of the most recently used profiles, without duplicates
+    pub fn recent_profile_ids(&self, limit: usize) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for record in &self.history {
+            if !ids.contains(&record.profile_id) {
+                ids.push(record.profile_id.clone());
+            }
+            if ids.len() == limit {
+                break;
+            }
+        }
+        ids
+    }
+
+    /// Forget everything about a profile
+    pub fn remove_profile(&mut self, profile_id: &str) {
+        self.profiles.remove(profile_id);
+        self.history.retain(|r| r.profile_id != profile_id);
+    }
+}
+
+/// JSON file holding usage statistics next to the profile file
+pub struct UsageStore {
+    usage_path: PathBuf,
+}
+
+impl UsageStore {
+    /// Open the store in the profiles directory
+    pub fn new(profiles_dir: PathBuf) -> Self {
+        Self {
+            usage_path: profiles_dir.join(USAGE_FILE),
+        }
+    }
+
+    pub fn load(&self) -> Result<UsageStats, AppError> {
+        if !self.usage_path.exists() {
+            return Ok(UsageStats::default());
+        }
+
+        let data = fs::read_to_string(&self.usage_path).map_err(|e| {
+            AppError::Storage(format!("Failed to read usage statistics: {}", e))
+        })?;
+        serde_json::from_str(&data)
+            .map_err(|e| AppError::Storage(format!("Failed to parse usage statistics: {}", e)))
+    }
+
+    pub fn save(&self, stats: &UsageStats) -> Result<(), AppError> {
+        let data = serde_json::to_string_pretty(stats).map_err(|e| {
+            AppError::Storage(format!("Failed to serialize usage statistics: {}", e))
+        })?;
+        fs::write(&self.usage_path, data).map_err(|e| {
+            AppError::Storage(format!("Failed to write usage statistics: {}", e))
+        })
+    }
+
+    /// Load, change, and save the statistics
+    pub fn update(&self, change: impl FnOnce(&mut UsageStats)) -> Result<(), AppError> {
+        let mut stats = self.load()?;
+        change(&mut stats);
+        self.save(&stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_connect_and_disconnect() {
+        let start = Utc::now();
+        let mut stats = UsageStats::default();
+
+        stats.record_connect("a", start);
+        stats.record_connect("b", start + Duration::seconds(60));
+        stats.record_disconnect(start + Duration::seconds(90));
+
+        assert_eq!(stats.profiles["a"].connect_count, 1);
+        assert_eq!(stats.profiles["a"].connected_seconds, 60);
+        assert_eq!(stats.profiles["b"].connected_seconds, 30);
+        assert!(stats.history.iter().all(|r| r.disconnected_at.is_some()));
+    }
+
+    #[test]
+    fn test_recent_profile_ids() {
+        let start = Utc::now();
+        let mut stats = UsageStats::default();
+        for (i, id) in ["a", "b", "a", "c"].iter().enumerate() {
+            stats.record_connect(id, start + Duration::seconds(i as i64));
+        }
+
+        assert_eq!(stats.recent_profile_ids(10), vec!["c", "a", "b"]);
+        assert_eq!(stats.recent_profile_ids(2), vec!["c", "a"]);
+
+        stats.remove_profile("a");
+        assert_eq!(stats.recent_profile_ids(10), vec!["c", "b"]);
+    }
+
+    #[test]
+    fn test_stale_sessions_are_not_counted() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = UsageStore::new(temp_dir.path().to_path_buf());
+
+        store.update(|stats| stats.record_connect("a", Utc::now() - Duration::hours(5))).unwrap();
+        store.update(|stats| stats.close_stale()).unwrap();
+        store.update(|stats| stats.record_disconnect(Utc::now())).unwrap();
+
+        let stats = store.load().unwrap();
+        assert_eq!(stats.profiles["a"].connect_count, 1);
+        assert_eq!(stats.profiles["a"].connected_seconds, 0);
+    }
+}
@@ -0,0 +1,255 @@
+use keyring::Entry;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use crate::error::AppError;
+use super::{crypto, safe_file};
+
+const APP_NAME: &str = "DataForge";
+const PROBE_ENTRY: &str = "keyring_probe";
+const VAULT_FILE: &str = "secrets.vault";
+const VAULT_KEY_FILE: &str = "secrets.key";
+
+/// Directory of the fallback vault, set once the profile storage is opened
+static VAULT_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Whether the OS keyring actually persists secrets, checked once per process
+static KEYRING_WORKS: OnceCell<bool> = OnceCell::new();
+
+/// Where secrets are being stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretBackend {
+    Keyring,
+    /// Encrypted local file whose key sits next to it; weaker than the OS keyring
+    Vault,
+}
+
+/// Secret storage status reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretStorageStatus {
+    pub backend: SecretBackend,
+    pub warning: Option<String>,
+}
+
+/// Encrypted file of named secrets, used when no OS keyring is available
+///
+/// The file is encrypted with a random key stored in a second file readable only by
+/// the current user. That keeps secrets out of plain sight, but anyone who can read
+/// the user's files can decrypt them.
+pub struct FallbackVault {
+    dir: PathBuf,
+}
+
+impl FallbackVault {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn key(&self) -> Result<Vec<u8>, AppError> {
+        let key_path = self.dir.join(VAULT_KEY_FILE);
+        if key_path.exists() {
+            return fs::read(&key_path)
+                .map_err(|e| AppError::Storage(format!("Failed to read vault key: {}", e)));
+        }
+
+        // Created private, so the key is never readable by others, not even briefly
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let key = crypto::generate_key();
+        match options.open(&key_path) {
+            Ok(mut file) => file
+                .write_all(&key)
+                .and_then(|_| file.sync_all())
+                .map_err(|e| AppError::Storage(format!("Failed to write vault key: {}", e)))?,
+            // Another process created the key first
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                return fs::read(&key_path)
+                    .map_err(|e| AppError::Storage(format!("Failed to read vault key: {}", e)));
+            }
+            Err(e) => return Err(AppError::Storage(format!("Failed to create vault key: {}", e))),
+        }
+
+        Ok(key)
+    }
+
+    fn load(&self) -> Result<BTreeMap<String, String>, AppError> {
+        let vault_path = self.dir.join(VAULT_FILE);
+        if !vault_path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let encrypted = fs::read_to_string(&vault_path)
+            .map_err(|e| AppError::Storage(format!("Failed to read vault: {}", e)))?;
+        let decrypted = crypto::decrypt_with_key(&self.key()?, &encrypted)?;
+        serde_json::from_slice(&decrypted)
+            .map_err(|e| AppError::Storage(format!("Failed to parse vault: {}", e)))
+    }
+
+    fn save(&self, secrets: &BTreeMap<String, String>) -> Result<(), AppError> {
+        let json_data = serde_json::to_vec(secrets)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize vault: {}", e)))?;
+        let encrypted = crypto::encrypt_with_key(&self.key()?, &json_data)?;
+        safe_file::write_atomic(&self.dir.join(VAULT_FILE), encrypted.as_bytes())
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<String>, AppError> {
+        Ok(self.load()?.remove(name))
+    }
+
+    pub fn set(&self, name: &str, secret: &str) -> Result<(), AppError> {
+        let mut secrets = self.load()?;
+        secrets.insert(name.to_string(), secret.to_string());
+        self.save(&secrets)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), AppError> {
+        let mut secrets = self.load()?;
+        if secrets.remove(name).is_some() {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+/// Use `dir` for the fallback vault
+pub fn configure(dir: PathBuf) {
+    let _ = VAULT_DIR.set(dir);
+}
+
+fn vault() -> Result<FallbackVault, AppError> {
+    VAULT_DIR
+        .get()
+        .cloned()
+        .map(FallbackVault::new)
+        .ok_or_else(|| AppError::Storage("No usable keyring and no vault configured".to_string()))
+}
+
+/// Write and read back a probe secret; some platforms accept writes without persisting them
+fn probe_keyring() -> bool {
+    let Ok(entry) = Entry::new(APP_NAME, PROBE_ENTRY) else {
+        return false;
+    };
+    if entry.set_password("probe").is_err() {
+        return false;
+    }
+
+    let persisted = Entry::new(APP_NAME, PROBE_ENTRY)
+        .and_then(|fresh| fresh.get_password())
+        .is_ok_and(|value| value == "probe");
+    let _ = entry.delete_credential();
+
+    if !persisted {
+        crate::log_warn!("vault", "OS keyring is not usable, storing secrets in the local vault");
+    }
+    persisted
+}
+
+/// The backend secrets are currently stored in
+pub fn backend() -> SecretBackend {
+    let works = *KEYRING_WORKS.get_or_init(|| !cfg!(test) && probe_keyring());
+    if works {
+        SecretBackend::Keyring
+    } else {
+        SecretBackend::Vault
+    }
+}
+
+pub fn status() -> SecretStorageStatus {
+    let backend = backend();
+    SecretStorageStatus {
+        backend,
+        warning: (backend == SecretBackend::Vault).then(|| {
+            "No OS keyring is available. Passwords are kept in an encrypted local file, \
+             which is less secure than a keyring."
+                .to_string()
+        }),
+    }
+}
+
+fn keyring_error(action: &str, e: keyring::Error) -> AppError {
+    AppError::Storage(format!("Failed to {}: {}", action, e))
+}
+
+/// Read a secret from the keyring, or from the vault when there is no keyring
+pub fn get_secret(name: &str) -> Result<Option<String>, AppError> {
+    match backend() {
+        SecretBackend::Keyring => {
+            let entry = Entry::new(APP_NAME, name).map_err(|e| keyring_error("access keyring", e))?;
+            match entry.get_password() {
+                Ok(secret) => Ok(Some(secret)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(e) => Err(keyring_error("get password", e)),
+            }
+        }
+        SecretBackend::Vault => vault()?.get(name),
+    }
+}
+
+/// Store a secret in the keyring, or in the vault when there is no keyring
+pub fn set_secret(name: &str, secret: &str) -> Result<(), AppError> {
+    match backend() {
+        SecretBackend::Keyring => Entry::new(APP_NAME, name)
+            .map_err(|e| keyring_error("access keyring", e))?
+            .set_password(secret)
+            .map_err(|e| keyring_error("save password", e)),
+        SecretBackend::Vault => vault()?.set(name, secret),
+    }
+}
+
+/// Remove a secret; missing secrets are not an error
+pub fn delete_secret(name: &str) -> Result<(), AppError> {
+    match backend() {
+        SecretBackend::Keyring => {
+            let entry = Entry::new(APP_NAME, name).map_err(|e| keyring_error("access keyring", e))?;
+            let _ = entry.delete_credential();
+            Ok(())
+        }
+        SecretBackend::Vault => vault()?.delete(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_fallback_vault() {
+        let temp_dir = TempDir::new().unwrap();
+        let vault = FallbackVault::new(temp_dir.path().to_path_buf());
+
+        assert_eq!(vault.get("profile_1").unwrap(), None);
+        vault.set("profile_1", "s3cret").unwrap();
+        vault.set("profile_2", "other").unwrap();
+        assert_eq!(vault.get("profile_1").unwrap().as_deref(), Some("s3cret"));
+
+        let on_disk = fs::read_to_string(temp_dir.path().join(VAULT_FILE)).unwrap();
+        assert!(!on_disk.contains("s3cret"));
+
+        vault.delete("profile_1").unwrap();
+        assert_eq!(vault.get("profile_1").unwrap(), None);
+        assert_eq!(vault.get("profile_2").unwrap().as_deref(), Some("other"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_vault_key_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        FallbackVault::new(temp_dir.path().to_path_buf()).set("name", "value").unwrap();
+
+        let mode = fs::metadata(temp_dir.path().join(VAULT_KEY_FILE)).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
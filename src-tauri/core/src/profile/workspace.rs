@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::error::AppError;
+use super::encrypted_file::EncryptedJsonFile;
+
+const WORKSPACE_FILE: &str = "workspace.encrypted";
+
+/// Page of a tab's result the user was looking at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResultPagination {
+    pub offset: usize,
+    pub page_size: usize,
+    /// Columns the result is ordered by, as given to `browse_query`
+    pub order_by: Vec<String>,
+}
+
+impl Default for ResultPagination {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            page_size: 100,
+            order_by: Vec::new(),
+        }
+    }
+}
+
+/// Editor tab with its SQL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditorTab {
+    pub id: String,
+    pub title: String,
+    pub sql: String,
+    /// Profile the tab runs its SQL on, when it is not the active connection
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Cursor position as a character offset into the SQL
+    #[serde(default)]
+    pub cursor: Option<usize>,
+    #[serde(default)]
+    pub pagination: Option<ResultPagination>,
+}
+
+/// Sizes and visibility of the window's panels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceLayout {
+    pub sidebar_width: u32,
+    pub sidebar_collapsed: bool,
+    /// Height of the result panel below the editor, in pixels
+    pub results_height: u32,
+    /// Panels that were open, by name (e.g. "history", "schema")
+    pub open_panels: Vec<String>,
+}
+
+impl Default for WorkspaceLayout {
+    fn default() -> Self {
+        Self {
+            sidebar_width: 280,
+            sidebar_collapsed: false,
+            results_height: 320,
+            open_panels: Vec::new(),
+        }
+    }
+}
+
+/// What the window looked like when the app was last closed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    /// Profile of the active connection
+    pub active_profile_id: Option<String>,
+    /// Profiles of the connections that were open, in the order of the connection list
+    pub open_profile_ids: Vec<String>,
+    pub tabs: Vec<EditorTab>,
+    pub active_tab_id: Option<String>,
+    pub layout: WorkspaceLayout,
+    pub saved_at: Option<DateTime<Utc>>,
+}
+
+impl Workspace {
+    /// Drop what refers to profiles that no longer exist
+    pub fn retain_profiles(&mut self, exists: impl Fn(&str) -> bool) {
+        self.open_profile_ids.retain(|id| exists(id));
+        if self.active_profile_id.as_deref().is_some_and(|id| !exists(id)) {
+            self.active_profile_id = None;
+        }
+        for tab in &mut self.tabs {
+            if tab.profile_id.as_deref().is_some_and(|id| !exists(id)) {
+                tab.profile_id = None;
+            }
+        }
+    }
+}
+
+/// The saved workspace, encrypted with the profile key like the SQL history
+pub struct WorkspaceStore {
+    file: EncryptedJsonFile,
+}
+
+impl WorkspaceStore {
+    pub fn new(profiles_dir: &Path) -> Self {
+        Self {
+            file: EncryptedJsonFile::new(profiles_dir.join(WORKSPACE_FILE)),
+        }
+    }
+
+    /// The saved workspace; an empty one when none was saved yet
+    pub fn load(&self) -> Result<Workspace, AppError> {
+        self.file.load()
+    }
+
+    pub fn save(&self, mut workspace: Workspace) -> Result<Workspace, AppError> {
+        if let Some(active) = &workspace.active_tab_id {
+            if !workspace.tabs.iter().any(|tab| &tab.id == active) {
+                workspace.active_tab_id = None;
+            }
+        }
+        workspace.saved_at = Some(Utc::now());
+
+        self.file.update(|saved: &mut Workspace| {
+            *saved = workspace.clone();
+            Ok(())
+        })?;
+        Ok(workspace)
+    }
+
+    pub fn clear(&self) -> Result<(), AppError> {
+        self.file.update(|saved: &mut Workspace| {
+            *saved = Workspace::default();
+            Ok(())
+        })
+    }
+
+    pub fn reencrypt(&self, from: &[u8], to: &[u8]) -> Result<(), AppError> {
+        self.file.reencrypt(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_restore() {
+        let dir = TempDir::new().unwrap();
+        let store = WorkspaceStore::new(dir.path());
+        assert_eq!(store.load().unwrap(), Workspace::default());
+
+        let tab = EditorTab {
+            id: "tab-1".to_string(),
+            title: "Orders".to_string(),
+            sql: "SELECT * FROM orders".to_string(),
+            profile_id: Some("gone".to_string()),
+            cursor: Some(8),
+            pagination: Some(ResultPagination { offset: 200, ..Default::default() }),
+        };
+        let saved = store
+            .save(Workspace {
+                active_profile_id: Some("local".to_string()),
+                open_profile_ids: vec!["local".to_string(), "gone".to_string()],
+                tabs: vec![tab],
+                active_tab_id: Some("closed-tab".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(saved.active_tab_id, None);
+
+        let mut restored = store.load().unwrap();
+        assert_eq!(restored, saved);
+        restored.retain_profiles(|id| id == "local");
+        assert_eq!(restored.open_profile_ids, vec!["local"]);
+        assert_eq!(restored.tabs[0].profile_id, None);
+        assert_eq!(restored.tabs[0].pagination.as_ref().unwrap().offset, 200);
+
+        store.clear().unwrap();
+        assert!(store.load().unwrap().tabs.is_empty());
+    }
+}
@@ -0,0 +1,369 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use crate::database::adapter::DatabaseType;
+use crate::error::AppError;
+use crate::profile::safe_file::FileLock;
+
+const AUDIT_FILE: &str = "audit.jsonl";
+
+/// One write statement that was run against a database
+///
+/// Entries form a hash chain: each one stores the hash of the entry before it, so
+/// removing or editing an entry in the file breaks every hash that follows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// OS account that ran the statement
+    pub user: String,
+    pub profile_id: Option<String>,
+    /// Profile name, or a description of the connection made without a profile
+    pub connection: String,
+    pub database_type: DatabaseType,
+    pub database: Option<String>,
+    pub statement: String,
+    pub rows_affected: Option<u64>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub prev_hash: String,
+    #[serde(default)]
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Hash of the entry's contents, including the previous hash
+    fn compute_hash(&self) -> Result<String, AppError> {
+        let unhashed = AuditEntry { hash: String::new(), ..self.clone() };
+        let json = serde_json::to_vec(&unhashed)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize audit entry: {}", e)))?;
+        Ok(format!("{:x}", Sha256::digest(&json)))
+    }
+}
+
+/// Name of the OS account running the application
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Criteria for searching the audit log
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub profile_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Case-insensitive text the statement must contain
+    pub text: Option<String>,
+    pub failed_only: bool,
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        if self.profile_id.is_some() && entry.profile_id != self.profile_id {
+            return false;
+        }
+        if self.since.is_some_and(|since| entry.timestamp < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| entry.timestamp > until) {
+            return false;
+        }
+        if self.failed_only && entry.success {
+            return false;
+        }
+        match self.text.as_deref() {
+            Some(text) => entry.statement.to_lowercase().contains(&text.to_lowercase()),
+            None => true,
+        }
+    }
+}
+
+/// File format of an audit log export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditExportFormat {
+    Json,
+    Csv,
+}
+
+/// Result of checking the hash chain of the audit log
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditVerification {
+    pub entries: usize,
+    pub valid: bool,
+    /// Line number (1-based) of the first entry that does not fit the chain
+    pub first_invalid_line: Option<usize>,
+}
+
+/// Append-only audit log stored as JSON lines
+pub struct AuditLog {
+    audit_path: PathBuf,
+}
+
+impl AuditLog {
+    /// Open the audit log in the app data directory
+    pub fn new(app_handle: &AppHandle) -> Result<Self, AppError> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Storage(format!("Could not resolve app data directory: {}", e)))?;
+
+        let audit_dir = app_data_dir.join("audit");
+        fs::create_dir_all(&audit_dir).map_err(|e| {
+            AppError::Storage(format!("Failed to create audit directory: {}", e))
+        })?;
+
+        Ok(Self::with_path(audit_dir.join(AUDIT_FILE)))
+    }
+
+    /// Open an audit log at a specific path
+    pub fn with_path(audit_path: PathBuf) -> Self {
+        Self { audit_path }
+    }
+
+    fn read_entries(&self) -> Result<Vec<AuditEntry>, AppError> {
+        if !self.audit_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = File::open(&self.audit_path)
+            .map_err(|e| AppError::Storage(format!("Failed to open audit log: {}", e)))?;
+
+        let mut entries = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| AppError::Storage(format!("Failed to read audit log: {}", e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|e| {
+                AppError::Storage(format!("Invalid audit log entry on line {}: {}", index + 1, e))
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Hash of the newest entry, read from the last line of the file
+    fn last_hash(&self) -> Result<String, AppError> {
+        let read_error = |e: std::io::Error| AppError::Storage(format!("Failed to read audit log: {}", e));
+        let mut file = match File::open(&self.audit_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+            Err(e) => return Err(read_error(e)),
+        };
+
+        // Read backwards until the tail holds a whole line before the trailing newline
+        let mut position = file.seek(SeekFrom::End(0)).map_err(read_error)?;
+        let mut tail: Vec<u8> = Vec::new();
+        while position > 0 && !tail.trim_ascii_end().contains(&b'\n') {
+            let block = position.min(4096);
+            position -= block;
+            let mut chunk = vec![0; block as usize];
+            file.seek(SeekFrom::Start(position)).map_err(read_error)?;
+            file.read_exact(&mut chunk).map_err(read_error)?;
+            chunk.extend(tail);
+            tail = chunk;
+        }
+
+        let tail = String::from_utf8_lossy(&tail);
+        let Some(line) = tail.lines().rev().find(|line| !line.trim().is_empty()) else {
+            return Ok(String::new());
+        };
+        let entry: AuditEntry = serde_json::from_str(line)
+            .map_err(|e| AppError::Storage(format!("Invalid last audit log entry: {}", e)))?;
+        Ok(entry.hash)
+    }
+
+    /// Append an entry, chaining it to the newest one
+    ///
+    /// The newest hash is read from the file under the lock every time, so
+    /// entries appended by other instances or processes stay in the chain.
+    pub fn append(&self, mut entry: AuditEntry) -> Result<(), AppError> {
+        let _lock = FileLock::acquire(&self.audit_path)?;
+
+        entry.prev_hash = self.last_hash()?;
+        entry.hash = entry.compute_hash()?;
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize audit entry: {}", e)))?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.audit_path)
+            .map_err(|e| AppError::Storage(format!("Failed to open audit log: {}", e)))?;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| AppError::Storage(format!("Failed to write audit log: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Entries matching the filter, newest first
+    pub fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, AppError> {
+        let entries = self.read_entries()?
+            .into_iter()
+            .rev()
+            .filter(|e| filter.matches(e))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect();
+        Ok(entries)
+    }
+
+    /// Write the entries matching the filter to a file, returning how many were written
+    pub fn export(&self, path: &Path, format: AuditExportFormat, filter: &AuditFilter) -> Result<usize, AppError> {
+        let entries = self.query(filter)?;
+        let file = File::create(path)
+            .map_err(|e| AppError::Storage(format!("Failed to create export file: {}", e)))?;
+
+        match format {
+            AuditExportFormat::Json => {
+                serde_json::to_writer_pretty(BufWriter::new(file), &entries)
+                    .map_err(|e| AppError::Storage(format!("Failed to write audit export: {}", e)))?;
+            }
+            AuditExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                for entry in &entries {
+                    writer.serialize(entry)
+                        .map_err(|e| AppError::Storage(format!("Failed to write audit export: {}", e)))?;
+                }
+                writer.flush()
+                    .map_err(|e| AppError::Storage(format!("Failed to write audit export: {}", e)))?;
+            }
+        }
+
+        Ok(entries.len())
+    }
+
+    /// Check that no entry was changed or removed since it was written
+    pub fn verify(&self) -> Result<AuditVerification, AppError> {
+        let entries = self.read_entries()?;
+
+        let mut prev_hash = String::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if entry.prev_hash != prev_hash || entry.compute_hash()? != entry.hash {
+                return Ok(AuditVerification {
+                    entries: entries.len(),
+                    valid: false,
+                    first_invalid_line: Some(index + 1),
+                });
+            }
+            prev_hash = entry.hash.clone();
+        }
+
+        Ok(AuditVerification {
+            entries: entries.len(),
+            valid: true,
+            first_invalid_line: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(statement: &str, success: bool) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            user: "tester".to_string(),
+            profile_id: Some("p1".to_string()),
+            connection: "Production".to_string(),
+            database_type: DatabaseType::PostgreSQL,
+            database: Some("app".to_string()),
+            statement: statement.to_string(),
+            rows_affected: success.then_some(1),
+            success,
+            error: (!success).then(|| "failed".to_string()),
+            duration_ms: 3,
+            prev_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_append_and_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = AuditLog::with_path(temp_dir.path().join(AUDIT_FILE));
+
+        log.append(entry("INSERT INTO t VALUES (1)", true)).unwrap();
+        log.append(entry("DELETE FROM t WHERE id = 1", false)).unwrap();
+
+        let all = log.query(&AuditFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(all[0].statement.starts_with("DELETE"));
+        assert_eq!(all[0].prev_hash, all[1].hash);
+
+        let failed = log.query(&AuditFilter { failed_only: true, ..Default::default() }).unwrap();
+        assert_eq!(failed.len(), 1);
+
+        let text = log.query(&AuditFilter { text: Some("insert".to_string()), ..Default::default() }).unwrap();
+        assert_eq!(text.len(), 1);
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(AUDIT_FILE);
+        let log = AuditLog::with_path(path.clone());
+
+        for statement in ["UPDATE t SET a = 1", "UPDATE t SET a = 2", "UPDATE t SET a = 3"] {
+            log.append(entry(statement, true)).unwrap();
+        }
+        assert!(log.verify().unwrap().valid);
+
+        let contents = fs::read_to_string(&path).unwrap().replace("a = 2", "a = 9");
+        fs::write(&path, contents).unwrap();
+
+        let verification = log.verify().unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.first_invalid_line, Some(2));
+    }
+
+    #[test]
+    fn test_chain_continues_across_instances() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(AUDIT_FILE);
+
+        AuditLog::with_path(path.clone()).append(entry("INSERT INTO t VALUES (1)", true)).unwrap();
+        AuditLog::with_path(path.clone()).append(entry("INSERT INTO t VALUES (2)", true)).unwrap();
+
+        // Two open logs writing in turn, as two app instances would
+        let first = AuditLog::with_path(path.clone());
+        let second = AuditLog::with_path(path.clone());
+        first.append(entry("INSERT INTO t VALUES (3)", true)).unwrap();
+        second.append(entry("INSERT INTO t VALUES (4)", true)).unwrap();
+        first.append(entry(&"x".repeat(5000), true)).unwrap();
+        second.append(entry("INSERT INTO t VALUES (5)", true)).unwrap();
+
+        let verification = AuditLog::with_path(path).verify().unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.entries, 6);
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = AuditLog::with_path(temp_dir.path().join(AUDIT_FILE));
+        log.append(entry("INSERT INTO t VALUES (1)", true)).unwrap();
+
+        let export_path = temp_dir.path().join("audit.csv");
+        let written = log.export(&export_path, AuditExportFormat::Csv, &AuditFilter::default()).unwrap();
+
+        assert_eq!(written, 1);
+        let csv = fs::read_to_string(export_path).unwrap();
+        assert!(csv.starts_with("timestamp,user,"));
+        assert!(csv.contains("INSERT INTO t VALUES (1)"));
+    }
+}
@@ -0,0 +1,245 @@
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::AppError;
+
+/// Operations a script may perform before it is stopped, so a runaway loop ends
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+/// Nested function calls allowed, well below what overflows the stack
+const MAX_CALL_LEVELS: usize = 32;
+
+/// Largest string, array and map a script may build
+const MAX_STRING_SIZE: usize = 10 * 1024 * 1024;
+const MAX_ARRAY_SIZE: usize = 1_000_000;
+const MAX_MAP_SIZE: usize = 10_000;
+
+/// Rows returned to a script by a query, with the columns in result order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+/// What a script can do outside of the sandbox
+///
+/// Statements are run through the host, so the connection's read-only mode,
+/// statement rules and masking apply to them as to statements from the editor.
+pub trait ScriptHost {
+    fn query(&self, sql: &str) -> Result<ScriptTable, String>;
+
+    /// Rows affected by the statements
+    fn execute(&self, sql: &str) -> Result<u64, String>;
+}
+
+/// Outcome of a script run
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptRun {
+    /// Lines written with `log` or `print`
+    pub output: Vec<String>,
+    /// Value of the last expression, unless it has none
+    pub result: Option<String>,
+    /// Files written with `export_csv`
+    pub exported: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// Runs automation scripts written in Rhai
+///
+/// Scripts have no access to the file system, the network or modules. They may
+/// only call these functions:
+///
+/// - `query(sql)`: rows of the last result as an array of maps, NULL being `()`
+/// - `execute(sql)`: number of rows affected
+/// - `export_csv(sql, file_name)`: write the result to a CSV file in the export
+///   directory and return its number of rows
+/// - `log(message)`, `print(message)`: add a line to the output
+pub struct ScriptRunner {
+    export_dir: PathBuf,
+}
+
+impl ScriptRunner {
+    pub fn new(export_dir: &Path) -> Self {
+        Self {
+            export_dir: export_dir.to_path_buf(),
+        }
+    }
+
+    /// Run `source` to completion; the run stops with `AppError::Cancelled` once
+    /// `cancel` is triggered
+    pub fn run(&self, source: &str, host: Arc<dyn ScriptHost>, cancel: CancellationToken) -> Result<ScriptRun, AppError> {
+        let start = Instant::now();
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let exported = Arc::new(Mutex::new(Vec::new()));
+
+        let mut engine = Engine::new();
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_max_string_size(MAX_STRING_SIZE);
+        engine.set_max_array_size(MAX_ARRAY_SIZE);
+        engine.set_max_map_size(MAX_MAP_SIZE);
+        engine.on_progress(move |_| cancel.is_cancelled().then(|| Dynamic::from("cancelled")));
+
+        let lines = output.clone();
+        engine.on_print(move |message| {
+            crate::log_info!("automation", "{}", message);
+            lines.lock().unwrap_or_else(|e| e.into_inner()).push(message.to_string());
+        });
+        let lines = output.clone();
+        engine.register_fn("log", move |message: &str| {
+            crate::log_info!("automation", "{}", message);
+            lines.lock().unwrap_or_else(|e| e.into_inner()).push(message.to_string());
+        });
+
+        let query_host = host.clone();
+        engine.register_fn("query", move |sql: &str| -> Result<Array, Box<EvalAltResult>> {
+            let table = query_host.query(sql)?;
+            Ok(rows_to_array(table))
+        });
+
+        let execute_host = host.clone();
+        engine.register_fn("execute", move |sql: &str| -> Result<i64, Box<EvalAltResult>> {
+            let affected = execute_host.execute(sql)?;
+            Ok(i64::try_from(affected).unwrap_or(i64::MAX))
+        });
+
+        let export_dir = self.export_dir.clone();
+        let files = exported.clone();
+        engine.register_fn("export_csv", move |sql: &str, file_name: &str| -> Result<i64, Box<EvalAltResult>> {
+            let path = export_path(&export_dir, file_name)?;
+            let table = host.query(sql)?;
+            write_csv(&path, &table).map_err(|e| e.to_string())?;
+            files.lock().unwrap_or_else(|e| e.into_inner()).push(path.to_string_lossy().into_owned());
+            Ok(table.rows.len() as i64)
+        });
+
+        let result = engine.eval::<Dynamic>(source).map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) => AppError::Cancelled,
+            e => AppError::Validation(format!("Script failed: {}", e)),
+        })?;
+
+        let output = std::mem::take(&mut *output.lock().unwrap_or_else(|e| e.into_inner()));
+        let exported = std::mem::take(&mut *exported.lock().unwrap_or_else(|e| e.into_inner()));
+        Ok(ScriptRun {
+            output,
+            result: (!result.is_unit()).then(|| result.to_string()),
+            exported,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+fn rows_to_array(table: ScriptTable) -> Array {
+    table
+        .rows
+        .into_iter()
+        .map(|values| {
+            let row: Map = table
+                .columns
+                .iter()
+                .zip(values)
+                .map(|(column, value)| (column.as_str().into(), value.map_or(Dynamic::UNIT, Dynamic::from)))
+                .collect();
+            Dynamic::from_map(row)
+        })
+        .collect()
+}
+
+/// Path of an export file; scripts name a file, not a directory to write to
+fn export_path(export_dir: &Path, file_name: &str) -> Result<PathBuf, String> {
+    let valid = !file_name.is_empty()
+        && !file_name.starts_with('.')
+        && !file_name.contains(['/', '\\', ':']);
+    if !valid {
+        return Err(format!("Invalid export file name: '{}'", file_name));
+    }
+
+    std::fs::create_dir_all(export_dir).map_err(|e| format!("Failed to create export directory: {}", e))?;
+    Ok(export_dir.join(file_name))
+}
+
+fn write_csv(path: &Path, table: &ScriptTable) -> Result<(), AppError> {
+    let mut writer = ::csv::Writer::from_path(path).map_err(|e| AppError::Io(e.into()))?;
+    writer.write_record(&table.columns).map_err(|e| AppError::Io(e.into()))?;
+    for row in &table.rows {
+        writer
+            .write_record(row.iter().map(|v| v.as_deref().unwrap_or("")))
+            .map_err(|e| AppError::Io(e.into()))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    struct FakeHost;
+
+    impl ScriptHost for FakeHost {
+        fn query(&self, sql: &str) -> Result<ScriptTable, String> {
+            if sql != "SELECT id, name FROM users" {
+                return Err(format!("unexpected query: {}", sql));
+            }
+            Ok(ScriptTable {
+                columns: vec!["id".to_string(), "name".to_string()],
+                rows: vec![
+                    vec![Some("1".to_string()), Some("Ada".to_string())],
+                    vec![Some("2".to_string()), None],
+                ],
+            })
+        }
+
+        fn execute(&self, _sql: &str) -> Result<u64, String> {
+            Ok(3)
+        }
+    }
+
+    fn run(source: &str, export_dir: &Path, cancel: CancellationToken) -> Result<ScriptRun, AppError> {
+        ScriptRunner::new(export_dir).run(source, Arc::new(FakeHost), cancel)
+    }
+
+    #[test]
+    fn test_script_api() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = r#"
+            let named = 0;
+            for row in query("SELECT id, name FROM users") {
+                if row.name != () { named += 1; }
+                log(`user ${row.id}`);
+            }
+            print("updated " + execute("UPDATE users SET active = true"));
+            export_csv("SELECT id, name FROM users", "users.csv");
+            named
+        "#;
+
+        let run = run(source, temp_dir.path(), CancellationToken::new()).unwrap();
+        assert_eq!(run.output, vec!["user 1", "user 2", "updated 3"]);
+        assert_eq!(run.result.as_deref(), Some("1"));
+        assert_eq!(run.exported.len(), 1);
+        let csv = std::fs::read_to_string(temp_dir.path().join("users.csv")).unwrap();
+        assert_eq!(csv, "id,name\n1,Ada\n2,\n");
+    }
+
+    #[test]
+    fn test_sandbox() {
+        let temp_dir = TempDir::new().unwrap();
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+
+        assert!(matches!(run("loop {}", temp_dir.path(), cancelled), Err(AppError::Cancelled)));
+        assert!(run(r#"import "os" as os;"#, temp_dir.path(), CancellationToken::new()).is_err());
+        assert!(run(r#"query("DROP TABLE users")"#, temp_dir.path(), CancellationToken::new()).is_err());
+        for name in ["../users.csv", "/tmp/users.csv", ".hidden", ""] {
+            let source = format!(r#"export_csv("SELECT id, name FROM users", "{}")"#, name);
+            assert!(run(&source, temp_dir.path(), CancellationToken::new()).is_err(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::database::adapter::DatabaseType;
+use crate::error::AppError;
+
+const CATALOG_FILE: &str = "catalog.json";
+
+/// A backup file known to the application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub id: String,
+    pub profile_id: Option<String>,
+    /// Schedule that produced the backup, if it was taken automatically
+    #[serde(default)]
+    pub schedule_id: Option<String>,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub path: String,
+    /// Output format, e.g. "plain" or "custom"
+    pub format: String,
+    /// Tool that produced the backup, e.g. "pg_dump"
+    pub tool: String,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BackupEntry {
+    pub fn new(
+        profile_id: Option<String>,
+        database_type: DatabaseType,
+        database: String,
+        path: String,
+        format: String,
+        tool: String,
+    ) -> Self {
+        let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            profile_id,
+            schedule_id: None,
+            database_type,
+            database,
+            path,
+            format,
+            tool,
+            size_bytes,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// JSON file listing the backups that have been taken
+pub struct BackupCatalog {
+    catalog_path: PathBuf,
+}
+
+impl BackupCatalog {
+    /// Open the catalog in Tauri's app data directory
+    pub fn new(app_handle: &AppHandle) -> Result<Self, AppError> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Storage(format!("Could not resolve app data directory: {}", e)))?;
+
+        let backups_dir = app_data_dir.join("backups");
+        fs::create_dir_all(&backups_dir).map_err(|e| {
+            AppError::Storage(format!("Failed to create backups directory: {}", e))
+        })?;
+
+        Ok(Self::with_path(backups_dir.join(CATALOG_FILE)))
+    }
+
+    /// Open a catalog stored at a specific path
+    pub fn with_path(catalog_path: PathBuf) -> Self {
+        Self { catalog_path }
+    }
+
+    /// List all backups, newest first
+    pub fn list(&self) -> Result<Vec<BackupEntry>, AppError> {
+        if !self.catalog_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read_to_string(&self.catalog_path).map_err(|e| {
+            AppError::Storage(format!("Failed to read backup catalog: {}", e))
+        })?;
+        let mut entries: Vec<BackupEntry> = serde_json::from_str(&data).map_err(|e| {
+            AppError::Storage(format!("Failed to parse backup catalog: {}", e))
+        })?;
+        entries.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+
+        Ok(entries)
+    }
+
+    /// Get a backup by ID
+    pub fn get(&self, id: &str) -> Result<BackupEntry, AppError> {
+        self.list()?
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Backup {} not found", id)))
+    }
+
+    /// Register a new backup
+    pub fn add(&self, entry: BackupEntry) -> Result<BackupEntry, AppError> {
+        let mut entries = self.list()?;
+        entries.push(entry.clone());
+        self.save(&entries)?;
+        Ok(entry)
+    }
+
+    /// Remove a backup from the catalog, returning the removed entry
+    pub fn remove(&self, id: &str) -> Result<BackupEntry, AppError> {
+        let mut entries = self.list()?;
+        let index = entries
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Backup {} not found", id)))?;
+        let removed = entries.remove(index);
+        self.save(&entries)?;
+        Ok(removed)
+    }
+
+    fn save(&self, entries: &[BackupEntry]) -> Result<(), AppError> {
+        let data = serde_json::to_string_pretty(entries).map_err(|e| {
+            AppError::Storage(format!("Failed to serialize backup catalog: {}", e))
+        })?;
+        fs::write(&self.catalog_path, data).map_err(|e| {
+            AppError::Storage(format!("Failed to write backup catalog: {}", e))
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = BackupCatalog::with_path(dir.path().join(CATALOG_FILE));
+        assert!(catalog.list().unwrap().is_empty());
+
+        let entry = catalog
+            .add(BackupEntry::new(
+                Some("profile-1".to_string()),
+                DatabaseType::PostgreSQL,
+                "app".to_string(),
+                dir.path().join("app.dump").to_string_lossy().to_string(),
+                "custom".to_string(),
+                "pg_dump".to_string(),
+            ))
+            .unwrap();
+
+        let entries = catalog.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].database, "app");
+
+        catalog.remove(&entry.id).unwrap();
+        assert!(catalog.list().unwrap().is_empty());
+        assert!(catalog.remove(&entry.id).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::tool::ToolCommand;
+use crate::database::adapter::{ConnectionParams, DatabaseType};
+use crate::error::AppError;
+
+/// Output format of a dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpFormat {
+    /// Plain SQL script
+    #[default]
+    Plain,
+    /// pg_dump's compressed custom archive (PostgreSQL only)
+    Custom,
+    /// pg_dump's tar archive (PostgreSQL only)
+    Tar,
+}
+
+impl DumpFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DumpFormat::Plain => "plain",
+            DumpFormat::Custom => "custom",
+            DumpFormat::Tar => "tar",
+        }
+    }
+
+    /// Parse a format name as stored in the backup catalog
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "plain" => Some(DumpFormat::Plain),
+            "custom" => Some(DumpFormat::Custom),
+            "tar" => Some(DumpFormat::Tar),
+            _ => None,
+        }
+    }
+}
+
+/// Name of the dump tool for a database type
+pub fn dump_tool_name(database_type: DatabaseType) -> Result<&'static str, AppError> {
+    match database_type {
+        DatabaseType::PostgreSQL => Ok("pg_dump"),
+        DatabaseType::MySQL => Ok("mysqldump"),
+        DatabaseType::SQLite => Err(AppError::Validation(
+            "SQLite databases are backed up without an external tool".to_string(),
+        )),
+        DatabaseType::Other(id) => Err(AppError::Validation(format!(
+            "Backups are not supported for {} databases",
+            id
+        ))),
+    }
+}
+
+/// Build the pg_dump or mysqldump invocation for the given connection
+pub fn dump_command(
+    program: &Path,
+    params: &ConnectionParams,
+    output: &Path,
+    format: DumpFormat,
+) -> Result<ToolCommand, AppError> {
+    dump_tool_name(params.database_type)?;
+
+    let output = output.to_string_lossy();
+    let command = ToolCommand::for_connection(program, params);
+
+    let command = match params.database_type {
+        DatabaseType::MySQL => {
+            if format != DumpFormat::Plain {
+                return Err(AppError::Validation(
+                    "mysqldump only supports plain SQL output".to_string(),
+                ));
+            }
+            command
+                .arg("--single-transaction")
+                .arg("--routines")
+                .arg("--triggers")
+                .arg(format!("--result-file={}", output))
+                .arg("--verbose")
+                // A database name starting with `-` is not taken for an option
+                .arg("--")
+                .arg(params.database.clone())
+        }
+        _ => command
+            .arg(format!("--format={}", format.as_str()))
+            .arg(format!("--file={}", output))
+            .arg("--verbose")
+            .arg(format!("--dbname={}", params.database)),
+    };
+
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(database_type: DatabaseType) -> ConnectionParams {
+        let mut params = ConnectionParams::new(database_type, "app".to_string());
+        params.username = Some("admin".to_string());
+        params.password = Some("s3cret".to_string());
+        params
+    }
+
+    #[test]
+    fn test_pg_dump_command() {
+        let command = dump_command(
+            Path::new("pg_dump"),
+            &params(DatabaseType::PostgreSQL),
+            Path::new("/tmp/app.dump"),
+            DumpFormat::Custom,
+        )
+        .unwrap();
+
+        assert!(command.args.contains(&"--format=custom".to_string()));
+        assert!(command.args.contains(&"--username=admin".to_string()));
+        assert_eq!(command.args.last().unwrap(), "--dbname=app");
+        assert!(!command.args.iter().any(|a| a.contains("s3cret")));
+        assert_eq!(command.env, vec![("PGPASSWORD".to_string(), "s3cret".to_string())]);
+    }
+
+    #[test]
+    fn test_mysqldump_command() {
+        let command = dump_command(
+            Path::new("mysqldump"),
+            &params(DatabaseType::MySQL),
+            Path::new("/tmp/app.sql"),
+            DumpFormat::Plain,
+        )
+        .unwrap();
+
+        assert!(command.args.contains(&"--result-file=/tmp/app.sql".to_string()));
+        assert_eq!(command.args[command.args.len() - 2..], ["--".to_string(), "app".to_string()]);
+        assert!(!command.args.iter().any(|a| a.contains("s3cret")));
+        assert_eq!(command.env, vec![("MYSQL_PWD".to_string(), "s3cret".to_string())]);
+
+        assert!(dump_command(
+            Path::new("mysqldump"),
+            &params(DatabaseType::MySQL),
+            Path::new("/tmp/app.sql"),
+            DumpFormat::Custom,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_sqlite_has_no_dump_tool() {
+        assert!(dump_tool_name(DatabaseType::SQLite).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod catalog;
+pub mod dump;
+pub mod restore;
+pub mod schedule;
+pub mod sqlite;
+pub mod tool;
+
+pub use catalog::{BackupCatalog, BackupEntry};
@@ -0,0 +1,443 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::dump::DumpFormat;
+use super::tool::ToolCommand;
+use crate::database::adapter::{ConnectionParams, DatabaseAdapter, DatabaseType};
+use crate::database::sql_utils::split_sql_statements;
+use crate::error::AppError;
+
+/// How a dump is fed back into the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMethod {
+    /// pg_restore for custom and tar archives
+    PgRestore,
+    /// psql for plain PostgreSQL scripts
+    Psql,
+    /// mysql client for plain MySQL scripts
+    Mysql,
+    /// The built-in script runner over the app's own connection
+    ScriptRunner,
+}
+
+impl RestoreMethod {
+    /// Client tool used by this method, if any
+    pub fn tool_name(&self) -> Option<&'static str> {
+        match self {
+            RestoreMethod::PgRestore => Some("pg_restore"),
+            RestoreMethod::Psql => Some("psql"),
+            RestoreMethod::Mysql => Some("mysql"),
+            RestoreMethod::ScriptRunner => None,
+        }
+    }
+
+    /// Pick the restore method for a dump format and target database
+    pub fn select(
+        format: DumpFormat,
+        database_type: DatabaseType,
+        use_script_runner: bool,
+    ) -> Result<Self, AppError> {
+        match (format, database_type) {
+            (DumpFormat::Custom | DumpFormat::Tar, DatabaseType::PostgreSQL) => {
+                Ok(RestoreMethod::PgRestore)
+            }
+            (DumpFormat::Custom | DumpFormat::Tar, _) => Err(AppError::Validation(
+                "Archive dumps can only be restored into PostgreSQL".to_string(),
+            )),
+            (DumpFormat::Plain, _) if use_script_runner => Ok(RestoreMethod::ScriptRunner),
+            (DumpFormat::Plain, DatabaseType::PostgreSQL) => Ok(RestoreMethod::Psql),
+            (DumpFormat::Plain, DatabaseType::MySQL) => Ok(RestoreMethod::Mysql),
+            (DumpFormat::Plain, DatabaseType::SQLite | DatabaseType::Other(_)) => {
+                Ok(RestoreMethod::ScriptRunner)
+            }
+        }
+    }
+}
+
+/// Options for restoring a dump
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestoreOptions {
+    /// Drop existing objects before recreating them (pg_restore archives only)
+    pub clean: bool,
+    /// Run plain SQL dumps through the app's connection instead of a client tool
+    pub use_script_runner: bool,
+    /// Explicit location of the client tool
+    pub tool_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreAction {
+    Create,
+    Drop,
+    /// Rows loaded into a table
+    Load,
+}
+
+/// A database object the restore will touch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestorePlanItem {
+    pub action: RestoreAction,
+    pub object_type: String,
+    pub name: String,
+}
+
+impl RestorePlanItem {
+    fn new(action: RestoreAction, object_type: &str, name: &str) -> Self {
+        Self {
+            action,
+            object_type: object_type.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Outcome of a restore or a dry run
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub method: RestoreMethod,
+    pub plan: Vec<RestorePlanItem>,
+    pub executed: bool,
+}
+
+/// Object types recognized after CREATE/DROP, longest first so multi-word types win
+const OBJECT_TYPES: &[&str] = &[
+    "MATERIALIZED VIEW",
+    "FOREIGN TABLE",
+    "TABLE",
+    "VIEW",
+    "INDEX",
+    "SEQUENCE",
+    "SCHEMA",
+    "DATABASE",
+    "FUNCTION",
+    "PROCEDURE",
+    "TRIGGER",
+    "TYPE",
+    "DOMAIN",
+    "EXTENSION",
+    "EVENT",
+];
+
+/// Words that may appear between CREATE/DROP and the object type
+const MODIFIERS: &[&str] = &["OR", "REPLACE", "UNIQUE", "TEMPORARY", "TEMP", "UNLOGGED", "DEFINER"];
+
+fn clean_name(token: &str) -> String {
+    token
+        .split('(')
+        .next()
+        .unwrap_or("")
+        .trim_end_matches(';')
+        .replace(['"', '`'], "")
+}
+
+/// Classify the first line of a statement in a SQL dump
+fn classify_line(line: &str) -> Option<RestorePlanItem> {
+    let upper = line.to_uppercase();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let upper_words: Vec<&str> = upper.split_whitespace().collect();
+
+    let action = match *upper_words.first()? {
+        "CREATE" => RestoreAction::Create,
+        "DROP" => RestoreAction::Drop,
+        "COPY" => {
+            let table = clean_name(words.get(1)?);
+            return Some(RestorePlanItem::new(RestoreAction::Load, "TABLE DATA", &table));
+        }
+        "INSERT" if upper_words.get(1) == Some(&"INTO") => {
+            let table = clean_name(words.get(2)?);
+            return Some(RestorePlanItem::new(RestoreAction::Load, "TABLE DATA", &table));
+        }
+        _ => return None,
+    };
+
+    // Skip modifiers such as OR REPLACE / UNIQUE and MySQL's DEFINER=`user`@`host`
+    let mut index = 1;
+    while upper_words
+        .get(index)
+        .is_some_and(|w| MODIFIERS.iter().any(|m| w == m || w.starts_with("DEFINER=")))
+    {
+        index += 1;
+    }
+
+    let rest = upper_words[index.min(upper_words.len())..].join(" ");
+    let object_type = OBJECT_TYPES
+        .iter()
+        .find(|t| rest == **t || rest.starts_with(&format!("{} ", t)))?;
+    index += object_type.split(' ').count();
+
+    // Skip IF [NOT] EXISTS and PostgreSQL's CONCURRENTLY
+    while upper_words
+        .get(index)
+        .is_some_and(|w| ["IF", "NOT", "EXISTS", "CONCURRENTLY"].contains(w))
+    {
+        index += 1;
+    }
+
+    Some(RestorePlanItem::new(action, object_type, &clean_name(words.get(index)?)))
+}
+
+/// List the objects a plain SQL dump will create, drop, or load data into
+pub fn plan_sql_script(sql: &str) -> Vec<RestorePlanItem> {
+    let mut plan: Vec<RestorePlanItem> = Vec::new();
+
+    for line in sql.lines() {
+        let Some(item) = classify_line(line.trim()) else {
+            continue;
+        };
+        // Consecutive INSERTs into the same table are one load
+        if item.action == RestoreAction::Load && plan.last() == Some(&item) {
+            continue;
+        }
+        plan.push(item);
+    }
+
+    plan
+}
+
+/// List the objects in a pg_restore archive from its `pg_restore --list` output
+///
+/// Each TOC line looks like `215; 1259 16386 TABLE public users postgres`. With `clean`,
+/// pg_restore drops every object first, in reverse order.
+pub fn plan_pg_archive(toc: &str, clean: bool) -> Vec<RestorePlanItem> {
+    const ARCHIVE_TYPES: &[&str] = &[
+        "TABLE DATA",
+        "SEQUENCE SET",
+        "SEQUENCE OWNED BY",
+        "FK CONSTRAINT",
+        "MATERIALIZED VIEW DATA",
+        "MATERIALIZED VIEW",
+        "DEFAULT ACL",
+        "CONSTRAINT",
+        "DEFAULT",
+        "COMMENT",
+        "ACL",
+    ];
+
+    let mut created = Vec::new();
+    for line in toc.lines() {
+        if line.starts_with(';') {
+            continue;
+        }
+        let Some((_, entry)) = line.split_once("; ") else {
+            continue;
+        };
+        // Skip the table OID and object OID
+        let description: Vec<&str> = entry.split_whitespace().skip(2).collect();
+        let joined = description.join(" ");
+
+        let type_words = ARCHIVE_TYPES
+            .iter()
+            .find(|t| joined.starts_with(&format!("{} ", t)))
+            .map(|t| t.split(' ').count())
+            .unwrap_or(1);
+        if description.len() < type_words + 2 {
+            continue;
+        }
+
+        let object_type = description[..type_words].join(" ");
+        let schema = description[type_words];
+        let name = description[type_words + 1..description.len() - 1].join(" ");
+        let name = if schema == "-" { name } else { format!("{}.{}", schema, name) };
+
+        let action = if object_type.ends_with("DATA") || object_type == "SEQUENCE SET" {
+            RestoreAction::Load
+        } else {
+            RestoreAction::Create
+        };
+        created.push(RestorePlanItem::new(action, &object_type, &name));
+    }
+
+    let mut plan = Vec::new();
+    if clean {
+        plan.extend(
+            created
+                .iter()
+                .rev()
+                .filter(|item| item.action == RestoreAction::Create)
+                .map(|item| RestorePlanItem::new(RestoreAction::Drop, &item.object_type, &item.name)),
+        );
+    }
+    plan.extend(created);
+    plan
+}
+
+/// Build a pg_restore invocation that only prints an archive's table of contents
+pub fn list_archive_command(program: &Path, input: &Path) -> ToolCommand {
+    ToolCommand {
+        program: program.to_path_buf(),
+        args: vec!["--list".to_string(), input.to_string_lossy().to_string()],
+        env: Vec::new(),
+        input: None,
+    }
+}
+
+/// Build the client tool invocation that restores `input` into the given database
+pub fn restore_command(
+    method: RestoreMethod,
+    program: &Path,
+    params: &ConnectionParams,
+    input: &Path,
+    options: &RestoreOptions,
+) -> Result<ToolCommand, AppError> {
+    let command = ToolCommand::for_connection(program, params);
+
+    let command = match method {
+        RestoreMethod::PgRestore => {
+            let mut command = command.arg(format!("--dbname={}", params.database)).arg("--verbose");
+            if options.clean {
+                command = command.arg("--clean").arg("--if-exists");
+            }
+            command.arg(input.to_string_lossy())
+        }
+        RestoreMethod::Psql => command
+            .arg(format!("--dbname={}", params.database))
+            .arg("--set=ON_ERROR_STOP=1")
+            .arg("--echo-errors")
+            .arg(format!("--file={}", input.to_string_lossy())),
+        // A database name starting with `-` is not taken for an option
+        RestoreMethod::Mysql => command.arg("--").arg(params.database.clone()).input(input),
+        RestoreMethod::ScriptRunner => {
+            return Err(AppError::Validation(
+                "The script runner does not use a client tool".to_string(),
+            ))
+        }
+    };
+
+    Ok(command)
+}
+
+/// Run a plain SQL dump statement by statement over an existing connection
+pub async fn run_script(
+    adapter: &dyn DatabaseAdapter,
+    sql: &str,
+    mut on_progress: impl FnMut(String),
+) -> Result<usize, AppError> {
+    let statements =
+        split_sql_statements(sql, &adapter.database_type()).map_err(AppError::Validation)?;
+
+    for (i, statement) in statements.iter().enumerate() {
+        adapter.execute_command(statement).await.map_err(|e| {
+            AppError::Database(crate::database::DatabaseError::QueryFailed(format!(
+                "Statement {} of {} failed: {}",
+                i + 1,
+                statements.len(),
+                e
+            )))
+        })?;
+        on_progress(format!("Executed statement {} of {}", i + 1, statements.len()));
+    }
+
+    Ok(statements.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_method() {
+        assert_eq!(
+            RestoreMethod::select(DumpFormat::Custom, DatabaseType::PostgreSQL, false).unwrap(),
+            RestoreMethod::PgRestore
+        );
+        assert_eq!(
+            RestoreMethod::select(DumpFormat::Plain, DatabaseType::MySQL, false).unwrap(),
+            RestoreMethod::Mysql
+        );
+        assert_eq!(
+            RestoreMethod::select(DumpFormat::Plain, DatabaseType::PostgreSQL, true).unwrap(),
+            RestoreMethod::ScriptRunner
+        );
+        assert!(RestoreMethod::select(DumpFormat::Tar, DatabaseType::MySQL, false).is_err());
+    }
+
+    #[test]
+    fn test_plan_sql_script() {
+        let sql = r#"
+DROP TABLE IF EXISTS `users`;
+CREATE TABLE `users` (
+  `id` int NOT NULL
+);
+INSERT INTO `users` VALUES (1);
+INSERT INTO `users` VALUES (2);
+CREATE UNIQUE INDEX users_email ON users (email);
+CREATE OR REPLACE VIEW public.active_users AS SELECT 1;
+CREATE DEFINER=`root`@`%` PROCEDURE `cleanup`()
+COPY public.orders (id, total) FROM stdin;
+ALTER TABLE users OWNER TO app;
+"#;
+        let plan: Vec<(RestoreAction, String, String)> = plan_sql_script(sql)
+            .into_iter()
+            .map(|i| (i.action, i.object_type, i.name))
+            .collect();
+
+        let item = |action, object_type: &str, name: &str| (action, object_type.to_string(), name.to_string());
+        assert_eq!(
+            plan,
+            vec![
+                item(RestoreAction::Drop, "TABLE", "users"),
+                item(RestoreAction::Create, "TABLE", "users"),
+                item(RestoreAction::Load, "TABLE DATA", "users"),
+                item(RestoreAction::Create, "INDEX", "users_email"),
+                item(RestoreAction::Create, "VIEW", "public.active_users"),
+                item(RestoreAction::Create, "PROCEDURE", "cleanup"),
+                item(RestoreAction::Load, "TABLE DATA", "public.orders"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_pg_archive() {
+        let toc = "\
+;
+; Archive created at 2024-01-01 00:00:00 UTC
+;
+215; 1259 16386 TABLE public users postgres
+3350; 0 16386 TABLE DATA public users postgres
+3201; 2606 16393 CONSTRAINT public users users_pkey postgres
+";
+        let plan = plan_pg_archive(toc, true);
+        assert_eq!(plan[0], RestorePlanItem::new(RestoreAction::Drop, "CONSTRAINT", "public.users users_pkey"));
+        assert_eq!(plan[1], RestorePlanItem::new(RestoreAction::Drop, "TABLE", "public.users"));
+        assert_eq!(plan[2], RestorePlanItem::new(RestoreAction::Create, "TABLE", "public.users"));
+        assert_eq!(plan[3], RestorePlanItem::new(RestoreAction::Load, "TABLE DATA", "public.users"));
+        assert_eq!(plan.len(), 5);
+    }
+
+    #[test]
+    fn test_restore_command() {
+        let mut params = ConnectionParams::new(DatabaseType::PostgreSQL, "app".to_string());
+        params.password = Some("s3cret".to_string());
+        let options = RestoreOptions {
+            clean: true,
+            ..RestoreOptions::default()
+        };
+
+        let command = restore_command(
+            RestoreMethod::PgRestore,
+            Path::new("pg_restore"),
+            &params,
+            Path::new("/tmp/app.dump"),
+            &options,
+        )
+        .unwrap();
+        assert!(command.args.contains(&"--clean".to_string()));
+        assert_eq!(command.args.last().unwrap(), "/tmp/app.dump");
+        assert!(!command.args.iter().any(|a| a.contains("s3cret")));
+
+        params.database_type = DatabaseType::MySQL;
+        let command = restore_command(
+            RestoreMethod::Mysql,
+            Path::new("mysql"),
+            &params,
+            Path::new("/tmp/app.sql"),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(command.input.as_deref(), Some(Path::new("/tmp/app.sql")));
+        assert_eq!(command.args[command.args.len() - 2..], ["--".to_string(), "app".to_string()]);
+    }
+}
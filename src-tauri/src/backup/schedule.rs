@@ -0,0 +1,260 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use super::catalog::BackupEntry;
+use super::dump::DumpFormat;
+use crate::error::AppError;
+
+const SCHEDULES_FILE: &str = "schedules.json";
+
+/// How many scheduled backups to keep
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Keep at most this many backups
+    pub keep_last: Option<usize>,
+    /// Delete backups older than this many days
+    pub max_age_days: Option<u32>,
+}
+
+/// A recurring backup of one profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSchedule {
+    pub id: String,
+    pub profile_id: String,
+    /// Five-field cron expression, evaluated in local time (e.g. "0 2 * * *")
+    pub cron: String,
+    /// Directory the backup files are written to
+    pub directory: String,
+    #[serde(default)]
+    pub format: DumpFormat,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl BackupSchedule {
+    /// Check the cron expression and directory
+    pub fn validate(&self) -> Result<(), AppError> {
+        parse_cron(&self.cron)?;
+        if self.directory.trim().is_empty() {
+            return Err(AppError::Validation("Backup directory is required".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Next time the schedule fires after its last run (or creation)
+    pub fn next_run(&self) -> Result<DateTime<Utc>, AppError> {
+        let after = self.last_run.unwrap_or(self.created_at).with_timezone(&Local);
+        parse_cron(&self.cron)?
+            .find_next_occurrence(&after, false)
+            .map(|next| next.with_timezone(&Utc))
+            .map_err(|e| AppError::Validation(format!("Invalid cron expression: {}", e)))
+    }
+
+    /// Whether the schedule should run at `now`
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.next_run().is_ok_and(|next| next <= now)
+    }
+
+    /// Path for a new backup file taken at `now`
+    pub fn backup_path(&self, database: &str, now: DateTime<Utc>) -> PathBuf {
+        let extension = match self.format {
+            DumpFormat::Plain => "sql",
+            DumpFormat::Custom => "dump",
+            DumpFormat::Tar => "tar",
+        };
+        let file_name = format!(
+            "{}_{}.{}",
+            database.replace(['/', '\\', ':'], "_"),
+            now.with_timezone(&Local).format("%Y%m%d_%H%M%S"),
+            extension
+        );
+        Path::new(&self.directory).join(file_name)
+    }
+}
+
+fn parse_cron(expression: &str) -> Result<Cron, AppError> {
+    Cron::new(expression)
+        .parse()
+        .map_err(|e| AppError::Validation(format!("Invalid cron expression: {}", e)))
+}
+
+/// Backups of a schedule that fall outside its retention policy
+pub fn expired_backups(
+    backups: &[BackupEntry],
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> Vec<BackupEntry> {
+    let mut newest_first: Vec<&BackupEntry> = backups.iter().collect();
+    newest_first.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+
+    newest_first
+        .into_iter()
+        .enumerate()
+        .filter(|(index, backup)| {
+            let too_many = policy.keep_last.is_some_and(|keep| *index >= keep);
+            let too_old = policy
+                .max_age_days
+                .is_some_and(|days| now - backup.created_at > Duration::days(days as i64));
+            too_many || too_old
+        })
+        .map(|(_, backup)| backup.clone())
+        .collect()
+}
+
+/// JSON file holding the configured backup schedules
+pub struct ScheduleStore {
+    schedules_path: PathBuf,
+}
+
+impl ScheduleStore {
+    /// Open the store in Tauri's app data directory
+    pub fn new(app_handle: &AppHandle) -> Result<Self, AppError> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Storage(format!("Could not resolve app data directory: {}", e)))?;
+
+        let backups_dir = app_data_dir.join("backups");
+        fs::create_dir_all(&backups_dir).map_err(|e| {
+            AppError::Storage(format!("Failed to create backups directory: {}", e))
+        })?;
+
+        Ok(Self::with_path(backups_dir.join(SCHEDULES_FILE)))
+    }
+
+    /// Open a store at a specific path
+    pub fn with_path(schedules_path: PathBuf) -> Self {
+        Self { schedules_path }
+    }
+
+    pub fn list(&self) -> Result<Vec<BackupSchedule>, AppError> {
+        if !self.schedules_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read_to_string(&self.schedules_path).map_err(|e| {
+            AppError::Storage(format!("Failed to read backup schedules: {}", e))
+        })?;
+        serde_json::from_str(&data)
+            .map_err(|e| AppError::Storage(format!("Failed to parse backup schedules: {}", e)))
+    }
+
+    /// Add or replace a schedule
+    pub fn save(&self, schedule: BackupSchedule) -> Result<BackupSchedule, AppError> {
+        schedule.validate()?;
+
+        let mut schedules = self.list()?;
+        schedules.retain(|s| s.id != schedule.id);
+        schedules.push(schedule.clone());
+        self.save_all(&schedules)?;
+
+        Ok(schedule)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        let mut schedules = self.list()?;
+        let before = schedules.len();
+        schedules.retain(|s| s.id != id);
+        if schedules.len() == before {
+            return Err(AppError::NotFound(format!("Backup schedule {} not found", id)));
+        }
+        self.save_all(&schedules)
+    }
+
+    fn save_all(&self, schedules: &[BackupSchedule]) -> Result<(), AppError> {
+        let data = serde_json::to_string_pretty(schedules).map_err(|e| {
+            AppError::Storage(format!("Failed to serialize backup schedules: {}", e))
+        })?;
+        fs::write(&self.schedules_path, data).map_err(|e| {
+            AppError::Storage(format!("Failed to write backup schedules: {}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::DatabaseType;
+
+    fn schedule(cron: &str) -> BackupSchedule {
+        BackupSchedule {
+            id: "nightly".to_string(),
+            profile_id: "profile-1".to_string(),
+            cron: cron.to_string(),
+            directory: "/backups".to_string(),
+            format: DumpFormat::Custom,
+            retention: RetentionPolicy::default(),
+            enabled: true,
+            created_at: Utc::now() - Duration::days(2),
+            last_run: None,
+            last_error: None,
+        }
+    }
+
+    fn backup(age_days: i64, now: DateTime<Utc>) -> BackupEntry {
+        let mut entry = BackupEntry::new(
+            Some("profile-1".to_string()),
+            DatabaseType::PostgreSQL,
+            "app".to_string(),
+            format!("/backups/{}.dump", age_days),
+            "custom".to_string(),
+            "pg_dump".to_string(),
+        );
+        entry.created_at = now - Duration::days(age_days);
+        entry
+    }
+
+    #[test]
+    fn test_due_schedule() {
+        let mut nightly = schedule("0 2 * * *");
+        assert!(nightly.validate().is_ok());
+        assert!(nightly.is_due(Utc::now()));
+
+        nightly.last_run = Some(Utc::now());
+        assert!(!nightly.is_due(Utc::now()));
+
+        nightly.enabled = false;
+        nightly.last_run = None;
+        assert!(!nightly.is_due(Utc::now()));
+    }
+
+    #[test]
+    fn test_invalid_cron() {
+        assert!(schedule("every night").validate().is_err());
+    }
+
+    #[test]
+    fn test_backup_path() {
+        let path = schedule("0 2 * * *").backup_path("app", Utc::now());
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert!(file_name.starts_with("app_"));
+        assert!(file_name.ends_with(".dump"));
+    }
+
+    #[test]
+    fn test_expired_backups() {
+        let now = Utc::now();
+        let backups: Vec<BackupEntry> = [0, 1, 2, 10].iter().map(|d| backup(*d, now)).collect();
+
+        let keep_two = RetentionPolicy { keep_last: Some(2), max_age_days: None };
+        let expired = expired_backups(&backups, &keep_two, now);
+        assert_eq!(expired.len(), 2);
+        assert!(expired.iter().all(|b| b.created_at <= now - Duration::days(2)));
+
+        let week = RetentionPolicy { keep_last: None, max_age_days: Some(7) };
+        let expired = expired_backups(&backups, &week, now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].path, "/backups/10.dump");
+
+        assert!(expired_backups(&backups, &RetentionPolicy::default(), now).is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::database::adapter::{DatabaseAdapter, DatabaseType};
+use crate::database::dialect::{SqlDialect, SQLiteDialect};
+use crate::error::AppError;
+
+/// Result of `PRAGMA integrity_check`
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    pub problems: Vec<String>,
+}
+
+impl IntegrityReport {
+    /// Build a report from the rows returned by the pragma, which is a single "ok" when healthy
+    pub fn from_messages(messages: Vec<String>) -> Self {
+        let ok = messages.len() == 1 && messages[0] == "ok";
+        Self {
+            ok,
+            problems: if ok { Vec::new() } else { messages },
+        }
+    }
+}
+
+fn ensure_sqlite(adapter: &dyn DatabaseAdapter) -> Result<(), AppError> {
+    if adapter.database_type() != DatabaseType::SQLite {
+        return Err(AppError::Validation(
+            "This operation is only available for SQLite databases".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Build a `VACUUM INTO` statement writing a compacted copy of the database to `path`
+pub fn vacuum_into_sql(path: &Path) -> String {
+    format!("VACUUM INTO {}", SQLiteDialect::new().string_literal(&path.to_string_lossy()))
+}
+
+/// Write a consistent copy of the connected SQLite database to `path`
+///
+/// `VACUUM INTO` reads from a single snapshot, so the copy is consistent even while
+/// other connections keep writing.
+pub async fn backup_sqlite(adapter: &dyn DatabaseAdapter, path: &Path) -> Result<(), AppError> {
+    ensure_sqlite(adapter)?;
+
+    if path.exists() {
+        return Err(AppError::Validation(format!(
+            "Backup target {} already exists",
+            path.display()
+        )));
+    }
+
+    adapter.execute_command(&vacuum_into_sql(path)).await?;
+    Ok(())
+}
+
+/// Run `PRAGMA integrity_check` on the connected SQLite database
+pub async fn integrity_check(adapter: &dyn DatabaseAdapter) -> Result<IntegrityReport, AppError> {
+    ensure_sqlite(adapter)?;
+
+    let result = adapter.execute_query("PRAGMA integrity_check").await?;
+    let messages = result
+        .rows
+        .iter()
+        .filter_map(|row| row.values.first().cloned().flatten())
+        .collect();
+
+    Ok(IntegrityReport::from_messages(messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vacuum_into_sql() {
+        assert_eq!(
+            vacuum_into_sql(Path::new("/tmp/it's.db")),
+            "VACUUM INTO '/tmp/it''s.db'"
+        );
+    }
+
+    #[test]
+    fn test_integrity_report() {
+        assert!(IntegrityReport::from_messages(vec!["ok".to_string()]).ok);
+
+        let report = IntegrityReport::from_messages(vec![
+            "row 3 missing from index idx_users_email".to_string(),
+        ]);
+        assert!(!report.ok);
+        assert_eq!(report.problems.len(), 1);
+    }
+}
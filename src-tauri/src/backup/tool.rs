@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+use crate::database::adapter::{ConnectionParams, DatabaseType};
+use crate::error::AppError;
+
+/// Directories where database client tools are commonly installed but which are
+/// often missing from the PATH of GUI applications
+fn well_known_tool_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = [
+        "/usr/local/bin",
+        "/opt/homebrew/bin",
+        "/usr/local/mysql/bin",
+        "/Applications/Postgres.app/Contents/Versions/latest/bin",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect();
+
+    // Versioned install directories, newest version first
+    for (root, prefix) in [
+        ("/usr/lib/postgresql", ""),
+        ("C:\\Program Files\\PostgreSQL", ""),
+        ("C:\\Program Files\\MySQL", "MySQL Server"),
+    ] {
+        if let Ok(entries) = std::fs::read_dir(root) {
+            let mut versions: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().starts_with(prefix))
+                .map(|e| e.path().join("bin"))
+                .collect();
+            versions.sort();
+            versions.reverse();
+            dirs.extend(versions);
+        }
+    }
+
+    dirs
+}
+
+/// Locate a client tool on the PATH or in well-known install directories
+pub fn find_tool(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+    let path_dirs = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    path_dirs
+        .into_iter()
+        .chain(well_known_tool_dirs())
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Resolve a tool from an explicit location or by searching for it
+pub fn resolve_tool(name: &str, tool_path: Option<&str>) -> Result<PathBuf, AppError> {
+    match tool_path {
+        Some(tool_path) => Ok(PathBuf::from(tool_path)),
+        None => find_tool(name).ok_or_else(|| {
+            AppError::NotFound(format!("{} was not found. Install it or set its location.", name))
+        }),
+    }
+}
+
+/// An external client tool invocation
+///
+/// Credentials are only ever placed in `env`, never in `args`, so they don't show up
+/// in process listings.
+#[derive(Debug, Clone)]
+pub struct ToolCommand {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// File fed to the tool's standard input
+    pub input: Option<PathBuf>,
+}
+
+impl ToolCommand {
+    /// Start a command with the connection options shared by the PostgreSQL and MySQL clients
+    pub fn for_connection(program: &Path, params: &ConnectionParams) -> Self {
+        let mut args = Vec::new();
+        let mut env = Vec::new();
+
+        if let Some(host) = &params.host {
+            args.push(format!("--host={}", host));
+        }
+        if let Some(port) = params.port {
+            args.push(format!("--port={}", port));
+        }
+
+        match params.database_type {
+            DatabaseType::PostgreSQL => {
+                if let Some(username) = &params.username {
+                    args.push(format!("--username={}", username));
+                }
+                args.push("--no-password".to_string());
+
+                if let Some(password) = &params.password {
+                    env.push(("PGPASSWORD".to_string(), password.clone()));
+                }
+                if let Some(ssl_mode) = &params.ssl_mode {
+                    env.push(("PGSSLMODE".to_string(), ssl_mode.clone()));
+                }
+            }
+            DatabaseType::MySQL => {
+                if let Some(username) = &params.username {
+                    args.push(format!("--user={}", username));
+                }
+                if let Some(ssl_mode) = &params.ssl_mode {
+                    args.push(format!("--ssl-mode={}", ssl_mode.to_uppercase().replace('-', "_")));
+                }
+
+                if let Some(password) = &params.password {
+                    env.push(("MYSQL_PWD".to_string(), password.clone()));
+                }
+            }
+            DatabaseType::SQLite | DatabaseType::Other(_) => {}
+        }
+
+        Self {
+            program: program.to_path_buf(),
+            args,
+            env,
+            input: None,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn input(mut self, path: &Path) -> Self {
+        self.input = Some(path.to_path_buf());
+        self
+    }
+
+    fn command(&self) -> Result<Command, AppError> {
+        let stdin = match &self.input {
+            Some(path) => Stdio::from(std::fs::File::open(path)?),
+            None => Stdio::null(),
+        };
+
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
+            .stdin(stdin)
+            .kill_on_drop(true);
+        Ok(command)
+    }
+
+    fn spawn_error(&self, e: std::io::Error) -> AppError {
+        AppError::Io(std::io::Error::new(
+            e.kind(),
+            format!("Failed to start {}: {}", self.program.display(), e),
+        ))
+    }
+
+    fn exit_error(&self, status: std::process::ExitStatus, details: &str) -> AppError {
+        AppError::Database(crate::database::DatabaseError::Other(format!(
+            "{} exited with {}: {}",
+            self.program.display(),
+            status,
+            details
+        )))
+    }
+
+    /// Run the tool, passing each line it reports on stderr to `on_progress`
+    pub async fn run(&self, mut on_progress: impl FnMut(String)) -> Result<(), AppError> {
+        let mut child = self
+            .command()?
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.spawn_error(e))?;
+
+        // Keep the last lines around to explain a failure
+        let mut recent: Vec<String> = Vec::new();
+        if let Some(stderr) = child.stderr.take() {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Some(line) = lines.next_line().await? {
+                recent.push(line.clone());
+                if recent.len() > 5 {
+                    recent.remove(0);
+                }
+                on_progress(line);
+            }
+        }
+
+        let status = child.wait().await?;
+        if !status.success() {
+            return Err(self.exit_error(status, &recent.join("\n")));
+        }
+
+        Ok(())
+    }
+
+    /// Run the tool to completion and return its standard output
+    pub async fn output(&self) -> Result<String, AppError> {
+        let output = self
+            .command()?
+            .output()
+            .await
+            .map_err(|e| self.spawn_error(e))?;
+
+        if !output.status.success() {
+            return Err(self.exit_error(output.status, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_stay_out_of_args() {
+        let mut params = ConnectionParams::new(DatabaseType::MySQL, "app".to_string());
+        params.username = Some("admin".to_string());
+        params.password = Some("s3cret".to_string());
+        params.ssl_mode = Some("verify-ca".to_string());
+
+        let command = ToolCommand::for_connection(Path::new("mysql"), &params);
+        assert_eq!(
+            command.args,
+            vec!["--host=localhost", "--port=3306", "--user=admin", "--ssl-mode=VERIFY_CA"]
+        );
+        assert_eq!(command.env, vec![("MYSQL_PWD".to_string(), "s3cret".to_string())]);
+    }
+}
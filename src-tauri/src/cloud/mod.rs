@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::database::adapter::{QueryResult, RowSink};
+use crate::error::AppError;
+use crate::export::csv::{CsvOptions, CsvSink};
+
+pub mod s3;
+pub mod sheets;
+
+/// Time allowed for a request to a cloud service
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where a result is uploaded to, other than the local disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CloudDestination {
+    /// A CSV object per upload
+    S3(s3::S3Destination),
+    /// The rows of a sheet, replaced or appended to
+    GoogleSheets(sheets::SheetsDestination),
+}
+
+pub(crate) fn http_client() -> Result<reqwest::Client, AppError> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// `name` reduced to characters that are safe in object keys and file names
+fn object_name(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    if name.is_empty() { "result".to_string() } else { name }
+}
+
+fn result_csv(result: &QueryResult) -> Result<Vec<u8>, AppError> {
+    let mut sink = CsvSink::new(Vec::new(), CsvOptions::default())?;
+    sink.columns(&result.columns)?;
+    for row in &result.rows {
+        sink.row(row)?;
+    }
+    sink.into_inner()
+}
+
+impl CloudDestination {
+    pub fn validate(&self) -> Result<(), AppError> {
+        match self {
+            CloudDestination::S3(destination) => destination.validate(),
+            CloudDestination::GoogleSheets(destination) => destination.validate(),
+        }
+    }
+
+    /// Upload `result` of `name` produced at `at`, with the credentials saved
+    /// for `owner`; returns where it landed
+    ///
+    /// S3 objects are named `{prefix}{name}/{timestamp}.csv`.
+    pub async fn upload(
+        &self,
+        owner: &str,
+        name: &str,
+        result: &QueryResult,
+        at: DateTime<Utc>,
+    ) -> Result<String, AppError> {
+        match self {
+            CloudDestination::S3(destination) => {
+                let object = format!("{}/{}.csv", object_name(name), at.format("%Y-%m-%dT%H-%M-%SZ"));
+                destination.upload(owner, &object, result_csv(result)?, "text/csv", at).await
+            }
+            CloudDestination::GoogleSheets(destination) => destination.upload(result).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destination_format() {
+        let destination: CloudDestination = serde_json::from_str(
+            r#"{"kind": "google_sheets", "spreadsheet_id": "abc", "sheet": "Daily"}"#,
+        )
+        .unwrap();
+        assert!(matches!(&destination, CloudDestination::GoogleSheets(d) if !d.append));
+        assert!(destination.validate().is_ok());
+
+        assert_eq!(object_name(" Daily sales/EU "), "Daily-sales-EU");
+        assert_eq!(object_name(""), "result");
+    }
+}
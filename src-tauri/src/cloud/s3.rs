@@ -0,0 +1,233 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::http_client;
+use crate::error::AppError;
+use crate::profile::vault;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Bucket of Amazon S3 or of an S3-compatible service (MinIO, R2, ...)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct S3Destination {
+    /// Endpoint of an S3-compatible service (e.g. "https://minio.example.com:9000");
+    /// Amazon S3 when not set
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub bucket: String,
+    /// Prepended to object keys (e.g. "reports/")
+    #[serde(default)]
+    pub prefix: String,
+    pub access_key_id: String,
+    /// Name the bucket in the path rather than the host name, as most
+    /// S3-compatible services need
+    #[serde(default)]
+    pub path_style: bool,
+}
+
+fn secret_name(owner: &str) -> String {
+    format!("{}_s3", owner)
+}
+
+/// Save the secret access key used for the uploads of `owner`
+pub fn save_secret(owner: &str, secret_access_key: &str) -> Result<(), AppError> {
+    vault::set_secret(&secret_name(owner), secret_access_key)
+}
+
+pub fn delete_secret(owner: &str) -> Result<(), AppError> {
+    vault::delete_secret(&secret_name(owner))
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Key requests are signed with, derived from the secret for one day, region and service
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{}", secret_access_key).as_bytes(), date);
+    let key = hmac(&key, region);
+    let key = hmac(&key, service);
+    hmac(&key, "aws4_request")
+}
+
+/// Percent-encode a path as Signature Version 4 expects, keeping the slashes
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'/') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+impl S3Destination {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.bucket.trim().is_empty() {
+            return Err(AppError::Validation("S3 bucket is required".to_string()));
+        }
+        if self.region.trim().is_empty() {
+            return Err(AppError::Validation("S3 region is required".to_string()));
+        }
+        if self.access_key_id.trim().is_empty() {
+            return Err(AppError::Validation("S3 access key ID is required".to_string()));
+        }
+        if let Some(endpoint) = &self.endpoint {
+            Url::parse(endpoint).map_err(|e| AppError::Validation(format!("Invalid S3 endpoint: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// URL of the object with `key`
+    fn object_url(&self, key: &str) -> Result<Url, AppError> {
+        let endpoint = match &self.endpoint {
+            Some(endpoint) => endpoint.trim_end_matches('/').to_string(),
+            None => format!("https://s3.{}.amazonaws.com", self.region),
+        };
+        let mut url = Url::parse(&endpoint).map_err(|e| AppError::Validation(format!("Invalid S3 endpoint: {}", e)))?;
+        if self.path_style {
+            url.set_path(&encode_path(&format!("/{}/{}", self.bucket, key)));
+        } else {
+            let host = url.host_str().unwrap_or_default().to_string();
+            url.set_host(Some(&format!("{}.{}", self.bucket, host)))
+                .map_err(|e| AppError::Validation(format!("Invalid S3 bucket name: {}", e)))?;
+            url.set_path(&encode_path(&format!("/{}", key)));
+        }
+        Ok(url)
+    }
+
+    /// Headers of a Signature Version 4 signed PUT of `body` to `url`
+    fn signed_headers(
+        &self,
+        url: &Url,
+        body: &[u8],
+        content_type: &str,
+        secret_access_key: &str,
+        at: DateTime<Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let amz_date = at.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = at.format("%Y%m%d").to_string();
+        let payload_hash = hex_sha256(body);
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let signed_header_names = "content-type;host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "PUT\n{}\n\ncontent-type:{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            url.path(),
+            content_type,
+            host,
+            payload_hash,
+            amz_date,
+            signed_header_names,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex_sha256(canonical_request.as_bytes())
+        );
+        let key = signing_key(secret_access_key, &date, &self.region, "s3");
+        let signature: String = hmac(&key, &string_to_sign).iter().map(|b| format!("{:02x}", b)).collect();
+
+        vec![
+            ("content-type", content_type.to_string()),
+            ("x-amz-content-sha256", payload_hash),
+            ("x-amz-date", amz_date),
+            (
+                "authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                    self.access_key_id, scope, signed_header_names, signature
+                ),
+            ),
+        ]
+    }
+
+    /// Upload `body` as `{prefix}{name}`, signed with the secret saved for
+    /// `owner`; returns the `s3://` location of the object
+    pub async fn upload(
+        &self,
+        owner: &str,
+        name: &str,
+        body: Vec<u8>,
+        content_type: &str,
+        at: DateTime<Utc>,
+    ) -> Result<String, AppError> {
+        let secret_access_key = vault::get_secret(&secret_name(owner))?
+            .ok_or_else(|| AppError::Auth("No S3 secret access key saved".to_string()))?;
+        let key = format!("{}{}", self.prefix, name);
+        let url = self.object_url(&key)?;
+
+        let headers = self.signed_headers(&url, &body, content_type, &secret_access_key, at);
+        let mut request = http_client()?.put(url);
+        for (header, value) in headers {
+            request = request.header(header, value);
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(format!("S3 upload failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(AppError::Network(format!("S3 upload returned {}: {}", status, detail.trim())));
+        }
+
+        Ok(format!("s3://{}/{}", self.bucket, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn destination(endpoint: Option<&str>, path_style: bool) -> S3Destination {
+        S3Destination {
+            endpoint: endpoint.map(str::to_string),
+            region: "eu-west-1".to_string(),
+            bucket: "reports".to_string(),
+            prefix: "daily/".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            path_style,
+        }
+    }
+
+    #[test]
+    fn test_signing() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        let key: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(key, "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+
+        let url = destination(None, false).object_url("daily/sales report.csv").unwrap();
+        assert_eq!(url.as_str(), "https://reports.s3.eu-west-1.amazonaws.com/daily/sales%20report.csv");
+        let url = destination(Some("http://localhost:9000/"), true).object_url("a+b.csv").unwrap();
+        assert_eq!(url.as_str(), "http://localhost:9000/reports/a%2Bb.csv");
+
+        let at = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let headers = destination(None, false).signed_headers(&url, b"a,b\n", "text/csv", "secret", at);
+        assert_eq!(headers[2], ("x-amz-date", "20240501T120000Z".to_string()));
+        assert!(headers[3].1.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20240501/eu-west-1/s3/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+    }
+}
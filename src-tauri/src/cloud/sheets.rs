@@ -0,0 +1,308 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::http_client;
+use crate::database::adapter::QueryResult;
+use crate::error::AppError;
+use crate::profile::vault;
+
+const AUTHORIZATION_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const SHEETS_API: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+
+/// Secret holding the OAuth client and refresh token of the authorized account
+const CREDENTIALS_SECRET: &str = "google_sheets_oauth";
+
+/// Time the user has to sign in before the authorization is given up
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Sheet of a Google Sheets spreadsheet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SheetsDestination {
+    /// ID from the spreadsheet's URL
+    pub spreadsheet_id: String,
+    /// Sheet (tab) written to; it must exist
+    pub sheet: String,
+    /// Add the rows below the existing ones instead of replacing the sheet's contents
+    #[serde(default)]
+    pub append: bool,
+}
+
+/// OAuth client of the user's Google Cloud project, with the refresh token
+/// of the account that authorized it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GoogleCredentials {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+/// Whether a Google account is authorized for Sheets exports
+pub fn is_authorized() -> Result<bool, AppError> {
+    Ok(vault::get_secret(CREDENTIALS_SECRET)?.is_some())
+}
+
+/// Forget the authorized Google account
+pub fn revoke() -> Result<(), AppError> {
+    vault::delete_secret(CREDENTIALS_SECRET)
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(len).map(char::from).collect()
+}
+
+/// A sign-in the user was sent to the browser for, waiting for Google to
+/// redirect back to a local port
+pub struct PendingAuthorization {
+    listener: TcpListener,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    state: String,
+    code_verifier: String,
+}
+
+/// Start authorizing access to Google Sheets with the OAuth client of a
+/// desktop app, returning the URL to open in the browser
+pub async fn authorize(client_id: String, client_secret: String) -> Result<(String, PendingAuthorization), AppError> {
+    if client_id.trim().is_empty() || client_secret.trim().is_empty() {
+        return Err(AppError::Validation("Google OAuth client ID and secret are required".to_string()));
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let redirect_uri = format!("http://127.0.0.1:{}", listener.local_addr()?.port());
+    let state = random_string(32);
+    let code_verifier = random_string(64);
+    let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+    let url = Url::parse_with_params(AUTHORIZATION_URL, &[
+        ("client_id", client_id.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("response_type", "code"),
+        ("scope", SCOPE),
+        ("access_type", "offline"),
+        ("prompt", "consent"),
+        ("state", state.as_str()),
+        ("code_challenge", code_challenge.as_str()),
+        ("code_challenge_method", "S256"),
+    ])
+    .map_err(|e| AppError::Unknown(e.to_string()))?;
+
+    Ok((url.to_string(), PendingAuthorization {
+        listener,
+        client_id,
+        client_secret,
+        redirect_uri,
+        state,
+        code_verifier,
+    }))
+}
+
+impl PendingAuthorization {
+    /// Wait for the browser to come back with the authorization code, then
+    /// exchange it for a refresh token and save it
+    pub async fn complete(self) -> Result<(), AppError> {
+        let (mut stream, _) = tokio::time::timeout(AUTHORIZATION_TIMEOUT, self.listener.accept())
+            .await
+            .map_err(|_| AppError::Auth("Google authorization timed out".to_string()))??;
+
+        let mut buffer = vec![0; 8192];
+        let read = stream.read(&mut buffer).await?;
+        let request = String::from_utf8_lossy(&buffer[..read]);
+        let target = request.split_whitespace().nth(1).unwrap_or("/");
+        let url = Url::parse(&format!("{}{}", self.redirect_uri, target))
+            .map_err(|e| AppError::Auth(format!("Invalid authorization response: {}", e)))?;
+        let param = |name: &str| url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.into_owned());
+
+        let outcome = match (param("code"), param("error")) {
+            _ if param("state").as_deref() != Some(self.state.as_str()) => {
+                Err(AppError::Auth("Authorization response does not match the request".to_string()))
+            }
+            (Some(code), _) => Ok(code),
+            (None, error) => Err(AppError::Auth(format!(
+                "Google authorization was denied: {}",
+                error.unwrap_or_else(|| "no code returned".to_string())
+            ))),
+        };
+        let page = if outcome.is_ok() {
+            "DataForge is now authorized. You can close this window."
+        } else {
+            "Authorization failed. Return to DataForge for details."
+        };
+        let _ = stream
+            .write_all(format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                page.len(),
+                page
+            ).as_bytes())
+            .await;
+        let code = outcome?;
+
+        let response: TokenResponse = request_token(&[
+            ("code", code.as_str()),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code_verifier", self.code_verifier.as_str()),
+        ])
+        .await?;
+        let refresh_token = response
+            .refresh_token
+            .ok_or_else(|| AppError::Auth("Google did not return a refresh token".to_string()))?;
+
+        let credentials = GoogleCredentials {
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+            refresh_token,
+        };
+        vault::set_secret(CREDENTIALS_SECRET, &serde_json::to_string(&credentials)?)
+    }
+}
+
+async fn request_token(form: &[(&str, &str)]) -> Result<TokenResponse, AppError> {
+    let response = http_client()?
+        .post(TOKEN_URL)
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Google token request failed: {}", e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(AppError::Auth(format!("Google token request returned {}: {}", status, detail.trim())));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Invalid Google token response: {}", e)))
+}
+
+/// A fresh access token for the authorized account
+async fn access_token() -> Result<String, AppError> {
+    let credentials: GoogleCredentials = match vault::get_secret(CREDENTIALS_SECRET)? {
+        Some(data) => serde_json::from_str(&data)?,
+        None => return Err(AppError::Auth("No Google account is authorized for Sheets".to_string())),
+    };
+    let response = request_token(&[
+        ("client_id", credentials.client_id.as_str()),
+        ("client_secret", credentials.client_secret.as_str()),
+        ("refresh_token", credentials.refresh_token.as_str()),
+        ("grant_type", "refresh_token"),
+    ])
+    .await?;
+    Ok(response.access_token)
+}
+
+async fn send(request: reqwest::RequestBuilder, token: &str) -> Result<(), AppError> {
+    let response = request
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Google Sheets request failed: {}", e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(AppError::Network(format!("Google Sheets returned {}: {}", status, detail.trim())));
+    }
+    Ok(())
+}
+
+/// Cells of `result`, NULL being empty; the column names come first unless appending
+fn cell_values(result: &QueryResult, header: bool) -> Vec<Vec<String>> {
+    let mut values = Vec::with_capacity(result.rows.len() + 1);
+    if header {
+        values.push(result.columns.iter().map(|c| c.name.clone()).collect());
+    }
+    for row in &result.rows {
+        values.push(row.values.iter().map(|v| v.clone().unwrap_or_default()).collect());
+    }
+    values
+}
+
+impl SheetsDestination {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.spreadsheet_id.trim().is_empty() {
+            return Err(AppError::Validation("Spreadsheet ID is required".to_string()));
+        }
+        if self.sheet.trim().is_empty() {
+            return Err(AppError::Validation("Sheet name is required".to_string()));
+        }
+        Ok(())
+    }
+
+    /// A1 range of the whole sheet, with `suffix` (e.g. ":clear") for the API method
+    fn range_url(&self, suffix: &str) -> Result<Url, AppError> {
+        let mut url = Url::parse(SHEETS_API).map_err(|e| AppError::Unknown(e.to_string()))?;
+        let range = format!("'{}'{}", self.sheet.replace('\'', "''"), suffix);
+        url.path_segments_mut()
+            .map_err(|_| AppError::Unknown("Invalid Sheets API URL".to_string()))?
+            .extend([self.spreadsheet_id.as_str(), "values", range.as_str()]);
+        Ok(url)
+    }
+
+    /// Write the rows of `result` to the sheet; returns the spreadsheet's URL
+    pub async fn upload(&self, result: &QueryResult) -> Result<String, AppError> {
+        let token = access_token().await?;
+        let client = http_client()?;
+
+        let body = serde_json::json!({ "values": cell_values(result, !self.append) });
+        if self.append {
+            let mut url = self.range_url(":append")?;
+            url.query_pairs_mut()
+                .append_pair("valueInputOption", "RAW")
+                .append_pair("insertDataOption", "INSERT_ROWS");
+            send(client.post(url).json(&body), &token).await?;
+        } else {
+            send(client.post(self.range_url(":clear")?).json(&serde_json::json!({})), &token).await?;
+            let mut url = self.range_url("")?;
+            url.query_pairs_mut().append_pair("valueInputOption", "RAW");
+            send(client.put(url).json(&body), &token).await?;
+        }
+
+        Ok(format!("https://docs.google.com/spreadsheets/d/{}", self.spreadsheet_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::{ColumnInfo, QueryRow};
+
+    #[test]
+    fn test_sheet_request() {
+        let destination = SheetsDestination {
+            spreadsheet_id: "abc123".to_string(),
+            sheet: "Bob's sales".to_string(),
+            append: false,
+        };
+        assert_eq!(
+            destination.range_url(":clear").unwrap().as_str(),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/'Bob''s%20sales':clear"
+        );
+
+        let result = QueryResult {
+            columns: vec![ColumnInfo { name: "region".to_string(), data_type: "text".to_string(), is_nullable: true }],
+            rows: vec![QueryRow { columns: vec!["region".to_string()], values: vec![None] }],
+            rows_affected: None,
+            execution_time: None,
+        };
+        assert_eq!(cell_values(&result, true), vec![vec!["region".to_string()], vec![String::new()]]);
+        assert_eq!(cell_values(&result, false).len(), 1);
+    }
+}
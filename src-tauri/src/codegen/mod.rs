@@ -0,0 +1,104 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::error::AppError;
+
+pub mod rust;
+pub mod snippet;
+pub mod typescript;
+
+/// Source files written by a code generator
+#[derive(Debug, Clone, Serialize)]
+pub struct CodegenSummary {
+    pub directory: String,
+    pub files: Vec<String>,
+}
+
+/// A generated source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    pub name: String,
+    pub contents: String,
+}
+
+/// Write `files` to `directory`, creating it if needed
+pub fn write_files(directory: &Path, files: &[SourceFile]) -> Result<CodegenSummary, AppError> {
+    fs::create_dir_all(directory)?;
+    for file in files {
+        fs::write(directory.join(&file.name), &file.contents)?;
+    }
+
+    Ok(CodegenSummary {
+        directory: directory.to_string_lossy().into_owned(),
+        files: files.iter().map(|f| f.name.clone()).collect(),
+    })
+}
+
+/// Words of an identifier, split at anything that is not a letter or digit
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// An identifier that starts with a letter or `_`, as most languages require
+fn identifier(name: String) -> String {
+    match name.chars().next() {
+        None => "_".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", name),
+        _ => name,
+    }
+}
+
+/// `order_items` → `OrderItems`
+pub fn pascal_case(name: &str) -> String {
+    identifier(words(name).map(capitalize).collect())
+}
+
+/// `order_items` → `orderItems`
+pub fn camel_case(name: &str) -> String {
+    let mut words = words(name);
+    let first = words.next().map(str::to_ascii_lowercase).unwrap_or_default();
+    identifier(first + words.map(capitalize).collect::<String>().as_str())
+}
+
+/// `OrderItems` → `order_items`
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len());
+    for word in words(name) {
+        if !snake.is_empty() {
+            snake.push('_');
+        }
+        let mut previous_lower = false;
+        for c in word.chars() {
+            if c.is_ascii_uppercase() && previous_lower {
+                snake.push('_');
+            }
+            previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            snake.push(c.to_ascii_lowercase());
+        }
+    }
+    identifier(snake)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(pascal_case("order_items"), "OrderItems");
+        assert_eq!(camel_case("Order Items"), "orderItems");
+        assert_eq!(camel_case("2fa_codes"), "_2faCodes");
+        assert_eq!(camel_case("__"), "_");
+        assert_eq!(snake_case("OrderItems"), "order_items");
+        assert_eq!(snake_case("user-ID 2"), "user_id_2");
+    }
+}
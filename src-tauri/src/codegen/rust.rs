@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use super::{pascal_case, snake_case, SourceFile};
+use crate::database::adapter::{ColumnInfo, DatabaseType};
+use crate::database::types::{AbstractType, TypeMapper};
+
+/// Keywords that need the `r#` prefix to be used as field names
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "final", "override", "yield",
+];
+
+/// Rust type sqlx decodes a column type of `database_type` as
+///
+/// Decimals need sqlx's `rust_decimal` feature, the date and time types its
+/// `chrono` feature.
+fn rust_type(database_type: DatabaseType, abstract_type: &AbstractType) -> &'static str {
+    match abstract_type {
+        AbstractType::SmallInt => "i16",
+        // SQLite stores every integer as 64 bits
+        AbstractType::Integer if database_type == DatabaseType::SQLite => "i64",
+        AbstractType::Integer => "i32",
+        AbstractType::BigInt => "i64",
+        AbstractType::Double => "f64",
+        AbstractType::Decimal { .. } => "rust_decimal::Decimal",
+        AbstractType::Boolean => "bool",
+        AbstractType::Varchar { .. } | AbstractType::Char { .. } | AbstractType::Text => "String",
+        AbstractType::Date => "chrono::NaiveDate",
+        AbstractType::Time => "chrono::NaiveTime",
+        AbstractType::Timestamp => "chrono::NaiveDateTime",
+        AbstractType::TimestampTz => "chrono::DateTime<chrono::Utc>",
+        AbstractType::Json => "serde_json::Value",
+        AbstractType::Uuid => "uuid::Uuid",
+        AbstractType::Binary => "Vec<u8>",
+    }
+}
+
+/// Field or module name of a column or table, unique among `taken`
+fn field_name(column: &str, taken: &mut HashSet<String>) -> String {
+    let mut name = snake_case(column);
+    // Keywords that cannot be raw identifiers either
+    if matches!(name.as_str(), "self" | "super" | "crate") {
+        name.push('_');
+    }
+    while !taken.insert(name.clone()) {
+        name.push('_');
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+/// Rust module with a struct deriving sqlx's `FromRow` for the rows of `table`
+///
+/// Fields are the snake_case column names, renamed back with `#[sqlx(rename)]`
+/// where they differ; nullable columns are `Option`s.
+fn table_module(database_type: DatabaseType, table: &str, columns: &[ColumnInfo]) -> String {
+    let mapper = TypeMapper::new(database_type);
+    let mut module = format!("// Generated by DataForge from table {}\n\n", table);
+    let _ = writeln!(module, "/// Row of table `{}`", table);
+    module.push_str("#[derive(Debug, Clone, PartialEq, sqlx::FromRow, serde::Serialize, serde::Deserialize)]\n");
+    let _ = writeln!(module, "pub struct {} {{", pascal_case(table));
+
+    let mut taken = HashSet::new();
+    for column in columns {
+        let name = field_name(&column.name, &mut taken);
+        if name.trim_start_matches("r#") != column.name {
+            let _ = writeln!(module, "    #[sqlx(rename = {:?})]", column.name);
+        }
+        let field_type = rust_type(database_type, &mapper.to_abstract(&column.data_type));
+        if column.is_nullable {
+            let _ = writeln!(module, "    pub {}: Option<{}>,", name, field_type);
+        } else {
+            let _ = writeln!(module, "    pub {}: {},", name, field_type);
+        }
+    }
+    module.push_str("}\n");
+    module
+}
+
+/// Modules of the given tables, and a `mod.rs` declaring them and re-exporting the structs
+pub fn table_modules(database_type: DatabaseType, tables: &[(String, Vec<ColumnInfo>)]) -> Vec<SourceFile> {
+    let mut taken = HashSet::new();
+    let modules: Vec<(String, &str)> = tables
+        .iter()
+        .map(|(table, _)| (field_name(table, &mut taken), table.as_str()))
+        .collect();
+
+    let mut files: Vec<SourceFile> = tables
+        .iter()
+        .zip(&modules)
+        .map(|((table, columns), (module, _))| SourceFile {
+            name: format!("{}.rs", module.trim_start_matches("r#")),
+            contents: table_module(database_type, table, columns),
+        })
+        .collect();
+
+    let mut mod_rs = String::new();
+    for (module, _) in &modules {
+        let _ = writeln!(mod_rs, "pub mod {};", module);
+    }
+    mod_rs.push('\n');
+    for (module, table) in &modules {
+        let _ = writeln!(mod_rs, "pub use {}::{};", module, pascal_case(table));
+    }
+    files.push(SourceFile { name: "mod.rs".to_string(), contents: mod_rs });
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, is_nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable,
+        }
+    }
+
+    #[test]
+    fn test_table_module() {
+        let columns = vec![
+            column("id", "integer", false),
+            column("type", "varchar(20)", false),
+            column("createdAt", "timestamp with time zone", true),
+            column("price", "numeric(10,2)", false),
+        ];
+
+        assert_eq!(
+            table_module(DatabaseType::PostgreSQL, "order_items", &columns),
+            "// Generated by DataForge from table order_items\n\
+             \n\
+             /// Row of table `order_items`\n\
+             #[derive(Debug, Clone, PartialEq, sqlx::FromRow, serde::Serialize, serde::Deserialize)]\n\
+             pub struct OrderItems {\n    pub id: i32,\n    pub r#type: String,\n    #[sqlx(rename = \"createdAt\")]\n    pub created_at: Option<chrono::DateTime<chrono::Utc>>,\n    pub price: rust_decimal::Decimal,\n}\n"
+        );
+        assert!(table_module(DatabaseType::SQLite, "t", &columns[..1]).contains("pub id: i64,"));
+        assert!(table_module(DatabaseType::SQLite, "t", &[column("self", "text", false)]).contains("pub self_: String,"));
+
+        let files = table_modules(DatabaseType::PostgreSQL, &[("Users".to_string(), columns)]);
+        assert_eq!(files[0].name, "users.rs");
+        assert_eq!(files[1].contents, "pub mod users;\n\npub use users::Users;\n");
+    }
+}
@@ -0,0 +1,378 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::database::adapter::DatabaseType;
+use crate::database::templates::placeholders;
+use crate::error::AppError;
+use crate::profile::ConnectionProfile;
+
+/// Library a query is copied as code for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeTarget {
+    /// Rust
+    Sqlx,
+    /// Python, PostgreSQL only
+    Psycopg,
+    /// JavaScript
+    Knex,
+    /// Java
+    Jdbc,
+}
+
+/// How a library marks bind parameters in the SQL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindStyle {
+    /// `$1`, one number per name
+    Numbered,
+    /// `?`, bound once per occurrence
+    Positional,
+    /// `%(name)s`; a literal `%` is doubled
+    Pyformat,
+    /// `:name`
+    Colon,
+}
+
+/// A parameter value, typed by how it reads
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value<'a> {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Text(&'a str),
+}
+
+impl<'a> Value<'a> {
+    /// Numbers and booleans only when they read back the same, so `007` stays text
+    fn parse(value: &'a str) -> Self {
+        if let Ok(integer) = value.parse::<i64>() {
+            if integer.to_string() == value {
+                return Value::Integer(integer);
+            }
+        }
+        if let Ok(float) = value.parse::<f64>() {
+            if float.is_finite() && value.contains('.') && float.to_string() == value {
+                return Value::Float(float);
+            }
+        }
+        match value {
+            "true" => Value::Boolean(true),
+            "false" => Value::Boolean(false),
+            _ => Value::Text(value),
+        }
+    }
+
+    /// Literal in Python, JavaScript or Java, whose double-quoted strings take JSON escapes
+    fn literal(&self, true_false: (&str, &str)) -> String {
+        match self {
+            Value::Integer(i) => i.to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Boolean(b) => if *b { true_false.0 } else { true_false.1 }.to_string(),
+            Value::Text(s) => quoted(s),
+        }
+    }
+}
+
+/// A double-quoted string literal
+fn quoted(text: &str) -> String {
+    serde_json::Value::String(text.to_string()).to_string()
+}
+
+/// A Rust raw string literal, with as many `#` as `text` needs
+fn raw_string(text: &str) -> String {
+    let mut hashes = "#".to_string();
+    while text.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{}\"{}\"{}", hashes, text, hashes)
+}
+
+/// `sql` with its `{{name}}` placeholders as bind parameters in `style`, and the
+/// names in the order they are bound
+fn bind_parameters(sql: &str, style: BindStyle) -> (String, Vec<&str>) {
+    let escape = |text: &str| if style == BindStyle::Pyformat { text.replace('%', "%%") } else { text.to_string() };
+    let mut text = String::with_capacity(sql.len());
+    let mut names: Vec<&str> = Vec::new();
+    let mut copied = 0;
+
+    for (range, name) in placeholders(sql) {
+        text.push_str(&escape(&sql[copied..range.start]));
+        let position = names.iter().position(|n| *n == name);
+        match style {
+            BindStyle::Positional => {
+                names.push(name);
+                text.push('?');
+            }
+            BindStyle::Numbered => {
+                let number = position.unwrap_or_else(|| {
+                    names.push(name);
+                    names.len() - 1
+                }) + 1;
+                let _ = write!(text, "${}", number);
+            }
+            BindStyle::Pyformat | BindStyle::Colon => {
+                if position.is_none() {
+                    names.push(name);
+                }
+                if style == BindStyle::Pyformat {
+                    let _ = write!(text, "%({})s", name);
+                } else {
+                    let _ = write!(text, ":{}", name);
+                }
+            }
+        }
+        copied = range.end;
+    }
+    text.push_str(&escape(&sql[copied..]));
+    (text, names)
+}
+
+/// Environment variable the password is read from; it never comes from the profile
+fn password_variable(database_type: DatabaseType) -> &'static str {
+    match database_type {
+        DatabaseType::PostgreSQL => "PGPASSWORD",
+        DatabaseType::MySQL => "MYSQL_PWD",
+        DatabaseType::SQLite | DatabaseType::Other(_) => "",
+    }
+}
+
+fn host(profile: &ConnectionProfile) -> &str {
+    profile.host.as_deref().unwrap_or("localhost")
+}
+
+fn port(profile: &ConnectionProfile) -> u16 {
+    profile.port.or(profile.database_type.default_port()).unwrap_or_default()
+}
+
+/// Connection URL of the profile, without a password
+fn database_url(profile: &ConnectionProfile) -> String {
+    if profile.database_type == DatabaseType::SQLite {
+        return format!("sqlite://{}", profile.database);
+    }
+    let scheme = if profile.database_type == DatabaseType::PostgreSQL { "postgres" } else { "mysql" };
+    let user = profile.username.as_deref().map(|u| format!("{}@", u)).unwrap_or_default();
+    let mut url = format!("{}://{}{}:{}/{}", scheme, user, host(profile), port(profile), profile.database);
+    if let Some(ssl_mode) = &profile.ssl_mode {
+        let key = if profile.database_type == DatabaseType::PostgreSQL { "sslmode" } else { "ssl-mode" };
+        let _ = write!(url, "?{}={}", key, ssl_mode);
+    }
+    url
+}
+
+fn sqlx(profile: &ConnectionProfile, sql: &str, values: &[Value]) -> String {
+    let pool = match profile.database_type {
+        DatabaseType::PostgreSQL => "PgPool",
+        DatabaseType::MySQL => "MySqlPool",
+        DatabaseType::SQLite => "SqlitePool",
+        DatabaseType::Other(_) => "AnyPool",
+    };
+    let mut code = format!("// DATABASE_URL={}", database_url(profile));
+    if profile.database_type != DatabaseType::SQLite {
+        code.push_str(", with the password added");
+    }
+    let _ = writeln!(code, "\nlet pool = sqlx::{}::connect(&std::env::var(\"DATABASE_URL\")?).await?;", pool);
+    let _ = writeln!(code, "let rows = sqlx::query({})", raw_string(sql));
+    for value in values {
+        let bound = match value {
+            Value::Integer(i) => format!("{}_i64", i),
+            Value::Float(f) => format!("{}_f64", f),
+            Value::Boolean(b) => b.to_string(),
+            Value::Text(s) => format!("{:?}", s),
+        };
+        let _ = writeln!(code, "    .bind({})", bound);
+    }
+    code.push_str("    .fetch_all(&pool)\n    .await?;\n");
+    code
+}
+
+fn psycopg(profile: &ConnectionProfile, sql: &str, parameters: &[(&str, Value)]) -> String {
+    let mut connect = format!("host={}, port={}, dbname={}", quoted(host(profile)), port(profile), quoted(&profile.database));
+    if let Some(username) = &profile.username {
+        let _ = write!(connect, ", user={}", quoted(username));
+    }
+    let _ = write!(connect, ", password=os.environ[\"{}\"]", password_variable(profile.database_type));
+    if let Some(ssl_mode) = &profile.ssl_mode {
+        let _ = write!(connect, ", sslmode={}", quoted(ssl_mode));
+    }
+
+    let mut code = format!("import os\nimport psycopg\n\nwith psycopg.connect({}) as conn:\n", connect);
+    if parameters.is_empty() {
+        let _ = writeln!(code, "    rows = conn.execute({}).fetchall()", quoted(sql));
+        return code;
+    }
+    let _ = writeln!(code, "    rows = conn.execute(\n        {},\n        {{", quoted(sql));
+    for (name, value) in parameters {
+        let _ = writeln!(code, "            {}: {},", quoted(name), value.literal(("True", "False")));
+    }
+    code.push_str("        },\n    ).fetchall()\n");
+    code
+}
+
+fn knex(profile: &ConnectionProfile, sql: &str, parameters: &[(&str, Value)]) -> String {
+    let mut code = "const knex = require('knex')({\n".to_string();
+    if profile.database_type == DatabaseType::SQLite {
+        let _ = writeln!(code, "  client: 'better-sqlite3',\n  connection: {{ filename: {} }},\n  useNullAsDefault: true,", quoted(&profile.database));
+    } else {
+        let client = if profile.database_type == DatabaseType::PostgreSQL { "pg" } else { "mysql2" };
+        let _ = writeln!(code, "  client: '{}',\n  connection: {{", client);
+        let _ = writeln!(code, "    host: {},\n    port: {},", quoted(host(profile)), port(profile));
+        if let Some(username) = &profile.username {
+            let _ = writeln!(code, "    user: {},", quoted(username));
+        }
+        let _ = writeln!(code, "    password: process.env.{},", password_variable(profile.database_type));
+        let _ = writeln!(code, "    database: {},\n  }},", quoted(&profile.database));
+    }
+    code.push_str("});\n\n");
+
+    if parameters.is_empty() {
+        let _ = writeln!(code, "const result = await knex.raw({});", quoted(sql));
+        return code;
+    }
+    let bindings: Vec<String> = parameters
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value.literal(("true", "false"))))
+        .collect();
+    let _ = writeln!(code, "const result = await knex.raw({}, {{ {} }});", quoted(sql), bindings.join(", "));
+    code
+}
+
+fn jdbc(profile: &ConnectionProfile, sql: &str, values: &[Value]) -> String {
+    let connection = match profile.database_type {
+        DatabaseType::SQLite => format!("DriverManager.getConnection({})", quoted(&format!("jdbc:sqlite:{}", profile.database))),
+        database_type => {
+            let driver = if database_type == DatabaseType::PostgreSQL { "postgresql" } else { "mysql" };
+            let mut url = format!("jdbc:{}://{}:{}/{}", driver, host(profile), port(profile), profile.database);
+            if let (DatabaseType::PostgreSQL, Some(ssl_mode)) = (database_type, &profile.ssl_mode) {
+                let _ = write!(url, "?sslmode={}", ssl_mode);
+            }
+            let username = profile.username.as_deref().map(quoted).unwrap_or_else(|| "null".to_string());
+            format!(
+                "DriverManager.getConnection({}, {}, System.getenv(\"{}\"))",
+                quoted(&url),
+                username,
+                password_variable(database_type)
+            )
+        }
+    };
+
+    let mut code = format!("try (Connection conn = {};\n     PreparedStatement statement = conn.prepareStatement({})) {{\n", connection, quoted(sql));
+    for (index, value) in values.iter().enumerate() {
+        let (setter, literal) = match value {
+            Value::Integer(i) => ("setLong", format!("{}L", i)),
+            Value::Float(f) => ("setDouble", f.to_string()),
+            Value::Boolean(b) => ("setBoolean", b.to_string()),
+            Value::Text(s) => ("setString", quoted(s)),
+        };
+        let _ = writeln!(code, "    statement.{}({}, {});", setter, index + 1, literal);
+    }
+    code.push_str(
+        "    if (statement.execute()) {\n        try (ResultSet rows = statement.getResultSet()) {\n            while (rows.next()) {\n                // ...\n            }\n        }\n    }\n}\n",
+    );
+    code
+}
+
+/// `sql` with its `{{name}}` parameters filled from `values`, as code that runs
+/// it with `target` on the connection of `profile`
+///
+/// Parameters become bind parameters of the library. The password is read from
+/// an environment variable, never written out.
+pub fn query_code(
+    target: CodeTarget,
+    profile: &ConnectionProfile,
+    sql: &str,
+    values: &HashMap<String, String>,
+) -> Result<String, AppError> {
+    if let DatabaseType::Other(id) = profile.database_type {
+        return Err(AppError::Validation(format!("No connection code is generated for {} databases", id)));
+    }
+    let style = match (target, profile.database_type) {
+        (CodeTarget::Sqlx, DatabaseType::PostgreSQL) => BindStyle::Numbered,
+        (CodeTarget::Sqlx, _) | (CodeTarget::Jdbc, _) => BindStyle::Positional,
+        (CodeTarget::Psycopg, DatabaseType::PostgreSQL) => BindStyle::Pyformat,
+        (CodeTarget::Psycopg, _) => {
+            return Err(AppError::Validation("psycopg only connects to PostgreSQL".to_string()));
+        }
+        (CodeTarget::Knex, _) => BindStyle::Colon,
+    };
+
+    let (bound_sql, names) = bind_parameters(sql, style);
+    let mut missing: Vec<&str> = Vec::new();
+    for name in names.iter().filter(|name| !values.contains_key(**name)) {
+        if !missing.contains(name) {
+            missing.push(name);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(AppError::Validation(format!("Missing values for parameters: {}", missing.join(", "))));
+    }
+    let parameters: Vec<(&str, Value)> = names.iter().map(|name| (*name, Value::parse(&values[*name]))).collect();
+    let bound: Vec<Value> = parameters.iter().map(|(_, value)| *value).collect();
+
+    Ok(match target {
+        CodeTarget::Sqlx => sqlx(profile, &bound_sql, &bound),
+        // psycopg only reads `%%` as `%` when there are parameters
+        CodeTarget::Psycopg if names.is_empty() => psycopg(profile, sql, &parameters),
+        CodeTarget::Psycopg => psycopg(profile, &bound_sql, &parameters),
+        CodeTarget::Knex => knex(profile, &bound_sql, &parameters),
+        CodeTarget::Jdbc => jdbc(profile, &bound_sql, &bound),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(database_type: DatabaseType) -> ConnectionProfile {
+        let mut profile = ConnectionProfile::new("shop".to_string(), database_type, "shop".to_string());
+        profile.username = Some("alice".to_string());
+        profile
+    }
+
+    #[test]
+    fn test_bind_parameters() {
+        let sql = "SELECT * FROM t WHERE a = {{id}} AND b LIKE '5%' AND c = {{id}} AND d = {{name}}";
+        assert_eq!(
+            bind_parameters(sql, BindStyle::Numbered),
+            ("SELECT * FROM t WHERE a = $1 AND b LIKE '5%' AND c = $1 AND d = $2".to_string(), vec!["id", "name"])
+        );
+        assert_eq!(bind_parameters(sql, BindStyle::Positional).1, vec!["id", "id", "name"]);
+        assert_eq!(
+            bind_parameters(sql, BindStyle::Pyformat).0,
+            "SELECT * FROM t WHERE a = %(id)s AND b LIKE '5%%' AND c = %(id)s AND d = %(name)s"
+        );
+        assert_eq!(Value::parse("007"), Value::Text("007"));
+        assert_eq!(Value::parse("-3"), Value::Integer(-3));
+        assert_eq!(raw_string("say \"#hi\""), "r##\"say \"#hi\"\"##");
+    }
+
+    #[test]
+    fn test_query_code() {
+        let values = HashMap::from([("id".to_string(), "42".to_string()), ("name".to_string(), "O'Brien".to_string())]);
+        let sql = "SELECT * FROM users WHERE id = {{id}} AND name = {{name}}";
+
+        let rust = query_code(CodeTarget::Sqlx, &profile(DatabaseType::PostgreSQL), sql, &values).unwrap();
+        assert_eq!(
+            rust,
+            "// DATABASE_URL=postgres://alice@localhost:5432/shop, with the password added\n\
+             let pool = sqlx::PgPool::connect(&std::env::var(\"DATABASE_URL\")?).await?;\n\
+             let rows = sqlx::query(r#\"SELECT * FROM users WHERE id = $1 AND name = $2\"#)\n    \
+             .bind(42_i64)\n    .bind(\"O'Brien\")\n    .fetch_all(&pool)\n    .await?;\n"
+        );
+
+        let python = query_code(CodeTarget::Psycopg, &profile(DatabaseType::PostgreSQL), sql, &values).unwrap();
+        assert!(python.contains("password=os.environ[\"PGPASSWORD\"]"));
+        assert!(python.contains("\"id\": 42,"));
+
+        let js = query_code(CodeTarget::Knex, &profile(DatabaseType::MySQL), sql, &values).unwrap();
+        assert!(js.contains("client: 'mysql2'"));
+        assert!(js.contains("{ id: 42, name: \"O'Brien\" }"));
+
+        let java = query_code(CodeTarget::Jdbc, &profile(DatabaseType::SQLite), sql, &values).unwrap();
+        assert!(java.contains("DriverManager.getConnection(\"jdbc:sqlite:shop\")"));
+        assert!(java.contains("statement.setString(2, \"O'Brien\");"));
+
+        assert!(query_code(CodeTarget::Psycopg, &profile(DatabaseType::MySQL), sql, &values).is_err());
+        assert!(query_code(CodeTarget::Jdbc, &profile(DatabaseType::MySQL), sql, &HashMap::new()).is_err());
+    }
+}
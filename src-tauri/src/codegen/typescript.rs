@@ -0,0 +1,142 @@
+use std::fmt::Write;
+
+use super::{pascal_case, SourceFile};
+use crate::database::adapter::{ColumnInfo, DatabaseType};
+use crate::database::types::{AbstractType, TypeMapper};
+
+/// TypeScript type and zod schema of a column type
+///
+/// 64-bit integers and decimals are strings, as a JavaScript number cannot hold
+/// them exactly; dates and times are ISO 8601 strings.
+fn column_types(abstract_type: &AbstractType) -> (&'static str, &'static str) {
+    match abstract_type {
+        AbstractType::SmallInt | AbstractType::Integer => ("number", "z.number().int()"),
+        AbstractType::Double => ("number", "z.number()"),
+        AbstractType::BigInt | AbstractType::Decimal { .. } => ("string", "z.string()"),
+        AbstractType::Boolean => ("boolean", "z.boolean()"),
+        AbstractType::Varchar { .. } | AbstractType::Char { .. } | AbstractType::Text => ("string", "z.string()"),
+        AbstractType::Date | AbstractType::Time | AbstractType::Timestamp | AbstractType::TimestampTz => {
+            ("string", "z.string()")
+        }
+        AbstractType::Json => ("unknown", "z.unknown()"),
+        AbstractType::Uuid => ("string", "z.string().uuid()"),
+        // Base64
+        AbstractType::Binary => ("string", "z.string()"),
+    }
+}
+
+/// Property name of a column; names that are not identifiers are quoted
+fn property_name(column: &str) -> String {
+    let identifier = column.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && column.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        column.to_string()
+    } else {
+        serde_json::Value::String(column.to_string()).to_string()
+    }
+}
+
+/// File name of the module of a table
+fn module_name(table: &str) -> String {
+    table
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// TypeScript module with an interface for the rows of `table`, and with `zod` a
+/// schema validating them
+///
+/// Properties keep the column names, as rows are keyed by them; nullable
+/// columns are `T | null`.
+fn table_module(database_type: DatabaseType, table: &str, columns: &[ColumnInfo], zod: bool) -> String {
+    let mapper = TypeMapper::new(database_type);
+    let type_name = pascal_case(table);
+    let types: Vec<(String, &str, &str)> = columns
+        .iter()
+        .map(|column| {
+            let (ts_type, schema) = column_types(&mapper.to_abstract(&column.data_type));
+            (property_name(&column.name), ts_type, schema)
+        })
+        .collect();
+
+    let mut module = format!("// Generated by DataForge from table {}\n", table);
+    if zod {
+        module.push_str("import { z } from 'zod';\n");
+    }
+
+    let _ = writeln!(module, "\nexport interface {} {{", type_name);
+    for ((name, ts_type, _), column) in types.iter().zip(columns) {
+        let null = if column.is_nullable { " | null" } else { "" };
+        let _ = writeln!(module, "  {}: {}{};", name, ts_type, null);
+    }
+    module.push_str("}\n");
+
+    if zod {
+        let _ = writeln!(module, "\nexport const {}Schema: z.ZodType<{}> = z.object({{", type_name, type_name);
+        for ((name, _, schema), column) in types.iter().zip(columns) {
+            let null = if column.is_nullable { ".nullable()" } else { "" };
+            let _ = writeln!(module, "  {}: {}{},", name, schema, null);
+        }
+        module.push_str("});\n");
+    }
+    module
+}
+
+/// Modules of the given tables, and an `index.ts` re-exporting them
+pub fn table_modules(database_type: DatabaseType, tables: &[(String, Vec<ColumnInfo>)], zod: bool) -> Vec<SourceFile> {
+    let mut files: Vec<SourceFile> = tables
+        .iter()
+        .map(|(table, columns)| SourceFile {
+            name: format!("{}.ts", module_name(table)),
+            contents: table_module(database_type, table, columns, zod),
+        })
+        .collect();
+
+    let index = tables
+        .iter()
+        .map(|(table, _)| format!("export * from './{}';\n", module_name(table)))
+        .collect();
+    files.push(SourceFile { name: "index.ts".to_string(), contents: index });
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, is_nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable,
+        }
+    }
+
+    #[test]
+    fn test_table_module() {
+        let columns = vec![
+            column("id", "bigint", false),
+            column("email", "varchar(255)", false),
+            column("score", "double precision", true),
+            column("is_admin", "boolean", false),
+            column("external id", "uuid", true),
+        ];
+
+        let module = table_module(DatabaseType::PostgreSQL, "app_users", &columns, true);
+        assert_eq!(
+            module,
+            "// Generated by DataForge from table app_users\n\
+             import { z } from 'zod';\n\
+             \n\
+             export interface AppUsers {\n  id: string;\n  email: string;\n  score: number | null;\n  is_admin: boolean;\n  \"external id\": string | null;\n}\n\
+             \n\
+             export const AppUsersSchema: z.ZodType<AppUsers> = z.object({\n  id: z.string(),\n  email: z.string(),\n  score: z.number().nullable(),\n  is_admin: z.boolean(),\n  \"external id\": z.string().uuid().nullable(),\n});\n"
+        );
+
+        let files = table_modules(DatabaseType::PostgreSQL, &[("app_users".to_string(), columns)], false);
+        assert_eq!(files[0].name, "app_users.ts");
+        assert!(!files[0].contents.contains("zod"));
+        assert_eq!(files[1].contents, "export * from './app_users';\n");
+    }
+}
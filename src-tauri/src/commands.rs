@@ -1,19 +1,89 @@
 use crate::database::adapter::{ConnectionParams, DatabaseAdapter, DatabaseType, create_adapter};
 use crate::database::capabilities::{DatabaseCapabilities, QueryTemplates};
+use crate::database::connection_check;
+use crate::database::dialect::{alter_table_statements, ColumnChange, TableDefinition};
+use crate::database::error::QueryError;
+use crate::database::metadata_cache::{load_schema, MetadataCache, SchemaSnapshot};
+use crate::database::result_cache::ResultCache;
+use crate::database::suggestions::{similar_names, UnknownObject};
+use crate::database::statement::{classify_statement, is_read_only, statement_tables, StatementKind};
+use crate::export::masking::{query_masker, MaskingRuleStore};
+use crate::notifications::{Notification, NotificationSource};
+use crate::error::{AppError, ErrorResponse, ScriptFailure};
+use crate::profile::ConnectionProfile;
+use crate::profile::history::QueryHistoryEntry;
+use crate::profile::rules::{StatementRuleStore, StatementRules};
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+use uuid::Uuid;
 use once_cell::sync::Lazy;
+use tauri::{AppHandle, Manager, State};
+use profile::ProfileManagerState;
+use query_log::StatementOutcome;
 
+pub mod activity;
+pub mod audit;
+pub mod automation;
+pub mod backup;
+pub mod bookmarks;
+pub mod browse;
+pub mod cloud;
+pub mod codegen;
+pub mod column_stats;
+pub mod demo;
+pub mod diagnostics;
+pub mod export;
+pub mod history;
+pub mod jobs;
+pub mod listen;
+pub mod logs;
+pub mod maintenance;
+pub mod masking;
+pub mod metrics;
+pub mod migrations;
+pub mod notebook;
+pub mod notifications;
 pub mod profile;
+pub mod query_log;
+pub mod result_cache;
+pub mod settings;
+pub mod stream;
+pub mod summary;
+pub mod templates;
+pub mod transfer;
+pub mod users;
+pub mod workspace;
 
 // Global adapter storage using Lazy static
 pub static ADAPTER_STATE: Lazy<Arc<Mutex<Option<Box<dyn DatabaseAdapter + Send + Sync>>>>> = Lazy::new(|| {
     Arc::new(Mutex::new(None))
 });
 
+// Profile of the active connection; None for connections made without a profile
+pub static ACTIVE_PROFILE: Lazy<Arc<Mutex<Option<ConnectionProfile>>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(None))
+});
+
+// ID of the active connection, attached to the log events of its queries
+pub static CONNECTION_ID: Lazy<Arc<Mutex<Option<String>>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(None))
+});
+
+// Table and column names of the active connection, for "did you mean" suggestions
+pub static METADATA_CACHE: Lazy<Arc<Mutex<MetadataCache>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(MetadataCache::new()))
+});
+
+// Query results cached on request, keyed by connection and normalized SQL
+pub static RESULT_CACHE: Lazy<Arc<Mutex<ResultCache>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(ResultCache::new()))
+});
+
 // Global connection cancellation token
 pub static CONNECTION_CANCEL_TOKEN: Lazy<Arc<Mutex<Option<CancellationToken>>>> = Lazy::new(|| {
     Arc::new(Mutex::new(None))
@@ -28,6 +98,11 @@ pub struct ConnectRequest {
     pub username: Option<String>,
     pub password: Option<String>,
     pub ssl_mode: Option<String>,
+    #[serde(default)]
+    pub default_schema: Option<String>,
+    /// Connections to open up front and keep while idle
+    #[serde(default)]
+    pub min_connections: Option<u32>,
 }
 
 impl From<ConnectRequest> for ConnectionParams {
@@ -38,12 +113,47 @@ impl From<ConnectRequest> for ConnectionParams {
         params.username = req.username;
         params.password = req.password;
         params.ssl_mode = req.ssl_mode;
+        params.default_schema = req.default_schema;
+        if req.min_connections.is_some() {
+            params.min_connections = req.min_connections;
+        }
         params
     }
 }
 
+/// Tauri's app data directory, where profiles and their companion files are kept
+pub(crate) fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Storage(format!("Could not resolve app data directory: {}", e)))
+}
+
+/// Fail when the active connection uses a read-only profile
+pub async fn ensure_writable() -> Result<(), String> {
+    match ACTIVE_PROFILE.lock().await.as_ref() {
+        Some(profile) if profile.read_only => Err(format!(
+            "Connection '{}' is read-only",
+            profile.name
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Close the usage session of the active profile connection, if there is one
+async fn record_profile_disconnect(state: &ProfileManagerState) {
+    if let Some(manager) = state.0.lock().await.as_ref() {
+        if let Err(e) = manager.record_disconnect() {
+            crate::log_warn!("commands", "Failed to record disconnect: {}", e);
+        }
+    }
+}
+
 #[tauri::command]
-pub async fn connect_database(request: ConnectRequest) -> Result<String, String> {
+pub async fn connect_database(
+    request: ConnectRequest,
+    state: State<'_, ProfileManagerState>,
+) -> Result<String, String> {
     let params: ConnectionParams = request.into();
 
     // Validate parameters
@@ -66,8 +176,10 @@ pub async fn connect_database(request: ConnectRequest) -> Result<String, String>
         .map_err(|e| format!("Failed to create adapter: {}", e))?;
 
     // Connect to database with cancellation support
+    let connection_id = Uuid::new_v4().to_string();
+    let connect_span = tracing::info_span!("connect", connection_id = %connection_id);
     let connect_result = tokio::select! {
-        result = adapter.connect(&params) => result,
+        result = adapter.connect(&params).instrument(connect_span) => result,
         _ = cancel_token.cancelled() => {
             // Clear the cancellation token
             let mut token_state = CONNECTION_CANCEL_TOKEN.lock().await;
@@ -82,17 +194,56 @@ pub async fn connect_database(request: ConnectRequest) -> Result<String, String>
         *token_state = None;
     }
 
-    connect_result.map_err(|e| format!("Connection failed: {}", e))?;
+    if let Err(e) = connect_result {
+        return Err(connection_failure(&params, &e).await);
+    }
 
     // Store adapter in global state
     let mut adapter_state = ADAPTER_STATE.lock().await;
     *adapter_state = Some(adapter);
+    *ACTIVE_PROFILE.lock().await = None;
+    *CONNECTION_ID.lock().await = Some(connection_id);
+    METADATA_CACHE.lock().await.clear();
+
+    // A profile connection that was replaced ends its usage session
+    record_profile_disconnect(&state).await;
 
     Ok("Connected successfully".to_string())
 }
 
+/// Error response for a failed connection attempt, naming the stage that failed
+pub(crate) async fn connection_failure(params: &ConnectionParams, error: &AppError) -> String {
+    let detail = error.detail().unwrap_or_default();
+    let diagnostics = connection_check::diagnose(params, &detail).await;
+    if let Some(stage) = diagnostics.as_ref().and_then(|d| d.failed_stage) {
+        crate::log_warn!("commands", "Connection failed at the {:?} stage: {}", stage, detail);
+    }
+
+    let mut response = ErrorResponse::from(error);
+    response.message = crate::i18n::error_message(
+        crate::i18n::locale(),
+        "connection_failed",
+        Some(diagnostics.as_ref().and_then(|d| d.failure()).unwrap_or(&detail)),
+    );
+    response.code = diagnostics
+        .as_ref()
+        .and_then(|d| d.failed_stage)
+        .and_then(|stage| serde_json::to_value(stage).ok())
+        .and_then(|value| value.as_str().map(str::to_string));
+    response.connection_diagnostics = diagnostics;
+    response.into()
+}
+
 #[tauri::command]
-pub async fn disconnect_database() -> Result<String, String> {
+pub async fn disconnect_database(state: State<'_, ProfileManagerState>) -> Result<String, String> {
+    close_active_connection(&state).await?;
+    Ok("Disconnected successfully".to_string())
+}
+
+/// Disconnect the active connection and forget its profile
+///
+/// Must not be called while holding the profile manager lock.
+pub(crate) async fn close_active_connection(state: &ProfileManagerState) -> Result<(), String> {
     // Take the adapter out of the mutex
     let adapter_option = {
         let mut adapter_state = ADAPTER_STATE.lock().await;
@@ -103,8 +254,13 @@ pub async fn disconnect_database() -> Result<String, String> {
         adapter.disconnect().await
             .map_err(|e| format!("Disconnect failed: {}", e))?;
     }
+    *ACTIVE_PROFILE.lock().await = None;
+    *CONNECTION_ID.lock().await = None;
+    METADATA_CACHE.lock().await.clear();
 
-    Ok("Disconnected successfully".to_string())
+    record_profile_disconnect(state).await;
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -119,41 +275,324 @@ pub async fn test_database_connection_adapter() -> Result<bool, String> {
     Err("No active connection".to_string())
 }
 
+/// Execute one or more SQL statements
+///
+/// Destructive statements (DROP, TRUNCATE, ALTER, and UPDATE or DELETE without a
+/// WHERE clause) are refused with a "confirmation required" error until the call is
+/// repeated with `force` set; the active profile's audit policy can require the same
+/// for every schema change. On a read-only profile only statements that read data
+/// are accepted. Every statement that is not a plain query is recorded in
+/// the audit log. Columns covered by a registered masking rule are masked; see
+/// `unmask_query` for the explicit way around that. The script is added to the
+/// encrypted query history.
 #[tauri::command]
-pub async fn execute_query(query: String) -> Result<serde_json::Value, String> {
-    let adapter_state = ADAPTER_STATE.lock().await;
+pub async fn execute_query(
+    query: String,
+    force: Option<bool>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<serde_json::Value, String> {
+    crate::profile::crypto::record_activity();
+    let start = std::time::Instant::now();
+    let result = run_query(&query, force.unwrap_or(false), true, &app_handle).await;
 
-    if let Some(adapter) = adapter_state.as_ref() {
-        // Get database type for SQL parsing
-        let db_type = adapter.database_type();
+    let (profile_id, profile_name) = match ACTIVE_PROFILE.lock().await.as_ref() {
+        Some(profile) => (Some(profile.id.clone()), profile.name.clone()),
+        None => (None, "query".to_string()),
+    };
+    // Keep only the message; the response may carry the rows of completed statements
+    let error = result.as_ref().err().map(|e| {
+        serde_json::from_str::<ErrorResponse>(e)
+            .map(|response| response.message)
+            .unwrap_or_else(|_| e.clone())
+    });
+
+    let outcome = match (&result, &error) {
+        (Ok(value), _) => Ok(value["rows"]
+            .as_array()
+            .map(|rows| rows.len() as u64)
+            .or_else(|| value["total_rows_affected"].as_u64())),
+        (Err(_), error) => Err(error.clone().unwrap_or_default()),
+    };
+    notifications::notify(&app_handle, Notification::new(NotificationSource::Query, profile_name, start.elapsed(), outcome));
+
+    let entry = QueryHistoryEntry::new(
+        profile_id,
+        query,
+        error,
+        start.elapsed().as_millis() as u64,
+    );
+    history::record_history(&state, entry).await;
+
+    result
+}
+
+/// Check that `connection_id`, the profile ID a command was given for its
+/// connection, is the active connection; without one the active connection is used
+pub(crate) async fn require_active_connection(connection_id: Option<String>) -> Result<(), String> {
+    let Some(id) = connection_id.filter(|id| !id.is_empty()) else {
+        return Ok(());
+    };
+    let active = ACTIVE_PROFILE.lock().await.as_ref().map(|p| p.id.clone());
+    if active.as_deref() != Some(id.as_str()) {
+        return Err(format!("Connection {} is not the active connection", id));
+    }
+    Ok(())
+}
+
+/// Refuse statements the statement rules block, writes on a read-only
+/// profile, statements its audit policy holds back, and unless `force`,
+/// destructive statements that have not been confirmed
+fn check_statements(
+    statements: &[(String, StatementKind)],
+    force: bool,
+    profile: Option<&ConnectionProfile>,
+    statement_rules: &StatementRules,
+    db_type: DatabaseType,
+) -> Result<(), String> {
+    for (statement, kind) in statements {
+        statement_rules
+            .check(statement.trim(), *kind, profile, db_type)
+            .map_err(String::from)?;
+    }
+
+    if let Some(profile) = profile {
+        if profile.read_only {
+            if let Some((statement, _)) = statements.iter().find(|(s, _)| !is_read_only(s, &db_type)) {
+                return Err(AppError::PermissionDenied(format!(
+                    "Connection '{}' is read-only; only SELECT, SHOW, and EXPLAIN may run: {}",
+                    profile.name,
+                    statement.trim()
+                )).into());
+            }
+        }
+
+        for (statement, kind) in statements {
+            profile.audit_policy
+                .check(statement.trim(), *kind, force)
+                .map_err(String::from)?;
+        }
+    }
+
+    if !force {
+        let destructive: Vec<String> = statements
+            .iter()
+            .filter_map(|(statement, kind)| {
+                kind.destructive_reason()
+                    .map(|reason| format!("{} -- {}", statement.trim(), reason))
+            })
+            .collect();
+
+        if !destructive.is_empty() {
+            return Err(ErrorResponse::confirmation_required(
+                "destructive_statement",
+                format!("{} statement(s) can destroy data and must be confirmed", destructive.len()),
+                &destructive,
+            ).into());
+        }
+    }
+    Ok(())
+}
+
+/// Check statements the app generates and runs itself on the active connection
+/// the way `run_query` checks a script, before any of them runs
+pub(crate) async fn check_generated_statements(
+    statements: &[String],
+    force: bool,
+    db_type: DatabaseType,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    let statement_rules = StatementRuleStore::new(&app_data_dir(app_handle)?)
+        .load()
+        .map_err(|e| e.to_string())?;
+    let profile = ACTIVE_PROFILE.lock().await.clone();
+    let statements: Vec<(String, StatementKind)> = statements
+        .iter()
+        .map(|statement| (statement.clone(), classify_statement(statement, &db_type)))
+        .collect();
+    check_statements(&statements, force, profile.as_ref(), &statement_rules, db_type)
+}
+
+/// Record generated statements that ran together in the audit log, each with
+/// the outcome of the whole run, and forget the cached results and metadata
+/// they may have changed; queries are left out as in `run_query`
+pub(crate) async fn record_generated_statements(
+    statements: &[String],
+    db_type: DatabaseType,
+    outcome: Result<(), &str>,
+    duration_ms: u64,
+    app_handle: &AppHandle,
+) {
+    let profile = ACTIVE_PROFILE.lock().await.clone();
+    let kinds: Vec<StatementKind> = statements.iter().map(|s| classify_statement(s, &db_type)).collect();
+    for (statement, kind) in statements.iter().zip(&kinds) {
+        if *kind != StatementKind::Query {
+            audit::record_statement(app_handle, profile.as_ref(), db_type, statement.trim(), outcome.map(|_| None), duration_ms);
+        }
+    }
+
+    if kinds.iter().any(StatementKind::is_ddl) {
+        METADATA_CACHE.lock().await.clear();
+    }
+    if kinds.iter().any(|kind| *kind != StatementKind::Query) {
+        let connection_id = CONNECTION_ID.lock().await.clone();
+        RESULT_CACHE.lock().await.invalidate(connection_id.as_deref());
+        browse::cancel_prefetch().await;
+    }
+}
+
+/// Run a script on the active connection, optionally masking query results
+///
+/// Log events while it runs carry the connection ID and a new query ID.
+pub(crate) async fn run_query(
+    query: &str,
+    force: bool,
+    mask_results: bool,
+    app_handle: &AppHandle,
+) -> Result<serde_json::Value, String> {
+    let connection_id = CONNECTION_ID.lock().await.clone().unwrap_or_default();
+    let span = tracing::info_span!(
+        "query",
+        connection_id = %connection_id,
+        query_id = %Uuid::new_v4(),
+    );
+    run_script(query, force, mask_results, &connection_id, app_handle).instrument(span).await
+}
+
+/// Error of a statement whose connection was closed or replaced while it waited to be retried
+const RETRY_CONNECTION_CLOSED: &str = "The connection was closed while the statement waited to be retried";
+
+async fn run_script(
+    query: &str,
+    force: bool,
+    mask_results: bool,
+    connection_id: &str,
+    app_handle: &AppHandle,
+) -> Result<serde_json::Value, String> {
+    let masking_rules = if mask_results {
+        MaskingRuleStore::new(app_handle)
+            .and_then(|store| store.list())
+            .map_err(|e| e.to_string())?
+    } else {
+        Vec::new()
+    };
+
+    let statement_rules = StatementRuleStore::new(&app_data_dir(app_handle)?)
+        .load()
+        .map_err(|e| e.to_string())?;
+
+    let profile = ACTIVE_PROFILE.lock().await.clone();
+    let mut adapter_state = ADAPTER_STATE.lock().await;
+
+    // Get database type for SQL parsing
+    if let Some(db_type) = adapter_state.as_ref().map(|adapter| adapter.database_type()) {
 
         // Split SQL statements
-        let statements = crate::database::sql_utils::split_sql_statements(&query, &db_type)
+        let statements = crate::database::sql_utils::split_sql_statements(query, &db_type)
             .map_err(|e| format!("Failed to parse SQL: {}", e))?;
 
         if statements.is_empty() {
             return Err("No valid SQL statements found".to_string());
         }
 
+        let statements: Vec<(String, StatementKind)> = statements
+            .into_iter()
+            .map(|statement| {
+                let kind = classify_statement(&statement, &db_type);
+                (statement, kind)
+            })
+            .collect();
+
+        // Check the whole script before anything runs so it is not left half-applied
+        check_statements(&statements, force, profile.as_ref(), &statement_rules, db_type)?;
+
         let mut results = Vec::new();
         let mut total_execution_time = 0u64;
         let mut total_rows_affected = 0u64;
+        // Where in the script to look for the next statement, for error positions
+        let mut search_from = 0usize;
 
         // Execute each statement
-        for statement in statements {
+        for (statement_index, (statement, kind)) in statements.into_iter().enumerate() {
             let trimmed = statement.trim();
             if trimmed.is_empty() {
                 continue;
             }
+            let audited = kind != StatementKind::Query;
+            let statement_from = search_from;
+            if let Some(index) = query.get(search_from..).and_then(|rest| rest.find(trimmed)) {
+                search_from += index + trimmed.len();
+            }
+
+            if let Some(profile) = profile.as_ref().filter(|p| p.audit_policy.log_statements) {
+                crate::log_info!("audit", "[{}] {}", profile.name, trimmed);
+            }
+
+            // Objects may be created, altered or dropped; the adapter lock keeps the
+            // schema from being reloaded before the statement has run
+            if kind.is_ddl() {
+                METADATA_CACHE.lock().await.clear();
+            }
+            // Cached results and the prefetched page may no longer match the data
+            if kind != StatementKind::Query {
+                RESULT_CACHE.lock().await.invalidate(Some(connection_id));
+                browse::cancel_prefetch().await;
+            }
 
             let start = std::time::Instant::now();
 
-            // Try to execute as query first (SELECT, SHOW, etc.)
-            match adapter.execute_query(trimmed).await {
-                Ok(result) => {
+            // Try to execute as query first (SELECT, SHOW, etc.), retrying read-only
+            // statements after transient errors when the profile allows it
+            let retry_policy = profile.as_ref().map(|p| &p.retry_policy).filter(|p| p.enabled);
+            let retryable = retry_policy.is_some() && is_read_only(trimmed, &db_type);
+            let mut retries = 0u32;
+            let query_result = loop {
+                let adapter = adapter_state.as_ref().ok_or(RETRY_CONNECTION_CLOSED)?;
+                match (adapter.execute_query(trimmed).await, retry_policy) {
+                    (Err(e), Some(policy)) if retryable && e.is_transient() && policy.should_retry(retries + 1) => {
+                        retries += 1;
+                        let delay = policy.delay(retries);
+                        crate::log_warn!("commands", "Transient error, retrying in {} ms: {}", delay.as_millis(), e);
+                        // Other commands may use the connection while this one waits
+                        drop(adapter_state);
+                        tokio::time::sleep(delay).await;
+                        adapter_state = ADAPTER_STATE.lock().await;
+                        if CONNECTION_ID.lock().await.as_deref() != Some(connection_id) {
+                            return Err(RETRY_CONNECTION_CLOSED.to_string());
+                        }
+                    }
+                    (result, _) => break result,
+                }
+            };
+            let adapter = adapter_state.as_ref().ok_or(RETRY_CONNECTION_CLOSED)?;
+
+            match query_result {
+                Ok(mut result) => {
                     let exec_time = start.elapsed().as_millis() as u64;
                     total_execution_time += exec_time;
 
+                    if audited {
+                        audit::record_statement(app_handle, profile.as_ref(), db_type, trimmed, Ok(result.rows_affected), exec_time);
+                    }
+                    query_log::record_statement(app_handle, connection_id, profile.as_ref(), trimmed, StatementOutcome {
+                        rows_returned: Some(result.rows.len() as u64),
+                        rows_affected: result.rows_affected,
+                        error: None,
+                    }, exec_time);
+                    metrics::record_statement(connection_id, profile.as_ref(), exec_time, true);
+
+                    let mut masked_columns = Vec::new();
+                    if !masking_rules.is_empty() {
+                        let masker = query_masker(
+                            &masking_rules,
+                            profile.as_ref().map(|p| p.id.as_str()),
+                            trimmed,
+                            &db_type,
+                        );
+                        masked_columns = masker.masked_columns(&result.columns);
+                        masker.mask_result(&mut result);
+                    }
+
                     // Transform rows from array format to object format
                     let transformed_rows: Vec<serde_json::Value> = result.rows.iter().map(|row| {
                         let mut obj = serde_json::Map::new();
@@ -173,7 +612,9 @@ pub async fn execute_query(query: String) -> Result<serde_json::Value, String> {
                         "columns": result.columns,
                         "rows": transformed_rows,
                         "rows_affected": result.rows_affected,
-                        "execution_time": exec_time
+                        "execution_time": exec_time,
+                        "masked_columns": masked_columns,
+                        "retries": retries
                     }));
                 }
                 Err(_) => {
@@ -184,15 +625,59 @@ pub async fn execute_query(query: String) -> Result<serde_json::Value, String> {
                             total_execution_time += exec_time;
                             total_rows_affected += affected;
 
+                            if audited {
+                                audit::record_statement(app_handle, profile.as_ref(), db_type, trimmed, Ok(Some(affected)), exec_time);
+                            }
+                            query_log::record_statement(app_handle, connection_id, profile.as_ref(), trimmed, StatementOutcome {
+                                rows_returned: None,
+                                rows_affected: Some(affected),
+                                error: None,
+                            }, exec_time);
+                            metrics::record_statement(connection_id, profile.as_ref(), exec_time, true);
+
                             results.push(serde_json::json!({
                                 "type": "command",
                                 "statement": trimmed,
                                 "rows_affected": affected,
-                                "execution_time": exec_time
+                                "execution_time": exec_time,
+                                "retries": retries
                             }));
                         }
                         Err(e) => {
-                            return Err(format!("Failed to execute statement: {}\nStatement: {}", e, trimmed));
+                            let exec_time = start.elapsed().as_millis() as u64;
+                            let error = e.to_string();
+                            if audited {
+                                audit::record_statement(app_handle, profile.as_ref(), db_type, trimmed, Err(&error), exec_time);
+                            }
+                            query_log::record_statement(app_handle, connection_id, profile.as_ref(), trimmed, StatementOutcome {
+                                rows_returned: None,
+                                rows_affected: None,
+                                error: Some(&error),
+                            }, exec_time);
+                            metrics::record_statement(connection_id, profile.as_ref(), exec_time, false);
+
+                            let mut response = ErrorResponse::from(&e);
+                            response.message = crate::i18n::error_message(
+                                crate::i18n::locale(),
+                                "statement_failed",
+                                e.detail().as_deref(),
+                            );
+                            response.details = Some(trimmed.to_string());
+                            response.query_error = match response.query_error {
+                                Some(query_error) => {
+                                    let mut query_error = query_error.relative_to(query, trimmed, statement_from);
+                                    query_error.suggestions = suggest_names(adapter.as_ref(), &query_error, trimmed).await;
+                                    Some(query_error)
+                                }
+                                None => None,
+                            };
+                            response.script_failure = Some(ScriptFailure {
+                                statement_index,
+                                statement: trimmed.to_string(),
+                                completed: results,
+                                rolled_back: false,
+                            });
+                            return Err(response.into());
                         }
                     }
                 }
@@ -212,7 +697,9 @@ pub async fn execute_query(query: String) -> Result<serde_json::Value, String> {
                         "columns": first["columns"],
                         "rows": first["rows"],
                         "rows_affected": first["rows_affected"],
-                        "execution_time": first["execution_time"]
+                        "execution_time": first["execution_time"],
+                        "masked_columns": first["masked_columns"],
+                        "retries": first["retries"]
                     }));
                 }
             }
@@ -229,6 +716,56 @@ pub async fn execute_query(query: String) -> Result<serde_json::Value, String> {
     Err("No active connection".to_string())
 }
 
+/// Known names similar to the unknown table or column a statement failed on
+///
+/// Names come from the metadata cache; missing entries are loaded once and kept.
+/// Columns are looked up in the tables the statement refers to.
+async fn suggest_names(
+    adapter: &(dyn DatabaseAdapter + Send + Sync),
+    error: &QueryError,
+    statement: &str,
+) -> Vec<String> {
+    const MAX_SUGGESTIONS: usize = 3;
+
+    let Some(unknown) = UnknownObject::from_message(&error.message) else {
+        return Vec::new();
+    };
+    let dialect = adapter.get_dialect();
+    let mut cache = METADATA_CACHE.lock().await;
+    if cache.tables().is_none() {
+        match adapter.list_tables().await {
+            Ok(tables) => cache.set_tables(tables.into_iter().map(|t| t.name).collect()),
+            Err(e) => crate::log_debug!("commands", "No tables for suggestions: {}", e),
+        }
+    }
+    let tables = cache.tables().map(<[String]>::to_vec).unwrap_or_default();
+
+    let candidates: Vec<String> = match &unknown {
+        UnknownObject::Table(_) => tables,
+        UnknownObject::Column(_) => {
+            // Statement table names are lowercased; look up the tables they name in the catalog
+            let referenced = statement_tables(statement, &adapter.database_type()).unwrap_or_default();
+            let mut columns = Vec::new();
+            for name in referenced {
+                let folded = dialect.fold_identifier(&name);
+                let Some(table) = tables.iter().find(|t| dialect.identifiers_equal(t, &folded)) else {
+                    continue;
+                };
+                if cache.columns(table).is_none() {
+                    match adapter.get_table_columns(table).await {
+                        Ok(found) => cache.set_columns(table, found.into_iter().map(|c| c.name).collect()),
+                        Err(e) => crate::log_debug!("commands", "No columns of {} for suggestions: {}", table, e),
+                    }
+                }
+                columns.extend(cache.columns(table).into_iter().flatten().cloned());
+            }
+            columns
+        }
+    };
+
+    similar_names(dialect.as_ref(), unknown.name(), candidates.iter().map(String::as_str), MAX_SUGGESTIONS)
+}
+
 #[tauri::command]
 pub async fn get_database_metadata() -> Result<serde_json::Value, String> {
     let adapter_state = ADAPTER_STATE.lock().await;
@@ -259,6 +796,7 @@ pub async fn list_database_tables() -> Result<serde_json::Value, String> {
             })?;
 
         crate::log_info!("command", "Found {} tables", tables.len());
+        METADATA_CACHE.lock().await.set_tables(tables.iter().map(|t| t.name.clone()).collect());
 
         // Convert to JSON
         let json_value = serde_json::to_value(tables)
@@ -272,6 +810,44 @@ pub async fn list_database_tables() -> Result<serde_json::Value, String> {
     Err("No active connection".to_string())
 }
 
+/// Tables, views, routines and columns for the schema browser
+///
+/// The schema is loaded concurrently and kept until `refresh` is set, the
+/// connection changes, or a DDL statement runs.
+#[tauri::command]
+pub async fn get_schema(refresh: Option<bool>) -> Result<SchemaSnapshot, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some(schema) = METADATA_CACHE.lock().await.schema() {
+            return Ok(schema.clone());
+        }
+    }
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    let start = std::time::Instant::now();
+    let schema = load_schema(adapter.as_ref()).await.map_err(String::from)?;
+    crate::log_info!(
+        "command",
+        "Loaded {} tables and {} routines in {} ms",
+        schema.tables.len(),
+        schema.routines.len(),
+        start.elapsed().as_millis()
+    );
+
+    METADATA_CACHE.lock().await.set_schema(schema.clone());
+    Ok(schema)
+}
+
+/// Count the rows of a table exactly; `list_database_tables` only gives the
+/// catalog's estimate, as counting every table can take very long
+#[tauri::command]
+pub async fn get_exact_row_count(table_name: String) -> Result<i64, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    adapter.exact_row_count(&table_name).await.map_err(String::from)
+}
+
 #[tauri::command]
 pub async fn cancel_connection() -> Result<String, String> {
     let mut token_state = CONNECTION_CANCEL_TOKEN.lock().await;
@@ -291,6 +867,8 @@ pub async fn get_table_indexes(table_name: String) -> Result<serde_json::Value,
     if let Some(adapter) = adapter_state.as_ref() {
         crate::log_info!("command", "Fetching indexes for table: {}", table_name);
         
+        let table_literal = adapter.get_dialect().string_literal(&table_name);
+
         // Get indexes using raw SQL query based on database type
         let query = match adapter.database_type() {
             DatabaseType::PostgreSQL => {
@@ -309,9 +887,9 @@ pub async fn get_table_indexes(table_name: String) -> Result<serde_json::Value,
                         pg_size_pretty(pg_relation_size(c.oid)) AS size
                     FROM pg_indexes i
                     LEFT JOIN pg_class c ON c.relname = i.indexname
-                    WHERE i.tablename = '{}'
+                    WHERE i.tablename = {}
                     ORDER BY i.indexname",
-                    table_name
+                    table_literal
                 )
             },
             DatabaseType::MySQL => {
@@ -330,9 +908,9 @@ pub async fn get_table_indexes(table_name: String) -> Result<serde_json::Value,
                         INDEX_TYPE AS index_type,
                         CARDINALITY AS cardinality
                     FROM information_schema.STATISTICS
-                    WHERE TABLE_NAME = '{}'
+                    WHERE TABLE_NAME = {}
                     ORDER BY INDEX_NAME, SEQ_IN_INDEX",
-                    table_name
+                    table_literal
                 )
             },
             DatabaseType::SQLite => {
@@ -350,11 +928,14 @@ pub async fn get_table_indexes(table_name: String) -> Result<serde_json::Value,
                         END AS is_unique
                     FROM sqlite_master
                     WHERE type = 'index' 
-                    AND tbl_name = '{}'
+                    AND tbl_name = {}
                     ORDER BY name",
-                    table_name
+                    table_literal
                 )
             },
+            DatabaseType::Other(id) => {
+                return Err(format!("Listing indexes is not supported for {} databases", id));
+            },
         };
         
         let result = adapter.execute_query(&query).await
@@ -389,28 +970,30 @@ pub async fn generate_select_query(table_name: String) -> Result<String, String>
     let adapter_state = ADAPTER_STATE.lock().await;
 
     if let Some(adapter) = adapter_state.as_ref() {
+        let table_literal = adapter.get_dialect().string_literal(&table_name);
+
         // Get table columns
         let columns_query = match adapter.database_type() {
-            DatabaseType::PostgreSQL => {
+            DatabaseType::PostgreSQL | DatabaseType::Other(_) => {
                 format!(
                     "SELECT column_name 
                     FROM information_schema.columns 
-                    WHERE table_name = '{}' 
+                    WHERE table_name = {} 
                     ORDER BY ordinal_position",
-                    table_name
+                    table_literal
                 )
             },
             DatabaseType::MySQL => {
                 format!(
                     "SELECT COLUMN_NAME AS column_name 
                     FROM information_schema.COLUMNS 
-                    WHERE TABLE_NAME = '{}' 
+                    WHERE TABLE_NAME = {} 
                     ORDER BY ORDINAL_POSITION",
-                    table_name
+                    table_literal
                 )
             },
             DatabaseType::SQLite => {
-                format!("PRAGMA table_info({})", table_name)
+                format!("PRAGMA table_info({})", table_literal)
             },
         };
         
@@ -459,6 +1042,22 @@ pub async fn generate_select_query(table_name: String) -> Result<String, String>
     Err("No active connection".to_string())
 }
 
+/// Generate the statements that apply column changes to a table, for review
+/// before they are run
+///
+/// `definition` is the table as it is now; SQLite needs it to rebuild the table
+/// for changes its ALTER TABLE cannot make.
+#[tauri::command]
+pub async fn generate_alter_table(
+    definition: TableDefinition,
+    changes: Vec<ColumnChange>,
+) -> Result<Vec<String>, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    alter_table_statements(&definition, &changes, adapter.get_dialect().as_ref()).map_err(|e| e.to_string())
+}
+
 /// Get database capabilities for the current connection
 #[tauri::command]
 pub async fn get_database_capabilities() -> Result<DatabaseCapabilities, String> {
@@ -0,0 +1,124 @@
+use once_cell::sync::Lazy;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use crate::commands::{ensure_writable, ADAPTER_STATE};
+use crate::database::adapter::{ReplicationStatus, SessionAction, SessionInfo};
+use crate::database::engine_status::EngineHealth;
+use crate::error::ErrorResponse;
+
+/// Event carrying the sessions listed by each refresh of `watch_sessions`
+pub const SERVER_ACTIVITY_EVENT: &str = "server-activity";
+
+const DEFAULT_REFRESH_SECS: u64 = 5;
+
+/// Stops the running `watch_sessions` task
+static SESSION_WATCH: Lazy<Mutex<Option<CancellationToken>>> = Lazy::new(|| Mutex::new(None));
+
+async fn current_sessions() -> Result<Vec<SessionInfo>, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    adapter.list_sessions().await.map_err(|e| e.to_string())
+}
+
+/// Sessions of other clients on the server of the current connection
+#[tauri::command]
+pub async fn list_sessions() -> Result<Vec<SessionInfo>, String> {
+    current_sessions().await
+}
+
+/// Emit the server's sessions as `server-activity` events every `interval_secs`
+/// seconds, replacing any earlier watch
+///
+/// The watch ends with `stop_watching_sessions` or once listing fails, e.g.
+/// because the connection was closed.
+#[tauri::command]
+pub async fn watch_sessions(interval_secs: Option<u64>, app_handle: AppHandle) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_secs.unwrap_or(DEFAULT_REFRESH_SECS).max(1));
+    let token = CancellationToken::new();
+    if let Some(previous) = SESSION_WATCH.lock().await.replace(token.clone()) {
+        previous.cancel();
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = ticks.tick() => {}
+            }
+            match current_sessions().await {
+                Ok(sessions) => {
+                    let _ = app_handle.emit(SERVER_ACTIVITY_EVENT, &sessions);
+                }
+                Err(e) => {
+                    crate::log_warn!("activity", "Stopped watching sessions: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Stop the refresh started by `watch_sessions`
+#[tauri::command]
+pub async fn stop_watching_sessions() -> Result<(), String> {
+    if let Some(token) = SESSION_WATCH.lock().await.take() {
+        token.cancel();
+    }
+    Ok(())
+}
+
+/// Replication links of the current server with their lag: the replicas and
+/// slots of a primary, or the server a replica streams from
+#[tauri::command]
+pub async fn replication_status() -> Result<Vec<ReplicationStatus>, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    adapter.replication_status().await.map_err(|e| e.to_string())
+}
+
+/// Thread, buffer pool and temporary table metrics with the InnoDB monitor
+/// output, for a MySQL health panel
+#[tauri::command]
+pub async fn engine_health() -> Result<EngineHealth, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    adapter.engine_health().await.map_err(|e| e.to_string())
+}
+
+/// Cancel the running query of session `pid` or close the session
+///
+/// Nothing happens until the call is repeated with `force` set; without it a
+/// "confirmation required" error carries the statement that would run.
+#[tauri::command]
+pub async fn end_session(pid: i64, action: SessionAction, force: Option<bool>) -> Result<bool, String> {
+    ensure_writable().await?;
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let statement = action.statement(adapter.database_type(), pid).map_err(String::from)?;
+    if !force.unwrap_or(false) {
+        let outcome = match action {
+            SessionAction::Cancel => "have its query cancelled",
+            SessionAction::Terminate => "be disconnected",
+        };
+        return Err(ErrorResponse::confirmation_required(
+            "end_session",
+            format!("Session {} will {}", pid, outcome),
+            &[statement],
+        )
+        .into());
+    }
+
+    let found = adapter.end_session(pid, action).await.map_err(String::from)?;
+    crate::log_info!("activity", "{} (session found: {})", statement, found);
+    Ok(found)
+}
@@ -0,0 +1,81 @@
+use chrono::Utc;
+use once_cell::sync::OnceCell;
+use std::path::Path;
+use tauri::AppHandle;
+use crate::audit::{self, AuditEntry, AuditExportFormat, AuditFilter, AuditLog, AuditVerification};
+use crate::database::adapter::DatabaseType;
+use crate::profile::ConnectionProfile;
+
+/// The audit log, opened on first use
+static AUDIT_LOG: OnceCell<AuditLog> = OnceCell::new();
+
+fn audit_log(app_handle: &AppHandle) -> Result<&'static AuditLog, String> {
+    AUDIT_LOG.get_or_try_init(|| AuditLog::new(app_handle).map_err(|e| e.to_string()))
+}
+
+/// Record a write statement in the audit log
+///
+/// A failure to write the audit log is logged but does not fail the statement,
+/// which has already run at this point.
+pub fn record_statement(
+    app_handle: &AppHandle,
+    profile: Option<&ConnectionProfile>,
+    database_type: DatabaseType,
+    statement: &str,
+    outcome: Result<Option<u64>, &str>,
+    duration_ms: u64,
+) {
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        user: audit::current_user(),
+        profile_id: profile.map(|p| p.id.clone()),
+        connection: profile
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| "Connection without profile".to_string()),
+        database_type,
+        database: profile.map(|p| p.database.clone()),
+        statement: statement.to_string(),
+        rows_affected: outcome.as_ref().ok().copied().flatten(),
+        success: outcome.is_ok(),
+        error: outcome.err().map(str::to_string),
+        duration_ms,
+        prev_hash: String::new(),
+        hash: String::new(),
+    };
+
+    if let Err(e) = audit_log(app_handle).and_then(|log| log.append(entry).map_err(|e| e.to_string())) {
+        crate::log_error!("audit", "Failed to write audit log: {}", e);
+    }
+}
+
+/// Search the audit log, newest entries first
+#[tauri::command]
+pub async fn query_audit_log(
+    filter: Option<AuditFilter>,
+    app_handle: AppHandle,
+) -> Result<Vec<AuditEntry>, String> {
+    audit_log(&app_handle)?
+        .query(&filter.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Export matching audit entries to a JSON or CSV file; returns the number exported
+#[tauri::command]
+pub async fn export_audit_log(
+    path: String,
+    format: AuditExportFormat,
+    filter: Option<AuditFilter>,
+    app_handle: AppHandle,
+) -> Result<usize, String> {
+    audit_log(&app_handle)?
+        .export(Path::new(&path), format, &filter.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Check that the audit log has not been edited
+#[tauri::command]
+pub async fn verify_audit_log(app_handle: AppHandle) -> Result<AuditVerification, String> {
+    audit_log(&app_handle)?
+        .verify()
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,191 @@
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use crate::automation::{ScriptHost, ScriptRun, ScriptRunner, ScriptTable};
+use crate::profile::automations::Automation;
+use crate::profile::ProfileManager;
+use super::{app_data_dir, run_query};
+use super::profile::ProfileManagerState;
+
+/// Directory under the app data directory that scripts export to
+const EXPORT_DIR: &str = "automation-exports";
+
+/// Cancelled to stop every running script, then replaced
+static AUTOMATION_CANCEL: Lazy<Mutex<CancellationToken>> = Lazy::new(|| Mutex::new(CancellationToken::new()));
+
+/// Runs the statements of a script on the active connection
+struct ConnectionHost {
+    app_handle: AppHandle,
+    runtime: tokio::runtime::Handle,
+    force: bool,
+}
+
+impl ConnectionHost {
+    fn run(&self, sql: &str) -> Result<serde_json::Value, String> {
+        self.runtime.block_on(run_query(sql, self.force, true, &self.app_handle))
+    }
+}
+
+impl ScriptHost for ConnectionHost {
+    fn query(&self, sql: &str) -> Result<ScriptTable, String> {
+        let result = self.run(sql)?;
+        // A script of several statements returns the last query's rows
+        let result = match result["results"].as_array() {
+            Some(results) => match results.iter().rev().find(|r| r["type"] == "query") {
+                Some(last) => last.clone(),
+                None => return Ok(ScriptTable::default()),
+            },
+            None => result,
+        };
+
+        let columns: Vec<String> = result["columns"]
+            .as_array()
+            .map(|columns| columns.iter().filter_map(|c| c["name"].as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        let rows = result["rows"]
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| columns.iter().map(|c| row[c].as_str().map(str::to_string)).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(ScriptTable { columns, rows })
+    }
+
+    fn execute(&self, sql: &str) -> Result<u64, String> {
+        let result = self.run(sql)?;
+        Ok(result["total_rows_affected"]
+            .as_u64()
+            .or_else(|| result["rows_affected"].as_u64())
+            .unwrap_or(0))
+    }
+}
+
+/// List the saved automation scripts
+#[tauri::command]
+pub async fn list_automations(
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<Automation>, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.list_automations().map_err(|e| e.to_string())
+}
+
+/// Add or update an automation script; a changed body becomes a new version
+#[tauri::command]
+pub async fn save_automation(
+    automation: Automation,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Automation, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.save_automation(automation).map_err(|e| e.to_string())
+}
+
+/// Make an earlier version of an automation script the current one
+#[tauri::command]
+pub async fn restore_automation(
+    id: String,
+    version: u32,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Automation, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.restore_automation(&id, version).map_err(|e| e.to_string())
+}
+
+/// Delete an automation script with all its versions
+#[tauri::command]
+pub async fn delete_automation(
+    id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.delete_automation(&id).map_err(|e| e.to_string())
+}
+
+/// Run a saved automation script by ID, or the given source
+///
+/// Statements of the script need `allow_destructive` to destroy data, as they
+/// cannot be confirmed one by one while the script runs.
+#[tauri::command]
+pub async fn run_automation(
+    id: Option<String>,
+    source: Option<String>,
+    allow_destructive: Option<bool>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<ScriptRun, String> {
+    let source = match (id, source) {
+        (_, Some(source)) => source,
+        (Some(id), None) => {
+            let mut manager_guard = state.0.lock().await;
+
+            if manager_guard.is_none() {
+                *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+            }
+
+            let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+            manager.get_automation(&id).map_err(|e| e.to_string())?.body
+        }
+        (None, None) => return Err("Either an automation ID or a script is required".to_string()),
+    };
+
+    let runner = ScriptRunner::new(&app_data_dir(&app_handle)?.join(EXPORT_DIR));
+    let cancel = AUTOMATION_CANCEL.lock().await.child_token();
+    let host = ConnectionHost {
+        app_handle,
+        runtime: tokio::runtime::Handle::current(),
+        force: allow_destructive.unwrap_or(false),
+    };
+
+    let run = tokio::task::spawn_blocking(move || runner.run(&source, Arc::new(host), cancel))
+        .await
+        .map_err(|e| format!("Script task failed: {}", e))?
+        .map_err(String::from)?;
+
+    crate::log_info!("automation", "Script finished in {} ms", run.duration_ms);
+    Ok(run)
+}
+
+/// Stop every running automation script
+#[tauri::command]
+pub async fn cancel_automations() -> Result<(), String> {
+    let mut cancel = AUTOMATION_CANCEL.lock().await;
+    cancel.cancel();
+    *cancel = CancellationToken::new();
+    Ok(())
+}
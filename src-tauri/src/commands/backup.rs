@@ -0,0 +1,386 @@
+use std::path::Path;
+use std::time::Duration;
+use chrono::Utc;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::backup::dump::{dump_command, dump_tool_name, DumpFormat};
+use crate::backup::schedule::{expired_backups, BackupSchedule, ScheduleStore};
+use crate::backup::restore::{self, RestoreMethod, RestoreOptions, RestoreReport};
+use crate::backup::sqlite::{self, IntegrityReport};
+use crate::backup::tool::resolve_tool;
+use crate::backup::{BackupCatalog, BackupEntry};
+use crate::commands::ADAPTER_STATE;
+use crate::commands::profile::{open_profile_adapter, profile_connection_params, ProfileManagerState};
+use crate::database::adapter::ConnectionParams;
+use crate::error::AppError;
+use crate::notifications::{Notification, NotificationSource};
+use super::notifications::notify;
+
+/// Event emitted with each progress line reported by a backup or restore
+pub const BACKUP_PROGRESS_EVENT: &str = "backup-progress";
+
+/// Event emitted when a scheduled backup fails
+pub const BACKUP_SCHEDULE_FAILED_EVENT: &str = "backup-schedule-failed";
+
+/// How often the scheduler checks for due backups
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupProgress {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupScheduleFailure {
+    pub schedule_id: String,
+    pub profile_id: String,
+    pub error: String,
+}
+
+/// Dump a profile's database and register the file in the backup catalog
+async fn dump_profile(
+    profile_id: &str,
+    path_for: impl FnOnce(&ConnectionParams) -> String,
+    format: DumpFormat,
+    tool_path: Option<&str>,
+    schedule_id: Option<String>,
+    state: &ProfileManagerState,
+    app_handle: &AppHandle,
+) -> Result<BackupEntry, String> {
+    let params = profile_connection_params(profile_id, state, app_handle).await?;
+    let path = path_for(&params);
+
+    let tool_name = dump_tool_name(params.database_type).map_err(|e| e.to_string())?;
+    let program = resolve_tool(tool_name, tool_path).map_err(|e| e.to_string())?;
+
+    let command = dump_command(&program, &params, Path::new(&path), format)
+        .map_err(|e| e.to_string())?;
+
+    crate::log_info!("backup", "Backing up {} with {}", params.database, program.display());
+
+    let progress_path = path.clone();
+    command
+        .run(|message| {
+            let _ = app_handle.emit(BACKUP_PROGRESS_EVENT, BackupProgress {
+                path: progress_path.clone(),
+                message,
+            });
+        })
+        .await
+        .map_err(|e| format!("Backup failed: {}", e))?;
+
+    let mut entry = BackupEntry::new(
+        Some(profile_id.to_string()),
+        params.database_type,
+        params.database,
+        path,
+        format.as_str().to_string(),
+        tool_name.to_string(),
+    );
+    entry.schedule_id = schedule_id;
+
+    crate::log_info!("backup", "Backup written to {} ({} bytes)", entry.path, entry.size_bytes);
+
+    BackupCatalog::new(app_handle)
+        .and_then(|catalog| catalog.add(entry))
+        .map_err(|e| e.to_string())
+}
+
+/// Send a notification about a finished backup, named after its database
+fn notify_backup(app_handle: &AppHandle, profile_id: &str, elapsed: Duration, result: &Result<BackupEntry, String>) {
+    let (name, outcome) = match result {
+        Ok(entry) => (entry.database.clone(), Ok(None)),
+        Err(error) => (profile_id.to_string(), Err(error.clone())),
+    };
+    notify(app_handle, Notification::new(NotificationSource::Backup, name, elapsed, outcome));
+}
+
+/// Back up a profile's database with pg_dump or mysqldump
+///
+/// The tool is looked up on the PATH and in common install locations unless
+/// `tool_path` is given. The finished backup is registered in the backup catalog.
+#[tauri::command]
+pub async fn create_backup(
+    profile_id: String,
+    path: String,
+    format: Option<DumpFormat>,
+    tool_path: Option<String>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<BackupEntry, String> {
+    let start = std::time::Instant::now();
+    let result = dump_profile(
+        &profile_id,
+        |_| path,
+        format.unwrap_or_default(),
+        tool_path.as_deref(),
+        None,
+        &state,
+        &app_handle,
+    )
+    .await;
+
+    notify_backup(&app_handle, &profile_id, start.elapsed(), &result);
+    result
+}
+
+/// Back up the active SQLite database to a file using `VACUUM INTO`
+#[tauri::command]
+pub async fn backup_database(path: String, app_handle: AppHandle) -> Result<BackupEntry, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    crate::log_info!("backup", "Backing up SQLite database to {}", path);
+
+    sqlite::backup_sqlite(adapter.as_ref(), Path::new(&path))
+        .await
+        .map_err(|e| format!("Backup failed: {}", e))?;
+
+    let database = adapter.current_database().await.map_err(|e| e.to_string())?;
+    let entry = BackupEntry::new(
+        None,
+        adapter.database_type(),
+        database,
+        path,
+        "sqlite".to_string(),
+        "vacuum_into".to_string(),
+    );
+
+    BackupCatalog::new(&app_handle)
+        .and_then(|catalog| catalog.add(entry))
+        .map_err(|e| e.to_string())
+}
+
+/// Run an integrity check on the active SQLite database
+#[tauri::command]
+pub async fn check_database_integrity() -> Result<IntegrityReport, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    sqlite::integrity_check(adapter.as_ref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List all backups in the catalog, newest first
+#[tauri::command]
+pub async fn list_backups(app_handle: AppHandle) -> Result<Vec<BackupEntry>, String> {
+    BackupCatalog::new(&app_handle)
+        .and_then(|catalog| catalog.list())
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a backup from the catalog, optionally deleting the file as well
+#[tauri::command]
+pub async fn delete_backup(
+    id: String,
+    delete_file: bool,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let catalog = BackupCatalog::new(&app_handle).map_err(|e| e.to_string())?;
+    let entry = catalog.remove(&id).map_err(|e| e.to_string())?;
+
+    if delete_file && Path::new(&entry.path).exists() {
+        std::fs::remove_file(&entry.path)
+            .map_err(|e| format!("Failed to delete backup file: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Restore a cataloged dump into a profile's database
+///
+/// With `dry_run`, nothing is executed and the report only lists the objects the
+/// restore would create, drop, or load data into.
+#[tauri::command]
+pub async fn restore_backup(
+    backup_id: String,
+    profile_id: String,
+    options: Option<RestoreOptions>,
+    dry_run: bool,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<RestoreReport, String> {
+    let options = options.unwrap_or_default();
+    let entry = BackupCatalog::new(&app_handle)
+        .and_then(|catalog| catalog.get(&backup_id))
+        .map_err(|e| e.to_string())?;
+    let format = DumpFormat::parse(&entry.format)
+        .ok_or_else(|| format!("Backups in '{}' format cannot be restored from a dump", entry.format))?;
+
+    let params = profile_connection_params(&profile_id, &state, &app_handle).await?;
+    if params.database_type != entry.database_type {
+        return Err(format!(
+            "Backup of a {:?} database cannot be restored into {:?}",
+            entry.database_type, params.database_type
+        ));
+    }
+
+    let method = RestoreMethod::select(format, params.database_type, options.use_script_runner)
+        .map_err(|e| e.to_string())?;
+    let program = match method.tool_name() {
+        Some(tool_name) => Some(
+            resolve_tool(tool_name, options.tool_path.as_deref()).map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+
+    let input = Path::new(&entry.path);
+    let plan = match (&program, method) {
+        (Some(program), RestoreMethod::PgRestore) => {
+            let toc = restore::list_archive_command(program, input)
+                .output()
+                .await
+                .map_err(|e| format!("Failed to read archive contents: {}", e))?;
+            restore::plan_pg_archive(&toc, options.clean)
+        }
+        _ => {
+            let sql = std::fs::read_to_string(input)
+                .map_err(|e| format!("Failed to read dump file: {}", e))?;
+            restore::plan_sql_script(&sql)
+        }
+    };
+
+    if dry_run {
+        return Ok(RestoreReport { method, plan, executed: false });
+    }
+
+    crate::log_info!("backup", "Restoring {} into {} via {:?}", entry.path, params.database, method);
+
+    let progress_path = entry.path.clone();
+    let on_progress = |message: String| {
+        let _ = app_handle.emit(BACKUP_PROGRESS_EVENT, BackupProgress {
+            path: progress_path.clone(),
+            message,
+        });
+    };
+
+    match &program {
+        Some(program) => {
+            restore::restore_command(method, program, &params, input, &options)
+                .map_err(|e| e.to_string())?
+                .run(on_progress)
+                .await
+                .map_err(|e| format!("Restore failed: {}", e))?;
+        }
+        None => {
+            let sql = std::fs::read_to_string(input)
+                .map_err(|e| format!("Failed to read dump file: {}", e))?;
+            let mut adapter = open_profile_adapter(&profile_id, &state, &app_handle).await?;
+            let result = restore::run_script(adapter.as_ref(), &sql, on_progress).await;
+            let _ = adapter.disconnect().await;
+            result.map_err(|e| format!("Restore failed: {}", e))?;
+        }
+    }
+
+    crate::log_info!("backup", "Restored {} into {}", entry.path, params.database);
+
+    Ok(RestoreReport { method, plan, executed: true })
+}
+
+/// List all backup schedules
+#[tauri::command]
+pub async fn list_backup_schedules(app_handle: AppHandle) -> Result<Vec<BackupSchedule>, String> {
+    ScheduleStore::new(&app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())
+}
+
+/// Create or update a backup schedule
+#[tauri::command]
+pub async fn save_backup_schedule(
+    schedule: BackupSchedule,
+    app_handle: AppHandle,
+) -> Result<BackupSchedule, String> {
+    ScheduleStore::new(&app_handle)
+        .and_then(|store| store.save(schedule))
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a backup schedule; backups it already took stay in the catalog
+#[tauri::command]
+pub async fn delete_backup_schedule(id: String, app_handle: AppHandle) -> Result<(), String> {
+    ScheduleStore::new(&app_handle)
+        .and_then(|store| store.delete(&id))
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a schedule's backups that fall outside its retention policy
+fn apply_retention(schedule: &BackupSchedule, app_handle: &AppHandle) -> Result<(), AppError> {
+    let catalog = BackupCatalog::new(app_handle)?;
+    let backups: Vec<BackupEntry> = catalog
+        .list()?
+        .into_iter()
+        .filter(|b| b.schedule_id.as_deref() == Some(schedule.id.as_str()))
+        .collect();
+
+    for expired in expired_backups(&backups, &schedule.retention, Utc::now()) {
+        catalog.remove(&expired.id)?;
+        if Path::new(&expired.path).exists() {
+            std::fs::remove_file(&expired.path)?;
+        }
+        crate::log_info!("backup", "Removed expired backup {}", expired.path);
+    }
+
+    Ok(())
+}
+
+/// Run every schedule that is due, recording the outcome on the schedule
+async fn run_due_schedules(app_handle: &AppHandle) -> Result<(), AppError> {
+    let store = ScheduleStore::new(app_handle)?;
+    let now = Utc::now();
+
+    for mut schedule in store.list()?.into_iter().filter(|s| s.is_due(now)) {
+        std::fs::create_dir_all(&schedule.directory)?;
+
+        let state = app_handle.state::<ProfileManagerState>();
+        let start = std::time::Instant::now();
+        let result = dump_profile(
+            &schedule.profile_id,
+            |params| schedule.backup_path(&params.database, now).to_string_lossy().to_string(),
+            schedule.format,
+            None,
+            Some(schedule.id.clone()),
+            &state,
+            app_handle,
+        )
+        .await;
+        notify_backup(app_handle, &schedule.profile_id, start.elapsed(), &result);
+
+        schedule.last_run = Some(now);
+        match result {
+            Ok(_) => {
+                schedule.last_error = None;
+                if let Err(e) = apply_retention(&schedule, app_handle) {
+                    crate::log_warn!("backup", "Failed to apply retention for {}: {}", schedule.id, e);
+                }
+            }
+            Err(error) => {
+                crate::log_error!("backup", "Scheduled backup {} failed: {}", schedule.id, error);
+                let _ = app_handle.emit(BACKUP_SCHEDULE_FAILED_EVENT, BackupScheduleFailure {
+                    schedule_id: schedule.id.clone(),
+                    profile_id: schedule.profile_id.clone(),
+                    error: error.clone(),
+                });
+                schedule.last_error = Some(error);
+            }
+        }
+        store.save(schedule)?;
+    }
+
+    Ok(())
+}
+
+/// Start the background task that runs scheduled backups while the app is open
+pub fn start_backup_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_schedules(&app_handle).await {
+                crate::log_error!("backup", "Backup scheduler failed: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,126 @@
+use tauri::{AppHandle, State};
+use crate::profile::bookmarks::{Bookmark, BookmarkListing};
+use crate::profile::recent_objects::{ObjectKind, RecentObject};
+use crate::profile::ProfileManager;
+use super::{app_data_dir, ACTIVE_PROFILE};
+use super::profile::ProfileManagerState;
+
+const DEFAULT_RECENT_OBJECTS: usize = 10;
+
+/// The given profile, or else the profile of the active connection
+async fn bookmark_profile(profile_id: Option<String>) -> Result<String, String> {
+    match profile_id.filter(|id| !id.is_empty()) {
+        Some(id) => Ok(id),
+        None => ACTIVE_PROFILE
+            .lock()
+            .await
+            .as_ref()
+            .map(|profile| profile.id.clone())
+            .ok_or_else(|| "No active connection".to_string()),
+    }
+}
+
+/// Bookmarked tables, views and queries of a connection, the active one by default
+#[tauri::command]
+pub async fn list_bookmarks(
+    profile_id: Option<String>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<BookmarkListing, String> {
+    let profile_id = bookmark_profile(profile_id).await?;
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.list_bookmarks(&profile_id).map_err(|e| e.to_string())
+}
+
+/// Bookmark a table, view or query, on the active connection unless the
+/// bookmark names a profile; bookmarks with an ID are updated
+#[tauri::command]
+pub async fn save_bookmark(
+    mut bookmark: Bookmark,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Bookmark, String> {
+    bookmark.profile_id = bookmark_profile(Some(bookmark.profile_id)).await?;
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.save_bookmark(bookmark).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_bookmark(
+    id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.delete_bookmark(&id).map_err(|e| e.to_string())
+}
+
+/// Record that the user opened a table or view, on the active connection by default
+#[tauri::command]
+pub async fn record_object_opened(
+    kind: ObjectKind,
+    name: String,
+    schema: Option<String>,
+    connection_id: Option<String>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let profile_id = bookmark_profile(connection_id).await?;
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager
+        .record_object_opened(&profile_id, kind, &name, schema.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Tables and views recently opened on a connection, the active one by default
+///
+/// Each opening counts for less as it ages, so objects opened often in the
+/// past drop below ones the user is working with now.
+#[tauri::command]
+pub async fn recent_objects(
+    connection_id: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<RecentObject>, String> {
+    let profile_id = bookmark_profile(connection_id).await?;
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager
+        .recent_objects(&profile_id, limit.unwrap_or(DEFAULT_RECENT_OBJECTS))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,800 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use crate::commands::{
+    check_generated_statements, ensure_writable, record_generated_statements, run_query, ACTIVE_PROFILE,
+    ADAPTER_STATE, CONNECTION_ID, METADATA_CACHE,
+};
+use crate::database::capabilities::DatabaseCapabilities;
+use crate::database::statement::is_read_only;
+use crate::database::dialect::{ConflictAction, SqlDialect, UpsertBuilder};
+use crate::export::insert::sql_literal;
+use crate::export::masking::{rules_for, Masker, MaskingRuleStore};
+
+/// Default number of rows per page when browsing a table
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Prefetched pages older than this are read again rather than served
+const PREFETCH_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// Number of saved edits that can be undone
+const EDIT_HISTORY_LIMIT: usize = 50;
+
+/// Where the next page of a table starts
+///
+/// Tables with a primary key are paged by seeking past the key of the last row;
+/// others, and tables whose key is masked, fall back to OFFSET.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageCursor {
+    /// Primary key values of the last row of the previous page
+    After(Vec<String>),
+    Offset(usize),
+}
+
+/// How a filter compares a column with its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOperator {
+    Equals,
+    /// Case-insensitive substring match
+    Contains,
+    /// Regular expression match, where the database has one
+    Matches,
+}
+
+/// Condition on a column, or on a value inside a JSON column when `path` is set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnFilter {
+    pub column: String,
+    /// Keys and array indexes leading to the value in the JSON document
+    #[serde(default)]
+    pub path: Vec<String>,
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+/// One page of rows of a table, in primary key order when it has one
+#[derive(Debug, Serialize)]
+pub struct TablePage {
+    pub columns: serde_json::Value,
+    pub rows: Vec<serde_json::Value>,
+    pub masked_columns: serde_json::Value,
+    /// Columns the rows are ordered and paged by; empty when paging by offset
+    pub key_columns: Vec<String>,
+    /// Cursor of the following page, `None` on the last page
+    pub next_cursor: Option<PageCursor>,
+}
+
+/// Split "schema.table" so both parts can be quoted
+pub(super) fn split_table_name(table_name: &str) -> (Option<&str>, &str) {
+    match table_name.split_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (None, table_name),
+    }
+}
+
+/// SQL condition of a filter
+pub(super) fn filter_condition(dialect: &dyn SqlDialect, filter: &ColumnFilter) -> Result<String, String> {
+    let column = dialect.quote_identifier(&filter.column);
+    let expression = if filter.path.is_empty() {
+        column
+    } else {
+        let path: Vec<&str> = filter.path.iter().map(String::as_str).collect();
+        dialect.json_extract(&column, &path)
+    };
+
+    match filter.operator {
+        FilterOperator::Equals => Ok(format!("{} = {}", expression, dialect.string_literal(&filter.value))),
+        FilterOperator::Contains => {
+            let escaped = filter.value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+            Ok(format!(
+                "{} {} {} ESCAPE {}",
+                expression,
+                dialect.case_insensitive_like(),
+                dialect.string_literal(&format!("%{}%", escaped)),
+                dialect.string_literal("\\")
+            ))
+        }
+        FilterOperator::Matches => dialect
+            .regex_match(&expression, &filter.value)
+            .ok_or_else(|| "Regular expressions are not supported by this database".to_string()),
+    }
+}
+
+/// Cursor of the page after `rows`, from the key values of its last row
+///
+/// Masked values are not the stored ones, so a masked key pages by offset.
+fn next_cursor(
+    rows: &[serde_json::Value],
+    key_columns: &[String],
+    masked_columns: &[String],
+    offset: usize,
+    page_size: usize,
+) -> Option<PageCursor> {
+    if key_columns.is_empty() || key_columns.iter().any(|column| masked_columns.contains(column)) {
+        return Some(PageCursor::Offset(offset + page_size));
+    }
+
+    let last = rows.last()?;
+    key_columns
+        .iter()
+        .map(|column| last.get(column).and_then(|v| v.as_str()).map(str::to_string))
+        .collect::<Option<Vec<String>>>()
+        .map(PageCursor::After)
+}
+
+/// A page of a table as requested from `browse_table`
+#[derive(Debug, Clone, PartialEq)]
+struct PageRequest {
+    table_name: String,
+    cursor: Option<PageCursor>,
+    page_size: usize,
+    filters: Vec<ColumnFilter>,
+}
+
+/// The page after the one last served, read in the background
+struct Prefetch {
+    request: PageRequest,
+    started: Instant,
+    token: CancellationToken,
+    page: JoinHandle<Result<TablePage, String>>,
+}
+
+/// Next page of the table being browsed
+static PREFETCH: Lazy<Mutex<Option<Prefetch>>> = Lazy::new(Default::default);
+
+/// Rows saved from the table view, with the statements putting them back
+struct DataEdit {
+    connection_id: String,
+    table_name: String,
+    /// Inverse of each changed row, the last changed row first
+    statements: Vec<String>,
+}
+
+/// Edits saved on the current connection, the latest last
+static EDIT_HISTORY: Lazy<Mutex<Vec<DataEdit>>> = Lazy::new(Default::default);
+
+/// Edit undone by `undo_last_edit`
+#[derive(Debug, Serialize)]
+pub struct UndoneEdit {
+    pub table_name: String,
+    pub rows_affected: u64,
+    /// Edits that can still be undone
+    pub remaining: usize,
+}
+
+/// Read the page of `request`
+async fn read_page(request: &PageRequest, app_handle: &AppHandle) -> Result<TablePage, String> {
+    let page_size = request.page_size;
+    let (schema, table) = split_table_name(&request.table_name);
+
+    let (query, key_columns, offset) = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        let dialect = adapter.get_dialect();
+
+        let key_columns = adapter
+            .primary_key_columns(&request.table_name)
+            .await
+            .map_err(|e| format!("Failed to get primary key: {}", e))?;
+
+        let mut conditions = request
+            .filters
+            .iter()
+            .map(|filter| filter_condition(dialect.as_ref(), filter))
+            .collect::<Result<Vec<String>, String>>()?;
+        if let (false, Some(PageCursor::After(values))) = (key_columns.is_empty(), &request.cursor) {
+            let columns: Vec<&str> = key_columns.iter().map(String::as_str).collect();
+            let values: Vec<&str> = values.iter().map(String::as_str).collect();
+            let keyset = dialect.keyset_clause(&columns, &values);
+            conditions.extend(keyset.strip_prefix(" WHERE ").map(str::to_string));
+        }
+
+        let mut query = format!("SELECT * FROM {}", dialect.qualified_table_name(schema, table));
+        if !conditions.is_empty() {
+            query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        let offset = match request.cursor {
+            Some(PageCursor::Offset(offset)) => offset,
+            _ => 0,
+        };
+        if !key_columns.is_empty() {
+            let order: Vec<String> = key_columns.iter().map(|c| dialect.quote_identifier(c)).collect();
+            query.push_str(&format!(" ORDER BY {}", order.join(", ")));
+        }
+        // One row more than shown tells whether another page follows
+        query.push_str(&dialect.limit_clause(Some(page_size + 1), Some(offset).filter(|o| *o > 0)));
+
+        (query, key_columns, offset)
+    };
+
+    let result = run_query(&query, false, true, app_handle).await?;
+    let mut rows = result["rows"].as_array().cloned().unwrap_or_default();
+
+    let has_more = rows.len() > page_size;
+    rows.truncate(page_size);
+    let masked_columns: Vec<String> = serde_json::from_value(result["masked_columns"].clone()).unwrap_or_default();
+    let next_cursor = if has_more {
+        next_cursor(&rows, &key_columns, &masked_columns, offset, page_size)
+    } else {
+        None
+    };
+
+    Ok(TablePage {
+        columns: result["columns"].clone(),
+        rows,
+        masked_columns: result["masked_columns"].clone(),
+        key_columns,
+        next_cursor,
+    })
+}
+
+/// The prefetched page if it is the one requested and recent enough; any other
+/// prefetch is cancelled, as the user moved to another table or filter
+async fn take_prefetch(request: &PageRequest) -> Option<JoinHandle<Result<TablePage, String>>> {
+    let prefetch = PREFETCH.lock().await.take()?;
+    if prefetch.request == *request && prefetch.started.elapsed() < PREFETCH_MAX_AGE {
+        return Some(prefetch.page);
+    }
+
+    prefetch.token.cancel();
+    None
+}
+
+/// Start reading the page of `request` in the background, replacing any earlier prefetch
+async fn start_prefetch(request: PageRequest, app_handle: AppHandle) {
+    let token = CancellationToken::new();
+    let cancelled = token.clone();
+    let background = request.clone();
+    let page = tauri::async_runtime::spawn(async move {
+        tokio::select! {
+            _ = cancelled.cancelled() => Err("Prefetch cancelled".to_string()),
+            page = read_page(&background, &app_handle) => page,
+        }
+    });
+
+    let prefetch = Prefetch {
+        request,
+        started: Instant::now(),
+        token,
+        page,
+    };
+    if let Some(earlier) = PREFETCH.lock().await.replace(prefetch) {
+        earlier.token.cancel();
+    }
+}
+
+/// Forget the prefetched page, e.g. once rows were changed
+pub(crate) async fn cancel_prefetch() {
+    if let Some(prefetch) = PREFETCH.lock().await.take() {
+        prefetch.token.cancel();
+    }
+}
+
+/// Read a page of a table for the table view
+///
+/// Deep pages stay fast because the database seeks to them through the primary
+/// key instead of reading and discarding every earlier row. Only rows matching
+/// every filter are returned. Once a page is served the next one is read in the
+/// background, so it is ready when the user scrolls on; changing the table or
+/// filters cancels that read.
+#[tauri::command]
+pub async fn browse_table(
+    table_name: String,
+    cursor: Option<PageCursor>,
+    page_size: Option<usize>,
+    filters: Option<Vec<ColumnFilter>>,
+    app_handle: AppHandle,
+) -> Result<TablePage, String> {
+    let request = PageRequest {
+        table_name,
+        cursor,
+        page_size: page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1),
+        filters: filters.unwrap_or_default(),
+    };
+
+    let prefetched = match take_prefetch(&request).await {
+        Some(page) => page.await.ok().and_then(Result::ok),
+        None => None,
+    };
+    let page = match prefetched {
+        Some(page) => page,
+        None => read_page(&request, &app_handle).await?,
+    };
+
+    if let Some(cursor) = &page.next_cursor {
+        let next = PageRequest {
+            cursor: Some(cursor.clone()),
+            ..request
+        };
+        start_prefetch(next, app_handle).await;
+    }
+    Ok(page)
+}
+
+/// Statement reading rows `offset + 1` to `offset + limit` of a SELECT query
+///
+/// Rows are numbered with ROW_NUMBER() where the server can, so each keeps its
+/// position in the whole result; other servers page with LIMIT/OFFSET.
+fn query_page(
+    dialect: &dyn SqlDialect,
+    capabilities: &DatabaseCapabilities,
+    query: &str,
+    order_by: &[&str],
+    offset: usize,
+    limit: usize,
+) -> String {
+    let query = query.trim().trim_end_matches(';').trim_end();
+    if capabilities.window_functions && capabilities.common_table_expressions {
+        return dialect.row_number_page(query, order_by, offset, limit);
+    }
+
+    let mut page = format!("SELECT * FROM ({}) {}", query, dialect.quote_identifier("_query"));
+    if !order_by.is_empty() {
+        let order: Vec<String> = order_by.iter().map(|c| dialect.quote_identifier(c)).collect();
+        page.push_str(&format!(" ORDER BY {}", order.join(", ")));
+    }
+    page.push_str(&dialect.limit_clause(Some(limit), Some(offset).filter(|o| *o > 0)));
+    page
+}
+
+/// Read a page of the result of a SELECT query, ordered by `order_by`
+#[tauri::command]
+pub async fn browse_query(
+    query: String,
+    order_by: Option<Vec<String>>,
+    offset: Option<usize>,
+    page_size: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<serde_json::Value, String> {
+    let statement = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        if !is_read_only(&query, &adapter.database_type()) {
+            return Err("Only SELECT queries can be paged".to_string());
+        }
+
+        let order_by = order_by.unwrap_or_default();
+        let order_by: Vec<&str> = order_by.iter().map(String::as_str).collect();
+        query_page(
+            adapter.get_dialect().as_ref(),
+            &adapter.get_capabilities(),
+            &query,
+            &order_by,
+            offset.unwrap_or(0),
+            page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1),
+        )
+    };
+
+    run_query(&statement, false, true, &app_handle).await
+}
+
+/// Write rows edited in the table view back to the table
+///
+/// Rows whose primary key already exists are updated and the rest inserted, in
+/// one statement. Returns the number of rows affected as reported by the database.
+#[tauri::command]
+pub async fn save_table_rows(
+    table_name: String,
+    mut columns: Vec<String>,
+    mut rows: Vec<Vec<Option<String>>>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    let (schema, table) = split_table_name(&table_name);
+
+    let masking_rules = MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())?;
+    let profile_id = ACTIVE_PROFILE.lock().await.as_ref().map(|p| p.id.clone());
+    let masker = Masker::new(rules_for(&masking_rules, profile_id.as_deref(), Some(&[table.to_lowercase()])));
+
+    let (statement, undo) = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        let dialect = adapter.get_dialect();
+
+        let key_columns = adapter
+            .primary_key_columns(&table_name)
+            .await
+            .map_err(|e| format!("Failed to get primary key: {}", e))?;
+        if key_columns.is_empty() {
+            return Err(format!("Table '{}' has no primary key to match edited rows by", table_name));
+        }
+        let table_columns = adapter
+            .get_table_columns(&table_name)
+            .await
+            .map_err(|e| format!("Failed to get columns: {}", e))?;
+        remove_masked_columns(&mut columns, &mut rows, &masker.masked_columns(&table_columns), &key_columns)?;
+
+        let data_types: Vec<&str> = columns
+            .iter()
+            .map(|name| {
+                table_columns
+                    .iter()
+                    .find(|c| &c.name == name)
+                    .map(|c| c.data_type.as_str())
+                    .ok_or_else(|| format!("Column '{}' not found in '{}'", name, table_name))
+            })
+            .collect::<Result<_, _>>()?;
+        let values: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let literals: Vec<String> = data_types
+                    .iter()
+                    .enumerate()
+                    .map(|(i, data_type)| sql_literal(row.get(i).and_then(|v| v.as_deref()), data_type, dialect.as_ref()))
+                    .collect();
+                format!("({})", literals.join(", "))
+            })
+            .collect();
+
+        let upsert = UpsertBuilder::new(dialect.as_ref(), table)
+            .schema(schema)
+            .columns(&columns)
+            .conflict_keys(&key_columns)
+            .on_conflict(ConflictAction::Update);
+        upsert.validate().map_err(|e| e.to_string())?;
+
+        // Rows as they are before the save, so it can be undone
+        let edit = TableEdit {
+            dialect: dialect.as_ref(),
+            schema,
+            table,
+            columns: &columns,
+            data_types: &data_types,
+            key_columns: &key_columns,
+        };
+        let undo = match edit.before_image_query(&rows) {
+            Some(query) => {
+                let before = adapter
+                    .execute_query(&query)
+                    .await
+                    .map_err(|e| format!("Failed to read the rows before saving: {}", e))?;
+                let before: Vec<Vec<Option<String>>> = before.rows.into_iter().map(|row| row.values).collect();
+                Some(edit.undo_statements(&rows, &before))
+            }
+            None => None,
+        };
+
+        (upsert.build(&values), undo)
+    };
+
+    let result = run_query(&statement, false, false, &app_handle).await?;
+
+    match undo.filter(|statements| !statements.is_empty()) {
+        Some(statements) => {
+            let connection_id = CONNECTION_ID.lock().await.clone().unwrap_or_default();
+            let mut history = EDIT_HISTORY.lock().await;
+            history.retain(|edit| edit.connection_id == connection_id);
+            history.push(DataEdit { connection_id, table_name, statements });
+            let excess = history.len().saturating_sub(EDIT_HISTORY_LIMIT);
+            history.drain(..excess);
+        }
+        None => crate::log_warn!("browse", "Rows saved to '{}' without their primary key cannot be undone", table_name),
+    }
+
+    Ok(result["total_rows_affected"].as_u64().unwrap_or(0))
+}
+
+/// Remove the columns masked for the connection from the edited rows
+///
+/// The table view shows placeholders in masked columns, which must not be
+/// written over the real values. Rows cannot be matched by a masked key, so
+/// editing a table whose key is masked is refused.
+fn remove_masked_columns(
+    columns: &mut Vec<String>,
+    rows: &mut [Vec<Option<String>>],
+    masked: &[String],
+    key_columns: &[String],
+) -> Result<(), String> {
+    let is_masked = |column: &str| masked.iter().any(|m| m.eq_ignore_ascii_case(column));
+    if let Some(key) = key_columns.iter().find(|key| is_masked(key)) {
+        return Err(format!("Rows cannot be saved while their key column '{}' is masked", key));
+    }
+
+    let masked_indexes: Vec<usize> = (0..columns.len()).filter(|&i| is_masked(&columns[i])).collect();
+    for &i in masked_indexes.iter().rev() {
+        columns.remove(i);
+        for row in rows.iter_mut().filter(|row| i < row.len()) {
+            row.remove(i);
+        }
+    }
+    Ok(())
+}
+
+/// Take the last edit saved on the active connection from `history`
+///
+/// Edits cannot be undone on a read-only connection, which leaves them in the history.
+async fn pop_edit(history: &mut Vec<DataEdit>) -> Result<DataEdit, String> {
+    ensure_writable().await?;
+    let connection_id = CONNECTION_ID.lock().await.clone().unwrap_or_default();
+    history.retain(|edit| edit.connection_id == connection_id);
+    history.pop().ok_or_else(|| "There are no edits to undo".to_string())
+}
+
+/// Run the statements undoing `edit`, checked and audited as queries from the editor are
+async fn run_undo(edit: &DataEdit, app_handle: &AppHandle) -> Result<u64, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    let db_type = adapter.database_type();
+    check_generated_statements(&edit.statements, false, db_type, app_handle).await?;
+
+    let start = Instant::now();
+    let outcome = adapter.execute_batch(&edit.statements).await.map_err(|e| e.to_string());
+    record_generated_statements(
+        &edit.statements,
+        db_type,
+        outcome.as_ref().map(|_| ()).map_err(String::as_str),
+        start.elapsed().as_millis() as u64,
+        app_handle,
+    )
+    .await;
+    METADATA_CACHE.lock().await.forget_table(split_table_name(&edit.table_name).1);
+    outcome
+}
+
+/// Revert the last rows saved from the table view on this connection
+///
+/// The rows' previous values are written back and inserted rows deleted, in
+/// reverse order inside one transaction, so a failed undo changes nothing and
+/// can be tried again.
+#[tauri::command]
+pub async fn undo_last_edit(app_handle: AppHandle) -> Result<UndoneEdit, String> {
+    let mut history = EDIT_HISTORY.lock().await;
+    let edit = pop_edit(&mut history).await?;
+
+    match run_undo(&edit, &app_handle).await {
+        Ok(rows_affected) => {
+            crate::log_info!("browse", "Undid {} row change(s) to '{}'", edit.statements.len(), edit.table_name);
+            Ok(UndoneEdit {
+                table_name: edit.table_name,
+                rows_affected,
+                remaining: history.len(),
+            })
+        }
+        Err(e) => {
+            history.push(edit);
+            Err(format!("Failed to undo the edit: {}", e))
+        }
+    }
+}
+
+/// Rows of one table being saved from the table view
+struct TableEdit<'a> {
+    dialect: &'a dyn SqlDialect,
+    schema: Option<&'a str>,
+    table: &'a str,
+    columns: &'a [String],
+    data_types: &'a [&'a str],
+    key_columns: &'a [String],
+}
+
+impl TableEdit<'_> {
+    /// Positions of the primary key in the edited columns; `None` unless all are edited
+    fn key_indexes(&self) -> Option<Vec<usize>> {
+        self.key_columns
+            .iter()
+            .map(|key| self.columns.iter().position(|c| c == key))
+            .collect()
+    }
+
+    fn literal(&self, row: &[Option<String>], i: usize) -> String {
+        sql_literal(row.get(i).and_then(|v| v.as_deref()), self.data_types[i], self.dialect)
+    }
+
+    /// `"key" = value AND ...` matching the row with the key of `row`
+    fn key_condition(&self, row: &[Option<String>], key_indexes: &[usize]) -> String {
+        let conditions: Vec<String> = key_indexes
+            .iter()
+            .map(|&i| format!("{} = {}", self.dialect.quote_identifier(&self.columns[i]), self.literal(row, i)))
+            .collect();
+        conditions.join(" AND ")
+    }
+
+    /// SELECT of the edited columns of the rows that already exist
+    fn before_image_query(&self, rows: &[Vec<Option<String>>]) -> Option<String> {
+        let key_indexes = self.key_indexes()?;
+        let columns: Vec<String> = self.columns.iter().map(|c| self.dialect.quote_identifier(c)).collect();
+        let conditions: Vec<String> = rows
+            .iter()
+            .map(|row| format!("({})", self.key_condition(row, &key_indexes)))
+            .collect();
+        Some(format!(
+            "SELECT {} FROM {} WHERE {}",
+            columns.join(", "),
+            self.dialect.qualified_table_name(self.schema, self.table),
+            conditions.join(" OR ")
+        ))
+    }
+
+    /// Statements putting the rows back as in `before`, the last edited row first
+    ///
+    /// Rows found in `before` get their old values back; the others were
+    /// inserted by the save and are deleted.
+    fn undo_statements(&self, rows: &[Vec<Option<String>>], before: &[Vec<Option<String>>]) -> Vec<String> {
+        let Some(key_indexes) = self.key_indexes() else {
+            return Vec::new();
+        };
+        let table = self.dialect.qualified_table_name(self.schema, self.table);
+        let key = |row: &[Option<String>]| -> Vec<String> { key_indexes.iter().map(|&i| self.literal(row, i)).collect() };
+
+        rows.iter()
+            .rev()
+            .filter_map(|row| {
+                let condition = self.key_condition(row, &key_indexes);
+                match before.iter().find(|old| key(old) == key(row)) {
+                    Some(old) => {
+                        let assignments: Vec<String> = (0..self.columns.len())
+                            .filter(|i| !key_indexes.contains(i))
+                            .map(|i| format!("{} = {}", self.dialect.quote_identifier(&self.columns[i]), self.literal(old, i)))
+                            .collect();
+                        (!assignments.is_empty())
+                            .then(|| format!("UPDATE {} SET {} WHERE {}", table, assignments.join(", "), condition))
+                    }
+                    None => Some(format!("DELETE FROM {} WHERE {}", table, condition)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_next_cursor() {
+        let rows = vec![json!({ "org_id": "1", "id": "9" }), json!({ "org_id": "2", "id": "3" })];
+        let keys = vec!["org_id".to_string(), "id".to_string()];
+
+        assert_eq!(
+            next_cursor(&rows, &keys, &[], 0, 2),
+            Some(PageCursor::After(vec!["2".to_string(), "3".to_string()]))
+        );
+        assert_eq!(next_cursor(&rows, &[], &[], 100, 2), Some(PageCursor::Offset(102)));
+
+        // Masked key values are not the stored ones
+        assert_eq!(next_cursor(&rows, &keys, &["id".to_string()], 4, 2), Some(PageCursor::Offset(6)));
+
+        // A key that is NULL cannot be sought past
+        assert_eq!(next_cursor(&[json!({ "id": null })], &["id".to_string()], &[], 0, 1), None);
+    }
+
+    #[test]
+    fn test_filter_condition() {
+        use crate::database::dialect::{PostgreSQLDialect, SQLiteDialect};
+
+        let filter = |column: &str, path: &[&str], operator, value: &str| ColumnFilter {
+            column: column.to_string(),
+            path: path.iter().map(|p| p.to_string()).collect(),
+            operator,
+            value: value.to_string(),
+        };
+        let pg = PostgreSQLDialect::new();
+
+        assert_eq!(
+            filter_condition(&pg, &filter("name", &[], FilterOperator::Contains, "50%_off")).unwrap(),
+            r#""name" ILIKE '%50\%\_off%' ESCAPE '\'"#
+        );
+        assert_eq!(
+            filter_condition(&pg, &filter("doc", &["status"], FilterOperator::Equals, "open")).unwrap(),
+            "\"doc\"->>'status' = 'open'"
+        );
+        assert_eq!(
+            filter_condition(&pg, &filter("code", &[], FilterOperator::Matches, "^A")).unwrap(),
+            "\"code\" ~ '^A'"
+        );
+        assert!(filter_condition(&SQLiteDialect::new(), &filter("code", &[], FilterOperator::Matches, "^A")).is_err());
+    }
+
+    #[test]
+    fn test_query_page() {
+        use crate::database::capabilities::ServerVersion;
+        use crate::database::dialect::MySQLDialect;
+        use crate::database::DatabaseType;
+
+        let mysql = MySQLDialect::new();
+        let current = DatabaseCapabilities::for_server(DatabaseType::MySQL, ServerVersion::parse("8.0.34"));
+        let old = DatabaseCapabilities::for_server(DatabaseType::MySQL, ServerVersion::parse("5.7.42"));
+
+        assert_eq!(
+            query_page(&mysql, &current, "SELECT * FROM t;", &["id"], 20, 10),
+            "WITH `_page` AS (SELECT `_query`.*, ROW_NUMBER() OVER (ORDER BY `id`) AS `_row_number` \
+             FROM (SELECT * FROM t) `_query`) \
+             SELECT * FROM `_page` WHERE `_row_number` BETWEEN 21 AND 30 ORDER BY `_row_number`"
+        );
+        assert_eq!(
+            query_page(&mysql, &old, "SELECT * FROM t", &["id"], 20, 10),
+            "SELECT * FROM (SELECT * FROM t) `_query` ORDER BY `id` LIMIT 10 OFFSET 20"
+        );
+    }
+
+    #[test]
+    fn test_cursor_json() {
+        let cursor: PageCursor = serde_json::from_str(r#"{"after":["7"]}"#).unwrap();
+        assert_eq!(cursor, PageCursor::After(vec!["7".to_string()]));
+        assert_eq!(split_table_name("public.users"), (Some("public"), "users"));
+    }
+
+    #[test]
+    fn test_undo_statements() {
+        use crate::database::dialect::SQLiteDialect;
+
+        let dialect = SQLiteDialect::new();
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let key_columns = vec!["id".to_string()];
+        let edit = TableEdit {
+            dialect: &dialect,
+            schema: None,
+            table: "users",
+            columns: &columns,
+            data_types: &["INTEGER", "TEXT"],
+            key_columns: &key_columns,
+        };
+        let row = |id: &str, name: &str| vec![Some(id.to_string()), Some(name.to_string())];
+        let rows = vec![row("1", "Ann"), row("7", "Bo")];
+
+        assert_eq!(
+            edit.before_image_query(&rows).unwrap(),
+            r#"SELECT "id", "name" FROM "users" WHERE ("id" = 1) OR ("id" = 7)"#
+        );
+        assert_eq!(
+            edit.undo_statements(&rows, &[row("1", "Anne")]),
+            vec![
+                r#"DELETE FROM "users" WHERE "id" = 7"#.to_string(),
+                r#"UPDATE "users" SET "name" = 'Anne' WHERE "id" = 1"#.to_string(),
+            ]
+        );
+
+        // Without the key the saved rows cannot be found again
+        let names = vec!["name".to_string()];
+        assert!(TableEdit { columns: &names, data_types: &["TEXT"], ..edit }.before_image_query(&rows).is_none());
+    }
+
+    #[test]
+    fn test_remove_masked_columns() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let row = |values: &[&str]| values.iter().map(|v| Some(v.to_string())).collect::<Vec<_>>();
+        let mut columns = names(&["id", "Email", "name"]);
+        let mut rows = vec![row(&["1", "a***@example.com", "Ann"])];
+
+        // The placeholder shown for the masked email is not written back
+        remove_masked_columns(&mut columns, &mut rows, &names(&["email"]), &names(&["id"])).unwrap();
+        assert_eq!(columns, names(&["id", "name"]));
+        assert_eq!(rows, vec![row(&["1", "Ann"])]);
+
+        assert!(remove_masked_columns(&mut columns, &mut rows, &names(&["id"]), &names(&["id"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_undo_refused_when_read_only() {
+        use crate::database::DatabaseType;
+        use crate::profile::ConnectionProfile;
+
+        let mut profile = ConnectionProfile::new("Replica".to_string(), DatabaseType::SQLite, "app.db".to_string());
+        profile.read_only = true;
+        *ACTIVE_PROFILE.lock().await = Some(profile);
+
+        let mut history = vec![DataEdit {
+            connection_id: String::new(),
+            table_name: "users".to_string(),
+            statements: vec![r#"DELETE FROM "users" WHERE "id" = 7"#.to_string()],
+        }];
+        let refused = pop_edit(&mut history).await.err();
+        *ACTIVE_PROFILE.lock().await = None;
+
+        assert!(refused.is_some_and(|e| e.contains("read-only")));
+        // The edit can still be undone once the connection is writable
+        assert_eq!(history.len(), 1);
+        assert_eq!(pop_edit(&mut history).await.map(|edit| edit.table_name), Ok("users".to_string()));
+    }
+}
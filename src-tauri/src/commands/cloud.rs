@@ -0,0 +1,57 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use crate::cloud::sheets;
+
+/// Event emitted when a Google authorization started with
+/// `start_google_authorization` succeeds or fails
+pub const GOOGLE_AUTHORIZATION_EVENT: &str = "google-authorization";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoogleAuthorization {
+    pub authorized: bool,
+    pub error: Option<String>,
+}
+
+/// Start authorizing Google Sheets exports with the OAuth client (desktop app)
+/// of the user's Google Cloud project
+///
+/// Returns the URL to open in the browser. Once the user signs in the refresh
+/// token is saved to the keyring, and the outcome is emitted as
+/// `GOOGLE_AUTHORIZATION_EVENT`.
+#[tauri::command]
+pub async fn start_google_authorization(
+    client_id: String,
+    client_secret: String,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let (url, pending) = sheets::authorize(client_id, client_secret).await.map_err(String::from)?;
+
+    tauri::async_runtime::spawn(async move {
+        let outcome = pending.complete().await;
+        if let Err(e) = &outcome {
+            crate::log_warn!("cloud", "Google authorization failed: {}", e);
+        } else {
+            crate::log_info!("cloud", "Authorized Google Sheets exports");
+        }
+        let _ = app_handle.emit(GOOGLE_AUTHORIZATION_EVENT, GoogleAuthorization {
+            authorized: outcome.is_ok(),
+            error: outcome.err().map(|e| e.to_string()),
+        });
+    });
+
+    Ok(url)
+}
+
+/// Whether a Google account is authorized for Sheets exports
+#[tauri::command]
+pub async fn get_google_authorization() -> Result<bool, String> {
+    sheets::is_authorized().map_err(String::from)
+}
+
+/// Forget the Google account authorized for Sheets exports
+#[tauri::command]
+pub async fn revoke_google_authorization() -> Result<(), String> {
+    sheets::revoke().map_err(String::from)?;
+    crate::log_info!("cloud", "Removed the Google Sheets authorization");
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::codegen::snippet::{self, CodeTarget};
+use crate::codegen::{self, rust, typescript, CodegenSummary};
+use crate::commands::{ACTIVE_PROFILE, ADAPTER_STATE};
+use crate::database::adapter::{ColumnInfo, DatabaseType};
+
+/// Columns of each of `tables` on the active connection, with its database type
+async fn table_columns(tables: &[String]) -> Result<(DatabaseType, Vec<(String, Vec<ColumnInfo>)>), String> {
+    if tables.is_empty() {
+        return Err("Select at least one table".to_string());
+    }
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let mut columns = Vec::with_capacity(tables.len());
+    for table in tables {
+        let table_columns = adapter.get_table_columns(table)
+            .await
+            .map_err(|e| format!("Failed to read columns of {}: {}", table, e))?;
+        columns.push((table.clone(), table_columns));
+    }
+    Ok((adapter.database_type(), columns))
+}
+
+/// Write a TypeScript module with the row interface of each table, and with
+/// `zod` a schema validating the rows, plus an `index.ts` re-exporting them
+#[tauri::command]
+pub async fn generate_typescript_types(
+    tables: Vec<String>,
+    directory: String,
+    zod: Option<bool>,
+) -> Result<CodegenSummary, String> {
+    let (database_type, columns) = table_columns(&tables).await?;
+
+    let files = typescript::table_modules(database_type, &columns, zod.unwrap_or(false));
+    let summary = codegen::write_files(Path::new(&directory), &files)
+        .map_err(|e| format!("Failed to write TypeScript types: {}", e))?;
+
+    crate::log_info!("codegen", "Wrote TypeScript types for {} tables to {}", tables.len(), directory);
+    Ok(summary)
+}
+
+/// Write a Rust module with a sqlx `FromRow` struct for each table, plus a
+/// `mod.rs` declaring them
+#[tauri::command]
+pub async fn generate_rust_models(tables: Vec<String>, directory: String) -> Result<CodegenSummary, String> {
+    let (database_type, columns) = table_columns(&tables).await?;
+
+    let files = rust::table_modules(database_type, &columns);
+    let summary = codegen::write_files(Path::new(&directory), &files)
+        .map_err(|e| format!("Failed to write Rust models: {}", e))?;
+
+    crate::log_info!("codegen", "Wrote Rust models for {} tables to {}", tables.len(), directory);
+    Ok(summary)
+}
+
+/// `query` with its `{{name}}` parameters as code for `target`, using the
+/// connection details of the active profile; the password is read from an
+/// environment variable
+#[tauri::command]
+pub async fn copy_query_as_code(
+    query: String,
+    params: Option<HashMap<String, String>>,
+    target: CodeTarget,
+) -> Result<String, String> {
+    let active_profile = ACTIVE_PROFILE.lock().await;
+    let profile = active_profile.as_ref().ok_or("No active connection")?;
+
+    snippet::query_code(target, profile, &query, &params.unwrap_or_default()).map_err(String::from)
+}
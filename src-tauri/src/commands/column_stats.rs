@@ -0,0 +1,81 @@
+use tauri::AppHandle;
+use crate::commands::{require_active_connection, run_query, ADAPTER_STATE};
+use crate::database::column_stats::{column_profiles, value_counts, ColumnStatsQueries, TableProfile};
+use super::browse::split_table_name;
+
+/// Rows read from the start of a table to profile it
+const SAMPLE_ROWS: usize = 100_000;
+
+/// Most frequent values listed per column when the request sets no number
+const DEFAULT_TOP_VALUES: usize = 5;
+
+fn is_masked(result: &serde_json::Value, column: &str) -> bool {
+    result["masked_columns"]
+        .as_array()
+        .is_some_and(|masked| masked.iter().any(|name| name.as_str() == Some(column)))
+}
+
+/// Null share, distinct count, range, average length and most frequent values
+/// of every column of a table, for the data quality overview
+///
+/// `connection_id` is the profile of the connection, which must be the active
+/// one; the active connection is used when it is not given. Tables with more
+/// rows than the sample holds are profiled from their first rows. Values of
+/// masked columns come back masked.
+#[tauri::command]
+pub async fn profile_table(
+    connection_id: Option<String>,
+    table: String,
+    top_values: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<TableProfile, String> {
+    require_active_connection(connection_id).await?;
+    let top_values = top_values.unwrap_or(DEFAULT_TOP_VALUES).max(1);
+    let (schema, name) = split_table_name(&table);
+
+    let (columns, stats_query, column_queries) = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        let dialect = adapter.get_dialect();
+
+        let columns = adapter
+            .get_table_columns(name)
+            .await
+            .map_err(|e| format!("Failed to get columns: {}", e))?;
+        if columns.is_empty() {
+            return Err(format!("Table '{}' not found", table));
+        }
+
+        let queries = ColumnStatsQueries::new(dialect.as_ref(), schema, name, SAMPLE_ROWS);
+        let column_queries: Vec<(Option<String>, String)> = columns
+            .iter()
+            .map(|column| (queries.min_max(column), queries.top_values(column, top_values)))
+            .collect();
+        let stats_query = queries.stats(&columns);
+        (columns, stats_query, column_queries)
+    };
+
+    let stats = run_query(&stats_query, false, true, &app_handle).await?;
+    let (rows_profiled, mut profiles) = column_profiles(&columns, &stats["rows"][0]);
+
+    for (profile, (min_max, top)) in profiles.iter_mut().zip(column_queries) {
+        if let Some(query) = min_max {
+            let range = run_query(&query, false, true, &app_handle).await?;
+            let value = |i: usize| range["rows"][i][&profile.name].as_str().map(str::to_string);
+            profile.min = value(0);
+            profile.max = value(1);
+            profile.masked |= is_masked(&range, &profile.name);
+        }
+
+        let top = run_query(&top, false, true, &app_handle).await?;
+        profile.top_values = value_counts(&profile.name, top["rows"].as_array().map(Vec::as_slice).unwrap_or_default());
+        profile.masked |= is_masked(&top, &profile.name);
+    }
+
+    Ok(TableProfile {
+        table,
+        rows_profiled,
+        sampled: rows_profiled >= SAMPLE_ROWS as u64,
+        columns: profiles,
+    })
+}
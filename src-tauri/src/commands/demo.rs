@@ -0,0 +1,50 @@
+use tauri::{AppHandle, State};
+use crate::database::demo::{create_demo_database, DEMO_DATABASE_FILE, DEMO_PROFILE_NAME};
+use crate::database::DatabaseType;
+use crate::profile::{ConnectionProfile, ProfileManager};
+use super::app_data_dir;
+use super::profile::{connect_with_profile, ProfileManagerState};
+
+/// Connect to a sample shop database with customers, products and orders
+///
+/// The database is created and seeded on first use, or again when `reset` is
+/// set, and gets a profile of its own so it shows up with the other connections.
+#[tauri::command]
+pub async fn start_demo(
+    reset: Option<bool>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<ConnectionProfile, String> {
+    let path = app_data_dir(&app_handle)?.join("demo").join(DEMO_DATABASE_FILE);
+    if reset.unwrap_or(false) || !path.exists() {
+        create_demo_database(&path).await.map_err(|e| e.to_string())?;
+        crate::log_info!("demo", "Created the demo database at {}", path.display());
+    }
+    let database = path.to_string_lossy().into_owned();
+
+    let profile = {
+        let mut manager_guard = state.0.lock().await;
+
+        if manager_guard.is_none() {
+            *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+        }
+
+        let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+        let existing = manager.list_profiles()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|p| p.database_type == DatabaseType::SQLite && p.database == database);
+        match existing {
+            Some(profile) => profile,
+            None => manager
+                .create_profile(ConnectionProfile::new(DEMO_PROFILE_NAME.to_string(), DatabaseType::SQLite, database), None)
+                .await
+                .map_err(|e| e.to_string())?,
+        }
+    };
+
+    connect_with_profile(profile.id.clone(), state, app_handle).await?;
+    Ok(profile)
+}
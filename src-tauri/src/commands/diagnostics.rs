@@ -0,0 +1,24 @@
+use crate::diagnostics::DiagnosticsBundle;
+use crate::settings;
+
+/// Write a diagnostics bundle to attach to a bug report, returning its path
+///
+/// The bundle is a JSON file in `~/.dataforge/diagnostics` with the app version, OS,
+/// the last 200 log lines and recent crash reports (credentials redacted), and the
+/// current metrics.
+#[tauri::command]
+pub async fn create_diagnostics_bundle() -> Result<String, String> {
+    let data_dir = settings::data_dir().map_err(|e| e.to_string())?;
+    let metrics = super::metrics::snapshot().await;
+
+    let path = tokio::task::spawn_blocking(move || {
+        DiagnosticsBundle::collect(&data_dir.join("crashes"), Some(metrics))
+            .write(&data_dir.join("diagnostics"))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    crate::log_info!("diagnostics", "Wrote diagnostics bundle to {}", path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,218 @@
+use std::fs::File;
+use std::io::BufWriter;
+use tauri::{AppHandle, Emitter};
+use crate::commands::{ACTIVE_PROFILE, ADAPTER_STATE};
+use crate::database::adapter::QueryResult;
+use crate::database::statement::{column_aliases, is_read_only, referenced_tables};
+use crate::export::{ExportProgress, ExportSink, ExportSummary};
+use crate::export::clipboard::{self, TextFormat, TextFormatOptions};
+use crate::export::csv::{CsvOptions, CsvSink};
+use crate::export::ddl::{self, DdlSummary};
+use crate::export::graphql;
+use crate::export::insert::{InsertOptions, InsertSink};
+use crate::export::json::{JsonFormat, JsonSink};
+use crate::export::masking::{self, rules_for, Masker, MaskingRule, MaskingRuleStore};
+
+/// Event emitted while an export is running
+pub const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+fn create_export_file(path: &str) -> Result<BufWriter<File>, String> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|e| format!("Failed to create export file: {}", e))
+}
+
+/// Stream a query through an export sink, emitting progress events along the way
+///
+/// Only queries that read data can be exported, so an export never changes the
+/// database behind the checks and audit log of `execute_query`. The masking rules
+/// registered for the tables the query reads and those passed by the caller are
+/// applied to each row before it reaches the sink.
+async fn run_export(
+    query: &str,
+    path: String,
+    sink: Box<dyn ExportSink>,
+    masking: Option<Vec<MaskingRule>>,
+    app_handle: AppHandle,
+) -> Result<ExportSummary, String> {
+    let registered = MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())?;
+    let profile_id = ACTIVE_PROFILE.lock().await.as_ref().map(|p| p.id.clone());
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    let database_type = adapter.database_type();
+    if !is_read_only(query, &database_type) {
+        // The sink has already created the file; leave nothing behind
+        drop(sink);
+        let _ = std::fs::remove_file(&path);
+        return Err("Only queries that read data can be exported".to_string());
+    }
+
+    let tables = referenced_tables(query, &database_type);
+    let mut rules = rules_for(&registered, profile_id.as_deref(), tables.as_deref());
+    rules.extend(masking.unwrap_or_default());
+    let masker = Masker::new(rules).with_aliases(&column_aliases(query, &database_type));
+    let mut sink = masking::with_masking(sink, masker);
+
+    let progress_handle = app_handle.clone();
+    let progress_path = path.clone();
+    sink.set_progress(Box::new(move |rows_written| {
+        let _ = progress_handle.emit(EXPORT_PROGRESS_EVENT, ExportProgress {
+            path: progress_path.clone(),
+            rows_written,
+            finished: false,
+        });
+    }));
+
+    crate::log_info!("export", "Exporting query result to {}", path);
+    let start = std::time::Instant::now();
+
+    let rows_written = adapter.stream_query(query, sink.as_mut())
+        .await
+        .map_err(|e| format!("Export failed: {}", e))?;
+
+    sink.finish()
+        .map_err(|e| format!("Failed to write export file: {}", e))?;
+
+    let execution_time = start.elapsed().as_millis() as u64;
+    let bytes_written = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    let _ = app_handle.emit(EXPORT_PROGRESS_EVENT, ExportProgress {
+        path: path.clone(),
+        rows_written,
+        finished: true,
+    });
+
+    crate::log_info!("export", "Exported {} rows ({} bytes) to {}", rows_written, bytes_written, path);
+
+    Ok(ExportSummary {
+        path,
+        rows_written,
+        bytes_written,
+        execution_time,
+    })
+}
+
+/// Stream the result of a query straight to a CSV file
+#[tauri::command]
+pub async fn export_result_csv(
+    query: String,
+    path: String,
+    options: Option<CsvOptions>,
+    masking: Option<Vec<MaskingRule>>,
+    app_handle: AppHandle,
+) -> Result<ExportSummary, String> {
+    let writer = create_export_file(&path)?;
+    let sink = CsvSink::new(writer, options.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    run_export(&query, path, Box::new(sink), masking, app_handle).await
+}
+
+/// Stream the result of a query to a JSON or NDJSON file, preserving value types
+#[tauri::command]
+pub async fn export_result_json(
+    query: String,
+    path: String,
+    format: Option<JsonFormat>,
+    masking: Option<Vec<MaskingRule>>,
+    app_handle: AppHandle,
+) -> Result<ExportSummary, String> {
+    let writer = create_export_file(&path)?;
+    let sink = JsonSink::new(writer, format.unwrap_or_default());
+
+    run_export(&query, path, Box::new(sink), masking, app_handle).await
+}
+
+/// Export rows as INSERT statements for the connected database's dialect
+///
+/// When no query is given, the whole table named in the options is exported.
+#[tauri::command]
+pub async fn export_result_inserts(
+    query: Option<String>,
+    path: String,
+    options: InsertOptions,
+    masking: Option<Vec<MaskingRule>>,
+    app_handle: AppHandle,
+) -> Result<ExportSummary, String> {
+    let dialect = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        adapter_state.as_ref().ok_or("No active connection")?.get_dialect()
+    };
+
+    let query = query.unwrap_or_else(|| {
+        format!(
+            "SELECT * FROM {}",
+            dialect.qualified_table_name(options.schema.as_deref(), &options.table_name)
+        )
+    });
+
+    let sink = InsertSink::new(create_export_file(&path)?, dialect, options)
+        .map_err(|e| e.to_string())?;
+
+    run_export(&query, path, Box::new(sink), masking, app_handle).await
+}
+
+/// Format an already-fetched result as Markdown, TSV, or HTML text for the clipboard
+///
+/// The query behind the result is unknown, so the registered masking rules of
+/// every table apply, along with those passed by the caller.
+#[tauri::command]
+pub async fn format_result_text(
+    mut result: QueryResult,
+    format: TextFormat,
+    options: Option<TextFormatOptions>,
+    masking: Option<Vec<MaskingRule>>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let registered = MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())?;
+    let profile_id = ACTIVE_PROFILE.lock().await.as_ref().map(|p| p.id.clone());
+
+    let mut rules = rules_for(&registered, profile_id.as_deref(), None);
+    rules.extend(masking.unwrap_or_default());
+    Masker::new(rules).mask_result(&mut result);
+
+    clipboard::format_result(&result, format, &options.unwrap_or_default())
+        .map_err(|e| format!("Failed to format result: {}", e))
+}
+
+/// Write a dependency-ordered DDL script of the whole connected database to a file
+#[tauri::command]
+pub async fn export_schema_ddl(path: String) -> Result<DdlSummary, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let database_name = adapter.current_database()
+        .await
+        .map_err(|e| format!("Failed to read database name: {}", e))?;
+    let objects = ddl::schema_objects(adapter.as_ref())
+        .await
+        .map_err(|e| format!("Failed to read schema: {}", e))?;
+
+    let script = ddl::render_script(adapter.database_type(), &database_name, &objects);
+    std::fs::write(&path, &script)
+        .map_err(|e| format!("Failed to write DDL script: {}", e))?;
+
+    crate::log_info!("export", "Exported DDL for {} objects to {}", objects.len(), path);
+
+    Ok(DdlSummary::new(path, script.len() as u64, &objects))
+}
+
+/// GraphQL SDL describing the tables of the connected database and the
+/// relations between them
+#[tauri::command]
+pub async fn generate_graphql_schema(refresh: Option<bool>) -> Result<String, String> {
+    let schema = crate::commands::get_schema(refresh).await?;
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    let foreign_keys = adapter.foreign_keys()
+        .await
+        .map_err(|e| format!("Failed to read foreign keys: {}", e))?;
+
+    Ok(graphql::graphql_schema(adapter.database_type(), &schema, &foreign_keys))
+}
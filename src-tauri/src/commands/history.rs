@@ -0,0 +1,137 @@
+use tauri::{AppHandle, State};
+use crate::database::templates::{expand, SnippetExpansion};
+use crate::profile::history::QueryHistoryEntry;
+use crate::profile::snippets::Snippet;
+use crate::profile::ProfileManager;
+use super::app_data_dir;
+use super::profile::ProfileManagerState;
+
+/// Number of history entries returned when no limit is given
+const DEFAULT_HISTORY_LIMIT: usize = 200;
+
+/// Add a script run from the editor to the query history
+///
+/// A failure, e.g. because the profiles are locked, is logged but does not fail
+/// the query, which has already run.
+pub async fn record_history(state: &ProfileManagerState, entry: QueryHistoryEntry) {
+    if let Some(manager) = state.0.lock().await.as_ref() {
+        if let Err(e) = manager.record_query(entry) {
+            crate::log_warn!("history", "Failed to record query history: {}", e);
+        }
+    }
+}
+
+/// Query history, newest first, optionally only that of one profile
+#[tauri::command]
+pub async fn get_query_history(
+    profile_id: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<QueryHistoryEntry>, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.query_history(profile_id.as_deref(), limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .map_err(|e| e.to_string())
+}
+
+/// Delete the query history, or only that of one profile
+#[tauri::command]
+pub async fn clear_query_history(
+    profile_id: Option<String>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.clear_query_history(profile_id.as_deref()).map_err(|e| e.to_string())
+}
+
+/// List the saved snippets
+#[tauri::command]
+pub async fn list_snippets(
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<Snippet>, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.list_snippets().map_err(|e| e.to_string())
+}
+
+/// Add or update a snippet; snippets without an ID get a new one
+#[tauri::command]
+pub async fn save_snippet(
+    snippet: Snippet,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Snippet, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.save_snippet(snippet).map_err(|e| e.to_string())
+}
+
+/// Snippet body with tab stops for its `{{name}}` placeholders
+#[tauri::command]
+pub async fn expand_snippet(
+    id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<SnippetExpansion, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    let snippet = manager
+        .list_snippets()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("Snippet {} not found", id))?;
+    Ok(expand(&snippet.body, &[]))
+}
+
+/// Delete a snippet
+#[tauri::command]
+pub async fn delete_snippet(
+    id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.delete_snippet(&id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,346 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+use crate::cloud::s3;
+use crate::database::adapter::{DatabaseAdapter, QueryResult};
+use crate::database::sql_utils::split_sql_statements;
+use crate::database::statement::{classify_statement, is_read_only};
+use crate::error::AppError;
+use crate::export::masking::{query_masker, MaskingRuleStore};
+use crate::jobs::runs::{JobResult, JobRun, RunStore, MAX_RESULT_ROWS};
+use crate::jobs::{JobStore, QueryJob, JOBS_DIR};
+use crate::notifications::{Notification, NotificationSource};
+use crate::profile::rules::StatementRuleStore;
+use crate::profile::{ConnectionProfile, ProfileManager};
+use super::app_data_dir;
+use super::audit;
+use super::notifications::notify;
+use super::profile::{open_profile_adapter, ProfileManagerState};
+
+/// Event emitted when a job run fails
+pub const JOB_FAILED_EVENT: &str = "job-failed";
+
+/// How often the scheduler checks for due jobs
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Number of runs returned when no limit is given
+const DEFAULT_RUN_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobFailure {
+    pub job_id: String,
+    pub job_name: String,
+    pub run_id: String,
+    pub error: String,
+}
+
+/// What the statements of a run returned
+#[derive(Default)]
+struct JobOutput {
+    last_result: Option<QueryResult>,
+    rows_affected: u64,
+}
+
+/// Name the credentials of a job's destination are saved under
+fn secret_owner(job_id: &str) -> String {
+    format!("job_{}", job_id)
+}
+
+fn jobs_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    Ok(app_data_dir(app_handle)?.join(JOBS_DIR))
+}
+
+async fn job_profile(
+    profile_id: &str,
+    state: &ProfileManagerState,
+    app_handle: &AppHandle,
+) -> Result<ConnectionProfile, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+    manager.get_profile(profile_id).await.map_err(|e| e.to_string())
+}
+
+async fn run_statements(
+    adapter: &(dyn DatabaseAdapter + Send + Sync),
+    statements: &[String],
+    profile: &ConnectionProfile,
+    app_handle: &AppHandle,
+) -> Result<JobOutput, AppError> {
+    let mut output = JobOutput::default();
+    for statement in statements {
+        let start = Instant::now();
+        if is_read_only(statement, &profile.database_type) {
+            output.last_result = Some(adapter.execute_query(statement).await?);
+            continue;
+        }
+
+        let outcome = adapter.execute_command(statement).await;
+        let duration_ms = start.elapsed().as_millis() as u64;
+        match &outcome {
+            Ok(affected) => audit::record_statement(app_handle, Some(profile), profile.database_type, statement, Ok(Some(*affected)), duration_ms),
+            Err(e) => audit::record_statement(app_handle, Some(profile), profile.database_type, statement, Err(&e.to_string()), duration_ms),
+        }
+        output.rows_affected += outcome?;
+    }
+    Ok(output)
+}
+
+/// Outcome of the statements of a successful run
+struct JobOutcome {
+    rows_returned: Option<u64>,
+    rows_affected: u64,
+    result: Option<JobResult>,
+    exported_to: Option<String>,
+}
+
+/// Run the statements of a job on a connection of its own
+///
+/// The connection's read-only mode, audit policy and statement rules apply as
+/// in the editor; statements that would need confirmation there are refused
+/// unless the job allows destructive statements.
+async fn execute_job_statements(
+    job: &QueryJob,
+    state: &ProfileManagerState,
+    app_handle: &AppHandle,
+) -> Result<JobOutcome, String> {
+    let profile = job_profile(&job.profile_id, state, app_handle).await?;
+    let db_type = profile.database_type;
+    let statements: Vec<String> = split_sql_statements(&job.sql, &db_type)?
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let statement_rules = StatementRuleStore::new(&app_data_dir(app_handle)?)
+        .load()
+        .map_err(|e| e.to_string())?;
+    for statement in &statements {
+        let kind = classify_statement(statement, &db_type);
+        statement_rules.check(statement, kind, Some(&profile), db_type).map_err(String::from)?;
+        if profile.read_only && !is_read_only(statement, &db_type) {
+            return Err(format!("Connection '{}' is read-only: {}", profile.name, statement));
+        }
+        profile.audit_policy.check(statement, kind, job.allow_destructive).map_err(String::from)?;
+        if let Some(reason) = kind.destructive_reason().filter(|_| !job.allow_destructive) {
+            return Err(format!("Job is not allowed to run destructive statements: {} -- {}", statement, reason));
+        }
+    }
+
+    let mut adapter = open_profile_adapter(&job.profile_id, state, app_handle).await?;
+    let output = run_statements(adapter.as_ref(), &statements, &profile, app_handle).await;
+    let _ = adapter.disconnect().await;
+    let output = output.map_err(|e| e.to_string())?;
+
+    let mut outcome = JobOutcome {
+        rows_returned: output.last_result.as_ref().map(|r| r.rows.len() as u64),
+        rows_affected: output.rows_affected,
+        result: None,
+        exported_to: None,
+    };
+    let Some(mut result) = output.last_result.filter(|_| job.keep_results || job.destination.is_some()) else {
+        return Ok(outcome);
+    };
+
+    let masking_rules = MaskingRuleStore::new(app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())?;
+    let last_statement = statements.last().map(String::as_str).unwrap_or_default();
+    query_masker(&masking_rules, Some(&profile.id), last_statement, &db_type).mask_result(&mut result);
+
+    if let Some(destination) = &job.destination {
+        let location = destination
+            .upload(&secret_owner(&job.id), &job.name, &result, Utc::now())
+            .await
+            .map_err(|e| format!("Failed to upload the result: {}", e))?;
+        crate::log_info!("jobs", "Uploaded result of job {} to {}", job.name, location);
+        outcome.exported_to = Some(location);
+    }
+    if job.keep_results {
+        outcome.result = Some(JobResult {
+            truncated: result.rows.len() > MAX_RESULT_ROWS,
+            columns: result.columns,
+            rows: result.rows.into_iter().take(MAX_RESULT_ROWS).map(|row| row.values).collect(),
+        });
+    }
+    Ok(outcome)
+}
+
+/// Run a job and record the run in its history
+///
+/// Scheduled runs also update the job's last run, which the schedule is
+/// counted from. A failure is emitted as `JOB_FAILED_EVENT`, and the outcome
+/// is sent as a notification.
+async fn run_job(
+    mut job: QueryJob,
+    scheduled: bool,
+    state: &ProfileManagerState,
+    app_handle: &AppHandle,
+) -> Result<JobRun, AppError> {
+    let started_at = Utc::now();
+    let start = Instant::now();
+    crate::log_info!("jobs", "Running job {}", job.name);
+    let outcome = execute_job_statements(&job, state, app_handle).await;
+
+    let mut run = JobRun {
+        id: Uuid::new_v4().to_string(),
+        job_id: job.id.clone(),
+        started_at,
+        duration_ms: start.elapsed().as_millis() as u64,
+        scheduled,
+        rows_returned: None,
+        rows_affected: 0,
+        error: None,
+        has_result: false,
+        exported_to: None,
+    };
+    let result = match outcome {
+        Ok(outcome) => {
+            run.rows_returned = outcome.rows_returned;
+            run.rows_affected = outcome.rows_affected;
+            run.exported_to = outcome.exported_to;
+            outcome.result
+        }
+        Err(error) => {
+            crate::log_error!("jobs", "Job {} failed: {}", job.name, error);
+            let _ = app_handle.emit(JOB_FAILED_EVENT, JobFailure {
+                job_id: job.id.clone(),
+                job_name: job.name.clone(),
+                run_id: run.id.clone(),
+                error: error.clone(),
+            });
+            run.error = Some(error);
+            None
+        }
+    };
+
+    let outcome = match &run.error {
+        None => Ok(run.rows_returned.or(Some(run.rows_affected))),
+        Some(error) => Err(error.clone()),
+    };
+    notify(app_handle, Notification::new(NotificationSource::Job, job.name.as_str(), start.elapsed(), outcome));
+
+    let jobs_dir = jobs_dir(app_handle)?;
+    let runs = RunStore::new(&jobs_dir)?;
+    let run = runs.record(run, result.as_ref())?;
+    let removed = runs.apply_retention(&job.id, &job.retention, Utc::now())?;
+    if removed > 0 {
+        crate::log_debug!("jobs", "Removed {} old runs of job {}", removed, job.name);
+    }
+
+    if scheduled {
+        job.last_run = Some(started_at);
+        job.last_error = run.error.clone();
+        JobStore::new(&jobs_dir)?.save(job)?;
+    }
+    Ok(run)
+}
+
+/// List all query jobs
+#[tauri::command]
+pub async fn list_jobs(app_handle: AppHandle) -> Result<Vec<QueryJob>, String> {
+    JobStore::new(&jobs_dir(&app_handle)?)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())
+}
+
+/// Create or update a query job; jobs without an ID get a new one
+///
+/// The secret access key of an S3 destination is saved to the keyring when given.
+#[tauri::command]
+pub async fn save_job(
+    mut job: QueryJob,
+    s3_secret_access_key: Option<String>,
+    app_handle: AppHandle,
+) -> Result<QueryJob, String> {
+    if job.id.is_empty() {
+        job.id = Uuid::new_v4().to_string();
+    }
+
+    let job = JobStore::new(&jobs_dir(&app_handle)?)
+        .and_then(|store| store.save(job))
+        .map_err(|e| e.to_string())?;
+    if let Some(secret) = s3_secret_access_key.filter(|s| !s.is_empty()) {
+        s3::save_secret(&secret_owner(&job.id), &secret).map_err(|e| e.to_string())?;
+    }
+    Ok(job)
+}
+
+/// Delete a query job with its run history
+#[tauri::command]
+pub async fn delete_job(id: String, app_handle: AppHandle) -> Result<(), String> {
+    let jobs_dir = jobs_dir(&app_handle)?;
+    JobStore::new(&jobs_dir)
+        .and_then(|store| store.delete(&id))
+        .and_then(|_| RunStore::new(&jobs_dir))
+        .and_then(|runs| runs.delete_job(&id))
+        .and_then(|_| s3::delete_secret(&secret_owner(&id)))
+        .map_err(|e| e.to_string())
+}
+
+/// Run a job now, outside of its schedule
+#[tauri::command]
+pub async fn run_job_now(
+    id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<JobRun, String> {
+    let job = JobStore::new(&jobs_dir(&app_handle)?)
+        .and_then(|store| store.get(&id))
+        .map_err(|e| e.to_string())?;
+
+    run_job(job, false, &state, &app_handle).await.map_err(String::from)
+}
+
+/// Runs of one job, or of all jobs, newest first
+#[tauri::command]
+pub async fn list_job_runs(
+    job_id: Option<String>,
+    limit: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<JobRun>, String> {
+    RunStore::new(&jobs_dir(&app_handle)?)
+        .and_then(|runs| runs.list(job_id.as_deref(), limit.unwrap_or(DEFAULT_RUN_LIMIT)))
+        .map_err(|e| e.to_string())
+}
+
+/// Result kept for a run of a job with `keep_results`
+#[tauri::command]
+pub async fn get_job_run_result(run_id: String, app_handle: AppHandle) -> Result<JobResult, String> {
+    RunStore::new(&jobs_dir(&app_handle)?)
+        .and_then(|runs| runs.result(&run_id))
+        .map_err(|e| e.to_string())
+}
+
+/// Run every job that is due
+async fn run_due_jobs(app_handle: &AppHandle) -> Result<(), AppError> {
+    let now = Utc::now();
+    let jobs = JobStore::new(&jobs_dir(app_handle)?)?.list()?;
+
+    for job in jobs.into_iter().filter(|j| j.is_due(now)) {
+        let state = app_handle.state::<ProfileManagerState>();
+        run_job(job, true, &state, app_handle).await?;
+    }
+
+    Ok(())
+}
+
+/// Start the background task that runs scheduled jobs while the app is open
+pub fn start_job_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_jobs(&app_handle).await {
+                crate::log_error!("jobs", "Job scheduler failed: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,65 @@
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use crate::commands::ADAPTER_STATE;
+
+/// Event carrying each notification received by `listen_channels`
+pub const NOTIFICATION_EVENT: &str = "db-notification";
+
+/// Stops the running `listen_channels` task
+static SUBSCRIPTION: Lazy<Mutex<Option<CancellationToken>>> = Lazy::new(|| Mutex::new(None));
+
+/// LISTEN on `channels` and emit every notification as a `db-notification`
+/// event, replacing any earlier subscription
+///
+/// The subscription ends with `unlisten_channels` or once the connection fails.
+#[tauri::command]
+pub async fn listen_channels(channels: Vec<String>, app_handle: AppHandle) -> Result<(), String> {
+    if channels.iter().all(|channel| channel.trim().is_empty()) {
+        return Err("At least one channel is required".to_string());
+    }
+
+    let mut notifications = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        adapter.listen(&channels).await.map_err(|e| e.to_string())?
+    };
+
+    let token = CancellationToken::new();
+    if let Some(previous) = SUBSCRIPTION.lock().await.replace(token.clone()) {
+        previous.cancel();
+    }
+    crate::log_info!("listen", "Listening on {}", channels.join(", "));
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let notification = tokio::select! {
+                _ = token.cancelled() => break,
+                notification = notifications.next() => notification,
+            };
+            match notification {
+                Some(Ok(notification)) => {
+                    let _ = app_handle.emit(NOTIFICATION_EVENT, &notification);
+                }
+                Some(Err(e)) => {
+                    crate::log_warn!("listen", "Stopped listening: {}", e);
+                    break;
+                }
+                None => break,
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Stop the subscription started by `listen_channels`
+#[tauri::command]
+pub async fn unlisten_channels() -> Result<(), String> {
+    if let Some(token) = SUBSCRIPTION.lock().await.take() {
+        token.cancel();
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use crate::error::AppError;
+use crate::logger::{self, LogEntry, LogQuery};
+use crate::settings::SettingsStore;
+
+/// Number of entries returned when no limit is given
+const DEFAULT_LOG_LIMIT: usize = 500;
+
+/// Recent application log entries, newest first
+///
+/// `filter` matches the message or module, `level` is the least severe level to
+/// include ("error", "warn", "info", "debug"), and `since` skips older entries.
+#[tauri::command]
+pub async fn get_logs(
+    filter: Option<String>,
+    level: Option<String>,
+    since: Option<DateTime<Utc>>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let level = level
+        .map(|level| {
+            level.parse().map_err(|_| AppError::Validation(format!("Unknown log level: {}", level)))
+        })
+        .transpose()?;
+
+    let Some(path) = logger::log_file_path() else {
+        return Ok(Vec::new());
+    };
+
+    let query = LogQuery {
+        filter,
+        level,
+        since,
+        limit: limit.unwrap_or(DEFAULT_LOG_LIMIT),
+    };
+    tokio::task::spawn_blocking(move || logger::read_logs(path, &query))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| AppError::Io(e).into())
+}
+
+/// Filter directives currently applied to the log, e.g. `info,sqlx=warn`
+#[tauri::command]
+pub async fn get_log_filter() -> Result<Option<String>, String> {
+    Ok(logger::current_filter())
+}
+
+/// Change which modules log at which level, e.g. `postgres_adapter=debug,commands=info`
+///
+/// Takes effect immediately and is saved in the settings for the next start. An
+/// empty filter goes back to the default level.
+#[tauri::command]
+pub async fn set_log_filter(filter: Option<String>) -> Result<Option<String>, String> {
+    let filter = filter.map(|f| f.trim().to_string()).filter(|f| !f.is_empty());
+    logger::set_filter(filter.as_deref())?;
+
+    let store = SettingsStore::open_default()?;
+    let mut settings = store.load()?;
+    settings.log_filter = filter;
+    store.save(&settings)?;
+
+    crate::log_info!("settings", "Log filter changed to {}", logger::current_filter().unwrap_or_default());
+    Ok(logger::current_filter())
+}
@@ -0,0 +1,127 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use crate::commands::{ensure_writable, run_query, ADAPTER_STATE};
+use crate::database::maintenance::{self, maintenance_statement, IndexReport, MaintenanceAction, TableHealthReport};
+use crate::database::storage::{storage_tree, StorageNode};
+
+/// Event emitted as each table of a maintenance run starts and finishes
+pub const MAINTENANCE_PROGRESS_EVENT: &str = "maintenance-progress";
+
+/// Progress update of a maintenance run
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceProgress {
+    pub table: String,
+    /// Position of the table in the run, from 1
+    pub index: usize,
+    pub total: usize,
+    pub finished: bool,
+}
+
+/// Outcome of a maintenance action on one table
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceResult {
+    pub table: String,
+    pub statement: String,
+    /// Size in bytes of the table with its indexes, where the database reports it
+    pub size_before: Option<i64>,
+    pub size_after: Option<i64>,
+    pub execution_time: u64,
+    pub error: Option<String>,
+}
+
+async fn table_size(table: &str) -> Option<i64> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    adapter_state.as_ref()?.table_size(table).await.ok().flatten()
+}
+
+/// Run a maintenance action on each table in turn
+///
+/// The statements go through the same checks as queries from the editor, and
+/// a table that fails does not stop the others.
+#[tauri::command]
+pub async fn run_maintenance(
+    tables: Vec<String>,
+    action: MaintenanceAction,
+    force: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<Vec<MaintenanceResult>, String> {
+    ensure_writable().await?;
+
+    let statements = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        let dialect = adapter.get_dialect();
+        tables
+            .iter()
+            .map(|table| maintenance_statement(action, dialect.as_ref(), table))
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut results = Vec::with_capacity(tables.len());
+    for (index, (table, statement)) in tables.iter().zip(statements).enumerate() {
+        let progress = |finished| MaintenanceProgress {
+            table: table.clone(),
+            index: index + 1,
+            total: tables.len(),
+            finished,
+        };
+        let _ = app_handle.emit(MAINTENANCE_PROGRESS_EVENT, progress(false));
+
+        let size_before = table_size(table).await;
+        let start = std::time::Instant::now();
+        let error = run_query(&statement, force.unwrap_or(false), false, &app_handle).await.err();
+        let execution_time = start.elapsed().as_millis() as u64;
+        let size_after = table_size(table).await;
+
+        match &error {
+            Some(e) => crate::log_warn!("maintenance", "{} failed: {}", statement, e),
+            None => crate::log_info!("maintenance", "{} took {} ms", statement, execution_time),
+        }
+        let _ = app_handle.emit(MAINTENANCE_PROGRESS_EVENT, progress(true));
+
+        results.push(MaintenanceResult {
+            table: table.clone(),
+            statement,
+            size_before,
+            size_after,
+            execution_time,
+            error,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Report the indexes of the current database that are scanned at most
+/// `max_scans` times (default 0) or duplicated by another index
+#[tauri::command]
+pub async fn index_report(max_scans: Option<i64>) -> Result<IndexReport, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let indexes = adapter.index_usage().await.map_err(|e| e.to_string())?;
+    Ok(maintenance::index_report(&indexes, max_scans.unwrap_or(0)))
+}
+
+/// Vacuum state and estimated bloat of each table, those needing attention first
+#[tauri::command]
+pub async fn table_health_report() -> Result<Vec<TableHealthReport>, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let tables = adapter.table_health().await.map_err(|e| e.to_string())?;
+    Ok(maintenance::table_health_report(tables))
+}
+
+/// Disk usage of the current database as a tree of schemas, tables, and their
+/// indexes and TOAST tables, for a treemap
+#[tauri::command]
+pub async fn storage_usage() -> Result<StorageNode, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let database = adapter.current_database().await.map_err(|e| e.to_string())?;
+    let relations = adapter.relation_sizes().await.map_err(|e| e.to_string())?;
+    Ok(storage_tree(&database, relations))
+}
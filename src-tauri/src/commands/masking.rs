@@ -0,0 +1,63 @@
+use tauri::AppHandle;
+use crate::commands::{audit, run_query, ACTIVE_PROFILE, ADAPTER_STATE};
+use crate::database::statement::is_read_only;
+use crate::export::masking::{MaskingRuleStore, RegisteredMaskingRule};
+
+/// List the registered masking rules
+#[tauri::command]
+pub async fn list_masking_rules(app_handle: AppHandle) -> Result<Vec<RegisteredMaskingRule>, String> {
+    MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())
+}
+
+/// Add or update a masking rule; rules without an ID get a new one
+#[tauri::command]
+pub async fn save_masking_rule(
+    rule: RegisteredMaskingRule,
+    app_handle: AppHandle,
+) -> Result<RegisteredMaskingRule, String> {
+    MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.save(rule))
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a masking rule
+#[tauri::command]
+pub async fn delete_masking_rule(id: String, app_handle: AppHandle) -> Result<(), String> {
+    MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.delete(&id))
+        .map_err(|e| e.to_string())
+}
+
+/// Run a read-only query again without masking its results
+///
+/// Unmasking is recorded in the audit log, since it reveals the data the rules hide.
+#[tauri::command]
+pub async fn unmask_query(query: String, app_handle: AppHandle) -> Result<serde_json::Value, String> {
+    let db_type = ADAPTER_STATE
+        .lock()
+        .await
+        .as_ref()
+        .map(|adapter| adapter.database_type())
+        .ok_or("No active connection")?;
+
+    if !is_read_only(&query, &db_type) {
+        return Err("Only read-only queries can be unmasked".to_string());
+    }
+
+    let start = std::time::Instant::now();
+    let result = run_query(&query, false, false, &app_handle).await;
+
+    let profile = ACTIVE_PROFILE.lock().await.clone();
+    audit::record_statement(
+        &app_handle,
+        profile.as_ref(),
+        db_type,
+        &format!("-- unmasked result\n{}", query.trim()),
+        result.as_ref().map(|_| None).map_err(String::as_str),
+        start.elapsed().as_millis() as u64,
+    );
+
+    result
+}
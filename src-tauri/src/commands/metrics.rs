@@ -0,0 +1,36 @@
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use crate::metrics::{MetricsSnapshot, QueryMetrics};
+use crate::profile::ConnectionProfile;
+use super::{ADAPTER_STATE, CONNECTION_ID};
+
+/// Statement counters of every connection made since the app started
+static QUERY_METRICS: Lazy<QueryMetrics> = Lazy::new(QueryMetrics::new);
+
+/// Count an executed statement towards the metrics of its connection
+pub fn record_statement(connection_id: &str, profile: Option<&ConnectionProfile>, duration_ms: u64, success: bool) {
+    QUERY_METRICS.record(connection_id, profile.map(|p| p.id.as_str()), duration_ms, success);
+    crate::telemetry::record_statement(connection_id, duration_ms, success);
+}
+
+/// Pool statistics of the active connection and query counts per connection
+pub async fn snapshot() -> MetricsSnapshot {
+    let pool = ADAPTER_STATE
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|adapter| adapter.pool_stats());
+
+    MetricsSnapshot {
+        collected_at: Utc::now(),
+        active_connection_id: CONNECTION_ID.lock().await.clone(),
+        pool,
+        connections: QUERY_METRICS.snapshot(),
+    }
+}
+
+/// Pool statistics of the active connection and query counts per connection
+#[tauri::command]
+pub async fn get_metrics() -> Result<MetricsSnapshot, String> {
+    Ok(snapshot().await)
+}
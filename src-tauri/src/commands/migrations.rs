@@ -0,0 +1,107 @@
+use std::path::Path;
+use tauri::AppHandle;
+use crate::commands::{check_generated_statements, ensure_writable, record_generated_statements, ADAPTER_STATE};
+use crate::database::adapter::DatabaseType;
+use crate::migrations::{load_migrations, migration_status, MigrationRunner, MigrationStatus, MigrationStep};
+
+/// Show which migrations in a directory are applied, pending, or modified
+#[tauri::command]
+pub async fn get_migration_status(directory: String) -> Result<Vec<MigrationStatus>, String> {
+    let migrations = load_migrations(Path::new(&directory)).map_err(|e| e.to_string())?;
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let applied = MigrationRunner::new(adapter.as_ref())
+        .applied()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(migration_status(&migrations, &applied))
+}
+
+/// Run migration steps in order and return their versions
+///
+/// The statements of all steps go through the same checks as queries from the
+/// editor before any of them runs, and each is recorded in the audit log.
+async fn run_steps(
+    runner: &MigrationRunner<'_>,
+    steps: &[MigrationStep],
+    force: bool,
+    db_type: DatabaseType,
+    app_handle: &AppHandle,
+) -> Result<Vec<u64>, String> {
+    let statements: Vec<String> = steps.iter().flat_map(|step| step.statements.iter().cloned()).collect();
+    check_generated_statements(&statements, force, db_type, app_handle).await?;
+
+    let mut versions = Vec::with_capacity(steps.len());
+    for step in steps {
+        let start = std::time::Instant::now();
+        let outcome = runner.run(step).await.map_err(|e| e.to_string());
+        record_generated_statements(
+            &step.statements,
+            db_type,
+            outcome.as_ref().map(|_| ()).map_err(String::as_str),
+            start.elapsed().as_millis() as u64,
+            app_handle,
+        )
+        .await;
+        outcome?;
+        versions.push(step.version);
+    }
+    Ok(versions)
+}
+
+/// Apply pending migrations, optionally stopping at a target version
+///
+/// Destructive statements in the migrations must be confirmed with `force`.
+#[tauri::command]
+pub async fn apply_migrations(
+    directory: String,
+    target_version: Option<u64>,
+    force: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<Vec<u64>, String> {
+    ensure_writable().await?;
+    let migrations = load_migrations(Path::new(&directory)).map_err(|e| e.to_string())?;
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let runner = MigrationRunner::new(adapter.as_ref());
+    let steps = runner
+        .pending_steps(&migrations, target_version)
+        .await
+        .map_err(|e| e.to_string())?;
+    let applied = run_steps(&runner, &steps, force.unwrap_or(false), adapter.database_type(), &app_handle).await?;
+
+    crate::log_info!("migrations", "Applied {} migration(s) from {}", applied.len(), directory);
+    Ok(applied)
+}
+
+/// Roll back the most recent migrations (one by default)
+///
+/// Destructive statements in the down scripts must be confirmed with `force`.
+#[tauri::command]
+pub async fn rollback_migrations(
+    directory: String,
+    steps: Option<usize>,
+    force: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<Vec<u64>, String> {
+    ensure_writable().await?;
+    let migrations = load_migrations(Path::new(&directory)).map_err(|e| e.to_string())?;
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    let runner = MigrationRunner::new(adapter.as_ref());
+    let rollback = runner
+        .rollback_steps(&migrations, steps.unwrap_or(1))
+        .await
+        .map_err(|e| e.to_string())?;
+    let rolled_back = run_steps(&runner, &rollback, force.unwrap_or(false), adapter.database_type(), &app_handle).await?;
+
+    crate::log_info!("migrations", "Rolled back {} migration(s) from {}", rolled_back.len(), directory);
+    Ok(rolled_back)
+}
@@ -0,0 +1,160 @@
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use crate::database::adapter::ColumnInfo;
+use crate::notebook::{Cell, CellKind, CellResult, Notebook, NotebookFormat};
+use super::run_query;
+
+/// Held while a notebook file is read and written back, so a cell finishing
+/// while the notebook is saved does not undo the edit
+static NOTEBOOK_FILES: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Results of the statements of a cell, from what `run_query` returns
+fn cell_results(value: &serde_json::Value) -> Vec<CellResult> {
+    let statement_result = |result: &serde_json::Value| {
+        let columns: Vec<ColumnInfo> = serde_json::from_value(result["columns"].clone()).unwrap_or_default();
+        let rows = result["rows"]
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| columns.iter().map(|c| row[&c.name].as_str().map(str::to_string)).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        CellResult::new(
+            result["statement"].as_str().map(str::to_string),
+            columns,
+            rows,
+            result["rows_affected"].as_u64(),
+        )
+    };
+
+    match value["results"].as_array() {
+        Some(results) => results.iter().map(statement_result).collect(),
+        None => vec![statement_result(value)],
+    }
+}
+
+/// Message of an error from `run_query`, which returns an `ErrorResponse` as JSON
+fn error_message(error: String) -> String {
+    serde_json::from_str::<serde_json::Value>(&error)
+        .ok()
+        .and_then(|response| response["message"].as_str().map(str::to_string))
+        .unwrap_or(error)
+}
+
+/// Run a SQL cell on the active connection and store its output in the notebook
+///
+/// The notebook is read again before the output is stored, so edits saved
+/// while the cell ran are kept.
+async fn run_cell(
+    path: &Path,
+    cell_id: &str,
+    source: &str,
+    allow_destructive: bool,
+    app_handle: &AppHandle,
+) -> Result<Cell, String> {
+    let start = Instant::now();
+    let outcome = run_query(source, allow_destructive, true, app_handle)
+        .await
+        .map(|value| cell_results(&value))
+        .map_err(error_message);
+
+    let _files = NOTEBOOK_FILES.lock().await;
+    let mut notebook = Notebook::load(path).map_err(String::from)?;
+    let cell = notebook
+        .record_run(cell_id, outcome, start.elapsed())
+        .map_err(String::from)?
+        .clone();
+    notebook.save(path).map_err(String::from)?;
+    Ok(cell)
+}
+
+/// Create an empty notebook file
+#[tauri::command]
+pub async fn create_notebook(path: String, title: String) -> Result<Notebook, String> {
+    let path = Path::new(&path);
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    let mut notebook = Notebook::new(title);
+    notebook.save(path).map_err(String::from)?;
+    Ok(notebook)
+}
+
+#[tauri::command]
+pub async fn open_notebook(path: String) -> Result<Notebook, String> {
+    Notebook::load(Path::new(&path)).map_err(String::from)
+}
+
+/// Save the edited title and cells of a notebook; new cells get an ID and
+/// existing cells keep their output
+#[tauri::command]
+pub async fn save_notebook(path: String, title: String, cells: Vec<Cell>) -> Result<Notebook, String> {
+    let path = Path::new(&path);
+    let _files = NOTEBOOK_FILES.lock().await;
+
+    let mut notebook = Notebook::load(path).map_err(String::from)?;
+    notebook.edit(title, cells);
+    notebook.save(path).map_err(String::from)?;
+    Ok(notebook)
+}
+
+/// Run one SQL cell of a saved notebook
+///
+/// Statements of the cell need `allow_destructive` to destroy data.
+#[tauri::command]
+pub async fn run_notebook_cell(
+    path: String,
+    cell_id: String,
+    allow_destructive: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<Cell, String> {
+    let path = Path::new(&path);
+    let cell = Notebook::load(path).map_err(String::from)?.cell(&cell_id).map_err(String::from)?.clone();
+    if cell.kind != CellKind::Sql {
+        return Err("Only SQL cells can be run".to_string());
+    }
+
+    run_cell(path, &cell.id, &cell.source, allow_destructive.unwrap_or(false), &app_handle).await
+}
+
+/// Run the SQL cells of a saved notebook from top to bottom, stopping at the
+/// first cell that fails
+#[tauri::command]
+pub async fn run_notebook(
+    path: String,
+    allow_destructive: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<Notebook, String> {
+    let path = Path::new(&path);
+    let notebook = Notebook::load(path).map_err(String::from)?;
+
+    for cell in notebook.cells.iter().filter(|cell| cell.kind == CellKind::Sql) {
+        if cell.source.trim().is_empty() {
+            continue;
+        }
+        let ran = run_cell(path, &cell.id, &cell.source, allow_destructive.unwrap_or(false), &app_handle).await?;
+        if ran.output.is_some_and(|output| output.error.is_some()) {
+            crate::log_info!("notebook", "Stopped running {} at a failed cell", path.display());
+            break;
+        }
+    }
+
+    Notebook::load(path).map_err(String::from)
+}
+
+/// Write a saved notebook, with the output of its cells, as Markdown or HTML
+#[tauri::command]
+pub async fn export_notebook(path: String, format: NotebookFormat, destination: String) -> Result<(), String> {
+    let notebook = Notebook::load(Path::new(&path)).map_err(String::from)?;
+    fs::write(&destination, notebook.export(format))
+        .map_err(|e| format!("Failed to write {}: {}", destination, e))?;
+
+    crate::log_info!("notebook", "Exported notebook {} to {}", path, destination);
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use crate::notifications::{send_webhook, Notification, NotificationSource};
+use crate::settings::{NotificationSettings, SettingsStore};
+
+/// Event emitted for the window to show a desktop notification
+pub const NOTIFICATION_EVENT: &str = "notification";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DesktopNotification {
+    pub title: String,
+    #[serde(flatten)]
+    pub notification: Notification,
+}
+
+/// Send a notification as the settings ask: as a desktop notification and to
+/// the webhook in the background
+///
+/// Failures are logged; they never fail what is being notified about.
+pub(crate) fn notify(app_handle: &AppHandle, notification: Notification) {
+    let settings = match SettingsStore::open_default().and_then(|store| store.load()) {
+        Ok(settings) => settings.notifications,
+        Err(e) => {
+            crate::log_warn!("notifications", "Failed to load notification settings: {}", e);
+            return;
+        }
+    };
+    if !notification.wanted(&settings) {
+        return;
+    }
+
+    if settings.desktop {
+        let _ = app_handle.emit(NOTIFICATION_EVENT, DesktopNotification {
+            title: notification.title(),
+            notification: notification.clone(),
+        });
+    }
+    if settings.webhook_url.is_some() {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = send_webhook(&settings, &notification).await {
+                crate::log_warn!("notifications", "Failed to send webhook notification: {}", e);
+            }
+        });
+    }
+}
+
+/// Settings for notifications about finished queries, jobs and backups
+#[tauri::command]
+pub async fn get_notification_settings() -> Result<NotificationSettings, String> {
+    let store = SettingsStore::open_default().map_err(|e| e.to_string())?;
+    Ok(store.load().map_err(|e| e.to_string())?.notifications)
+}
+
+#[tauri::command]
+pub async fn save_notification_settings(notifications: NotificationSettings) -> Result<(), String> {
+    notifications.validate().map_err(|e| e.to_string())?;
+
+    let store = SettingsStore::open_default().map_err(|e| e.to_string())?;
+    let mut settings = store.load().map_err(|e| e.to_string())?;
+    settings.notifications = notifications;
+    store.save(&settings).map_err(|e| e.to_string())?;
+
+    crate::log_info!("settings", "Saved notification settings");
+    Ok(())
+}
+
+/// Send a sample notification to check the settings; a webhook error is returned
+#[tauri::command]
+pub async fn send_test_notification(
+    notifications: NotificationSettings,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    notifications.validate().map_err(|e| e.to_string())?;
+
+    let notification = Notification::new(NotificationSource::Job, "Test", Duration::from_millis(1200), Ok(Some(42)));
+    if notifications.desktop {
+        let _ = app_handle.emit(NOTIFICATION_EVENT, DesktopNotification {
+            title: notification.title(),
+            notification: notification.clone(),
+        });
+    }
+    send_webhook(&notifications, &notification).await.map_err(String::from)
+}
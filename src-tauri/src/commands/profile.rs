@@ -1,9 +1,29 @@
 use serde::Deserialize;
-use tauri::{State, AppHandle};
+use tauri::{State, AppHandle, Emitter, Manager};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
-use crate::profile::{ConnectionProfile, ProfileManager};
-use crate::database::adapter::DatabaseType;
+use crate::profile::{self, crypto, ConnectionProfile, ProfileManager};
+use crate::profile::master::LockStatus;
+use crate::profile::policy::AuditPolicy;
+use crate::profile::retry::RetryPolicy;
+use crate::profile::rules::{StatementRuleStore, StatementRules};
+use crate::profile::secret_ref::SecretReference;
+use crate::profile::ssh::SshTunnelConfig;
+use crate::profile::usage::ProfileUsage;
+use crate::profile::vault::{self, SecretStorageStatus};
+use crate::commands::app_data_dir;
+use crate::database::adapter::{create_adapter, ConnectionParams, DatabaseAdapter, DatabaseType};
+
+/// Event emitted when the profiles were locked after being idle; the active
+/// connection has been closed by then
+pub const PROFILES_LOCKED_EVENT: &str = "profiles-locked";
+
+/// Number of profiles returned by `recent_profiles` when no limit is given
+const DEFAULT_RECENT_PROFILES: usize = 5;
+
+/// How often the idle lock is checked
+const LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
 
 /// Request structure for creating a profile
 #[derive(Debug, Deserialize)]
@@ -15,7 +35,23 @@ pub struct CreateProfileRequest {
     pub database: String,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Secret manager entry to read the password from instead of storing it
+    #[serde(default)]
+    pub password_ref: Option<SecretReference>,
     pub ssl_mode: Option<String>,
+    #[serde(default)]
+    pub default_schema: Option<String>,
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnelConfig>,
+    /// SSH key passphrase or password, stored in the keyring
+    #[serde(default)]
+    pub ssh_passphrase: Option<String>,
+    #[serde(default)]
+    pub audit_policy: AuditPolicy,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
     pub color: Option<String>,
     pub icon: Option<String>,
 }
@@ -31,7 +67,23 @@ pub struct UpdateProfileRequest {
     pub database: String,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Secret manager entry to read the password from instead of storing it
+    #[serde(default)]
+    pub password_ref: Option<SecretReference>,
     pub ssl_mode: Option<String>,
+    #[serde(default)]
+    pub default_schema: Option<String>,
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnelConfig>,
+    /// SSH key passphrase or password, stored in the keyring
+    #[serde(default)]
+    pub ssh_passphrase: Option<String>,
+    #[serde(default)]
+    pub audit_policy: AuditPolicy,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
     pub color: Option<String>,
     pub icon: Option<String>,
 }
@@ -55,7 +107,7 @@ pub async fn create_profile(
     let mut manager_guard = state.0.lock().await;
 
     if manager_guard.is_none() {
-        *manager_guard = Some(ProfileManager::new(&app_handle).map_err(|e| e.to_string())?);
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
     }
 
     let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
@@ -79,6 +131,14 @@ pub async fn create_profile(
     if let Some(ssl_mode) = request.ssl_mode {
         profile.ssl_mode = Some(ssl_mode);
     }
+    if let Some(default_schema) = request.default_schema {
+        profile.default_schema = Some(default_schema);
+    }
+    profile.password_ref = request.password_ref;
+    profile.ssh_tunnel = request.ssh_tunnel;
+    profile.audit_policy = request.audit_policy;
+    profile.read_only = request.read_only;
+    profile.retry_policy = request.retry_policy;
     if let Some(color) = request.color {
         profile.color = Some(color);
     }
@@ -86,9 +146,16 @@ pub async fn create_profile(
         profile.icon = Some(icon);
     }
 
-    manager.create_profile(profile, request.password)
+    let profile = manager.create_profile(profile, request.password)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    if let Some(passphrase) = request.ssh_passphrase.as_deref() {
+        manager.save_ssh_passphrase(&profile.id, passphrase)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(profile)
 }
 
 /// List all connection profiles
@@ -100,7 +167,7 @@ pub async fn list_profiles(
     let mut manager_guard = state.0.lock().await;
 
     if manager_guard.is_none() {
-        *manager_guard = Some(ProfileManager::new(&app_handle).map_err(|e| e.to_string())?);
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
     }
 
     let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
@@ -120,7 +187,7 @@ pub async fn get_profile(
     let mut manager_guard = state.0.lock().await;
 
     if manager_guard.is_none() {
-        *manager_guard = Some(ProfileManager::new(&app_handle).map_err(|e| e.to_string())?);
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
     }
 
     let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
@@ -140,7 +207,7 @@ pub async fn update_profile(
     let mut manager_guard = state.0.lock().await;
 
     if manager_guard.is_none() {
-        *manager_guard = Some(ProfileManager::new(&app_handle).map_err(|e| e.to_string())?);
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
     }
 
     let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
@@ -157,13 +224,26 @@ pub async fn update_profile(
     profile.host = request.host;
     profile.port = request.port;
     profile.username = request.username;
+    profile.password_ref = request.password_ref;
     profile.ssl_mode = request.ssl_mode;
+    profile.default_schema = request.default_schema;
+    profile.ssh_tunnel = request.ssh_tunnel;
+    profile.audit_policy = request.audit_policy;
+    profile.read_only = request.read_only;
+    profile.retry_policy = request.retry_policy;
     profile.color = request.color;
     profile.icon = request.icon;
 
-    manager.update_profile(profile, request.password)
+    let profile = manager.update_profile(profile, request.password)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    if let (Some(_), Some(passphrase)) = (profile.ssh_tunnel.as_ref(), request.ssh_passphrase.as_deref()) {
+        manager.save_ssh_passphrase(&profile.id, passphrase)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(profile)
 }
 
 /// Delete a profile
@@ -176,7 +256,7 @@ pub async fn delete_profile(
     let mut manager_guard = state.0.lock().await;
 
     if manager_guard.is_none() {
-        *manager_guard = Some(ProfileManager::new(&app_handle).map_err(|e| e.to_string())?);
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
     }
 
     let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
@@ -186,6 +266,367 @@ pub async fn delete_profile(
         .map_err(|e| e.to_string())
 }
 
+/// Pin or unpin a profile so it is listed first
+#[tauri::command]
+pub async fn set_profile_pinned(
+    id: String,
+    pinned: bool,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<ConnectionProfile, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.set_pinned(&id, pinned)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Arrange the connection list manually; returns the profiles in their new order
+#[tauri::command]
+pub async fn reorder_profiles(
+    profile_ids: Vec<String>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<ConnectionProfile>, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.reorder_profiles(&profile_ids)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export profiles to a passphrase-encrypted bundle file
+///
+/// Passwords are only read from the keyring and written to the bundle when
+/// `include_passwords` is set. Returns the number of exported profiles.
+#[tauri::command]
+pub async fn export_profiles(
+    profile_ids: Vec<String>,
+    path: String,
+    passphrase: String,
+    include_passwords: bool,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<usize, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    let bundle = manager.export_bundle(&profile_ids, include_passwords)
+        .await
+        .map_err(|e| e.to_string())?;
+    let sealed = profile::bundle::seal(&bundle, &passphrase).map_err(|e| e.to_string())?;
+
+    std::fs::write(&path, sealed)
+        .map_err(|e| format!("Failed to write profile bundle: {}", e))?;
+
+    crate::log_info!("profile", "Exported {} profiles to {}", bundle.profiles.len(), path);
+    Ok(bundle.profiles.len())
+}
+
+/// Import the profiles of a passphrase-encrypted bundle file
+#[tauri::command]
+pub async fn import_profiles(
+    path: String,
+    passphrase: String,
+    overwrite: Option<bool>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<ConnectionProfile>, String> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read profile bundle: {}", e))?;
+    let bundle = profile::bundle::open(&contents, &passphrase).map_err(|e| e.to_string())?;
+
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    let imported = manager.import_bundle(bundle, overwrite.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::log_info!("profile", "Imported {} profiles from {}", imported.len(), path);
+    Ok(imported)
+}
+
+/// The most recently used profiles, newest first
+#[tauri::command]
+pub async fn recent_profiles(
+    limit: Option<usize>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<ConnectionProfile>, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.recent_profiles(limit.unwrap_or(DEFAULT_RECENT_PROFILES))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Connect count and total connected time of a profile
+#[tauri::command]
+pub async fn get_profile_usage(
+    id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<ProfileUsage, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.profile_usage(&id).map_err(|e| e.to_string())
+}
+
+/// Report whether a master password is set and the profiles are locked
+#[tauri::command]
+pub async fn get_lock_status(
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<LockStatus, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.lock_status().map_err(|e| e.to_string())
+}
+
+/// Unlock the profiles with the master password
+#[tauri::command]
+pub async fn unlock_profiles(
+    password: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.unlock(&password).map_err(|e| e.to_string())?;
+    crypto::record_activity();
+    Ok(())
+}
+
+/// Lock the profiles until the master password is entered again
+///
+/// The active connection is closed, since it was opened with the decrypted profile.
+#[tauri::command]
+pub async fn lock_profiles(
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut manager_guard = state.0.lock().await;
+
+        if manager_guard.is_none() {
+            *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+        }
+
+        let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+        manager.lock().map_err(|e| e.to_string())?;
+    }
+
+    if crypto::password_mode() {
+        crate::commands::close_active_connection(&state).await?;
+    }
+    Ok(())
+}
+
+/// Restart the idle lock timer; the frontend calls this on user input
+#[tauri::command]
+pub fn record_activity() {
+    crypto::record_activity();
+}
+
+/// Protect the profiles with a master password instead of the keyring key
+#[tauri::command]
+pub async fn enable_master_password(
+    password: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.enable_master_password(&password)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove the master password and go back to the keyring key
+#[tauri::command]
+pub async fn disable_master_password(
+    password: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.disable_master_password(&password).map_err(|e| e.to_string())
+}
+
+/// Set how many idle minutes pass before the profiles lock; 0 never locks
+///
+/// Only profiles protected by a master password are locked.
+#[tauri::command]
+pub async fn set_profile_lock_timeout(
+    minutes: u32,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.set_lock_timeout(minutes).map_err(|e| e.to_string())
+}
+
+/// The administrator's statement rules, so the UI can explain what is blocked
+#[tauri::command]
+pub async fn get_statement_rules(app_handle: AppHandle) -> Result<StatementRules, String> {
+    StatementRuleStore::new(&app_data_dir(&app_handle)?)
+        .load()
+        .map_err(|e| e.to_string())
+}
+
+/// Report whether passwords are kept in the OS keyring or the weaker fallback vault
+#[tauri::command]
+pub fn get_secret_storage_status() -> SecretStorageStatus {
+    vault::status()
+}
+
+/// Lock the profiles once they have been idle for the configured time
+async fn lock_if_idle(app_handle: &AppHandle) -> Result<(), String> {
+    if !crypto::password_mode() || !crypto::is_unlocked() {
+        return Ok(());
+    }
+
+    let state = app_handle.state::<ProfileManagerState>();
+    let lock_after_minutes = {
+        let manager_guard = state.0.lock().await;
+        let Some(manager) = manager_guard.as_ref() else {
+            return Ok(());
+        };
+
+        let status = manager.lock_status().map_err(|e| e.to_string())?;
+        let timeout = Duration::from_secs(u64::from(status.lock_after_minutes) * 60);
+        if status.lock_after_minutes == 0 || crypto::idle_time() < timeout {
+            return Ok(());
+        }
+
+        manager.lock().map_err(|e| e.to_string())?;
+        status.lock_after_minutes
+    };
+
+    // Open connections go with the key, so resuming requires the master password
+    if let Err(e) = crate::commands::close_active_connection(&state).await {
+        crate::log_warn!("profile", "Failed to close the connection on lock: {}", e);
+    }
+
+    crate::log_info!("profile", "Locked profiles after {} idle minutes", lock_after_minutes);
+    let _ = app_handle.emit(PROFILES_LOCKED_EVENT, ());
+
+    Ok(())
+}
+
+/// Start the background task that locks idle profiles
+pub fn start_profile_lock_timer(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(LOCK_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = lock_if_idle(&app_handle).await {
+                crate::log_error!("profile", "Idle lock check failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Resolve a profile's connection parameters, including its stored password
+pub(crate) async fn profile_connection_params(
+    profile_id: &str,
+    state: &ProfileManagerState,
+    app_handle: &AppHandle,
+) -> Result<ConnectionParams, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+    manager.get_connection_params(profile_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Open a standalone connection from a profile without touching the active connection
+pub(crate) async fn open_profile_adapter(
+    profile_id: &str,
+    state: &ProfileManagerState,
+    app_handle: &AppHandle,
+) -> Result<Box<dyn DatabaseAdapter + Send + Sync>, String> {
+    let params = profile_connection_params(profile_id, state, app_handle).await?;
+
+    let mut adapter = create_adapter(params.database_type).map_err(|e| e.to_string())?;
+    adapter.connect(&params).await.map_err(|e| e.to_string())?;
+
+    Ok(adapter)
+}
+
 /// Connect to a database using a profile
 #[tauri::command]
 pub async fn connect_with_profile(
@@ -193,14 +634,15 @@ pub async fn connect_with_profile(
     state: State<'_, ProfileManagerState>,
     app_handle: AppHandle,
 ) -> Result<String, String> {
-    use crate::database::adapter::create_adapter;
-    use crate::commands::{ADAPTER_STATE, CONNECTION_CANCEL_TOKEN};
+    use crate::commands::{ACTIVE_PROFILE, ADAPTER_STATE, CONNECTION_CANCEL_TOKEN, CONNECTION_ID};
     use tokio_util::sync::CancellationToken;
+    use tracing::Instrument;
 
+    crypto::record_activity();
     let mut manager_guard = state.0.lock().await;
 
     if manager_guard.is_none() {
-        *manager_guard = Some(ProfileManager::new(&app_handle).map_err(|e| e.to_string())?);
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
     }
 
     let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
@@ -228,8 +670,10 @@ pub async fn connect_with_profile(
     // Create adapter and connect with cancellation support
     let mut adapter = create_adapter(params.database_type).map_err(|e| e.to_string())?;
 
+    let connection_id = uuid::Uuid::new_v4().to_string();
+    let connect_span = tracing::info_span!("connect", connection_id = %connection_id, profile_id = %profile.id);
     let connect_result = tokio::select! {
-        result = adapter.connect(&params) => result,
+        result = adapter.connect(&params).instrument(connect_span) => result,
         _ = cancel_token.cancelled() => {
             // Clear the cancellation token
             let mut token_state = CONNECTION_CANCEL_TOKEN.lock().await;
@@ -244,11 +688,15 @@ pub async fn connect_with_profile(
         *token_state = None;
     }
 
-    connect_result.map_err(|e| e.to_string())?;
+    if let Err(e) = connect_result {
+        return Err(crate::commands::connection_failure(&params, &e).await);
+    }
 
     // Store the adapter in global state
     let mut adapter_state = ADAPTER_STATE.lock().await;
     *adapter_state = Some(adapter);
+    *ACTIVE_PROFILE.lock().await = Some(profile.clone());
+    *CONNECTION_ID.lock().await = Some(connection_id);
 
     // Update last connected timestamp
     profile.update_last_connected();
@@ -256,6 +704,8 @@ pub async fn connect_with_profile(
         .await
         .map_err(|e| e.to_string())?;
 
+    manager.record_connect(&profile.id).map_err(|e| e.to_string())?;
+
     Ok(format!(
         "Connected to {} ({})",
         profile.name,
@@ -277,7 +727,14 @@ mod tests {
             database: "testdb".to_string(),
             username: Some("user".to_string()),
             password: Some("pass".to_string()),
+            password_ref: None,
             ssl_mode: None,
+            default_schema: None,
+            ssh_tunnel: None,
+            ssh_passphrase: None,
+            audit_policy: AuditPolicy::default(),
+            read_only: false,
+            retry_policy: RetryPolicy::default(),
             color: None,
             icon: None,
         };
@@ -0,0 +1,66 @@
+use chrono::Utc;
+use once_cell::sync::OnceCell;
+use tauri::{AppHandle, Emitter};
+use crate::profile::ConnectionProfile;
+use crate::query_log::{QueryLog, QueryLogEntry, QueryLogFilter};
+
+/// Event emitted with each new query log entry, for a live console panel
+pub const QUERY_LOGGED_EVENT: &str = "query-logged";
+
+/// The query log, opened on first use
+static QUERY_LOG: OnceCell<QueryLog> = OnceCell::new();
+
+fn query_log(app_handle: &AppHandle) -> Result<&'static QueryLog, String> {
+    QUERY_LOG.get_or_try_init(|| QueryLog::new(app_handle).map_err(|e| e.to_string()))
+}
+
+/// Outcome of one executed statement
+pub struct StatementOutcome<'a> {
+    pub rows_returned: Option<u64>,
+    pub rows_affected: Option<u64>,
+    pub error: Option<&'a str>,
+}
+
+/// Record an executed statement in the query log
+///
+/// A failure to write the log is logged but does not fail the statement.
+pub fn record_statement(
+    app_handle: &AppHandle,
+    connection_id: &str,
+    profile: Option<&ConnectionProfile>,
+    statement: &str,
+    outcome: StatementOutcome,
+    duration_ms: u64,
+) {
+    let entry = QueryLogEntry {
+        timestamp: Utc::now(),
+        connection_id: connection_id.to_string(),
+        profile_id: profile.map(|p| p.id.clone()),
+        statement: statement.to_string(),
+        duration_ms,
+        rows_returned: outcome.rows_returned,
+        rows_affected: outcome.rows_affected,
+        success: outcome.error.is_none(),
+        error: outcome.error.map(str::to_string),
+    };
+
+    match query_log(app_handle).and_then(|log| log.append(entry.clone()).map_err(|e| e.to_string())) {
+        Ok(()) => {
+            let _ = app_handle.emit(QUERY_LOGGED_EVENT, &entry);
+        }
+        Err(e) => crate::log_error!("query_log", "Failed to write query log: {}", e),
+    }
+}
+
+/// The newest query log entries matching the filter, newest first
+///
+/// Pass the timestamp of the newest entry already shown as `since` to poll for new ones.
+#[tauri::command]
+pub async fn tail_query_log(
+    filter: Option<QueryLogFilter>,
+    app_handle: AppHandle,
+) -> Result<Vec<QueryLogEntry>, String> {
+    query_log(&app_handle)?
+        .tail(&filter.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use crate::commands::{run_query, ADAPTER_STATE, CONNECTION_ID, RESULT_CACHE};
+use crate::database::result_cache::CacheKey;
+use crate::database::statement::is_read_only;
+
+const DEFAULT_TTL_SECONDS: u64 = 60;
+
+/// Run a read-only query, or return its result from the cache when the same
+/// query ran on this connection less than `ttl_seconds` (default 60) ago
+///
+/// `params` are the template values the query was rendered from, if any.
+/// Results are dropped early by `invalidate_result_cache` or when a statement
+/// that is not a plain query runs on the connection.
+pub(crate) async fn run_cached_query(
+    query: &str,
+    params: &HashMap<String, String>,
+    ttl_seconds: Option<u64>,
+    mask_results: bool,
+    app_handle: &AppHandle,
+) -> Result<serde_json::Value, String> {
+    {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        if !is_read_only(query, &adapter.database_type()) {
+            return Err("Only queries that read data can be cached".to_string());
+        }
+    }
+
+    let connection_id = CONNECTION_ID.lock().await.clone().unwrap_or_default();
+    let key = CacheKey::new(&connection_id, query, params, mask_results);
+    if let Some(result) = RESULT_CACHE.lock().await.get(&key, Instant::now()) {
+        crate::log_debug!("result_cache", "Cache hit on connection {}", connection_id);
+        return Ok(result.clone());
+    }
+
+    let result = run_query(query, false, mask_results, app_handle).await?;
+    let ttl = Duration::from_secs(ttl_seconds.unwrap_or(DEFAULT_TTL_SECONDS));
+    RESULT_CACHE.lock().await.insert(key, result.clone(), ttl, Instant::now());
+    Ok(result)
+}
+
+/// `execute_query` for dashboard-style queries that are repeated often: the
+/// result is reused for `ttl_seconds` (default 60)
+///
+/// Only statements that read data are accepted, and the query is not added to
+/// the query history.
+#[tauri::command]
+pub async fn execute_cached_query(
+    query: String,
+    ttl_seconds: Option<u64>,
+    app_handle: AppHandle,
+) -> Result<serde_json::Value, String> {
+    run_cached_query(&query, &HashMap::new(), ttl_seconds, true, &app_handle).await
+}
+
+/// Drop cached results of the active connection, or of every connection when
+/// `all_connections` is set. Returns the number dropped.
+#[tauri::command]
+pub async fn invalidate_result_cache(all_connections: Option<bool>) -> Result<usize, String> {
+    let mut cache = RESULT_CACHE.lock().await;
+    if all_connections.unwrap_or(false) {
+        return Ok(cache.invalidate(None));
+    }
+
+    let connection_id = CONNECTION_ID.lock().await.clone().unwrap_or_default();
+    Ok(cache.invalidate(Some(&connection_id)))
+}
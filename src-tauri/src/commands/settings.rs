@@ -0,0 +1,63 @@
+use crate::i18n::{self, Locale};
+use crate::settings::{SettingsStore, TelemetrySettings};
+
+/// Settings for exporting traces and metrics to an OpenTelemetry collector
+#[tauri::command]
+pub async fn get_telemetry_settings() -> Result<TelemetrySettings, String> {
+    let store = SettingsStore::open_default().map_err(|e| e.to_string())?;
+    Ok(store.load().map_err(|e| e.to_string())?.telemetry)
+}
+
+/// Save the telemetry settings; they take effect the next time the app starts
+#[tauri::command]
+pub async fn save_telemetry_settings(telemetry: TelemetrySettings) -> Result<(), String> {
+    telemetry.validate().map_err(|e| e.to_string())?;
+
+    let store = SettingsStore::open_default().map_err(|e| e.to_string())?;
+    let mut settings = store.load().map_err(|e| e.to_string())?;
+    settings.telemetry = telemetry;
+    store.save(&settings).map_err(|e| e.to_string())?;
+
+    crate::log_info!("settings", "Saved telemetry settings");
+    Ok(())
+}
+
+/// Language of error messages
+#[tauri::command]
+pub async fn get_locale() -> Result<Locale, String> {
+    Ok(i18n::locale())
+}
+
+/// Change the language of error messages; it applies at once and is saved
+#[tauri::command]
+pub async fn set_locale(locale: Locale) -> Result<(), String> {
+    let store = SettingsStore::open_default().map_err(|e| e.to_string())?;
+    let mut settings = store.load().map_err(|e| e.to_string())?;
+    settings.locale = locale;
+    store.save(&settings).map_err(|e| e.to_string())?;
+
+    i18n::set_locale(locale);
+    Ok(())
+}
+
+/// Megabytes of a spooled query result kept in memory before the rest goes to disk
+#[tauri::command]
+pub async fn get_result_memory_limit() -> Result<u64, String> {
+    let store = SettingsStore::open_default().map_err(|e| e.to_string())?;
+    let settings = store.load().map_err(|e| e.to_string())?;
+    Ok(settings.result_memory_limit() as u64 / (1024 * 1024))
+}
+
+/// Change the result memory limit; `None` restores the default of 256 MB
+#[tauri::command]
+pub async fn set_result_memory_limit(megabytes: Option<u64>) -> Result<(), String> {
+    if megabytes == Some(0) {
+        return Err("The result memory limit must be at least 1 MB".to_string());
+    }
+
+    let store = SettingsStore::open_default().map_err(|e| e.to_string())?;
+    let mut settings = store.load().map_err(|e| e.to_string())?;
+    settings.result_memory_limit_mb = megabytes;
+    store.save(&settings).map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::ipc::{Channel, Response};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use crate::commands::{ACTIVE_PROFILE, ADAPTER_STATE};
+use crate::database::adapter::{ColumnInfo, QueryRow, RowSink};
+use crate::database::spill::SpooledRows;
+use crate::database::statement::is_read_only;
+use crate::error::AppError;
+use crate::export::arrow;
+use crate::export::masking::{query_masker, Masker, MaskingRuleStore};
+use crate::settings::SettingsStore;
+
+const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// A result of `spool_query` still open
+struct OpenResult {
+    columns: Vec<ColumnInfo>,
+    rows: SpooledRows,
+}
+
+/// Results of `spool_query` still open, by id
+static SPOOLED_RESULTS: Lazy<Mutex<HashMap<String, OpenResult>>> = Lazy::new(Default::default);
+
+/// Part of a query result sent to the frontend as the rows arrive
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultBatch {
+    /// Result columns; only in the first batch
+    pub columns: Vec<ColumnInfo>,
+    /// Columns whose values are masked; only in the first batch
+    pub masked_columns: Vec<String>,
+    /// Values of each row, in column order
+    pub rows: Vec<Vec<Option<String>>>,
+    /// Index of the first row of the batch in the result
+    pub offset: u64,
+    /// Set on the last batch, which may have no rows
+    pub done: bool,
+}
+
+/// Collects streamed rows into batches of `batch_size` and hands each to `send`
+pub struct BatchSink<F> {
+    batch_size: usize,
+    send: F,
+    masker: Masker,
+    columns: Option<Vec<ColumnInfo>>,
+    rows: Vec<Vec<Option<String>>>,
+    sent: u64,
+}
+
+impl<F: FnMut(ResultBatch) -> Result<(), AppError> + Send> BatchSink<F> {
+    pub fn new(batch_size: usize, masker: Masker, send: F) -> Self {
+        Self {
+            batch_size: batch_size.max(1),
+            send,
+            masker,
+            columns: None,
+            rows: Vec::new(),
+            sent: 0,
+        }
+    }
+
+    /// Send the remaining rows as the last batch
+    pub fn finish(mut self) -> Result<(), AppError> {
+        self.flush(true)
+    }
+
+    fn flush(&mut self, done: bool) -> Result<(), AppError> {
+        let columns = self.columns.take().unwrap_or_default();
+        let rows = std::mem::take(&mut self.rows);
+        let offset = self.sent;
+        self.sent += rows.len() as u64;
+
+        (self.send)(ResultBatch {
+            masked_columns: self.masker.masked_columns(&columns),
+            columns,
+            rows,
+            offset,
+            done,
+        })
+    }
+}
+
+impl<F: FnMut(ResultBatch) -> Result<(), AppError> + Send> RowSink for BatchSink<F> {
+    fn columns(&mut self, columns: &[ColumnInfo]) -> Result<(), AppError> {
+        self.columns = Some(columns.to_vec());
+        Ok(())
+    }
+
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError> {
+        let mut row = row.clone();
+        self.masker.mask_row(&mut row);
+        self.rows.push(row.values);
+
+        if self.rows.len() >= self.batch_size {
+            self.flush(false)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run a query and send its rows over `on_batch` in batches of `batch_size`
+/// (default 500) as they arrive, instead of returning the whole result at once
+///
+/// Only statements that read data are accepted. Registered masking rules apply
+/// as they do for `execute_query`. Returns the number of rows sent.
+#[tauri::command]
+pub async fn stream_query_batches(
+    query: String,
+    batch_size: Option<usize>,
+    on_batch: Channel<ResultBatch>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    let masking_rules = MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())?;
+    let profile_id = ACTIVE_PROFILE.lock().await.as_ref().map(|p| p.id.clone());
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    let database_type = adapter.database_type();
+    if !is_read_only(&query, &database_type) {
+        return Err("Only queries that read data can be streamed".to_string());
+    }
+
+    let masker = query_masker(&masking_rules, profile_id.as_deref(), &query, &database_type);
+    let mut sink = BatchSink::new(batch_size.unwrap_or(DEFAULT_BATCH_SIZE), masker, |batch| {
+        on_batch.send(batch).map_err(|e| AppError::Unknown(e.to_string()))
+    });
+
+    let rows = adapter.stream_query(&query, &mut sink).await.map_err(String::from)?;
+    sink.finish().map_err(String::from)?;
+    Ok(rows)
+}
+
+/// A query result held by the backend, to be read a page at a time with `fetch_spooled_rows`
+#[derive(Debug, Clone, Serialize)]
+pub struct SpooledResult {
+    pub id: String,
+    pub columns: Vec<ColumnInfo>,
+    pub masked_columns: Vec<String>,
+    pub row_count: u64,
+    /// Whether the result outgrew the memory limit and part of it is on disk
+    pub spilled: bool,
+}
+
+/// Masks streamed rows and keeps them in a `SpooledRows`
+struct SpoolSink {
+    masker: Masker,
+    columns: Vec<ColumnInfo>,
+    rows: SpooledRows,
+}
+
+impl RowSink for SpoolSink {
+    fn columns(&mut self, columns: &[ColumnInfo]) -> Result<(), AppError> {
+        self.columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError> {
+        let mut row = row.clone();
+        self.masker.mask_row(&mut row);
+        self.rows.push(row.values)
+    }
+}
+
+/// Run a query and keep its whole result in the backend, for browsing results
+/// too large to send to the frontend at once
+///
+/// Rows beyond the result memory limit of the settings are spilled to a scratch
+/// file. Only statements that read data are accepted, and masking rules apply as
+/// they do for `execute_query`. Release the result with `release_spooled_result`.
+#[tauri::command]
+pub async fn spool_query(query: String, app_handle: AppHandle) -> Result<SpooledResult, String> {
+    let memory_limit = SettingsStore::open_default()
+        .and_then(|store| store.load())
+        .map_err(|e| e.to_string())?
+        .result_memory_limit();
+    let masking_rules = MaskingRuleStore::new(&app_handle)
+        .and_then(|store| store.list())
+        .map_err(|e| e.to_string())?;
+    let profile_id = ACTIVE_PROFILE.lock().await.as_ref().map(|p| p.id.clone());
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    let database_type = adapter.database_type();
+    if !is_read_only(&query, &database_type) {
+        return Err("Only queries that read data can be spooled".to_string());
+    }
+
+    let mut sink = SpoolSink {
+        masker: query_masker(&masking_rules, profile_id.as_deref(), &query, &database_type),
+        columns: Vec::new(),
+        rows: SpooledRows::new(memory_limit),
+    };
+    adapter.stream_query(&query, &mut sink).await.map_err(String::from)?;
+    drop(adapter_state);
+
+    let result = SpooledResult {
+        id: uuid::Uuid::new_v4().to_string(),
+        masked_columns: sink.masker.masked_columns(&sink.columns),
+        columns: sink.columns,
+        row_count: sink.rows.row_count(),
+        spilled: sink.rows.spilled(),
+    };
+    if result.spilled {
+        crate::log_info!("stream", "Spilled a result of {} rows to disk", result.row_count);
+    }
+    let open = OpenResult {
+        columns: result.columns.clone(),
+        rows: sink.rows,
+    };
+    SPOOLED_RESULTS.lock().await.insert(result.id.clone(), open);
+    Ok(result)
+}
+
+/// Up to `limit` rows of a spooled result, starting at `offset`
+#[tauri::command]
+pub async fn fetch_spooled_rows(
+    result_id: String,
+    offset: u64,
+    limit: usize,
+) -> Result<Vec<Vec<Option<String>>>, String> {
+    let mut results = SPOOLED_RESULTS.lock().await;
+    let result = results.get_mut(&result_id).ok_or("Result not found")?;
+    result.rows.page(offset, limit).map_err(String::from)
+}
+
+/// Up to `limit` rows of a spooled result as an Arrow IPC stream, sent to the
+/// frontend as raw bytes instead of JSON
+///
+/// Numeric and boolean columns arrive as typed Arrow arrays that charts can use
+/// without parsing every value.
+#[tauri::command]
+pub async fn fetch_spooled_arrow(result_id: String, offset: u64, limit: usize) -> Result<Response, String> {
+    let mut results = SPOOLED_RESULTS.lock().await;
+    let result = results.get_mut(&result_id).ok_or("Result not found")?;
+    let page = result.rows.page(offset, limit).map_err(String::from)?;
+    let bytes = arrow::encode_ipc(&result.columns, &page).map_err(String::from)?;
+    Ok(Response::new(bytes))
+}
+
+/// Drop a spooled result and its scratch file
+#[tauri::command]
+pub async fn release_spooled_result(result_id: String) -> Result<(), String> {
+    SPOOLED_RESULTS.lock().await.remove(&result_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: u32) -> QueryRow {
+        QueryRow {
+            columns: vec!["id".to_string()],
+            values: vec![Some(id.to_string())],
+        }
+    }
+
+    #[test]
+    fn test_batches() {
+        let mut batches = Vec::new();
+        let mut sink = BatchSink::new(2, Masker::new(Vec::new()), |batch| {
+            batches.push(batch);
+            Ok(())
+        });
+        sink.columns(&[ColumnInfo {
+            name: "id".to_string(),
+            data_type: "INTEGER".to_string(),
+            is_nullable: false,
+        }])
+        .unwrap();
+        for id in 1..=5 {
+            sink.row(&row(id)).unwrap();
+        }
+        sink.finish().unwrap();
+
+        let shape: Vec<(usize, usize, u64, bool)> =
+            batches.iter().map(|b| (b.columns.len(), b.rows.len(), b.offset, b.done)).collect();
+        assert_eq!(shape, vec![(1, 2, 0, false), (0, 2, 2, false), (0, 1, 4, true)]);
+        assert_eq!(batches[2].rows[0], vec![Some("5".to_string())]);
+    }
+}
@@ -0,0 +1,165 @@
+use serde::Serialize;
+use tauri::AppHandle;
+use crate::commands::{require_active_connection, run_query, ADAPTER_STATE};
+use crate::database::dialect::summary::{group_alias, value_alias};
+use crate::database::dialect::{Aggregate, SummaryBuilder, SummarySource};
+use crate::database::statement::is_read_only;
+use super::browse::{filter_condition, ColumnFilter};
+
+/// Most groups summarized when the request sets no limit
+const DEFAULT_GROUP_LIMIT: usize = 1000;
+
+/// Category of a group whose column is NULL
+const NULL_CATEGORY: &str = "NULL";
+
+/// Values of one line, bar set or slice of a chart, one per category
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChartSeries {
+    pub name: String,
+    /// `None` where the group has no rows or the aggregate is NULL
+    pub data: Vec<Option<f64>>,
+}
+
+/// Aggregated rows shaped for the chart panel
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    /// Values of the first group column, in order; a single empty category without grouping
+    pub categories: Vec<String>,
+    /// One series per aggregate, and per value of the second group column if there is one
+    pub series: Vec<ChartSeries>,
+    /// Whether groups beyond the limit were left out
+    pub truncated: bool,
+}
+
+fn text(row: &serde_json::Value, column: &str) -> String {
+    row.get(column).and_then(|v| v.as_str()).unwrap_or(NULL_CATEGORY).to_string()
+}
+
+/// Turn the rows of a summary query into chart series
+///
+/// The first group column gives the categories; the second, if any, splits
+/// each aggregate into one series per value, named after that value.
+fn chart_series(rows: &[serde_json::Value], group_columns: usize, aggregates: &[Aggregate]) -> (Vec<String>, Vec<ChartSeries>) {
+    let mut categories: Vec<String> = Vec::new();
+    let mut series: Vec<ChartSeries> = Vec::new();
+
+    for row in rows {
+        let category = if group_columns == 0 { String::new() } else { text(row, &group_alias(0)) };
+        let index = match categories.iter().position(|c| *c == category) {
+            Some(index) => index,
+            None => {
+                categories.push(category);
+                for s in &mut series {
+                    s.data.push(None);
+                }
+                categories.len() - 1
+            }
+        };
+
+        for (i, aggregate) in aggregates.iter().enumerate() {
+            let name = match (group_columns, aggregates.len()) {
+                (2, 1) => text(row, &group_alias(1)),
+                (2, _) => format!("{} · {}", text(row, &group_alias(1)), aggregate.label()),
+                _ => aggregate.label(),
+            };
+            let position = match series.iter().position(|s| s.name == name) {
+                Some(position) => position,
+                None => {
+                    series.push(ChartSeries { name, data: vec![None; categories.len()] });
+                    series.len() - 1
+                }
+            };
+            series[position].data[index] = row.get(value_alias(i)).and_then(|v| v.as_str()).and_then(|v| v.parse().ok());
+        }
+    }
+
+    (categories, series)
+}
+
+/// Aggregate a table or SELECT query by up to two columns for the chart panel
+///
+/// `connection_id` is the profile of the connection to summarize, which must
+/// be the active one; the active connection is used when it is not given.
+/// Filters work as in the table view. Masked columns come back masked, so
+/// their aggregates are not plotted.
+#[tauri::command]
+pub async fn summarize(
+    connection_id: Option<String>,
+    source: SummarySource,
+    group_by: Option<Vec<String>>,
+    aggregates: Vec<Aggregate>,
+    filters: Option<Vec<ColumnFilter>>,
+    limit: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Summary, String> {
+    require_active_connection(connection_id).await?;
+    let group_by = group_by.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_GROUP_LIMIT).max(1);
+
+    let statement = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        let dialect = adapter.get_dialect();
+
+        if let SummarySource::Query { sql } = &source {
+            if !is_read_only(sql, &adapter.database_type()) {
+                return Err("Only SELECT queries can be summarized".to_string());
+            }
+        }
+        let conditions = filters
+            .unwrap_or_default()
+            .iter()
+            .map(|filter| filter_condition(dialect.as_ref(), filter))
+            .collect::<Result<Vec<String>, String>>()?;
+
+        // One group more than shown tells whether any were left out
+        SummaryBuilder::new(dialect.as_ref(), source)
+            .group_by(&group_by)
+            .aggregates(&aggregates)
+            .conditions(conditions)
+            .limit(limit + 1)
+            .build()
+            .map_err(|e| e.to_string())?
+    };
+
+    let result = run_query(&statement, false, true, &app_handle).await?;
+    let mut rows = result["rows"].as_array().cloned().unwrap_or_default();
+    let truncated = rows.len() > limit;
+    rows.truncate(limit);
+
+    let (categories, series) = chart_series(&rows, group_by.len(), &aggregates);
+    Ok(Summary { categories, series, truncated })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::summary::AggregateFunction;
+    use serde_json::json;
+
+    #[test]
+    fn test_chart_series() {
+        let total = Aggregate { function: AggregateFunction::Sum, column: Some("total".to_string()), label: None };
+        let rows = vec![
+            json!({ "_group0": "2025-01", "_group1": "Coffee", "_value0": "120.5" }),
+            json!({ "_group0": "2025-01", "_group1": "Tea", "_value0": "30" }),
+            json!({ "_group0": "2025-02", "_group1": "Tea", "_value0": null }),
+            json!({ "_group0": null, "_group1": "Coffee", "_value0": "7" }),
+        ];
+
+        let (categories, series) = chart_series(&rows, 2, std::slice::from_ref(&total));
+        assert_eq!(categories, vec!["2025-01", "2025-02", "NULL"]);
+        assert_eq!(
+            series,
+            vec![
+                ChartSeries { name: "Coffee".to_string(), data: vec![Some(120.5), None, Some(7.0)] },
+                ChartSeries { name: "Tea".to_string(), data: vec![Some(30.0), None, None] },
+            ]
+        );
+
+        let (categories, series) = chart_series(&[json!({ "_value0": "42" })], 0, &[total]);
+        assert_eq!(categories, vec![""]);
+        assert_eq!(series[0].name, "sum of total");
+        assert_eq!(series[0].data, vec![Some(42.0)]);
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use crate::commands::result_cache::run_cached_query;
+use crate::commands::{app_data_dir, run_query, ADAPTER_STATE};
+use crate::database::templates::{
+    search, DataTypeInfo, QueryTemplate, QueryTemplates, RenderedTemplate, SnippetExpansion, TemplateCategory,
+};
+use crate::profile::templates::TemplateLibrary;
+use crate::profile::ProfileManager;
+use super::profile::ProfileManagerState;
+
+/// Built-in and user templates the connected server can run; user templates
+/// replace built-in ones with the same ID
+async fn available_templates(
+    state: &State<'_, ProfileManagerState>,
+    app_handle: &AppHandle,
+) -> Result<Vec<QueryTemplate>, String> {
+    let (database_type, capabilities) = {
+        let adapter_state = ADAPTER_STATE.lock().await;
+        let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+        (adapter.database_type(), adapter.get_capabilities())
+    };
+
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    let user_templates: Vec<QueryTemplate> = manager
+        .list_templates()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|t| t.supported_databases.contains(&database_type) && t.is_available(&capabilities))
+        .collect();
+    let mut templates: Vec<QueryTemplate> = QueryTemplates::for_database(database_type, &capabilities)
+        .into_iter()
+        .filter(|t| !user_templates.iter().any(|u| u.id == t.id))
+        .collect();
+    templates.extend(user_templates);
+    Ok(templates)
+}
+
+/// Templates for the database of the current connection
+#[tauri::command]
+pub async fn list_templates(
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<QueryTemplate>, String> {
+    available_templates(&state, &app_handle).await
+}
+
+/// Templates of the current database matching `query`, best matches first
+#[tauri::command]
+pub async fn search_templates(
+    query: String,
+    category: Option<TemplateCategory>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<QueryTemplate>, String> {
+    let templates = available_templates(&state, &app_handle).await?;
+    Ok(search(templates, &query, category))
+}
+
+/// Column types of the database of the current connection
+#[tauri::command]
+pub async fn list_data_types() -> Result<Vec<DataTypeInfo>, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    Ok(QueryTemplates::data_types(adapter.database_type()))
+}
+
+/// An available template of the current database
+async fn find_template(
+    template_id: &str,
+    state: &State<'_, ProfileManagerState>,
+    app_handle: &AppHandle,
+) -> Result<QueryTemplate, String> {
+    available_templates(state, app_handle)
+        .await?
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("Template '{}' not found", template_id))
+}
+
+/// Render an available template of the current database with `params`
+async fn render(
+    template_id: &str,
+    params: &HashMap<String, String>,
+    state: &State<'_, ProfileManagerState>,
+    app_handle: &AppHandle,
+) -> Result<RenderedTemplate, String> {
+    let template = find_template(template_id, state, app_handle).await?;
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    Ok(template.render(params, adapter.get_dialect().as_ref()))
+}
+
+/// Template text with tab stops for the editor to insert it as a snippet
+#[tauri::command]
+pub async fn expand_template(
+    template_id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<SnippetExpansion, String> {
+    Ok(find_template(&template_id, &state, &app_handle).await?.expansion())
+}
+
+/// Fill in a template with `params` without running it, so the SQL can be
+/// previewed and edited first
+#[tauri::command]
+pub async fn render_template(
+    template_id: String,
+    params: HashMap<String, String>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<RenderedTemplate, String> {
+    render(&template_id, &params, &state, &app_handle).await
+}
+
+/// Fill in a template with `params` and run it
+///
+/// Nothing is run while a parameter is still unresolved. With `cache_ttl_seconds`
+/// a read-only template's result is cached like `execute_cached_query` does.
+#[tauri::command]
+pub async fn execute_template(
+    template_id: String,
+    params: HashMap<String, String>,
+    cache_ttl_seconds: Option<u64>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<serde_json::Value, String> {
+    let rendered = render(&template_id, &params, &state, &app_handle).await?;
+
+    if !rendered.unresolved.is_empty() {
+        return Err(format!("Missing values for parameters: {}", rendered.unresolved.join(", ")));
+    }
+    if cache_ttl_seconds.is_some() {
+        return run_cached_query(&rendered.sql, &params, cache_ttl_seconds, false, &app_handle).await;
+    }
+    run_query(&rendered.sql, false, false, &app_handle).await
+}
+
+/// Add or update a user template
+#[tauri::command]
+pub async fn save_template(
+    template: QueryTemplate,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<QueryTemplate, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.save_template(template).map_err(|e| e.to_string())
+}
+
+/// Delete a user template
+#[tauri::command]
+pub async fn delete_template(
+    id: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.delete_template(&id).map_err(|e| e.to_string())
+}
+
+/// Write user templates to a JSON library file, all of them when no IDs are
+/// given. Returns the number of exported templates.
+#[tauri::command]
+pub async fn export_templates(
+    template_ids: Option<Vec<String>>,
+    path: String,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<usize, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    let templates: Vec<QueryTemplate> = manager
+        .list_templates()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|t| template_ids.as_ref().is_none_or(|ids| ids.contains(&t.id)))
+        .collect();
+    let count = templates.len();
+
+    let json = TemplateLibrary::new(templates).to_json().map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write template library: {}", e))?;
+
+    crate::log_info!("templates", "Exported {} templates to {}", count, path);
+    Ok(count)
+}
+
+/// Add the templates of a library file to the user templates
+///
+/// Templates whose ID is already taken are skipped unless `overwrite` is set.
+#[tauri::command]
+pub async fn import_templates(
+    path: String,
+    overwrite: Option<bool>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Vec<QueryTemplate>, String> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read template library: {}", e))?;
+    let library = TemplateLibrary::from_json(&contents).map_err(|e| e.to_string())?;
+
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    let imported = manager
+        .import_templates(library, overwrite.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+
+    crate::log_info!("templates", "Imported {} templates from {}", imported.len(), path);
+    Ok(imported)
+}
@@ -0,0 +1,104 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use crate::commands::{ensure_writable, ADAPTER_STATE};
+use crate::commands::profile::{open_profile_adapter, ProfileManagerState};
+use crate::database::adapter::DatabaseAdapter;
+use crate::transfer::{self, CopyOptions, CopySummary};
+
+/// Event emitted while a table copy is running
+pub const COPY_PROGRESS_EVENT: &str = "copy-progress";
+
+/// Progress update emitted after every inserted batch
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyProgress {
+    pub target_table: String,
+    pub rows_copied: u64,
+    pub rows_per_second: f64,
+}
+
+/// Copy a table between two connections
+///
+/// Each side is identified by a profile ID; `None` uses the active connection.
+/// The target table is created from the source columns (with types translated to the
+/// target dialect) unless `create_table` is disabled in the options.
+#[tauri::command]
+pub async fn copy_table(
+    source_connection: Option<String>,
+    source_table: String,
+    target_connection: Option<String>,
+    target_table: String,
+    options: Option<CopyOptions>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<CopySummary, String> {
+    let options = options.unwrap_or_default();
+
+    if target_connection.is_none() {
+        ensure_writable().await?;
+    }
+
+    let source_adapter = match &source_connection {
+        Some(profile_id) => Some(open_profile_adapter(profile_id, &state, &app_handle).await?),
+        None => None,
+    };
+    let target_adapter = match &target_connection {
+        Some(profile_id) => Some(open_profile_adapter(profile_id, &state, &app_handle).await?),
+        None => None,
+    };
+
+    // Only hold the active connection while it is actually part of the copy
+    let active_guard = if source_adapter.is_none() || target_adapter.is_none() {
+        Some(ADAPTER_STATE.lock().await)
+    } else {
+        None
+    };
+    let active: Option<&dyn DatabaseAdapter> = active_guard
+        .as_ref()
+        .and_then(|guard| guard.as_deref())
+        .map(|adapter| adapter as &dyn DatabaseAdapter);
+
+    let source: &dyn DatabaseAdapter = match &source_adapter {
+        Some(adapter) => adapter.as_ref(),
+        None => active.ok_or("No active connection")?,
+    };
+    let target: &dyn DatabaseAdapter = match &target_adapter {
+        Some(adapter) => adapter.as_ref(),
+        None => active.ok_or("No active connection")?,
+    };
+
+    crate::log_info!("transfer", "Copying {} to {}", source_table, target_table);
+
+    let progress_handle = app_handle.clone();
+    let progress_table = target_table.clone();
+    let start = std::time::Instant::now();
+    let on_progress = Box::new(move |rows_copied: u64| {
+        let seconds = start.elapsed().as_secs_f64();
+        let _ = progress_handle.emit(COPY_PROGRESS_EVENT, CopyProgress {
+            target_table: progress_table.clone(),
+            rows_copied,
+            rows_per_second: if seconds > 0.0 { rows_copied as f64 / seconds } else { 0.0 },
+        });
+    });
+
+    let result = transfer::copy_table(source, &source_table, target, &target_table, &options, on_progress)
+        .await
+        .map_err(|e| format!("Copy failed: {}", e));
+
+    drop(active_guard);
+    for adapter in [source_adapter, target_adapter].into_iter().flatten() {
+        let mut adapter = adapter;
+        let _ = adapter.disconnect().await;
+    }
+
+    let summary = result?;
+    crate::log_info!(
+        "transfer",
+        "Copied {} rows to {} in {} ms ({:.0} rows/s)",
+        summary.rows_copied,
+        summary.target_table,
+        summary.execution_time,
+        summary.rows_per_second
+    );
+
+    Ok(summary)
+}
@@ -0,0 +1,64 @@
+use tauri::AppHandle;
+use crate::commands::{check_generated_statements, ensure_writable, record_generated_statements, ADAPTER_STATE};
+use crate::database::dialect::{user_statement, UserChange};
+use crate::error::ErrorResponse;
+
+/// The statement `apply_user_change` will run, with any password masked
+#[tauri::command]
+pub async fn preview_user_change(change: UserChange) -> Result<String, String> {
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+
+    user_statement(&change, adapter.get_dialect().as_ref(), true).map_err(String::from)
+}
+
+/// Create or drop a user, change a password, or grant or revoke privileges
+///
+/// `preview` must be the statement `preview_user_change` returned for the same
+/// change; otherwise nothing runs and a "confirmation required" error carries
+/// the statement to show. The statement rules of the connection apply as in
+/// the editor, and the statement is recorded in the audit log as previewed.
+#[tauri::command]
+pub async fn apply_user_change(
+    change: UserChange,
+    preview: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    ensure_writable().await?;
+
+    let adapter_state = ADAPTER_STATE.lock().await;
+    let adapter = adapter_state.as_ref().ok_or("No active connection")?;
+    let dialect = adapter.get_dialect();
+
+    let shown = user_statement(&change, dialect.as_ref(), true).map_err(String::from)?;
+    if preview.as_deref() != Some(shown.as_str()) {
+        return Err(ErrorResponse::confirmation_required(
+            "preview_required",
+            "Review the statement before it runs".to_string(),
+            &[shown],
+        )
+        .into());
+    }
+
+    // Checked and logged as previewed so passwords stay out of errors and logs;
+    // the preview already confirmed the change
+    let db_type = adapter.database_type();
+    let shown = vec![shown];
+    check_generated_statements(&shown, true, db_type, &app_handle).await?;
+
+    let statement = user_statement(&change, dialect.as_ref(), false).map_err(String::from)?;
+    let start = std::time::Instant::now();
+    let outcome = adapter.execute_command(&statement).await.map_err(String::from);
+    record_generated_statements(
+        &shown,
+        db_type,
+        outcome.as_ref().map(|_| ()).map_err(String::as_str),
+        start.elapsed().as_millis() as u64,
+        &app_handle,
+    )
+    .await;
+    outcome?;
+
+    crate::log_info!("users", "Ran {}", shown[0]);
+    Ok(())
+}
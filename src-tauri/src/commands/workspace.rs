@@ -0,0 +1,112 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, State};
+use crate::profile::workspace::Workspace;
+use crate::profile::ProfileManager;
+use super::app_data_dir;
+use super::profile::{connect_with_profile, ProfileManagerState};
+
+/// A restored workspace, and how reconnecting its active connection went
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceRestore {
+    pub workspace: Workspace,
+    pub connected: bool,
+    pub connection_error: Option<String>,
+}
+
+/// The saved workspace, without restoring anything
+#[tauri::command]
+pub async fn get_workspace(
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Workspace, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.load_workspace().map_err(|e| e.to_string())
+}
+
+/// Save the open connections, editor tabs, result pages and layout, encrypted
+#[tauri::command]
+pub async fn save_workspace(
+    workspace: Workspace,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<Workspace, String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.save_workspace(workspace).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_workspace(
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut manager_guard = state.0.lock().await;
+
+    if manager_guard.is_none() {
+        *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+    }
+
+    let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+    manager.clear_workspace().map_err(|e| e.to_string())
+}
+
+/// Reopen the app where the user left off: the saved workspace, without
+/// profiles deleted since, with its active connection connected again
+///
+/// A failed reconnect is reported rather than failing the restore, so the
+/// tabs still come back.
+#[tauri::command]
+pub async fn restore_workspace(
+    reconnect: Option<bool>,
+    state: State<'_, ProfileManagerState>,
+    app_handle: AppHandle,
+) -> Result<WorkspaceRestore, String> {
+    let mut workspace = {
+        let mut manager_guard = state.0.lock().await;
+
+        if manager_guard.is_none() {
+            *manager_guard = Some(ProfileManager::new(&app_data_dir(&app_handle)?).map_err(|e| e.to_string())?);
+        }
+
+        let manager = manager_guard.as_ref().ok_or("Profile manager not initialized")?;
+
+        let profile_ids: HashSet<String> = manager.list_profiles()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        let mut workspace = manager.load_workspace().map_err(|e| e.to_string())?;
+        workspace.retain_profiles(|id| profile_ids.contains(id));
+        workspace
+    };
+
+    let mut restore = WorkspaceRestore { workspace: workspace.clone(), connected: false, connection_error: None };
+    let Some(profile_id) = workspace.active_profile_id.take().filter(|_| reconnect.unwrap_or(true)) else {
+        return Ok(restore);
+    };
+
+    match connect_with_profile(profile_id, state, app_handle).await {
+        Ok(_) => restore.connected = true,
+        Err(e) => {
+            crate::log_warn!("workspace", "Failed to reconnect the saved workspace: {}", e);
+            restore.connection_error = Some(e);
+        }
+    }
+    Ok(restore)
+}
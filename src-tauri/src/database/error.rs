@@ -1,33 +0,0 @@
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum DatabaseError {
-    #[error("Connection failed: {0}")]
-    ConnectionFailed(String),
-
-    #[error("Query failed: {0}")]
-    QueryFailed(String),
-
-    #[error("Configuration error: {0}")]
-    ConfigError(String),
-
-    #[error("SQLx error: {0}")]
-    Sqlx(#[from] sqlx::Error),
-
-    #[error("Environment variable error: {0}")]
-    Env(#[from] std::env::VarError),
-
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
-
-    #[error("Other error: {0}")]
-    Other(String),
-}
-
-pub type Result<T> = std::result::Result<T, DatabaseError>;
-
-impl From<DatabaseError> for String {
-    fn from(err: DatabaseError) -> Self {
-        err.to_string()
-    }
-}
\ No newline at end of file
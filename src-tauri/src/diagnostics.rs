@@ -0,0 +1,234 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use crate::error::AppError;
+use crate::metrics::MetricsSnapshot;
+
+/// Log lines included in crash reports and diagnostics bundles
+const LOG_LINES: usize = 200;
+
+/// Crash reports included in a diagnostics bundle, newest first
+const BUNDLED_CRASH_REPORTS: usize = 5;
+
+/// The last `count` lines of a file, with credentials redacted
+pub fn tail_lines(path: &Path, count: usize) -> Vec<String> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+
+    let mut lines = VecDeque::with_capacity(count);
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if lines.len() == count {
+            lines.pop_front();
+        }
+        if count > 0 {
+            lines.push_back(line);
+        }
+    }
+    lines.into_iter().map(crate::redact::redacted).collect()
+}
+
+fn recent_log_lines() -> Vec<String> {
+    crate::logger::log_file_path()
+        .map(|path| tail_lines(path, LOG_LINES))
+        .unwrap_or_default()
+}
+
+fn system_summary() -> String {
+    format!(
+        "DataForge {} on {} {} ({})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::FAMILY
+    )
+}
+
+/// Text of a crash report
+fn crash_report(time: DateTime<Utc>, panic: &str, backtrace: &str, log_lines: &[String]) -> String {
+    let mut report = format!(
+        "DataForge crash report\nTime: {}\nVersion: {}\n\nPanic: {}\n\nBacktrace:\n{}\n\nLast {} log lines:\n",
+        time.to_rfc3339(),
+        system_summary(),
+        crate::redact::redacted(panic),
+        backtrace,
+        log_lines.len()
+    );
+    for line in log_lines {
+        report.push_str(line);
+        report.push('\n');
+    }
+    report
+}
+
+fn panic_description(info: &PanicHookInfo) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+
+    match info.location() {
+        Some(location) => format!("{} (thread '{}', at {})", message, thread, location),
+        None => format!("{} (thread '{}')", message, thread),
+    }
+}
+
+/// Write a crash report to `crash_dir` for every panic, then run the default hook
+pub fn install_panic_hook(crash_dir: PathBuf) {
+    let previous = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        let now = Utc::now();
+        let report = crash_report(
+            now,
+            &panic_description(info),
+            &Backtrace::force_capture().to_string(),
+            &recent_log_lines(),
+        );
+        let path = crash_dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S%.3f")));
+
+        match fs::create_dir_all(&crash_dir).and_then(|_| fs::write(&path, report)) {
+            Ok(()) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+
+        previous(info);
+    }));
+}
+
+/// A crash report file included in a bundle
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReportFile {
+    pub file_name: String,
+    pub contents: String,
+}
+
+/// Everything attached to a bug report, as one JSON file the user can review first
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsBundle {
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    /// Last lines of the application log, with credentials redacted
+    pub log_lines: Vec<String>,
+    pub crash_reports: Vec<CrashReportFile>,
+    pub metrics: Option<MetricsSnapshot>,
+}
+
+impl DiagnosticsBundle {
+    /// Collect the recent log lines and the newest crash reports in `crash_dir`
+    pub fn collect(crash_dir: &Path, metrics: Option<MetricsSnapshot>) -> Self {
+        Self {
+            created_at: Utc::now(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            log_lines: recent_log_lines(),
+            crash_reports: newest_crash_reports(crash_dir, BUNDLED_CRASH_REPORTS),
+            metrics,
+        }
+    }
+
+    /// Write the bundle into `dir`, returning the file path
+    pub fn write(&self, dir: &Path) -> Result<PathBuf, AppError> {
+        fs::create_dir_all(dir)
+            .map_err(|e| AppError::Storage(format!("Failed to create diagnostics directory: {}", e)))?;
+
+        let path = dir.join(format!(
+            "dataforge-diagnostics-{}.json",
+            self.created_at.format("%Y%m%d-%H%M%S")
+        ));
+        let data = serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize diagnostics: {}", e)))?;
+        fs::write(&path, data)
+            .map_err(|e| AppError::Storage(format!("Failed to write diagnostics: {}", e)))?;
+        Ok(path)
+    }
+}
+
+fn newest_crash_reports(crash_dir: &Path, count: usize) -> Vec<CrashReportFile> {
+    let Ok(entries) = fs::read_dir(crash_dir) else {
+        return Vec::new();
+    };
+
+    // Report names start with their time, so they sort chronologically
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("crash-") && name.ends_with(".txt"))
+        .collect();
+    names.sort_unstable_by(|a, b| b.cmp(a));
+
+    names
+        .into_iter()
+        .take(count)
+        .filter_map(|file_name| {
+            let contents = fs::read_to_string(crash_dir.join(&file_name)).ok()?;
+            Some(CrashReportFile { file_name, contents })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tail_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("dataforge.jsonl");
+        let lines: Vec<String> = (0..300).map(|i| format!("line {}", i)).collect();
+        fs::write(&path, lines.join("\n") + "\npostgres://app:hunter2@db/app\n").unwrap();
+
+        let tail = tail_lines(&path, LOG_LINES);
+        assert_eq!(tail.len(), LOG_LINES);
+        assert_eq!(tail[0], "line 101");
+        assert_eq!(tail[LOG_LINES - 1], "postgres://app:***@db/app");
+        assert!(tail_lines(&temp_dir.path().join("missing"), 10).is_empty());
+    }
+
+    #[test]
+    fn test_crash_report() {
+        let report = crash_report(
+            Utc::now(),
+            "connect failed: password=hunter2 (thread 'main', at src/lib.rs:1:1)",
+            "0: main",
+            &["last line".to_string()],
+        );
+
+        assert!(report.contains(env!("CARGO_PKG_VERSION")));
+        assert!(report.contains(std::env::consts::OS));
+        assert!(report.contains("0: main"));
+        assert!(report.ends_with("last line\n"));
+        assert!(!report.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_bundle_includes_newest_crash_reports() {
+        let temp_dir = TempDir::new().unwrap();
+        let crash_dir = temp_dir.path().join("crashes");
+        fs::create_dir_all(&crash_dir).unwrap();
+        for i in 0..7 {
+            fs::write(crash_dir.join(format!("crash-2026010{}-000000.000.txt", i)), format!("report {}", i)).unwrap();
+        }
+        fs::write(crash_dir.join("notes.txt"), "not a report").unwrap();
+
+        let bundle = DiagnosticsBundle::collect(&crash_dir, None);
+        assert_eq!(bundle.crash_reports.len(), BUNDLED_CRASH_REPORTS);
+        assert_eq!(bundle.crash_reports[0].contents, "report 6");
+
+        let path = bundle.write(&temp_dir.path().join("diagnostics")).unwrap();
+        let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(written["app_version"], env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,146 @@
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, Field, Schema};
+use std::sync::Arc;
+
+use crate::database::adapter::ColumnInfo;
+use crate::error::AppError;
+
+/// Arrow type a result column is encoded as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Int,
+    Float,
+    Bool,
+    Utf8,
+}
+
+/// Kind of a column by its declared type, with the same type names `json::typed_value` knows
+fn column_kind(data_type: &str) -> ColumnKind {
+    let data_type = data_type.to_uppercase();
+    match data_type.split(['(', ' ']).next().unwrap_or("") {
+        "INT2" | "INT4" | "INT8" | "SMALLINT" | "INTEGER" | "INT" | "BIGINT" | "TINYINT"
+        | "MEDIUMINT" | "SERIAL" | "BIGSERIAL" => ColumnKind::Int,
+        "FLOAT4" | "FLOAT8" | "REAL" | "FLOAT" | "DOUBLE" => ColumnKind::Float,
+        "BOOL" | "BOOLEAN" => ColumnKind::Bool,
+        _ => ColumnKind::Utf8,
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, ()> {
+    match value.to_lowercase().as_str() {
+        "true" | "t" | "1" => Ok(true),
+        "false" | "f" | "0" => Ok(false),
+        _ => Err(()),
+    }
+}
+
+/// Values of one column as an Arrow array; a column with a value that does not
+/// parse as its declared type is sent as strings, so nothing is lost
+fn column_array(kind: ColumnKind, values: &[Option<&str>]) -> ArrayRef {
+    let typed: Option<ArrayRef> = match kind {
+        ColumnKind::Int => values
+            .iter()
+            .map(|v| v.map(str::parse::<i64>).transpose())
+            .collect::<Result<Int64Array, _>>()
+            .ok()
+            .map(|array| Arc::new(array) as ArrayRef),
+        ColumnKind::Float => values
+            .iter()
+            .map(|v| v.map(str::parse::<f64>).transpose())
+            .collect::<Result<Float64Array, _>>()
+            .ok()
+            .map(|array| Arc::new(array) as ArrayRef),
+        ColumnKind::Bool => values
+            .iter()
+            .map(|v| v.map(parse_bool).transpose())
+            .collect::<Result<BooleanArray, _>>()
+            .ok()
+            .map(|array| Arc::new(array) as ArrayRef),
+        ColumnKind::Utf8 => None,
+    };
+
+    typed.unwrap_or_else(|| Arc::new(StringArray::from(values.to_vec())))
+}
+
+fn arrow_error(e: ArrowError) -> AppError {
+    AppError::Io(std::io::Error::other(e))
+}
+
+/// Encode rows as an Arrow IPC stream holding the schema and one record batch
+///
+/// Integer, floating point and boolean columns become Int64, Float64 and Boolean
+/// arrays; every other column, including exact decimals, is sent as Utf8.
+pub fn encode_ipc(columns: &[ColumnInfo], rows: &[Vec<Option<String>>]) -> Result<Vec<u8>, AppError> {
+    let arrays: Vec<ArrayRef> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let values: Vec<Option<&str>> = rows.iter().map(|row| row.get(i).and_then(|v| v.as_deref())).collect();
+            column_array(column_kind(&column.data_type), &values)
+        })
+        .collect();
+    let fields: Vec<Field> = columns
+        .iter()
+        .zip(&arrays)
+        .map(|(column, array)| Field::new(&column.name, array.data_type().clone(), true))
+        .collect();
+    let schema = Arc::new(Schema::new(fields));
+
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    let batch = RecordBatch::try_new_with_options(schema.clone(), arrays, &options).map_err(arrow_error)?;
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema).map_err(arrow_error)?;
+    writer.write(&batch).map_err(arrow_error)?;
+    writer.finish().map_err(arrow_error)?;
+    writer.into_inner().map_err(arrow_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use arrow_ipc::reader::StreamReader;
+    use arrow_schema::DataType;
+
+    fn column(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+        }
+    }
+
+    #[test]
+    fn test_encode_ipc() {
+        let columns = vec![
+            column("id", "int8"),
+            column("score", "DOUBLE"),
+            column("active", "bool"),
+            column("price", "NUMERIC(10,2)"),
+            column("code", "INTEGER"),
+        ];
+        let rows = vec![
+            vec![Some("1".into()), Some("0.5".into()), Some("t".into()), Some("9.99".into()), Some("7".into())],
+            vec![Some("2".into()), None, Some("false".into()), None, Some("n/a".into())],
+        ];
+
+        let bytes = encode_ipc(&columns, &rows).unwrap();
+        let batches: Vec<RecordBatch> = StreamReader::try_new(bytes.as_slice(), None)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let batch = &batches[0];
+        let types: Vec<DataType> = batch.schema().fields().iter().map(|f| f.data_type().clone()).collect();
+        // A value that is not an integer sends the whole column as strings
+        assert_eq!(
+            types,
+            vec![DataType::Int64, DataType::Float64, DataType::Boolean, DataType::Utf8, DataType::Utf8]
+        );
+        assert_eq!(batch.num_rows(), 2);
+        assert!(batch.column(1).is_null(1));
+        let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(ids.values(), &[1, 2]);
+    }
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::adapter::QueryResult;
+use crate::error::AppError;
+
+/// Text formats a result can be copied as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextFormat {
+    Markdown,
+    Tsv,
+    Html,
+}
+
+/// Options controlling text formatting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextFormatOptions {
+    /// Text shown for NULL values
+    pub null_display: String,
+    /// Include column names as the first row (Markdown tables always have a header)
+    pub include_header: bool,
+}
+
+impl Default for TextFormatOptions {
+    fn default() -> Self {
+        Self {
+            null_display: "NULL".to_string(),
+            include_header: true,
+        }
+    }
+}
+
+/// Format a query result as Markdown, TSV, or HTML table text
+pub fn format_result(
+    result: &QueryResult,
+    format: TextFormat,
+    options: &TextFormatOptions,
+) -> Result<String, AppError> {
+    let header: Vec<&str> = if result.columns.is_empty() {
+        result
+            .rows
+            .first()
+            .map(|row| row.columns.iter().map(|c| c.as_str()).collect())
+            .unwrap_or_default()
+    } else {
+        result.columns.iter().map(|c| c.name.as_str()).collect()
+    };
+
+    let rows: Vec<Vec<&str>> = result
+        .rows
+        .iter()
+        .map(|row| {
+            (0..header.len())
+                .map(|i| {
+                    row.values
+                        .get(i)
+                        .and_then(|v| v.as_deref())
+                        .unwrap_or(options.null_display.as_str())
+                })
+                .collect()
+        })
+        .collect();
+
+    match format {
+        TextFormat::Markdown => Ok(to_markdown(&header, &rows)),
+        TextFormat::Tsv => to_tsv(&header, &rows, options.include_header),
+        TextFormat::Html => Ok(to_html(&header, &rows, options.include_header)),
+    }
+}
+
+fn escape_markdown(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+pub(crate) fn to_markdown(header: &[&str], rows: &[Vec<&str>]) -> String {
+    let mut output = String::new();
+
+    let cells: Vec<String> = header.iter().map(|h| escape_markdown(h)).collect();
+    output.push_str(&format!("| {} |\n", cells.join(" | ")));
+    output.push_str(&format!("|{}\n", " --- |".repeat(header.len())));
+
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|v| escape_markdown(v)).collect();
+        output.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+
+    output
+}
+
+fn to_tsv(header: &[&str], rows: &[Vec<&str>], include_header: bool) -> Result<String, AppError> {
+    // Fields containing tabs, quotes, or newlines are quoted the way spreadsheets expect
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .from_writer(Vec::new());
+
+    if include_header {
+        writer.write_record(header).map_err(|e| AppError::Io(e.into()))?;
+    }
+    for row in rows {
+        writer.write_record(row).map_err(|e| AppError::Io(e.into()))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| AppError::Io(e.into_error()))?;
+    String::from_utf8(bytes).map_err(|e| AppError::Unknown(e.to_string()))
+}
+
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub(crate) fn to_html(header: &[&str], rows: &[Vec<&str>], include_header: bool) -> String {
+    let mut output = String::from("<table>\n");
+
+    if include_header {
+        output.push_str("  <thead>\n    <tr>");
+        for h in header {
+            output.push_str(&format!("<th>{}</th>", escape_html(h)));
+        }
+        output.push_str("</tr>\n  </thead>\n");
+    }
+
+    output.push_str("  <tbody>\n");
+    for row in rows {
+        output.push_str("    <tr>");
+        for v in row {
+            output.push_str(&format!("<td>{}</td>", escape_html(v)));
+        }
+        output.push_str("</tr>\n");
+    }
+    output.push_str("  </tbody>\n</table>\n");
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::{ColumnInfo, QueryRow};
+
+    fn result() -> QueryResult {
+        let columns = vec!["id".to_string(), "note".to_string()];
+        QueryResult {
+            columns: columns
+                .iter()
+                .map(|name| ColumnInfo {
+                    name: name.clone(),
+                    data_type: "TEXT".to_string(),
+                    is_nullable: true,
+                })
+                .collect(),
+            rows: vec![
+                QueryRow {
+                    columns: columns.clone(),
+                    values: vec![Some("1".to_string()), Some("a|b\nc".to_string())],
+                },
+                QueryRow {
+                    columns: columns.clone(),
+                    values: vec![Some("2".to_string()), None],
+                },
+            ],
+            rows_affected: None,
+            execution_time: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown() {
+        let text =
+            format_result(&result(), TextFormat::Markdown, &TextFormatOptions::default()).unwrap();
+        assert_eq!(
+            text,
+            "| id | note |\n| --- | --- |\n| 1 | a\\|b<br>c |\n| 2 | NULL |\n"
+        );
+    }
+
+    #[test]
+    fn test_tsv() {
+        let options = TextFormatOptions {
+            null_display: String::new(),
+            include_header: true,
+        };
+        let text = format_result(&result(), TextFormat::Tsv, &options).unwrap();
+        assert_eq!(text, "id\tnote\n1\t\"a|b\nc\"\n2\t\n");
+    }
+
+    #[test]
+    fn test_html() {
+        let mut input = result();
+        input.rows[0].values[1] = Some("<b>&</b>".to_string());
+        let options = TextFormatOptions {
+            include_header: false,
+            ..TextFormatOptions::default()
+        };
+        let text = format_result(&input, TextFormat::Html, &options).unwrap();
+        assert_eq!(
+            text,
+            "<table>\n  <tbody>\n    <tr><td>1</td><td>&lt;b&gt;&amp;&lt;/b&gt;</td></tr>\n    \
+             <tr><td>2</td><td>NULL</td></tr>\n  </tbody>\n</table>\n"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use super::{ExportSink, ProgressCallback, ProgressTracker};
+use crate::database::adapter::{ColumnInfo, QueryRow, RowSink};
+use crate::error::AppError;
+
+/// When CSV fields are wrapped in quotes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    /// Only quote fields containing the delimiter, quotes, or newlines
+    Necessary,
+    /// Quote every field
+    Always,
+    /// Quote every field that doesn't look like a number
+    NonNumeric,
+    /// Never quote fields
+    Never,
+}
+
+/// Options controlling CSV output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub quote_style: QuoteStyle,
+    pub null_value: String,
+    pub include_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote_style: QuoteStyle::Necessary,
+            null_value: String::new(),
+            include_header: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Validate the options
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !self.delimiter.is_ascii() {
+            return Err(AppError::Validation(format!(
+                "CSV delimiter must be an ASCII character, got '{}'",
+                self.delimiter
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Row sink that writes CSV records to any writer
+pub struct CsvSink<W: Write> {
+    writer: ::csv::Writer<W>,
+    include_header: bool,
+    null_value: String,
+    progress: ProgressTracker,
+}
+
+impl<W: Write> CsvSink<W> {
+    /// Create a new CSV sink
+    pub fn new(inner: W, options: CsvOptions) -> Result<Self, AppError> {
+        options.validate()?;
+
+        let quote_style = match options.quote_style {
+            QuoteStyle::Necessary => ::csv::QuoteStyle::Necessary,
+            QuoteStyle::Always => ::csv::QuoteStyle::Always,
+            QuoteStyle::NonNumeric => ::csv::QuoteStyle::NonNumeric,
+            QuoteStyle::Never => ::csv::QuoteStyle::Never,
+        };
+
+        let writer = ::csv::WriterBuilder::new()
+            .delimiter(options.delimiter as u8)
+            .quote_style(quote_style)
+            .from_writer(inner);
+
+        Ok(Self {
+            writer,
+            include_header: options.include_header,
+            null_value: options.null_value,
+            progress: ProgressTracker::default(),
+        })
+    }
+
+    /// Flush any buffered records and return the underlying writer
+    pub fn into_inner(self) -> Result<W, AppError> {
+        self.writer
+            .into_inner()
+            .map_err(|e| AppError::Io(e.into_error()))
+    }
+}
+
+impl<W: Write + Send> RowSink for CsvSink<W> {
+    fn columns(&mut self, columns: &[ColumnInfo]) -> Result<(), AppError> {
+        if self.include_header {
+            self.writer
+                .write_record(columns.iter().map(|c| c.name.as_str()))
+                .map_err(|e| AppError::Io(e.into()))?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError> {
+        let null_value = self.null_value.as_str();
+        self.writer
+            .write_record(row.values.iter().map(|v| v.as_deref().unwrap_or(null_value)))
+            .map_err(|e| AppError::Io(e.into()))?;
+
+        self.progress.tick();
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> ExportSink for CsvSink<W> {
+    fn set_progress(&mut self, on_progress: ProgressCallback) {
+        self.progress.set_callback(on_progress);
+    }
+
+    fn finish(self: Box<Self>) -> Result<(), AppError> {
+        self.into_inner()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<ColumnInfo> {
+        vec![
+            ColumnInfo {
+                name: "id".to_string(),
+                data_type: "INT4".to_string(),
+                is_nullable: false,
+            },
+            ColumnInfo {
+                name: "name".to_string(),
+                data_type: "TEXT".to_string(),
+                is_nullable: true,
+            },
+        ]
+    }
+
+    fn row(id: &str, name: Option<&str>) -> QueryRow {
+        QueryRow {
+            columns: vec!["id".to_string(), "name".to_string()],
+            values: vec![Some(id.to_string()), name.map(|n| n.to_string())],
+        }
+    }
+
+    fn write(options: CsvOptions, rows: &[QueryRow]) -> String {
+        let mut sink = CsvSink::new(Vec::new(), options).unwrap();
+        sink.columns(&columns()).unwrap();
+        for r in rows {
+            sink.row(r).unwrap();
+        }
+        String::from_utf8(sink.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_default_options() {
+        let output = write(
+            CsvOptions::default(),
+            &[row("1", Some("Alice")), row("2", Some("Smith, Bob")), row("3", None)],
+        );
+        assert_eq!(output, "id,name\n1,Alice\n2,\"Smith, Bob\"\n3,\n");
+    }
+
+    #[test]
+    fn test_custom_delimiter_and_null() {
+        let options = CsvOptions {
+            delimiter: ';',
+            null_value: "NULL".to_string(),
+            include_header: false,
+            ..CsvOptions::default()
+        };
+        let output = write(options, &[row("1", None)]);
+        assert_eq!(output, "1;NULL\n");
+    }
+
+    #[test]
+    fn test_quote_always() {
+        let options = CsvOptions {
+            quote_style: QuoteStyle::Always,
+            ..CsvOptions::default()
+        };
+        let output = write(options, &[row("1", Some("say \"hi\""))]);
+        assert_eq!(output, "\"id\",\"name\"\n\"1\",\"say \"\"hi\"\"\"\n");
+    }
+
+    #[test]
+    fn test_non_ascii_delimiter_rejected() {
+        let options = CsvOptions {
+            delimiter: '→',
+            ..CsvOptions::default()
+        };
+        assert!(CsvSink::new(Vec::new(), options).is_err());
+    }
+}
@@ -0,0 +1,581 @@
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::database::adapter::{DatabaseAdapter, DatabaseType, QueryResult};
+use crate::database::dialect::SqlDialect;
+use crate::error::AppError;
+
+/// Kind of schema object, in the order the objects are created by the script
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaObjectKind {
+    Schema,
+    Type,
+    Sequence,
+    Table,
+    ForeignKey,
+    Index,
+    View,
+    Trigger,
+}
+
+/// A single DDL statement of the script
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaObject {
+    pub kind: SchemaObjectKind,
+    /// Name used to resolve dependencies, schema-qualified where the database has schemas
+    pub name: String,
+    /// Objects of the same kind that must be created first
+    pub depends_on: Vec<String>,
+    pub sql: String,
+}
+
+impl SchemaObject {
+    fn new(kind: SchemaObjectKind, name: impl Into<String>, sql: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            depends_on: Vec::new(),
+            sql: sql.into(),
+        }
+    }
+}
+
+/// Summary returned once a DDL script has been written
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DdlSummary {
+    pub path: String,
+    pub bytes_written: u64,
+    pub tables: usize,
+    pub views: usize,
+    pub indexes: usize,
+    pub constraints: usize,
+    pub other: usize,
+}
+
+impl DdlSummary {
+    pub fn new(path: String, bytes_written: u64, objects: &[SchemaObject]) -> Self {
+        let mut summary = Self {
+            path,
+            bytes_written,
+            ..Self::default()
+        };
+        for object in objects {
+            match object.kind {
+                SchemaObjectKind::Table => summary.tables += 1,
+                SchemaObjectKind::View => summary.views += 1,
+                SchemaObjectKind::Index => summary.indexes += 1,
+                SchemaObjectKind::ForeignKey => summary.constraints += 1,
+                _ => summary.other += 1,
+            }
+        }
+        summary
+    }
+}
+
+/// Read every user-defined schema object of the connected database
+pub async fn schema_objects(adapter: &dyn DatabaseAdapter) -> Result<Vec<SchemaObject>, AppError> {
+    let dialect = adapter.get_dialect();
+    let objects = match adapter.database_type() {
+        DatabaseType::PostgreSQL => postgres_objects(adapter, dialect.as_ref()).await?,
+        DatabaseType::MySQL => mysql_objects(adapter, dialect.as_ref()).await?,
+        DatabaseType::SQLite => sqlite_objects(adapter).await?,
+        DatabaseType::Other(id) => {
+            return Err(AppError::Validation(format!(
+                "Schema export is not supported for {} databases",
+                id
+            )))
+        }
+    };
+    Ok(order_objects(objects))
+}
+
+/// Sort objects so that everything is created after what it depends on
+///
+/// Objects are grouped by kind first. Within a kind, dependencies are resolved
+/// topologically; objects caught in a cycle keep their original order.
+pub fn order_objects(mut objects: Vec<SchemaObject>) -> Vec<SchemaObject> {
+    objects.sort_by_key(|o| o.kind);
+
+    let mut ordered = Vec::with_capacity(objects.len());
+    let mut remaining = objects.into_iter().peekable();
+    while let Some(kind) = remaining.peek().map(|o| o.kind) {
+        let mut group = Vec::new();
+        while let Some(object) = remaining.next_if(|o| o.kind == kind) {
+            group.push(object);
+        }
+        ordered.extend(order_group(group));
+    }
+    ordered
+}
+
+fn order_group(mut pending: Vec<SchemaObject>) -> Vec<SchemaObject> {
+    let mut ordered = Vec::with_capacity(pending.len());
+
+    while !pending.is_empty() {
+        let waiting: HashSet<String> = pending.iter().map(|o| o.name.to_lowercase()).collect();
+        let (ready, blocked): (Vec<_>, Vec<_>) = pending.into_iter().partition(|object| {
+            object.depends_on.iter().all(|dep| {
+                let dep = dep.to_lowercase();
+                dep == object.name.to_lowercase() || !waiting.contains(&dep)
+            })
+        });
+
+        if ready.is_empty() {
+            // Circular dependencies: emit the rest as-is
+            ordered.extend(blocked);
+            break;
+        }
+        ordered.extend(ready);
+        pending = blocked;
+    }
+
+    ordered
+}
+
+/// Render the ordered objects as a script
+pub fn render_script(
+    database_type: DatabaseType,
+    database_name: &str,
+    objects: &[SchemaObject],
+) -> String {
+    let mut script = format!(
+        "-- Schema of {} ({:?})\n-- Generated by DataForge on {}\n\n",
+        database_name,
+        database_type,
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+    );
+
+    // MySQL foreign keys are part of CREATE TABLE, so cycles need the checks disabled
+    if database_type == DatabaseType::MySQL {
+        script.push_str("SET FOREIGN_KEY_CHECKS = 0;\n\n");
+    }
+
+    for object in objects {
+        script.push_str(object.sql.trim().trim_end_matches(';').trim_end());
+        script.push_str(";\n\n");
+    }
+
+    if database_type == DatabaseType::MySQL {
+        script.push_str("SET FOREIGN_KEY_CHECKS = 1;\n");
+    }
+
+    script
+}
+
+/// Rows of a catalog query with NULLs as empty strings
+fn text_rows(result: QueryResult) -> Vec<Vec<String>> {
+    result
+        .rows
+        .into_iter()
+        .map(|row| row.values.into_iter().map(Option::unwrap_or_default).collect())
+        .collect()
+}
+
+/// Names among `candidates` that appear as identifiers in `sql`
+fn referenced_names(sql: &str, candidates: &[String], own_name: &str) -> Vec<String> {
+    let tokens: HashSet<String> = sql
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect();
+
+    candidates
+        .iter()
+        .filter(|name| !name.eq_ignore_ascii_case(own_name))
+        .filter(|name| {
+            // Match on the unqualified part, which is how views usually refer to each other
+            let short = name.rsplit('.').next().unwrap_or(name);
+            tokens.contains(&short.to_lowercase())
+        })
+        .cloned()
+        .collect()
+}
+
+/// Work out which views are built on top of other views
+fn resolve_view_dependencies(objects: &mut [SchemaObject]) {
+    let views: Vec<String> = objects
+        .iter()
+        .filter(|o| o.kind == SchemaObjectKind::View)
+        .map(|o| o.name.clone())
+        .collect();
+
+    for object in objects.iter_mut().filter(|o| o.kind == SchemaObjectKind::View) {
+        object.depends_on = referenced_names(&object.sql, &views, &object.name);
+    }
+}
+
+const PG_SYSTEM_SCHEMAS: &str = "('pg_catalog', 'information_schema', 'pg_toast')";
+
+async fn postgres_objects(
+    adapter: &dyn DatabaseAdapter,
+    dialect: &dyn SqlDialect,
+) -> Result<Vec<SchemaObject>, AppError> {
+    let qualified = |schema: &str, name: &str| dialect.qualified_table_name(Some(schema), name);
+    let mut objects = Vec::new();
+
+    let schemas = adapter
+        .execute_query(
+            "SELECT nspname::text FROM pg_namespace \
+             WHERE nspname NOT LIKE 'pg\\_%' AND nspname NOT IN ('information_schema', 'public') \
+             ORDER BY nspname",
+        )
+        .await?;
+    for row in text_rows(schemas) {
+        let sql = format!("CREATE SCHEMA IF NOT EXISTS {}", dialect.quote_identifier(&row[0]));
+        objects.push(SchemaObject::new(SchemaObjectKind::Schema, row[0].clone(), sql));
+    }
+
+    let enums = adapter
+        .execute_query(&format!(
+            "SELECT n.nspname::text, t.typname::text, \
+                    string_agg(quote_literal(e.enumlabel), ', ' ORDER BY e.enumsortorder) \
+             FROM pg_type t \
+             JOIN pg_enum e ON e.enumtypid = t.oid \
+             JOIN pg_namespace n ON n.oid = t.typnamespace \
+             WHERE n.nspname NOT IN {} \
+             GROUP BY n.nspname, t.typname \
+             ORDER BY n.nspname, t.typname",
+            PG_SYSTEM_SCHEMAS
+        ))
+        .await?;
+    for row in text_rows(enums) {
+        let name = qualified(&row[0], &row[1]);
+        let sql = format!("CREATE TYPE {} AS ENUM ({})", name, row[2]);
+        objects.push(SchemaObject::new(SchemaObjectKind::Type, name, sql));
+    }
+
+    // Sequences owned by identity columns are created along with their table
+    let sequences = adapter
+        .execute_query(&format!(
+            "SELECT n.nspname::text, c.relname::text \
+             FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE c.relkind = 'S' AND n.nspname NOT IN {} \
+               AND NOT EXISTS ( \
+                   SELECT 1 FROM pg_depend d WHERE d.objid = c.oid AND d.deptype = 'i' \
+               ) \
+             ORDER BY n.nspname, c.relname",
+            PG_SYSTEM_SCHEMAS
+        ))
+        .await?;
+    for row in text_rows(sequences) {
+        let name = qualified(&row[0], &row[1]);
+        let sql = format!("CREATE SEQUENCE IF NOT EXISTS {}", name);
+        objects.push(SchemaObject::new(SchemaObjectKind::Sequence, name, sql));
+    }
+
+    let columns = adapter
+        .execute_query(&format!(
+            "SELECT n.nspname::text, c.relname::text, a.attname::text, \
+                    format_type(a.atttypid, a.atttypmod), \
+                    CASE WHEN a.attnotnull THEN 'YES' ELSE 'NO' END, \
+                    pg_get_expr(d.adbin, d.adrelid), \
+                    a.attidentity::text, a.attgenerated::text \
+             FROM pg_attribute a \
+             JOIN pg_class c ON c.oid = a.attrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+             WHERE c.relkind IN ('r', 'p') AND a.attnum > 0 AND NOT a.attisdropped \
+               AND n.nspname NOT IN {} \
+             ORDER BY n.nspname, c.relname, a.attnum",
+            PG_SYSTEM_SCHEMAS
+        ))
+        .await?;
+
+    // Table name -> column and inline constraint definitions
+    let mut tables: Vec<(String, Vec<String>)> = Vec::new();
+    for row in text_rows(columns) {
+        let table = qualified(&row[0], &row[1]);
+        let mut definition = format!("    {} {}", dialect.quote_identifier(&row[2]), row[3]);
+        match (row[6].as_str(), row[7].as_str()) {
+            ("a", _) => definition.push_str(" GENERATED ALWAYS AS IDENTITY"),
+            ("d", _) => definition.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+            (_, "s") => definition.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", row[5])),
+            _ if !row[5].is_empty() => definition.push_str(&format!(" DEFAULT {}", row[5])),
+            _ => {}
+        }
+        if row[4] == "YES" {
+            definition.push_str(" NOT NULL");
+        }
+
+        match tables.last_mut() {
+            Some((name, definitions)) if *name == table => definitions.push(definition),
+            _ => tables.push((table, vec![definition])),
+        }
+    }
+
+    let constraints = adapter
+        .execute_query(&format!(
+            "SELECT n.nspname::text, c.relname::text, con.conname::text, con.contype::text, \
+                    pg_get_constraintdef(con.oid), fn.nspname::text, fc.relname::text \
+             FROM pg_constraint con \
+             JOIN pg_class c ON c.oid = con.conrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             LEFT JOIN pg_class fc ON fc.oid = con.confrelid \
+             LEFT JOIN pg_namespace fn ON fn.oid = fc.relnamespace \
+             WHERE con.contype IN ('p', 'u', 'c', 'f', 'x') AND n.nspname NOT IN {} \
+             ORDER BY n.nspname, c.relname, con.contype, con.conname",
+            PG_SYSTEM_SCHEMAS
+        ))
+        .await?;
+
+    let mut table_dependencies: Vec<(String, String)> = Vec::new();
+    for row in text_rows(constraints) {
+        let table = qualified(&row[0], &row[1]);
+        let constraint = format!("CONSTRAINT {} {}", dialect.quote_identifier(&row[2]), row[4]);
+
+        if row[3] == "f" {
+            // Foreign keys are added once all tables exist, which also covers cycles
+            let referenced = qualified(&row[5], &row[6]);
+            table_dependencies.push((table.clone(), referenced));
+
+            let sql = format!("ALTER TABLE {} ADD {}", table, constraint);
+            let name = format!("{}.{}", table, row[2]);
+            objects.push(SchemaObject::new(SchemaObjectKind::ForeignKey, name, sql));
+        } else if let Some((_, definitions)) = tables.iter_mut().find(|(name, _)| *name == table) {
+            definitions.push(format!("    {}", constraint));
+        }
+    }
+
+    for (table, definitions) in tables {
+        let sql = format!("CREATE TABLE {} (\n{}\n)", table, definitions.join(",\n"));
+        let mut object = SchemaObject::new(SchemaObjectKind::Table, table.clone(), sql);
+        object.depends_on = table_dependencies
+            .iter()
+            .filter(|(from, _)| *from == table)
+            .map(|(_, to)| to.clone())
+            .collect();
+        objects.push(object);
+    }
+
+    // Indexes backing primary key and unique constraints come with the constraint
+    let indexes = adapter
+        .execute_query(&format!(
+            "SELECT n.nspname::text, i.relname::text, pg_get_indexdef(i.oid) \
+             FROM pg_index x \
+             JOIN pg_class i ON i.oid = x.indexrelid \
+             JOIN pg_class t ON t.oid = x.indrelid \
+             JOIN pg_namespace n ON n.oid = t.relnamespace \
+             WHERE n.nspname NOT IN {} \
+               AND NOT EXISTS (SELECT 1 FROM pg_constraint con WHERE con.conindid = x.indexrelid) \
+             ORDER BY n.nspname, t.relname, i.relname",
+            PG_SYSTEM_SCHEMAS
+        ))
+        .await?;
+    for row in text_rows(indexes) {
+        let name = qualified(&row[0], &row[1]);
+        objects.push(SchemaObject::new(SchemaObjectKind::Index, name, row[2].clone()));
+    }
+
+    let views = adapter
+        .execute_query(&format!(
+            "SELECT n.nspname::text, c.relname::text, c.relkind::text, pg_get_viewdef(c.oid, true) \
+             FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE c.relkind IN ('v', 'm') AND n.nspname NOT IN {} \
+             ORDER BY c.oid",
+            PG_SYSTEM_SCHEMAS
+        ))
+        .await?;
+    for row in text_rows(views) {
+        let name = qualified(&row[0], &row[1]);
+        let definition = row[3].trim().trim_end_matches(';');
+        let sql = if row[2] == "m" {
+            format!("CREATE MATERIALIZED VIEW {} AS\n{}\nWITH NO DATA", name, definition)
+        } else {
+            format!("CREATE VIEW {} AS\n{}", name, definition)
+        };
+        objects.push(SchemaObject::new(SchemaObjectKind::View, name, sql));
+    }
+
+    resolve_view_dependencies(&mut objects);
+    Ok(objects)
+}
+
+async fn mysql_objects(
+    adapter: &dyn DatabaseAdapter,
+    dialect: &dyn SqlDialect,
+) -> Result<Vec<SchemaObject>, AppError> {
+    let mut objects = Vec::new();
+
+    let references = adapter
+        .execute_query(
+            "SELECT CAST(TABLE_NAME AS CHAR), CAST(REFERENCED_TABLE_NAME AS CHAR) \
+             FROM information_schema.REFERENTIAL_CONSTRAINTS \
+             WHERE CONSTRAINT_SCHEMA = DATABASE()",
+        )
+        .await?;
+    let references = text_rows(references);
+
+    let tables = adapter
+        .execute_query(
+            "SELECT CAST(TABLE_NAME AS CHAR) FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_TYPE = 'BASE TABLE' \
+             ORDER BY TABLE_NAME",
+        )
+        .await?;
+
+    // SHOW CREATE TABLE already includes indexes and constraints
+    for row in text_rows(tables) {
+        let table = row[0].clone();
+        let created = adapter
+            .execute_query(&format!("SHOW CREATE TABLE {}", dialect.quote_identifier(&table)))
+            .await?;
+        let sql = text_rows(created)
+            .into_iter()
+            .next()
+            .and_then(|row| row.into_iter().nth(1))
+            .ok_or_else(|| AppError::NotFound(format!("No definition returned for table {}", table)))?;
+
+        let mut object = SchemaObject::new(SchemaObjectKind::Table, table.clone(), sql);
+        object.depends_on = references
+            .iter()
+            .filter(|r| r[0] == table)
+            .map(|r| r[1].clone())
+            .collect();
+        objects.push(object);
+    }
+
+    // Built from the definition rather than SHOW CREATE VIEW to leave out the DEFINER clause
+    let views = adapter
+        .execute_query(
+            "SELECT CAST(TABLE_NAME AS CHAR), CAST(VIEW_DEFINITION AS CHAR) \
+             FROM information_schema.VIEWS \
+             WHERE TABLE_SCHEMA = DATABASE() \
+             ORDER BY TABLE_NAME",
+        )
+        .await?;
+    for row in text_rows(views) {
+        let sql = format!("CREATE VIEW {} AS\n{}", dialect.quote_identifier(&row[0]), row[1]);
+        objects.push(SchemaObject::new(SchemaObjectKind::View, row[0].clone(), sql));
+    }
+
+    resolve_view_dependencies(&mut objects);
+    Ok(objects)
+}
+
+async fn sqlite_objects(adapter: &dyn DatabaseAdapter) -> Result<Vec<SchemaObject>, AppError> {
+    let references = adapter
+        .execute_query(
+            "SELECT m.name, p.\"table\" FROM sqlite_master m \
+             JOIN pragma_foreign_key_list(m.name) p \
+             WHERE m.type = 'table'",
+        )
+        .await?;
+    let references = text_rows(references);
+
+    // SQLite keeps the original statement of every object; rowid order is creation order
+    let definitions = adapter
+        .execute_query(
+            "SELECT type, name, sql FROM sqlite_master \
+             WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' \
+             ORDER BY rowid",
+        )
+        .await?;
+
+    let mut objects = Vec::new();
+    for row in text_rows(definitions) {
+        let kind = match row[0].as_str() {
+            "table" => SchemaObjectKind::Table,
+            "index" => SchemaObjectKind::Index,
+            "view" => SchemaObjectKind::View,
+            "trigger" => SchemaObjectKind::Trigger,
+            _ => continue,
+        };
+
+        let mut object = SchemaObject::new(kind, row[1].clone(), row[2].clone());
+        if kind == SchemaObjectKind::Table {
+            object.depends_on = references
+                .iter()
+                .filter(|r| r[0] == row[1])
+                .map(|r| r[1].clone())
+                .collect();
+        }
+        objects.push(object);
+    }
+
+    resolve_view_dependencies(&mut objects);
+    Ok(objects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(name: &str, depends_on: &[&str]) -> SchemaObject {
+        let mut object = SchemaObject::new(SchemaObjectKind::Table, name, format!("CREATE TABLE {}", name));
+        object.depends_on = depends_on.iter().map(|d| d.to_string()).collect();
+        object
+    }
+
+    fn names(objects: &[SchemaObject]) -> Vec<&str> {
+        objects.iter().map(|o| o.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_order_by_dependencies() {
+        let objects = vec![
+            SchemaObject::new(SchemaObjectKind::Index, "idx_orders", "CREATE INDEX idx_orders"),
+            table("order_items", &["orders", "products"]),
+            table("orders", &["customers"]),
+            table("customers", &[]),
+            table("products", &[]),
+        ];
+
+        let ordered = order_objects(objects);
+        assert_eq!(
+            names(&ordered),
+            vec!["customers", "products", "orders", "order_items", "idx_orders"]
+        );
+    }
+
+    #[test]
+    fn test_order_with_cycle() {
+        let objects = vec![
+            table("a", &["b"]),
+            table("b", &["a"]),
+            table("c", &[]),
+            table("self_ref", &["self_ref"]),
+        ];
+
+        let ordered = order_objects(objects);
+        assert_eq!(names(&ordered), vec!["c", "self_ref", "a", "b"]);
+    }
+
+    #[test]
+    fn test_view_dependencies() {
+        let mut objects = vec![
+            SchemaObject::new(
+                SchemaObjectKind::View,
+                "public.top_customers",
+                "CREATE VIEW top_customers AS SELECT * FROM \"customer_totals\" LIMIT 10",
+            ),
+            SchemaObject::new(
+                SchemaObjectKind::View,
+                "public.customer_totals",
+                "CREATE VIEW customer_totals AS SELECT customer_id, sum(total) FROM orders GROUP BY 1",
+            ),
+        ];
+
+        resolve_view_dependencies(&mut objects);
+        assert_eq!(objects[0].depends_on, vec!["public.customer_totals"]);
+        assert!(objects[1].depends_on.is_empty());
+
+        let ordered = order_objects(objects);
+        assert_eq!(names(&ordered), vec!["public.customer_totals", "public.top_customers"]);
+    }
+
+    #[test]
+    fn test_render_script() {
+        let objects = vec![table("customers", &[])];
+
+        let script = render_script(DatabaseType::MySQL, "shop", &objects);
+        assert!(script.starts_with("-- Schema of shop (MySQL)"));
+        assert!(script.contains("SET FOREIGN_KEY_CHECKS = 0;\n\nCREATE TABLE customers;\n\n"));
+        assert!(script.ends_with("SET FOREIGN_KEY_CHECKS = 1;\n"));
+
+        let script = render_script(DatabaseType::SQLite, "shop.db", &objects);
+        assert!(!script.contains("FOREIGN_KEY_CHECKS"));
+    }
+}
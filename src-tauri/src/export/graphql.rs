@@ -0,0 +1,209 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::codegen::{camel_case, pascal_case};
+use crate::database::adapter::{DatabaseType, ForeignKeyInfo};
+use crate::database::metadata_cache::SchemaSnapshot;
+use crate::database::types::{AbstractType, TypeMapper};
+
+/// Scalars every GraphQL server knows; the others are declared in the schema
+const BUILT_IN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+/// GraphQL scalar of a column type
+fn scalar(abstract_type: &AbstractType) -> &'static str {
+    match abstract_type {
+        AbstractType::SmallInt | AbstractType::Integer => "Int",
+        // Int is 32-bit in GraphQL
+        AbstractType::BigInt => "BigInt",
+        AbstractType::Double => "Float",
+        AbstractType::Decimal { .. } => "Decimal",
+        AbstractType::Boolean => "Boolean",
+        AbstractType::Varchar { .. } | AbstractType::Char { .. } | AbstractType::Text => "String",
+        AbstractType::Date => "Date",
+        AbstractType::Time => "Time",
+        AbstractType::Timestamp | AbstractType::TimestampTz => "DateTime",
+        AbstractType::Json => "JSON",
+        AbstractType::Uuid => "UUID",
+        AbstractType::Binary => "Bytes",
+    }
+}
+
+/// `name`, or `name2`, `name3`, ... when it is taken
+fn unique(name: String, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut n = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{}{}", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+struct ObjectType {
+    name: String,
+    fields: Vec<(String, String)>,
+    taken: HashSet<String>,
+}
+
+impl ObjectType {
+    fn add_field(&mut self, name: String, field_type: String) {
+        let name = unique(name, &mut self.taken);
+        self.fields.push((name, field_type));
+    }
+}
+
+/// GraphQL SDL describing the tables and views of a schema
+///
+/// Every table becomes an object type with a field per column, `!` marking NOT
+/// NULL columns. A foreign key adds a field to the referenced row on the
+/// referencing type, named after the key column without its `_id` suffix, and a
+/// list of referencing rows on the referenced type. The `Query` type lists every
+/// table with `limit` and `offset` arguments.
+pub fn graphql_schema(database_type: DatabaseType, schema: &SchemaSnapshot, foreign_keys: &[ForeignKeyInfo]) -> String {
+    let mapper = TypeMapper::new(database_type);
+    let mut type_names: HashSet<String> = BUILT_IN_SCALARS.iter().map(|s| s.to_string()).collect();
+    type_names.insert("Query".to_string());
+    let mut scalars = BTreeSet::new();
+
+    let mut tables: Vec<&str> = schema.tables.iter().map(|t| t.name.as_str()).collect();
+    tables.sort_unstable();
+    tables.dedup();
+
+    let mut types: Vec<ObjectType> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for table in &tables {
+        let mut object = ObjectType {
+            name: unique(pascal_case(table), &mut type_names),
+            fields: Vec::new(),
+            taken: HashSet::new(),
+        };
+        for column in schema.columns.get(*table).into_iter().flatten() {
+            let scalar = scalar(&mapper.to_abstract(&column.data_type));
+            scalars.insert(scalar);
+            let required = if column.is_nullable { "" } else { "!" };
+            object.add_field(camel_case(&column.name), format!("{}{}", scalar, required));
+        }
+        index.insert(table, types.len());
+        types.push(object);
+    }
+
+    for key in foreign_keys {
+        let (Some(&from), Some(&to)) = (index.get(key.table.as_str()), index.get(key.referenced_table.as_str())) else {
+            continue;
+        };
+
+        let target = match key.columns.as_slice() {
+            [column] if column.to_lowercase().ends_with("_id") && column.len() > 3 => &column[..column.len() - 3],
+            _ => key.referenced_table.as_str(),
+        };
+        let columns = schema.columns.get(key.table.as_str());
+        let required = key.columns.iter().all(|name| {
+            columns.into_iter().flatten().any(|c| &c.name == name && !c.is_nullable)
+        });
+        let field_type = format!("{}{}", types[to].name, if required { "!" } else { "" });
+        types[from].add_field(camel_case(target), field_type);
+
+        let field_type = format!("[{}!]!", types[from].name);
+        types[to].add_field(camel_case(&key.table), field_type);
+    }
+
+    let mut sdl = String::new();
+    for scalar in scalars.iter().filter(|s| !BUILT_IN_SCALARS.contains(s)) {
+        let _ = writeln!(sdl, "scalar {}", scalar);
+    }
+    for object in &types {
+        if !sdl.is_empty() {
+            sdl.push('\n');
+        }
+        let _ = writeln!(sdl, "type {} {{", object.name);
+        for (name, field_type) in &object.fields {
+            let _ = writeln!(sdl, "  {}: {}", name, field_type);
+        }
+        sdl.push_str("}\n");
+    }
+
+    let mut query = ObjectType {
+        name: "Query".to_string(),
+        fields: Vec::new(),
+        taken: HashSet::new(),
+    };
+    for object in &types {
+        query.add_field(camel_case(&object.name), format!("[{}!]!", object.name));
+    }
+    if !sdl.is_empty() {
+        sdl.push('\n');
+    }
+    sdl.push_str("type Query {\n");
+    for (name, field_type) in &query.fields {
+        let _ = writeln!(sdl, "  {}(limit: Int, offset: Int): {}", name, field_type);
+    }
+    sdl.push_str("}\n");
+    sdl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::{ColumnInfo, TableInfo};
+
+    fn column(name: &str, data_type: &str, is_nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable,
+        }
+    }
+
+    fn table(name: &str) -> TableInfo {
+        TableInfo {
+            name: name.to_string(),
+            schema: None,
+            table_type: "TABLE".to_string(),
+            row_count: None,
+        }
+    }
+
+    #[test]
+    fn test_graphql_schema() {
+        let schema = SchemaSnapshot {
+            tables: vec![table("users"), table("orders")],
+            routines: Vec::new(),
+            columns: HashMap::from([
+                (
+                    "users".to_string(),
+                    vec![column("id", "bigint", false), column("email", "varchar(255)", true)],
+                ),
+                (
+                    "orders".to_string(),
+                    vec![
+                        column("id", "integer", false),
+                        column("user_id", "bigint", false),
+                        column("created_at", "timestamp with time zone", true),
+                    ],
+                ),
+            ]),
+        };
+        let foreign_keys = vec![ForeignKeyInfo {
+            name: Some("orders_user_id_fkey".to_string()),
+            schema: None,
+            table: "orders".to_string(),
+            columns: vec!["user_id".to_string()],
+            referenced_schema: None,
+            referenced_table: "users".to_string(),
+            referenced_columns: vec!["id".to_string()],
+        }];
+
+        let sdl = graphql_schema(DatabaseType::PostgreSQL, &schema, &foreign_keys);
+        assert_eq!(
+            sdl,
+            "scalar BigInt\n\
+             scalar DateTime\n\
+             \n\
+             type Orders {\n  id: Int!\n  userId: BigInt!\n  createdAt: DateTime\n  user: Users!\n}\n\
+             \n\
+             type Users {\n  id: BigInt!\n  email: String\n  orders: [Orders!]!\n}\n\
+             \n\
+             type Query {\n  orders(limit: Int, offset: Int): [Orders!]!\n  users(limit: Int, offset: Int): [Users!]!\n}\n"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Write;
+
+use super::json::typed_value;
+use super::{ExportSink, ProgressCallback, ProgressTracker};
+use crate::database::adapter::{ColumnInfo, QueryRow, RowSink};
+use crate::database::dialect::{ConflictAction, SqlDialect, UpsertBuilder};
+use crate::database::types::{AbstractType, TypeMapper};
+use crate::error::AppError;
+
+/// Options controlling INSERT statement export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsertOptions {
+    pub table_name: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    #[serde(default = "default_rows_per_statement")]
+    pub rows_per_statement: usize,
+    #[serde(default)]
+    pub on_conflict: ConflictAction,
+    #[serde(default)]
+    pub conflict_columns: Vec<String>,
+}
+
+fn default_rows_per_statement() -> usize {
+    1
+}
+
+impl InsertOptions {
+    /// Validate the options against the target dialect
+    pub fn validate(&self, dialect: &dyn SqlDialect) -> Result<(), AppError> {
+        if self.table_name.trim().is_empty() {
+            return Err(AppError::Validation("Target table name is required".to_string()));
+        }
+
+        if self.rows_per_statement == 0 {
+            return Err(AppError::Validation("Rows per statement must be at least 1".to_string()));
+        }
+
+        UpsertBuilder::new(dialect, &self.table_name)
+            .conflict_keys(&self.conflict_columns)
+            .on_conflict(self.on_conflict)
+            .validate()
+    }
+}
+
+/// Render a cell value as a SQL literal, using the column type to decide on quoting
+pub fn sql_literal(value: Option<&str>, data_type: &str, dialect: &dyn SqlDialect) -> String {
+    match (value, typed_value(value, data_type)) {
+        (None, _) => "NULL".to_string(),
+        // Keep the digits as the database returned them, e.g. NUMERIC scale
+        (Some(text), Value::Number(n)) => dialect.number_literal(text).unwrap_or_else(|| n.to_string()),
+        (_, Value::Bool(b)) => dialect.boolean_literal(b),
+        (Some(text), _) => match binary_value(text, data_type, dialect) {
+            Some(bytes) => dialect.bytes_literal(&bytes),
+            None => dialect.string_literal(text),
+        },
+    }
+}
+
+/// Bytes of a binary column value in PostgreSQL's hex output format (`\x0aff`)
+fn binary_value(text: &str, data_type: &str, dialect: &dyn SqlDialect) -> Option<Vec<u8>> {
+    if TypeMapper::new(dialect.database_type()).to_abstract(data_type) != AbstractType::Binary {
+        return None;
+    }
+    let digits = text.strip_prefix("\\x")?;
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Row sink that renders rows as INSERT statements
+pub struct InsertSink<W: Write> {
+    writer: W,
+    dialect: Box<dyn SqlDialect>,
+    options: InsertOptions,
+    columns: Vec<ColumnInfo>,
+    pending: Vec<String>,
+    progress: ProgressTracker,
+}
+
+impl<W: Write> InsertSink<W> {
+    /// Create a new INSERT sink
+    pub fn new(writer: W, dialect: Box<dyn SqlDialect>, options: InsertOptions) -> Result<Self, AppError> {
+        options.validate(dialect.as_ref())?;
+
+        Ok(Self {
+            writer,
+            dialect,
+            options,
+            columns: Vec::new(),
+            pending: Vec::new(),
+            progress: ProgressTracker::default(),
+        })
+    }
+
+    /// Write any buffered rows and return the underlying writer
+    pub fn into_inner(mut self) -> Result<W, AppError> {
+        self.flush_pending()?;
+        Ok(self.writer)
+    }
+
+    fn upsert(&self) -> UpsertBuilder<'_> {
+        let columns: Vec<String> = self.columns.iter().map(|c| c.name.clone()).collect();
+        UpsertBuilder::new(self.dialect.as_ref(), &self.options.table_name)
+            .schema(self.options.schema.as_deref())
+            .columns(&columns)
+            .conflict_keys(&self.options.conflict_columns)
+            .on_conflict(self.options.on_conflict)
+    }
+
+    fn flush_pending(&mut self) -> Result<(), AppError> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let upsert = self.upsert();
+        let values = if self.pending.len() == 1 {
+            format!(" {}", self.pending[0])
+        } else {
+            format!("\n    {}", self.pending.join(",\n    "))
+        };
+
+        writeln!(self.writer, "{}{}{};", upsert.insert_into(), values, upsert.conflict_clause())?;
+
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> RowSink for InsertSink<W> {
+    fn columns(&mut self, columns: &[ColumnInfo]) -> Result<(), AppError> {
+        self.columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError> {
+        let literals: Vec<String> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let value = row.values.get(i).and_then(|v| v.as_deref());
+                sql_literal(value, &column.data_type, self.dialect.as_ref())
+            })
+            .collect();
+
+        self.pending.push(format!("({})", literals.join(", ")));
+        if self.pending.len() >= self.options.rows_per_statement {
+            self.flush_pending()?;
+        }
+
+        self.progress.tick();
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> ExportSink for InsertSink<W> {
+    fn set_progress(&mut self, on_progress: ProgressCallback) {
+        self.progress.set_callback(on_progress);
+    }
+
+    fn finish(self: Box<Self>) -> Result<(), AppError> {
+        self.into_inner()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dialect::{MySQLDialect, PostgreSQLDialect, SQLiteDialect};
+
+    fn columns() -> Vec<ColumnInfo> {
+        vec![
+            ColumnInfo {
+                name: "id".to_string(),
+                data_type: "INT4".to_string(),
+                is_nullable: false,
+            },
+            ColumnInfo {
+                name: "name".to_string(),
+                data_type: "TEXT".to_string(),
+                is_nullable: true,
+            },
+        ]
+    }
+
+    fn row(id: &str, name: Option<&str>) -> QueryRow {
+        QueryRow {
+            columns: vec!["id".to_string(), "name".to_string()],
+            values: vec![Some(id.to_string()), name.map(|n| n.to_string())],
+        }
+    }
+
+    fn insert_options(table_name: &str) -> InsertOptions {
+        InsertOptions {
+            table_name: table_name.to_string(),
+            schema: None,
+            rows_per_statement: default_rows_per_statement(),
+            on_conflict: ConflictAction::None,
+            conflict_columns: Vec::new(),
+        }
+    }
+
+    fn write(dialect: Box<dyn SqlDialect>, options: InsertOptions, rows: &[QueryRow]) -> String {
+        let mut sink = InsertSink::new(Vec::new(), dialect, options).unwrap();
+        sink.columns(&columns()).unwrap();
+        for r in rows {
+            sink.row(r).unwrap();
+        }
+        String::from_utf8(sink.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_sql_literals() {
+        let pg = PostgreSQLDialect::new();
+        let mysql = MySQLDialect::new();
+
+        assert_eq!(sql_literal(None, "TEXT", &pg), "NULL");
+        assert_eq!(sql_literal(Some("42"), "INT4", &pg), "42");
+        assert_eq!(sql_literal(Some("t"), "BOOL", &pg), "TRUE");
+        assert_eq!(sql_literal(Some("true"), "BOOLEAN", &mysql), "1");
+        assert_eq!(sql_literal(Some("O'Brien"), "TEXT", &pg), "'O''Brien'");
+        assert_eq!(sql_literal(Some(r"C:\temp"), "TEXT", &pg), r"'C:\temp'");
+        assert_eq!(sql_literal(Some(r"C:\temp"), "VARCHAR", &mysql), r"'C:\\temp'");
+        assert_eq!(sql_literal(Some(r"\x0aff"), "BYTEA", &pg), r"'\x0aff'::bytea");
+        assert_eq!(sql_literal(Some(r"\x0aff"), "LONGBLOB", &mysql), "X'0aff'");
+        assert_eq!(sql_literal(Some(r"\xzz"), "BYTEA", &pg), r"'\xzz'");
+    }
+
+    #[test]
+    fn test_single_row_statements() {
+        let output = write(
+            Box::new(PostgreSQLDialect::new()),
+            insert_options("users"),
+            &[row("1", Some("Alice")), row("2", None)],
+        );
+        assert_eq!(
+            output,
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES (1, 'Alice');\n\
+             INSERT INTO \"users\" (\"id\", \"name\") VALUES (2, NULL);\n"
+        );
+    }
+
+    #[test]
+    fn test_batched_statements() {
+        let mut options = insert_options("users");
+        options.rows_per_statement = 2;
+        let output = write(
+            Box::new(SQLiteDialect::new()),
+            options,
+            &[row("1", Some("a")), row("2", Some("b")), row("3", Some("c"))],
+        );
+        assert_eq!(
+            output,
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES\n    (1, 'a'),\n    (2, 'b');\n\
+             INSERT INTO \"users\" (\"id\", \"name\") VALUES (3, 'c');\n"
+        );
+    }
+
+    #[test]
+    fn test_postgres_upsert() {
+        let mut options = insert_options("users");
+        options.schema = Some("public".to_string());
+        options.on_conflict = ConflictAction::Update;
+        options.conflict_columns = vec!["id".to_string()];
+        let output = write(Box::new(PostgreSQLDialect::new()), options, &[row("1", Some("Alice"))]);
+        assert_eq!(
+            output,
+            "INSERT INTO \"public\".\"users\" (\"id\", \"name\") VALUES (1, 'Alice') \
+             ON CONFLICT (\"id\") DO UPDATE SET \"name\" = EXCLUDED.\"name\";\n"
+        );
+    }
+
+    #[test]
+    fn test_mysql_conflict_handling() {
+        let mut options = insert_options("users");
+        options.on_conflict = ConflictAction::Update;
+        let output = write(Box::new(MySQLDialect::new()), options.clone(), &[row("1", Some("Alice"))]);
+        assert_eq!(
+            output,
+            "INSERT INTO `users` (`id`, `name`) VALUES (1, 'Alice') \
+             ON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `name` = VALUES(`name`);\n"
+        );
+
+        options.on_conflict = ConflictAction::DoNothing;
+        let output = write(Box::new(MySQLDialect::new()), options, &[row("1", Some("Alice"))]);
+        assert_eq!(output, "INSERT IGNORE INTO `users` (`id`, `name`) VALUES (1, 'Alice');\n");
+    }
+
+    #[test]
+    fn test_update_requires_conflict_columns() {
+        let mut options = insert_options("users");
+        options.on_conflict = ConflictAction::Update;
+        assert!(options.validate(&SQLiteDialect::new()).is_err());
+        assert!(options.validate(&MySQLDialect::new()).is_ok());
+    }
+}
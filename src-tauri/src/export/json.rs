@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::io::Write;
+
+use super::{ExportSink, ProgressCallback, ProgressTracker};
+use crate::database::adapter::{ColumnInfo, QueryRow, RowSink};
+use crate::error::AppError;
+
+/// JSON export layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonFormat {
+    /// A single JSON array of row objects
+    #[default]
+    Json,
+    /// One JSON object per line (newline-delimited JSON)
+    Ndjson,
+}
+
+/// Convert a textual cell value into a JSON value based on its column type
+///
+/// Integers, floats, and booleans become JSON numbers/booleans and JSON columns are
+/// embedded as-is. Exact decimals (NUMERIC/DECIMAL) stay strings so no precision is lost.
+/// Values that don't parse as their declared type fall back to strings.
+pub fn typed_value(value: Option<&str>, data_type: &str) -> Value {
+    let Some(value) = value else {
+        return Value::Null;
+    };
+
+    let data_type = data_type.to_uppercase();
+    let base_type = data_type.split(['(', ' ']).next().unwrap_or("");
+
+    let typed = match base_type {
+        "INT2" | "INT4" | "INT8" | "SMALLINT" | "INTEGER" | "INT" | "BIGINT" | "TINYINT"
+        | "MEDIUMINT" | "SERIAL" | "BIGSERIAL" => value
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| value.parse::<u64>().map(Value::from))
+            .ok(),
+        "FLOAT4" | "FLOAT8" | "REAL" | "FLOAT" | "DOUBLE" => value
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        "BOOL" | "BOOLEAN" => match value.to_lowercase().as_str() {
+            "true" | "t" | "1" => Some(Value::Bool(true)),
+            "false" | "f" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        "JSON" | "JSONB" => serde_json::from_str(value).ok(),
+        _ => None,
+    };
+
+    typed.unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+/// Row sink that writes rows as JSON objects
+pub struct JsonSink<W: Write> {
+    writer: W,
+    format: JsonFormat,
+    columns: Vec<ColumnInfo>,
+    progress: ProgressTracker,
+}
+
+impl<W: Write> JsonSink<W> {
+    /// Create a new JSON sink
+    pub fn new(writer: W, format: JsonFormat) -> Self {
+        Self {
+            writer,
+            format,
+            columns: Vec::new(),
+            progress: ProgressTracker::default(),
+        }
+    }
+
+    /// Close the JSON array if needed and return the underlying writer
+    pub fn into_inner(mut self) -> Result<W, AppError> {
+        if self.format == JsonFormat::Json {
+            if self.progress.rows_written() == 0 {
+                self.writer.write_all(b"[]\n")?;
+            } else {
+                self.writer.write_all(b"\n]\n")?;
+            }
+        }
+        Ok(self.writer)
+    }
+
+    fn row_object(&self, row: &QueryRow) -> Value {
+        let mut obj = Map::new();
+        for (i, name) in row.columns.iter().enumerate() {
+            let data_type = self.columns.get(i).map(|c| c.data_type.as_str()).unwrap_or("");
+            let value = row.values.get(i).and_then(|v| v.as_deref());
+            obj.insert(name.clone(), typed_value(value, data_type));
+        }
+        Value::Object(obj)
+    }
+}
+
+impl<W: Write + Send> RowSink for JsonSink<W> {
+    fn columns(&mut self, columns: &[ColumnInfo]) -> Result<(), AppError> {
+        self.columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError> {
+        let object = self.row_object(row);
+
+        match self.format {
+            JsonFormat::Json => {
+                let separator: &[u8] = if self.progress.rows_written() == 0 { b"[\n" } else { b",\n" };
+                self.writer.write_all(separator)?;
+                serde_json::to_writer(&mut self.writer, &object)?;
+            }
+            JsonFormat::Ndjson => {
+                serde_json::to_writer(&mut self.writer, &object)?;
+                self.writer.write_all(b"\n")?;
+            }
+        }
+
+        self.progress.tick();
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> ExportSink for JsonSink<W> {
+    fn set_progress(&mut self, on_progress: ProgressCallback) {
+        self.progress.set_callback(on_progress);
+    }
+
+    fn finish(self: Box<Self>) -> Result<(), AppError> {
+        self.into_inner()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<ColumnInfo> {
+        vec![
+            ColumnInfo {
+                name: "id".to_string(),
+                data_type: "INT4".to_string(),
+                is_nullable: false,
+            },
+            ColumnInfo {
+                name: "active".to_string(),
+                data_type: "BOOL".to_string(),
+                is_nullable: true,
+            },
+            ColumnInfo {
+                name: "name".to_string(),
+                data_type: "TEXT".to_string(),
+                is_nullable: true,
+            },
+        ]
+    }
+
+    fn row(id: &str, active: Option<&str>, name: Option<&str>) -> QueryRow {
+        QueryRow {
+            columns: vec!["id".to_string(), "active".to_string(), "name".to_string()],
+            values: vec![
+                Some(id.to_string()),
+                active.map(|v| v.to_string()),
+                name.map(|v| v.to_string()),
+            ],
+        }
+    }
+
+    fn write(format: JsonFormat, rows: &[QueryRow]) -> String {
+        let mut sink = JsonSink::new(Vec::new(), format);
+        sink.columns(&columns()).unwrap();
+        for r in rows {
+            sink.row(r).unwrap();
+        }
+        String::from_utf8(sink.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_typed_values() {
+        assert_eq!(typed_value(None, "INT4"), Value::Null);
+        assert_eq!(typed_value(Some("42"), "INT4"), Value::from(42));
+        assert_eq!(typed_value(Some("42"), "int unsigned"), Value::from(42));
+        assert_eq!(typed_value(Some("1.5"), "FLOAT8"), Value::from(1.5));
+        assert_eq!(typed_value(Some("t"), "BOOL"), Value::Bool(true));
+        assert_eq!(typed_value(Some("0"), "BOOLEAN"), Value::Bool(false));
+        assert_eq!(typed_value(Some(r#"{"a":1}"#), "JSONB"), serde_json::json!({"a": 1}));
+        assert_eq!(typed_value(Some("12.30"), "NUMERIC"), Value::from("12.30"));
+        assert_eq!(typed_value(Some("abc"), "INT4"), Value::from("abc"));
+    }
+
+    #[test]
+    fn test_json_array() {
+        let output = write(
+            JsonFormat::Json,
+            &[row("1", Some("true"), Some("Alice")), row("2", None, None)],
+        );
+        let parsed: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!([
+                {"id": 1, "active": true, "name": "Alice"},
+                {"id": 2, "active": null, "name": null}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_json_empty_result() {
+        let output = write(JsonFormat::Json, &[]);
+        assert_eq!(output, "[]\n");
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let output = write(
+            JsonFormat::Ndjson,
+            &[row("1", Some("false"), Some("Alice")), row("2", None, Some("Bob"))],
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            serde_json::from_str::<Value>(lines[1]).unwrap(),
+            serde_json::json!({"id": 2, "active": null, "name": "Bob"})
+        );
+    }
+}
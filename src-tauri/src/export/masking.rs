@@ -0,0 +1,439 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use super::{ExportSink, ProgressCallback};
+use crate::database::adapter::{ColumnInfo, DatabaseType, QueryResult, QueryRow, RowSink};
+use crate::database::statement::{column_aliases, referenced_tables};
+use crate::error::AppError;
+
+const DEFAULT_REDACTION: &str = "***";
+const MASK_CHAR: char = '*';
+const RULES_FILE: &str = "masking_rules.json";
+
+/// How a masked column's values are rewritten
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum MaskStrategy {
+    /// Replace the value with its SHA-256 hex digest, so equal values still match
+    Hash,
+    /// Replace the value with a fixed string
+    Redact {
+        #[serde(default)]
+        replacement: Option<String>,
+    },
+    /// Keep the first and last few characters and mask the rest
+    Partial {
+        #[serde(default)]
+        keep_start: usize,
+        #[serde(default)]
+        keep_end: usize,
+    },
+    /// Keep the first character of the local part and the domain: `j***@example.com`
+    Email,
+}
+
+impl MaskStrategy {
+    /// Apply the strategy to a single non-NULL value
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            MaskStrategy::Hash => {
+                let digest = Sha256::digest(value.as_bytes());
+                digest.iter().map(|b| format!("{:02x}", b)).collect()
+            }
+            MaskStrategy::Redact { replacement } => replacement
+                .clone()
+                .unwrap_or_else(|| DEFAULT_REDACTION.to_string()),
+            MaskStrategy::Partial { keep_start, keep_end } => {
+                let chars: Vec<char> = value.chars().collect();
+                if chars.len() <= keep_start + keep_end {
+                    return MASK_CHAR.to_string().repeat(chars.len());
+                }
+                chars
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        if i < *keep_start || i >= chars.len() - keep_end {
+                            *c
+                        } else {
+                            MASK_CHAR
+                        }
+                    })
+                    .collect()
+            }
+            MaskStrategy::Email => match value.split_once('@') {
+                Some((local, domain)) => {
+                    let first: String = local.chars().take(1).collect();
+                    format!("{}{}@{}", first, DEFAULT_REDACTION, domain)
+                }
+                None => DEFAULT_REDACTION.to_string(),
+            },
+        }
+    }
+}
+
+/// Masking rule for a single column
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaskingRule {
+    /// Column name, matched case-insensitively
+    pub column: String,
+    #[serde(flatten)]
+    pub strategy: MaskStrategy,
+}
+
+/// Masking rule registered by the user, applied to query results by default
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredMaskingRule {
+    #[serde(default)]
+    pub id: String,
+    /// Profile the rule belongs to; `None` applies to every connection
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Table the column belongs to, matched case-insensitively; `None` matches any table
+    #[serde(default)]
+    pub table: Option<String>,
+    #[serde(flatten)]
+    pub rule: MaskingRule,
+}
+
+impl RegisteredMaskingRule {
+    /// Whether the rule covers a query on `profile_id` reading from `tables`
+    ///
+    /// `tables` is `None` when the tables of the query are unknown, in which case
+    /// table-specific rules apply as well.
+    fn applies_to(&self, profile_id: Option<&str>, tables: Option<&[String]>) -> bool {
+        let profile_matches = self.profile_id.is_none() || self.profile_id.as_deref() == profile_id;
+        let table_matches = match (self.table.as_deref(), tables) {
+            (Some(table), Some(tables)) => tables.iter().any(|t| t.eq_ignore_ascii_case(table)),
+            _ => true,
+        };
+        profile_matches && table_matches
+    }
+}
+
+/// The column rules that apply to one query
+pub fn rules_for(
+    registered: &[RegisteredMaskingRule],
+    profile_id: Option<&str>,
+    tables: Option<&[String]>,
+) -> Vec<MaskingRule> {
+    registered
+        .iter()
+        .filter(|r| r.applies_to(profile_id, tables))
+        .map(|r| r.rule.clone())
+        .collect()
+}
+
+/// Masker for the result of `sql`, with the registered rules for the tables it
+/// reads, also covering the names the query gives masked columns
+pub fn query_masker(
+    registered: &[RegisteredMaskingRule],
+    profile_id: Option<&str>,
+    sql: &str,
+    database_type: &DatabaseType,
+) -> Masker {
+    let tables = referenced_tables(sql, database_type);
+    Masker::new(rules_for(registered, profile_id, tables.as_deref()))
+        .with_aliases(&column_aliases(sql, database_type))
+}
+
+/// JSON file holding the registered masking rules
+pub struct MaskingRuleStore {
+    rules_path: PathBuf,
+}
+
+impl MaskingRuleStore {
+    /// Open the store in the app data directory
+    pub fn new(app_handle: &AppHandle) -> Result<Self, AppError> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Storage(format!("Could not resolve app data directory: {}", e)))?;
+
+        fs::create_dir_all(&app_data_dir).map_err(|e| {
+            AppError::Storage(format!("Failed to create app data directory: {}", e))
+        })?;
+
+        Ok(Self::with_path(app_data_dir.join(RULES_FILE)))
+    }
+
+    /// Open a store at a specific path
+    pub fn with_path(rules_path: PathBuf) -> Self {
+        Self { rules_path }
+    }
+
+    pub fn list(&self) -> Result<Vec<RegisteredMaskingRule>, AppError> {
+        if !self.rules_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read_to_string(&self.rules_path)
+            .map_err(|e| AppError::Storage(format!("Failed to read masking rules: {}", e)))?;
+        serde_json::from_str(&data)
+            .map_err(|e| AppError::Storage(format!("Failed to parse masking rules: {}", e)))
+    }
+
+    fn write(&self, rules: &[RegisteredMaskingRule]) -> Result<(), AppError> {
+        let data = serde_json::to_string_pretty(rules)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize masking rules: {}", e)))?;
+        fs::write(&self.rules_path, data)
+            .map_err(|e| AppError::Storage(format!("Failed to write masking rules: {}", e)))
+    }
+
+    /// Add a rule, or replace the rule with the same ID; a new ID is assigned when empty
+    pub fn save(&self, mut rule: RegisteredMaskingRule) -> Result<RegisteredMaskingRule, AppError> {
+        if rule.rule.column.trim().is_empty() {
+            return Err(AppError::Validation("Masking rule needs a column".to_string()));
+        }
+        if rule.id.is_empty() {
+            rule.id = Uuid::new_v4().to_string();
+        }
+
+        let mut rules = self.list()?;
+        match rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule.clone(),
+            None => rules.push(rule.clone()),
+        }
+        self.write(&rules)?;
+
+        Ok(rule)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        let mut rules = self.list()?;
+        let before = rules.len();
+        rules.retain(|r| r.id != id);
+        if rules.len() == before {
+            return Err(AppError::NotFound(format!("Masking rule {} not found", id)));
+        }
+        self.write(&rules)
+    }
+}
+
+/// Applies a set of masking rules to rows
+#[derive(Debug, Clone, Default)]
+pub struct Masker {
+    rules: Vec<MaskingRule>,
+}
+
+impl Masker {
+    pub fn new(rules: Vec<MaskingRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Mask aliased columns like their source, so `SELECT email AS e` is masked
+    /// like `email`
+    ///
+    /// `aliases` are output names with the identifiers of the expression they
+    /// name, as from `column_aliases`; an alias referring to any masked column
+    /// gets that column's strategy.
+    pub fn with_aliases(mut self, aliases: &[(String, Vec<String>)]) -> Self {
+        // Aliases of aliases, as with subqueries, need another pass
+        loop {
+            let mut added = false;
+            for (alias, sources) in aliases {
+                if self.strategy_for(alias).is_some() {
+                    continue;
+                }
+                if let Some(strategy) = sources.iter().find_map(|source| self.strategy_for(source)).cloned() {
+                    self.rules.push(MaskingRule { column: alias.clone(), strategy });
+                    added = true;
+                }
+            }
+            if !added {
+                return self;
+            }
+        }
+    }
+
+    fn strategy_for(&self, column: &str) -> Option<&MaskStrategy> {
+        self.rules
+            .iter()
+            .find(|rule| rule.column.eq_ignore_ascii_case(column))
+            .map(|rule| &rule.strategy)
+    }
+
+    /// Names of the given columns that this masker rewrites
+    pub fn masked_columns(&self, columns: &[ColumnInfo]) -> Vec<String> {
+        columns
+            .iter()
+            .filter(|c| self.strategy_for(&c.name).is_some())
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    /// Mask the values of a row in place; NULLs are left untouched
+    pub fn mask_row(&self, row: &mut QueryRow) {
+        for (name, value) in row.columns.iter().zip(row.values.iter_mut()) {
+            if let (Some(strategy), Some(v)) = (self.strategy_for(name), value.as_mut()) {
+                *v = strategy.apply(v);
+            }
+        }
+    }
+
+    /// Mask every row of a query result in place
+    pub fn mask_result(&self, result: &mut QueryResult) {
+        for row in result.rows.iter_mut() {
+            self.mask_row(row);
+        }
+    }
+}
+
+/// Export sink that masks rows before passing them to another sink
+pub struct MaskingSink {
+    inner: Box<dyn ExportSink>,
+    masker: Masker,
+}
+
+impl MaskingSink {
+    pub fn new(inner: Box<dyn ExportSink>, masker: Masker) -> Self {
+        Self { inner, masker }
+    }
+}
+
+impl RowSink for MaskingSink {
+    fn columns(&mut self, columns: &[ColumnInfo]) -> Result<(), AppError> {
+        self.inner.columns(columns)
+    }
+
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError> {
+        let mut row = row.clone();
+        self.masker.mask_row(&mut row);
+        self.inner.row(&row)
+    }
+}
+
+impl ExportSink for MaskingSink {
+    fn set_progress(&mut self, on_progress: ProgressCallback) {
+        self.inner.set_progress(on_progress);
+    }
+
+    fn finish(self: Box<Self>) -> Result<(), AppError> {
+        self.inner.finish()
+    }
+}
+
+/// Wrap a sink with masking when the masker has any rules
+pub fn with_masking(sink: Box<dyn ExportSink>, masker: Masker) -> Box<dyn ExportSink> {
+    if masker.is_empty() {
+        sink
+    } else {
+        Box::new(MaskingSink::new(sink, masker))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategies() {
+        assert_eq!(
+            MaskStrategy::Hash.apply("alice"),
+            "2bd806c97f0e00af1a1fc3328fa763a9269723c8db8fac4f93af71db186d6e90"
+        );
+        assert_eq!(MaskStrategy::Redact { replacement: None }.apply("secret"), "***");
+        assert_eq!(
+            MaskStrategy::Redact { replacement: Some("[hidden]".to_string()) }.apply("secret"),
+            "[hidden]"
+        );
+        let partial = MaskStrategy::Partial { keep_start: 1, keep_end: 4 };
+        assert_eq!(partial.apply("alice@example.com"), "a************.com");
+        assert_eq!(partial.apply("abc"), "***");
+        assert_eq!(MaskStrategy::Email.apply("jane@example.com"), "j***@example.com");
+        assert_eq!(MaskStrategy::Email.apply("not an email"), "***");
+    }
+
+    fn registered(profile_id: Option<&str>, table: Option<&str>, column: &str) -> RegisteredMaskingRule {
+        RegisteredMaskingRule {
+            id: String::new(),
+            profile_id: profile_id.map(str::to_string),
+            table: table.map(str::to_string),
+            rule: MaskingRule {
+                column: column.to_string(),
+                strategy: MaskStrategy::Email,
+            },
+        }
+    }
+
+    #[test]
+    fn test_rules_for() {
+        let rules = vec![
+            registered(Some("prod"), Some("users"), "email"),
+            registered(None, None, "ssn"),
+            registered(Some("staging"), None, "phone"),
+        ];
+        let columns = |rules: Vec<MaskingRule>| -> Vec<String> { rules.into_iter().map(|r| r.column).collect() };
+
+        let users = vec!["Users".to_string()];
+        assert_eq!(columns(rules_for(&rules, Some("prod"), Some(&users))), vec!["email", "ssn"]);
+        assert_eq!(columns(rules_for(&rules, Some("prod"), Some(&["orders".to_string()]))), vec!["ssn"]);
+        assert_eq!(columns(rules_for(&rules, Some("prod"), None)), vec!["email", "ssn"]);
+        assert_eq!(columns(rules_for(&rules, None, None)), vec!["ssn"]);
+    }
+
+    #[test]
+    fn test_rule_store() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = MaskingRuleStore::with_path(temp_dir.path().join(RULES_FILE));
+
+        let saved = store.save(registered(None, Some("users"), "email")).unwrap();
+        assert!(!saved.id.is_empty());
+        store.save(RegisteredMaskingRule { table: None, ..saved.clone() }).unwrap();
+
+        let rules = store.list().unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].table, None);
+
+        store.delete(&saved.id).unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(matches!(store.delete(&saved.id), Err(AppError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_mask_row() {
+        let masker = Masker::new(vec![MaskingRule {
+            column: "Email".to_string(),
+            strategy: MaskStrategy::Redact { replacement: None },
+        }]);
+        let mut row = QueryRow {
+            columns: vec!["id".to_string(), "email".to_string(), "EMAIL".to_string()],
+            values: vec![Some("1".to_string()), Some("a@b.c".to_string()), None],
+        };
+        masker.mask_row(&mut row);
+        assert_eq!(row.values, vec![Some("1".to_string()), Some("***".to_string()), None]);
+    }
+
+    #[test]
+    fn test_query_masker() {
+        let rules = vec![registered(None, Some("users"), "email"), registered(None, None, "ssn")];
+        let postgres = DatabaseType::PostgreSQL;
+        let masked = |sql: &str, column: &str| {
+            let columns = vec![ColumnInfo { name: column.to_string(), data_type: "text".to_string(), is_nullable: true }];
+            !query_masker(&rules, None, sql, &postgres).masked_columns(&columns).is_empty()
+        };
+
+        assert!(masked("SELECT email FROM users", "email"));
+        assert!(masked("SELECT email AS e FROM users", "e"));
+        assert!(masked("SELECT upper(email) contact FROM users", "contact"));
+        assert!(masked("SELECT y FROM (SELECT x AS y FROM (SELECT ssn AS x FROM people) a) b", "y"));
+        assert!(!masked("SELECT email AS e FROM orders", "e"));
+        assert!(!masked("SELECT name AS e FROM users", "e"));
+    }
+
+    #[test]
+    fn test_rule_deserialization() {
+        let rule: MaskingRule = serde_json::from_str(
+            r#"{"column": "phone", "strategy": "partial", "keep_end": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(rule.strategy, MaskStrategy::Partial { keep_start: 0, keep_end: 2 });
+    }
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+use crate::database::adapter::RowSink;
+use crate::error::AppError;
+
+pub mod arrow;
+pub mod clipboard;
+pub mod csv;
+pub mod graphql;
+pub mod ddl;
+pub mod insert;
+pub mod json;
+pub mod masking;
+
+/// Number of rows written between progress callbacks
+pub const PROGRESS_INTERVAL: u64 = 1000;
+
+/// Callback invoked with the number of rows written so far
+pub type ProgressCallback = Box<dyn FnMut(u64) + Send>;
+
+/// Row sink that writes an export file
+pub trait ExportSink: RowSink {
+    /// Register a callback that is invoked every `PROGRESS_INTERVAL` rows
+    fn set_progress(&mut self, on_progress: ProgressCallback);
+
+    /// Write any trailing output and flush the underlying writer
+    fn finish(self: Box<Self>) -> Result<(), AppError>;
+}
+
+/// Counts written rows and reports progress at a fixed interval
+#[derive(Default)]
+pub struct ProgressTracker {
+    rows_written: u64,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl ProgressTracker {
+    pub fn set_callback(&mut self, on_progress: ProgressCallback) {
+        self.on_progress = Some(on_progress);
+    }
+
+    /// Record one written row
+    pub fn tick(&mut self) {
+        self.rows_written += 1;
+        if self.rows_written.is_multiple_of(PROGRESS_INTERVAL) {
+            if let Some(on_progress) = self.on_progress.as_mut() {
+                on_progress(self.rows_written);
+            }
+        }
+    }
+
+    pub fn rows_written(&self) -> u64 {
+        self.rows_written
+    }
+}
+
+/// Progress update emitted while an export is running
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub path: String,
+    pub rows_written: u64,
+    pub finished: bool,
+}
+
+/// Summary returned once an export has completed
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub rows_written: u64,
+    pub bytes_written: u64,
+    pub execution_time: u64, // in milliseconds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_progress_tracker() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let reported_clone = reported.clone();
+
+        let mut tracker = ProgressTracker::default();
+        tracker.set_callback(Box::new(move |n| reported_clone.lock().unwrap().push(n)));
+
+        for _ in 0..(PROGRESS_INTERVAL * 2 + 5) {
+            tracker.tick();
+        }
+
+        assert_eq!(tracker.rows_written(), PROGRESS_INTERVAL * 2 + 5);
+        assert_eq!(*reported.lock().unwrap(), vec![PROGRESS_INTERVAL, PROGRESS_INTERVAL * 2]);
+    }
+}
@@ -0,0 +1,216 @@
+use chrono::{DateTime, Local, Utc};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cloud::CloudDestination;
+use crate::error::AppError;
+
+pub mod runs;
+
+const JOBS_FILE: &str = "jobs.json";
+
+/// Directory under the app data directory holding jobs and their runs
+pub const JOBS_DIR: &str = "jobs";
+
+/// How many runs of a job, with their results, to keep
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunRetention {
+    /// Keep at most this many runs
+    pub keep_last: Option<usize>,
+    /// Delete runs older than this many days
+    pub max_age_days: Option<u32>,
+}
+
+impl Default for RunRetention {
+    fn default() -> Self {
+        Self {
+            keep_last: Some(50),
+            max_age_days: None,
+        }
+    }
+}
+
+/// A saved query run on a schedule against one profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryJob {
+    pub id: String,
+    pub name: String,
+    pub profile_id: String,
+    pub sql: String,
+    /// Five-field cron expression, evaluated in local time (e.g. "*/15 * * * *")
+    pub cron: String,
+    pub enabled: bool,
+    /// Statements that can destroy data run only when set, as nobody is there to confirm them
+    #[serde(default)]
+    pub allow_destructive: bool,
+    /// Keep the rows of the last query of each run
+    #[serde(default)]
+    pub keep_results: bool,
+    #[serde(default)]
+    pub retention: RunRetention,
+    /// Where the rows of the last query of each run are uploaded to
+    #[serde(default)]
+    pub destination: Option<CloudDestination>,
+    pub created_at: DateTime<Utc>,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl QueryJob {
+    /// Check the name, SQL, cron expression and destination
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::Validation("Job name is required".to_string()));
+        }
+        if self.sql.trim().is_empty() {
+            return Err(AppError::Validation("Job SQL is required".to_string()));
+        }
+        parse_cron(&self.cron)?;
+        if let Some(destination) = &self.destination {
+            destination.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Next time the job fires after its last run (or creation)
+    pub fn next_run(&self) -> Result<DateTime<Utc>, AppError> {
+        let after = self.last_run.unwrap_or(self.created_at).with_timezone(&Local);
+        parse_cron(&self.cron)?
+            .find_next_occurrence(&after, false)
+            .map(|next| next.with_timezone(&Utc))
+            .map_err(|e| AppError::Validation(format!("Invalid cron expression: {}", e)))
+    }
+
+    /// Whether the job should run at `now`; runs missed while the app was closed
+    /// are made up for once
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.next_run().is_ok_and(|next| next <= now)
+    }
+}
+
+fn parse_cron(expression: &str) -> Result<Cron, AppError> {
+    Cron::new(expression)
+        .parse()
+        .map_err(|e| AppError::Validation(format!("Invalid cron expression: {}", e)))
+}
+
+/// JSON file holding the configured query jobs
+pub struct JobStore {
+    jobs_path: PathBuf,
+}
+
+impl JobStore {
+    /// Open the store in `jobs_dir`, creating the directory if needed
+    pub fn new(jobs_dir: &Path) -> Result<Self, AppError> {
+        fs::create_dir_all(jobs_dir)
+            .map_err(|e| AppError::Storage(format!("Failed to create jobs directory: {}", e)))?;
+
+        Ok(Self {
+            jobs_path: jobs_dir.join(JOBS_FILE),
+        })
+    }
+
+    pub fn list(&self) -> Result<Vec<QueryJob>, AppError> {
+        if !self.jobs_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read_to_string(&self.jobs_path)
+            .map_err(|e| AppError::Storage(format!("Failed to read jobs: {}", e)))?;
+        serde_json::from_str(&data).map_err(|e| AppError::Storage(format!("Failed to parse jobs: {}", e)))
+    }
+
+    pub fn get(&self, id: &str) -> Result<QueryJob, AppError> {
+        self.list()?
+            .into_iter()
+            .find(|j| j.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Job {} not found", id)))
+    }
+
+    /// Add or replace a job
+    pub fn save(&self, job: QueryJob) -> Result<QueryJob, AppError> {
+        job.validate()?;
+
+        let mut jobs = self.list()?;
+        jobs.retain(|j| j.id != job.id);
+        jobs.push(job.clone());
+        self.save_all(&jobs)?;
+
+        Ok(job)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        let mut jobs = self.list()?;
+        let before = jobs.len();
+        jobs.retain(|j| j.id != id);
+        if jobs.len() == before {
+            return Err(AppError::NotFound(format!("Job {} not found", id)));
+        }
+        self.save_all(&jobs)
+    }
+
+    fn save_all(&self, jobs: &[QueryJob]) -> Result<(), AppError> {
+        let data = serde_json::to_string_pretty(jobs)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize jobs: {}", e)))?;
+        fs::write(&self.jobs_path, data).map_err(|e| AppError::Storage(format!("Failed to write jobs: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn job(cron: &str) -> QueryJob {
+        QueryJob {
+            id: "daily-report".to_string(),
+            name: "Daily report".to_string(),
+            profile_id: "profile-1".to_string(),
+            sql: "SELECT count(*) FROM orders".to_string(),
+            cron: cron.to_string(),
+            enabled: true,
+            allow_destructive: false,
+            keep_results: true,
+            retention: RunRetention::default(),
+            destination: None,
+            created_at: Utc::now() - Duration::days(2),
+            last_run: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn test_due_job() {
+        let mut daily = job("0 6 * * *");
+        assert!(daily.is_due(Utc::now()));
+
+        daily.last_run = Some(Utc::now());
+        assert!(!daily.is_due(Utc::now()));
+
+        daily.enabled = false;
+        daily.last_run = None;
+        assert!(!daily.is_due(Utc::now()));
+    }
+
+    #[test]
+    fn test_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = JobStore::new(&temp_dir.path().join(JOBS_DIR)).unwrap();
+
+        assert!(store.save(job("every morning")).is_err());
+        assert!(store.save(QueryJob { sql: " ".to_string(), ..job("0 6 * * *") }).is_err());
+
+        store.save(job("0 6 * * *")).unwrap();
+        store.save(QueryJob { enabled: false, ..job("0 7 * * *") }).unwrap();
+        let jobs = store.list().unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].cron, "0 7 * * *");
+
+        store.delete("daily-report").unwrap();
+        assert!(matches!(store.get("daily-report"), Err(AppError::NotFound(_))));
+    }
+}
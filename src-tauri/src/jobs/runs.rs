@@ -0,0 +1,217 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::RunRetention;
+use crate::database::adapter::ColumnInfo;
+use crate::error::AppError;
+
+const RUNS_FILE: &str = "runs.json";
+const RESULTS_DIR: &str = "results";
+
+/// Rows kept from the result of a run; the rest are dropped
+pub const MAX_RESULT_ROWS: usize = 1000;
+
+/// One execution of a query job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    pub id: String,
+    pub job_id: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Whether the run was started by the scheduler rather than by hand
+    pub scheduled: bool,
+    /// Rows returned by the last query of the job
+    pub rows_returned: Option<u64>,
+    pub rows_affected: u64,
+    pub error: Option<String>,
+    /// Whether the result was kept and can be loaded with `RunStore::result`
+    pub has_result: bool,
+    /// Where the result was uploaded to, for jobs with a destination
+    #[serde(default)]
+    pub exported_to: Option<String>,
+}
+
+/// Rows of the last query of a run, masked like results in the editor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobResult {
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Vec<Option<String>>>,
+    /// Whether rows past `MAX_RESULT_ROWS` were dropped
+    pub truncated: bool,
+}
+
+/// Run history of all jobs, with the kept results stored one file per run
+pub struct RunStore {
+    runs_path: PathBuf,
+    results_dir: PathBuf,
+}
+
+impl RunStore {
+    /// Open the store in `jobs_dir`, creating its directories if needed
+    pub fn new(jobs_dir: &Path) -> Result<Self, AppError> {
+        let results_dir = jobs_dir.join(RESULTS_DIR);
+        fs::create_dir_all(&results_dir)
+            .map_err(|e| AppError::Storage(format!("Failed to create job results directory: {}", e)))?;
+
+        Ok(Self {
+            runs_path: jobs_dir.join(RUNS_FILE),
+            results_dir,
+        })
+    }
+
+    /// Runs of one job, or of all jobs, newest first
+    pub fn list(&self, job_id: Option<&str>, limit: usize) -> Result<Vec<JobRun>, AppError> {
+        let mut runs: Vec<JobRun> = self
+            .load()?
+            .into_iter()
+            .filter(|r| job_id.is_none_or(|id| r.job_id == id))
+            .collect();
+        runs.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        runs.truncate(limit);
+        Ok(runs)
+    }
+
+    pub fn record(&self, mut run: JobRun, result: Option<&JobResult>) -> Result<JobRun, AppError> {
+        run.has_result = result.is_some();
+        if let Some(result) = result {
+            let data = serde_json::to_vec(result)
+                .map_err(|e| AppError::Storage(format!("Failed to serialize job result: {}", e)))?;
+            fs::write(self.result_path(&run.id), data)
+                .map_err(|e| AppError::Storage(format!("Failed to write job result: {}", e)))?;
+        }
+
+        let mut runs = self.load()?;
+        runs.push(run.clone());
+        self.save_all(&runs)?;
+        Ok(run)
+    }
+
+    /// Kept result of a run
+    pub fn result(&self, run_id: &str) -> Result<JobResult, AppError> {
+        let path = self.result_path(run_id);
+        if !path.exists() {
+            return Err(AppError::NotFound(format!("No result kept for run {}", run_id)));
+        }
+
+        let data = fs::read(path).map_err(|e| AppError::Storage(format!("Failed to read job result: {}", e)))?;
+        serde_json::from_slice(&data).map_err(|e| AppError::Storage(format!("Failed to parse job result: {}", e)))
+    }
+
+    /// Delete the runs of a job that fall outside its retention policy; returns how many
+    pub fn apply_retention(&self, job_id: &str, retention: &RunRetention, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let newest_first = self.list(Some(job_id), usize::MAX)?;
+        let expired: Vec<String> = newest_first
+            .iter()
+            .enumerate()
+            .filter(|(index, run)| {
+                let too_many = retention.keep_last.is_some_and(|keep| *index >= keep);
+                let too_old = retention
+                    .max_age_days
+                    .is_some_and(|days| now - run.started_at > Duration::days(days as i64));
+                too_many || too_old
+            })
+            .map(|(_, run)| run.id.clone())
+            .collect();
+
+        self.remove(|run| expired.contains(&run.id))?;
+        Ok(expired.len())
+    }
+
+    /// Delete every run of a job, when the job itself is deleted
+    pub fn delete_job(&self, job_id: &str) -> Result<(), AppError> {
+        self.remove(|run| run.job_id == job_id)
+    }
+
+    fn remove(&self, matches: impl Fn(&JobRun) -> bool) -> Result<(), AppError> {
+        let (removed, kept): (Vec<JobRun>, Vec<JobRun>) = self.load()?.into_iter().partition(|run| matches(run));
+        if removed.is_empty() {
+            return Ok(());
+        }
+
+        for run in removed.iter().filter(|run| run.has_result) {
+            let path = self.result_path(&run.id);
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        self.save_all(&kept)
+    }
+
+    fn result_path(&self, run_id: &str) -> PathBuf {
+        self.results_dir.join(format!("{}.json", run_id))
+    }
+
+    fn load(&self) -> Result<Vec<JobRun>, AppError> {
+        if !self.runs_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read_to_string(&self.runs_path)
+            .map_err(|e| AppError::Storage(format!("Failed to read job runs: {}", e)))?;
+        serde_json::from_str(&data).map_err(|e| AppError::Storage(format!("Failed to parse job runs: {}", e)))
+    }
+
+    fn save_all(&self, runs: &[JobRun]) -> Result<(), AppError> {
+        let data = serde_json::to_string(runs)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize job runs: {}", e)))?;
+        fs::write(&self.runs_path, data).map_err(|e| AppError::Storage(format!("Failed to write job runs: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(id: &str, job_id: &str, age_days: i64, now: DateTime<Utc>) -> JobRun {
+        JobRun {
+            id: id.to_string(),
+            job_id: job_id.to_string(),
+            started_at: now - Duration::days(age_days),
+            duration_ms: 5,
+            scheduled: true,
+            rows_returned: Some(1),
+            rows_affected: 0,
+            error: None,
+            has_result: false,
+            exported_to: None,
+        }
+    }
+
+    #[test]
+    fn test_history_and_retention() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = RunStore::new(temp_dir.path()).unwrap();
+        let now = Utc::now();
+        let result = JobResult {
+            columns: Vec::new(),
+            rows: vec![vec![Some("42".to_string())]],
+            truncated: false,
+        };
+
+        for age in [3, 0, 10, 1] {
+            store.record(run(&format!("run-{}", age), "daily", age, now), Some(&result)).unwrap();
+        }
+        store.record(run("other", "hourly", 30, now), None).unwrap();
+
+        let runs = store.list(Some("daily"), 10).unwrap();
+        let ids: Vec<&str> = runs.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["run-0", "run-1", "run-3", "run-10"]);
+        assert_eq!(store.result("run-10").unwrap().rows, result.rows);
+
+        let keep_two = RunRetention { keep_last: Some(2), max_age_days: None };
+        assert_eq!(store.apply_retention("daily", &keep_two, now).unwrap(), 2);
+        assert_eq!(store.list(Some("daily"), 10).unwrap().len(), 2);
+        assert!(matches!(store.result("run-10"), Err(AppError::NotFound(_))));
+
+        // Other jobs keep their runs
+        let week = RunRetention { keep_last: None, max_age_days: Some(7) };
+        assert_eq!(store.apply_retention("daily", &week, now).unwrap(), 0);
+        assert_eq!(store.list(None, 10).unwrap().len(), 3);
+
+        store.delete_job("daily").unwrap();
+        assert_eq!(store.list(None, 10).unwrap().len(), 1);
+    }
+}
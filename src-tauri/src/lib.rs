@@ -1,8 +1,23 @@
+mod audit;
+mod automation;
+mod backup;
+mod cloud;
+mod codegen;
 mod commands;
-mod database;
-mod error;
+mod diagnostics;
+mod export;
+mod jobs;
 mod logger;
-mod profile;
+mod migrations;
+mod notebook;
+mod notifications;
+mod query_log;
+mod settings;
+mod telemetry;
+mod transfer;
+
+use dataforge_core::{database, error, i18n, metrics, profile, redact};
+use dataforge_core::{log_debug, log_error, log_info, log_warn};
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -38,9 +53,9 @@ async fn test_database_connection() -> Result<String, String> {
 pub fn run() {
     // Initialize logger
     let log_level = if cfg!(debug_assertions) {
-        logger::LogLevel::Debug
+        tracing::Level::DEBUG
     } else {
-        logger::LogLevel::Info
+        tracing::Level::INFO
     };
 
     // Get app data directory for log file
@@ -49,15 +64,41 @@ pub fn run() {
             .join(".dataforge")
             .join("logs");
         std::fs::create_dir_all(&log_dir).ok();
-        Some(log_dir.join("dataforge.log"))
+        Some(log_dir.join("dataforge.jsonl"))
     } else {
         None
     };
 
+    // Settings for the log filter and telemetry export
+    let (app_settings, settings_error) = match settings::SettingsStore::open_default().and_then(|store| store.load()) {
+        Ok(app_settings) => (app_settings, None),
+        Err(e) => (settings::AppSettings::default(), Some(e)),
+    };
+
+    i18n::set_locale(app_settings.locale);
+
+    // Export traces and metrics when configured in the settings
+    let (tracer, telemetry_error) = match telemetry::init(&app_settings.telemetry) {
+        Ok(tracer) => (tracer, None),
+        Err(e) => (None, Some(e)),
+    };
+
     // Initialize the logger
-    if let Err(e) = logger::init_logger(log_level, log_file) {
+    if let Err(e) = logger::init_logger(log_level, log_file.as_deref(), tracer, app_settings.log_filter.as_deref()) {
         eprintln!("Failed to initialize logger: {}", e);
     }
+    if let Some(e) = settings_error {
+        log_error!("main", "Failed to load settings, using defaults: {}", e);
+    }
+    if let Some(e) = telemetry_error {
+        log_error!("main", "Telemetry export disabled: {}", e);
+    }
+
+    // Write a crash report for panics, next to the logs
+    match settings::data_dir() {
+        Ok(dir) => diagnostics::install_panic_hook(dir.join("crashes")),
+        Err(e) => log_error!("main", "Crash reports disabled: {}", e),
+    }
 
     log_info!("main", "Starting DataForge application");
 
@@ -82,23 +123,167 @@ pub fn run() {
             commands::execute_query,
             commands::get_database_metadata,
             commands::list_database_tables,
+            commands::get_schema,
+            commands::get_exact_row_count,
+            commands::stream::stream_query_batches,
+            commands::stream::spool_query,
+            commands::stream::fetch_spooled_rows,
+            commands::stream::fetch_spooled_arrow,
+            commands::stream::release_spooled_result,
             commands::cancel_connection,
             commands::get_table_indexes,
             commands::generate_select_query,
+            commands::generate_alter_table,
             commands::get_database_capabilities,
             commands::get_query_templates,
             commands::get_dialect_info,
+            commands::export::export_result_csv,
+            commands::export::export_result_json,
+            commands::export::export_result_inserts,
+            commands::export::format_result_text,
+            commands::export::export_schema_ddl,
+            commands::export::generate_graphql_schema,
+            commands::codegen::generate_typescript_types,
+            commands::codegen::generate_rust_models,
+            commands::codegen::copy_query_as_code,
+            commands::notebook::create_notebook,
+            commands::notebook::open_notebook,
+            commands::notebook::save_notebook,
+            commands::notebook::run_notebook_cell,
+            commands::notebook::run_notebook,
+            commands::notebook::export_notebook,
+            commands::workspace::get_workspace,
+            commands::workspace::save_workspace,
+            commands::workspace::clear_workspace,
+            commands::workspace::restore_workspace,
+            commands::bookmarks::list_bookmarks,
+            commands::bookmarks::save_bookmark,
+            commands::bookmarks::delete_bookmark,
+            commands::bookmarks::record_object_opened,
+            commands::bookmarks::recent_objects,
+            commands::demo::start_demo,
+            commands::transfer::copy_table,
+            commands::migrations::get_migration_status,
+            commands::migrations::apply_migrations,
+            commands::migrations::rollback_migrations,
+            commands::masking::list_masking_rules,
+            commands::masking::save_masking_rule,
+            commands::masking::delete_masking_rule,
+            commands::masking::unmask_query,
+            commands::audit::query_audit_log,
+            commands::audit::export_audit_log,
+            commands::audit::verify_audit_log,
+            commands::backup::create_backup,
+            commands::backup::list_backups,
+            commands::backup::delete_backup,
+            commands::backup::restore_backup,
+            commands::backup::backup_database,
+            commands::backup::check_database_integrity,
+            commands::backup::list_backup_schedules,
+            commands::backup::save_backup_schedule,
+            commands::backup::delete_backup_schedule,
+            commands::jobs::list_jobs,
+            commands::jobs::save_job,
+            commands::jobs::delete_job,
+            commands::jobs::run_job_now,
+            commands::jobs::list_job_runs,
+            commands::jobs::get_job_run_result,
+            commands::cloud::start_google_authorization,
+            commands::cloud::get_google_authorization,
+            commands::cloud::revoke_google_authorization,
             commands::profile::create_profile,
             commands::profile::list_profiles,
             commands::profile::get_profile,
             commands::profile::update_profile,
             commands::profile::delete_profile,
+            commands::profile::set_profile_pinned,
+            commands::profile::reorder_profiles,
+            commands::profile::export_profiles,
+            commands::profile::import_profiles,
+            commands::profile::recent_profiles,
+            commands::profile::get_profile_usage,
+            commands::profile::get_lock_status,
+            commands::profile::unlock_profiles,
+            commands::profile::lock_profiles,
+            commands::profile::record_activity,
+            commands::profile::get_statement_rules,
+            commands::history::get_query_history,
+            commands::history::clear_query_history,
+            commands::history::list_snippets,
+            commands::history::save_snippet,
+            commands::history::expand_snippet,
+            commands::history::delete_snippet,
+            commands::automation::list_automations,
+            commands::automation::save_automation,
+            commands::automation::restore_automation,
+            commands::automation::delete_automation,
+            commands::automation::run_automation,
+            commands::automation::cancel_automations,
+            commands::query_log::tail_query_log,
+            commands::result_cache::execute_cached_query,
+            commands::result_cache::invalidate_result_cache,
+            commands::logs::get_logs,
+            commands::logs::get_log_filter,
+            commands::logs::set_log_filter,
+            commands::metrics::get_metrics,
+            commands::activity::list_sessions,
+            commands::activity::watch_sessions,
+            commands::activity::stop_watching_sessions,
+            commands::activity::replication_status,
+            commands::activity::engine_health,
+            commands::activity::end_session,
+            commands::listen::listen_channels,
+            commands::listen::unlisten_channels,
+            commands::maintenance::run_maintenance,
+            commands::maintenance::index_report,
+            commands::maintenance::table_health_report,
+            commands::maintenance::storage_usage,
+            commands::users::preview_user_change,
+            commands::users::apply_user_change,
+            commands::browse::browse_table,
+            commands::browse::browse_query,
+            commands::browse::save_table_rows,
+            commands::browse::undo_last_edit,
+            commands::summary::summarize,
+            commands::column_stats::profile_table,
+            commands::templates::list_templates,
+            commands::templates::search_templates,
+            commands::templates::list_data_types,
+            commands::templates::expand_template,
+            commands::templates::render_template,
+            commands::templates::execute_template,
+            commands::templates::save_template,
+            commands::templates::delete_template,
+            commands::templates::export_templates,
+            commands::templates::import_templates,
+            commands::diagnostics::create_diagnostics_bundle,
+            commands::settings::get_telemetry_settings,
+            commands::settings::save_telemetry_settings,
+            commands::settings::get_locale,
+            commands::settings::set_locale,
+            commands::settings::get_result_memory_limit,
+            commands::settings::set_result_memory_limit,
+            commands::notifications::get_notification_settings,
+            commands::notifications::save_notification_settings,
+            commands::notifications::send_test_notification,
+            commands::profile::enable_master_password,
+            commands::profile::disable_master_password,
+            commands::profile::set_profile_lock_timeout,
+            commands::profile::get_secret_storage_status,
             commands::profile::connect_with_profile,
         ])
-        .setup(|_app| {
+        .setup(|app| {
+            commands::backup::start_backup_scheduler(app.handle().clone());
+            commands::jobs::start_job_scheduler(app.handle().clone());
+            commands::profile::start_profile_lock_timer(app.handle().clone());
             log_info!("main", "Application setup complete");
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                telemetry::shutdown();
+            }
+        });
 }
@@ -0,0 +1,462 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::database::adapter::DatabaseAdapter;
+use crate::database::sql_utils::split_sql_statements;
+use crate::error::AppError;
+use crate::export::insert::sql_literal;
+
+/// Table used to record applied migrations in the target database
+pub const TRACKING_TABLE: &str = "_dataforge_migrations";
+
+/// A versioned migration loaded from `<version>_<name>.up.sql` / `.down.sql` files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub version: u64,
+    pub name: String,
+    pub up_sql: String,
+    pub down_sql: Option<String>,
+}
+
+impl Migration {
+    /// SHA-256 of the up script, used to detect edits after a migration was applied
+    pub fn checksum(&self) -> String {
+        Sha256::digest(self.up_sql.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+/// A migration as recorded in the tracking table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    pub version: u64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    /// Not yet applied
+    Pending,
+    /// Applied and unchanged
+    Applied,
+    /// Applied, but the up script has changed since
+    Modified,
+    /// Recorded as applied, but its files are gone
+    Missing,
+}
+
+/// Status of a single migration
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: u64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<String>,
+    pub reversible: bool,
+}
+
+/// Parse `<version>_<name>.<up|down>.sql` into its parts
+fn parse_file_name(file_name: &str) -> Option<(u64, String, bool)> {
+    let stem = file_name.strip_suffix(".sql")?;
+    let (stem, is_up) = if let Some(stem) = stem.strip_suffix(".up") {
+        (stem, true)
+    } else {
+        (stem.strip_suffix(".down")?, false)
+    };
+    let (version, name) = stem.split_once('_')?;
+    let version = version.parse().ok()?;
+    Some((version, name.to_string(), is_up))
+}
+
+/// Load all migrations from a directory, ordered by version
+pub fn load_migrations(dir: &Path) -> Result<Vec<Migration>, AppError> {
+    let mut ups: HashMap<u64, (String, String)> = HashMap::new();
+    let mut downs: HashMap<u64, String> = HashMap::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some((version, name, is_up)) = parse_file_name(file_name) else {
+            continue;
+        };
+
+        let sql = std::fs::read_to_string(&path)?;
+        let duplicate = if is_up {
+            ups.insert(version, (name, sql)).is_some()
+        } else {
+            downs.insert(version, sql).is_some()
+        };
+        if duplicate {
+            return Err(AppError::Validation(format!(
+                "Duplicate migration version {}",
+                version
+            )));
+        }
+    }
+
+    if let Some(version) = downs.keys().find(|v| !ups.contains_key(v)) {
+        return Err(AppError::Validation(format!(
+            "Migration {} has a down script but no up script",
+            version
+        )));
+    }
+
+    let mut migrations: Vec<Migration> = ups
+        .into_iter()
+        .map(|(version, (name, up_sql))| Migration {
+            version,
+            name,
+            up_sql,
+            down_sql: downs.remove(&version),
+        })
+        .collect();
+    migrations.sort_by_key(|m| m.version);
+
+    Ok(migrations)
+}
+
+/// Combine migration files with the tracking table into a status list
+pub fn migration_status(
+    migrations: &[Migration],
+    applied: &[AppliedMigration],
+) -> Vec<MigrationStatus> {
+    let applied_by_version: HashMap<u64, &AppliedMigration> =
+        applied.iter().map(|a| (a.version, a)).collect();
+
+    let mut statuses: Vec<MigrationStatus> = migrations
+        .iter()
+        .map(|migration| {
+            let record = applied_by_version.get(&migration.version);
+            let state = match record {
+                None => MigrationState::Pending,
+                Some(record) if record.checksum != migration.checksum() => MigrationState::Modified,
+                Some(_) => MigrationState::Applied,
+            };
+            MigrationStatus {
+                version: migration.version,
+                name: migration.name.clone(),
+                state,
+                applied_at: record.map(|r| r.applied_at.clone()),
+                reversible: migration.down_sql.is_some(),
+            }
+        })
+        .collect();
+
+    statuses.extend(
+        applied
+            .iter()
+            .filter(|a| !migrations.iter().any(|m| m.version == a.version))
+            .map(|a| MigrationStatus {
+                version: a.version,
+                name: a.name.clone(),
+                state: MigrationState::Missing,
+                applied_at: Some(a.applied_at.clone()),
+                reversible: false,
+            }),
+    );
+    statuses.sort_by_key(|s| s.version);
+
+    statuses
+}
+
+/// Statements applying or rolling back one migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStep {
+    pub version: u64,
+    pub name: String,
+    /// The script's statements, followed by the tracking table update
+    pub statements: Vec<String>,
+    pub rollback: bool,
+}
+
+/// Applies and rolls back migrations against a connected database
+pub struct MigrationRunner<'a> {
+    adapter: &'a dyn DatabaseAdapter,
+}
+
+impl<'a> MigrationRunner<'a> {
+    pub fn new(adapter: &'a dyn DatabaseAdapter) -> Self {
+        Self { adapter }
+    }
+
+    fn tracking_table(&self) -> String {
+        self.adapter.get_dialect().quote_identifier(TRACKING_TABLE)
+    }
+
+    /// Create the tracking table if it does not exist yet
+    pub async fn ensure_tracking_table(&self) -> Result<(), AppError> {
+        let ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {} (\
+             version BIGINT PRIMARY KEY, \
+             name VARCHAR(255) NOT NULL, \
+             checksum VARCHAR(64) NOT NULL, \
+             applied_at VARCHAR(64) NOT NULL)",
+            self.tracking_table()
+        );
+        self.adapter.execute_command(&ddl).await?;
+        Ok(())
+    }
+
+    /// Read the applied migrations from the tracking table
+    pub async fn applied(&self) -> Result<Vec<AppliedMigration>, AppError> {
+        self.ensure_tracking_table().await?;
+
+        let result = self
+            .adapter
+            .execute_query(&format!(
+                "SELECT version, name, checksum, applied_at FROM {} ORDER BY version",
+                self.tracking_table()
+            ))
+            .await?;
+
+        result
+            .rows
+            .iter()
+            .map(|row| {
+                let value = |i: usize| row.values.get(i).cloned().flatten().unwrap_or_default();
+                let version = value(0).parse().map_err(|_| {
+                    AppError::Validation(format!("Invalid migration version '{}'", value(0)))
+                })?;
+                Ok(AppliedMigration {
+                    version,
+                    name: value(1),
+                    checksum: value(2),
+                    applied_at: value(3),
+                })
+            })
+            .collect()
+    }
+
+    /// A migration script split into statements, with its tracking table update last
+    fn step(&self, version: u64, name: &str, script: &str, bookkeeping: String, rollback: bool) -> Result<MigrationStep, AppError> {
+        let mut statements =
+            split_sql_statements(script, &self.adapter.database_type()).map_err(AppError::Validation)?;
+        statements.push(bookkeeping);
+        Ok(MigrationStep { version, name: name.to_string(), statements, rollback })
+    }
+
+    /// Steps applying pending migrations in order, up to and including `target_version`
+    pub async fn pending_steps(
+        &self,
+        migrations: &[Migration],
+        target_version: Option<u64>,
+    ) -> Result<Vec<MigrationStep>, AppError> {
+        let applied = self.applied().await?;
+        let dialect = self.adapter.get_dialect();
+
+        if let Some(modified) = migration_status(migrations, &applied)
+            .iter()
+            .find(|s| s.state == MigrationState::Modified)
+        {
+            return Err(AppError::Validation(format!(
+                "Migration {} ({}) was changed after it was applied",
+                modified.version, modified.name
+            )));
+        }
+
+        let mut steps = Vec::new();
+        for migration in migrations {
+            if applied.iter().any(|a| a.version == migration.version) {
+                continue;
+            }
+            if target_version.is_some_and(|target| migration.version > target) {
+                break;
+            }
+
+            let text = |value: &str| sql_literal(Some(value), "TEXT", dialect.as_ref());
+            let bookkeeping = format!(
+                "INSERT INTO {} (version, name, checksum, applied_at) VALUES ({}, {}, {}, {})",
+                self.tracking_table(),
+                migration.version,
+                text(&migration.name),
+                text(&migration.checksum()),
+                text(&chrono::Utc::now().to_rfc3339())
+            );
+            steps.push(self.step(migration.version, &migration.name, &migration.up_sql, bookkeeping, false)?);
+        }
+
+        Ok(steps)
+    }
+
+    /// Steps rolling back the most recently applied migrations, newest first
+    pub async fn rollback_steps(&self, migrations: &[Migration], steps: usize) -> Result<Vec<MigrationStep>, AppError> {
+        let applied = self.applied().await?;
+
+        let mut rollback = Vec::new();
+        for record in applied.iter().rev().take(steps) {
+            let down_sql = migrations
+                .iter()
+                .find(|m| m.version == record.version)
+                .and_then(|m| m.down_sql.as_deref())
+                .ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "Migration {} ({}) has no down script",
+                        record.version, record.name
+                    ))
+                })?;
+
+            let bookkeeping = format!(
+                "DELETE FROM {} WHERE version = {}",
+                self.tracking_table(),
+                record.version
+            );
+            rollback.push(self.step(record.version, &record.name, down_sql, bookkeeping, true)?);
+        }
+
+        Ok(rollback)
+    }
+
+    /// Run the statements of a step
+    ///
+    /// Databases with transactional DDL apply everything atomically. Others (MySQL)
+    /// run statement by statement and only update the tracking table once all succeeded.
+    pub async fn run(&self, step: &MigrationStep) -> Result<(), AppError> {
+        let outcome: Result<(), AppError> = async {
+            if self.adapter.get_capabilities().transactional_ddl {
+                self.adapter.execute_batch(&step.statements).await?;
+            } else {
+                for statement in &step.statements {
+                    self.adapter.execute_command(statement).await?;
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        outcome.map_err(|e| {
+            let action = if step.rollback { "Rollback of migration" } else { "Migration" };
+            AppError::Database(crate::database::DatabaseError::QueryFailed(format!(
+                "{} {} ({}) failed: {}",
+                action, step.version, step.name, e
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(version: u64, up_sql: &str) -> Migration {
+        Migration {
+            version,
+            name: format!("migration_{}", version),
+            up_sql: up_sql.to_string(),
+            down_sql: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        assert_eq!(
+            parse_file_name("20240101_create_users.up.sql"),
+            Some((20240101, "create_users".to_string(), true))
+        );
+        assert_eq!(
+            parse_file_name("002_add_index.down.sql"),
+            Some((2, "add_index".to_string(), false))
+        );
+        assert_eq!(parse_file_name("README.md"), None);
+        assert_eq!(parse_file_name("init.up.sql"), None);
+    }
+
+    #[test]
+    fn test_load_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, sql: &str| std::fs::write(dir.path().join(name), sql).unwrap();
+        write("002_add_email.up.sql", "ALTER TABLE users ADD email TEXT");
+        write("001_create_users.up.sql", "CREATE TABLE users (id INT)");
+        write("001_create_users.down.sql", "DROP TABLE users");
+        write("notes.txt", "ignored");
+
+        let migrations = load_migrations(dir.path()).unwrap();
+        assert_eq!(migrations.len(), 2);
+        assert_eq!(migrations[0].version, 1);
+        assert_eq!(migrations[0].down_sql.as_deref(), Some("DROP TABLE users"));
+        assert_eq!(migrations[1].name, "add_email");
+        assert!(migrations[1].down_sql.is_none());
+    }
+
+    #[test]
+    fn test_down_without_up_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("001_orphan.down.sql"), "DROP TABLE x").unwrap();
+        assert!(load_migrations(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_migration_status() {
+        let migrations = vec![
+            migration(1, "CREATE TABLE a (id INT)"),
+            migration(2, "CREATE TABLE b (id INT)"),
+            migration(3, "CREATE TABLE c (id INT)"),
+        ];
+        let record = |m: &Migration, checksum: String| AppliedMigration {
+            version: m.version,
+            name: m.name.clone(),
+            checksum,
+            applied_at: "2024-01-01T00:00:00+00:00".to_string(),
+        };
+        let applied = vec![
+            record(&migrations[0], migrations[0].checksum()),
+            record(&migrations[1], "stale".to_string()),
+            record(&migration(0, ""), String::new()),
+        ];
+
+        let states: Vec<(u64, MigrationState)> = migration_status(&migrations, &applied)
+            .iter()
+            .map(|s| (s.version, s.state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (0, MigrationState::Missing),
+                (1, MigrationState::Applied),
+                (2, MigrationState::Modified),
+                (3, MigrationState::Pending),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runner_applies_pending_migrations() {
+        use crate::database::adapter::mock::MockAdapter;
+        use crate::database::DatabaseType;
+
+        let first = migration(1, "CREATE TABLE a (id INT)");
+        let adapter = MockAdapter::new(DatabaseType::PostgreSQL).with_result(
+            "SELECT version",
+            &["version", "name", "checksum", "applied_at"],
+            vec![vec![Some("1"), Some("migration_1"), Some(first.checksum().as_str()), Some("2024-01-01")]],
+        );
+        let migrations = vec![first.clone(), migration(2, "CREATE TABLE b (id INT); CREATE INDEX b_id ON b(id)")];
+
+        let runner = MigrationRunner::new(&adapter);
+        let steps = runner.pending_steps(&migrations, None).await.unwrap();
+        assert_eq!(steps.iter().map(|s| s.version).collect::<Vec<_>>(), vec![2]);
+        runner.run(&steps[0]).await.unwrap();
+        let executed = adapter.executed();
+        assert!(executed[0].starts_with("CREATE TABLE IF NOT EXISTS"));
+        assert_eq!(&executed[2..4], ["CREATE TABLE b (id INT)", "CREATE INDEX b_id ON b(id)"]);
+        assert!(executed[4].starts_with("INSERT INTO \"_dataforge_migrations\""));
+
+        // A failed script leaves its migration unrecorded, after the earlier ones
+        let failing = MockAdapter::new(DatabaseType::PostgreSQL).failing_on("CREATE INDEX");
+        let runner = MigrationRunner::new(&failing);
+        let steps = runner.pending_steps(&migrations, None).await.unwrap();
+        assert!(runner.run(&steps[0]).await.is_ok());
+        assert!(runner.run(&steps[1]).await.is_err());
+        let recorded: Vec<String> = failing.executed().into_iter().filter(|s| s.starts_with("INSERT")).collect();
+        assert_eq!(recorded.len(), 1);
+        assert!(recorded[0].contains("(1, 'migration_1'"));
+    }
+}
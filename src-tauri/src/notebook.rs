@@ -0,0 +1,363 @@
+use chrono::{DateTime, Utc};
+use pulldown_cmark::{html, Options, Parser};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::database::adapter::ColumnInfo;
+use crate::error::AppError;
+use crate::export::clipboard::{escape_html, to_html, to_markdown};
+
+/// Version of the notebook file format written by this build
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Rows of each result kept in the notebook, so the file stays small
+pub const MAX_STORED_ROWS: usize = 200;
+
+/// Text shown for NULL values in exports
+const NULL_DISPLAY: &str = "NULL";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellKind {
+    Sql,
+    Markdown,
+}
+
+/// Formats a notebook can be exported as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotebookFormat {
+    Markdown,
+    Html,
+}
+
+/// Result of one statement of a SQL cell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellResult {
+    /// The statement, when the cell has several
+    pub statement: Option<String>,
+    /// Empty for statements that return no rows
+    pub columns: Vec<ColumnInfo>,
+    /// At most `MAX_STORED_ROWS` of the rows returned
+    pub rows: Vec<Vec<Option<String>>>,
+    /// Rows returned before they were cut to `MAX_STORED_ROWS`
+    pub row_count: usize,
+    pub rows_affected: Option<u64>,
+}
+
+impl CellResult {
+    pub fn new(
+        statement: Option<String>,
+        columns: Vec<ColumnInfo>,
+        mut rows: Vec<Vec<Option<String>>>,
+        rows_affected: Option<u64>,
+    ) -> Self {
+        let row_count = rows.len();
+        rows.truncate(MAX_STORED_ROWS);
+        Self { statement, columns, rows, row_count, rows_affected }
+    }
+
+    fn table(&self) -> (Vec<&str>, Vec<Vec<&str>>) {
+        let header = self.columns.iter().map(|c| c.name.as_str()).collect();
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|v| v.as_deref().unwrap_or(NULL_DISPLAY)).collect())
+            .collect();
+        (header, rows)
+    }
+
+    /// Line below the table on rows cut off, or on the rows a command affected
+    fn note(&self) -> Option<String> {
+        if self.columns.is_empty() {
+            return self.rows_affected.map(|n| format!("{} rows affected", n));
+        }
+        (self.rows.len() < self.row_count).then(|| format!("Showing {} of {} rows", self.rows.len(), self.row_count))
+    }
+}
+
+/// Outcome of the last run of a SQL cell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellOutput {
+    /// Position of the run among all runs of the notebook, starting at 1
+    pub execution_count: u32,
+    pub results: Vec<CellResult>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub executed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cell {
+    /// Assigned when a new cell is saved
+    #[serde(default)]
+    pub id: String,
+    pub kind: CellKind,
+    pub source: String,
+    /// Kept by the backend; outputs sent with a saved cell are ignored
+    #[serde(default)]
+    pub output: Option<CellOutput>,
+}
+
+/// A document of SQL and Markdown cells, run in order against the active
+/// connection, with the output of each SQL cell's last run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notebook {
+    pub version: u32,
+    pub title: String,
+    pub cells: Vec<Cell>,
+    /// Runs of the notebook's cells so far
+    #[serde(default)]
+    pub execution_count: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Notebook {
+    pub fn new(title: String) -> Self {
+        let now = Utc::now();
+        Self {
+            version: FORMAT_VERSION,
+            title,
+            cells: Vec::new(),
+            execution_count: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let data = fs::read_to_string(path)
+            .map_err(|e| AppError::Storage(format!("Failed to read notebook {}: {}", path.display(), e)))?;
+        let notebook: Notebook = serde_json::from_str(&data)?;
+        if notebook.version > FORMAT_VERSION {
+            return Err(AppError::Validation(format!(
+                "Notebook format version {} is newer than this version of DataForge supports",
+                notebook.version
+            )));
+        }
+        Ok(notebook)
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<(), AppError> {
+        self.version = FORMAT_VERSION;
+        self.updated_at = Utc::now();
+        let data = serde_json::to_string_pretty(self)?;
+        fs::write(path, data)
+            .map_err(|e| AppError::Storage(format!("Failed to write notebook {}: {}", path.display(), e)))
+    }
+
+    /// Replace the title and cells with edited ones, keeping the output of
+    /// each cell that is still there
+    pub fn edit(&mut self, title: String, cells: Vec<Cell>) {
+        let mut outputs: HashMap<String, CellOutput> = self
+            .cells
+            .drain(..)
+            .filter_map(|cell| cell.output.map(|output| (cell.id, output)))
+            .collect();
+
+        self.title = title;
+        self.cells = cells
+            .into_iter()
+            .map(|mut cell| {
+                if cell.id.is_empty() {
+                    cell.id = Uuid::new_v4().to_string();
+                }
+                cell.output = match cell.kind {
+                    CellKind::Sql => outputs.remove(&cell.id),
+                    CellKind::Markdown => None,
+                };
+                cell
+            })
+            .collect();
+    }
+
+    pub fn cell(&self, id: &str) -> Result<&Cell, AppError> {
+        self.cells
+            .iter()
+            .find(|cell| cell.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Notebook cell {} not found", id)))
+    }
+
+    /// Store the outcome of running a SQL cell as its output
+    pub fn record_run(
+        &mut self,
+        id: &str,
+        outcome: Result<Vec<CellResult>, String>,
+        duration: Duration,
+    ) -> Result<&Cell, AppError> {
+        let execution_count = self.execution_count + 1;
+        let cell = self
+            .cells
+            .iter_mut()
+            .find(|cell| cell.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Notebook cell {} not found", id)))?;
+
+        let (results, error) = match outcome {
+            Ok(results) => (results, None),
+            Err(error) => (Vec::new(), Some(error)),
+        };
+        cell.output = Some(CellOutput {
+            execution_count,
+            results,
+            error,
+            duration_ms: duration.as_millis() as u64,
+            executed_at: Utc::now(),
+        });
+        self.execution_count = execution_count;
+        Ok(cell)
+    }
+
+    pub fn export(&self, format: NotebookFormat) -> String {
+        match format {
+            NotebookFormat::Markdown => self.to_markdown(),
+            NotebookFormat::Html => self.to_html(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut output = format!("# {}\n", self.title);
+        for cell in &self.cells {
+            output.push('\n');
+            match cell.kind {
+                CellKind::Markdown => {
+                    output.push_str(cell.source.trim_end());
+                    output.push('\n');
+                }
+                CellKind::Sql => {
+                    let _ = writeln!(output, "```sql\n{}\n```", cell.source.trim_end());
+                    let Some(cell_output) = &cell.output else { continue };
+                    for result in &cell_output.results {
+                        if !result.columns.is_empty() {
+                            let (header, rows) = result.table();
+                            output.push('\n');
+                            output.push_str(&to_markdown(&header, &rows));
+                        }
+                        if let Some(note) = result.note() {
+                            let _ = writeln!(output, "\n_{}_", note);
+                        }
+                    }
+                    if let Some(error) = &cell_output.error {
+                        let _ = writeln!(output, "\n> **Error:** {}", error.replace('\n', "\n> "));
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    fn to_html(&self) -> String {
+        let title = escape_html(&self.title);
+        let mut output = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; max-width: 60rem; margin: 2rem auto; }}\n\
+             pre {{ background: #f4f4f4; padding: 0.75rem; overflow-x: auto; }}\n\
+             table {{ border-collapse: collapse; margin: 0.5rem 0; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 0.25rem 0.5rem; text-align: left; }}\n\
+             .note {{ color: #666; }}\n.error {{ color: #b00020; white-space: pre-wrap; }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            title, title
+        );
+
+        for cell in &self.cells {
+            match cell.kind {
+                CellKind::Markdown => {
+                    html::push_html(&mut output, Parser::new_ext(&cell.source, Options::ENABLE_TABLES));
+                }
+                CellKind::Sql => {
+                    let _ = writeln!(
+                        output,
+                        "<pre><code class=\"language-sql\">{}</code></pre>",
+                        escape_html(cell.source.trim_end())
+                    );
+                    let Some(cell_output) = &cell.output else { continue };
+                    for result in &cell_output.results {
+                        if !result.columns.is_empty() {
+                            let (header, rows) = result.table();
+                            output.push_str(&to_html(&header, &rows, true));
+                        }
+                        if let Some(note) = result.note() {
+                            let _ = writeln!(output, "<p class=\"note\">{}</p>", escape_html(&note));
+                        }
+                    }
+                    if let Some(error) = &cell_output.error {
+                        let _ = writeln!(output, "<p class=\"error\">{}</p>", escape_html(error));
+                    }
+                }
+            }
+        }
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(kind: CellKind, source: &str) -> Cell {
+        Cell { id: String::new(), kind, source: source.to_string(), output: None }
+    }
+
+    fn column(name: &str) -> ColumnInfo {
+        ColumnInfo { name: name.to_string(), data_type: "text".to_string(), is_nullable: true }
+    }
+
+    #[test]
+    fn test_run_and_export() {
+        let mut notebook = Notebook::new("Weekly report".to_string());
+        notebook.edit(
+            notebook.title.clone(),
+            vec![cell(CellKind::Markdown, "Active **users**"), cell(CellKind::Sql, "SELECT name FROM users")],
+        );
+        let sql_id = notebook.cells[1].id.clone();
+        assert!(!sql_id.is_empty());
+
+        let rows = (0..MAX_STORED_ROWS + 5).map(|i| vec![Some(format!("user{}", i))]).collect();
+        let result = CellResult::new(None, vec![column("name")], rows, None);
+        let ran = notebook.record_run(&sql_id, Ok(vec![result]), Duration::from_millis(12)).unwrap();
+        assert_eq!(ran.output.as_ref().unwrap().execution_count, 1);
+        assert_eq!(ran.output.as_ref().unwrap().results[0].rows.len(), MAX_STORED_ROWS);
+
+        let markdown = notebook.export(NotebookFormat::Markdown);
+        assert!(markdown.starts_with("# Weekly report\n\nActive **users**\n\n```sql\nSELECT name FROM users\n```\n\n| name |\n"));
+        assert!(markdown.ends_with("| user199 |\n\n_Showing 200 of 205 rows_\n"));
+
+        let html = notebook.export(NotebookFormat::Html);
+        assert!(html.contains("<p>Active <strong>users</strong></p>"));
+        assert!(html.contains("<td>user0</td>"));
+
+        // Edits keep the output of cells that are still there
+        let mut cells = notebook.cells.clone();
+        cells[1].output = None;
+        cells.insert(0, cell(CellKind::Sql, "DELETE FROM users"));
+        notebook.edit("Report".to_string(), cells);
+        assert!(notebook.cell(&sql_id).unwrap().output.is_some());
+
+        let delete_id = notebook.cells[0].id.clone();
+        notebook.record_run(&delete_id, Err("read-only".to_string()), Duration::ZERO).unwrap();
+        assert_eq!(notebook.execution_count, 2);
+        assert!(notebook.export(NotebookFormat::Markdown).contains("> **Error:** read-only\n"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.dfnb");
+        let mut notebook = Notebook::new("Report".to_string());
+        notebook.edit("Report".to_string(), vec![cell(CellKind::Sql, "SELECT 1")]);
+        notebook.save(&path).unwrap();
+
+        let loaded = Notebook::load(&path).unwrap();
+        assert_eq!(loaded.cells[0].id, notebook.cells[0].id);
+
+        notebook.version = FORMAT_VERSION + 1;
+        fs::write(&path, serde_json::to_string(&notebook).unwrap()).unwrap();
+        assert!(Notebook::load(&path).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::settings::NotificationSettings;
+
+/// Time allowed for a webhook request before it is given up
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSource {
+    Query,
+    Job,
+    Backup,
+}
+
+/// A query, job or backup that finished, as shown on the desktop and POSTed to the webhook
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub source: NotificationSource,
+    /// Name of the job or database, or the connection of a query
+    pub name: String,
+    pub succeeded: bool,
+    /// Rows returned or affected, when known
+    pub row_count: Option<u64>,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(source: NotificationSource, name: impl Into<String>, duration: Duration, outcome: Result<Option<u64>, String>) -> Self {
+        let (row_count, error) = match outcome {
+            Ok(row_count) => (row_count, None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            source,
+            name: name.into(),
+            succeeded: error.is_none(),
+            row_count,
+            duration_ms: duration.as_millis() as u64,
+            error,
+            finished_at: Utc::now(),
+        }
+    }
+
+    /// One-line summary for the desktop notification
+    pub fn title(&self) -> String {
+        let what = match self.source {
+            NotificationSource::Query => "Query",
+            NotificationSource::Job => "Job",
+            NotificationSource::Backup => "Backup",
+        };
+        let outcome = if self.succeeded { "finished" } else { "failed" };
+        format!("{} {} {}", what, self.name, outcome)
+    }
+
+    /// Whether the settings ask for this notification at all
+    pub fn wanted(&self, settings: &NotificationSettings) -> bool {
+        let outcome_wanted = if self.succeeded { settings.on_success } else { settings.on_failure };
+        let long_enough = self.source != NotificationSource::Query
+            || self.duration_ms >= settings.long_query_seconds.saturating_mul(1000);
+        outcome_wanted && long_enough
+    }
+}
+
+/// POST `notification` as JSON to the configured webhook
+pub async fn send_webhook(settings: &NotificationSettings, notification: &Notification) -> Result<(), AppError> {
+    let Some(url) = settings.webhook_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) else {
+        return Ok(());
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))?;
+    let mut request = client.post(url).json(notification);
+    for (name, value) in &settings.webhook_headers {
+        request = request.header(name, value);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Webhook request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::Network(format!("Webhook returned {}", response.status())));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wanted() {
+        let settings = NotificationSettings { on_success: false, ..Default::default() };
+        let short_query = Notification::new(NotificationSource::Query, "local", Duration::from_secs(2), Err("boom".to_string()));
+        let long_query = Notification::new(NotificationSource::Query, "local", Duration::from_secs(60), Err("boom".to_string()));
+        let job = Notification::new(NotificationSource::Job, "nightly", Duration::from_secs(1), Ok(Some(10)));
+
+        assert!(!short_query.wanted(&settings));
+        assert!(long_query.wanted(&settings));
+        assert!(!job.wanted(&settings));
+        assert!(job.wanted(&NotificationSettings::default()));
+        assert_eq!(long_query.title(), "Query local failed");
+        assert_eq!(job.row_count, Some(10));
+    }
+}
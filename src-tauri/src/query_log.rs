@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use crate::error::AppError;
+
+const QUERY_LOG_FILE: &str = "queries.jsonl";
+
+/// Size at which the query log is rotated to `queries.jsonl.1`
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Number of entries returned when the filter sets no limit
+const DEFAULT_LIMIT: usize = 500;
+
+/// One statement executed on a connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub connection_id: String,
+    pub profile_id: Option<String>,
+    /// Statement text with credentials redacted
+    pub statement: String,
+    pub duration_ms: u64,
+    /// Rows returned by a query
+    pub rows_returned: Option<u64>,
+    pub rows_affected: Option<u64>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Criteria for reading the query log
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryLogFilter {
+    pub connection_id: Option<String>,
+    pub profile_id: Option<String>,
+    /// Only entries logged after this time, for polling new entries
+    pub since: Option<DateTime<Utc>>,
+    /// Case-insensitive text the statement must contain
+    pub text: Option<String>,
+    pub failed_only: bool,
+    pub min_duration_ms: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+impl QueryLogFilter {
+    fn matches(&self, entry: &QueryLogEntry) -> bool {
+        if self.connection_id.as_ref().is_some_and(|id| *id != entry.connection_id) {
+            return false;
+        }
+        if self.profile_id.is_some() && entry.profile_id != self.profile_id {
+            return false;
+        }
+        if self.since.is_some_and(|since| entry.timestamp <= since) {
+            return false;
+        }
+        if self.failed_only && entry.success {
+            return false;
+        }
+        if self.min_duration_ms.is_some_and(|min| entry.duration_ms < min) {
+            return false;
+        }
+        match self.text.as_deref() {
+            Some(text) => entry.statement.to_lowercase().contains(&text.to_lowercase()),
+            None => true,
+        }
+    }
+}
+
+/// Log of every executed statement, kept apart from the application log
+///
+/// Stored as JSON lines; the file is rotated once it grows past 10 MB, keeping one
+/// previous file.
+pub struct QueryLog {
+    log_path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl QueryLog {
+    /// Open the query log in the app data directory
+    pub fn new(app_handle: &AppHandle) -> Result<Self, AppError> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Storage(format!("Could not resolve app data directory: {}", e)))?;
+
+        let log_dir = app_data_dir.join("query_log");
+        fs::create_dir_all(&log_dir).map_err(|e| {
+            AppError::Storage(format!("Failed to create query log directory: {}", e))
+        })?;
+
+        Ok(Self::with_path(log_dir.join(QUERY_LOG_FILE)))
+    }
+
+    /// Open a query log at a specific path
+    pub fn with_path(log_path: PathBuf) -> Self {
+        Self {
+            log_path,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn rotated_path(&self) -> PathBuf {
+        let mut file_name = self.log_path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".1");
+        self.log_path.with_file_name(file_name)
+    }
+
+    /// Append an entry, redacting credentials in the statement and error
+    pub fn append(&self, mut entry: QueryLogEntry) -> Result<(), AppError> {
+        entry.statement = crate::redact::redacted(&entry.statement);
+        entry.error = entry.error.map(crate::redact::redacted);
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize query log entry: {}", e)))?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        if fs::metadata(&self.log_path).is_ok_and(|m| m.len() >= MAX_LOG_BYTES) {
+            fs::rename(&self.log_path, self.rotated_path())
+                .map_err(|e| AppError::Storage(format!("Failed to rotate query log: {}", e)))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .map_err(|e| AppError::Storage(format!("Failed to open query log: {}", e)))?;
+        file.write_all(line.as_bytes())
+            .map_err(|e| AppError::Storage(format!("Failed to write query log: {}", e)))
+    }
+
+    /// The newest matching entries, newest first
+    pub fn tail(&self, filter: &QueryLogFilter) -> Result<Vec<QueryLogEntry>, AppError> {
+        let limit = filter.limit.unwrap_or(DEFAULT_LIMIT);
+        let mut newest = VecDeque::with_capacity(limit.min(DEFAULT_LIMIT));
+
+        for path in [self.rotated_path(), self.log_path.clone()] {
+            if !path.exists() {
+                continue;
+            }
+            let file = File::open(&path)
+                .map_err(|e| AppError::Storage(format!("Failed to open query log: {}", e)))?;
+
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(|e| AppError::Storage(format!("Failed to read query log: {}", e)))?;
+                // A line cut short by a crash is skipped rather than failing the panel
+                let Ok(entry) = serde_json::from_str::<QueryLogEntry>(&line) else {
+                    continue;
+                };
+                if filter.matches(&entry) {
+                    if newest.len() == limit {
+                        newest.pop_front();
+                    }
+                    if limit > 0 {
+                        newest.push_back(entry);
+                    }
+                }
+            }
+        }
+
+        Ok(newest.into_iter().rev().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(connection_id: &str, statement: &str, duration_ms: u64, error: Option<&str>) -> QueryLogEntry {
+        QueryLogEntry {
+            timestamp: Utc::now(),
+            connection_id: connection_id.to_string(),
+            profile_id: None,
+            statement: statement.to_string(),
+            duration_ms,
+            rows_returned: Some(1),
+            rows_affected: None,
+            success: error.is_none(),
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_append_and_tail() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = QueryLog::with_path(temp_dir.path().join(QUERY_LOG_FILE));
+
+        log.append(entry("c1", "SELECT 1", 5, None)).unwrap();
+        log.append(entry("c2", "SELECT slow()", 900, None)).unwrap();
+        log.append(entry("c1", "SELECT x", 1, Some("no column x"))).unwrap();
+
+        let all = log.tail(&QueryLogFilter::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].statement, "SELECT x");
+
+        let c1 = log.tail(&QueryLogFilter { connection_id: Some("c1".to_string()), ..Default::default() }).unwrap();
+        assert_eq!(c1.len(), 2);
+
+        let slow = log.tail(&QueryLogFilter { min_duration_ms: Some(100), ..Default::default() }).unwrap();
+        assert_eq!(slow[0].statement, "SELECT slow()");
+
+        let failed = log.tail(&QueryLogFilter { failed_only: true, ..Default::default() }).unwrap();
+        assert_eq!(failed.len(), 1);
+
+        let last = log.tail(&QueryLogFilter { limit: Some(1), ..Default::default() }).unwrap();
+        assert_eq!(last[0].statement, "SELECT x");
+    }
+
+    #[test]
+    fn test_statements_are_redacted() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(QUERY_LOG_FILE);
+        let log = QueryLog::with_path(path.clone());
+
+        log.append(entry("c1", "ALTER USER app PASSWORD 'hunter2'", 3, None)).unwrap();
+
+        assert!(!fs::read_to_string(path).unwrap().contains("hunter2"));
+    }
+}
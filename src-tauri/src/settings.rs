@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::error::AppError;
+
+const SETTINGS_FILE: &str = "settings.json";
+const DEFAULT_RESULT_MEMORY_LIMIT_MB: u64 = 256;
+
+fn default_otlp_endpoint() -> String {
+    "http://localhost:4318".to_string()
+}
+
+fn default_service_name() -> String {
+    "dataforge".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// `~/.dataforge`, holding the settings, logs and crash reports
+pub fn data_dir() -> Result<PathBuf, AppError> {
+    dirs::home_dir()
+        .map(|home_dir| home_dir.join(".dataforge"))
+        .ok_or_else(|| AppError::Config("Could not resolve home directory".to_string()))
+}
+
+/// Export of traces and metrics to an OpenTelemetry collector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetrySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the collector's OTLP/HTTP receiver; `/v1/traces` and
+    /// `/v1/metrics` are appended
+    #[serde(default = "default_otlp_endpoint")]
+    pub endpoint: String,
+    /// Extra request headers, e.g. an API key for a hosted collector
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    #[serde(default = "default_true")]
+    pub export_metrics: bool,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_otlp_endpoint(),
+            headers: HashMap::new(),
+            service_name: default_service_name(),
+            export_metrics: true,
+        }
+    }
+}
+
+impl TelemetrySettings {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let endpoint = self.endpoint.trim();
+        if !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+            return Err(AppError::Validation(
+                "OTLP endpoint must be an http:// or https:// URL".to_string(),
+            ));
+        }
+        if self.service_name.trim().is_empty() {
+            return Err(AppError::Validation("Service name is required".to_string()));
+        }
+        if self.headers.keys().any(|name| name.trim().is_empty()) {
+            return Err(AppError::Validation("Header names must not be empty".to_string()));
+        }
+        Ok(())
+    }
+}
+
+fn default_long_query_seconds() -> u64 {
+    30
+}
+
+/// Notifications sent when queries, jobs and backups finish
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Show a desktop notification
+    #[serde(default = "default_true")]
+    pub desktop: bool,
+    /// URL a JSON summary of each notification is POSTed to
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Extra request headers, e.g. an authorization token
+    #[serde(default)]
+    pub webhook_headers: HashMap<String, String>,
+    #[serde(default = "default_true")]
+    pub on_success: bool,
+    #[serde(default = "default_true")]
+    pub on_failure: bool,
+    /// Queries from the editor notify only when they run at least this long
+    #[serde(default = "default_long_query_seconds")]
+    pub long_query_seconds: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            desktop: true,
+            webhook_url: None,
+            webhook_headers: HashMap::new(),
+            on_success: true,
+            on_failure: true,
+            long_query_seconds: default_long_query_seconds(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if let Some(url) = self.webhook_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(AppError::Validation(
+                    "Webhook URL must be an http:// or https:// URL".to_string(),
+                ));
+            }
+        }
+        if self.webhook_headers.keys().any(|name| name.trim().is_empty()) {
+            return Err(AppError::Validation("Header names must not be empty".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Application settings that are read before the window opens
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppSettings {
+    #[serde(default)]
+    pub telemetry: TelemetrySettings,
+    /// Log filter directives, e.g. `postgres_adapter=debug,commands=info`
+    #[serde(default)]
+    pub log_filter: Option<String>,
+    /// Language of error messages
+    #[serde(default)]
+    pub locale: crate::i18n::Locale,
+    /// Megabytes of a spooled query result kept in memory before the rest is
+    /// written to a scratch file; 256 when unset
+    #[serde(default)]
+    pub result_memory_limit_mb: Option<u64>,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+}
+
+impl AppSettings {
+    /// Bytes of a spooled query result kept in memory
+    pub fn result_memory_limit(&self) -> usize {
+        let megabytes = self.result_memory_limit_mb.unwrap_or(DEFAULT_RESULT_MEMORY_LIMIT_MB);
+        usize::try_from(megabytes.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX)
+    }
+}
+
+/// JSON file holding the application settings in `~/.dataforge`, next to the logs
+pub struct SettingsStore {
+    settings_path: PathBuf,
+}
+
+impl SettingsStore {
+    pub fn new(settings_dir: PathBuf) -> Self {
+        Self {
+            settings_path: settings_dir.join(SETTINGS_FILE),
+        }
+    }
+
+    /// The store in `~/.dataforge`
+    pub fn open_default() -> Result<Self, AppError> {
+        Ok(Self::new(data_dir()?))
+    }
+
+    pub fn load(&self) -> Result<AppSettings, AppError> {
+        if !self.settings_path.exists() {
+            return Ok(AppSettings::default());
+        }
+
+        let data = fs::read_to_string(&self.settings_path)
+            .map_err(|e| AppError::Storage(format!("Failed to read settings: {}", e)))?;
+        serde_json::from_str(&data)
+            .map_err(|e| AppError::Config(format!("Failed to parse settings: {}", e)))
+    }
+
+    pub fn save(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if let Some(parent) = self.settings_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::Storage(format!("Failed to create settings directory: {}", e)))?;
+        }
+
+        let data = serde_json::to_string_pretty(settings)
+            .map_err(|e| AppError::Storage(format!("Failed to serialize settings: {}", e)))?;
+        crate::profile::safe_file::write_atomic(&self.settings_path, data.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_settings_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SettingsStore::new(temp_dir.path().join("dataforge"));
+
+        let settings = store.load().unwrap();
+        assert!(!settings.telemetry.enabled);
+        assert_eq!(settings.telemetry.endpoint, "http://localhost:4318");
+
+        let mut settings = AppSettings::default();
+        settings.telemetry.enabled = true;
+        settings.telemetry.headers.insert("x-api-key".to_string(), "key".to_string());
+        store.save(&settings).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert!(loaded.telemetry.enabled);
+        assert_eq!(loaded.telemetry.headers["x-api-key"], "key");
+    }
+
+    #[test]
+    fn test_validate_telemetry() {
+        let mut telemetry = TelemetrySettings { endpoint: "collector:4318".to_string(), ..Default::default() };
+        assert!(telemetry.validate().is_ok());
+
+        telemetry.enabled = true;
+        assert!(telemetry.validate().is_err());
+
+        telemetry.endpoint = "https://otel.example.com".to_string();
+        assert!(telemetry.validate().is_ok());
+    }
+}
@@ -0,0 +1,213 @@
+use once_cell::sync::{Lazy, OnceCell};
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::trace::Status;
+use opentelemetry::{Array, KeyValue, Value};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::{self, SdkTracerProvider, SpanData, Tracer};
+use opentelemetry_sdk::Resource;
+use std::future::Future;
+use std::time::Duration;
+use crate::redact::redact;
+use crate::error::AppError;
+use crate::settings::TelemetrySettings;
+
+/// Providers kept for flushing on exit
+static TRACER_PROVIDER: OnceCell<SdkTracerProvider> = OnceCell::new();
+static METER_PROVIDER: OnceCell<SdkMeterProvider> = OnceCell::new();
+
+/// Statement counters exported as OTLP metrics; these are no-ops unless metrics
+/// export was set up before their first use
+static STATEMENTS: Lazy<Counter<u64>> = Lazy::new(|| {
+    opentelemetry::global::meter("dataforge")
+        .u64_counter("dataforge.statements")
+        .with_description("Statements executed")
+        .build()
+});
+
+static STATEMENT_DURATION: Lazy<Histogram<f64>> = Lazy::new(|| {
+    opentelemetry::global::meter("dataforge")
+        .f64_histogram("dataforge.statement.duration")
+        .with_description("Statement execution time")
+        .with_unit("ms")
+        .build()
+});
+
+/// URL of one OTLP/HTTP signal below the collector's base URL
+fn signal_url(endpoint: &str, path: &str) -> String {
+    format!("{}/{}", endpoint.trim().trim_end_matches('/'), path)
+}
+
+/// Span exporter passing span names, attributes, events, and error descriptions
+/// through `redact` before handing the spans to the OTLP exporter, as the log
+/// writers do for the console and the log file
+#[derive(Debug)]
+struct RedactingExporter<E>(E);
+
+impl<E: trace::SpanExporter> trace::SpanExporter for RedactingExporter<E> {
+    fn export(&self, mut batch: Vec<SpanData>) -> impl Future<Output = OTelSdkResult> + Send {
+        batch.iter_mut().for_each(redact_span);
+        self.0.export(batch)
+    }
+
+    fn shutdown_with_timeout(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.0.shutdown_with_timeout(timeout)
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.0.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.0.set_resource(resource);
+    }
+}
+
+fn redact_span(span: &mut SpanData) {
+    span.name = redact(&span.name).into_owned().into();
+    redact_attributes(&mut span.attributes);
+    for event in span.events.events.iter_mut() {
+        event.name = redact(&event.name).into_owned().into();
+        redact_attributes(&mut event.attributes);
+    }
+    if let Status::Error { description } = &mut span.status {
+        *description = redact(description).into_owned().into();
+    }
+}
+
+fn redact_attributes(attributes: &mut [KeyValue]) {
+    for attribute in attributes {
+        match &mut attribute.value {
+            Value::String(text) => *text = redact(text.as_str()).into_owned().into(),
+            Value::Array(Array::String(items)) => {
+                for text in items {
+                    *text = redact(text.as_str()).into_owned().into();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Set up export to the collector in `settings`, returning the tracer for the
+/// `tracing` layer; `None` when export is disabled
+pub fn init(settings: &TelemetrySettings) -> Result<Option<Tracer>, AppError> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    settings.validate()?;
+
+    let resource = Resource::builder()
+        .with_service_name(settings.service_name.clone())
+        .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let span_exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(signal_url(&settings.endpoint, "v1/traces"))
+        .with_headers(settings.headers.clone())
+        .build()
+        .map_err(|e| AppError::Config(format!("Failed to set up trace export: {}", e)))?;
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_batch_exporter(RedactingExporter(span_exporter))
+        .with_resource(resource.clone())
+        .build();
+    let tracer = tracer_provider.tracer("dataforge");
+    let _ = TRACER_PROVIDER.set(tracer_provider);
+
+    if settings.export_metrics {
+        let metric_exporter = MetricExporter::builder()
+            .with_http()
+            .with_endpoint(signal_url(&settings.endpoint, "v1/metrics"))
+            .with_headers(settings.headers.clone())
+            .build()
+            .map_err(|e| AppError::Config(format!("Failed to set up metric export: {}", e)))?;
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metric_exporter)
+            .with_resource(resource)
+            .build();
+        opentelemetry::global::set_meter_provider(meter_provider.clone());
+        let _ = METER_PROVIDER.set(meter_provider);
+    }
+
+    Ok(Some(tracer))
+}
+
+/// Count an executed statement in the exported metrics
+pub fn record_statement(connection_id: &str, duration_ms: u64, success: bool) {
+    let attributes = [
+        KeyValue::new("connection_id", connection_id.to_string()),
+        KeyValue::new("success", success),
+    ];
+    STATEMENTS.add(1, &attributes);
+    STATEMENT_DURATION.record(duration_ms as f64, &attributes);
+}
+
+/// Send what is still buffered to the collector; called when the app exits
+pub fn shutdown() {
+    if let Some(provider) = TRACER_PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to flush traces: {}", e);
+        }
+    }
+    if let Some(provider) = METER_PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to flush metrics: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_url() {
+        assert_eq!(signal_url("http://localhost:4318", "v1/traces"), "http://localhost:4318/v1/traces");
+        assert_eq!(signal_url("https://otel.example.com/otlp/ ", "v1/metrics"), "https://otel.example.com/otlp/v1/metrics");
+    }
+
+    #[test]
+    fn test_redact_span() {
+        use opentelemetry::trace::{Event, SpanContext, SpanId, SpanKind};
+        use opentelemetry::InstrumentationScope;
+        use std::time::SystemTime;
+
+        let secret = "postgres://admin:hunter2@db/app";
+        let mut span = SpanData {
+            span_context: SpanContext::empty_context(),
+            parent_span_id: SpanId::INVALID,
+            parent_span_is_remote: false,
+            span_kind: SpanKind::Internal,
+            name: "connect".into(),
+            start_time: SystemTime::now(),
+            end_time: SystemTime::now(),
+            attributes: vec![KeyValue::new("url", secret), KeyValue::new("rows", 5)],
+            dropped_attributes_count: 0,
+            events: trace::SpanEvents::default(),
+            links: trace::SpanLinks::default(),
+            status: Status::error(format!("Connection failed: {}", secret)),
+            instrumentation_scope: InstrumentationScope::builder("dataforge").build(),
+        };
+        span.events.events.push(Event::new(
+            "query",
+            SystemTime::now(),
+            vec![KeyValue::new("message", "ALTER USER app WITH PASSWORD 'hunter2'")],
+            0,
+        ));
+
+        redact_span(&mut span);
+
+        let exported = format!("{:?}", span);
+        assert!(!exported.contains("hunter2"), "{}", exported);
+        assert_eq!(span.attributes[1], KeyValue::new("rows", 5));
+    }
+
+    #[test]
+    fn test_disabled_export_has_no_tracer() {
+        assert!(init(&TelemetrySettings::default()).unwrap().is_none());
+        record_statement("c1", 5, true);
+    }
+}
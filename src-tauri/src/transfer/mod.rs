@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+use crate::database::adapter::{ColumnInfo, DatabaseAdapter, QueryRow, RowSink};
+use crate::database::dialect::{ColumnDefinition, ConflictAction, SqlDialect, TableDefinition, UpsertBuilder};
+use crate::database::types::TypeMapper;
+use crate::error::AppError;
+use crate::export::insert::sql_literal;
+use crate::export::ProgressCallback;
+
+/// Options for copying a table between connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CopyOptions {
+    /// Create the target table from the source columns if it does not exist
+    pub create_table: bool,
+    /// Number of rows inserted per statement
+    pub batch_size: usize,
+    /// What to do with rows whose primary key already exists in the target table
+    pub on_conflict: ConflictAction,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            create_table: true,
+            batch_size: 500,
+            on_conflict: ConflictAction::None,
+        }
+    }
+}
+
+/// Summary returned once a table copy has completed
+#[derive(Debug, Clone, Serialize)]
+pub struct CopySummary {
+    pub source_table: String,
+    pub target_table: String,
+    pub rows_copied: u64,
+    pub execution_time: u64, // in milliseconds
+    pub rows_per_second: f64,
+}
+
+/// Definition of the target table of a copy, from the source columns and
+/// primary key, whose types are read with `source_types`
+pub fn table_definition(
+    source_types: TypeMapper,
+    table: &str,
+    columns: &[ColumnInfo],
+    primary_key: &[String],
+) -> TableDefinition {
+    columns
+        .iter()
+        .fold(TableDefinition::new(table), |definition, column| {
+            let mut target = ColumnDefinition::new(&column.name, source_types.to_abstract(&column.data_type));
+            if !column.is_nullable {
+                target = target.not_null();
+            }
+            definition.column(target)
+        })
+        .primary_key(primary_key)
+        .if_not_exists()
+}
+
+/// Row sink that batches source rows into INSERT statements for the target
+struct CopySink {
+    dialect: Box<dyn SqlDialect>,
+    insert_prefix: String,
+    conflict_clause: String,
+    columns: Vec<ColumnInfo>,
+    batch_size: usize,
+    pending: Vec<String>,
+    statements: UnboundedSender<(String, u64)>,
+}
+
+impl CopySink {
+    fn flush_pending(&mut self) -> Result<(), AppError> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let row_count = self.pending.len() as u64;
+        let statement = format!("{}{}{}", self.insert_prefix, self.pending.join(", "), self.conflict_clause);
+        self.pending.clear();
+
+        // Sending only fails once the writer has given up after an error
+        self.statements
+            .send((statement, row_count))
+            .map_err(|_| AppError::Cancelled)
+    }
+}
+
+impl RowSink for CopySink {
+    fn columns(&mut self, _columns: &[ColumnInfo]) -> Result<(), AppError> {
+        // Source column types were already fetched from the table definition
+        Ok(())
+    }
+
+    fn row(&mut self, row: &QueryRow) -> Result<(), AppError> {
+        let values: Vec<String> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let value = row.values.get(i).and_then(|v| v.as_deref());
+                sql_literal(value, &column.data_type, self.dialect.as_ref())
+            })
+            .collect();
+        self.pending.push(format!("({})", values.join(", ")));
+
+        if self.pending.len() >= self.batch_size {
+            self.flush_pending()?;
+        }
+        Ok(())
+    }
+}
+
+/// Copy every row of a table from one connection to another
+///
+/// Rows are streamed from the source and inserted into the target in batches while
+/// the source is still being read. `on_progress` is called after every batch with the
+/// total number of rows copied so far.
+pub async fn copy_table(
+    source: &dyn DatabaseAdapter,
+    source_table: &str,
+    target: &dyn DatabaseAdapter,
+    target_table: &str,
+    options: &CopyOptions,
+    mut on_progress: ProgressCallback,
+) -> Result<CopySummary, AppError> {
+    if options.batch_size == 0 {
+        return Err(AppError::Validation("Batch size must be at least 1".to_string()));
+    }
+
+    let start = std::time::Instant::now();
+    let columns = source.get_table_columns(source_table).await?;
+    if columns.is_empty() {
+        return Err(AppError::NotFound(format!("Table '{}' not found", source_table)));
+    }
+
+    let source_dialect = source.get_dialect();
+    let target_dialect = target.get_dialect();
+
+    if options.create_table {
+        let primary_key = source.primary_key_columns(source_table).await?;
+        let mut definition = table_definition(source_dialect.type_mapper(), target_table, &columns, &primary_key);
+        if let Err(e) = definition.validate(target_dialect.as_ref()) {
+            crate::log_warn!("transfer", "Creating '{}' without a primary key: {}", target_table, e);
+            definition.primary_key.clear();
+        }
+        for ddl in definition.create_statements(target_dialect.as_ref())? {
+            target.execute_command(&ddl).await?;
+        }
+    }
+
+    let select = format!(
+        "SELECT {} FROM {}",
+        columns
+            .iter()
+            .map(|c| source_dialect.quote_identifier(&c.name))
+            .collect::<Vec<_>>()
+            .join(", "),
+        source_dialect.quote_identifier(source_table)
+    );
+    let column_names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+    let conflict_keys = if options.on_conflict == ConflictAction::None {
+        Vec::new()
+    } else {
+        target.primary_key_columns(target_table).await?
+    };
+    let upsert = UpsertBuilder::new(target_dialect.as_ref(), target_table)
+        .columns(&column_names)
+        .conflict_keys(&conflict_keys)
+        .on_conflict(options.on_conflict);
+    upsert.validate()?;
+    let insert_prefix = format!("{} ", upsert.insert_into());
+    let conflict_clause = upsert.conflict_clause();
+
+    let (sender, mut receiver) = unbounded_channel();
+    let mut sink = CopySink {
+        dialect: target_dialect,
+        insert_prefix,
+        conflict_clause,
+        columns,
+        batch_size: options.batch_size,
+        pending: Vec::new(),
+        statements: sender,
+    };
+
+    let read = async move {
+        source.stream_query(&select, &mut sink).await?;
+        // The sink is dropped afterwards, closing the channel so the writer can finish
+        sink.flush_pending()
+    };
+
+    let write = async move {
+        let mut rows_copied = 0u64;
+        while let Some((statement, row_count)) = receiver.recv().await {
+            target.execute_command(&statement).await?;
+            rows_copied += row_count;
+            on_progress(rows_copied);
+        }
+        Ok::<u64, AppError>(rows_copied)
+    };
+
+    let (read_result, write_result) = tokio::join!(read, write);
+    // A failed write also aborts the read, so report the write error first
+    let rows_copied = write_result?;
+    read_result?;
+
+    let elapsed = start.elapsed();
+    let seconds = elapsed.as_secs_f64();
+
+    Ok(CopySummary {
+        source_table: source_table.to_string(),
+        target_table: target_table.to_string(),
+        rows_copied,
+        execution_time: elapsed.as_millis() as u64,
+        rows_per_second: if seconds > 0.0 { rows_copied as f64 / seconds } else { 0.0 },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::adapter::DatabaseType;
+    use crate::database::dialect::{MySQLDialect, SQLiteDialect};
+
+    fn columns() -> Vec<ColumnInfo> {
+        vec![
+            ColumnInfo {
+                name: "id".to_string(),
+                data_type: "integer".to_string(),
+                is_nullable: false,
+            },
+            ColumnInfo {
+                name: "email".to_string(),
+                data_type: "character varying".to_string(),
+                is_nullable: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_table_definition() {
+        let definition = table_definition(TypeMapper::new(DatabaseType::PostgreSQL), "users", &columns(), &["id".to_string()]);
+        assert_eq!(
+            definition.create_statements(&SQLiteDialect::new()).unwrap(),
+            vec!["CREATE TABLE IF NOT EXISTS \"users\" (\n    \"id\" INTEGER NOT NULL,\n    \"email\" TEXT,\n    PRIMARY KEY (\"id\")\n)"]
+        );
+        assert_eq!(
+            definition.create_statements(&MySQLDialect::new()).unwrap(),
+            vec!["CREATE TABLE IF NOT EXISTS `users` (\n    `id` INT NOT NULL,\n    `email` LONGTEXT,\n    PRIMARY KEY (`id`)\n)"]
+        );
+    }
+
+    #[test]
+    fn test_copy_sink_batches() {
+        let (sender, mut receiver) = unbounded_channel();
+        let mut sink = CopySink {
+            dialect: Box::new(SQLiteDialect::new()),
+            insert_prefix: "INSERT INTO \"users\" (\"id\", \"email\") VALUES ".to_string(),
+            conflict_clause: String::new(),
+            columns: columns(),
+            batch_size: 2,
+            pending: Vec::new(),
+            statements: sender,
+        };
+
+        for (id, email) in [("1", Some("a@example.com")), ("2", None), ("3", Some("o'neil"))] {
+            sink.row(&QueryRow {
+                columns: vec!["id".to_string(), "email".to_string()],
+                values: vec![Some(id.to_string()), email.map(|e| e.to_string())],
+            })
+            .unwrap();
+        }
+        sink.flush_pending().unwrap();
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            (
+                "INSERT INTO \"users\" (\"id\", \"email\") VALUES (1, 'a@example.com'), (2, NULL)"
+                    .to_string(),
+                2
+            )
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ("INSERT INTO \"users\" (\"id\", \"email\") VALUES (3, 'o''neil')".to_string(), 1)
+        );
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -1,4 +1,7 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { toast } from "sonner";
 import { Sidebar } from "./components/Sidebar";
 import { ConnectionForm } from "./components/ConnectionForm";
 import { DatabaseExplorerEnhanced } from "./components/DatabaseExplorerEnhanced";
@@ -22,9 +25,34 @@ function App() {
   const [sidebarOpen, setSidebarOpen] = useState(true);
   const [showConnectionForm, setShowConnectionForm] = useState(false);
   const [editingProfile, setEditingProfile] = useState<ConnectionProfile | null>(null);
-  const { currentProfile } = useConnectionStore();
+  const { currentProfile, setConnectionState } = useConnectionStore();
   const { tabs, activeTabId, removeTab, setActiveTab, openQueryTab, updateTab } = useTabStore();
 
+  // 操作があればアイドルロックのタイマーをリセット（1分に1回まで）
+  useEffect(() => {
+    let lastReported = 0;
+    const reportActivity = () => {
+      const now = Date.now();
+      if (now - lastReported > 60_000) {
+        lastReported = now;
+        invoke("record_activity").catch(() => {});
+      }
+    };
+    window.addEventListener("keydown", reportActivity);
+    window.addEventListener("pointerdown", reportActivity);
+
+    const unlisten = listen("profiles-locked", () => {
+      setConnectionState({ isConnected: false, currentProfile: undefined });
+      toast.info("一定時間操作がなかったためロックしました。マスターパスワードで再開してください");
+    });
+
+    return () => {
+      window.removeEventListener("keydown", reportActivity);
+      window.removeEventListener("pointerdown", reportActivity);
+      unlisten.then((stop) => stop());
+    };
+  }, [setConnectionState]);
+
   const handleNewConnection = () => {
     setEditingProfile(null);
     setShowConnectionForm(true);
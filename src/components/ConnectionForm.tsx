@@ -3,6 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { Database, CheckCircle, XCircle, Loader2, Eye, EyeOff, Save } from "lucide-react";
 import { useProfileStore } from "../stores/profileStore";
 import { ConnectionProfile } from "../types/profile";
+import { parseCommandError } from "../lib/query";
 
 type DatabaseType = "postgresql" | "mysql" | "sqlite";
 
@@ -168,7 +169,7 @@ export function ConnectionForm({ editingProfile, onSaveSuccess, isEditMode }: Co
     } catch (error) {
       setConnectionStatus({
         status: "error",
-        message: String(error),
+        message: parseCommandError(error)?.message ?? String(error),
       });
     } finally {
       setIsConnecting(false);
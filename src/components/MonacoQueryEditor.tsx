@@ -1,7 +1,7 @@
 import React, { useState, useRef, useMemo, useCallback, useEffect } from 'react';
 import Editor, { Monaco } from '@monaco-editor/react';
 import { editor } from 'monaco-editor';
-import { invoke } from '@tauri-apps/api/core';
+import { executeQuery, parseCommandError } from '@/lib/query';
 import { Play, Loader2, Download, ChevronUp, ChevronDown, ChevronsUpDown } from 'lucide-react';
 import { Button } from './ui/button';
 import { useConnectionStore } from '@/stores/connectionStore';
@@ -25,10 +25,12 @@ export function MonacoQueryEditor({ initialContent = 'SELECT * FROM ', onContent
   const { currentProfile } = useConnectionStore();
   const { resolvedTheme } = useTheme();
   const editorRef = useRef<editor.IStandaloneCodeEditor | null>(null);
+  const monacoRef = useRef<Monaco | null>(null);
   const executeRef = useRef<() => void>();
 
   const handleEditorDidMount = (editor: editor.IStandaloneCodeEditor, monaco: Monaco) => {
     editorRef.current = editor;
+    monacoRef.current = monaco;
 
     // Add keyboard shortcut for execution directly in Monaco
     editor.addAction({
@@ -116,10 +118,14 @@ export function MonacoQueryEditor({ initialContent = 'SELECT * FROM ', onContent
     setLoading(true);
     setError(null);
     setResults(null);
+    const model = editorRef.current?.getModel();
+    if (model && monacoRef.current) {
+      monacoRef.current.editor.setModelMarkers(model, 'query-error', []);
+    }
     const startTime = Date.now();
 
     try {
-      const result = await invoke<any>('execute_query', { query: currentQuery });
+      const result = await executeQuery(currentQuery);
       const endTime = Date.now();
       setExecutionTime(endTime - startTime);
 
@@ -138,7 +144,42 @@ export function MonacoQueryEditor({ initialContent = 'SELECT * FROM ', onContent
       }
     } catch (err) {
       console.error('Query execution failed:', err);
-      setError(err instanceof Error ? err.message : 'クエリの実行に失敗しました');
+      const commandError = parseCommandError(err);
+      const queryError = commandError?.query_error;
+
+      // Underline the token the database reported the error at
+      const position = queryError?.position;
+      if (model && monacoRef.current && position) {
+        const code = queryError.sqlstate ?? queryError.native_code;
+        monacoRef.current.editor.setModelMarkers(model, 'query-error', [{
+          severity: monacoRef.current.MarkerSeverity.Error,
+          message: [queryError.message, queryError.detail, queryError.hint].filter(Boolean).join('\n'),
+          code,
+          startLineNumber: position.line,
+          startColumn: position.column,
+          endLineNumber: position.line,
+          endColumn: position.column + position.length,
+        }]);
+        editorRef.current?.revealPositionInCenterIfOutsideViewport({
+          lineNumber: position.line,
+          column: position.column,
+        });
+      }
+
+      // Keep showing what the statements before the failing one returned
+      const scriptFailure = commandError?.script_failure;
+      if (scriptFailure?.completed.length) {
+        setResults({ results: scriptFailure.completed });
+        setCurrentResultIndex(0);
+      }
+      const failedAt = scriptFailure && scriptFailure.statement_index > 0
+        ? `${scriptFailure.statement_index + 1}番目のステートメントで失敗しました (それまでの変更は保持されています)\n`
+        : '';
+
+      const suggestions = queryError?.suggestions?.length
+        ? `\nもしかして: ${queryError.suggestions.join(', ')}`
+        : '';
+      setError(failedAt + (commandError?.message ?? (err instanceof Error ? err.message : 'クエリの実行に失敗しました')) + suggestions);
       toast.error('クエリの実行に失敗しました');
     } finally {
       setLoading(false);
@@ -379,11 +420,12 @@ export function MonacoQueryEditor({ initialContent = 'SELECT * FROM ', onContent
         </div>
 
         <div className="flex-1 overflow-auto">
-          {error ? (
+          {error && (
             <div className="p-4">
-              <div className="text-sm text-destructive">{error}</div>
+              <div className="text-sm text-destructive whitespace-pre-line">{error}</div>
             </div>
-          ) : results ? (
+          )}
+          {results ? (
             (() => {
               // Get current result to display
               const currentResult = results.results ? results.results[currentResultIndex] : results;
@@ -460,7 +502,7 @@ export function MonacoQueryEditor({ initialContent = 'SELECT * FROM ', onContent
                 </div>
               );
             })()
-          ) : (
+          ) : !error && (
             <div className="p-4 text-center text-muted-foreground">
               クエリを実行すると結果がここに表示されます
             </div>
@@ -1,5 +1,5 @@
 import React, { useState } from 'react';
-import { invoke } from '@tauri-apps/api/core';
+import { executeQuery } from '@/lib/query';
 import { Play, Save, Loader2 } from 'lucide-react';
 import { Button } from './ui/button';
 import { useConnectionStore } from '@/stores/connectionStore';
@@ -36,7 +36,7 @@ export function QueryEditor({ initialContent = 'SELECT * FROM ', onContentChange
     const startTime = Date.now();
 
     try {
-      const result = await invoke<any>('execute_query', { query });
+      const result = await executeQuery(query);
       const endTime = Date.now();
       setExecutionTime(endTime - startTime);
 
@@ -10,6 +10,16 @@ interface TableViewProps {
   tableName: string;
 }
 
+/** Where a page starts: after the primary key of a row, or at an offset */
+type PageCursor = { after: string[] } | { offset: number };
+
+interface TablePage {
+  columns: Array<{ name: string; type: string }>;
+  rows: any[];
+  key_columns: string[];
+  next_cursor: PageCursor | null;
+}
+
 interface TableData {
   columns: Array<{
     name: string;
@@ -24,7 +34,17 @@ export function TableView({ tableName }: TableViewProps) {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [limit, setLimit] = useState(100);
-  const [offset, setOffset] = useState(0);
+  // Cursors of the pages seen so far; the last one is the current page
+  const [cursors, setCursors] = useState<Array<PageCursor | null>>([null]);
+  const [nextCursor, setNextCursor] = useState<PageCursor | null>(null);
+
+  // 別のテーブルやページサイズでは最初のページから表示する
+  const [pagedTable, setPagedTable] = useState(`${tableName}:${limit}`);
+  if (pagedTable !== `${tableName}:${limit}`) {
+    setPagedTable(`${tableName}:${limit}`);
+    setCursors([null]);
+    setNextCursor(null);
+  }
   const [searchQuery, setSearchQuery] = useState('');
   const [sortColumn, setSortColumn] = useState<string | null>(null);
   const [sortDirection, setSortDirection] = useState<'asc' | 'desc'>('asc');
@@ -41,13 +61,13 @@ export function TableView({ tableName }: TableViewProps) {
     setError(null);
 
     try {
-      // テーブルデータを取得するクエリ
-      const query = `SELECT * FROM ${tableName} LIMIT ${limit} OFFSET ${offset}`;
-      const result = await invoke<any>('execute_query', { query });
-
-      console.log('Query result:', result);
-      console.log('Columns:', result?.columns);
-      console.log('First row:', result?.rows?.[0]);
+      // 主キーがあれば前ページの最後の行からシークして取得する
+      const result = await invoke<TablePage>('browse_table', {
+        tableName,
+        cursor: cursors[cursors.length - 1],
+        pageSize: limit,
+      });
+      setNextCursor(result.next_cursor);
 
       if (result) {
         setData({
@@ -67,7 +87,7 @@ export function TableView({ tableName }: TableViewProps) {
 
   useEffect(() => {
     loadTableData();
-  }, [tableName, limit, offset]);
+  }, [tableName, limit, cursors]);
 
   const handleRefresh = () => {
     loadTableData();
@@ -316,16 +336,16 @@ export function TableView({ tableName }: TableViewProps) {
           <Button
             size="sm"
             variant="ghost"
-            onClick={() => setOffset(Math.max(0, offset - limit))}
-            disabled={offset === 0}
+            onClick={() => setCursors(cursors.slice(0, -1))}
+            disabled={cursors.length === 1}
           >
             前へ
           </Button>
           <Button
             size="sm"
             variant="ghost"
-            onClick={() => setOffset(offset + limit)}
-            disabled={!sortedData || sortedData.rows.length < limit}
+            onClick={() => nextCursor && setCursors([...cursors, nextCursor])}
+            disabled={!nextCursor}
           >
             次へ
           </Button>
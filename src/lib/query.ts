@@ -0,0 +1,91 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ErrorPosition {
+  /** Offset in characters from the start of the script */
+  offset: number;
+  line: number;
+  column: number;
+  length: number;
+}
+
+export interface QueryError {
+  message: string;
+  /** SQLSTATE code (PostgreSQL and MySQL) */
+  sqlstate?: string;
+  /** MySQL error number or SQLite result code */
+  native_code?: string;
+  detail?: string;
+  hint?: string;
+  position?: ErrorPosition;
+  /** Deadlocks, serialization failures and dropped connections */
+  transient: boolean;
+  /** Similar known names when a table or column does not exist */
+  suggestions?: string[];
+}
+
+export type DiagnosticStage = 'dns' | 'tcp' | 'tls' | 'auth';
+
+export interface StageResult {
+  stage: DiagnosticStage;
+  status: 'passed' | 'failed' | 'skipped';
+  message: string;
+  duration_ms: number;
+}
+
+/** Which step of a failed connection attempt went wrong */
+export interface ConnectionDiagnostics {
+  stages: StageResult[];
+  failed_stage?: DiagnosticStage;
+}
+
+/** The failing statement of a script and the results of those run before it */
+export interface ScriptFailure {
+  statement_index: number;
+  statement: string;
+  completed: any[];
+  /** Scripts run without a surrounding transaction, so this is false for now */
+  rolled_back: boolean;
+}
+
+export interface CommandError {
+  error_type: string;
+  message: string;
+  details?: string;
+  code?: string;
+  query_error?: QueryError;
+  connection_diagnostics?: ConnectionDiagnostics;
+  script_failure?: ScriptFailure;
+}
+
+export function parseCommandError(err: unknown): CommandError | null {
+  if (typeof err !== 'string') {
+    return null;
+  }
+  try {
+    const parsed = JSON.parse(err);
+    return parsed && typeof parsed.error_type === 'string' ? parsed : null;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Run `execute_query`, asking the user before destructive statements are forced through.
+ * Rejects with the original error when the user declines.
+ */
+export async function executeQuery<T = any>(query: string): Promise<T> {
+  try {
+    return await invoke<T>('execute_query', { query });
+  } catch (err) {
+    const error = parseCommandError(err);
+    if (error?.error_type !== 'confirmation_required') {
+      throw err;
+    }
+
+    const confirmed = window.confirm(`${error.message}\n\n${error.details ?? ''}\n\n実行しますか?`);
+    if (!confirmed) {
+      throw err;
+    }
+    return invoke<T>('execute_query', { query, force: true });
+  }
+}
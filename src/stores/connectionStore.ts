@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { ConnectionProfile, ConnectionState } from '../types/profile';
+import { parseCommandError } from '../lib/query';
 
 interface ConnectionStoreState extends ConnectionState {
   isConnecting: boolean;
@@ -43,7 +44,7 @@ export const useConnectionStore = create<ConnectionStoreState>((set) => ({
         isConnected: false,
         isConnecting: false,
         currentProfile: undefined,
-        connectionMessage: error instanceof Error ? error.message : String(error)
+        connectionMessage: parseCommandError(error)?.message ?? (error instanceof Error ? error.message : String(error))
       });
       throw error;
     }
@@ -0,0 +1,19 @@
+export interface QueryHistoryEntry {
+  id: string;
+  executed_at: string;
+  profile_id?: string;
+  query: string;
+  success: boolean;
+  error?: string;
+  duration_ms: number;
+}
+
+export interface Snippet {
+  /** Empty for a snippet that has not been saved yet */
+  id: string;
+  name: string;
+  body: string;
+  description?: string;
+  created_at?: string;
+  updated_at?: string;
+}
@@ -0,0 +1,12 @@
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug';
+
+export interface LogEntry {
+  timestamp: string;
+  level: string;
+  /** Module the event was logged from */
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+  /** Enclosing spans, outermost first (e.g. connection_id, query_id) */
+  spans: Record<string, unknown>[];
+}
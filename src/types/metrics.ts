@@ -0,0 +1,31 @@
+export interface PoolStats {
+  /** Open connections, idle or in use */
+  size: number;
+  idle: number;
+  active: number;
+  max_connections: number;
+  acquire_count: number;
+  avg_acquire_wait_ms: number;
+  max_acquire_wait_ms: number;
+}
+
+export interface ConnectionMetrics {
+  connection_id: string;
+  profile_id?: string;
+  first_query_at: string;
+  last_query_at: string;
+  queries: number;
+  errors: number;
+  /** Share of statements that failed, from 0 to 1 */
+  error_rate: number;
+  avg_duration_ms: number;
+  max_duration_ms: number;
+}
+
+export interface MetricsSnapshot {
+  collected_at: string;
+  active_connection_id?: string;
+  /** Pool of the active connection */
+  pool?: PoolStats;
+  connections: ConnectionMetrics[];
+}
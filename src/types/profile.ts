@@ -1,5 +1,33 @@
 export type DatabaseType = "postgresql" | "mysql" | "sqlite";
 
+/** Secret manager entry a profile's password is read from at connect time */
+export type SecretReference =
+  | { provider: 'vault'; path: string; field?: string; mount?: string }
+  | { provider: 'one_password'; reference: string };
+
+export interface SshTunnelConfig {
+  host: string;
+  port?: number;
+  username: string;
+  key_path?: string;
+  local_port?: number;
+}
+
+export interface AuditPolicy {
+  log_statements: boolean;
+  confirm_ddl: boolean;
+  block_unfiltered_dml: boolean;
+}
+
+/** Retrying read-only statements after transient errors */
+export interface RetryPolicy {
+  enabled: boolean;
+  /** Attempts in total, including the first */
+  max_attempts: number;
+  base_delay_ms: number;
+  max_delay_ms: number;
+}
+
 export interface ConnectionProfile {
   id: string;
   name: string;
@@ -8,9 +36,17 @@ export interface ConnectionProfile {
   port?: number;
   database: string;
   username?: string;
+  password_ref?: SecretReference;
   ssl_mode?: string;
+  default_schema?: string;
+  ssh_tunnel?: SshTunnelConfig;
+  audit_policy?: AuditPolicy;
+  read_only?: boolean;
+  retry_policy?: RetryPolicy;
   color?: string;
   icon?: string;
+  pinned?: boolean;
+  sort_order?: number;
   project?: string;
   created_at: string;
   updated_at: string;
@@ -25,7 +61,14 @@ export interface CreateProfileRequest {
   database: string;
   username?: string;
   password?: string;
+  password_ref?: SecretReference;
   ssl_mode?: string;
+  default_schema?: string;
+  ssh_tunnel?: SshTunnelConfig;
+  ssh_passphrase?: string;
+  audit_policy?: AuditPolicy;
+  read_only?: boolean;
+  retry_policy?: RetryPolicy;
   color?: string;
   icon?: string;
 }
@@ -38,4 +81,29 @@ export interface ConnectionState {
   isConnected: boolean;
   currentProfile?: ConnectionProfile;
   connectionMessage?: string;
-}
\ No newline at end of file
+}
+export type StatementCategory =
+  | 'all'
+  | 'query'
+  | 'insert'
+  | 'update'
+  | 'delete'
+  | 'unfiltered_dml'
+  | 'ddl'
+  | 'destructive_ddl'
+  | 'other';
+
+/** Administrator-provided rule; the first matching rule decides */
+export interface StatementRule {
+  action: 'allow' | 'deny';
+  categories: StatementCategory[];
+  profiles?: string[];
+  database_types?: DatabaseType[];
+  tables?: string[];
+  message?: string;
+}
+
+export interface StatementRules {
+  default_action: 'allow' | 'deny';
+  rules: StatementRule[];
+}
@@ -0,0 +1,22 @@
+export interface QueryLogEntry {
+  timestamp: string;
+  connection_id: string;
+  profile_id?: string;
+  statement: string;
+  duration_ms: number;
+  rows_returned?: number;
+  rows_affected?: number;
+  success: boolean;
+  error?: string;
+}
+
+export interface QueryLogFilter {
+  connection_id?: string;
+  profile_id?: string;
+  /** Only entries after this time, for polling new entries */
+  since?: string;
+  text?: string;
+  failed_only?: boolean;
+  min_duration_ms?: number;
+  limit?: number;
+}
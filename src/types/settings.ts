@@ -0,0 +1,12 @@
+export interface TelemetrySettings {
+  enabled: boolean;
+  /** Base URL of the collector's OTLP/HTTP receiver, e.g. http://localhost:4318 */
+  endpoint: string;
+  /** Extra request headers, e.g. an API key for a hosted collector */
+  headers: Record<string, string>;
+  service_name: string;
+  export_metrics: boolean;
+}
+
+/** Language of error messages returned by commands */
+export type Locale = 'en' | 'ja';